@@ -0,0 +1,9 @@
+-- Migration 008: Add namespace column for multi-tenant memory isolation
+-- Several agents can share one database; every read and write is scoped to a namespace.
+-- Existing rows land in the 'default' namespace.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS namespace TEXT NOT NULL DEFAULT 'default';
+
+-- Composite index matches list_memories ordering (created_at DESC, id) within a namespace
+CREATE INDEX IF NOT EXISTS idx_memories_namespace_created
+    ON memories(namespace, created_at DESC, id);
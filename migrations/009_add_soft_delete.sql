@@ -0,0 +1,10 @@
+-- Migration 009: Soft delete (trash) for memories
+-- delete_memory sets deleted_at instead of removing the row; restore_memory clears it,
+-- purge_trash removes trashed rows permanently. Trashed rows are excluded from search/list.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+-- Partial index: the trash is small relative to live memories
+CREATE INDEX IF NOT EXISTS idx_memories_deleted_at
+    ON memories(deleted_at)
+    WHERE deleted_at IS NOT NULL;
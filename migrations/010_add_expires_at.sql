@@ -0,0 +1,7 @@
+-- Migration 010: Add optional expiration timestamp (memory TTL)
+-- Expired memories are hidden from search/list and removed by the background sweeper.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+-- Partial index: the sweeper only ever scans rows that have an expiry set
+CREATE INDEX IF NOT EXISTS idx_memories_expires_at ON memories(expires_at) WHERE expires_at IS NOT NULL;
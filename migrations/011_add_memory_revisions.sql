@@ -0,0 +1,19 @@
+-- Migration 011: Add memory_revisions table
+-- Every update_memory snapshots the previous content/metadata so edits can be reviewed and reverted.
+
+CREATE TABLE IF NOT EXISTS memory_revisions (
+    id TEXT PRIMARY KEY NOT NULL,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    type_hint TEXT NOT NULL,
+    source TEXT NOT NULL,
+    tags JSONB,
+    -- When this version became current (the memory's updated_at at snapshot time)
+    valid_from TIMESTAMPTZ NOT NULL,
+    -- When this version was replaced by an update
+    replaced_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (memory_id, revision)
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_revisions_memory ON memory_revisions(memory_id, revision DESC);
@@ -0,0 +1,16 @@
+-- Migration 012: Add memory_links table
+-- Typed, directed edges between memories ("works_at", "supersedes", "related_to", ...).
+
+CREATE TABLE IF NOT EXISTS memory_links (
+    id TEXT PRIMARY KEY NOT NULL,
+    source_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    target_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    relation TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (source_id, target_id, relation),
+    CHECK (source_id <> target_id)
+);
+
+-- Outgoing and incoming edge lookups for graph traversal and degree counts
+CREATE INDEX IF NOT EXISTS idx_memory_links_source ON memory_links(source_id);
+CREATE INDEX IF NOT EXISTS idx_memory_links_target ON memory_links(target_id);
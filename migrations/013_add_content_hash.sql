@@ -0,0 +1,10 @@
+-- Migration 013: Add normalized content hash for write-ahead deduplication
+-- Hash of lowercased, whitespace-collapsed content. Generated so every insert path
+-- (single, batch, consolidation) populates it and existing rows are backfilled.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS content_hash TEXT
+    GENERATED ALWAYS AS (md5(lower(btrim(regexp_replace(content, '\s+', ' ', 'g'))))) STORED;
+
+-- Exact-duplicate probe on store_memory looks up live rows by (namespace, content_hash)
+CREATE INDEX IF NOT EXISTS idx_memories_namespace_content_hash
+    ON memories(namespace, content_hash) WHERE deleted_at IS NULL;
@@ -0,0 +1,13 @@
+-- Migration 014: Add embedding dead-letter table
+-- One row per memory whose embedding has failed at least once. Cleared on success.
+
+CREATE TABLE IF NOT EXISTS memory_embedding_failures (
+    memory_id TEXT PRIMARY KEY NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    last_error TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 1,
+    first_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Stats and retry-failed list failures most-recent first
+CREATE INDEX IF NOT EXISTS idx_memory_embedding_failures_last_failed ON memory_embedding_failures(last_failed_at DESC);
@@ -0,0 +1,11 @@
+-- Migration 015: Archived state for memories whose salience has decayed
+-- The decay archiver sets archived_at on memories whose FSRS retrievability fell below
+-- decay.archive_threshold. Archived memories stay readable by ID and listable, but are
+-- excluded from search until restore_memory clears the flag.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS archived_at TIMESTAMPTZ;
+
+-- Partial index: archived rows are a small subset of live memories
+CREATE INDEX IF NOT EXISTS idx_memories_archived_at
+    ON memories(archived_at)
+    WHERE archived_at IS NOT NULL;
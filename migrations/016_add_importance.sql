@@ -0,0 +1,5 @@
+-- Migration 016: Caller-assigned importance (1 = trivia, 5 = critical)
+-- Feeds the w_importance salience dimension and the list_memories min_importance filter.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS importance SMALLINT NOT NULL DEFAULT 3
+    CHECK (importance BETWEEN 1 AND 5);
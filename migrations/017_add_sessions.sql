@@ -0,0 +1,20 @@
+-- Migration 017: Session-scoped memories
+-- start_session opens a row here; memories stored with its id carry session_id.
+-- end_session records the condensed summary memory that the session primer prefers.
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY NOT NULL,
+    namespace TEXT NOT NULL DEFAULT 'default',
+    title TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at TIMESTAMPTZ,
+    summary_memory_id TEXT REFERENCES memories(id) ON DELETE SET NULL
+);
+
+-- Recent sessions per namespace (session primer, list ordering)
+CREATE INDEX IF NOT EXISTS idx_sessions_namespace_started ON sessions(namespace, started_at DESC);
+
+-- Plain TEXT (no FK) so memories survive purging old session rows
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS session_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_memories_session ON memories(session_id) WHERE session_id IS NOT NULL;
@@ -0,0 +1,24 @@
+-- Migration 018: Per-fact embeddings for fine-grained retrieval
+-- When search.fact_embeddings is enabled, each fact the extraction pipeline pulls out of a
+-- memory is embedded on its own; the vector search leg also matches these rows and maps
+-- them back to the parent memory. Rows are replaced whenever the memory is re-extracted.
+
+CREATE TABLE IF NOT EXISTS fact_embeddings (
+    id TEXT PRIMARY KEY NOT NULL,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    fact_index INTEGER NOT NULL,
+    fact TEXT NOT NULL,
+    model_name TEXT NOT NULL,
+    dimension INTEGER NOT NULL,
+    embedding vector NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (memory_id, model_name, fact_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fact_embeddings_memory_id ON fact_embeddings(memory_id);
+
+-- Same parameters as the memory embedding index (migration 003)
+CREATE INDEX IF NOT EXISTS idx_fact_embeddings_hnsw
+    ON fact_embeddings
+    USING hnsw (embedding vector_cosine_ops)
+    WITH (m = 16, ef_construction = 64);
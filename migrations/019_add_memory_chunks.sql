@@ -0,0 +1,9 @@
+-- Migration 019: Chunked storage for oversized memories
+-- Content longer than content.max_chars is kept whole on a parent row and split into child
+-- rows (parent_id + chunk_index). Only the children are embedded and extracted; search maps
+-- chunk hits back to the parent.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS parent_id TEXT REFERENCES memories(id) ON DELETE CASCADE;
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS chunk_index INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_memories_parent_id ON memories(parent_id) WHERE parent_id IS NOT NULL;
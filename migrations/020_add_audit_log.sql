@@ -0,0 +1,22 @@
+-- Migration 020: Tool-call audit log
+-- With [audit] enabled, every tool call appends one row: which tool ran, a hash of its
+-- parameters (not the parameters themselves, which may contain user data), the memory IDs it
+-- touched, how long it took, and whether it succeeded.
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    tool TEXT NOT NULL,
+    params_hash TEXT NOT NULL,
+    memory_ids TEXT[] NOT NULL DEFAULT '{}',
+    latency_ms INTEGER NOT NULL,
+    outcome TEXT NOT NULL,
+    error_code TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Newest-first listing, optionally per tool
+CREATE INDEX IF NOT EXISTS idx_audit_log_created ON audit_log(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_log_tool_created ON audit_log(tool, created_at DESC);
+
+-- "Who touched this memory?" lookups
+CREATE INDEX IF NOT EXISTS idx_audit_log_memory_ids ON audit_log USING GIN (memory_ids);
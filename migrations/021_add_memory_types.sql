@@ -0,0 +1,22 @@
+-- Migration 021: Structured memory types
+-- A memory type is a per-namespace schema (e.g. "contact" with name/email/company).
+-- Memories stored against a type keep their validated values in memories.fields; the
+-- generated field_values array lets the symbolic search leg match on those values with the
+-- same JSONB containment it uses for tags.
+
+CREATE TABLE IF NOT EXISTS memory_types (
+    namespace TEXT NOT NULL DEFAULT 'default',
+    name TEXT NOT NULL,
+    description TEXT,
+    fields JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (namespace, name)
+);
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS fields JSONB;
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS field_values JSONB
+    GENERATED ALWAYS AS (jsonb_path_query_array(fields, '$.*')) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_memories_field_values ON memories USING GIN (field_values)
+    WHERE field_values IS NOT NULL;
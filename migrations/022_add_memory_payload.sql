@@ -0,0 +1,10 @@
+-- Migration 022: Structured memory payloads
+-- A memory can carry a machine-readable payload (JSON blob, URLs, code snippets) next to its
+-- content. Payloads are returned with the memory but not embedded; search and list filter on
+-- them with SQL/JSON path predicates (payload @@ '$.language == "rust"'), which the
+-- jsonb_path_ops GIN index serves.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS payload JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_memories_payload ON memories USING GIN (payload jsonb_path_ops)
+    WHERE payload IS NOT NULL;
@@ -0,0 +1,20 @@
+-- Migration 023: Pipeline job outbox
+-- With [outbox] enabled, a new memory's embedding/extraction jobs are inserted here in the
+-- same transaction as the memory. Workers claim rows with FOR UPDATE SKIP LOCKED (setting
+-- claimed_at/claimed_by) and delete them once the job finishes; a claim older than the
+-- configured lease is treated as abandoned and claimed again.
+
+CREATE TABLE IF NOT EXISTS pipeline_jobs (
+    id BIGSERIAL PRIMARY KEY,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('embedding', 'extraction')),
+    -- Extraction jobs: also classify the memory's type_hint
+    classify BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    claimed_at TIMESTAMPTZ,
+    claimed_by TEXT,
+    UNIQUE (memory_id, kind)
+);
+
+-- Workers claim the oldest jobs of one kind first
+CREATE INDEX IF NOT EXISTS idx_pipeline_jobs_kind_id ON pipeline_jobs(kind, id);
@@ -0,0 +1,9 @@
+-- Migration 024: Pinned memories
+-- A pinned memory is injected at the top of every search whose hard filters it matches and is
+-- always listed in the session primer, regardless of salience. The partial index keeps the
+-- per-search pinned lookup cheap since only a handful of memories are ever pinned.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS pinned BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_memories_pinned ON memories (namespace, updated_at DESC)
+    WHERE pinned;
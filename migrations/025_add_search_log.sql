@@ -0,0 +1,26 @@
+-- Migration 025: Search quality log
+-- With [search_log] enabled, every hybrid search appends one row: the query and its expanded
+-- variants, how many fused hits each leg contributed, the fused and final result IDs with the
+-- final scores, and which results the client fetched with get_memory afterwards. The rows are
+-- offline signal for tuning RRF k values and salience weights.
+
+CREATE TABLE IF NOT EXISTS search_log (
+    id BIGSERIAL PRIMARY KEY,
+    query TEXT NOT NULL,
+    namespace TEXT NOT NULL,
+    variants TEXT[] NOT NULL DEFAULT '{}',
+    leg_hits JSONB NOT NULL DEFAULT '{}',
+    fused_ids TEXT[] NOT NULL DEFAULT '{}',
+    result_ids TEXT[] NOT NULL DEFAULT '{}',
+    scores DOUBLE PRECISION[] NOT NULL DEFAULT '{}',
+    fetched_ids TEXT[] NOT NULL DEFAULT '{}',
+    latency_ms INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Newest-first export, optionally per namespace
+CREATE INDEX IF NOT EXISTS idx_search_log_created ON search_log(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_search_log_namespace_created ON search_log(namespace, created_at DESC);
+
+-- Attributing a later get_memory to the search that returned the memory
+CREATE INDEX IF NOT EXISTS idx_search_log_result_ids ON search_log USING GIN (result_ids);
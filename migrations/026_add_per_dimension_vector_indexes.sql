@@ -0,0 +1,35 @@
+-- Migration 026: Per-dimension vector indexes
+-- memory_embeddings and fact_embeddings hold vectors of any dimension so two embedding models
+-- can coexist while switching between them, but an HNSW index needs a fixed dimension. Replace
+-- the single indexes from migrations 003 and 018 with one partial expression index per
+-- dimension; searches cast to the query's dimension and filter on it so the matching index is
+-- used. `memcp embed switch-model` builds the index for a new model's dimension.
+
+DROP INDEX IF EXISTS idx_memory_embeddings_hnsw;
+DROP INDEX IF EXISTS idx_fact_embeddings_hnsw;
+
+DO $$
+DECLARE
+    dim INTEGER;
+BEGIN
+    FOR dim IN SELECT DISTINCT dimension FROM memory_embeddings LOOP
+        EXECUTE format(
+            'CREATE INDEX IF NOT EXISTS idx_memory_embeddings_hnsw_%s ON memory_embeddings '
+            'USING hnsw ((embedding::vector(%s)) vector_cosine_ops) WITH (m = 16, ef_construction = 64) '
+            'WHERE dimension = %s',
+            dim, dim, dim
+        );
+    END LOOP;
+    FOR dim IN SELECT DISTINCT dimension FROM fact_embeddings LOOP
+        EXECUTE format(
+            'CREATE INDEX IF NOT EXISTS idx_fact_embeddings_hnsw_%s ON fact_embeddings '
+            'USING hnsw ((embedding::vector(%s)) vector_cosine_ops) WITH (m = 16, ef_construction = 64) '
+            'WHERE dimension = %s',
+            dim, dim, dim
+        );
+    END LOOP;
+END $$;
+
+-- Cheap "do current embeddings all share the query's dimension?" checks before searching
+CREATE INDEX IF NOT EXISTS idx_memory_embeddings_current_dimension
+    ON memory_embeddings(dimension) WHERE is_current;
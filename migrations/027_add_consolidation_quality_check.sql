@@ -0,0 +1,20 @@
+-- Migration 027: Consolidation quality guardrails
+-- With consolidation.quality_check on, each synthesized memory is checked for length and fact
+-- preservation before it replaces its originals. Accepted consolidations record the measured
+-- fact coverage on their provenance rows; rejected ones are recorded here so the same group is
+-- not synthesized again.
+
+ALTER TABLE memory_consolidations ADD COLUMN IF NOT EXISTS fact_coverage REAL;
+
+CREATE TABLE IF NOT EXISTS consolidation_rejections (
+    id TEXT PRIMARY KEY NOT NULL,
+    -- Every memory in the rejected group, sorted
+    memory_ids TEXT[] NOT NULL,
+    -- "too_short", "too_long" or "facts_dropped"
+    reason TEXT NOT NULL,
+    fact_coverage REAL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_consolidation_rejections_memory_ids
+    ON consolidation_rejections USING GIN (memory_ids);
@@ -0,0 +1,16 @@
+-- Migration 028: Write-path content filtering
+-- With [privacy] enabled, sensitive spans are replaced by [REDACTED:<KIND>] placeholders
+-- before a memory is stored. The originals are kept here for audit, encrypted like memory
+-- content when security.encryption_key is set, and removed with their memory.
+
+CREATE TABLE IF NOT EXISTS memory_redactions (
+    id BIGSERIAL PRIMARY KEY,
+    memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE,
+    -- "email", "phone", "api_key", "deny_list", a custom pattern name, or an LLM-reported kind
+    kind TEXT NOT NULL,
+    original TEXT NOT NULL,
+    placeholder TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_redactions_memory_id ON memory_redactions (memory_id);
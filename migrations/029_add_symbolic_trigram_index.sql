@@ -0,0 +1,13 @@
+-- Migration 029: Fuzzy symbolic matching
+-- The symbolic search leg matches tags, extracted entities and extracted facts by exact JSONB
+-- containment, so a "Postgres" query misses a "PostgreSQL" tag. This trigram index over the
+-- three arrays flattened to text (pg_trgm ignores the JSON punctuation) lets the leg also
+-- match by word similarity. The expression must stay identical to SYMBOLIC_TEXT_SQL in
+-- src/store/postgres.rs, or the planner won't use the index.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_memories_symbolic_trgm ON memories USING GIN (
+    (COALESCE(tags::text, '') || ' ' || COALESCE(extracted_entities::text, '') || ' ' || COALESCE(extracted_facts::text, ''))
+    gin_trgm_ops
+);
@@ -0,0 +1,10 @@
+-- Migration 030: Memory provenance
+-- A memory can point back to where it came from: a conversation and message, a file, or a
+-- URL. origin_refs is a JSON array of such references — one for a stored memory, the union
+-- of its originals' for a consolidated one. Filters use JSONB containment
+-- (origin_refs @> '[{"conversation_id": "..."}]'), which the jsonb_path_ops GIN index serves.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS origin_refs JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_memories_origin_refs ON memories USING GIN (origin_refs jsonb_path_ops)
+    WHERE origin_refs IS NOT NULL;
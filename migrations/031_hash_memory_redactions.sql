@@ -0,0 +1,10 @@
+-- Migration 031: Redaction records without the redacted values
+-- memory_redactions kept every redacted original, in plaintext unless content encryption
+-- was on. A record now holds only the kind, the placeholder, where the placeholder sits in
+-- the stored content (byte_offset), and — with security.encryption_key set — an HMAC-SHA256
+-- of the original under a key derived from it, so whoever holds the key can check whether a
+-- given value was redacted. Originals already recorded are dropped.
+
+ALTER TABLE memory_redactions ADD COLUMN IF NOT EXISTS byte_offset INTEGER;
+ALTER TABLE memory_redactions ADD COLUMN IF NOT EXISTS original_hash TEXT;
+ALTER TABLE memory_redactions DROP COLUMN IF EXISTS original;
@@ -0,0 +1,11 @@
+-- Migration 032: Exact-duplicate lookups under content encryption
+-- content_hash (migration 013) hashes the stored content, which is ciphertext once
+-- security.encryption_key is set — a fresh nonce per write, so equal memories never hash
+-- equally. With a key, every write also stores content_digest: an HMAC-SHA256 of the
+-- normalized plaintext under a key derived from the encryption key, and the exact-duplicate
+-- probe compares that instead. Rows written without a key leave it NULL.
+
+ALTER TABLE memories ADD COLUMN IF NOT EXISTS content_digest TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_memories_namespace_content_digest
+    ON memories(namespace, content_digest) WHERE deleted_at IS NULL AND content_digest IS NOT NULL;
@@ -0,0 +1,331 @@
+//! Admin listener: JSON-RPC 2.0 over localhost TCP, separate from the MCP tool surface.
+//!
+//! Operational commands — queue depths, embedding stats, clearing the search cache, forcing
+//! a consolidation scan — are served on [admin] listen_addr for the CLI subcommands, so they
+//! neither go through agent-facing tools nor open their own database pool. Requests and
+//! responses are newline-delimited JSON-RPC 2.0 objects. Each connection must first call
+//! `auth` with the configured token; the listener only binds loopback addresses.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Methods a handler answers, besides `auth`.
+pub const METHODS: &[&str] = &["status", "embed.stats", "cache.clear", "consolidation.run"];
+
+/// Longest request line accepted; longer requests close the connection.
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// How long the client waits to connect before assuming no server is running.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// JSON-RPC 2.0 error codes used by the admin listener.
+pub mod rpc_codes {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// The method ran and failed (storage error, missing provider)
+    pub const SERVER_ERROR: i64 = -32000;
+    /// The connection has not authenticated, or the token was wrong
+    pub const UNAUTHORIZED: i64 = -32001;
+}
+
+/// Errors from the admin listener and client.
+#[derive(Debug, Error)]
+pub enum AdminError {
+    /// Invalid [admin] settings (missing token, non-loopback address)
+    #[error("Admin configuration error: {0}")]
+    Config(String),
+
+    #[error("Admin connection failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The server answered with a JSON-RPC error
+    #[error("Admin call failed ({code}): {message}")]
+    Rpc { code: i64, message: String },
+
+    /// The server's response was not valid JSON-RPC
+    #[error("Invalid admin response: {0}")]
+    Protocol(String),
+}
+
+/// A JSON-RPC error returned by an `AdminHandler`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError { code, message: message.into() }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        RpcError::new(rpc_codes::INVALID_PARAMS, message)
+    }
+
+    pub fn server(message: impl Into<String>) -> Self {
+        RpcError::new(rpc_codes::SERVER_ERROR, message)
+    }
+}
+
+/// Answers authenticated admin calls. Implemented by `MemoryService`.
+#[async_trait]
+pub trait AdminHandler: Send + Sync {
+    /// Run `method` (one of METHODS) with its `params` object.
+    async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError>;
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    jsonrpc: String,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A bound admin listener, ready to serve.
+pub struct AdminListener {
+    listener: TcpListener,
+    token: Arc<String>,
+}
+
+impl AdminListener {
+    /// Bind `listen_addr`. Fails when the token is empty or the address is not loopback.
+    pub async fn bind(listen_addr: &str, token: Option<&str>) -> Result<Self, AdminError> {
+        let token = token.map(str::trim).filter(|t| !t.is_empty()).ok_or_else(|| {
+            AdminError::Config(
+                "admin.token is required when admin.listen_addr is set. \
+                 Set MEMCP_ADMIN__TOKEN in the environment"
+                    .to_string(),
+            )
+        })?;
+        let listener = TcpListener::bind(listen_addr).await?;
+        let local = listener.local_addr()?;
+        if !local.ip().is_loopback() {
+            return Err(AdminError::Config(format!(
+                "admin.listen_addr must be a loopback address (got {})",
+                local
+            )));
+        }
+        Ok(AdminListener { listener, token: Arc::new(token.to_string()) })
+    }
+
+    /// The bound address (useful with port 0).
+    pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accept connections until the process exits, one task per connection.
+    pub async fn serve<H: AdminHandler + 'static>(self, handler: Arc<H>) {
+        if let Ok(addr) = self.listener.local_addr() {
+            tracing::info!(addr = %addr, "Admin listener started");
+        }
+        loop {
+            let socket = match self.listener.accept().await {
+                Ok((socket, _)) => socket,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Admin listener accept failed");
+                    continue;
+                }
+            };
+            let (handler, token) = (handler.clone(), self.token.clone());
+            tokio::spawn(async move {
+                if let Err(e) = serve_connection(socket, handler.as_ref(), &token).await {
+                    tracing::debug!(error = %e, "Admin connection closed");
+                }
+            });
+        }
+    }
+}
+
+/// Read one request line. Returns None at end of stream; oversized lines are an error.
+async fn read_line(reader: &mut BufReader<OwnedReadHalf>) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    let n = reader.take(MAX_REQUEST_BYTES as u64 + 1).read_line(&mut line).await?;
+    if n == 0 {
+        return Ok(None);
+    }
+    if n > MAX_REQUEST_BYTES {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "admin request too large"));
+    }
+    Ok(Some(line))
+}
+
+async fn write_message(writer: &mut OwnedWriteHalf, message: &Value) -> std::io::Result<()> {
+    let mut bytes = serde_json::to_vec(message).map_err(std::io::Error::other)?;
+    bytes.push(b'\n');
+    writer.write_all(&bytes).await
+}
+
+/// Compare tokens without short-circuiting on the first differing byte.
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn serve_connection(socket: TcpStream, handler: &dyn AdminHandler, token: &str) -> std::io::Result<()> {
+    let (reader, mut writer) = socket.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut authenticated = false;
+    while let Some(line) = read_line(&mut reader).await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Err(e) => error_response(Value::Null, rpc_codes::PARSE_ERROR, &format!("Invalid request JSON: {}", e)),
+            Ok(request) if request.jsonrpc != "2.0" => {
+                error_response(request.id, rpc_codes::INVALID_REQUEST, "Field 'jsonrpc' must be \"2.0\"")
+            }
+            Ok(request) if request.method == "auth" => {
+                let given = request.params.get("token").and_then(Value::as_str).unwrap_or_default();
+                authenticated = token_matches(given, token);
+                if authenticated {
+                    result_response(request.id, json!({ "authenticated": true }))
+                } else {
+                    tracing::warn!("Admin connection presented a wrong token");
+                    error_response(request.id, rpc_codes::UNAUTHORIZED, "Invalid admin token")
+                }
+            }
+            Ok(request) if !authenticated => {
+                error_response(request.id, rpc_codes::UNAUTHORIZED, "Call 'auth' with the admin token first")
+            }
+            Ok(request) if !METHODS.contains(&request.method.as_str()) => error_response(
+                request.id,
+                rpc_codes::METHOD_NOT_FOUND,
+                &format!("Unknown method '{}' (expected one of: {})", request.method, METHODS.join(", ")),
+            ),
+            Ok(request) => {
+                tracing::info!(method = %request.method, "Admin call");
+                let params = if request.params.is_null() { json!({}) } else { request.params };
+                match handler.handle(&request.method, params).await {
+                    Ok(result) => result_response(request.id, result),
+                    Err(e) => error_response(request.id, e.code, &e.message),
+                }
+            }
+        };
+        write_message(&mut writer, &response).await?;
+    }
+    Ok(())
+}
+
+fn result_response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Client for a running server's admin listener, used by the CLI subcommands.
+pub struct AdminClient {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+    next_id: u64,
+}
+
+impl AdminClient {
+    /// Connect to `addr` and authenticate with `token`.
+    pub async fn connect(addr: &str, token: &str) -> Result<Self, AdminError> {
+        let socket = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr))
+            .await
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out connecting"))??;
+        let (reader, writer) = socket.into_split();
+        let mut client = AdminClient { reader: BufReader::new(reader), writer, next_id: 1 };
+        client.call("auth", json!({ "token": token })).await?;
+        Ok(client)
+    }
+
+    /// Call `method` and return its result.
+    pub async fn call(&mut self, method: &str, params: Value) -> Result<Value, AdminError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        write_message(&mut self.writer, &request).await?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).await? == 0 {
+            return Err(AdminError::Protocol("connection closed before a response".to_string()));
+        }
+        let mut response: Value =
+            serde_json::from_str(&line).map_err(|e| AdminError::Protocol(e.to_string()))?;
+        if response["id"] != json!(id) {
+            return Err(AdminError::Protocol(format!("expected response id {}, got {}", id, response["id"])));
+        }
+        if let Some(error) = response.get("error") {
+            return Err(AdminError::Rpc {
+                code: error["code"].as_i64().unwrap_or(rpc_codes::SERVER_ERROR),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        response
+            .get_mut("result")
+            .map(Value::take)
+            .ok_or_else(|| AdminError::Protocol("response has neither result nor error".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    #[async_trait]
+    impl AdminHandler for Echo {
+        async fn handle(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+            match method {
+                "status" => Ok(json!({ "method": method, "params": params })),
+                _ => Err(RpcError::server("unavailable")),
+            }
+        }
+    }
+
+    async fn start() -> String {
+        let listener = AdminListener::bind("127.0.0.1:0", Some("secret")).await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(listener.serve(Arc::new(Echo)));
+        addr
+    }
+
+    #[tokio::test]
+    async fn calls_require_the_token() {
+        let addr = start().await;
+        let mut client = AdminClient::connect(&addr, "secret").await.unwrap();
+        let result = client.call("status", json!({ "verbose": true })).await.unwrap();
+        assert_eq!(result, json!({ "method": "status", "params": { "verbose": true } }));
+
+        match client.call("cache.clear", json!({})).await {
+            Err(AdminError::Rpc { code, .. }) => assert_eq!(code, rpc_codes::SERVER_ERROR),
+            other => panic!("expected a server error, got {:?}", other.map(|_| ())),
+        }
+        match client.call("drop_tables", json!({})).await {
+            Err(AdminError::Rpc { code, .. }) => assert_eq!(code, rpc_codes::METHOD_NOT_FOUND),
+            other => panic!("expected method not found, got {:?}", other.map(|_| ())),
+        }
+
+        match AdminClient::connect(&addr, "wrong").await {
+            Err(AdminError::Rpc { code, .. }) => assert_eq!(code, rpc_codes::UNAUTHORIZED),
+            other => panic!("expected unauthorized, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn bind_rejects_missing_token_and_remote_addresses() {
+        assert!(matches!(AdminListener::bind("127.0.0.1:0", None).await, Err(AdminError::Config(_))));
+        assert!(matches!(AdminListener::bind("127.0.0.1:0", Some("  ")).await, Err(AdminError::Config(_))));
+        assert!(matches!(AdminListener::bind("0.0.0.0:0", Some("secret")).await, Err(AdminError::Config(_))));
+    }
+}
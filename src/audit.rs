@@ -0,0 +1,145 @@
+//! Tool-call audit log.
+//!
+//! With `[audit] enabled = true`, MemoryService records one entry per tool call after it
+//! completes. Parameters are stored only as an MD5 hash (computed by Postgres) so the log
+//! never duplicates memory content; the memory IDs a call touched are pulled from its
+//! parameters and structured result instead.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Keys whose string (or string array) values are memory IDs in tool parameters and results.
+/// `session_id` is deliberately absent — sessions are not memories.
+const MEMORY_ID_KEYS: &[&str] = &[
+    "id",
+    "ids",
+    "memory_id",
+    "memory_ids",
+    "source_id",
+    "target_id",
+    "source_ids",
+    "parent_id",
+    "chunk_ids",
+    "stored_id",
+    "restored_ids",
+    "consolidated_id",
+    "consolidated_ids",
+    "summary_memory_id",
+];
+
+/// One tool call to record.
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    /// Tool name as called
+    pub tool: String,
+    /// Canonical JSON of the call's arguments; only its hash is persisted
+    pub params_json: String,
+    /// Memory IDs named in the arguments or result, deduplicated in first-seen order
+    pub memory_ids: Vec<String>,
+    /// Wall-clock time spent in the tool
+    pub latency_ms: i32,
+    /// "ok" or "error"
+    pub outcome: String,
+    /// Error code from the structured error body (None on success)
+    pub error_code: Option<String>,
+}
+
+/// A recorded audit log row.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    pub id: i64,
+    pub tool: String,
+    /// MD5 of the canonical JSON arguments, so identical calls can be correlated
+    pub params_hash: String,
+    pub memory_ids: Vec<String>,
+    pub latency_ms: i32,
+    pub outcome: String,
+    pub error_code: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters for reading the audit log back, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub tool: Option<String>,
+    /// "ok" or "error"
+    pub outcome: Option<String>,
+    /// Only calls that touched this memory
+    pub memory_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+/// Collect the memory IDs a call touched from its arguments and structured result.
+pub fn affected_memory_ids(params: &Value, result: Option<&Value>) -> Vec<String> {
+    let mut ids = Vec::new();
+    collect_ids(params, &mut ids);
+    if let Some(result) = result {
+        collect_ids(result, &mut ids);
+    }
+    ids
+}
+
+fn collect_ids(value: &Value, ids: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if MEMORY_ID_KEYS.contains(&key.as_str()) {
+                    match value {
+                        Value::String(id) => push_id(ids, id),
+                        Value::Array(items) => items
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .for_each(|id| push_id(ids, id)),
+                        _ => {}
+                    }
+                } else {
+                    collect_ids(value, ids);
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_ids(item, ids)),
+        _ => {}
+    }
+}
+
+fn push_id(ids: &mut Vec<String>, id: &str) {
+    if !id.is_empty() && !ids.iter().any(|existing| existing == id) {
+        ids.push(id.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn collects_ids_from_params_and_nested_results() {
+        let params = json!({"id": "a", "namespace": "default"});
+        let result = json!({
+            "results": [{"id": "b", "content": "x"}, {"id": "a"}],
+            "chunk_ids": ["c1", "c2"],
+            "edges": [{"source_id": "b", "target_id": "d"}]
+        });
+        let mut ids = affected_memory_ids(&params, Some(&result));
+        assert_eq!(ids[0], "a", "params are read first");
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b", "c1", "c2", "d"]);
+    }
+
+    #[test]
+    fn ignores_session_ids_and_non_string_values() {
+        let params = json!({"session_id": "s1", "ids": [1, "m1"], "limit": 5});
+        let result = json!({"session_id": "s1", "id": null, "summary_memory_id": "m2"});
+        assert_eq!(affected_memory_ids(&params, Some(&result)), vec!["m1", "m2"]);
+    }
+
+    #[test]
+    fn missing_result_uses_params_only() {
+        assert_eq!(affected_memory_ids(&json!({"id": "x"}), None), vec!["x"]);
+        assert!(affected_memory_ids(&json!({}), None).is_empty());
+    }
+}
@@ -59,6 +59,7 @@ pub async fn ingest_question(
                     format!("role:{}", turn.role),
                 ]),
                 created_at: session_date,
+                ..CreateMemory::default()
             };
 
             let stored = store.store(memory).await?;
@@ -22,6 +22,17 @@ pub struct BenchmarkConfig {
     pub symbolic_weight: f64,
     pub qi_expansion: bool,
     pub qi_reranking: bool,
+    /// Fusion strategy for hybrid search: "rrf" (default) or "weighted"
+    #[serde(default = "default_fusion")]
+    pub fusion: String,
+    /// Re-rank fused hits by salience with these settings, e.g. to compare decay models
+    /// (None = keep fusion order)
+    #[serde(default)]
+    pub salience: Option<crate::config::SalienceConfig>,
+}
+
+fn default_fusion() -> String {
+    "rrf".to_string()
 }
 
 /// Predefined configurations for comparison runs.
@@ -34,6 +45,8 @@ pub fn default_configs() -> Vec<BenchmarkConfig> {
             symbolic_weight: 0.0,
             qi_expansion: false,
             qi_reranking: false,
+            fusion: default_fusion(),
+            salience: None,
         },
         BenchmarkConfig {
             name: "hybrid".into(),
@@ -42,6 +55,18 @@ pub fn default_configs() -> Vec<BenchmarkConfig> {
             symbolic_weight: 1.0,
             qi_expansion: false,
             qi_reranking: false,
+            fusion: default_fusion(),
+            salience: None,
+        },
+        BenchmarkConfig {
+            name: "hybrid-weighted".into(),
+            bm25_weight: 1.0,
+            vector_weight: 1.0,
+            symbolic_weight: 1.0,
+            qi_expansion: false,
+            qi_reranking: false,
+            fusion: "weighted".into(),
+            salience: None,
         },
         BenchmarkConfig {
             name: "hybrid+qi".into(),
@@ -50,6 +75,8 @@ pub fn default_configs() -> Vec<BenchmarkConfig> {
             symbolic_weight: 1.0,
             qi_expansion: true,
             qi_reranking: true,
+            fusion: default_fusion(),
+            salience: None,
         },
     ]
 }
@@ -13,7 +13,11 @@ use reqwest::Client;
 
 use crate::embedding::pipeline::EmbeddingPipeline;
 use crate::embedding::EmbeddingProvider;
+use crate::config::SalienceConfig;
+use crate::search::salience::{SalienceInput, SalienceScorer, ScoredHit};
+use crate::search::HybridRawHit;
 use crate::store::postgres::PostgresMemoryStore;
+use crate::store::{Memory, SearchFilter};
 
 use super::dataset::LongMemEvalQuestion;
 use super::{evaluate, BenchmarkConfig, BenchmarkState, QuestionResult};
@@ -99,6 +103,16 @@ pub async fn run_benchmark(
             None
         };
 
+        let fusion = if config.fusion == "weighted" {
+            crate::search::Fusion::Weighted {
+                bm25: config.bm25_weight,
+                vector: config.vector_weight,
+                symbolic: config.symbolic_weight,
+            }
+        } else {
+            crate::search::Fusion::Rrf
+        };
+
         // Embed the question for vector search leg; fall back to BM25-only if embedding fails
         let query_embedding = if vector_k.is_some() {
             match embedding_provider.embed(&question.question).await {
@@ -120,18 +134,24 @@ pub async fn run_benchmark(
             .hybrid_search(
                 &question.question,
                 query_embedding.as_ref(),
-                20,    // fetch 20 candidates from fused results
-                None,  // no date filters for benchmark
-                None,
-                None,  // no tag filters
+                &[],  // no extra symbolic terms
+                // fetch 20 candidates from fused results; no filters, all namespaces
+                &SearchFilter { limit: 20, ..SearchFilter::default() },
                 bm25_k,
                 vector_k,
                 symbolic_k,
+                None,  // configured candidate pool per leg
+                fusion,
             )
             .await?;
 
+        let hits = match config.salience {
+            Some(ref salience) => rerank_by_salience(&store, hits, salience).await?,
+            None => hits.into_iter().map(|h| h.memory).collect(),
+        };
+
         // Take top 10 memories for answer generation (fits context window)
-        let memories: Vec<_> = hits.into_iter().take(10).map(|h| h.memory).collect();
+        let memories: Vec<_> = hits.into_iter().take(10).collect();
         let retrieved_count = memories.len();
 
         // Step 4: Generate answer from retrieved memories via GPT-4o
@@ -184,6 +204,41 @@ pub async fn run_benchmark(
     Ok(state.results)
 }
 
+/// Re-rank fused hits with the salience scorer, as search_memory does.
+async fn rerank_by_salience(
+    store: &PostgresMemoryStore,
+    hits: Vec<HybridRawHit>,
+    config: &SalienceConfig,
+) -> Result<Vec<Memory>, anyhow::Error> {
+    let ids: Vec<String> = hits.iter().map(|h| h.memory.id.clone()).collect();
+    let salience_data = store.get_salience_data(&ids).await?;
+    let mut scored: Vec<ScoredHit> = hits
+        .into_iter()
+        .map(|hit| ScoredHit {
+            memory: hit.memory,
+            rrf_score: hit.rrf_score,
+            salience_score: 0.0,
+            match_source: hit.match_source,
+            breakdown: None,
+        })
+        .collect();
+    let inputs: Vec<SalienceInput> = scored
+        .iter()
+        .map(|hit| {
+            let row = salience_data.get(&hit.memory.id).cloned().unwrap_or_default();
+            let days_since_reinforced = row
+                .last_reinforced_at
+                .map(|dt| (chrono::Utc::now().signed_duration_since(dt).num_seconds() as f64 / 86_400.0).max(0.0))
+                .unwrap_or(365.0);
+            SalienceInput { stability: row.stability, days_since_reinforced, link_degree: 0 }
+        })
+        .collect();
+    // Link degrees aren't fetched
+    let config = SalienceConfig { w_links: 0.0, ..config.clone() };
+    SalienceScorer::new(&config).rank(&mut scored, &inputs);
+    Ok(scored.into_iter().map(|hit| hit.memory).collect())
+}
+
 /// Save benchmark state as JSON to the given path for checkpoint/resume support.
 fn save_checkpoint(state: &BenchmarkState, path: &std::path::Path) -> Result<(), anyhow::Error> {
     let json = serde_json::to_string_pretty(state)?;
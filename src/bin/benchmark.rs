@@ -1,7 +1,7 @@
 /// Benchmark CLI binary for LongMemEval evaluation.
 ///
 /// Runs the full benchmark pipeline: load dataset → ingest → search → generate → score.
-/// Supports single config or "all" for comparison across vector-only / hybrid / hybrid+qi.
+/// Supports single config or "all" for comparison across vector-only / hybrid / hybrid-weighted / hybrid+qi.
 /// CI integration via --subset (stratified sample) and --min-accuracy (exit code threshold).
 
 use clap::Parser;
@@ -24,10 +24,15 @@ struct Cli {
     #[arg(long, default_value = "data/longmemeval/longmemeval_s_cleaned.json")]
     dataset: PathBuf,
 
-    /// Search configuration: "vector-only", "hybrid", "hybrid+qi", or "all" for comparison
+    /// Search configuration: "vector-only", "hybrid", "hybrid-weighted", "hybrid+qi", or "all" for comparison
     #[arg(long, default_value = "hybrid")]
     config: String,
 
+    /// Re-rank fused hits by salience using this recency decay curve ("exponential",
+    /// "power_law", or "step"); results are saved under "<config>+<decay model>"
+    #[arg(long)]
+    decay_model: Option<String>,
+
     /// Run only first N questions (for CI speed). Preserves category distribution via truncation sorted by question_id.
     #[arg(long)]
     subset: Option<usize>,
@@ -116,25 +121,43 @@ async fn main() -> Result<(), anyhow::Error> {
         Arc::new(LocalEmbeddingProvider::new(".fastembed_cache").await?);
 
     // No consolidation sender for benchmark (consolidation is MCP live-trigger only)
-    let pipeline = EmbeddingPipeline::new(embedding_provider.clone(), store.clone(), 1000, None);
+    let pipeline = EmbeddingPipeline::new(
+        memcp::embedding::router::EmbeddingRouter::new(embedding_provider.clone()),
+        store.clone(),
+        1000,
+        memcp::config::EmbeddingConfig::default().batch_size,
+        None,
+        memcp::embedding::pipeline::RetryPolicy::default(),
+        memcp::shutdown::Shutdown::new(),
+    );
 
     // 9. Determine configs to run
     let all_configs = default_configs();
-    let configs_to_run: Vec<_> = if cli.config == "all" {
-        all_configs.iter().collect()
+    let mut configs_to_run: Vec<_> = if cli.config == "all" {
+        all_configs.clone()
     } else {
         let found = all_configs
             .iter()
             .find(|c| c.name == cli.config)
             .ok_or_else(|| {
                 anyhow::anyhow!(
-                    "Unknown config '{}'. Valid options: vector-only, hybrid, hybrid+qi, all",
+                    "Unknown config '{}'. Valid options: vector-only, hybrid, hybrid-weighted, hybrid+qi, all",
                     cli.config
                 )
             })?;
-        vec![found]
+        vec![found.clone()]
     };
 
+    // 9b. Re-rank by salience with the requested decay curve (kept apart in checkpoints and reports)
+    if let Some(ref decay_model) = cli.decay_model {
+        let salience = memcp::config::SalienceConfig { decay_model: decay_model.clone(), ..Default::default() };
+        memcp::search::decay::decay_model(&salience)?;
+        for config in &mut configs_to_run {
+            config.name = format!("{}+{}", config.name, decay_model);
+            config.salience = Some(salience.clone());
+        }
+    }
+
     // 10. Run each config
     let mut reports: Vec<BenchmarkReport> = Vec::new();
 
@@ -0,0 +1,102 @@
+//! Splitting oversized memory content into overlapping chunks.
+//!
+//! Chunks end on the strongest nearby boundary — a paragraph break, then a line break,
+//! then a sentence end, then whitespace — and only fall back to a hard cut when the
+//! second half of the window has none. Lengths are counted in chars, not bytes.
+
+/// Split `content` into chunks of at most `chunk_chars` characters.
+///
+/// Consecutive chunks share up to `overlap` characters (capped at half a chunk), starting
+/// on a word boundary, so a sentence cut at a boundary is still seen whole by one chunk.
+/// Content that already fits is returned as a single chunk.
+pub fn chunk_content(content: &str, chunk_chars: usize, overlap: usize) -> Vec<String> {
+    let chunk_chars = chunk_chars.max(1);
+    let overlap = overlap.min(chunk_chars / 2);
+    let chars: Vec<char> = content.chars().collect();
+    if chars.len() <= chunk_chars {
+        return vec![content.trim().to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let mut end = (start + chunk_chars).min(chars.len());
+        if end < chars.len() {
+            end = break_point(&chars, start + chunk_chars / 2, end).unwrap_or(end);
+        }
+        let chunk: String = chars[start..end].iter().collect();
+        let chunk = chunk.trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+        if end >= chars.len() {
+            break;
+        }
+
+        // Step back for the overlap, then forward to the start of a word
+        let mut next = end.saturating_sub(overlap).max(start + 1);
+        while next < end && !chars[next - 1].is_whitespace() {
+            next += 1;
+        }
+        start = next;
+    }
+    chunks
+}
+
+/// Latest position in `min..=max` to end a chunk at (exclusive), strongest boundary first.
+fn break_point(chars: &[char], min: usize, max: usize) -> Option<usize> {
+    let is_paragraph = |p: usize| p >= 2 && chars[p - 1] == '\n' && chars[p - 2] == '\n';
+    let is_line = |p: usize| chars[p - 1] == '\n';
+    let is_sentence = |p: usize| p >= 2 && matches!(chars[p - 2], '.' | '!' | '?') && chars[p - 1].is_whitespace();
+    let is_word = |p: usize| chars[p - 1].is_whitespace();
+
+    let boundaries: [&dyn Fn(usize) -> bool; 4] = [&is_paragraph, &is_line, &is_sentence, &is_word];
+    boundaries
+        .iter()
+        .find_map(|is_boundary| (min.max(1)..=max).rev().find(|&p| is_boundary(p)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_content_is_one_chunk() {
+        assert_eq!(chunk_content("  hello world  ", 100, 10), vec!["hello world"]);
+    }
+
+    #[test]
+    fn test_chunks_respect_limit_and_prefer_sentence_ends() {
+        let content = "The first sentence is here. The second sentence follows it. A third one ends the text.";
+        let chunks = chunk_content(content, 40, 0);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 40));
+        assert_eq!(chunks[0], "The first sentence is here.");
+    }
+
+    #[test]
+    fn test_chunks_prefer_paragraph_breaks() {
+        let content = format!("{}\n\n{}", "alpha ".repeat(8).trim(), "beta ".repeat(8).trim());
+        let chunks = chunk_content(&content, 60, 0);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("alpha") && !chunks[0].contains("beta"));
+        assert!(chunks[1].starts_with("beta"));
+    }
+
+    #[test]
+    fn test_overlap_repeats_trailing_words() {
+        let content = "one two three four five six seven eight nine ten eleven twelve";
+        let chunks = chunk_content(content, 30, 12);
+        assert!(chunks.len() > 1);
+        let last_word = chunks[0].split_whitespace().last().unwrap();
+        assert!(chunks[1].contains(last_word), "overlap should carry words into the next chunk");
+    }
+
+    #[test]
+    fn test_hard_cut_without_boundaries_is_char_safe() {
+        let content = "é".repeat(25);
+        let chunks = chunk_content(&content, 10, 0);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), content);
+    }
+}
@@ -0,0 +1,116 @@
+//! Quality guardrails for synthesized consolidations.
+//!
+//! With consolidation.quality_check on, a synthesized memory is only accepted when its length
+//! is plausible for the originals it replaces and — when an extraction provider is
+//! configured — the facts extracted from the originals reappear among the facts extracted
+//! from the synthesis. A rejected synthesis leaves the originals untouched.
+
+use std::collections::HashSet;
+
+use crate::config::ConsolidationConfig;
+
+/// Share of an original fact's words a synthesized fact must contain to count as a match.
+const FACT_MATCH_THRESHOLD: f64 = 0.6;
+
+/// Why a synthesized memory was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    TooShort,
+    TooLong,
+    FactsDropped,
+}
+
+impl Rejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Rejection::TooShort => "too_short",
+            Rejection::TooLong => "too_long",
+            Rejection::FactsDropped => "facts_dropped",
+        }
+    }
+}
+
+/// Check the synthesis length against the originals it replaces.
+///
+/// Too short (relative to the longest original) suggests dropped content; too long
+/// (relative to all originals combined) suggests invented content.
+pub fn check_length(config: &ConsolidationConfig, originals: &[&str], synthesized: &str) -> Result<(), Rejection> {
+    let length = synthesized.chars().count() as f64;
+    let longest = originals.iter().map(|c| c.chars().count()).max().unwrap_or(0) as f64;
+    let combined = originals.iter().map(|c| c.chars().count()).sum::<usize>() as f64;
+    if length < longest * config.min_length_ratio {
+        return Err(Rejection::TooShort);
+    }
+    if length > combined * config.max_length_ratio {
+        return Err(Rejection::TooLong);
+    }
+    Ok(())
+}
+
+/// Lowercased alphanumeric words, dropping single characters.
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 1)
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+/// Fraction of `original_facts` found among `synthesized_facts` (1.0 when there is nothing
+/// to preserve).
+///
+/// Matching is fuzzy: an original fact is preserved when some synthesized fact contains at
+/// least FACT_MATCH_THRESHOLD of its words, so rewording and merged facts still count.
+pub fn fact_coverage(original_facts: &[String], synthesized_facts: &[String]) -> f64 {
+    let originals: Vec<HashSet<String>> = original_facts
+        .iter()
+        .map(|fact| words(fact))
+        .filter(|w| !w.is_empty())
+        .collect();
+    if originals.is_empty() {
+        return 1.0;
+    }
+    let synthesized: Vec<HashSet<String>> = synthesized_facts.iter().map(|fact| words(fact)).collect();
+    let preserved = originals
+        .iter()
+        .filter(|original| {
+            synthesized.iter().any(|candidate| {
+                original.intersection(candidate).count() as f64 / original.len() as f64 >= FACT_MATCH_THRESHOLD
+            })
+        })
+        .count();
+    preserved as f64 / originals.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn facts(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn length_bounds() {
+        let config = ConsolidationConfig::default();
+        let originals = ["The user prefers dark mode in every editor", "User likes dark mode"];
+        assert_eq!(check_length(&config, &originals, "The user prefers dark mode in all editors"), Ok(()));
+        assert_eq!(check_length(&config, &originals, "Dark mode"), Err(Rejection::TooShort));
+        let padded = "The user prefers dark mode. ".repeat(10);
+        assert_eq!(check_length(&config, &originals, &padded), Err(Rejection::TooLong));
+    }
+
+    #[test]
+    fn fact_coverage_matches_reworded_facts() {
+        let original = facts(&[
+            "User prefers dark mode",
+            "User's editor is Neovim",
+            "Deploys happen on Fridays",
+        ]);
+        let synthesized = facts(&["The user prefers dark mode in Neovim, their editor"]);
+        let coverage = fact_coverage(&original, &synthesized);
+        assert!((coverage - 2.0 / 3.0).abs() < 1e-9, "got {}", coverage);
+
+        assert_eq!(fact_coverage(&[], &synthesized), 1.0);
+        assert_eq!(fact_coverage(&original, &[]), 0.0);
+    }
+}
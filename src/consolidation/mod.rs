@@ -8,16 +8,83 @@
 ///
 /// Consolidation is triggered via an mpsc channel from the embedding pipeline.
 /// The background worker processes jobs asynchronously — store_memory never blocks.
-
+/// Synthesis is pluggable via SynthesisProvider — Ollama (local, default) or OpenAI API.
+/// Synthesis runs unlocked; the final re-check and write are serialized across processes by
+/// an advisory lock, so memcp instances sharing a database never consolidate the same
+/// memory twice.
+/// With consolidation.quality_check on, each synthesis passes the guardrails (length and
+/// fact preservation) before it replaces the originals.
+
+pub mod guardrails;
+pub mod ollama;
+pub mod openai;
 pub mod similarity;
 
+use std::collections::HashSet;
 use std::sync::Arc;
+use async_trait::async_trait;
+use thiserror::Error;
 use tokio::sync::mpsc;
 
 use crate::config::ConsolidationConfig;
+use crate::errors::MemcpError;
+use crate::events::{EventKind, EventNotifier, MemoryEvent};
+use crate::extraction::ExtractionProvider;
+use crate::live_config::LiveConfig;
+use crate::shutdown::Shutdown;
 use crate::store::postgres::PostgresMemoryStore;
 use similarity::find_similar_memories;
 
+/// Errors that can occur during consolidation synthesis.
+#[derive(Debug, Error)]
+pub enum SynthesisError {
+    /// Transport failure or non-success HTTP status
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    /// Response could not be parsed, or was empty
+    #[error("Parse error: {0}")]
+    Parse(String),
+
+    /// Provider not configured (e.g., missing API key)
+    #[error("Provider not configured: {0}")]
+    NotConfigured(String),
+}
+
+impl From<crate::llm_client::LlmError> for SynthesisError {
+    fn from(e: crate::llm_client::LlmError) -> Self {
+        match e {
+            crate::llm_client::LlmError::Decode(_) => SynthesisError::Parse(e.to_string()),
+            other => SynthesisError::Http(other.to_string()),
+        }
+    }
+}
+
+/// Core trait for free-text LLM generation over memory contents.
+///
+/// Implementations only provide `complete` (prompt in, plain text out); consolidation
+/// synthesis and on-demand summaries are built on top of it.
+/// Implementations must be Send + Sync to support use across the background worker task
+/// (e.g., Arc<dyn SynthesisProvider>). Callers fall back to concatenation on `Err`.
+#[async_trait]
+pub trait SynthesisProvider: Send + Sync {
+    /// Send a single user prompt and return the trimmed, non-empty response text.
+    async fn complete(&self, prompt: &str) -> Result<String, SynthesisError>;
+
+    /// Synthesize the given memory contents into a single consolidated memory text.
+    async fn synthesize(&self, contents: &[&str]) -> Result<String, SynthesisError> {
+        self.complete(&build_synthesis_prompt(contents)).await
+    }
+
+    /// Summarize the given memory contents into a digest, optionally focused on a topic.
+    async fn summarize(&self, contents: &[&str], focus: Option<&str>) -> Result<String, SynthesisError> {
+        self.complete(&build_summary_prompt(contents, focus)).await
+    }
+
+    /// Return the model name identifier used by this provider.
+    fn model_name(&self) -> &str;
+}
+
 /// A pending consolidation job.
 ///
 /// Created by the embedding pipeline after successful embedding storage.
@@ -34,8 +101,8 @@ pub struct ConsolidationJob {
 /// Background consolidation worker.
 ///
 /// Receives jobs from the embedding pipeline via mpsc channel.
-/// For each job: checks similarity, and if matches found, calls Ollama/OpenAI to synthesize
-/// a consolidated memory, then creates the consolidation record atomically.
+/// For each job: checks similarity, and if matches found, calls the SynthesisProvider to
+/// synthesize a consolidated memory, then creates the consolidation record atomically.
 pub struct ConsolidationWorker {
     sender: mpsc::Sender<ConsolidationJob>,
 }
@@ -44,121 +111,53 @@ impl ConsolidationWorker {
     /// Create a new ConsolidationWorker and spawn the background task.
     ///
     /// - `store`: PostgresMemoryStore for DB operations.
-    /// - `config`: ConsolidationConfig (threshold, max group size).
-    /// - `ollama_base_url`: Ollama base URL for synthesis (e.g., "http://localhost:11434").
-    /// - `ollama_model`: Model to use for synthesis (e.g., "llama3.2:3b").
+    /// - `live`: running config; each job reads the current consolidation threshold and max
+    ///   group size, so a config reload applies to the next job.
+    /// - `provider`: SynthesisProvider used to merge similar memories (Ollama or OpenAI).
+    /// - `extractor`: extraction provider for the quality check's fact comparison (None =
+    ///   length checks only).
     /// - `capacity`: Bounded channel capacity (recommended: 500).
+    /// - `events`: receives a `consolidated` event for each consolidated memory created.
+    /// - `shutdown`: Stops the worker after its current job; queued checks are dropped.
     pub fn new(
         store: Arc<PostgresMemoryStore>,
-        config: ConsolidationConfig,
-        ollama_base_url: String,
-        ollama_model: String,
+        live: LiveConfig,
+        provider: Arc<dyn SynthesisProvider>,
+        extractor: Option<Arc<dyn ExtractionProvider>>,
         capacity: usize,
+        events: EventNotifier,
+        shutdown: Shutdown,
     ) -> Self {
         let (tx, mut rx) = mpsc::channel::<ConsolidationJob>(capacity);
 
-        let client = reqwest::Client::new();
-
-        tokio::spawn(async move {
-            while let Some(job) = rx.recv().await {
-                let pool = store.pool();
-
-                // Find similar memories above threshold
-                let similar = match find_similar_memories(
-                    pool,
-                    &job.memory_id,
-                    &job.embedding,
-                    config.similarity_threshold,
-                    config.max_consolidation_group as i64,
-                )
-                .await
-                {
-                    Ok(s) => s,
-                    Err(e) => {
-                        tracing::warn!(
-                            memory_id = %job.memory_id,
-                            error = %e,
-                            "Similarity search failed during consolidation check"
-                        );
-                        continue;
-                    }
-                };
-
-                if similar.is_empty() {
-                    tracing::debug!(
-                        memory_id = %job.memory_id,
-                        "No similar memories found — skipping consolidation"
-                    );
-                    continue;
-                }
-
-                tracing::info!(
-                    memory_id = %job.memory_id,
-                    similar_count = similar.len(),
-                    "Similar memories found — consolidating"
-                );
-
-                // Collect all contents for synthesis
-                let mut all_contents: Vec<&str> = vec![job.content.as_str()];
-                for s in &similar {
-                    all_contents.push(s.content.as_str());
-                }
-
-                // Synthesize consolidated content via LLM (fallback: concatenation)
-                let synthesized = match synthesize_memories(
-                    &client,
-                    &ollama_base_url,
-                    &ollama_model,
-                    &all_contents,
-                )
-                .await
-                {
-                    Ok(text) => text,
-                    Err(e) => {
-                        tracing::warn!(
-                            memory_id = %job.memory_id,
-                            error = %e,
-                            "LLM synthesis failed — using concatenation fallback"
-                        );
-                        concatenate_memories(&all_contents)
-                    }
+        let worker_shutdown = shutdown.clone();
+        shutdown.spawn(async move {
+            loop {
+                let job = tokio::select! {
+                    biased;
+                    _ = worker_shutdown.cancelled() => break,
+                    job = rx.recv() => match job {
+                        Some(job) => job,
+                        None => break,
+                    },
                 };
-
-                // Collect source IDs and similarity scores (new memory gets similarity 1.0)
-                let mut source_ids: Vec<String> = vec![job.memory_id.clone()];
-                let mut similarities: Vec<f64> = vec![1.0];
-                for s in &similar {
-                    source_ids.push(s.memory_id.clone());
-                    similarities.push(s.similarity);
-                }
-
-                // Atomically create consolidated memory + links + mark originals
-                match store.create_consolidated_memory(&synthesized, &source_ids, &similarities).await {
-                    Ok(consolidated_id) => {
-                        tracing::info!(
-                            consolidated_id = %consolidated_id,
-                            source_count = source_ids.len(),
-                            "Memory consolidation complete"
-                        );
-                    }
-                    Err(e) => {
-                        // UNIQUE constraint violation = already consolidated — safe to ignore
-                        let msg = e.to_string();
-                        if msg.contains("duplicate key") || msg.contains("unique") || msg.contains("23505") {
-                            tracing::debug!(
-                                memory_id = %job.memory_id,
-                                "Consolidation already exists (idempotent) — skipping"
-                            );
-                        } else {
-                            tracing::error!(
-                                memory_id = %job.memory_id,
-                                error = %e,
-                                "Failed to create consolidated memory"
+                let config = live.load();
+                // Failures are logged inside consolidate_memory — keep draining the channel
+                let consolidated = consolidate_memory(&store, &config.consolidation, provider.as_ref(), extractor.as_deref(), &job).await;
+                if let Ok(Some((consolidated_id, source_ids))) = consolidated {
+                    if let Ok(created) = store.get_memories_by_ids(std::slice::from_ref(&consolidated_id)).await {
+                        if let Some(memory) = created.get(&consolidated_id) {
+                            events.emit(
+                                MemoryEvent::new(EventKind::Consolidated, &memory.namespace, vec![consolidated_id.clone()])
+                                    .with_data(serde_json::json!({ "source_ids": source_ids })),
                             );
                         }
                     }
                 }
             }
+            if !rx.is_empty() {
+                tracing::info!(dropped = rx.len(), "Consolidation worker stopped — run `memcp consolidate scan` to catch up");
+            }
         });
 
         ConsolidationWorker { sender: tx }
@@ -170,126 +169,318 @@ impl ConsolidationWorker {
     }
 }
 
-/// Build the synthesis prompt for LLM consolidation.
-fn build_synthesis_prompt(contents: &[&str]) -> String {
-    let mut prompt = "Synthesize these related memories into one comprehensive memory. \
-        Preserve all unique facts, preferences, and specific details. \
-        Do not add information not present in the originals. \
-        Write a single cohesive paragraph.\n\n"
-        .to_string();
-    for (i, content) in contents.iter().enumerate() {
-        prompt.push_str(&format!("Memory {}:\n{}\n\n", i + 1, content));
+/// Advisory lock serializing the final re-check and write of a consolidation across processes.
+pub const CONSOLIDATION_LOCK: &str = "memcp:consolidation";
+
+/// Consolidate one memory with any similar memories above the configured threshold.
+///
+/// Shared by the background worker and on-demand scans: finds similar memories, synthesizes
+/// the group via the provider (falling back to concatenation), and creates the consolidated
+/// memory atomically. Returns the consolidated memory ID plus every source ID it absorbed,
+/// or None when nothing was similar enough, the group was already consolidated, or the
+/// quality check rejected the synthesis (`extractor` supplies its fact comparison).
+pub async fn consolidate_memory(
+    store: &PostgresMemoryStore,
+    config: &ConsolidationConfig,
+    provider: &dyn SynthesisProvider,
+    extractor: Option<&dyn ExtractionProvider>,
+    job: &ConsolidationJob,
+) -> Result<Option<(String, Vec<String>)>, MemcpError> {
+    let current = store.get_memories_by_ids(std::slice::from_ref(&job.memory_id)).await?;
+    if current.get(&job.memory_id).is_some_and(|m| m.is_consolidated_original) {
+        tracing::debug!(memory_id = %job.memory_id, "Memory was already consolidated — skipping");
+        return Ok(None);
     }
-    prompt.push_str("Synthesized memory:");
-    prompt
-}
 
-/// Concatenate memories as a fallback when LLM synthesis fails.
-fn concatenate_memories(contents: &[&str]) -> String {
-    contents
-        .iter()
-        .enumerate()
-        .map(|(i, c)| format!("Memory {}:\n{}", i + 1, c))
-        .collect::<Vec<_>>()
-        .join("\n---\n")
-}
+    // Find similar memories above threshold
+    let mut similar = match find_similar_memories(
+        store.pool(),
+        &job.memory_id,
+        &job.embedding,
+        config.similarity_threshold,
+        config.max_consolidation_group as i64,
+    )
+    .await
+    {
+        Ok(s) => s,
+        Err(e) => {
+            tracing::warn!(
+                memory_id = %job.memory_id,
+                error = %e,
+                "Similarity search failed during consolidation check"
+            );
+            return Err(e);
+        }
+    };
 
-/// Synthesis error for internal use.
-#[derive(Debug)]
-enum SynthesisError {
-    Http(String),
-    Parse(String),
-}
+    if similar.is_empty() {
+        tracing::debug!(
+            memory_id = %job.memory_id,
+            "No similar memories found — skipping consolidation"
+        );
+        return Ok(None);
+    }
+
+    tracing::info!(
+        memory_id = %job.memory_id,
+        similar_count = similar.len(),
+        "Similar memories found — consolidating"
+    );
 
-impl std::fmt::Display for SynthesisError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SynthesisError::Http(e) => write!(f, "HTTP error: {}", e),
-            SynthesisError::Parse(e) => write!(f, "Parse error: {}", e),
+    // find_similar_memories reads the table directly, so content may still be encrypted
+    for s in &mut similar {
+        s.content = store.decrypt_content(std::mem::take(&mut s.content))?;
+    }
+
+    // Collect source IDs and similarity scores (new memory gets similarity 1.0)
+    let mut source_ids: Vec<String> = vec![job.memory_id.clone()];
+    let mut similarities: Vec<f64> = vec![1.0];
+    for s in &similar {
+        source_ids.push(s.memory_id.clone());
+        similarities.push(s.similarity);
+    }
+
+    if config.quality_check && store.consolidation_rejected(&source_ids).await? {
+        tracing::debug!(
+            memory_id = %job.memory_id,
+            "Synthesis of this group was rejected before — skipping consolidation"
+        );
+        return Ok(None);
+    }
+
+    // Collect all contents for synthesis
+    let mut all_contents: Vec<&str> = vec![job.content.as_str()];
+    for s in &similar {
+        all_contents.push(s.content.as_str());
+    }
+
+    // Synthesize consolidated content via LLM (fallback: concatenation, which loses nothing
+    // and skips the quality check)
+    let (synthesized, fact_coverage) = match provider.synthesize(&all_contents).await {
+        Ok(text) if config.quality_check => {
+            match review_synthesis(config, extractor, &all_contents, &text).await {
+                Ok(coverage) => (text, coverage),
+                Err((reason, coverage)) => {
+                    crate::metrics::global().consolidation_rejections.inc();
+                    tracing::info!(
+                        memory_id = %job.memory_id,
+                        reason = reason.as_str(),
+                        fact_coverage = ?coverage,
+                        "Synthesized consolidation rejected by quality check — keeping originals"
+                    );
+                    store.record_consolidation_rejection(&source_ids, reason.as_str(), coverage).await?;
+                    return Ok(None);
+                }
+            }
+        }
+        Ok(text) => (text, None),
+        Err(e) => {
+            tracing::warn!(
+                memory_id = %job.memory_id,
+                model = provider.model_name(),
+                error = %e,
+                "LLM synthesis failed — using concatenation fallback"
+            );
+            (concatenate_memories(&all_contents), None)
         }
+    };
+
+    // Synthesis ran unlocked, so another process may have absorbed some of the group since.
+    // Re-check and write under a lock shared by every process using the database, held only
+    // for these two queries rather than through the LLM calls.
+    let _lock = store.advisory_lock(CONSOLIDATION_LOCK).await?;
+    let current = store.get_memories_by_ids(&source_ids).await?;
+    if source_ids.iter().any(|id| current.get(id).is_none_or(|m| m.is_consolidated_original || m.deleted_at.is_some())) {
+        tracing::debug!(
+            memory_id = %job.memory_id,
+            "Part of the group was consolidated or deleted during synthesis — skipping"
+        );
+        return Ok(None);
     }
-}
 
-/// Ollama request for free-form synthesis (no format schema — want plain text).
-#[derive(serde::Serialize)]
-struct OllamaSynthesisRequest {
-    model: String,
-    messages: Vec<OllamaMessage>,
-    stream: bool,
-    options: OllamaOptions,
+    // Atomically create consolidated memory + links + mark originals
+    match store.create_consolidated_memory(&synthesized, &source_ids, &similarities, fact_coverage).await {
+        Ok(consolidated_id) => {
+            crate::metrics::global().consolidation_merges.inc();
+            tracing::info!(
+                consolidated_id = %consolidated_id,
+                source_count = source_ids.len(),
+                "Memory consolidation complete"
+            );
+            Ok(Some((consolidated_id, source_ids)))
+        }
+        Err(e) => {
+            // UNIQUE constraint violation = already consolidated — safe to ignore
+            let msg = e.to_string();
+            if msg.contains("duplicate key") || msg.contains("unique") || msg.contains("23505") {
+                tracing::debug!(
+                    memory_id = %job.memory_id,
+                    "Consolidation already exists (idempotent) — skipping"
+                );
+                Ok(None)
+            } else {
+                tracing::error!(
+                    memory_id = %job.memory_id,
+                    error = %e,
+                    "Failed to create consolidated memory"
+                );
+                Err(e)
+            }
+        }
+    }
 }
 
-#[derive(serde::Serialize)]
-struct OllamaMessage {
-    role: String,
-    content: String,
-}
+/// Run the quality check on an LLM synthesis of `originals`.
+///
+/// Returns the measured fact coverage (None when there was no extractor or extraction
+/// failed — the length check alone then decides), or the rejection reason with the coverage.
+async fn review_synthesis(
+    config: &ConsolidationConfig,
+    extractor: Option<&dyn ExtractionProvider>,
+    originals: &[&str],
+    synthesized: &str,
+) -> Result<Option<f64>, (guardrails::Rejection, Option<f64>)> {
+    guardrails::check_length(config, originals, synthesized).map_err(|reason| (reason, None))?;
+    let Some(extractor) = extractor else {
+        return Ok(None);
+    };
 
-#[derive(serde::Serialize)]
-struct OllamaOptions {
-    temperature: f32,
-}
+    // Facts of each original, then of the synthesis
+    let mut extracted = Vec::with_capacity(originals.len() + 1);
+    for content in originals.iter().chain(std::iter::once(&synthesized)) {
+        match extractor.extract(content).await {
+            Ok(result) => extracted.push(result.facts),
+            Err(e) => {
+                tracing::warn!(error = %e, "Fact extraction failed during consolidation quality check — checking length only");
+                return Ok(None);
+            }
+        }
+    }
+    let synthesized_facts = extracted.pop().unwrap_or_default();
+    let original_facts: Vec<String> = extracted.into_iter().flatten().collect();
 
-#[derive(serde::Deserialize)]
-struct OllamaSynthesisResponse {
-    message: OllamaResponseMessage,
+    let coverage = guardrails::fact_coverage(&original_facts, &synthesized_facts);
+    if coverage < config.min_fact_coverage {
+        return Err((guardrails::Rejection::FactsDropped, Some(coverage)));
+    }
+    Ok(Some(coverage))
 }
 
-#[derive(serde::Deserialize)]
-struct OllamaResponseMessage {
-    content: String,
+/// Running totals for an on-demand consolidation scan.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct ScanProgress {
+    /// Memories checked for similar neighbours so far.
+    pub scanned: u64,
+    /// Clusters merged into a new consolidated memory.
+    pub clusters: u64,
+    /// Original memories absorbed into those clusters.
+    pub consolidated: u64,
+    /// Memories whose check failed (similarity search or storage error).
+    pub failed: u64,
+    /// IDs of the consolidated memories created.
+    pub consolidated_ids: Vec<String>,
 }
 
-/// Call Ollama for free-form text synthesis of consolidated memory.
+/// Consolidate the existing corpus rather than waiting for new embeddings.
 ///
-/// No `format` field (unlike extraction) — we want plain text, not structured JSON.
-/// Falls through to `Err` on any failure; caller falls back to concatenation.
-async fn synthesize_memories(
-    client: &reqwest::Client,
-    base_url: &str,
-    model: &str,
-    contents: &[&str],
-) -> Result<String, SynthesisError> {
-    let prompt = build_synthesis_prompt(contents);
-
-    let request = OllamaSynthesisRequest {
-        model: model.to_string(),
-        messages: vec![OllamaMessage {
-            role: "user".to_string(),
-            content: prompt,
-        }],
-        stream: false,
-        options: OllamaOptions { temperature: 0.2 },
-    };
+/// Walks embedded memories oldest first in batches of `batch_size` (optionally restricted to
+/// one namespace) and runs each through the same path as the background worker. Stops after
+/// `max_memories` checks when set. `on_batch` is called with the running totals after every
+/// batch so callers can report progress.
+#[allow(clippy::too_many_arguments)]
+pub async fn scan_existing(
+    store: &PostgresMemoryStore,
+    config: &ConsolidationConfig,
+    provider: &dyn SynthesisProvider,
+    extractor: Option<&dyn ExtractionProvider>,
+    namespace: Option<&str>,
+    batch_size: i64,
+    max_memories: Option<u64>,
+    mut on_batch: impl FnMut(&ScanProgress),
+) -> Result<ScanProgress, MemcpError> {
+    let mut progress = ScanProgress::default();
+    // Memories absorbed earlier in this scan are still in already-fetched batches
+    let mut absorbed: HashSet<String> = HashSet::new();
+    let mut after = None;
+
+    loop {
+        let remaining = max_memories.map(|max| max.saturating_sub(progress.scanned));
+        if remaining == Some(0) {
+            break;
+        }
+        let limit = remaining.map_or(batch_size, |r| batch_size.min(r as i64)).max(1);
+        let batch = store.get_consolidation_candidates(namespace, after.as_ref(), limit).await?;
+        let Some((last, _)) = batch.last() else {
+            break;
+        };
+        after = Some((last.created_at, last.id.clone()));
+        let fetched = batch.len() as i64;
+
+        for (memory, embedding) in batch {
+            if absorbed.contains(&memory.id) {
+                continue;
+            }
+            progress.scanned += 1;
+            let job = ConsolidationJob { memory_id: memory.id, embedding, content: memory.content };
+            match consolidate_memory(store, config, provider, extractor, &job).await {
+                Ok(Some((consolidated_id, source_ids))) => {
+                    progress.clusters += 1;
+                    progress.consolidated += source_ids.len() as u64;
+                    progress.consolidated_ids.push(consolidated_id);
+                    absorbed.extend(source_ids);
+                }
+                Ok(None) => {}
+                Err(_) => progress.failed += 1,
+            }
+        }
 
-    let url = format!("{}/api/chat", base_url);
-
-    let response = client
-        .post(&url)
-        .json(&request)
-        .send()
-        .await
-        .map_err(|e| SynthesisError::Http(format!("Request failed: {}", e)))?;
-
-    if !response.status().is_success() {
-        let status = response.status().as_u16();
-        let body = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "unknown error".to_string());
-        return Err(SynthesisError::Http(format!("Status {}: {}", status, body)));
+        on_batch(&progress);
+        if fetched < limit {
+            break;
+        }
     }
 
-    let chat_response: OllamaSynthesisResponse = response
-        .json()
-        .await
-        .map_err(|e| SynthesisError::Parse(format!("Failed to parse Ollama response: {}", e)))?;
+    Ok(progress)
+}
 
-    let text = chat_response.message.content.trim().to_string();
-    if text.is_empty() {
-        return Err(SynthesisError::Parse("Empty synthesis response".to_string()));
+/// Build the synthesis prompt for LLM consolidation.
+pub fn build_synthesis_prompt(contents: &[&str]) -> String {
+    let mut prompt = "Synthesize these related memories into one comprehensive memory. \
+        Preserve all unique facts, preferences, and specific details. \
+        Do not add information not present in the originals. \
+        Write a single cohesive paragraph.\n\n"
+        .to_string();
+    for (i, content) in contents.iter().enumerate() {
+        prompt.push_str(&format!("Memory {}:\n{}\n\n", i + 1, content));
     }
+    prompt.push_str("Synthesized memory:");
+    prompt
+}
 
-    Ok(text)
+/// Build the prompt for an on-demand digest of many memories (summarize_memories tool).
+pub fn build_summary_prompt(contents: &[&str], focus: Option<&str>) -> String {
+    let mut prompt = "Write a concise summary document of the memories below. \
+        Group related points under short headings, keep specific names, dates, and numbers, \
+        and note any memories that contradict each other. \
+        Do not add information not present in the memories.\n"
+        .to_string();
+    if let Some(focus) = focus {
+        prompt.push_str(&format!("Focus the summary on: {}\n", focus));
+    }
+    prompt.push('\n');
+    for (i, content) in contents.iter().enumerate() {
+        prompt.push_str(&format!("Memory {}:\n{}\n\n", i + 1, content));
+    }
+    prompt.push_str("Summary:");
+    prompt
 }
 
+/// Concatenate memories as a fallback when LLM synthesis fails.
+fn concatenate_memories(contents: &[&str]) -> String {
+    contents
+        .iter()
+        .enumerate()
+        .map(|(i, c)| format!("Memory {}:\n{}", i + 1, c))
+        .collect::<Vec<_>>()
+        .join("\n---\n")
+}
@@ -0,0 +1,101 @@
+//! Ollama synthesis provider
+//!
+//! Calls a local Ollama instance's /api/chat endpoint for free-form consolidation text.
+//! Default provider — no API key required.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::config::LlmConfig;
+use crate::llm_client::LlmClient;
+
+use super::{SynthesisError, SynthesisProvider};
+
+/// Ollama request for free-form synthesis (no format schema — want plain text).
+#[derive(Serialize)]
+struct OllamaSynthesisRequest {
+    model: String,
+    messages: Vec<OllamaMessage>,
+    stream: bool,
+    options: OllamaOptions,
+}
+
+#[derive(Serialize)]
+struct OllamaMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+}
+
+#[derive(Deserialize)]
+struct OllamaSynthesisResponse {
+    message: OllamaResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponseMessage {
+    content: String,
+}
+
+/// Ollama-backed synthesis provider.
+pub struct OllamaSynthesisProvider {
+    client: LlmClient,
+    base_url: String,
+    model: String,
+}
+
+impl OllamaSynthesisProvider {
+    /// Create a new OllamaSynthesisProvider.
+    ///
+    /// # Arguments
+    /// * `base_url` - Ollama server URL (e.g., "http://localhost:11434")
+    /// * `model` - Model name (e.g., "llama3.2:3b")
+    pub fn new(base_url: String, model: String) -> Self {
+        OllamaSynthesisProvider {
+            client: LlmClient::new(&LlmConfig::default()),
+            base_url,
+            model,
+        }
+    }
+
+    /// Route requests through a configured `LlmClient` (retries, timeout, circuit breaker).
+    pub fn with_llm_client(mut self, client: LlmClient) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+#[async_trait]
+impl SynthesisProvider for OllamaSynthesisProvider {
+    /// No `format` field (unlike extraction) — we want plain text, not structured JSON.
+    async fn complete(&self, prompt: &str) -> Result<String, SynthesisError> {
+        let request = OllamaSynthesisRequest {
+            model: self.model.clone(),
+            messages: vec![OllamaMessage {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            stream: false,
+            options: OllamaOptions { temperature: 0.2 },
+        };
+
+        let url = format!("{}/api/chat", self.base_url);
+
+        let chat_response: OllamaSynthesisResponse = self.client.post_json(&url, None, &request).await?;
+
+        let text = chat_response.message.content.trim().to_string();
+        if text.is_empty() {
+            return Err(SynthesisError::Parse("Empty synthesis response".to_string()));
+        }
+
+        Ok(text)
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+}
@@ -0,0 +1,123 @@
+//! OpenAI synthesis provider
+//!
+//! Calls an OpenAI-compatible Chat Completions API for free-form consolidation text.
+//! Uses gpt-4o-mini by default — requires MEMCP_CONSOLIDATION__OPENAI_API_KEY
+//! (or falls back to the extraction API key).
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::config::LlmConfig;
+use crate::llm_client::LlmClient;
+
+use super::{SynthesisError, SynthesisProvider};
+
+/// Request body for OpenAI Chat Completions API
+#[derive(Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+}
+
+#[derive(Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+/// Response from OpenAI Chat Completions API
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatResponseMessage {
+    content: String,
+}
+
+/// OpenAI-backed synthesis provider.
+///
+/// Works with any OpenAI-compatible endpoint via `base_url`.
+pub struct OpenAISynthesisProvider {
+    client: LlmClient,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+impl OpenAISynthesisProvider {
+    /// Create a new OpenAISynthesisProvider.
+    ///
+    /// # Arguments
+    /// * `base_url` - API base URL (e.g., "https://api.openai.com/v1")
+    /// * `api_key` - API key (must be non-empty)
+    /// * `model` - Model name (default: "gpt-4o-mini")
+    ///
+    /// # Errors
+    /// Returns `SynthesisError::NotConfigured` if api_key is empty.
+    pub fn new(base_url: String, api_key: String, model: String) -> Result<Self, SynthesisError> {
+        if api_key.trim().is_empty() {
+            return Err(SynthesisError::NotConfigured(
+                "OpenAI API key is required when using the openai consolidation provider. \
+                 Set MEMCP_CONSOLIDATION__OPENAI_API_KEY in the environment"
+                    .to_string(),
+            ));
+        }
+
+        Ok(OpenAISynthesisProvider {
+            client: LlmClient::new(&LlmConfig::default()),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+        })
+    }
+
+    /// Route requests through a configured `LlmClient` (retries, timeout, circuit breaker).
+    pub fn with_llm_client(mut self, client: LlmClient) -> Self {
+        self.client = client;
+        self
+    }
+}
+
+#[async_trait]
+impl SynthesisProvider for OpenAISynthesisProvider {
+    async fn complete(&self, prompt: &str) -> Result<String, SynthesisError> {
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            }],
+            temperature: 0.2,
+        };
+
+        let url = format!("{}/chat/completions", self.base_url);
+        let chat_response: ChatResponse = self
+            .client
+            .post_json(&url, Some(&self.api_key), &request)
+            .await?;
+
+        let text = chat_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message.content.trim().to_string())
+            .unwrap_or_default();
+        if text.is_empty() {
+            return Err(SynthesisError::Parse("Empty synthesis response".to_string()));
+        }
+
+        Ok(text)
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+}
@@ -23,6 +23,9 @@ pub struct SimilarMemory {
 /// - The memory itself (`memory_id != $2`)
 /// - Memories already marked as consolidated originals (`is_consolidated_original = FALSE`)
 /// - Memories that haven't been embedded yet (`embedding_status = 'complete'`)
+/// - Memories in a different namespace than the source memory
+/// - Memories embedded with a different model (e.g. by an embedding route)
+/// - Trashed memories (`deleted_at IS NOT NULL`)
 ///
 /// Returns at most `limit` results, ordered by descending similarity.
 pub async fn find_similar_memories(
@@ -41,7 +44,11 @@ pub async fn find_similar_memories(
          WHERE me.is_current = TRUE
            AND m.embedding_status = 'complete'
            AND m.is_consolidated_original = FALSE
+           AND m.deleted_at IS NULL
            AND me.memory_id != $2
+           AND m.namespace = (SELECT namespace FROM memories WHERE id = $2)
+           AND me.model_name = (SELECT model_name FROM memory_embeddings
+                                WHERE memory_id = $2 AND is_current = TRUE LIMIT 1)
            AND (1 - (me.embedding <=> $1)) >= $3
          ORDER BY cosine_similarity DESC
          LIMIT $4",
@@ -0,0 +1,59 @@
+//! Salience decay archiver.
+//!
+//! Salience is otherwise only computed at query time. This background task periodically
+//! archives memories whose FSRS retrievability has decayed below `decay.archive_threshold`,
+//! keeping long-forgotten memories out of search without deleting them. Archived memories
+//! stay readable by ID, and `restore_memory` brings them back.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::DecayConfig;
+use crate::store::postgres::PostgresMemoryStore;
+
+/// Advisory lock serializing archival scans across processes.
+const DECAY_LOCK: &str = "memcp:decay-archiver";
+
+/// Spawn the background archiver task.
+///
+/// Runs one scan per `sweep_interval_secs` (the first after one full interval).
+/// A tick is skipped while another process is scanning. Scan failures are logged and
+/// retried on the next tick — the task never exits.
+pub fn spawn_decay_archiver(
+    store: Arc<PostgresMemoryStore>,
+    config: DecayConfig,
+) -> tokio::task::JoinHandle<()> {
+    let threshold = config.archive_threshold.clamp(0.0, 1.0);
+    let period = Duration::from_secs(config.sweep_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let lock = match store.try_advisory_lock(DECAY_LOCK).await {
+                Ok(Some(lock)) => lock,
+                Ok(None) => {
+                    tracing::debug!("Another memcp process is running the decay scan — skipping this tick");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Decay archival lock failed — will retry next interval");
+                    continue;
+                }
+            };
+            let result = store.archive_stale_memories(threshold).await;
+            lock.release().await;
+            match result {
+                Ok(0) => {}
+                Ok(count) => {
+                    tracing::info!(count = count, threshold = threshold, "Decayed memories archived");
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Decay archival scan failed — will retry next interval");
+                }
+            }
+        }
+    })
+}
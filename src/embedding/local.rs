@@ -8,16 +8,24 @@ use async_trait::async_trait;
 use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
 use tokio::task;
 
 use super::{EmbeddingError, EmbeddingProvider};
 
+/// Name recorded in memory_embeddings.model_name for vectors from this provider.
+pub const MODEL_NAME: &str = "all-MiniLM-L6-v2";
+
+/// The loaded model, or why loading failed (loading is attempted once).
+type LoadedModel = Result<Arc<Mutex<TextEmbedding>>, String>;
+
 /// Local embedding provider backed by fastembed.
 ///
 /// Uses all-MiniLM-L6-v2 model (384 dimensions) as the default.
 /// fastembed is synchronous, so embed() uses spawn_blocking internally.
 pub struct LocalEmbeddingProvider {
-    model: Arc<Mutex<TextEmbedding>>,
+    model: Arc<OnceCell<LoadedModel>>,
+    cache_dir: PathBuf,
     name: String,
     dim: usize,
 }
@@ -28,31 +36,66 @@ impl LocalEmbeddingProvider {
     /// # Arguments
     /// * `cache_dir` - Directory to cache model weights (fastembed downloads on first use)
     pub async fn new(cache_dir: &str) -> Result<Self, EmbeddingError> {
-        let cache_path = PathBuf::from(cache_dir);
-
-        let te = task::spawn_blocking(move || {
-            TextEmbedding::try_new(
-                InitOptions::new(EmbeddingModel::AllMiniLML6V2)
-                    .with_cache_dir(cache_path)
-                    .with_show_download_progress(true),
-            )
-        })
-        .await
-        .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?
-        .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?;
+        let provider = Self::unloaded(cache_dir);
+        provider.model().await?;
+        Ok(provider)
+    }
+
+    /// Create a provider whose model downloads and loads in the background.
+    ///
+    /// is_ready() reports false until the model is loaded; embed() calls made meanwhile
+    /// wait for it. Must be called within a tokio runtime.
+    pub fn lazy(cache_dir: &str) -> Self {
+        let provider = Self::unloaded(cache_dir);
+        let model = Arc::clone(&provider.model);
+        let cache_path = provider.cache_dir.clone();
+        tokio::spawn(async move {
+            match model.get_or_init(|| load_model(cache_path)).await {
+                Ok(_) => tracing::info!(model = MODEL_NAME, "Local embedding model loaded"),
+                Err(e) => tracing::error!(error = %e, "Local embedding model failed to load"),
+            }
+        });
+        provider
+    }
 
-        Ok(LocalEmbeddingProvider {
-            model: Arc::new(Mutex::new(te)),
-            name: "all-MiniLM-L6-v2".to_string(),
+    fn unloaded(cache_dir: &str) -> Self {
+        LocalEmbeddingProvider {
+            model: Arc::new(OnceCell::new()),
+            cache_dir: PathBuf::from(cache_dir),
+            name: MODEL_NAME.to_string(),
             dim: 384,
-        })
+        }
+    }
+
+    /// The loaded model, loading it first if nothing has started to.
+    async fn model(&self) -> Result<Arc<Mutex<TextEmbedding>>, EmbeddingError> {
+        self.model
+            .get_or_init(|| load_model(self.cache_dir.clone()))
+            .await
+            .clone()
+            .map_err(EmbeddingError::ModelInit)
     }
 }
 
+/// Download the model weights into `cache_path` (unless already cached) and load them.
+async fn load_model(cache_path: PathBuf) -> LoadedModel {
+    task::spawn_blocking(move || {
+        TextEmbedding::try_new(
+            InitOptions::new(EmbeddingModel::AllMiniLML6V2)
+                .with_cache_dir(cache_path)
+                .with_show_download_progress(true),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map(|te| Arc::new(Mutex::new(te)))
+    .map_err(|e| e.to_string())
+}
+
 #[async_trait]
 impl EmbeddingProvider for LocalEmbeddingProvider {
     async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
-        let model = Arc::clone(&self.model);
+        let model = self.model().await?;
         let text = text.to_string();
 
         task::spawn_blocking(move || {
@@ -69,6 +112,21 @@ impl EmbeddingProvider for LocalEmbeddingProvider {
         .map_err(|e| EmbeddingError::Generation(format!("spawn_blocking panicked: {}", e)))?
     }
 
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let model = self.model().await?;
+        let texts: Vec<String> = texts.iter().map(|t| t.to_string()).collect();
+
+        // One inference call for the whole batch — fastembed batches internally
+        task::spawn_blocking(move || {
+            let mut model = model.lock().unwrap();
+            model
+                .embed(texts, None)
+                .map_err(|e| EmbeddingError::Generation(e.to_string()))
+        })
+        .await
+        .map_err(|e| EmbeddingError::Generation(format!("spawn_blocking panicked: {}", e)))?
+    }
+
     fn model_name(&self) -> &str {
         &self.name
     }
@@ -76,4 +134,8 @@ impl EmbeddingProvider for LocalEmbeddingProvider {
     fn dimension(&self) -> usize {
         self.dim
     }
+
+    fn is_ready(&self) -> bool {
+        matches!(self.model.get(), Some(Ok(_)))
+    }
 }
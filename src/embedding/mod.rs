@@ -6,6 +6,8 @@
 pub mod local;
 pub mod openai;
 pub mod pipeline;
+pub mod router;
+pub mod template;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -101,9 +103,27 @@ pub trait EmbeddingProvider: Send + Sync {
     /// Generate an embedding vector for the given text.
     async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError>;
 
+    /// Generate embeddings for several texts, returned in input order.
+    ///
+    /// Providers with a native batch API should override this. The default
+    /// implementation calls embed() sequentially and fails on the first error.
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for text in texts {
+            vectors.push(self.embed(text).await?);
+        }
+        Ok(vectors)
+    }
+
     /// Return the model name identifier (e.g., "all-MiniLM-L6-v2").
     fn model_name(&self) -> &str;
 
     /// Return the dimension of the embedding vectors produced by this model.
     fn dimension(&self) -> usize;
+
+    /// Whether embed() can answer without first waiting for the model to load.
+    /// Search skips the vector leg rather than wait while this is false.
+    fn is_ready(&self) -> bool {
+        true
+    }
 }
@@ -2,16 +2,32 @@
 ///
 /// Calls the OpenAI Embeddings API using reqwest.
 /// Supports text-embedding-3-small (1536 dimensions) by default.
+/// The base_url is configurable — supports Azure OpenAI, vLLM, LiteLLM, and any compatible endpoint.
 /// Requires MEMCP_EMBEDDING__OPENAI_API_KEY env var or openai_api_key in config.
 
 use async_trait::async_trait;
 
 use super::{EmbeddingError, EmbeddingProvider};
 
-/// Request body for OpenAI Embeddings API
+/// Model used when no other is configured.
+pub const DEFAULT_MODEL: &str = "text-embedding-3-small";
+
+/// Base URL used when no other is configured.
+pub const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Output dimension of OpenAI's own embedding models, or None for models we don't know.
+pub fn known_dimension(model: &str) -> Option<usize> {
+    match model {
+        "text-embedding-3-small" | "text-embedding-ada-002" => Some(1536),
+        "text-embedding-3-large" => Some(3072),
+        _ => None,
+    }
+}
+
+/// Request body for OpenAI Embeddings API (input accepts an array for batch requests)
 #[derive(serde::Serialize)]
 struct EmbedRequest {
-    input: String,
+    input: Vec<String>,
     model: String,
 }
 
@@ -24,6 +40,9 @@ struct EmbedResponse {
 /// Single embedding result from OpenAI
 #[derive(serde::Deserialize)]
 struct EmbedData {
+    /// Position of the corresponding input in the request array
+    #[serde(default)]
+    index: usize,
     embedding: Vec<f32>,
 }
 
@@ -34,6 +53,7 @@ struct EmbedData {
 pub struct OpenAIEmbeddingProvider {
     client: reqwest::Client,
     api_key: String,
+    base_url: String,
     model: String,
     dim: usize,
 }
@@ -43,10 +63,19 @@ impl OpenAIEmbeddingProvider {
     ///
     /// # Arguments
     /// * `api_key` - OpenAI API key (must be non-empty)
+    /// * `base_url` - API base URL (e.g., "https://api.openai.com/v1" or a proxy endpoint)
+    /// * `model` - Embedding model name sent with every request
+    /// * `dimension` - Output dimension; None uses the known dimension of OpenAI's models
     ///
     /// # Errors
-    /// Returns `EmbeddingError::NotConfigured` if api_key is empty.
-    pub fn new(api_key: String) -> Result<Self, EmbeddingError> {
+    /// Returns `EmbeddingError::NotConfigured` if api_key is empty, or if `dimension` is None
+    /// and the model's dimension is not known.
+    pub fn new(
+        api_key: String,
+        base_url: String,
+        model: String,
+        dimension: Option<usize>,
+    ) -> Result<Self, EmbeddingError> {
         if api_key.trim().is_empty() {
             return Err(EmbeddingError::NotConfigured(
                 "OpenAI API key is required when using the openai embedding provider. \
@@ -55,11 +84,20 @@ impl OpenAIEmbeddingProvider {
             ));
         }
 
+        let dim = dimension.or_else(|| known_dimension(&model)).ok_or_else(|| {
+            EmbeddingError::NotConfigured(format!(
+                "Unknown dimension for embedding model '{}'. \
+                 Set MEMCP_EMBEDDING__OPENAI_DIMENSION or openai_dimension in memcp.toml",
+                model
+            ))
+        })?;
+
         Ok(OpenAIEmbeddingProvider {
             client: reqwest::Client::new(),
             api_key,
-            model: "text-embedding-3-small".to_string(),
-            dim: 1536,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+            dim,
         })
     }
 }
@@ -67,14 +105,23 @@ impl OpenAIEmbeddingProvider {
 #[async_trait]
 impl EmbeddingProvider for OpenAIEmbeddingProvider {
     async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        self.embed_batch(&[text])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| EmbeddingError::Generation("API returned empty embedding list".to_string()))
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
         let request = EmbedRequest {
-            input: text.to_string(),
+            input: texts.iter().map(|t| t.to_string()).collect(),
             model: self.model.clone(),
         };
 
+        let url = format!("{}/embeddings", self.base_url);
         let response = self
             .client
-            .post("https://api.openai.com/v1/embeddings")
+            .post(&url)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .json(&request)
             .send()
@@ -98,12 +145,18 @@ impl EmbeddingProvider for OpenAIEmbeddingProvider {
             .await
             .map_err(|e| EmbeddingError::Generation(format!("Failed to parse API response: {}", e)))?;
 
-        embed_response
-            .data
-            .into_iter()
-            .next()
-            .map(|d| d.embedding)
-            .ok_or_else(|| EmbeddingError::Generation("API returned empty embedding list".to_string()))
+        if embed_response.data.len() != texts.len() {
+            return Err(EmbeddingError::Generation(format!(
+                "API returned {} embeddings for {} inputs",
+                embed_response.data.len(),
+                texts.len()
+            )));
+        }
+
+        // The API documents results in input order, but sort by index to be safe
+        let mut data = embed_response.data;
+        data.sort_by_key(|d| d.index);
+        Ok(data.into_iter().map(|d| d.embedding).collect())
     }
 
     fn model_name(&self) -> &str {
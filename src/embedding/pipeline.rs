@@ -1,148 +1,227 @@
 /// Async embedding pipeline with bounded mpsc channel and background worker.
 ///
 /// Non-blocking design: store_memory never waits for embedding completion.
-/// Failed embeddings are retried up to 3 times with exponential backoff (1s, 2s, 4s),
-/// then marked as failed for backfill on next startup.
+/// The worker drains up to `batch_size` queued jobs at a time and embeds them with a
+/// single provider call; if the batch call fails, items are retried individually.
+/// Failed embeddings are retried with exponential backoff (RetryPolicy — by default 3 retries
+/// at 1s, 2s, 4s), then marked as failed for backfill on next startup. Every failed attempt
+/// is recorded with its error in the memory_embedding_failures dead-letter table, which is
+/// cleared once the memory embeds successfully.
+/// On shutdown the worker finishes its current batch and stops; queued jobs stay 'pending'.
+/// Jobs that came from the outbox (see `crate::outbox`) are removed from it once they reach
+/// a final status.
+/// With `[[embedding.routes]]` configured, each batch is split by the provider its memories
+/// route to (see `super::router`).
 
+use std::fmt;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
+use chrono::{DateTime, Utc};
 use tokio::sync::mpsc;
+use tokio::time::Instant;
 use uuid::Uuid;
 
-use super::{EmbeddingJob, EmbeddingProvider, build_embedding_text};
+use super::router::EmbeddingRouter;
+use super::template::EmbeddingTemplate;
+use super::{EmbeddingError, EmbeddingJob, EmbeddingProvider};
 use crate::consolidation::ConsolidationJob;
+use crate::errors::MemcpError;
+use crate::metrics;
+use crate::outbox::JobKind;
+use crate::shutdown::Shutdown;
 use crate::store::MemoryStore;
 use crate::store::postgres::PostgresMemoryStore;
 
+/// Advisory lock held by the one instance running the startup backfill until its queue drains.
+pub const EMBEDDING_BACKFILL_LOCK: &str = "memcp:backfill:embedding";
+
+/// Exponential backoff policy for failed embedding jobs.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first failure before giving up
+    pub max_retries: u8,
+    /// Delay before the first retry; doubles on each subsequent attempt
+    pub base_delay: Duration,
+    /// Upper bound on any single delay
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Build the policy from the [embedding] config section.
+    pub fn from_config(config: &crate::config::EmbeddingConfig) -> Self {
+        RetryPolicy {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_millis(config.retry_base_delay_ms),
+            max_delay: Duration::from_millis(config.retry_max_delay_ms),
+        }
+    }
+
+    /// Delay before retrying a job whose `attempt` (0-based) just failed.
+    pub fn delay_for(&self, attempt: u8) -> Duration {
+        let factor = 2u32.saturating_pow(attempt as u32);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::from_config(&crate::config::EmbeddingConfig::default())
+    }
+}
+
 /// Async embedding pipeline: enqueues jobs onto a bounded mpsc channel and
-/// processes them in a background tokio task.
+/// processes them in a background tokio task. Clones share the same queue and worker.
+#[derive(Clone)]
 pub struct EmbeddingPipeline {
     sender: mpsc::Sender<EmbeddingJob>,
     /// Count of jobs currently in-flight (enqueued but not yet completed).
     /// Used by flush() to block until the pipeline drains.
     pending_count: Arc<AtomicUsize>,
+    shutdown: Shutdown,
 }
 
 impl EmbeddingPipeline {
     /// Create a new EmbeddingPipeline and spawn the background worker.
     ///
-    /// - `provider`: The embedding provider to call for each job.
+    /// - `router`: The embedding provider(s) to call for each batch.
     /// - `store`: The PostgresMemoryStore for storing embeddings and updating status.
     /// - `capacity`: Bounded channel capacity (recommended: 1000).
+    /// - `batch_size`: Maximum jobs drained from the channel and embedded per provider call.
     /// - `consolidation_sender`: Optional channel to the consolidation worker. When provided,
     ///   each successfully embedded memory triggers a consolidation check via this channel.
+    /// - `retry`: Backoff policy for failed jobs.
+    /// - `shutdown`: Stops the worker after its current batch; `Shutdown::drain` waits for it.
     pub fn new(
-        provider: Arc<dyn EmbeddingProvider>,
+        router: EmbeddingRouter,
         store: Arc<PostgresMemoryStore>,
         capacity: usize,
+        batch_size: usize,
         consolidation_sender: Option<mpsc::Sender<ConsolidationJob>>,
+        retry: RetryPolicy,
+        shutdown: Shutdown,
     ) -> Self {
         let (tx, mut rx) = mpsc::channel::<EmbeddingJob>(capacity);
         // Clone tx for retry re-sends inside the worker
         let retry_tx = tx.clone();
+        let batch_size = batch_size.max(1);
 
         // Shared counter tracking jobs currently in-flight (enqueued but not completed).
         let pending_count = Arc::new(AtomicUsize::new(0));
         let worker_pending = Arc::clone(&pending_count);
 
-        tokio::spawn(async move {
-            while let Some(job) = rx.recv().await {
-                let text = job.text.clone();
-                match provider.embed(&text).await {
-                    Ok(vector) => {
-                        let embedding = pgvector::Vector::from(vector);
-                        let emb_id = Uuid::new_v4().to_string();
-                        let model = provider.model_name().to_string();
-                        let dim = provider.dimension() as i32;
-                        if let Err(e) = store
-                            .insert_embedding(&emb_id, &job.memory_id, &model, "v1", dim, &embedding, true)
-                            .await
-                        {
-                            tracing::error!(
-                                memory_id = %job.memory_id,
-                                error = %e,
-                                "Failed to store embedding"
-                            );
-                            // Storage error is not retryable — mark as failed
-                            let _ = store.update_embedding_status(&job.memory_id, "failed").await;
-                            worker_pending.fetch_sub(1, Ordering::Relaxed);
-                        } else {
-                            let _ = store.update_embedding_status(&job.memory_id, "complete").await;
-                            tracing::debug!(memory_id = %job.memory_id, "Embedding complete");
-
-                            // Trigger consolidation check after successful embedding.
-                            // Consolidation requires the embedding to exist first (for cosine similarity).
-                            // try_send is non-blocking — if the channel is full, skip consolidation for
-                            // this memory (not critical, backfill does not apply here).
-                            if let Some(ref consolidation_tx) = consolidation_sender {
-                                // Fetch the memory content for synthesis if consolidation triggers
-                                match store.get(&job.memory_id).await {
-                                    Ok(memory) => {
-                                        let _ = consolidation_tx.try_send(ConsolidationJob {
-                                            memory_id: job.memory_id.clone(),
-                                            embedding: embedding.clone(),
-                                            content: memory.content,
-                                        });
+        let worker_shutdown = shutdown.clone();
+        shutdown.spawn(async move {
+            loop {
+                let first = tokio::select! {
+                    biased;
+                    _ = worker_shutdown.cancelled() => break,
+                    job = rx.recv() => match job {
+                        Some(job) => job,
+                        None => break,
+                    },
+                };
+                // Drain whatever else is already queued, up to batch_size, without waiting
+                let mut batch = vec![first];
+                while batch.len() < batch_size {
+                    match rx.try_recv() {
+                        Ok(job) => batch.push(job),
+                        Err(_) => break,
+                    }
+                }
+
+                for (provider, batch) in route_batch(&router, &store, batch).await {
+                    let results = embed_jobs(provider.as_ref(), &batch).await;
+                    for (job, result) in batch.into_iter().zip(results) {
+                        match result {
+                            Ok(vector) => {
+                                store_embedding(&store, provider.as_ref(), &job, vector, consolidation_sender.as_ref()).await;
+                                worker_pending.fetch_sub(1, Ordering::Relaxed);
+                                metrics::global().embedding_queue_depth.dec();
+                            }
+                            Err(e) if job.attempt < retry.max_retries => {
+                                tracing::warn!(
+                                    memory_id = %job.memory_id,
+                                    attempt = job.attempt + 1,
+                                    error = %e,
+                                    "Embedding failed, retrying"
+                                );
+                                let _ = store.record_embedding_failure(&job.memory_id, &e.to_string()).await;
+                                // Exponential backoff — delayed off the worker so the rest of the
+                                // queue keeps flowing.
+                                let delay = retry.delay_for(job.attempt);
+                                let retry_tx = retry_tx.clone();
+                                let retry_store = Arc::clone(&store);
+                                let retry_pending = Arc::clone(&worker_pending);
+                                let retry_shutdown = worker_shutdown.clone();
+                                tokio::spawn(async move {
+                                    // A retry cut short by shutdown leaves the memory pending for backfill
+                                    tokio::select! {
+                                        _ = retry_shutdown.cancelled() => return,
+                                        _ = tokio::time::sleep(delay) => {}
                                     }
-                                    Err(e) => {
-                                        tracing::warn!(
-                                            memory_id = %job.memory_id,
-                                            error = %e,
-                                            "Failed to fetch memory for consolidation job — skipping"
-                                        );
+                                    let memory_id = job.memory_id.clone();
+                                    // Re-enqueue with incremented attempt (pending_count stays the same — job continues)
+                                    let sent = retry_tx.try_send(EmbeddingJob { attempt: job.attempt + 1, ..job });
+                                    if sent.is_err() && !retry_shutdown.is_shutting_down() {
+                                        let _ = retry_store.record_embedding_failure(&memory_id, "Embedding queue full, retry dropped").await;
+                                        let _ = retry_store.update_embedding_status(&memory_id, "failed").await;
+                                        let _ = retry_store.complete_pipeline_job(&memory_id, JobKind::Embedding).await;
+                                        retry_pending.fetch_sub(1, Ordering::Relaxed);
+                                        metrics::global().embedding_queue_depth.dec();
+                                        metrics::global().embeddings_failed.inc();
                                     }
-                                }
+                                });
+                            }
+                            Err(e) => {
+                                tracing::error!(
+                                    memory_id = %job.memory_id,
+                                    attempts = job.attempt + 1,
+                                    error = %e,
+                                    "Embedding retries exhausted, marking as failed"
+                                );
+                                let _ = store.record_embedding_failure(&job.memory_id, &e.to_string()).await;
+                                let _ = store.update_embedding_status(&job.memory_id, "failed").await;
+                                let _ = store.complete_pipeline_job(&job.memory_id, JobKind::Embedding).await;
+                                worker_pending.fetch_sub(1, Ordering::Relaxed);
+                                metrics::global().embedding_queue_depth.dec();
+                                metrics::global().embeddings_failed.inc();
                             }
-                            worker_pending.fetch_sub(1, Ordering::Relaxed);
                         }
                     }
-                    Err(e) if job.attempt < 3 => {
-                        tracing::warn!(
-                            memory_id = %job.memory_id,
-                            attempt = job.attempt + 1,
-                            error = %e,
-                            "Embedding failed, retrying"
-                        );
-                        // Exponential backoff: 1s, 2s, 4s
-                        let delay = Duration::from_secs(2u64.pow(job.attempt as u32));
-                        tokio::time::sleep(delay).await;
-                        // Re-enqueue with incremented attempt (pending_count stays the same — job continues)
-                        let _ = retry_tx.try_send(EmbeddingJob {
-                            attempt: job.attempt + 1,
-                            ..job
-                        });
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            memory_id = %job.memory_id,
-                            attempts = 3,
-                            error = %e,
-                            "Embedding failed after 3 retries, marking as failed"
-                        );
-                        let _ = store.update_embedding_status(&job.memory_id, "failed").await;
-                        worker_pending.fetch_sub(1, Ordering::Relaxed);
-                    }
                 }
             }
+            if !rx.is_empty() {
+                tracing::info!(queued = rx.len(), "Embedding worker stopped — queued memories stay pending for backfill");
+            }
         });
 
-        EmbeddingPipeline { sender: tx, pending_count }
+        EmbeddingPipeline { sender: tx, pending_count, shutdown }
     }
 
     /// Enqueue an embedding job (non-blocking).
     ///
-    /// Uses try_send — if the channel is full, the job is dropped and a warning is logged.
-    /// The backfill process will pick up missed memories on next startup.
-    pub fn enqueue(&self, job: EmbeddingJob) {
+    /// Uses try_send — if the channel is full, the job is dropped, a warning is logged, and
+    /// false is returned so callers can signal backpressure. The memory stays 'pending' and
+    /// the backfill process picks it up on next startup. Jobs are refused the same way once
+    /// shutdown has begun.
+    pub fn enqueue(&self, job: EmbeddingJob) -> bool {
+        if self.shutdown.is_shutting_down() {
+            return false;
+        }
         self.pending_count.fetch_add(1, Ordering::Relaxed);
+        metrics::global().embedding_queue_depth.inc();
         if let Err(_) = self.sender.try_send(job) {
             // Job dropped — decrement since no worker will process it
             self.pending_count.fetch_sub(1, Ordering::Relaxed);
+            metrics::global().embedding_queue_depth.dec();
+            metrics::global().embedding_jobs_dropped.inc();
             tracing::warn!(
                 "Embedding queue full — memory stored, embedding deferred to backfill"
             );
+            return false;
         }
+        true
     }
 
     /// Return a clone of the underlying mpsc sender (for use with the backfill function).
@@ -150,6 +229,11 @@ impl EmbeddingPipeline {
         self.sender.clone()
     }
 
+    /// Number of jobs enqueued but not yet completed.
+    pub fn queue_depth(&self) -> usize {
+        self.pending_count.load(Ordering::Relaxed)
+    }
+
     /// Wait until all enqueued embedding jobs have completed (success or failure).
     /// Polls pending count every 100ms. Used by benchmark to ensure all embeddings
     /// are complete before running search.
@@ -165,13 +249,142 @@ impl EmbeddingPipeline {
     }
 }
 
+/// Split a batch by the provider each job's memory routes to, keeping queue order within
+/// each group. Without routes the whole batch goes to the default provider.
+async fn route_batch(
+    router: &EmbeddingRouter,
+    store: &PostgresMemoryStore,
+    batch: Vec<EmbeddingJob>,
+) -> Vec<(Arc<dyn EmbeddingProvider>, Vec<EmbeddingJob>)> {
+    if !router.is_routed() {
+        return vec![(router.default_provider().clone(), batch)];
+    }
+    let ids: Vec<String> = batch.iter().map(|j| j.memory_id.clone()).collect();
+    let memories = match store.get_memories_by_ids(&ids).await {
+        Ok(memories) => memories,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load memories for embedding routing — using the default provider");
+            Default::default()
+        }
+    };
+
+    let mut groups: Vec<(Arc<dyn EmbeddingProvider>, Vec<EmbeddingJob>)> = Vec::new();
+    for job in batch {
+        let provider = match memories.get(&job.memory_id) {
+            Some(m) => router.for_memory(&m.namespace, &m.type_hint, &m.source),
+            None => router.default_provider(),
+        };
+        match groups.iter_mut().find(|(p, _)| p.model_name() == provider.model_name()) {
+            Some((_, jobs)) => jobs.push(job),
+            None => groups.push((provider.clone(), vec![job])),
+        }
+    }
+    groups
+}
+
+/// Embed a batch of jobs with one provider call, returning one result per job in order.
+async fn embed_jobs(provider: &dyn EmbeddingProvider, batch: &[EmbeddingJob]) -> Vec<Result<Vec<f32>, EmbeddingError>> {
+    let texts: Vec<&str> = batch.iter().map(|j| j.text.as_str()).collect();
+    match provider.embed_batch(&texts).await {
+        Ok(vectors) if vectors.len() == batch.len() => vectors.into_iter().map(Ok).collect(),
+        // Single job: nothing to isolate, the error goes straight to retry handling
+        Err(e) if batch.len() == 1 => vec![Err(e)],
+        outcome => {
+            // Whole-batch failure (or a malformed response): embed items one by one
+            // so a single bad input doesn't fail its neighbours.
+            match outcome {
+                Ok(vectors) => tracing::warn!(
+                    expected = batch.len(),
+                    got = vectors.len(),
+                    "Batch embedding returned wrong count, retrying items individually"
+                ),
+                Err(e) => tracing::warn!(
+                    batch = batch.len(),
+                    error = %e,
+                    "Batch embedding failed, retrying items individually"
+                ),
+            }
+            let mut per_item = Vec::with_capacity(batch.len());
+            for job in batch {
+                per_item.push(provider.embed(&job.text).await);
+            }
+            per_item
+        }
+    }
+}
+
+/// Persist a successfully generated embedding and trigger a consolidation check.
+///
+/// Storage errors are not retryable — the memory is marked as failed for backfill and
+/// false is returned.
+async fn store_embedding(
+    store: &PostgresMemoryStore,
+    provider: &dyn EmbeddingProvider,
+    job: &EmbeddingJob,
+    vector: Vec<f32>,
+    consolidation_sender: Option<&mpsc::Sender<ConsolidationJob>>,
+) -> bool {
+    let embedding = pgvector::Vector::from(vector);
+    let emb_id = Uuid::new_v4().to_string();
+    let model = provider.model_name().to_string();
+    let dim = provider.dimension() as i32;
+    if let Err(e) = store
+        .insert_embedding(&emb_id, &job.memory_id, &model, "v1", dim, &embedding, true)
+        .await
+    {
+        tracing::error!(
+            memory_id = %job.memory_id,
+            error = %e,
+            "Failed to store embedding"
+        );
+        let _ = store.record_embedding_failure(&job.memory_id, &e.to_string()).await;
+        let _ = store.update_embedding_status(&job.memory_id, "failed").await;
+        let _ = store.complete_pipeline_job(&job.memory_id, JobKind::Embedding).await;
+        metrics::global().embeddings_failed.inc();
+        return false;
+    }
+
+    let _ = store.update_embedding_status(&job.memory_id, "complete").await;
+    let _ = store.complete_pipeline_job(&job.memory_id, JobKind::Embedding).await;
+    // Memories retried by backfill start again at attempt 0, so always clear the dead letter
+    let _ = store.clear_embedding_failure(&job.memory_id).await;
+    metrics::global().embeddings_completed.inc();
+    tracing::debug!(memory_id = %job.memory_id, "Embedding complete");
+
+    // Trigger consolidation check after successful embedding.
+    // Consolidation requires the embedding to exist first (for cosine similarity).
+    // try_send is non-blocking — if the channel is full, skip consolidation for
+    // this memory (not critical, backfill does not apply here).
+    if let Some(consolidation_tx) = consolidation_sender {
+        // Fetch the memory content for synthesis if consolidation triggers
+        match store.get(&job.memory_id).await {
+            Ok(memory) => {
+                let _ = consolidation_tx.try_send(ConsolidationJob {
+                    memory_id: job.memory_id.clone(),
+                    embedding,
+                    content: memory.content,
+                });
+            }
+            Err(e) => {
+                tracing::warn!(
+                    memory_id = %job.memory_id,
+                    error = %e,
+                    "Failed to fetch memory for consolidation job — skipping"
+                );
+            }
+        }
+    }
+    true
+}
+
 /// Queue all pending/failed memories for re-embedding.
 ///
-/// Queries the store in batches of 100 and enqueues each memory on the pipeline channel.
-/// Returns the total count of memories queued.
+/// Queries the store in batches of 100 and enqueues each memory, rendered with `template`,
+/// on the pipeline. Returns the total count of memories queued.
 pub async fn backfill(
     store: &PostgresMemoryStore,
-    sender: &mpsc::Sender<EmbeddingJob>,
+    pipeline: &EmbeddingPipeline,
+    template: &EmbeddingTemplate,
 ) -> u64 {
     let mut total_queued: u64 = 0;
 
@@ -190,15 +403,14 @@ pub async fn backfill(
 
         let batch_size = pending.len() as u64;
         for memory in pending {
-            let text = build_embedding_text(&memory.content, &memory.tags);
+            let text = template.render_memory(&memory);
             let job = EmbeddingJob {
                 memory_id: memory.id,
                 text,
                 attempt: 0,
             };
-            if let Err(_) = sender.try_send(job) {
-                tracing::warn!("Embedding queue full during backfill — some memories deferred");
-                // Channel is full — stop trying this batch; next startup will continue
+            if !pipeline.enqueue(job) {
+                // Queue full or shutting down — stop trying this batch; next startup will continue
                 return total_queued;
             }
         }
@@ -217,3 +429,365 @@ pub async fn backfill(
 
     total_queued
 }
+
+/// Settings for `memcp embed backfill`.
+#[derive(Debug, Clone, Copy)]
+pub struct BackfillOptions {
+    /// Memories fetched and embedded per provider call
+    pub batch_size: usize,
+    /// Maximum provider requests per second (None = unlimited)
+    pub rate_limit: Option<f64>,
+    /// Backoff for failed batch calls before falling back to embedding items one by one
+    pub retry: RetryPolicy,
+}
+
+/// Spaces provider requests evenly so no more than `per_second` start in any second.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Instant,
+}
+
+impl RateLimiter {
+    /// `per_second` must be positive.
+    pub fn new(per_second: f64) -> Self {
+        RateLimiter { interval: Duration::from_secs_f64(1.0 / per_second), next: Instant::now() }
+    }
+
+    /// Wait until the next request may start.
+    pub async fn acquire(&mut self) {
+        let now = Instant::now();
+        if self.next > now {
+            tokio::time::sleep_until(self.next).await;
+        }
+        self.next = self.next.max(now) + self.interval;
+    }
+}
+
+/// How far a backfill run has got, reported after every batch.
+#[derive(Debug, Clone, Default)]
+pub struct BackfillProgress {
+    /// Memories embedded and stored
+    pub embedded: u64,
+    /// Memories that failed (marked failed and recorded in the dead-letter table)
+    pub failed: u64,
+    /// Memories to process: the pending count at the start, raised if more arrive
+    pub total: u64,
+    pub elapsed: Duration,
+}
+
+impl BackfillProgress {
+    pub fn processed(&self) -> u64 {
+        self.embedded + self.failed
+    }
+
+    /// Estimated time to finish at the average rate so far (None before the first batch).
+    pub fn eta(&self) -> Option<Duration> {
+        let processed = self.processed();
+        if processed == 0 {
+            return None;
+        }
+        let remaining = self.total.saturating_sub(processed);
+        Some(self.elapsed.mul_f64(remaining as f64 / processed as f64))
+    }
+}
+
+impl fmt::Display for BackfillProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let processed = self.processed();
+        let percent = if self.total == 0 { 100.0 } else { processed as f64 * 100.0 / self.total as f64 };
+        write!(f, "{}/{} ({:.1}%), {} failed", processed, self.total, percent, self.failed)?;
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            write!(f, ", {:.1}/s", processed as f64 / secs)?;
+        }
+        match self.eta() {
+            Some(eta) if processed < self.total => write!(f, ", ETA {}", format_duration(eta)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// "1h 05m", "3m 07s" or "42s".
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, _) => format!("{}h {:02}m", h, m),
+    }
+}
+
+/// Embed every pending or failed memory inline, batch by batch, calling `on_progress` after
+/// each batch.
+///
+/// Unlike the startup `backfill`, this waits for the results: each batch is embedded (per
+/// `[[embedding.routes]]`) and stored before the next is fetched. Memories still failing
+/// after retries are marked failed and skipped for the rest of the run. Every stored
+/// embedding is final, so an interrupted run can simply be started again to pick up the rest.
+pub async fn run_backfill(
+    store: &PostgresMemoryStore,
+    router: &EmbeddingRouter,
+    template: &EmbeddingTemplate,
+    options: BackfillOptions,
+    mut on_progress: impl FnMut(&BackfillProgress),
+) -> Result<BackfillProgress, MemcpError> {
+    let started = Instant::now();
+    let mut limiter = options.rate_limit.map(RateLimiter::new);
+    let mut progress = BackfillProgress {
+        total: store.count_pending_memories().await?.max(0) as u64,
+        ..BackfillProgress::default()
+    };
+    let mut cursor: Option<(DateTime<Utc>, String)> = None;
+
+    loop {
+        let page = store
+            .get_pending_memories_after(cursor.as_ref(), options.batch_size.max(1) as i64)
+            .await?;
+        let Some(last) = page.last() else { break };
+        cursor = Some((last.created_at, last.id.clone()));
+
+        let jobs: Vec<EmbeddingJob> = page
+            .iter()
+            .map(|memory| EmbeddingJob { memory_id: memory.id.clone(), text: template.render_memory(memory), attempt: 0 })
+            .collect();
+        for (provider, batch) in route_batch(router, store, jobs).await {
+            let results = embed_jobs_limited(provider.as_ref(), &batch, &mut limiter, &options.retry).await;
+            for (job, result) in batch.iter().zip(results) {
+                let stored = match result {
+                    Ok(vector) => store_embedding(store, provider.as_ref(), job, vector, None).await,
+                    Err(e) => {
+                        tracing::warn!(memory_id = %job.memory_id, error = %e, "Backfill embedding failed");
+                        let _ = store.record_embedding_failure(&job.memory_id, &e.to_string()).await;
+                        let _ = store.update_embedding_status(&job.memory_id, "failed").await;
+                        metrics::global().embeddings_failed.inc();
+                        false
+                    }
+                };
+                if stored {
+                    progress.embedded += 1;
+                } else {
+                    progress.failed += 1;
+                }
+            }
+        }
+
+        progress.total = progress.total.max(progress.processed());
+        progress.elapsed = started.elapsed();
+        on_progress(&progress);
+    }
+
+    tracing::info!(embedded = progress.embedded, failed = progress.failed, "Embedding backfill finished");
+    Ok(progress)
+}
+
+/// `embed_jobs` for backfill: waits on the rate limiter before every provider call and
+/// retries a failed batch call with backoff (rate-limit errors usually hit the whole batch)
+/// before isolating items one by one.
+async fn embed_jobs_limited(
+    provider: &dyn EmbeddingProvider,
+    batch: &[EmbeddingJob],
+    limiter: &mut Option<RateLimiter>,
+    retry: &RetryPolicy,
+) -> Vec<Result<Vec<f32>, EmbeddingError>> {
+    let texts: Vec<&str> = batch.iter().map(|j| j.text.as_str()).collect();
+    let mut attempt: u8 = 0;
+    loop {
+        if let Some(limiter) = limiter.as_mut() {
+            limiter.acquire().await;
+        }
+        match provider.embed_batch(&texts).await {
+            Ok(vectors) if vectors.len() == batch.len() => return vectors.into_iter().map(Ok).collect(),
+            Ok(vectors) => {
+                tracing::warn!(expected = batch.len(), got = vectors.len(), "Batch embedding returned wrong count");
+                break;
+            }
+            Err(e) if attempt < retry.max_retries => {
+                tracing::warn!(batch = batch.len(), attempt = attempt + 1, error = %e, "Batch embedding failed, retrying");
+                tokio::time::sleep(retry.delay_for(attempt)).await;
+                attempt += 1;
+            }
+            Err(e) if batch.len() == 1 => return vec![Err(e)],
+            Err(e) => {
+                tracing::warn!(batch = batch.len(), error = %e, "Batch embedding failed, embedding items individually");
+                break;
+            }
+        }
+    }
+
+    let mut per_item = Vec::with_capacity(batch.len());
+    for job in batch {
+        if let Some(limiter) = limiter.as_mut() {
+            limiter.acquire().await;
+        }
+        per_item.push(provider.embed(&job.text).await);
+    }
+    per_item
+}
+
+/// Embed every live memory that has no embedding under `provider`'s model.
+///
+/// Runs inline rather than through the pipeline, `options.batch_size` memories per provider
+/// call (at most `options.rate_limit` calls per second), and never touches embeddings
+/// stored under other models. When `is_current` is true the new vectors become the ones
+/// default search uses and embedding_status is marked complete; otherwise they are stored
+/// alongside the current model for model-filtered search.
+/// Returns the count of memories embedded.
+pub async fn backfill_model(
+    store: &PostgresMemoryStore,
+    provider: &dyn EmbeddingProvider,
+    options: BackfillOptions,
+    is_current: bool,
+    template: &EmbeddingTemplate,
+) -> Result<u64, MemcpError> {
+    let model = provider.model_name().to_string();
+    let dim = provider.dimension() as i32;
+    let mut limiter = options.rate_limit.map(RateLimiter::new);
+    let mut total: u64 = 0;
+
+    loop {
+        let missing = store
+            .get_memories_missing_embedding(&model, options.batch_size.max(1) as i64)
+            .await?;
+        if missing.is_empty() {
+            break;
+        }
+
+        let texts: Vec<String> = missing
+            .iter()
+            .map(|m| template.render_memory(m))
+            .collect();
+        let text_refs: Vec<&str> = texts.iter().map(String::as_str).collect();
+        if let Some(limiter) = limiter.as_mut() {
+            limiter.acquire().await;
+        }
+        // A failed batch aborts the run — retrying here would loop on the same memories
+        let vectors = provider.embed_batch(&text_refs).await?;
+        if vectors.len() != missing.len() {
+            return Err(MemcpError::Internal(format!(
+                "Embedding provider returned {} vectors for {} texts",
+                vectors.len(),
+                missing.len()
+            )));
+        }
+
+        for (memory, vector) in missing.iter().zip(vectors) {
+            let embedding = pgvector::Vector::from(vector);
+            let emb_id = Uuid::new_v4().to_string();
+            store
+                .insert_embedding(&emb_id, &memory.id, &model, "v1", dim, &embedding, is_current)
+                .await?;
+            if is_current {
+                store.update_embedding_status(&memory.id, "complete").await?;
+                let _ = store.clear_embedding_failure(&memory.id).await;
+            }
+        }
+
+        total += missing.len() as u64;
+        tracing::info!(model = %model, embedded = total, "Model backfill progress");
+    }
+
+    Ok(total)
+}
+
+/// Embed each extracted fact of a memory on its own and replace its fact embeddings.
+///
+/// Called after extraction when `search.fact_embeddings` is enabled. An empty fact list
+/// clears the memory's fact rows. Returns the number of facts embedded.
+pub async fn embed_facts(
+    store: &PostgresMemoryStore,
+    provider: &dyn EmbeddingProvider,
+    memory_id: &str,
+    facts: &[String],
+) -> Result<usize, MemcpError> {
+    let vectors = if facts.is_empty() {
+        Vec::new()
+    } else {
+        let fact_refs: Vec<&str> = facts.iter().map(String::as_str).collect();
+        provider.embed_batch(&fact_refs).await?
+    };
+    if vectors.len() != facts.len() {
+        return Err(MemcpError::Internal(format!(
+            "Embedding provider returned {} vectors for {} facts",
+            vectors.len(),
+            facts.len()
+        )));
+    }
+    store
+        .replace_fact_embeddings(memory_id, provider.model_name(), facts, vectors)
+        .await?;
+    Ok(facts.len())
+}
+
+/// Embed the facts of every extracted memory that has no fact embeddings under `provider`'s model.
+///
+/// Used by `memcp embed facts` after turning on `search.fact_embeddings` for an existing corpus.
+/// Returns the number of memories processed.
+pub async fn backfill_facts(
+    store: &PostgresMemoryStore,
+    provider: &dyn EmbeddingProvider,
+    batch_size: usize,
+) -> Result<u64, MemcpError> {
+    let model = provider.model_name().to_string();
+    let mut total: u64 = 0;
+
+    loop {
+        let missing = store
+            .get_memories_missing_fact_embeddings(&model, batch_size.max(1) as i64)
+            .await?;
+        if missing.is_empty() {
+            break;
+        }
+
+        for (memory_id, facts) in &missing {
+            // A failed batch aborts the run — retrying here would loop on the same memories
+            embed_facts(store, provider, memory_id, facts).await?;
+        }
+
+        total += missing.len() as u64;
+        tracing::info!(model = %model, memories = total, "Fact embedding backfill progress");
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+        };
+        assert_eq!(policy.delay_for(0), Duration::from_secs(1));
+        assert_eq!(policy.delay_for(1), Duration::from_secs(2));
+        assert_eq!(policy.delay_for(2), Duration::from_secs(4));
+        assert_eq!(policy.delay_for(3), Duration::from_secs(5));
+        assert_eq!(policy.delay_for(40), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_spaces_requests() {
+        let mut limiter = RateLimiter::new(50.0);
+        let started = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+        // The first request goes immediately, the next three wait 20ms each
+        assert!(started.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn backfill_progress_reports_rate_and_eta() {
+        let progress = BackfillProgress { embedded: 240, failed: 10, total: 1000, elapsed: Duration::from_secs(50) };
+        assert_eq!(progress.eta(), Some(Duration::from_secs(150)));
+        assert_eq!(progress.to_string(), "250/1000 (25.0%), 10 failed, 5.0/s, ETA 2m 30s");
+
+        let done = BackfillProgress { embedded: 1000, total: 1000, elapsed: Duration::from_secs(5000), ..Default::default() };
+        assert_eq!(done.to_string(), "1000/1000 (100.0%), 0 failed, 0.2/s");
+        assert_eq!(format_duration(Duration::from_secs(3900)), "1h 05m");
+        assert!(BackfillProgress::default().eta().is_none());
+    }
+}
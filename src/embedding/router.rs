@@ -0,0 +1,174 @@
+//! Embedding provider routing.
+//!
+//! `[[embedding.routes]]` send memories matching a namespace, type_hint, and/or source to a
+//! named provider from `[embedding.providers]` — e.g. a code embedding model for code memories
+//! and the default sentence model for everything else. The pipeline picks the provider per
+//! memory; search_memory embeds the query with the routed model when its filters pin a route,
+//! and vector-matches only embeddings of that model.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::EmbeddingProvider;
+use crate::config::{EmbeddingConfig, EmbeddingRoute};
+use crate::errors::MemcpError;
+
+/// Check that every route names a configured provider and matches on something.
+pub fn validate_routes(config: &EmbeddingConfig) -> Result<(), MemcpError> {
+    for (i, route) in config.routes.iter().enumerate() {
+        if !config.providers.contains_key(&route.provider) {
+            return Err(MemcpError::Config(format!(
+                "embedding.routes[{}]: unknown provider {:?} (define it under [embedding.providers.{}])",
+                i, route.provider, route.provider
+            )));
+        }
+        if route.namespace.is_none() && route.type_hint.is_none() && route.source.is_none() {
+            return Err(MemcpError::Config(format!(
+                "embedding.routes[{}]: set at least one of namespace, type_hint, source",
+                i
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Provider name of the first route matching a memory.
+pub fn route_for_memory<'a>(
+    routes: &'a [EmbeddingRoute],
+    namespace: &str,
+    type_hint: &str,
+    source: &str,
+) -> Option<&'a str> {
+    let matches = |want: &Option<String>, have: &str| want.as_deref().is_none_or(|w| w == have);
+    routes
+        .iter()
+        .find(|r| matches(&r.namespace, namespace) && matches(&r.type_hint, type_hint) && matches(&r.source, source))
+        .map(|r| r.provider.as_str())
+}
+
+/// Provider name of the first route whose every condition is pinned to the same value by a
+/// search's filters — only then are all results guaranteed to use that route's model.
+pub fn route_for_search<'a>(
+    routes: &'a [EmbeddingRoute],
+    namespace: &str,
+    type_hint: Option<&str>,
+    source: Option<&str>,
+) -> Option<&'a str> {
+    let pinned = |want: &Option<String>, have: Option<&str>| want.as_deref().is_none_or(|w| have == Some(w));
+    routes
+        .iter()
+        .find(|r| pinned(&r.namespace, Some(namespace)) && pinned(&r.type_hint, type_hint) && pinned(&r.source, source))
+        .map(|r| r.provider.as_str())
+}
+
+/// The default embedding provider plus any routed ones.
+#[derive(Clone)]
+pub struct EmbeddingRouter {
+    default: Arc<dyn EmbeddingProvider>,
+    named: HashMap<String, Arc<dyn EmbeddingProvider>>,
+    routes: Vec<EmbeddingRoute>,
+}
+
+impl EmbeddingRouter {
+    /// A router that sends every memory to `default`.
+    pub fn new(default: Arc<dyn EmbeddingProvider>) -> Self {
+        EmbeddingRouter { default, named: HashMap::new(), routes: Vec::new() }
+    }
+
+    /// Add the named providers and the routes that use them (validated with validate_routes).
+    pub fn with_routes(
+        mut self,
+        named: HashMap<String, Arc<dyn EmbeddingProvider>>,
+        routes: Vec<EmbeddingRoute>,
+    ) -> Self {
+        self.named = named;
+        self.routes = routes;
+        self
+    }
+
+    /// The provider for memories no route matches.
+    pub fn default_provider(&self) -> &Arc<dyn EmbeddingProvider> {
+        &self.default
+    }
+
+    /// Whether any route is configured.
+    pub fn is_routed(&self) -> bool {
+        !self.routes.is_empty()
+    }
+
+    fn resolve(&self, name: Option<&str>) -> &Arc<dyn EmbeddingProvider> {
+        name.and_then(|n| self.named.get(n)).unwrap_or(&self.default)
+    }
+
+    /// The provider that embeds a memory.
+    pub fn for_memory(&self, namespace: &str, type_hint: &str, source: &str) -> &Arc<dyn EmbeddingProvider> {
+        self.resolve(route_for_memory(&self.routes, namespace, type_hint, source))
+    }
+
+    /// The provider a search with these filters embeds its query with.
+    pub fn for_search(&self, namespace: &str, type_hint: Option<&str>, source: Option<&str>) -> &Arc<dyn EmbeddingProvider> {
+        self.resolve(route_for_search(&self.routes, namespace, type_hint, source))
+    }
+
+    /// Every provider a memory can be routed to, default first, one per model.
+    pub fn providers(&self) -> Vec<&Arc<dyn EmbeddingProvider>> {
+        let mut providers = vec![&self.default];
+        for route in &self.routes {
+            if let Some(provider) = self.named.get(&route.provider) {
+                if providers.iter().all(|p| p.model_name() != provider.model_name()) {
+                    providers.push(provider);
+                }
+            }
+        }
+        providers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(namespace: Option<&str>, type_hint: Option<&str>, source: Option<&str>, provider: &str) -> EmbeddingRoute {
+        EmbeddingRoute {
+            namespace: namespace.map(str::to_string),
+            type_hint: type_hint.map(str::to_string),
+            source: source.map(str::to_string),
+            provider: provider.to_string(),
+        }
+    }
+
+    #[test]
+    fn memories_take_the_first_matching_route() {
+        let routes = vec![
+            route(None, Some("code"), Some("github"), "code-gh"),
+            route(None, Some("code"), None, "code"),
+            route(Some("chat"), None, None, "chat"),
+        ];
+        assert_eq!(route_for_memory(&routes, "default", "code", "github"), Some("code-gh"));
+        assert_eq!(route_for_memory(&routes, "chat", "code", "user"), Some("code"));
+        assert_eq!(route_for_memory(&routes, "chat", "fact", "user"), Some("chat"));
+        assert_eq!(route_for_memory(&routes, "default", "fact", "user"), None);
+    }
+
+    #[test]
+    fn searches_route_only_when_filters_pin_the_route() {
+        let routes = vec![route(None, Some("code"), None, "code"), route(Some("chat"), None, None, "chat")];
+        assert_eq!(route_for_search(&routes, "default", Some("code"), None), Some("code"));
+        assert_eq!(route_for_search(&routes, "default", None, Some("github")), None);
+        assert_eq!(route_for_search(&routes, "chat", None, None), Some("chat"));
+        assert_eq!(route_for_search(&routes, "default", Some("fact"), None), None);
+    }
+
+    #[test]
+    fn invalid_routes_are_rejected() {
+        let mut config = EmbeddingConfig::default();
+        config.routes.push(route(None, Some("code"), None, "code"));
+        assert!(validate_routes(&config).is_err());
+
+        config.providers.insert("code".to_string(), Default::default());
+        assert!(validate_routes(&config).is_ok());
+
+        config.routes.push(route(None, None, None, "code"));
+        assert!(validate_routes(&config).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! Configurable embedding text.
+//!
+//! `embedding.text_template` decides what text a memory is embedded as, e.g.
+//! `"{type_hint}: {content}\nTags: {tags}"`. Placeholders: {content}, {type_hint}, {source},
+//! {tags} and {entities} (comma-separated), and {payload} (compact JSON; payloads are not
+//! embedded unless the template asks for them). A line whose placeholders all render empty is
+//! dropped, so "Tags: {tags}" disappears for untagged memories. An empty template keeps the
+//! default text from `build_embedding_text` (content followed by tags).
+//!
+//! Changing the template changes every embedding's input — run `memcp embed backfill`
+//! after marking embeddings stale to re-embed existing memories consistently.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use super::build_embedding_text;
+use crate::errors::MemcpError;
+use crate::store::Memory;
+
+/// Placeholders a template may use.
+pub const TEMPLATE_FIELDS: &[&str] = &["content", "type_hint", "source", "tags", "entities", "payload"];
+
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{([a-z_]+)\}").expect("placeholder pattern must compile"));
+
+/// A validated embedding text template. The default renders `build_embedding_text`.
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingTemplate {
+    template: Option<String>,
+}
+
+/// The memory values a template is rendered from.
+pub struct TemplateValues<'a> {
+    pub content: &'a str,
+    pub type_hint: &'a str,
+    pub source: &'a str,
+    pub tags: &'a Option<serde_json::Value>,
+    pub entities: &'a Option<serde_json::Value>,
+    pub payload: &'a Option<serde_json::Value>,
+}
+
+impl<'a> From<&'a Memory> for TemplateValues<'a> {
+    fn from(memory: &'a Memory) -> Self {
+        TemplateValues {
+            content: &memory.content,
+            type_hint: &memory.type_hint,
+            source: &memory.source,
+            tags: &memory.tags,
+            entities: &memory.extracted_entities,
+            payload: &memory.payload,
+        }
+    }
+}
+
+/// Join the strings of a JSON array with ", " (non-arrays render empty).
+fn join_strings(value: &Option<serde_json::Value>) -> String {
+    value
+        .as_ref()
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default()
+}
+
+impl EmbeddingTemplate {
+    /// Parse a template. Empty means the default text; otherwise every placeholder must be
+    /// one of TEMPLATE_FIELDS and {content} must appear.
+    pub fn parse(template: &str) -> Result<Self, MemcpError> {
+        if template.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        for caps in PLACEHOLDER_RE.captures_iter(template) {
+            if !TEMPLATE_FIELDS.contains(&&caps[1]) {
+                return Err(MemcpError::Config(format!(
+                    "Unknown placeholder {{{}}} in embedding.text_template (expected one of: {})",
+                    &caps[1],
+                    TEMPLATE_FIELDS.join(", ")
+                )));
+            }
+        }
+        if !template.contains("{content}") {
+            return Err(MemcpError::Config("embedding.text_template must include {content}".to_string()));
+        }
+        Ok(EmbeddingTemplate { template: Some(template.to_string()) })
+    }
+
+    /// Whether the rendered text includes `field` (one of TEMPLATE_FIELDS), so a change to
+    /// it calls for re-embedding. The default text includes content and tags.
+    pub fn uses_field(&self, field: &str) -> bool {
+        match self.template {
+            Some(ref template) => template.contains(&format!("{{{}}}", field)),
+            None => field == "content" || field == "tags",
+        }
+    }
+
+    /// Whether the rendered text depends on extraction output ({entities}, or {type_hint}
+    /// which extraction may classify), so it should be re-embedded once extraction finishes.
+    pub fn uses_extraction(&self) -> bool {
+        self.template.is_some() && (self.uses_field("entities") || self.uses_field("type_hint"))
+    }
+
+    /// Render the embedding text for a stored memory.
+    pub fn render_memory(&self, memory: &Memory) -> String {
+        self.render(&TemplateValues::from(memory))
+    }
+
+    /// Render the embedding text for a set of memory values.
+    pub fn render(&self, values: &TemplateValues<'_>) -> String {
+        let Some(ref template) = self.template else {
+            return build_embedding_text(values.content, values.tags);
+        };
+        let tags = join_strings(values.tags);
+        let entities = join_strings(values.entities);
+        // A bare string payload renders without JSON quotes
+        let payload = match values.payload {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Null) | None => String::new(),
+            Some(value) => value.to_string(),
+        };
+
+        let lines: Vec<String> = template
+            .lines()
+            .filter_map(|line| {
+                let mut has_placeholder = false;
+                let mut all_empty = true;
+                let rendered = PLACEHOLDER_RE.replace_all(line, |caps: &regex::Captures| {
+                    let value = match &caps[1] {
+                        "content" => values.content,
+                        "type_hint" => values.type_hint,
+                        "source" => values.source,
+                        "tags" => tags.as_str(),
+                        "payload" => payload.as_str(),
+                        _ => entities.as_str(),
+                    };
+                    has_placeholder = true;
+                    all_empty &= value.is_empty();
+                    value.to_string()
+                });
+                (!has_placeholder || !all_empty).then(|| rendered.into_owned())
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renders_fields_and_drops_empty_lines() {
+        let template = EmbeddingTemplate::parse("{type_hint}: {content}\nTags: {tags}\nEntities: {entities}").unwrap();
+        let tags = Some(json!(["rust", "db"]));
+        let values = TemplateValues {
+            content: "Uses sqlx",
+            type_hint: "fact",
+            source: "chat",
+            tags: &tags,
+            entities: &None,
+            payload: &None,
+        };
+        assert_eq!(template.render(&values), "fact: Uses sqlx\nTags: rust, db");
+        assert!(template.uses_extraction());
+        assert!(!template.uses_field("source"));
+
+        let default = EmbeddingTemplate::parse("").unwrap();
+        assert_eq!(default.render(&values), "Uses sqlx rust db");
+        assert!(!default.uses_extraction());
+        assert!(default.uses_field("tags"));
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders_and_missing_content() {
+        assert!(EmbeddingTemplate::parse("{content} {title}").is_err());
+        assert!(EmbeddingTemplate::parse("Tags: {tags}").is_err());
+        assert!(EmbeddingTemplate::parse("{source} — {content}").is_ok());
+    }
+}
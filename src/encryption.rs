@@ -0,0 +1,176 @@
+//! Application-level encryption of memory content at rest.
+//!
+//! With `security.encryption_key` set, the PostgreSQL store encrypts memory and revision
+//! content with AES-256-GCM before writing it and decrypts it after reading, so the
+//! database only ever holds ciphertext. Embedding and consolidation work on the decrypted
+//! content in memory.
+//!
+//! Extraction is turned off rather than encrypted: its entities and facts are verbatim parts
+//! of the content, and symbolic search and entity lookups match them in SQL. The server
+//! doesn't start the extraction pipeline while a key is set, the store refuses to write
+//! extraction results or fact embeddings, and `memcp encrypt-existing` drops those written
+//! before.
+//!
+//! Stored values are `enc:v1:` followed by base64(nonce || ciphertext). Values without the
+//! prefix are plaintext written before encryption was enabled; they are read as-is until
+//! `memcp encrypt-existing` rewrites them. Because the database can't read the content,
+//! BM25 keyword search no longer matches encrypted memories — vector and tag search are
+//! unaffected, and exact-duplicate detection compares a keyed digest of the plaintext. Payloads stay in plaintext so
+//! `payload_path` filters keep working; don't put secrets in them.
+//!
+//! Where the database has to match values it can't read (redacted originals), it stores
+//! `keyed_hash`es: HMAC-SHA256 under a second key derived from the encryption key.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::config::SecurityConfig;
+use crate::errors::MemcpError;
+
+/// Prefix marking an encrypted content value (and its format version).
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// AES-GCM nonce length in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Encrypts and decrypts content with one AES-256-GCM key.
+pub struct ContentCipher {
+    cipher: Aes256Gcm,
+    /// HMAC key for `keyed_hash`, derived from the encryption key
+    hash_key: Vec<u8>,
+}
+
+impl std::fmt::Debug for ContentCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print key material
+        f.write_str("ContentCipher")
+    }
+}
+
+impl ContentCipher {
+    /// Build a cipher from a base64-encoded 32-byte key (e.g. `openssl rand -base64 32`).
+    pub fn from_base64_key(key: &str) -> Result<Self, MemcpError> {
+        let bytes = STANDARD
+            .decode(key.trim())
+            .map_err(|_| MemcpError::Config("Encryption key must be base64-encoded".to_string()))?;
+        if bytes.len() != 32 {
+            return Err(MemcpError::Config(format!(
+                "Encryption key must be 32 bytes (AES-256), got {}",
+                bytes.len()
+            )));
+        }
+        let hash_key = <Hmac<Sha256> as Mac>::new_from_slice(&bytes)
+            .expect("HMAC accepts keys of any length")
+            .chain_update(b"memcp keyed hash v1")
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        Ok(ContentCipher {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)),
+            hash_key,
+        })
+    }
+
+    /// Build the cipher configured in `[security]`, or None when encryption is off.
+    pub fn from_config(config: &SecurityConfig) -> Result<Option<Self>, MemcpError> {
+        let key = match (&config.encryption_key, &config.encryption_key_file) {
+            (Some(key), _) => key.clone(),
+            (None, Some(path)) => std::fs::read_to_string(path).map_err(|e| {
+                MemcpError::Config(format!("Failed to read encryption key file {}: {}", path, e))
+            })?,
+            (None, None) => return Ok(None),
+        };
+        Self::from_base64_key(&key).map(Some)
+    }
+
+    /// Encrypt `plaintext` with a fresh random nonce.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, MemcpError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| MemcpError::Internal("Failed to encrypt memory content".to_string()))?;
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload)))
+    }
+
+    /// Hex HMAC-SHA256 of `text`. Deterministic for a given key, so equal values hash equally,
+    /// but can't be reversed or brute-forced without the key.
+    pub fn keyed_hash(&self, text: &str) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.hash_key).expect("HMAC accepts keys of any length");
+        mac.update(text.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    /// Decrypt a stored value. Values without the encrypted prefix are returned unchanged.
+    pub fn decrypt(&self, stored: String) -> Result<String, MemcpError> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored);
+        };
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|_| MemcpError::Storage("Encrypted content is not valid base64".to_string()))?;
+        if payload.len() < NONCE_LEN {
+            return Err(MemcpError::Storage("Encrypted content is truncated".to_string()));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| MemcpError::Storage("Failed to decrypt memory content — wrong encryption key?".to_string()))?;
+        String::from_utf8(plaintext)
+            .map_err(|_| MemcpError::Storage("Decrypted memory content is not UTF-8".to_string()))
+    }
+}
+
+/// Whether a stored content value is encrypted.
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(ENCRYPTED_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY="; // "0123456789abcdef" x2
+
+    #[test]
+    fn roundtrips_and_passes_plaintext_through() {
+        let cipher = ContentCipher::from_base64_key(KEY).unwrap();
+        let stored = cipher.encrypt("Prefers dark roast").unwrap();
+        assert!(is_encrypted(&stored));
+        assert_ne!(cipher.encrypt("Prefers dark roast").unwrap(), stored, "fresh nonce per write");
+        assert_eq!(cipher.decrypt(stored).unwrap(), "Prefers dark roast");
+        assert_eq!(cipher.decrypt("legacy plaintext".to_string()).unwrap(), "legacy plaintext");
+    }
+
+    #[test]
+    fn keyed_hashes_depend_on_the_key() {
+        let cipher = ContentCipher::from_base64_key(KEY).unwrap();
+        let other = ContentCipher::from_base64_key(&STANDARD.encode([7u8; 32])).unwrap();
+        let hash = cipher.keyed_hash("ann@example.com");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(cipher.keyed_hash("ann@example.com"), hash);
+        assert_ne!(cipher.keyed_hash("bob@example.com"), hash);
+        assert_ne!(other.keyed_hash("ann@example.com"), hash);
+    }
+
+    #[test]
+    fn rejects_bad_keys_and_tampered_content() {
+        assert!(ContentCipher::from_base64_key("not base64!").is_err());
+        assert!(ContentCipher::from_base64_key("c2hvcnQ=").is_err());
+
+        let cipher = ContentCipher::from_base64_key(KEY).unwrap();
+        let other = ContentCipher::from_base64_key(&STANDARD.encode([7u8; 32])).unwrap();
+        let stored = cipher.encrypt("secret").unwrap();
+        assert!(other.decrypt(stored.clone()).is_err());
+
+        let mut payload = STANDARD.decode(stored.strip_prefix(ENCRYPTED_PREFIX).unwrap()).unwrap();
+        *payload.last_mut().unwrap() ^= 1;
+        assert!(cipher.decrypt(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload))).is_err());
+    }
+}
@@ -38,7 +38,45 @@ impl From<crate::embedding::EmbeddingError> for MemcpError {
     }
 }
 
+/// Stable, machine-readable error codes.
+///
+/// Every tool error payload carries one as `code` so clients can branch on the error kind
+/// instead of parsing the message. Codes are part of the tool contract — never rename one.
+pub mod codes {
+    /// Bad or missing tool argument (see `field`)
+    pub const VALIDATION: &str = "VALIDATION";
+    /// Memory, revision, or link does not exist (or is outside the namespace)
+    pub const NOT_FOUND: &str = "NOT_FOUND";
+    /// Database query or connection failed
+    pub const STORAGE_UNAVAILABLE: &str = "STORAGE_UNAVAILABLE";
+    /// The tool needs the PostgreSQL backend
+    pub const BACKEND_UNSUPPORTED: &str = "BACKEND_UNSUPPORTED";
+    /// No LLM / extraction provider is configured for this tool
+    pub const PROVIDER_UNAVAILABLE: &str = "PROVIDER_UNAVAILABLE";
+    /// The configured LLM / extraction provider returned an error
+    pub const PROVIDER_ERROR: &str = "PROVIDER_ERROR";
+    /// Invalid server configuration
+    pub const CONFIG: &str = "CONFIG";
+    /// The tool modifies memories and the server runs in read-only mode
+    pub const READ_ONLY: &str = "READ_ONLY";
+    /// The tool call ran past server.tool_timeout_ms or was cancelled by the client
+    pub const TIMEOUT: &str = "TIMEOUT";
+    /// Unexpected internal failure
+    pub const INTERNAL: &str = "INTERNAL";
+}
+
 impl MemcpError {
+    /// Stable error code for this error (see [`codes`]).
+    pub fn code(&self) -> &'static str {
+        match self {
+            MemcpError::Validation { .. } => codes::VALIDATION,
+            MemcpError::NotFound { .. } => codes::NOT_FOUND,
+            MemcpError::Config(_) => codes::CONFIG,
+            MemcpError::Internal(_) => codes::INTERNAL,
+            MemcpError::Storage(_) => codes::STORAGE_UNAVAILABLE,
+        }
+    }
+
     /// Helper to create validation errors with field names
     ///
     /// Example:
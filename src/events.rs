@@ -0,0 +1,358 @@
+//! Memory lifecycle event notifications.
+//!
+//! With `[events] enabled`, MemoryService and the consolidation worker emit a `MemoryEvent`
+//! whenever memories are created, consolidated, reinforced, or deleted. Emitting never blocks
+//! the caller: events go onto a bounded queue (new events are dropped while it is full) and a
+//! single dispatcher delivers them in order. `events.webhook_url` receives each event as a
+//! JSON POST, retried with exponential backoff on transport errors, 429 and 5xx, and signed
+//! with HMAC-SHA256 over the raw body when `events.secret` is set. `events.notify_channel`
+//! receives it through pg_notify. Delivery is best effort: an event whose retries run out is
+//! logged and dropped. Expiry, decay, and retention sweeps do not emit events.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::sync::mpsc;
+
+use crate::config::EventsConfig;
+use crate::shutdown::Shutdown;
+use crate::store::postgres::PostgresMemoryStore;
+
+/// Header carrying "sha256=<hex HMAC of the body>" when `events.secret` is set.
+pub const SIGNATURE_HEADER: &str = "X-Memcp-Signature";
+/// Header carrying the event kind, e.g. "created".
+pub const EVENT_HEADER: &str = "X-Memcp-Event";
+/// Header carrying the event's unique ID, the same on every retry.
+pub const DELIVERY_HEADER: &str = "X-Memcp-Delivery";
+
+/// pg_notify rejects payloads of 8000 bytes or more.
+const MAX_NOTIFY_PAYLOAD: usize = 7900;
+
+/// What happened to the memories an event names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Created,
+    Consolidated,
+    Reinforced,
+    Deleted,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::Consolidated => "consolidated",
+            EventKind::Reinforced => "reinforced",
+            EventKind::Deleted => "deleted",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "created" => Some(EventKind::Created),
+            "consolidated" => Some(EventKind::Consolidated),
+            "reinforced" => Some(EventKind::Reinforced),
+            "deleted" => Some(EventKind::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// One lifecycle event, serialized as the webhook body and the notify payload.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryEvent {
+    /// Unique event ID, sent in X-Memcp-Delivery
+    pub id: String,
+    pub event: EventKind,
+    pub namespace: String,
+    /// Memories the event is about; empty for filter-based bulk deletes (see `count`)
+    pub memory_ids: Vec<String>,
+    /// Number of memories affected
+    pub count: u64,
+    pub occurred_at: DateTime<Utc>,
+    /// Event-specific details, e.g. a consolidation's source IDs or a reinforcement's rating
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+}
+
+impl MemoryEvent {
+    pub fn new(event: EventKind, namespace: &str, memory_ids: Vec<String>) -> Self {
+        MemoryEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            event,
+            namespace: namespace.to_string(),
+            count: memory_ids.len() as u64,
+            memory_ids,
+            occurred_at: Utc::now(),
+            data: Value::Null,
+        }
+    }
+
+    /// Set the affected count for events that don't list their memories.
+    pub fn with_count(mut self, count: u64) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Attach event-specific details.
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+/// The X-Memcp-Signature value for `body`: "sha256=" followed by the hex HMAC-SHA256.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Handle for emitting events. Cheap to clone; the default handle is disabled and drops
+/// every event.
+#[derive(Clone, Default)]
+pub struct EventNotifier {
+    inner: Option<Arc<NotifierInner>>,
+}
+
+struct NotifierInner {
+    sender: mpsc::Sender<MemoryEvent>,
+    kinds: Vec<EventKind>,
+}
+
+impl EventNotifier {
+    /// A notifier that sends nothing.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Spawn the dispatcher for `config` and return a handle feeding it.
+    ///
+    /// `store` is needed for `notify_channel`; without it only the webhook is used. On
+    /// shutdown the dispatcher stops retrying and makes one attempt at each queued event.
+    pub fn spawn(config: &EventsConfig, store: Option<Arc<PostgresMemoryStore>>, shutdown: &Shutdown) -> Self {
+        if !config.enabled {
+            return Self::disabled();
+        }
+        let notify_channel = match (&config.notify_channel, &store) {
+            (Some(channel), Some(_)) => Some(channel.clone()),
+            (Some(_), None) => {
+                tracing::warn!("events.notify_channel requires PostgreSQL backend — notifications disabled");
+                None
+            }
+            (None, _) => None,
+        };
+        let dispatcher = Dispatcher {
+            http: reqwest::Client::new(),
+            webhook_url: config.webhook_url.clone(),
+            secret: config.secret.clone(),
+            notify_channel,
+            store,
+            max_retries: config.max_retries,
+            retry_base_delay_ms: config.retry_base_delay_ms,
+            timeout: Duration::from_secs(config.timeout_secs),
+        };
+        let kinds = config.events.iter().filter_map(|name| EventKind::parse(name)).collect();
+        let (tx, mut rx) = mpsc::channel::<MemoryEvent>(config.queue_capacity.max(1));
+
+        let task_shutdown = shutdown.clone();
+        shutdown.spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    biased;
+                    _ = task_shutdown.cancelled() => break,
+                    event = rx.recv() => match event {
+                        Some(event) => event,
+                        None => return,
+                    },
+                };
+                dispatcher.deliver(&event, dispatcher.max_retries).await;
+            }
+            // Events from the last tool calls before a disconnect are still worth sending
+            rx.close();
+            while let Some(event) = rx.recv().await {
+                dispatcher.deliver(&event, 0).await;
+            }
+        });
+
+        EventNotifier { inner: Some(Arc::new(NotifierInner { sender: tx, kinds })) }
+    }
+
+    /// Queue `event` for delivery unless its kind is filtered out. Never blocks.
+    pub fn emit(&self, event: MemoryEvent) {
+        let Some(inner) = &self.inner else {
+            return;
+        };
+        if !inner.kinds.contains(&event.event) {
+            return;
+        }
+        if let Err(e) = inner.sender.try_send(event) {
+            crate::metrics::global().events_dropped.inc();
+            tracing::warn!(error = %e, "Event queue full or closed — memory event dropped");
+        }
+    }
+}
+
+/// Delivers events to the configured webhook and notify channel.
+struct Dispatcher {
+    http: reqwest::Client,
+    webhook_url: Option<String>,
+    secret: Option<String>,
+    notify_channel: Option<String>,
+    store: Option<Arc<PostgresMemoryStore>>,
+    max_retries: u32,
+    retry_base_delay_ms: u64,
+    timeout: Duration,
+}
+
+impl Dispatcher {
+    /// Send `event` to every target; the webhook is retried up to `max_retries` times.
+    async fn deliver(&self, event: &MemoryEvent, max_retries: u32) {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to serialize memory event");
+                return;
+            }
+        };
+        let metrics = crate::metrics::global();
+        if let Some(url) = &self.webhook_url {
+            match self.post_webhook(url, event, &body, max_retries).await {
+                Ok(()) => metrics.events_delivered.inc(),
+                Err(e) => {
+                    metrics.events_failed.inc();
+                    tracing::warn!(event = event.event.as_str(), delivery = %event.id, error = %e, "Webhook delivery failed — event dropped");
+                }
+            }
+        }
+        if let (Some(channel), Some(store)) = (&self.notify_channel, &self.store) {
+            let payload = notify_payload(event, body);
+            let sent = sqlx::query("SELECT pg_notify($1, $2)")
+                .bind(channel)
+                .bind(&payload)
+                .execute(store.pool())
+                .await;
+            match sent {
+                Ok(_) => metrics.events_delivered.inc(),
+                Err(e) => {
+                    metrics.events_failed.inc();
+                    tracing::warn!(event = event.event.as_str(), channel = %channel, error = %e, "pg_notify failed — event dropped");
+                }
+            }
+        }
+    }
+
+    /// POST `body`, retrying transient failures up to `max_retries` times.
+    async fn post_webhook(&self, url: &str, event: &MemoryEvent, body: &[u8], max_retries: u32) -> Result<(), String> {
+        let mut attempt = 0u32;
+        loop {
+            let mut request = self
+                .http
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event.event.as_str())
+                .header(DELIVERY_HEADER, &event.id)
+                .body(body.to_vec());
+            if let Some(secret) = &self.secret {
+                request = request.header(SIGNATURE_HEADER, sign(secret, body));
+            }
+            if !self.timeout.is_zero() {
+                request = request.timeout(self.timeout);
+            }
+            let (error, transient) = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    (format!("status {}", status), status.as_u16() == 429 || status.is_server_error())
+                }
+                Err(e) => (e.to_string(), true),
+            };
+            if !transient || attempt >= max_retries {
+                return Err(error);
+            }
+            let delay = retry_delay(self.retry_base_delay_ms, attempt);
+            tracing::debug!(delivery = %event.id, attempt = attempt + 1, delay_ms = delay.as_millis() as u64, error = %error, "Webhook delivery failed, retrying");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Delay before retrying after the given (0-based) failed attempt, capped at one minute.
+fn retry_delay(base_ms: u64, attempt: u32) -> Duration {
+    Duration::from_millis(base_ms.saturating_mul(1u64 << attempt.min(20)).min(60_000))
+}
+
+/// The pg_notify payload for `event`: its JSON body, or without `memory_ids` and `data` when
+/// that would exceed the notify size limit (`truncated` is then true).
+fn notify_payload(event: &MemoryEvent, body: Vec<u8>) -> String {
+    if body.len() <= MAX_NOTIFY_PAYLOAD {
+        return String::from_utf8(body).unwrap_or_default();
+    }
+    let mut value = serde_json::to_value(event).unwrap_or_default();
+    value["memory_ids"] = Value::Array(Vec::new());
+    value["truncated"] = Value::Bool(true);
+    if let Some(object) = value.as_object_mut() {
+        object.remove("data");
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_is_hex_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn oversized_notify_payloads_drop_the_id_list() {
+        let ids: Vec<String> = (0..500).map(|_| uuid::Uuid::new_v4().to_string()).collect();
+        let event = MemoryEvent::new(EventKind::Deleted, "default", ids);
+        let payload = notify_payload(&event, serde_json::to_vec(&event).unwrap());
+        assert!(payload.len() <= MAX_NOTIFY_PAYLOAD);
+        let value: Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(value["count"], 500);
+        assert_eq!(value["truncated"], true);
+        assert_eq!(value["memory_ids"].as_array().unwrap().len(), 0);
+
+        let small = MemoryEvent::new(EventKind::Created, "default", vec!["a".to_string()]);
+        let value: Value = serde_json::from_str(&notify_payload(&small, serde_json::to_vec(&small).unwrap())).unwrap();
+        assert_eq!(value["memory_ids"][0], "a");
+        assert!(value.get("data").is_none());
+    }
+
+    #[tokio::test]
+    async fn filtered_kinds_are_not_queued() {
+        let config = EventsConfig {
+            enabled: true,
+            webhook_url: Some("http://127.0.0.1:9/hook".to_string()),
+            events: vec!["deleted".to_string()],
+            ..Default::default()
+        };
+        let notifier = EventNotifier::spawn(&config, None, &Shutdown::new());
+        let inner = notifier.inner.clone().unwrap();
+        let before = inner.sender.capacity();
+        notifier.emit(MemoryEvent::new(EventKind::Created, "default", vec!["a".to_string()]));
+        assert_eq!(inner.sender.capacity(), before);
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_a_minute() {
+        assert_eq!(retry_delay(1000, 0), Duration::from_millis(1000));
+        assert_eq!(retry_delay(1000, 3), Duration::from_millis(8000));
+        assert_eq!(retry_delay(1000, 10), Duration::from_secs(60));
+    }
+}
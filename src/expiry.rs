@@ -0,0 +1,59 @@
+//! Memory expiration (TTL) sweeper.
+//!
+//! Memories stored with `expires_at` are hidden from search/list as soon as they expire.
+//! This background task periodically removes them — permanently, or into the trash
+//! when `expiry.action = "trash"` so they can still be restored. A skipped or failed
+//! sweep only delays reclaiming the rows; the memories are already out of sight.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::ExpiryConfig;
+use crate::store::postgres::PostgresMemoryStore;
+
+/// Advisory lock serializing sweeps across processes.
+const EXPIRY_LOCK: &str = "memcp:expiry-sweeper";
+
+/// Spawn the background sweeper task.
+///
+/// Runs one sweep per `sweep_interval_secs` (the first after one full interval).
+/// A tick is skipped while another process is sweeping. Sweep failures are logged and
+/// retried on the next tick — the task never exits.
+pub fn spawn_expiry_sweeper(
+    store: Arc<PostgresMemoryStore>,
+    config: ExpiryConfig,
+) -> tokio::task::JoinHandle<()> {
+    let archive = config.action == "trash";
+    let period = Duration::from_secs(config.sweep_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let lock = match store.try_advisory_lock(EXPIRY_LOCK).await {
+                Ok(Some(lock)) => lock,
+                Ok(None) => {
+                    tracing::debug!("Another memcp process is sweeping expired memories — skipping this tick");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Expiry sweep lock failed — will retry next interval");
+                    continue;
+                }
+            };
+            let result = store.sweep_expired(archive).await;
+            lock.release().await;
+            match result {
+                Ok(0) => {}
+                Ok(count) => {
+                    tracing::info!(count = count, archive = archive, "Expired memories swept");
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Expiry sweep failed — will retry next interval");
+                }
+            }
+        }
+    })
+}
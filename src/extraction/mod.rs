@@ -8,6 +8,8 @@ pub mod openai;
 pub mod pipeline;
 
 use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
 use thiserror::Error;
 
 use crate::errors::MemcpError;
@@ -32,6 +34,15 @@ pub enum ExtractionError {
     NotConfigured(String),
 }
 
+impl From<crate::llm_client::LlmError> for ExtractionError {
+    fn from(e: crate::llm_client::LlmError) -> Self {
+        match e {
+            crate::llm_client::LlmError::Api { status, message } => ExtractionError::Api { status, message },
+            other => ExtractionError::Generation(other.to_string()),
+        }
+    }
+}
+
 impl From<ExtractionError> for MemcpError {
     fn from(e: ExtractionError) -> Self {
         MemcpError::Internal(e.to_string())
@@ -45,6 +56,8 @@ pub struct ExtractionResult {
     pub entities: Vec<String>,
     /// Key facts: specific assertions, preferences, relationships, or instructions
     pub facts: Vec<String>,
+    /// Classified memory type, one of MEMORY_TYPE_HINTS (None when the model gave no valid type)
+    pub type_hint: Option<String>,
 }
 
 /// A pending extraction job for a memory.
@@ -56,20 +69,220 @@ pub struct ExtractionJob {
     pub content: String,
     /// Current attempt number (for retry logic)
     pub attempt: u8,
+    /// Overwrite the memory's type_hint with the classified type (set when the caller omitted
+    /// type_hint, or always when extraction.keep_explicit_type_hint is false)
+    pub classify: bool,
 }
 
 /// Build the extraction prompt for a given content string.
 pub fn build_extraction_prompt(content: &str) -> String {
     format!(
-        "Extract named entities and key facts from the following text.\n\
+        "Extract named entities and key facts from the following text, and classify it.\n\
          Entities: people, places, dates, tools, projects, concepts, preferences.\n\
          Facts: specific assertions, preferences, relationships, or instructions stated.\n\
-         Be comprehensive. Output only JSON matching the provided schema.\n\n\
+         type_hint: what the text is as a whole, one of: fact, preference, instruction, event, decision.\n\
+         Be comprehensive. Output only JSON with keys entities, facts, and type_hint.\n\n\
          Text:\n{}",
         content
     )
 }
 
+/// JSON schema for structured extraction output (entities, facts, and the classified type).
+pub fn extraction_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "entities": {
+                "type": "array",
+                "items": {"type": "string"}
+            },
+            "facts": {
+                "type": "array",
+                "items": {"type": "string"}
+            },
+            "type_hint": {"type": "string", "enum": MEMORY_TYPE_HINTS}
+        },
+        "required": ["entities", "facts", "type_hint"]
+    })
+}
+
+/// Memory types the extractors may assign, both when classifying a stored memory and when
+/// splitting a conversation.
+pub const MEMORY_TYPE_HINTS: [&str; 5] = ["fact", "preference", "decision", "instruction", "event"];
+
+/// Lowercase and validate a model-assigned type; None when it is not one of MEMORY_TYPE_HINTS.
+pub fn normalize_type_hint(raw: &str) -> Option<String> {
+    let hint = raw.trim().to_lowercase();
+    MEMORY_TYPE_HINTS.contains(&hint.as_str()).then_some(hint)
+}
+
+/// A discrete memory extracted from a conversation transcript.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtractedMemory {
+    /// Self-contained memory text
+    pub content: String,
+    /// One of MEMORY_TYPE_HINTS
+    #[serde(default)]
+    pub type_hint: String,
+    /// Index of the transcript turn the memory came from (as numbered in the prompt)
+    #[serde(default)]
+    pub turn: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ConversationOutput {
+    #[serde(default)]
+    memories: Vec<ExtractedMemory>,
+}
+
+/// Build the prompt that splits a numbered transcript into discrete memories.
+pub fn build_conversation_prompt(transcript: &str) -> String {
+    format!(
+        "Read the conversation below and extract the durable memories worth keeping about the user \
+         and their work: facts, preferences, decisions, instructions, and notable events.\n\
+         Each memory must be a single self-contained statement that makes sense without the conversation \
+         (resolve pronouns, name the subject). Skip greetings, small talk, and anything only relevant to this exchange.\n\
+         For each memory give type_hint (one of: fact, preference, decision, instruction, event) and \
+         turn (the [number] of the turn it came from).\n\
+         Output only JSON matching the provided schema.\n\n\
+         Conversation:\n{}",
+        transcript
+    )
+}
+
+/// JSON schema for conversation extraction output.
+pub fn conversation_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "memories": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "content": {"type": "string"},
+                        "type_hint": {"type": "string", "enum": MEMORY_TYPE_HINTS},
+                        "turn": {"type": "integer"}
+                    },
+                    "required": ["content", "type_hint"]
+                }
+            }
+        },
+        "required": ["memories"]
+    })
+}
+
+/// Parse and normalize model output for conversation extraction.
+///
+/// Drops empty memories and maps unknown type_hints to "fact".
+pub fn parse_conversation_output(raw: &str) -> Result<Vec<ExtractedMemory>, ExtractionError> {
+    let output: ConversationOutput = serde_json::from_str(raw).map_err(|e| {
+        ExtractionError::Generation(format!(
+            "Failed to parse conversation JSON from model output: {} (content: {})",
+            e, raw
+        ))
+    })?;
+
+    Ok(output
+        .memories
+        .into_iter()
+        .filter(|m| !m.content.trim().is_empty())
+        .map(|mut m| {
+            m.content = m.content.trim().to_string();
+            m.type_hint = normalize_type_hint(&m.type_hint).unwrap_or_else(|| "fact".to_string());
+            m
+        })
+        .collect())
+}
+
+/// An existing memory the model judged to contradict a newly extracted memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contradiction {
+    /// The contradicted (existing) memory
+    pub memory_id: String,
+    /// Why the model thinks the two cannot both hold, e.g. "tabs vs spaces"
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+struct ContradictionOutput {
+    #[serde(default)]
+    contradictions: Vec<ContradictionItem>,
+}
+
+#[derive(Deserialize)]
+struct ContradictionItem {
+    index: usize,
+    #[serde(default)]
+    reason: String,
+}
+
+/// Build the prompt that checks a new memory's facts against existing memories, which are
+/// numbered from 1 in the order given.
+pub fn build_contradiction_prompt(facts: &[String], candidates: &[(String, String)]) -> String {
+    let facts: Vec<String> = facts.iter().map(|f| format!("- {}", f)).collect();
+    let existing: Vec<String> = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, (_, content))| format!("[{}] {}", i + 1, content))
+        .collect();
+    format!(
+        "A new memory states these facts:\n{}\n\n         Which of the existing memories below contradict them — state something that cannot also be          true now, such as an opposite preference or a changed value? Ignore memories that are merely          different, more detailed, or about another subject.\n         For each contradiction give index (the [number] of the existing memory) and a short reason.\n         Output only JSON with key contradictions (an empty list when there are none).\n\n         Existing memories:\n{}",
+        facts.join("\n"),
+        existing.join("\n")
+    )
+}
+
+/// JSON schema for contradiction check output.
+pub fn contradiction_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "contradictions": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "index": {"type": "integer"},
+                        "reason": {"type": "string"}
+                    },
+                    "required": ["index", "reason"]
+                }
+            }
+        },
+        "required": ["contradictions"]
+    })
+}
+
+/// Parse contradiction check output, mapping the 1-based indexes back to candidate IDs.
+///
+/// Out-of-range indexes are dropped; each candidate is reported at most once.
+pub fn parse_contradiction_output(
+    raw: &str,
+    candidates: &[(String, String)],
+) -> Result<Vec<Contradiction>, ExtractionError> {
+    let output: ContradictionOutput = serde_json::from_str(raw).map_err(|e| {
+        ExtractionError::Generation(format!(
+            "Failed to parse contradiction JSON from model output: {} (content: {})",
+            e, raw
+        ))
+    })?;
+
+    let mut found: Vec<Contradiction> = Vec::new();
+    for item in output.contradictions {
+        let Some((memory_id, _)) = item.index.checked_sub(1).and_then(|i| candidates.get(i)) else {
+            continue;
+        };
+        if !found.iter().any(|c| c.memory_id == *memory_id) {
+            found.push(Contradiction {
+                memory_id: memory_id.clone(),
+                reason: item.reason.trim().to_string(),
+            });
+        }
+    }
+    Ok(found)
+}
+
 /// Core trait for extracting entities and facts from text.
 ///
 /// Implementations must be Send + Sync to support use in async contexts
@@ -79,6 +292,42 @@ pub trait ExtractionProvider: Send + Sync {
     /// Extract entities and facts from the given content.
     async fn extract(&self, content: &str) -> Result<ExtractionResult, ExtractionError>;
 
+    /// Split a numbered conversation transcript into discrete, typed memories.
+    ///
+    /// Callers chunk long transcripts; implementations do not truncate.
+    async fn extract_conversation(&self, transcript: &str) -> Result<Vec<ExtractedMemory>, ExtractionError>;
+
+    /// Find which `candidates` (id, content) contradict a new memory's extracted `facts`.
+    async fn detect_contradictions(
+        &self,
+        facts: &[String],
+        candidates: &[(String, String)],
+    ) -> Result<Vec<Contradiction>, ExtractionError>;
+
     /// Return the model name identifier used by this provider.
     fn model_name(&self) -> &str;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_contradiction_output_maps_indexes_to_ids() {
+        let candidates = vec![
+            ("a".to_string(), "User prefers tabs".to_string()),
+            ("b".to_string(), "User lives in Berlin".to_string()),
+        ];
+        let raw = r#"{"contradictions": [
+            {"index": 1, "reason": " tabs vs spaces "},
+            {"index": 1, "reason": "duplicate"},
+            {"index": 3, "reason": "out of range"},
+            {"index": 0, "reason": "not 1-based"}
+        ]}"#;
+        let found = parse_contradiction_output(raw, &candidates).unwrap();
+        assert_eq!(found, vec![Contradiction { memory_id: "a".to_string(), reason: "tabs vs spaces".to_string() }]);
+
+        assert!(parse_contradiction_output(r#"{"contradictions": []}"#, &candidates).unwrap().is_empty());
+        assert!(parse_contradiction_output("not json", &candidates).is_err());
+    }
+}
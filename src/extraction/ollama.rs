@@ -6,9 +6,16 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 
-use super::{ExtractionError, ExtractionProvider, ExtractionResult, build_extraction_prompt};
+use crate::config::LlmConfig;
+use crate::llm_client::LlmClient;
+
+use super::{
+    build_contradiction_prompt, build_conversation_prompt, build_extraction_prompt, contradiction_schema,
+    conversation_schema, extraction_schema, normalize_type_hint, parse_contradiction_output,
+    parse_conversation_output, Contradiction, ExtractedMemory, ExtractionError, ExtractionProvider,
+    ExtractionResult,
+};
 
 /// Request body for Ollama /api/chat with structured output
 #[derive(Serialize)]
@@ -49,6 +56,8 @@ struct ExtractionOutput {
     entities: Vec<String>,
     #[serde(default)]
     facts: Vec<String>,
+    #[serde(default)]
+    type_hint: String,
 }
 
 /// Ollama-backed extraction provider.
@@ -56,7 +65,7 @@ struct ExtractionOutput {
 /// Uses the /api/chat endpoint with structured JSON output (format field).
 /// Truncates content to max_content_chars to avoid context overflow.
 pub struct OllamaExtractionProvider {
-    client: reqwest::Client,
+    client: LlmClient,
     base_url: String,
     model: String,
     max_content_chars: usize,
@@ -71,30 +80,18 @@ impl OllamaExtractionProvider {
     /// * `max_content_chars` - Maximum content length before truncation (default: 1500)
     pub fn new(base_url: String, model: String, max_content_chars: usize) -> Self {
         OllamaExtractionProvider {
-            client: reqwest::Client::new(),
+            client: LlmClient::new(&LlmConfig::default()),
             base_url,
             model,
             max_content_chars,
         }
     }
-}
 
-/// JSON schema for structured extraction output
-fn extraction_schema() -> serde_json::Value {
-    json!({
-        "type": "object",
-        "properties": {
-            "entities": {
-                "type": "array",
-                "items": {"type": "string"}
-            },
-            "facts": {
-                "type": "array",
-                "items": {"type": "string"}
-            }
-        },
-        "required": ["entities", "facts"]
-    })
+    /// Route requests through a configured `LlmClient` (retries, timeout, circuit breaker).
+    pub fn with_llm_client(mut self, client: LlmClient) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[async_trait]
@@ -112,57 +109,62 @@ impl ExtractionProvider for OllamaExtractionProvider {
             content
         };
 
-        let prompt = build_extraction_prompt(truncated_content);
-
-        let request = OllamaChatRequest {
-            model: self.model.clone(),
-            messages: vec![OllamaMessage {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-            stream: false,
-            options: OllamaOptions { temperature: 0.0 },
-            format: extraction_schema(),
-        };
-
-        let url = format!("{}/api/chat", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| ExtractionError::Generation(format!("HTTP request failed: {}", e)))?;
-
-        let status = response.status().as_u16();
-        if !response.status().is_success() {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "unknown error".to_string());
-            return Err(ExtractionError::Api { status, message: body });
-        }
-
-        let chat_response: OllamaChatResponse = response
-            .json()
-            .await
-            .map_err(|e| ExtractionError::Generation(format!("Failed to parse Ollama response: {}", e)))?;
+        let content = self.chat_json(build_extraction_prompt(truncated_content), extraction_schema()).await?;
 
         // The content field is a JSON string — parse it into ExtractionOutput
-        let output: ExtractionOutput = serde_json::from_str(&chat_response.message.content)
+        let output: ExtractionOutput = serde_json::from_str(&content)
             .map_err(|e| ExtractionError::Generation(format!(
                 "Failed to parse extraction JSON from model output: {} (content: {})",
-                e, &chat_response.message.content
+                e, &content
             )))?;
 
         Ok(ExtractionResult {
             entities: output.entities,
             facts: output.facts,
+            type_hint: normalize_type_hint(&output.type_hint),
         })
     }
 
+    async fn extract_conversation(&self, transcript: &str) -> Result<Vec<ExtractedMemory>, ExtractionError> {
+        let content = self.chat_json(build_conversation_prompt(transcript), conversation_schema()).await?;
+        parse_conversation_output(&content)
+    }
+
+    async fn detect_contradictions(
+        &self,
+        facts: &[String],
+        candidates: &[(String, String)],
+    ) -> Result<Vec<Contradiction>, ExtractionError> {
+        if facts.is_empty() || candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let content = self.chat_json(build_contradiction_prompt(facts, candidates), contradiction_schema()).await?;
+        parse_contradiction_output(&content, candidates)
+    }
+
     fn model_name(&self) -> &str {
         &self.model
     }
 }
+
+impl OllamaExtractionProvider {
+    /// Send one prompt with a JSON schema and return the raw JSON string the model produced.
+    async fn chat_json(&self, prompt: String, format: serde_json::Value) -> Result<String, ExtractionError> {
+        let request = OllamaChatRequest {
+            model: self.model.clone(),
+            messages: vec![OllamaMessage {
+                role: "user".to_string(),
+                content: prompt,
+            }],
+            stream: false,
+            options: OllamaOptions { temperature: 0.0 },
+            format,
+        };
+
+        let url = format!("{}/api/chat", self.base_url);
+
+        let chat_response: OllamaChatResponse = self.client.post_json(&url, None, &request).await?;
+
+        Ok(chat_response.message.content)
+    }
+}
@@ -6,7 +6,14 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
-use super::{ExtractionError, ExtractionProvider, ExtractionResult, build_extraction_prompt};
+use crate::config::LlmConfig;
+use crate::llm_client::LlmClient;
+
+use super::{
+    build_contradiction_prompt, build_conversation_prompt, build_extraction_prompt, normalize_type_hint,
+    parse_contradiction_output, parse_conversation_output, Contradiction, ExtractedMemory, ExtractionError,
+    ExtractionProvider, ExtractionResult,
+};
 
 /// Request body for OpenAI Chat Completions API
 #[derive(Serialize)]
@@ -51,6 +58,8 @@ struct ExtractionOutput {
     entities: Vec<String>,
     #[serde(default)]
     facts: Vec<String>,
+    #[serde(default)]
+    type_hint: String,
 }
 
 /// OpenAI-backed extraction provider.
@@ -58,7 +67,7 @@ struct ExtractionOutput {
 /// Uses the chat completions API with json_object response format.
 /// Requires a valid OpenAI API key.
 pub struct OpenAIExtractionProvider {
-    client: reqwest::Client,
+    client: LlmClient,
     api_key: String,
     model: String,
     max_content_chars: usize,
@@ -84,12 +93,18 @@ impl OpenAIExtractionProvider {
         }
 
         Ok(OpenAIExtractionProvider {
-            client: reqwest::Client::new(),
+            client: LlmClient::new(&LlmConfig::default()),
             api_key,
             model,
             max_content_chars,
         })
     }
+
+    /// Route requests through a configured `LlmClient` (retries, timeout, circuit breaker).
+    pub fn with_llm_client(mut self, client: LlmClient) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 #[async_trait]
@@ -107,8 +122,46 @@ impl ExtractionProvider for OpenAIExtractionProvider {
             content
         };
 
-        let prompt = build_extraction_prompt(truncated_content);
+        let content_str = self.chat_json(build_extraction_prompt(truncated_content)).await?;
+
+        let output: ExtractionOutput = serde_json::from_str(&content_str)
+            .map_err(|e| ExtractionError::Generation(format!(
+                "Failed to parse extraction JSON from model output: {} (content: {})",
+                e, &content_str
+            )))?;
+
+        Ok(ExtractionResult {
+            entities: output.entities,
+            facts: output.facts,
+            type_hint: normalize_type_hint(&output.type_hint),
+        })
+    }
+
+    async fn extract_conversation(&self, transcript: &str) -> Result<Vec<ExtractedMemory>, ExtractionError> {
+        let content = self.chat_json(build_conversation_prompt(transcript)).await?;
+        parse_conversation_output(&content)
+    }
+
+    async fn detect_contradictions(
+        &self,
+        facts: &[String],
+        candidates: &[(String, String)],
+    ) -> Result<Vec<Contradiction>, ExtractionError> {
+        if facts.is_empty() || candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let content = self.chat_json(build_contradiction_prompt(facts, candidates)).await?;
+        parse_contradiction_output(&content, candidates)
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+}
 
+impl OpenAIExtractionProvider {
+    /// Send one prompt in json_object mode and return the raw JSON string the model produced.
+    async fn chat_json(&self, prompt: String) -> Result<String, ExtractionError> {
         let request = ChatRequest {
             model: self.model.clone(),
             messages: vec![ChatMessage {
@@ -120,49 +173,16 @@ impl ExtractionProvider for OpenAIExtractionProvider {
             },
         };
 
-        let response = self
+        let chat_response: ChatResponse = self
             .client
-            .post("https://api.openai.com/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| ExtractionError::Generation(format!("HTTP request failed: {}", e)))?;
-
-        let status = response.status().as_u16();
-        if !response.status().is_success() {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "unknown error".to_string());
-            return Err(ExtractionError::Api { status, message: body });
-        }
+            .post_json("https://api.openai.com/v1/chat/completions", Some(&self.api_key), &request)
+            .await?;
 
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .map_err(|e| ExtractionError::Generation(format!("Failed to parse OpenAI response: {}", e)))?;
-
-        let content_str = chat_response
+        chat_response
             .choices
             .into_iter()
             .next()
             .map(|c| c.message.content)
-            .ok_or_else(|| ExtractionError::Generation("OpenAI returned empty choices list".to_string()))?;
-
-        let output: ExtractionOutput = serde_json::from_str(&content_str)
-            .map_err(|e| ExtractionError::Generation(format!(
-                "Failed to parse extraction JSON from model output: {} (content: {})",
-                e, &content_str
-            )))?;
-
-        Ok(ExtractionResult {
-            entities: output.entities,
-            facts: output.facts,
-        })
-    }
-
-    fn model_name(&self) -> &str {
-        &self.model
+            .ok_or_else(|| ExtractionError::Generation("OpenAI returned empty choices list".to_string()))
     }
 }
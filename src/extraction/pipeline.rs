@@ -1,110 +1,401 @@
-/// Async extraction pipeline with bounded mpsc channel and background worker.
+/// Async extraction pipeline with bounded mpsc channel and background workers.
 ///
 /// Non-blocking design: store_memory never waits for extraction completion.
-/// Failed extractions are retried up to 3 times with exponential backoff (1s, 2s, 4s),
-/// then marked as failed.
+/// Up to `concurrency` jobs run at once, bounded by a semaphore. Each job retries in its own
+/// task up to 3 times with exponential backoff (1s, 2s, 4s), then is marked as failed.
+/// A memory is never processed by two tasks at once, so its status updates stay ordered; a
+/// job for changed content that arrives meanwhile runs after the current one.
+/// When a fact embedder is configured, each extracted fact is also embedded on its own.
+/// Jobs flagged `classify` also write the classified type back as the memory's type_hint.
+/// When the embedding text template uses extraction output, the memory is re-embedded after.
+/// With contradiction detection on, the extracted facts are then checked against similar
+/// memories and each contradicted one gets a `contradicts` link from the new memory.
+/// On shutdown no new jobs start; running ones finish (or, mid-backoff, stay 'pending').
+/// Jobs that came from the outbox are removed from it once they reach a final status.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
 
-use super::{ExtractionJob, ExtractionProvider};
+use super::{ExtractionJob, ExtractionProvider, ExtractionResult};
+use crate::embedding::{EmbeddingJob, EmbeddingProvider};
+use crate::embedding::pipeline::embed_facts;
+use crate::embedding::template::EmbeddingTemplate;
+use crate::metrics;
+use crate::outbox::JobKind;
+use crate::shutdown::Shutdown;
 use crate::store::postgres::PostgresMemoryStore;
+use crate::store::CONTRADICTS_RELATION;
+
+/// Retries after the first failed attempt before a job is marked as failed.
+const MAX_RETRIES: u8 = 3;
+
+/// Advisory lock held by the one instance running the startup backfill until its queue drains.
+pub const EXTRACTION_BACKFILL_LOCK: &str = "memcp:backfill:extraction";
+
+/// A memory whose extraction is running.
+struct InFlight {
+    /// Content the running job extracts from
+    content: String,
+    /// Job for newer content to run once this one finishes (the newest wins)
+    rerun: Option<ExtractionJob>,
+}
+
+/// Settings for `ExtractionPipeline::new`.
+#[derive(Clone)]
+pub struct ExtractionOptions {
+    /// Bounded channel capacity (recommended: 1000)
+    pub capacity: usize,
+    /// Maximum jobs processed at once (values below 1 are treated as 1)
+    pub concurrency: usize,
+    /// Embeds each extracted fact for fact-level search (None disables it)
+    pub fact_embedder: Option<Arc<dyn EmbeddingProvider + Send + Sync>>,
+    /// Embedding queue and template to re-embed memories with once extracted
+    /// (None = the embedding text doesn't depend on extraction)
+    pub reembed: Option<(mpsc::Sender<EmbeddingJob>, EmbeddingTemplate)>,
+    /// Similar memories to check each extracted memory's facts against
+    /// (None disables contradiction detection)
+    pub contradiction_candidates: Option<usize>,
+}
 
 /// Async extraction pipeline: enqueues jobs onto a bounded mpsc channel and
-/// processes them in a background tokio task.
+/// processes them in background tokio tasks. Clones share the same queue and dispatcher.
+#[derive(Clone)]
 pub struct ExtractionPipeline {
     sender: mpsc::Sender<ExtractionJob>,
+    /// Jobs currently being processed, by memory ID
+    in_flight: Arc<Mutex<HashMap<String, InFlight>>>,
+    shutdown: Shutdown,
 }
 
 impl ExtractionPipeline {
-    /// Create a new ExtractionPipeline and spawn the background worker.
+    /// Create a new ExtractionPipeline and spawn the background dispatcher.
     ///
     /// - `provider`: The extraction provider to call for each job.
     /// - `store`: The PostgresMemoryStore for storing results and updating status.
-    /// - `capacity`: Bounded channel capacity (recommended: 1000).
+    /// - `options`: Queue size, concurrency and the optional follow-up steps.
+    /// - `shutdown`: Stops dispatching new jobs; `Shutdown::drain` waits for running ones.
     pub fn new(
         provider: Arc<dyn ExtractionProvider>,
         store: Arc<PostgresMemoryStore>,
-        capacity: usize,
+        options: ExtractionOptions,
+        shutdown: Shutdown,
     ) -> Self {
+        let ExtractionOptions { capacity, concurrency, fact_embedder, reembed, contradiction_candidates } = options;
         let (tx, mut rx) = mpsc::channel::<ExtractionJob>(capacity);
-        let retry_tx = tx.clone();
-
-        tokio::spawn(async move {
-            while let Some(job) = rx.recv().await {
-                let content = job.content.clone();
-                match provider.extract(&content).await {
-                    Ok(result) => {
-                        if let Err(e) = store
-                            .update_extraction_results(
-                                &job.memory_id,
-                                &result.entities,
-                                &result.facts,
-                            )
-                            .await
-                        {
-                            tracing::error!(
-                                memory_id = %job.memory_id,
-                                error = %e,
-                                "Failed to store extraction results"
-                            );
-                            let _ = store.update_extraction_status(&job.memory_id, "failed").await;
+        let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+        let in_flight: Arc<Mutex<HashMap<String, InFlight>>> = Arc::new(Mutex::new(HashMap::new()));
+
+        let dispatcher_in_flight = Arc::clone(&in_flight);
+        let dispatcher_shutdown = shutdown.clone();
+        shutdown.spawn(async move {
+            let in_flight = dispatcher_in_flight;
+            let shutdown = dispatcher_shutdown;
+            loop {
+                let job = tokio::select! {
+                    biased;
+                    _ = shutdown.cancelled() => break,
+                    job = rx.recv() => match job {
+                        Some(job) => job,
+                        None => break,
+                    },
+                };
+                // Backfill and store_memory can both enqueue the same memory — run it once. A
+                // job for content changed since (update_memory) runs after the current one.
+                {
+                    let mut in_flight = in_flight.lock().unwrap();
+                    if let Some(running) = in_flight.get_mut(&job.memory_id) {
+                        if running.content == job.content {
+                            tracing::debug!(memory_id = %job.memory_id, "Extraction already in flight — skipping duplicate job");
+                            running.rerun = None;
                         } else {
-                            let _ = store.update_extraction_status(&job.memory_id, "complete").await;
-                            tracing::debug!(
-                                memory_id = %job.memory_id,
-                                entities = result.entities.len(),
-                                facts = result.facts.len(),
-                                "Extraction complete"
-                            );
+                            tracing::debug!(memory_id = %job.memory_id, "Extraction in flight — re-running with the updated content once it finishes");
+                            running.rerun = Some(job);
                         }
+                        continue;
                     }
-                    Err(e) if job.attempt < 3 => {
-                        tracing::warn!(
-                            memory_id = %job.memory_id,
-                            attempt = job.attempt + 1,
-                            error = %e,
-                            "Extraction failed, retrying"
-                        );
-                        // Exponential backoff: 1s, 2s, 4s
-                        let delay = Duration::from_secs(2u64.pow(job.attempt as u32));
-                        tokio::time::sleep(delay).await;
-                        let _ = retry_tx.try_send(ExtractionJob {
-                            attempt: job.attempt + 1,
-                            ..job
-                        });
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            memory_id = %job.memory_id,
-                            attempts = 3,
-                            error = %e,
-                            "Extraction failed after 3 retries, marking as failed"
-                        );
-                        let _ = store.update_extraction_status(&job.memory_id, "failed").await;
-                    }
+                    in_flight.insert(job.memory_id.clone(), InFlight { content: job.content.clone(), rerun: None });
                 }
+
+                // Waiting for a permit here keeps unstarted jobs in the bounded channel
+                let permit = tokio::select! {
+                    biased;
+                    _ = shutdown.cancelled() => break,
+                    permit = permits.clone().acquire_owned() => match permit {
+                        Ok(p) => p,
+                        Err(_) => break,
+                    },
+                };
+
+                let provider = Arc::clone(&provider);
+                let store = Arc::clone(&store);
+                let permits = Arc::clone(&permits);
+                let in_flight = Arc::clone(&in_flight);
+                let fact_embedder = fact_embedder.clone();
+                let reembed = reembed.clone();
+                let job_shutdown = shutdown.clone();
+                shutdown.spawn(async move {
+                    let memory_id = job.memory_id.clone();
+                    let (mut job, mut permit) = (job, permit);
+                    loop {
+                        if let Some(result) = process_job(provider.as_ref(), &store, fact_embedder.as_deref(), job, permit, &permits, &job_shutdown).await {
+                            if let Some((ref sender, ref template)) = reembed {
+                                reembed_extracted(&store, sender, template, &memory_id).await;
+                            }
+                            if let Some(limit) = contradiction_candidates {
+                                detect_contradictions(provider.as_ref(), &store, &memory_id, &result, limit).await;
+                            }
+                        }
+
+                        let rerun = {
+                            let mut in_flight = in_flight.lock().unwrap();
+                            let rerun = in_flight.get_mut(&memory_id).and_then(|running| {
+                                let rerun = running.rerun.take()?;
+                                running.content = rerun.content.clone();
+                                Some(rerun)
+                            });
+                            if rerun.is_none() {
+                                in_flight.remove(&memory_id);
+                            }
+                            rerun
+                        };
+                        let Some(rerun) = rerun else { break };
+                        // The results just stored are for older content — not complete until the re-run is
+                        let _ = store.update_extraction_status(&memory_id, "pending").await;
+                        let next_permit = tokio::select! {
+                            biased;
+                            _ = job_shutdown.cancelled() => None,
+                            permit = permits.clone().acquire_owned() => permit.ok(),
+                        };
+                        let Some(next_permit) = next_permit else {
+                            // Shutting down: the memory stays 'pending' for the next startup's backfill
+                            in_flight.lock().unwrap().remove(&memory_id);
+                            break;
+                        };
+                        (job, permit) = (rerun, next_permit);
+                    }
+                });
+            }
+            if !rx.is_empty() {
+                tracing::info!(queued = rx.len(), "Extraction dispatcher stopped — queued memories stay pending for backfill");
             }
         });
 
-        ExtractionPipeline { sender: tx }
+        ExtractionPipeline { sender: tx, in_flight, shutdown }
     }
 
     /// Enqueue an extraction job (non-blocking).
     ///
-    /// Uses try_send — if the channel is full, the job is dropped and a warning is logged.
-    /// The backfill process will pick up missed memories on next startup.
-    pub fn enqueue(&self, job: ExtractionJob) {
+    /// Uses try_send — if the channel is full, the job is dropped, a warning is logged, and
+    /// false is returned. The backfill process will pick up missed memories on next startup.
+    /// Jobs are refused the same way once shutdown has begun.
+    pub fn enqueue(&self, job: ExtractionJob) -> bool {
+        if self.shutdown.is_shutting_down() {
+            return false;
+        }
         if let Err(_) = self.sender.try_send(job) {
+            metrics::global().extraction_jobs_dropped.inc();
             tracing::warn!(
                 "Extraction queue full — memory stored, extraction deferred to backfill"
             );
+            return false;
         }
+        true
     }
 
     /// Return a clone of the underlying mpsc sender (for use with the backfill function).
     pub fn sender(&self) -> mpsc::Sender<ExtractionJob> {
         self.sender.clone()
     }
+
+    /// Number of jobs waiting in the channel (jobs already picked up by a worker are not counted).
+    pub fn queue_depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Wait until no job is queued or running. Polls every 100ms.
+    pub async fn flush(&self) {
+        while self.queue_depth() > 0 || !self.in_flight.lock().unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+}
+
+/// Queue a freshly extracted memory for re-embedding with the extraction-aware template.
+async fn reembed_extracted(
+    store: &PostgresMemoryStore,
+    sender: &mpsc::Sender<EmbeddingJob>,
+    template: &EmbeddingTemplate,
+    memory_id: &str,
+) {
+    let memory = match store.get_memories_by_ids(&[memory_id.to_string()]).await {
+        Ok(mut memories) => memories.remove(memory_id),
+        Err(e) => {
+            tracing::warn!(memory_id = %memory_id, error = %e, "Failed to fetch memory for re-embedding after extraction");
+            return;
+        }
+    };
+    let Some(memory) = memory.filter(|m| m.deleted_at.is_none()) else {
+        return;
+    };
+    let job = EmbeddingJob {
+        memory_id: memory.id.clone(),
+        text: template.render_memory(&memory),
+        attempt: 0,
+    };
+    if sender.try_send(job).is_err() {
+        tracing::warn!(memory_id = %memory_id, "Embedding queue full — extracted memory keeps its earlier embedding");
+    }
+}
+
+/// Check a freshly extracted memory's facts against similar memories — its nearest
+/// neighbours by embedding (once embedded) and memories sharing its entities — and link it
+/// to each one the provider finds contradicted. Failures are logged and leave no links.
+async fn detect_contradictions(
+    provider: &dyn ExtractionProvider,
+    store: &PostgresMemoryStore,
+    memory_id: &str,
+    result: &ExtractionResult,
+    limit: usize,
+) {
+    if result.facts.is_empty() || limit == 0 {
+        return;
+    }
+    // The embedding may still be pending — entity overlap alone still finds candidates
+    let related = store.get_related_memories(memory_id, limit as i64).await.unwrap_or_default();
+    let neighbours = match store.get_entity_neighbours(memory_id, &result.entities, limit as i64).await {
+        Ok(memories) => memories,
+        Err(e) => {
+            tracing::warn!(memory_id = %memory_id, error = %e, "Failed to fetch contradiction candidates");
+            Vec::new()
+        }
+    };
+    let mut candidates: Vec<(String, String)> = Vec::with_capacity(limit);
+    for memory in related.into_iter().map(|hit| hit.memory).chain(neighbours) {
+        if candidates.len() < limit && !candidates.iter().any(|(id, _)| *id == memory.id) {
+            candidates.push((memory.id, memory.content));
+        }
+    }
+    if candidates.is_empty() {
+        return;
+    }
+
+    let contradictions = match provider.detect_contradictions(&result.facts, &candidates).await {
+        Ok(found) => found,
+        Err(e) => {
+            tracing::warn!(memory_id = %memory_id, error = %e, "Contradiction check failed");
+            return;
+        }
+    };
+    for contradiction in contradictions {
+        match store.create_link(memory_id, &contradiction.memory_id, CONTRADICTS_RELATION).await {
+            Ok(_) => tracing::info!(
+                memory_id = %memory_id,
+                contradicts = %contradiction.memory_id,
+                reason = %contradiction.reason,
+                "Contradiction detected"
+            ),
+            Err(e) => tracing::warn!(
+                memory_id = %memory_id,
+                contradicts = %contradiction.memory_id,
+                error = %e,
+                "Failed to record contradiction"
+            ),
+        }
+    }
+}
+
+/// Run one job to completion, retrying with backoff until it succeeds or exhausts its retries.
+///
+/// The permit is released while sleeping between attempts so other jobs can use the slot.
+/// Shutdown during a backoff gives up and leaves the memory 'pending' for the next startup.
+/// Returns the extraction results once they are stored.
+async fn process_job(
+    provider: &dyn ExtractionProvider,
+    store: &PostgresMemoryStore,
+    fact_embedder: Option<&(dyn EmbeddingProvider + Send + Sync)>,
+    mut job: ExtractionJob,
+    mut permit: tokio::sync::OwnedSemaphorePermit,
+    permits: &Arc<Semaphore>,
+    shutdown: &Shutdown,
+) -> Option<ExtractionResult> {
+    loop {
+        match provider.extract(&job.content).await {
+            Ok(result) => {
+                return if let Err(e) = store
+                    .update_extraction_results(
+                        &job.memory_id,
+                        &result.entities,
+                        &result.facts,
+                        result.type_hint.as_deref().filter(|_| job.classify),
+                    )
+                    .await
+                {
+                    tracing::error!(
+                        memory_id = %job.memory_id,
+                        error = %e,
+                        "Failed to store extraction results"
+                    );
+                    let _ = store.update_extraction_status(&job.memory_id, "failed").await;
+                    let _ = store.complete_pipeline_job(&job.memory_id, JobKind::Extraction).await;
+                    metrics::global().extraction_failures.inc();
+                    None
+                } else {
+                    let _ = store.update_extraction_status(&job.memory_id, "complete").await;
+                    let _ = store.complete_pipeline_job(&job.memory_id, JobKind::Extraction).await;
+                    tracing::debug!(
+                        memory_id = %job.memory_id,
+                        entities = result.entities.len(),
+                        facts = result.facts.len(),
+                        type_hint = ?result.type_hint.as_deref().filter(|_| job.classify),
+                        "Extraction complete"
+                    );
+                    // Fact embeddings are an index over the facts — a failure leaves extraction complete
+                    if let Some(embedder) = fact_embedder {
+                        if let Err(e) = embed_facts(store, embedder, &job.memory_id, &result.facts).await {
+                            tracing::warn!(
+                                memory_id = %job.memory_id,
+                                error = %e,
+                                "Failed to embed extracted facts"
+                            );
+                        }
+                    }
+                    Some(result)
+                };
+            }
+            Err(e) if job.attempt < MAX_RETRIES => {
+                tracing::warn!(
+                    memory_id = %job.memory_id,
+                    attempt = job.attempt + 1,
+                    error = %e,
+                    "Extraction failed, retrying"
+                );
+                // Exponential backoff: 1s, 2s, 4s
+                let delay = Duration::from_secs(2u64.pow(job.attempt as u32));
+                drop(permit);
+                tokio::select! {
+                    _ = shutdown.cancelled() => return None,
+                    _ = tokio::time::sleep(delay) => {}
+                }
+                permit = match permits.clone().acquire_owned().await {
+                    Ok(p) => p,
+                    Err(_) => return None,
+                };
+                job.attempt += 1;
+            }
+            Err(e) => {
+                tracing::error!(
+                    memory_id = %job.memory_id,
+                    attempts = MAX_RETRIES,
+                    error = %e,
+                    "Extraction failed after 3 retries, marking as failed"
+                );
+                let _ = store.update_extraction_status(&job.memory_id, "failed").await;
+                let _ = store.complete_pipeline_job(&job.memory_id, JobKind::Extraction).await;
+                metrics::global().extraction_failures.inc();
+                return None;
+            }
+        }
+    }
 }
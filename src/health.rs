@@ -0,0 +1,165 @@
+//! Subsystem health probes for the health_check tool.
+//!
+//! The server only learns that an LLM endpoint is unreachable when a background job fails.
+//! These helpers describe the configured endpoints and probe them on demand, so health_check
+//! can report each subsystem as ok, degraded, or down.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// Timeout for a single health probe (database ping or LLM endpoint request).
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// An LLM endpoint a subsystem depends on.
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmEndpoint {
+    /// Subsystem that uses the endpoint: "extraction", "consolidation", "query_expansion", "reranking"
+    pub subsystem: String,
+    /// "ollama" or "openai"
+    pub provider: String,
+    /// Base URL of the provider API
+    pub base_url: String,
+    /// Model the subsystem requests from the endpoint
+    pub model: String,
+    /// Bearer token sent with OpenAI-compatible probes (never serialized)
+    #[serde(skip)]
+    pub api_key: Option<String>,
+}
+
+impl LlmEndpoint {
+    fn new(subsystem: &str, provider: &str, base_url: &str, model: &str, api_key: Option<&String>) -> Self {
+        let provider = if provider == "openai" { "openai" } else { "ollama" };
+        LlmEndpoint {
+            subsystem: subsystem.to_string(),
+            provider: provider.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: api_key.cloned(),
+        }
+    }
+
+    /// Cheap read-only URL that proves the API is reachable: Ollama's model list or /models.
+    fn probe_url(&self) -> String {
+        match self.provider.as_str() {
+            "openai" => format!("{}/models", self.base_url),
+            _ => format!("{}/api/tags", self.base_url),
+        }
+    }
+}
+
+/// List the LLM endpoints used by enabled subsystems.
+///
+/// Mirrors the provider selection in main.rs — the local cross-encoder reranker has no endpoint.
+pub fn llm_endpoints(config: &Config) -> Vec<LlmEndpoint> {
+    let mut endpoints = Vec::new();
+
+    let ex = &config.extraction;
+    if ex.enabled {
+        let (base_url, model) = if ex.provider == "openai" {
+            ("https://api.openai.com/v1", &ex.openai_model)
+        } else {
+            (ex.ollama_base_url.as_str(), &ex.ollama_model)
+        };
+        endpoints.push(LlmEndpoint::new("extraction", &ex.provider, base_url, model, ex.openai_api_key.as_ref()));
+    }
+
+    let co = &config.consolidation;
+    if co.enabled {
+        let (base_url, model) = if co.provider == "openai" {
+            (co.openai_base_url.as_str(), co.openai_model.as_str())
+        } else {
+            (
+                co.ollama_base_url.as_deref().unwrap_or(&ex.ollama_base_url),
+                co.ollama_model.as_deref().unwrap_or(&ex.ollama_model),
+            )
+        };
+        endpoints.push(LlmEndpoint::new("consolidation", &co.provider, base_url, model, co.openai_api_key.as_ref()));
+    }
+
+    let qi = &config.query_intelligence;
+    let qi_base_url = |provider: &str| {
+        if provider == "openai" { qi.openai_base_url.clone() } else { qi.ollama_base_url.clone() }
+    };
+    if qi.expansion_enabled {
+        let model = if qi.expansion_provider == "openai" { &qi.expansion_openai_model } else { &qi.expansion_ollama_model };
+        endpoints.push(LlmEndpoint::new(
+            "query_expansion",
+            &qi.expansion_provider,
+            &qi_base_url(&qi.expansion_provider),
+            model,
+            qi.openai_api_key.as_ref(),
+        ));
+    }
+    if qi.reranking_enabled && qi.reranking_provider != "local" {
+        let model = if qi.reranking_provider == "openai" { &qi.reranking_openai_model } else { &qi.reranking_ollama_model };
+        endpoints.push(LlmEndpoint::new(
+            "reranking",
+            &qi.reranking_provider,
+            &qi_base_url(&qi.reranking_provider),
+            model,
+            qi.openai_api_key.as_ref(),
+        ));
+    }
+
+    endpoints
+}
+
+/// Probe an endpoint, returning the round-trip latency in ms or a description of the failure.
+pub async fn probe_endpoint(client: &reqwest::Client, endpoint: &LlmEndpoint) -> Result<u64, String> {
+    let start = Instant::now();
+    let mut request = client.get(endpoint.probe_url()).timeout(PROBE_TIMEOUT);
+    if let Some(key) = &endpoint.api_key {
+        request = request.bearer_auth(key);
+    }
+    let response = request.send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    Ok(start.elapsed().as_millis() as u64)
+}
+
+/// List the models an Ollama server has pulled (names as reported by /api/tags, e.g. "llama3.2:3b").
+pub async fn ollama_models(client: &reqwest::Client, base_url: &str) -> Result<Vec<String>, String> {
+    #[derive(serde::Deserialize)]
+    struct Tags {
+        models: Vec<TagModel>,
+    }
+    #[derive(serde::Deserialize)]
+    struct TagModel {
+        name: String,
+    }
+
+    let response = client
+        .get(format!("{}/api/tags", base_url.trim_end_matches('/')))
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    let tags: Tags = response.json().await.map_err(|e| e.to_string())?;
+    Ok(tags.models.into_iter().map(|m| m.name).collect())
+}
+
+/// Whether `model` is among `pulled` — an untagged name matches its ":latest" tag.
+pub fn ollama_model_pulled(pulled: &[String], model: &str) -> bool {
+    pulled.iter().any(|name| name == model || (!model.contains(':') && *name == format!("{}:latest", model)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ollama_model_pulled_matches_latest_tag() {
+        let pulled = vec!["llama3.2:3b".to_string(), "nomic-embed-text:latest".to_string()];
+        assert!(ollama_model_pulled(&pulled, "llama3.2:3b"));
+        assert!(ollama_model_pulled(&pulled, "nomic-embed-text"));
+        assert!(!ollama_model_pulled(&pulled, "llama3.2"));
+        assert!(!ollama_model_pulled(&pulled, "llama3.2:1b"));
+    }
+}
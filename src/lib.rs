@@ -1,11 +1,29 @@
+pub mod admin;
+pub mod audit;
 pub mod benchmark;
+pub mod chunking;
 pub mod config;
 pub mod consolidation;
+pub mod decay;
 pub mod embedding;
+pub mod encryption;
 pub mod errors;
+pub mod events;
+pub mod expiry;
 pub mod extraction;
+pub mod health;
+pub mod live_config;
+pub mod llm_client;
 pub mod logging;
+pub mod memory_pack;
+pub mod memory_types;
+pub mod metrics;
+pub mod outbox;
+pub mod privacy;
 pub mod query_intelligence;
+pub mod retention;
 pub mod search;
+pub mod search_log;
 pub mod server;
+pub mod shutdown;
 pub mod store;
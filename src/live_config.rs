@@ -0,0 +1,211 @@
+//! Hot-reloadable configuration.
+//!
+//! Salience weights, query intelligence tuning, consolidation thresholds, and the log level
+//! can change without restarting the server (and dropping the stdio session). LiveConfig
+//! holds the running Config in an ArcSwap: readers take a snapshot per call, and `reload`
+//! swaps in the new reloadable sections atomically — on SIGHUP or via the reload_config tool.
+//!
+//! Everything else (database, embedding, pipelines, provider selection) is structural: a
+//! changed value is reported as needing a restart and the running value is kept. Within the
+//! reloadable sections, settings that only take effect at startup (e.g. which QI or synthesis
+//! provider is built) likewise wait for the next restart.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use serde::Serialize;
+
+use crate::config::{Config, ConfigIssue};
+use crate::errors::MemcpError;
+
+/// Top-level Config keys applied by `reload`.
+pub const RELOADABLE_SECTIONS: &[&str] = &["salience", "query_intelligence", "consolidation", "log_level"];
+
+/// What a reload changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReloadReport {
+    /// Reloadable sections whose values changed and are now live
+    pub applied: Vec<String>,
+    /// Changed sections that keep their running values until restart
+    pub restart_required: Vec<String>,
+}
+
+/// Adjustments re-applied to every config read from disk (e.g. CLI flags)
+type Overrides = Arc<dyn Fn(&mut Config) + Send + Sync>;
+
+/// Shared handle to the running Config. Cheap to clone; clones see the same swaps.
+#[derive(Clone)]
+pub struct LiveConfig {
+    current: Arc<ArcSwap<Config>>,
+    /// Bumped by every reload that applied a change
+    generation: Arc<AtomicU64>,
+    overrides: Option<Overrides>,
+}
+
+impl LiveConfig {
+    pub fn new(config: Config) -> Self {
+        LiveConfig {
+            current: Arc::new(ArcSwap::from_pointee(config)),
+            generation: Arc::new(AtomicU64::new(0)),
+            overrides: None,
+        }
+    }
+
+    /// Apply `overrides` to every config `reload_from_disk` reads, so values set on the
+    /// command line are not reported as changed.
+    pub fn with_overrides(mut self, overrides: impl Fn(&mut Config) + Send + Sync + 'static) -> Self {
+        self.overrides = Some(Arc::new(overrides));
+        self
+    }
+
+    /// Number of reloads that changed a live setting. Caches keyed on it drop stale entries.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Snapshot of the running config. Hold it for the duration of one operation so every
+    /// read sees the same values.
+    pub fn load(&self) -> Arc<Config> {
+        self.current.load_full()
+    }
+
+    /// Apply the reloadable sections of `next` and report what changed.
+    ///
+    /// A config `Config::validate` finds any problem with is refused as a whole, before
+    /// anything is swapped. Also sets the log level when it changed (unless RUST_LOG
+    /// overrides it). Concurrent reloads are last-writer-wins; SIGHUP and reload_config
+    /// both re-read the same file.
+    pub fn reload(&self, next: &Config) -> Result<ReloadReport, MemcpError> {
+        let issues = next.validate();
+        if !issues.is_empty() {
+            return Err(rejected(&issues));
+        }
+        let current = self.load();
+        let report = diff_sections(&current, next)?;
+        // An invalid decay curve or log level rejects the whole reload before anything is swapped
+        crate::search::decay::decay_model(&next.salience)?;
+        if current.log_level != next.log_level {
+            crate::logging::set_log_level(&next.log_level)?;
+        }
+        self.current.store(Arc::new(Config {
+            salience: next.salience.clone(),
+            query_intelligence: next.query_intelligence.clone(),
+            consolidation: next.consolidation.clone(),
+            log_level: next.log_level.clone(),
+            ..(*current).clone()
+        }));
+        if !report.applied.is_empty() {
+            self.generation.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(report)
+    }
+
+    /// Re-read memcp.toml and the environment (as at startup) and apply the result. Unknown
+    /// keys and values that fail to load refuse the reload like invalid ones do.
+    pub fn reload_from_disk(&self) -> Result<ReloadReport, MemcpError> {
+        let (loaded, issues) = Config::load_checked();
+        let mut next = match loaded {
+            Some(config) if issues.is_empty() => config,
+            _ => return Err(rejected(&issues)),
+        };
+        if let Some(ref overrides) = self.overrides {
+            overrides(&mut next);
+        }
+        self.reload(&next)
+    }
+}
+
+/// Error for a reload refused because of `issues`; the running config is kept.
+fn rejected(issues: &[ConfigIssue]) -> MemcpError {
+    let problems: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+    MemcpError::Config(format!(
+        "Config not reloaded ({} problem(s); running config kept): {}",
+        issues.len(),
+        problems.join("; ")
+    ))
+}
+
+/// Compare two configs section by section (top-level keys).
+fn diff_sections(current: &Config, next: &Config) -> Result<ReloadReport, MemcpError> {
+    let to_map = |config: &Config| match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(map)) => Ok(map),
+        Ok(_) => Err(MemcpError::Internal("Config did not serialize to an object".to_string())),
+        Err(e) => Err(MemcpError::Internal(format!("Failed to serialize config: {}", e))),
+    };
+    let (current, next) = (to_map(current)?, to_map(next)?);
+
+    let mut report = ReloadReport::default();
+    for (key, value) in &next {
+        if current.get(key) == Some(value) {
+            continue;
+        }
+        if RELOADABLE_SECTIONS.contains(&key.as_str()) {
+            report.applied.push(key.clone());
+        } else {
+            report.restart_required.push(key.clone());
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_applies_live_sections_and_keeps_structural_ones() {
+        let live = LiveConfig::new(Config::default());
+        let before = live.load();
+
+        let mut next = Config::default();
+        next.salience.w_importance = 0.4;
+        next.salience.w_semantic = 0.05;
+        next.consolidation.similarity_threshold = 0.8;
+        next.database_url = "postgres://elsewhere/memcp".to_string();
+
+        let report = live.reload(&next).unwrap();
+        assert_eq!(report.applied, vec!["consolidation", "salience"]);
+        assert_eq!(report.restart_required, vec!["database_url"]);
+
+        let after = live.load();
+        assert_eq!(after.salience.w_importance, 0.4);
+        assert_eq!(after.consolidation.similarity_threshold, 0.8);
+        assert_eq!(after.database_url, before.database_url, "structural values wait for restart");
+        assert_eq!(before.salience.w_importance, 0.0, "earlier snapshots are unchanged");
+        assert_eq!(live.generation(), 1);
+    }
+
+    #[test]
+    fn invalid_decay_model_rejects_the_reload() {
+        let live = LiveConfig::new(Config::default());
+        let mut next = Config::default();
+        next.salience.decay_model = "linear".to_string();
+        next.salience.w_importance = 0.4;
+        next.salience.w_semantic = 0.05;
+        assert!(live.reload(&next).is_err());
+        assert_eq!(live.load().salience.w_importance, 0.0);
+        assert_eq!(live.generation(), 0);
+    }
+
+    #[test]
+    fn invalid_config_rejects_the_reload() {
+        let live = LiveConfig::new(Config::default());
+        let mut next = Config::default();
+        next.salience.w_importance = 0.4;
+        next.salience.w_semantic = 0.05;
+        next.query_intelligence.rerank_blend = 1.5;
+        let err = live.reload(&next).unwrap_err().to_string();
+        assert!(err.contains("query_intelligence.rerank_blend"), "{}", err);
+        assert_eq!(live.load().salience.w_importance, 0.0, "nothing is swapped in");
+        assert_eq!(live.generation(), 0);
+    }
+
+    #[test]
+    fn unchanged_config_reports_nothing() {
+        let live = LiveConfig::new(Config::default());
+        let report = live.reload(&Config::default()).unwrap();
+        assert!(report.applied.is_empty() && report.restart_required.is_empty());
+        assert_eq!(live.generation(), 0);
+    }
+}
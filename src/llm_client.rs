@@ -0,0 +1,288 @@
+//! Shared HTTP client for LLM providers (extraction, query intelligence, consolidation).
+//!
+//! Every provider call goes through `LlmClient::post_json`, which applies a per-attempt
+//! timeout, retries transient failures (transport errors, timeouts, 429 and 5xx) with
+//! exponential backoff, and feeds a circuit breaker. After `breaker_failure_threshold`
+//! consecutive failed calls the breaker opens and calls fail immediately with
+//! `LlmError::CircuitOpen` for `breaker_cooldown_secs`, so search stops waiting on a dead
+//! endpoint. The first call after the cooldown is let through as a trial: success closes
+//! the breaker, failure re-opens it.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::config::LlmConfig;
+
+/// Errors returned by `LlmClient`. Providers map these into their own error types.
+#[derive(Debug, Error)]
+pub enum LlmError {
+    /// The breaker is open; the endpoint was not contacted
+    #[error("Circuit breaker open after repeated failures (retry in {retry_in_secs}s)")]
+    CircuitOpen { retry_in_secs: u64 },
+
+    /// Connection or transport failure
+    #[error("HTTP request failed: {0}")]
+    Transport(String),
+
+    /// The attempt exceeded the configured timeout
+    #[error("Request timed out after {0}s")]
+    Timeout(u64),
+
+    /// The endpoint returned a non-success status
+    #[error("API error (status {status}): {message}")]
+    Api { status: u16, message: String },
+
+    /// The response body was not the expected JSON
+    #[error("Failed to parse response: {0}")]
+    Decode(String),
+}
+
+impl LlmError {
+    /// Whether another attempt could succeed: transport errors, timeouts, 429 and 5xx.
+    fn is_transient(&self) -> bool {
+        match self {
+            LlmError::Transport(_) | LlmError::Timeout(_) => true,
+            LlmError::Api { status, .. } => *status == 429 || *status >= 500,
+            LlmError::CircuitOpen { .. } | LlmError::Decode(_) => false,
+        }
+    }
+}
+
+/// Retrying, circuit-breaking JSON POST client. Cheap to clone; clones share one breaker.
+#[derive(Clone)]
+pub struct LlmClient {
+    http: reqwest::Client,
+    config: LlmConfig,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl LlmClient {
+    pub fn new(config: &LlmConfig) -> Self {
+        LlmClient {
+            http: reqwest::Client::new(),
+            config: config.clone(),
+            breaker: Arc::new(CircuitBreaker::new(
+                config.breaker_failure_threshold,
+                Duration::from_secs(config.breaker_cooldown_secs),
+            )),
+        }
+    }
+
+    /// POST `body` as JSON to `url` (with an optional bearer token) and decode the JSON response.
+    pub async fn post_json<B, T>(&self, url: &str, bearer: Option<&str>, body: &B) -> Result<T, LlmError>
+    where
+        B: Serialize + ?Sized,
+        T: DeserializeOwned,
+    {
+        self.breaker.check()?;
+
+        let mut attempt = 0u32;
+        loop {
+            match self.send_once(url, bearer, body).await {
+                Ok(response) => {
+                    self.breaker.record_success();
+                    return response.json::<T>().await.map_err(|e| LlmError::Decode(e.to_string()));
+                }
+                Err(e) if e.is_transient() && attempt < self.config.max_retries => {
+                    let delay = self.delay_for(attempt);
+                    tracing::warn!(
+                        url = url,
+                        attempt = attempt + 1,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %e,
+                        "LLM request failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    // A 4xx means the endpoint is up and answering — only transient
+                    // failures count towards tripping the breaker.
+                    if e.is_transient() {
+                        self.breaker.record_failure(url);
+                    } else {
+                        self.breaker.record_success();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    async fn send_once<B>(&self, url: &str, bearer: Option<&str>, body: &B) -> Result<reqwest::Response, LlmError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let mut request = self.http.post(url).json(body);
+        if let Some(token) = bearer {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        if self.config.timeout_secs > 0 {
+            request = request.timeout(Duration::from_secs(self.config.timeout_secs));
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                LlmError::Timeout(self.config.timeout_secs)
+            } else {
+                LlmError::Transport(e.to_string())
+            }
+        })?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let message = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "unknown error".to_string());
+            return Err(LlmError::Api { status, message });
+        }
+
+        Ok(response)
+    }
+
+    /// Delay before retrying after the given (0-based) failed attempt.
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let base = self.config.retry_base_delay_ms;
+        let delay = base.saturating_mul(1u64 << attempt.min(20));
+        Duration::from_millis(delay.min(self.config.retry_max_delay_ms))
+    }
+}
+
+#[derive(Debug)]
+enum BreakerState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// One trial call is in flight. A trial that never reports back (e.g. its future was
+    /// dropped by a search timeout) is superseded once another cooldown has passed.
+    HalfOpen { since: Instant },
+}
+
+struct CircuitBreaker {
+    /// Consecutive failed calls that open the breaker (0 = never open)
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    /// Admit a call, or reject it while the breaker is open.
+    fn check(&self) -> Result<(), LlmError> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        match *state {
+            BreakerState::Closed { .. } => Ok(()),
+            BreakerState::Open { until } if now < until => Err(LlmError::CircuitOpen {
+                retry_in_secs: (until - now).as_secs().max(1),
+            }),
+            BreakerState::HalfOpen { since } if now < since + self.cooldown => Err(LlmError::CircuitOpen {
+                retry_in_secs: (since + self.cooldown - now).as_secs().max(1),
+            }),
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => {
+                *state = BreakerState::HalfOpen { since: now };
+                Ok(())
+            }
+        }
+    }
+
+    fn record_success(&self) {
+        *self.state.lock().unwrap() = BreakerState::Closed { failures: 0 };
+    }
+
+    fn record_failure(&self, url: &str) {
+        if self.threshold == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        let failures = match *state {
+            BreakerState::Closed { failures } => failures + 1,
+            // A failed trial re-opens immediately
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => self.threshold,
+        };
+        if failures >= self.threshold {
+            tracing::warn!(
+                url = url,
+                failures = failures,
+                cooldown_secs = self.cooldown.as_secs(),
+                "LLM circuit breaker opened"
+            );
+            *state = BreakerState::Open { until: Instant::now() + self.cooldown };
+        } else {
+            *state = BreakerState::Closed { failures };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_retries: u32, threshold: u32) -> LlmConfig {
+        LlmConfig {
+            max_retries,
+            retry_base_delay_ms: 1,
+            retry_max_delay_ms: 4,
+            timeout_secs: 5,
+            breaker_failure_threshold: threshold,
+            breaker_cooldown_secs: 60,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let client = LlmClient::new(&config(3, 0));
+        let delays: Vec<u128> = (0..4).map(|a| client.delay_for(a).as_millis()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 4]);
+    }
+
+    #[test]
+    fn breaker_opens_after_threshold_and_admits_a_trial_after_cooldown() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure("u");
+        assert!(breaker.check().is_ok(), "one failure stays closed");
+        breaker.record_failure("u");
+        assert!(matches!(breaker.check(), Err(LlmError::CircuitOpen { .. })));
+
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.record_failure("u");
+        assert!(breaker.check().is_ok(), "cooldown elapsed: trial admitted");
+        breaker.record_success();
+        assert!(matches!(*breaker.state.lock().unwrap(), BreakerState::Closed { failures: 0 }));
+    }
+
+    #[test]
+    fn success_resets_the_failure_count_and_zero_threshold_never_opens() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure("u");
+        breaker.record_success();
+        breaker.record_failure("u");
+        assert!(breaker.check().is_ok());
+
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        (0..10).for_each(|_| breaker.record_failure("u"));
+        assert!(breaker.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn unreachable_endpoint_retries_then_trips_the_breaker() {
+        // Port 1 on loopback refuses connections immediately.
+        let client = LlmClient::new(&config(1, 1));
+        let body = serde_json::json!({});
+        let first = client.post_json::<_, serde_json::Value>("http://127.0.0.1:1/api/chat", None, &body).await;
+        assert!(matches!(first, Err(LlmError::Transport(_))), "{:?}", first);
+        let second = client.post_json::<_, serde_json::Value>("http://127.0.0.1:1/api/chat", None, &body).await;
+        assert!(matches!(second, Err(LlmError::CircuitOpen { .. })), "{:?}", second);
+    }
+}
@@ -5,12 +5,19 @@
 /// structured JSON when piped/redirected.
 
 use std::io::IsTerminal;
+use std::sync::OnceLock;
 use tracing_subscriber::{
     layer::SubscriberExt,
+    reload,
     util::SubscriberInitExt,
     EnvFilter,
+    Registry,
 };
 use crate::config::Config;
+use crate::errors::MemcpError;
+
+/// Handle for swapping the level filter at runtime (None until init_logging runs)
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
 
 /// Initialize tracing subscriber with stderr-only output
 ///
@@ -24,6 +31,8 @@ pub fn init_logging(config: &Config) {
     // Build env filter from config, with RUST_LOG override
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(&config.log_level));
+    let (env_filter, handle) = reload::Layer::new(env_filter);
+    let _ = FILTER_HANDLE.set(handle);
 
     // Auto-detect format based on stderr terminal status
     let stderr_is_terminal = std::io::stderr().is_terminal();
@@ -59,3 +68,24 @@ pub fn init_logging(config: &Config) {
         );
     }
 }
+
+/// Change the log level of the running subscriber (config reload).
+///
+/// A no-op when RUST_LOG is set (it overrides config.log_level) or logging was never
+/// initialized.
+pub fn set_log_level(level: &str) -> Result<(), MemcpError> {
+    if std::env::var_os("RUST_LOG").is_some() {
+        tracing::info!(level, "RUST_LOG is set — ignoring reloaded log_level");
+        return Ok(());
+    }
+    let Some(handle) = FILTER_HANDLE.get() else {
+        return Ok(());
+    };
+    let filter = EnvFilter::try_new(level)
+        .map_err(|e| MemcpError::Config(format!("Invalid log_level '{}': {}", level, e)))?;
+    handle
+        .reload(filter)
+        .map_err(|e| MemcpError::Internal(format!("Failed to apply log level: {}", e)))?;
+    tracing::info!(level, "Log level changed");
+    Ok(())
+}
@@ -1,24 +1,48 @@
 use anyhow::Result;
+use memcp::admin::{AdminClient, AdminListener};
 use clap::{Parser, Subcommand};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use memcp::config::Config;
-use memcp::consolidation::ConsolidationWorker;
+use memcp::config::{Config, NamedEmbeddingProvider};
+use memcp::live_config::LiveConfig;
+use memcp::consolidation::{ConsolidationWorker, scan_existing};
+use memcp::consolidation::ollama::OllamaSynthesisProvider;
+use memcp::consolidation::openai::OpenAISynthesisProvider;
+use memcp::consolidation::SynthesisProvider;
 use memcp::embedding::EmbeddingProvider;
+use memcp::encryption::ContentCipher;
+use memcp::events::EventNotifier;
 use memcp::embedding::local::LocalEmbeddingProvider;
 use memcp::embedding::openai::OpenAIEmbeddingProvider;
-use memcp::embedding::pipeline::{EmbeddingPipeline, backfill};
+use memcp::embedding::pipeline::{
+    BackfillOptions, EmbeddingPipeline, RetryPolicy, EMBEDDING_BACKFILL_LOCK, backfill, backfill_facts, backfill_model,
+    run_backfill,
+};
+use memcp::embedding::router::{EmbeddingRouter, validate_routes};
+use memcp::embedding::template::EmbeddingTemplate;
+use memcp::decay::spawn_decay_archiver;
+use memcp::expiry::spawn_expiry_sweeper;
+use memcp::retention::spawn_retention_sweeper;
 use memcp::extraction::ExtractionJob;
 use memcp::extraction::ExtractionProvider;
 use memcp::extraction::ollama::OllamaExtractionProvider;
 use memcp::extraction::openai::OpenAIExtractionProvider;
-use memcp::extraction::pipeline::ExtractionPipeline;
+use memcp::extraction::pipeline::{ExtractionOptions, ExtractionPipeline, EXTRACTION_BACKFILL_LOCK};
+use memcp::llm_client::LlmClient;
 use memcp::logging;
+use memcp::outbox::{JobKind, OutboxRelay};
+use memcp::privacy::llm::{LlmContentFilter, LlmEndpoint};
+use memcp::privacy::patterns::PatternFilter;
+use memcp::privacy::{ContentFilter, ContentScrubber};
 use memcp::query_intelligence::QueryIntelligenceProvider;
+use memcp::query_intelligence::health::{spawn_health_probes, MonitoredProvider};
+use memcp::query_intelligence::local::LocalRerankingProvider;
 use memcp::query_intelligence::ollama::OllamaQueryIntelligenceProvider;
 use memcp::query_intelligence::openai::OpenAIQueryIntelligenceProvider;
 use memcp::server::MemoryService;
-use memcp::store::postgres::PostgresMemoryStore;
+use memcp::shutdown::Shutdown;
+use memcp::store::postgres::{MigrationState, PostgresMemoryStore, MIGRATOR};
 use rmcp::ServiceExt;
 
 #[derive(Parser)]
@@ -30,25 +54,205 @@ struct Cli {
     /// Skip automatic database migration on startup
     #[arg(long)]
     skip_migrate: bool,
+
+    /// Default namespace for tool calls that omit one (overrides default_namespace in config)
+    #[arg(long, global = true)]
+    namespace: Option<String>,
+
+    /// Serve search/list/get only — tools that modify memories return an error (overrides server.read_only)
+    #[arg(long)]
+    read_only: bool,
+
+    /// Refuse to start when the configuration has problems (unknown keys, invalid values)
+    /// instead of warning and carrying on
+    #[arg(long, global = true)]
+    strict_config: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Run database migrations and exit
-    Migrate,
+    Migrate {
+        /// List bundled and applied migrations with their state and checksum, then exit
+        #[arg(long, conflicts_with_all = ["dry_run", "to"])]
+        status: bool,
+        /// Print the SQL of the migrations that would be applied, without applying them
+        #[arg(long)]
+        dry_run: bool,
+        /// Apply pending migrations up to and including this version only
+        #[arg(long)]
+        to: Option<i64>,
+    },
     /// Embedding management operations
     Embed {
         #[command(subcommand)]
         action: EmbedAction,
     },
+    /// Check the database, models, and LLM providers, and print fixes for any problems
+    Doctor,
+    /// Configuration file and environment checks
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Encrypt memory content stored before security.encryption_key was set
+    EncryptExisting {
+        /// Rows encrypted per batch
+        #[arg(long, default_value_t = 500)]
+        batch_size: i64,
+    },
+    /// Memory consolidation operations
+    Consolidate {
+        #[command(subcommand)]
+        action: ConsolidateAction,
+    },
+    /// pgvector HNSW index maintenance
+    Index {
+        #[command(subcommand)]
+        action: IndexAction,
+    },
+    /// Print the tool-call audit log as JSON, newest first (recorded when [audit] enabled = true)
+    Audit {
+        /// Only calls to this tool
+        #[arg(long)]
+        tool: Option<String>,
+        /// Only calls with this outcome ("ok" or "error")
+        #[arg(long)]
+        outcome: Option<String>,
+        /// Only calls that read or changed this memory
+        #[arg(long)]
+        memory_id: Option<String>,
+        /// Only calls at or after this RFC 3339 timestamp
+        #[arg(long)]
+        since: Option<String>,
+        /// Only calls before this RFC 3339 timestamp
+        #[arg(long)]
+        until: Option<String>,
+        /// Maximum entries to print
+        #[arg(long, default_value_t = 100)]
+        limit: i64,
+    },
+    /// Operational commands sent to a running server's admin listener ([admin] listen_addr)
+    Admin {
+        #[command(subcommand)]
+        action: AdminAction,
+    },
+    /// Print memory counts, content and salience distributions, and table sizes as JSON
+    /// (restricted to --namespace when given)
+    Stats,
+    /// Export the search quality log as JSON lines, oldest first (recorded when [search_log] enabled = true)
+    SearchLog {
+        /// Only searches in this namespace
+        #[arg(long)]
+        namespace: Option<String>,
+        /// Only searches at or after this RFC 3339 timestamp
+        #[arg(long)]
+        since: Option<String>,
+        /// Only searches before this RFC 3339 timestamp
+        #[arg(long)]
+        until: Option<String>,
+        /// Maximum entries to print
+        #[arg(long, default_value_t = 1000)]
+        limit: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Check memcp.toml and MEMCP_ environment variables for unknown keys and invalid values,
+    /// exiting non-zero when any are found
+    Validate,
+}
+
+#[derive(Subcommand)]
+enum AdminAction {
+    /// Show uptime, backend, and pipeline queue depths
+    Status,
+    /// Clear the search response cache
+    ClearCache,
+    /// Run a consolidation scan now (in --namespace, or the server's default namespace)
+    Consolidate {
+        /// Maximum memories to check for similar neighbours (1-5000)
+        #[arg(long)]
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum IndexAction {
+    /// Show index size, build parameters, and an estimated recall
+    Stats {
+        /// Stored embeddings sampled as queries for the recall estimate (0 to skip)
+        #[arg(long, default_value_t = 20)]
+        sample: i64,
+        /// Neighbours compared per sampled query
+        #[arg(long, default_value_t = 10)]
+        k: i64,
+        /// hnsw.ef_search used for the estimate (default: the database setting)
+        #[arg(long)]
+        ef_search: Option<u32>,
+    },
+    /// Rebuild the index with its current parameters (e.g. after a large backfill)
+    Rebuild,
+    /// Rebuild the index with new HNSW parameters; omitted ones keep their current value
+    Tune {
+        /// Links per node (2-100; higher = better recall, more memory)
+        #[arg(long)]
+        m: Option<u32>,
+        /// Candidate list size while building (4-1000, at least 2 * m; higher = better recall, slower build)
+        #[arg(long)]
+        ef_construction: Option<u32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConsolidateAction {
+    /// Find clusters of similar existing memories and merge them (restricted to --namespace when given)
+    Scan {
+        /// Maximum number of memories to check
+        #[arg(long)]
+        limit: Option<u64>,
+        /// Memories fetched and checked per batch
+        #[arg(long, default_value_t = 100)]
+        batch_size: i64,
+    },
 }
 
 #[derive(Subcommand)]
 enum EmbedAction {
-    /// Queue all un-embedded or failed memories for re-embedding
-    Backfill,
-    /// Show embedding statistics (counts by model, pending, failed)
+    /// Embed all un-embedded or failed memories, reporting progress until done. Safe to
+    /// interrupt: run it again to continue with what is left
+    Backfill {
+        /// Only embed memories missing an embedding under this model (e.g. "text-embedding-3-small").
+        /// Other models' embeddings are kept, so two models can be maintained side by side.
+        #[arg(long)]
+        model: Option<String>,
+        /// Memories embedded per provider request (default: embedding.batch_size)
+        #[arg(long)]
+        batch_size: Option<usize>,
+        /// Maximum provider requests per second, e.g. to stay under an OpenAI rate limit
+        #[arg(long)]
+        rate_limit: Option<f64>,
+    },
+    /// Embed extracted facts that have no fact embeddings yet (for search.fact_embeddings)
+    Facts,
+    /// Show embedding statistics (counts by model, pending, failed, dead-letter summary)
     Stats,
+    /// Download the local model's weights into embedding.cache_dir ahead of the first start
+    Warmup,
+    /// Re-queue memories whose embedding failed and wait for the results
+    RetryFailed {
+        /// Maximum number of failed memories to retry
+        #[arg(long, default_value_t = 1000)]
+        limit: i64,
+    },
+    /// Check embeddings against the configured model: wrong model or dimension, orphaned
+    /// rows, and memories marked complete without an embedding
+    Verify {
+        /// Repair what was found (mark stale and pending, delete orphans); run `embed backfill` after
+        #[arg(long)]
+        fix: bool,
+    },
     /// Switch to a new embedding model (marks current embeddings as stale)
     SwitchModel {
         /// New model name to switch to (e.g., "text-embedding-3-small")
@@ -73,18 +277,105 @@ fn create_extraction_provider(config: &Config) -> Result<Arc<dyn ExtractionProvi
                 api_key,
                 config.extraction.openai_model.clone(),
                 config.extraction.max_content_chars,
-            )?))
+            )?.with_llm_client(LlmClient::new(&config.llm))))
         }
         "ollama" | _ => {
             Ok(Arc::new(OllamaExtractionProvider::new(
                 config.extraction.ollama_base_url.clone(),
                 config.extraction.ollama_model.clone(),
                 config.extraction.max_content_chars,
-            )))
+            ).with_llm_client(LlmClient::new(&config.llm))))
         }
     }
 }
 
+/// Create the consolidation synthesis provider based on configuration.
+///
+/// Connection fields left unset in [consolidation] fall back to the [extraction] values.
+fn create_synthesis_provider(config: &Config) -> Result<Arc<dyn SynthesisProvider>> {
+    match config.consolidation.provider.as_str() {
+        "openai" => {
+            let api_key = config.consolidation.openai_api_key.clone()
+                .or_else(|| config.extraction.openai_api_key.clone())
+                .ok_or_else(|| anyhow::anyhow!(
+                    "OpenAI API key required when consolidation provider is 'openai'. \
+                     Set MEMCP_CONSOLIDATION__OPENAI_API_KEY or consolidation.openai_api_key in memcp.toml"
+                ))?;
+            let provider = OpenAISynthesisProvider::new(
+                config.consolidation.openai_base_url.clone(),
+                api_key,
+                config.consolidation.openai_model.clone(),
+            ).map_err(|e| anyhow::anyhow!("{}", e))?
+                .with_llm_client(LlmClient::new(&config.llm));
+            Ok(Arc::new(provider))
+        }
+        "ollama" => {
+            Ok(Arc::new(OllamaSynthesisProvider::new(
+                config.consolidation.ollama_base_url.clone()
+                    .unwrap_or_else(|| config.extraction.ollama_base_url.clone()),
+                config.consolidation.ollama_model.clone()
+                    .unwrap_or_else(|| config.extraction.ollama_model.clone()),
+            ).with_llm_client(LlmClient::new(&config.llm))))
+        }
+        other => anyhow::bail!(
+            "consolidation.provider: unknown provider '{}' (expected 'ollama' or 'openai')",
+            other
+        ),
+    }
+}
+
+/// Create the write-path content filters from [privacy] (None when disabled).
+///
+/// LLM connection fields left unset in [privacy] fall back to the [extraction] values.
+fn create_content_scrubber(config: &Config) -> Result<Option<ContentScrubber>> {
+    let privacy = &config.privacy;
+    if !privacy.enabled {
+        return Ok(None);
+    }
+    let mut filters: Vec<Arc<dyn ContentFilter>> = vec![Arc::new(PatternFilter::new(
+        &privacy.redact,
+        &privacy.deny_list,
+        &privacy.patterns,
+    )?)];
+    if privacy.llm {
+        let (endpoint, model) = match privacy.provider.as_str() {
+            "openai" => (
+                LlmEndpoint::OpenAI {
+                    base_url: privacy.openai_base_url.clone(),
+                    api_key: privacy.openai_api_key.clone()
+                        .or_else(|| config.extraction.openai_api_key.clone())
+                        .unwrap_or_default(),
+                },
+                privacy.openai_model.clone(),
+            ),
+            "ollama" => (
+                LlmEndpoint::Ollama {
+                    base_url: privacy.ollama_base_url.clone()
+                        .unwrap_or_else(|| config.extraction.ollama_base_url.clone()),
+                },
+                privacy.ollama_model.clone()
+                    .unwrap_or_else(|| config.extraction.ollama_model.clone()),
+            ),
+            other => anyhow::bail!(
+                "privacy.provider: unknown provider '{}' (expected 'ollama' or 'openai')",
+                other
+            ),
+        };
+        let filter = LlmContentFilter::new(endpoint, model)?.with_llm_client(LlmClient::new(&config.llm));
+        filters.push(Arc::new(filter));
+    }
+    Ok(Some(ContentScrubber::new(filters, privacy.fail_closed)))
+}
+
+/// Connect to the running server's admin listener ([admin] listen_addr and token).
+async fn connect_admin(config: &Config) -> Result<AdminClient> {
+    let addr = config.admin.listen_addr.as_deref().ok_or_else(|| anyhow::anyhow!(
+        "No admin listener configured. Set admin.listen_addr and admin.token \
+         (MEMCP_ADMIN__LISTEN_ADDR, MEMCP_ADMIN__TOKEN) for both the server and the CLI"
+    ))?;
+    Ok(AdminClient::connect(addr, config.admin.token.as_deref().unwrap_or_default()).await?)
+}
+
 /// Create the QI expansion provider based on configuration.
 fn create_qi_expansion_provider(config: &Config) -> Result<Arc<dyn QueryIntelligenceProvider + Send + Sync>> {
     match config.query_intelligence.expansion_provider.as_str() {
@@ -98,21 +389,29 @@ fn create_qi_expansion_provider(config: &Config) -> Result<Arc<dyn QueryIntellig
                 config.query_intelligence.openai_base_url.clone(),
                 api_key,
                 config.query_intelligence.expansion_openai_model.clone(),
-            ).map_err(|e| anyhow::anyhow!("{}", e))?;
+            ).map_err(|e| anyhow::anyhow!("{}", e))?
+                .with_llm_client(LlmClient::new(&config.llm));
             Ok(Arc::new(provider))
         }
         "ollama" | _ => {
             Ok(Arc::new(OllamaQueryIntelligenceProvider::new(
                 config.query_intelligence.ollama_base_url.clone(),
                 config.query_intelligence.expansion_ollama_model.clone(),
-            )))
+            ).with_llm_client(LlmClient::new(&config.llm))))
         }
     }
 }
 
 /// Create the QI reranking provider based on configuration.
-fn create_qi_reranking_provider(config: &Config) -> Result<Arc<dyn QueryIntelligenceProvider + Send + Sync>> {
+async fn create_qi_reranking_provider(config: &Config) -> Result<Arc<dyn QueryIntelligenceProvider + Send + Sync>> {
     match config.query_intelligence.reranking_provider.as_str() {
+        "local" => {
+            let provider = LocalRerankingProvider::new(
+                &config.embedding.cache_dir,
+                &config.query_intelligence.local_reranker_model,
+            ).await?;
+            Ok(Arc::new(provider))
+        }
         "openai" => {
             let api_key = config.query_intelligence.openai_api_key.clone()
                 .ok_or_else(|| anyhow::anyhow!(
@@ -123,18 +422,39 @@ fn create_qi_reranking_provider(config: &Config) -> Result<Arc<dyn QueryIntellig
                 config.query_intelligence.openai_base_url.clone(),
                 api_key,
                 config.query_intelligence.reranking_openai_model.clone(),
-            ).map_err(|e| anyhow::anyhow!("{}", e))?;
+            ).map_err(|e| anyhow::anyhow!("{}", e))?
+                .with_llm_client(LlmClient::new(&config.llm));
             Ok(Arc::new(provider))
         }
         "ollama" | _ => {
             Ok(Arc::new(OllamaQueryIntelligenceProvider::new(
                 config.query_intelligence.ollama_base_url.clone(),
                 config.query_intelligence.reranking_ollama_model.clone(),
-            )))
+            ).with_llm_client(LlmClient::new(&config.llm))))
         }
     }
 }
 
+/// Wrap a QI provider in a `MonitoredProvider` probing its endpoint (collected into `probes`),
+/// unless query_intelligence.health_probe_interval_secs is 0.
+fn monitor_qi_provider(
+    config: &Config,
+    provider: Arc<dyn QueryIntelligenceProvider + Send + Sync>,
+    subsystem: &str,
+    probes: &mut Vec<Arc<MonitoredProvider>>,
+) -> Arc<dyn QueryIntelligenceProvider + Send + Sync> {
+    if config.query_intelligence.health_probe_interval_secs == 0 {
+        return provider;
+    }
+    let mut monitored = MonitoredProvider::new(provider, subsystem, config.query_intelligence.failure_threshold);
+    if let Some(endpoint) = memcp::health::llm_endpoints(config).into_iter().find(|e| e.subsystem == subsystem) {
+        monitored = monitored.with_probe_endpoint(endpoint);
+    }
+    let monitored = Arc::new(monitored);
+    probes.push(monitored.clone());
+    monitored
+}
+
 /// Create the embedding provider based on configuration.
 async fn create_embedding_provider(config: &Config) -> Result<Arc<dyn EmbeddingProvider + Send + Sync>> {
     match config.embedding.provider.as_str() {
@@ -144,7 +464,12 @@ async fn create_embedding_provider(config: &Config) -> Result<Arc<dyn EmbeddingP
                     "OpenAI API key required when provider is 'openai'. \
                      Set MEMCP_EMBEDDING__OPENAI_API_KEY or embedding.openai_api_key in memcp.toml"
                 ))?;
-            Ok(Arc::new(OpenAIEmbeddingProvider::new(api_key)?))
+            Ok(Arc::new(OpenAIEmbeddingProvider::new(
+                api_key,
+                config.embedding.openai_base_url.clone(),
+                config.embedding.openai_model.clone(),
+                config.embedding.openai_dimension,
+            )?))
         }
         "local" | _ => {
             Ok(Arc::new(LocalEmbeddingProvider::new(&config.embedding.cache_dir).await?))
@@ -152,16 +477,361 @@ async fn create_embedding_provider(config: &Config) -> Result<Arc<dyn EmbeddingP
     }
 }
 
+/// Create a provider from `[embedding.providers.<name>]`; unset OpenAI settings fall back
+/// to the `[embedding]` section's.
+async fn create_named_embedding_provider(
+    config: &Config,
+    name: &str,
+    named: &NamedEmbeddingProvider,
+) -> Result<Arc<dyn EmbeddingProvider + Send + Sync>> {
+    match named.provider.as_str() {
+        "local" => Ok(Arc::new(LocalEmbeddingProvider::new(&config.embedding.cache_dir).await?)),
+        "openai" => {
+            let api_key = named.openai_api_key.clone()
+                .or_else(|| config.embedding.openai_api_key.clone())
+                .ok_or_else(|| anyhow::anyhow!(
+                    "OpenAI API key required for embedding provider '{}'. \
+                     Set embedding.providers.{}.openai_api_key or embedding.openai_api_key in memcp.toml",
+                    name, name
+                ))?;
+            let model = named.openai_model.clone()
+                .ok_or_else(|| anyhow::anyhow!("embedding.providers.{}.openai_model is required", name))?;
+            Ok(Arc::new(OpenAIEmbeddingProvider::new(
+                api_key,
+                named.openai_base_url.clone().unwrap_or_else(|| config.embedding.openai_base_url.clone()),
+                model,
+                named.openai_dimension,
+            )?))
+        }
+        other => anyhow::bail!(
+            "embedding.providers.{}: unknown provider '{}' (expected 'openai' or 'local')",
+            name, other
+        ),
+    }
+}
+
+/// Wrap the default embedding provider in a router with the configured
+/// `[[embedding.routes]]` and the named providers they use.
+async fn create_embedding_router(
+    config: &Config,
+    default: Arc<dyn EmbeddingProvider + Send + Sync>,
+) -> Result<EmbeddingRouter> {
+    validate_routes(&config.embedding)?;
+    let mut named: HashMap<String, Arc<dyn EmbeddingProvider>> = HashMap::new();
+    for route in &config.embedding.routes {
+        if named.contains_key(&route.provider) {
+            continue;
+        }
+        let provider = create_named_embedding_provider(
+            config,
+            &route.provider,
+            &config.embedding.providers[&route.provider],
+        )
+        .await?;
+        named.insert(route.provider.clone(), provider);
+    }
+    Ok(EmbeddingRouter::new(default).with_routes(named, config.embedding.routes.clone()))
+}
+
+/// Attach the configured content cipher to `store` (unchanged when encryption is off).
+fn with_configured_encryption(store: PostgresMemoryStore, config: &Config) -> Result<PostgresMemoryStore> {
+    Ok(match ContentCipher::from_config(&config.security)? {
+        Some(cipher) => store.with_encryption(cipher),
+        None => store,
+    })
+}
+
+/// Resolve on SIGINT or (on unix) SIGTERM.
+async fn termination_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to install SIGTERM handler — only SIGINT stops the server"),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// `embed backfill` settings from its flags, falling back to the [embedding] config.
+fn backfill_options(config: &Config, batch_size: Option<usize>, rate_limit: Option<f64>) -> Result<BackfillOptions> {
+    if batch_size == Some(0) {
+        anyhow::bail!("--batch-size must be at least 1");
+    }
+    if let Some(rate) = rate_limit {
+        if !(rate > 0.0 && rate.is_finite()) {
+            anyhow::bail!("--rate-limit must be a positive number of requests per second");
+        }
+    }
+    Ok(BackfillOptions {
+        batch_size: batch_size.unwrap_or(config.embedding.batch_size),
+        rate_limit,
+        retry: RetryPolicy::from_config(&config.embedding),
+    })
+}
+
+/// Name of the model the configured embedding provider stores vectors under.
+fn configured_embedding_model(config: &Config) -> &str {
+    match config.embedding.provider.as_str() {
+        "openai" => &config.embedding.openai_model,
+        _ => memcp::embedding::local::MODEL_NAME,
+    }
+}
+
+/// Models of the `[embedding.providers]` used by routes, other than the default model.
+/// Their embeddings are current alongside the default model's and only searched when a
+/// search pins their route.
+fn routed_embedding_models(config: &Config) -> Vec<String> {
+    let default_model = configured_embedding_model(config);
+    let mut models: Vec<String> = config
+        .embedding
+        .routes
+        .iter()
+        .filter_map(|route| config.embedding.providers.get(&route.provider))
+        .map(|named| match named.provider.as_str() {
+            "local" => memcp::embedding::local::MODEL_NAME.to_string(),
+            _ => named.openai_model.clone().unwrap_or_default(),
+        })
+        .filter(|model| model != default_model)
+        .collect();
+    models.sort();
+    models.dedup();
+    models
+}
+
+/// Create the embedding provider that produces vectors for `model`, regardless of which
+/// provider is configured (used by `embed backfill --model`).
+async fn create_embedding_provider_for_model(
+    config: &Config,
+    model: &str,
+) -> Result<Arc<dyn EmbeddingProvider + Send + Sync>> {
+    if model == memcp::embedding::local::MODEL_NAME {
+        Ok(Arc::new(LocalEmbeddingProvider::new(&config.embedding.cache_dir).await?))
+    } else if model == config.embedding.openai_model
+        || memcp::embedding::openai::known_dimension(model).is_some()
+    {
+        let api_key = config.embedding.openai_api_key.clone()
+            .ok_or_else(|| anyhow::anyhow!(
+                "OpenAI API key required to embed with '{}'. \
+                 Set MEMCP_EMBEDDING__OPENAI_API_KEY or embedding.openai_api_key in memcp.toml",
+                model
+            ))?;
+        // The configured dimension only describes the configured model
+        let dimension = config.embedding.openai_dimension.filter(|_| model == config.embedding.openai_model);
+        Ok(Arc::new(OpenAIEmbeddingProvider::new(
+            api_key,
+            config.embedding.openai_base_url.clone(),
+            model.to_string(),
+            dimension,
+        )?))
+    } else {
+        anyhow::bail!(
+            "Unknown embedding model '{}'. Supported models: {}, {}, or the configured embedding.openai_model",
+            model,
+            memcp::embedding::local::MODEL_NAME,
+            memcp::embedding::openai::DEFAULT_MODEL
+        )
+    }
+}
+
+/// Outcome of one `memcp doctor` check.
+enum Check {
+    Ok(String),
+    Warn(String, String),
+    Fail(String, String),
+}
+
+impl Check {
+    fn print(&self) {
+        match self {
+            Check::Ok(msg) => println!("[ok]   {}", msg),
+            Check::Warn(msg, fix) => println!("[warn] {}\n       fix: {}", msg, fix),
+            Check::Fail(msg, fix) => println!("[FAIL] {}\n       fix: {}", msg, fix),
+        }
+    }
+}
+
+/// Parse "major.minor.patch" (missing parts are 0) for extension version comparisons.
+fn parse_version(version: &str) -> (u32, u32, u32) {
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// Run every `memcp doctor` check, printing each result as it completes.
+///
+/// Returns false if any check failed. Uses the same store and provider constructors as
+/// the server, so a clean report means the server will start with this configuration.
+async fn run_doctor(config: &Config) -> bool {
+    let mut checks: Vec<Check> = Vec::new();
+    let mut record = |check: Check| {
+        check.print();
+        checks.push(check);
+    };
+
+    // Database, migrations, and extensions
+    match PostgresMemoryStore::new_with_config(&config.database_url, false, &config.search, &config.database).await {
+        Err(e) => record(Check::Fail(
+            format!("Database unreachable: {}", e),
+            "Start PostgreSQL and check database_url (MEMCP_DATABASE_URL)".to_string(),
+        )),
+        Ok(store) => {
+            record(Check::Ok("Database reachable".to_string()));
+
+            match store.pending_migrations().await {
+                Ok(pending) if pending.is_empty() => record(Check::Ok("Migrations current".to_string())),
+                Ok(pending) => record(Check::Fail(
+                    format!("{} pending migration(s): {}", pending.len(), pending.join(", ")),
+                    "Run `memcp migrate` (or start the server without --skip-migrate)".to_string(),
+                )),
+                Err(e) => record(Check::Fail(format!("Could not read migration history: {}", e), "Check database permissions".to_string())),
+            }
+            if let Err(e) = store.check_schema_version().await {
+                record(Check::Fail(e.to_string(), "Run the memcp release that migrated this database, or a newer one".to_string()));
+            }
+
+            match store.extension_version("vector").await {
+                Ok(Some(version)) if parse_version(&version) < (0, 5, 0) => record(Check::Fail(
+                    format!("pgvector {} does not support HNSW indexes", version),
+                    "Upgrade pgvector to 0.8.0 or later, then run ALTER EXTENSION vector UPDATE".to_string(),
+                )),
+                Ok(Some(version)) if parse_version(&version) < (0, 8, 0) => record(Check::Warn(
+                    format!("pgvector {} supports HNSW but not iterative index scans — filtered searches may return too few results", version),
+                    "Upgrade pgvector to 0.8.0 or later".to_string(),
+                )),
+                Ok(Some(version)) => record(Check::Ok(format!("pgvector {} (HNSW and iterative scans supported)", version))),
+                Ok(None) => record(Check::Fail(
+                    "pgvector extension not installed".to_string(),
+                    "Install pgvector and run `memcp migrate` (it creates the extension)".to_string(),
+                )),
+                Err(e) => record(Check::Fail(format!("Could not query extensions: {}", e), "Check database permissions".to_string())),
+            }
+
+            match (store.paradedb_available(), config.search.bm25_backend == "paradedb") {
+                (true, true) if store.paradedb_index_ready().await => {
+                    record(Check::Ok("ParadeDB pg_search available and BM25 index valid".to_string()))
+                }
+                (true, true) => record(Check::Warn(
+                    "search.bm25_backend = \"paradedb\" but memories has no valid BM25 index — falling back to native full-text search".to_string(),
+                    "Run `memcp migrate` to create memories_bm25_idx (check search.paradedb_tokenizer if it fails)".to_string(),
+                )),
+                (true, false) => record(Check::Ok("ParadeDB pg_search available".to_string())),
+                (false, true) => record(Check::Warn(
+                    "search.bm25_backend = \"paradedb\" but pg_search is not installed — falling back to native full-text search".to_string(),
+                    "Install ParadeDB pg_search, or set search.bm25_backend = \"native\"".to_string(),
+                )),
+                (false, false) => record(Check::Ok("ParadeDB not installed (native full-text search in use)".to_string())),
+            }
+
+            match store.text_search_config_exists().await {
+                Ok(true) => record(Check::Ok(format!("Text search configuration '{}' available", store.text_search_config()))),
+                Ok(false) => record(Check::Fail(
+                    format!("Text search configuration '{}' does not exist — keyword search will fail", store.text_search_config()),
+                    "Check search.text_search_config, or run `memcp migrate` to create the stop-list configuration".to_string(),
+                )),
+                Err(e) => record(Check::Fail(format!("Could not check text search configuration: {}", e), "Check database permissions".to_string())),
+            }
+        }
+    }
+
+    // Embedding model: constructing the provider downloads local weights; one embed proves it works
+    match create_embedding_provider(config).await {
+        Err(e) => record(Check::Fail(
+            format!("Embedding provider '{}' failed to initialize: {}", config.embedding.provider, e),
+            "Check network access and embedding.cache_dir, or the OpenAI API key for provider = \"openai\"".to_string(),
+        )),
+        Ok(provider) => match provider.embed("memcp doctor").await {
+            Ok(_) => record(Check::Ok(format!("Embedding model {} ready", provider.model_name()))),
+            Err(e) => record(Check::Fail(
+                format!("Embedding model {} failed to embed: {}", provider.model_name(), e),
+                "For OpenAI, verify embedding.openai_api_key; for local, delete embedding.cache_dir and retry".to_string(),
+            )),
+        },
+    }
+
+    // LLM providers: constructors catch missing keys, probes catch unreachable servers and bad keys
+    let constructors: Vec<(&str, Result<()>)> = vec![
+        ("extraction", if config.extraction.enabled { create_extraction_provider(config).map(|_| ()) } else { Ok(()) }),
+        ("consolidation", if config.consolidation.enabled { create_synthesis_provider(config).map(|_| ()) } else { Ok(()) }),
+        ("query_expansion", if config.query_intelligence.expansion_enabled { create_qi_expansion_provider(config).map(|_| ()) } else { Ok(()) }),
+        ("reranking", if config.query_intelligence.reranking_enabled { create_qi_reranking_provider(config).await.map(|_| ()) } else { Ok(()) }),
+    ];
+    for (subsystem, result) in constructors {
+        if let Err(e) = result {
+            record(Check::Fail(format!("{} provider misconfigured: {}", subsystem, e), format!("Fix the provider settings for {}, or disable it", subsystem)));
+        }
+    }
+
+    let client = reqwest::Client::new();
+    for endpoint in memcp::health::llm_endpoints(config) {
+        let label = format!("{} ({} at {})", endpoint.subsystem, endpoint.provider, endpoint.base_url);
+        if endpoint.provider == "ollama" {
+            match memcp::health::ollama_models(&client, &endpoint.base_url).await {
+                Err(e) => record(Check::Fail(
+                    format!("{}: Ollama unreachable: {}", label, e),
+                    format!("Start Ollama (`ollama serve`) or fix the base URL, or disable {}", endpoint.subsystem),
+                )),
+                Ok(pulled) if !memcp::health::ollama_model_pulled(&pulled, &endpoint.model) => record(Check::Fail(
+                    format!("{}: model {} is not pulled", label, endpoint.model),
+                    format!("Run `ollama pull {}`", endpoint.model),
+                )),
+                Ok(_) => record(Check::Ok(format!("{}: model {} available", label, endpoint.model))),
+            }
+        } else {
+            match memcp::health::probe_endpoint(&client, &endpoint).await {
+                Ok(_) => record(Check::Ok(format!("{}: API key accepted", label))),
+                Err(e) if e.contains("401") || e.contains("403") => record(Check::Fail(
+                    format!("{}: API key rejected ({})", label, e),
+                    format!("Set a valid OpenAI API key for {}", endpoint.subsystem),
+                )),
+                Err(e) => record(Check::Fail(
+                    format!("{}: endpoint unreachable: {}", label, e),
+                    "Check network access and the configured base URL".to_string(),
+                )),
+            }
+        }
+    }
+
+    let failed = checks.iter().filter(|c| matches!(c, Check::Fail(..))).count();
+    let warned = checks.iter().filter(|c| matches!(c, Check::Warn(..))).count();
+    println!("\n{} checks: {} failed, {} warnings.", checks.len(), failed, warned);
+    failed == 0
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // 1. Parse CLI args
     let cli = Cli::parse();
 
     // 2. Load configuration
-    let config = Config::load().unwrap_or_else(|e| {
-        eprintln!("Config error (using defaults): {}", e);
+    let (loaded, issues) = Config::load_checked();
+    // `config validate` prints the problems itself
+    let validating = matches!(cli.command, Some(Commands::Config { .. }));
+    if !validating {
+        for issue in &issues {
+            eprintln!("Config error: {}", issue);
+        }
+    }
+    if cli.strict_config && !validating && !issues.is_empty() {
+        eprintln!("Refusing to start with {} configuration problem(s) (--strict-config); run `memcp config validate` to recheck", issues.len());
+        std::process::exit(1);
+    }
+    let mut config = loaded.unwrap_or_else(|| {
+        eprintln!("Config could not be loaded — using defaults");
         Config::default()
     });
+    if let Some(ref namespace) = cli.namespace {
+        config.default_namespace = namespace.clone();
+    }
+    if cli.read_only {
+        config.server.read_only = true;
+    }
 
     // 3. Initialize logging FIRST (before any other output)
     // CRITICAL: logging goes to stderr only — stdout is reserved for JSON-RPC
@@ -169,56 +839,436 @@ async fn main() -> Result<()> {
 
     // 4. Handle subcommands
     match cli.command {
-        Some(Commands::Migrate) => {
+        Some(Commands::Migrate { status, dry_run, to }) if status || dry_run || to.is_some() => {
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, false, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+            let migrations = store.migration_status().await?;
+            if status {
+                for m in &migrations {
+                    let applied_at = m.applied_at.map(|at| at.to_rfc3339()).unwrap_or_else(|| "-".to_string());
+                    println!("{:03}  {:<8}  {}  {:<25}  {}", m.version, m.state.as_str(), &m.checksum[..16.min(m.checksum.len())], applied_at, m.description);
+                }
+                let count = |state: MigrationState| migrations.iter().filter(|m| m.state == state).count();
+                println!(
+                    "\n{} applied, {} pending, {} modified, {} unknown to this binary",
+                    count(MigrationState::Applied),
+                    count(MigrationState::Pending),
+                    count(MigrationState::Modified),
+                    count(MigrationState::Unknown)
+                );
+                if count(MigrationState::Modified) + count(MigrationState::Unknown) > 0 {
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
+
+            store.check_schema_version().await?;
+            if let Some(target) = to {
+                if !MIGRATOR.iter().any(|m| m.version == target) {
+                    anyhow::bail!("Unknown migration version {} — see `memcp migrate --status`", target);
+                }
+            }
+            let planned: Vec<_> = MIGRATOR
+                .iter()
+                .filter(|m| to.is_none_or(|target| m.version <= target))
+                .filter(|m| migrations.iter().any(|s| s.version == m.version && s.state == MigrationState::Pending))
+                .collect();
+            if dry_run {
+                for m in &planned {
+                    println!("-- {:03} {}\n{}\n", m.version, m.description, m.sql.trim_end());
+                }
+                println!("-- {} migration(s) would be applied", planned.len());
+                return Ok(());
+            }
+            let applied = store.migrate_to(to).await?;
+            println!("Applied {} migration(s){}.", applied.len(), to.map(|t| format!(" up to {:03}", t)).unwrap_or_default());
+            return Ok(());
+        }
+
+        Some(Commands::Migrate { .. }) => {
             tracing::info!("Running database migrations...");
             // run_migrations=true, just connect and migrate then exit
-            let _store = PostgresMemoryStore::new(&config.database_url, true)
+            let _store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
                 .await
                 .expect("Failed to connect and run migrations");
             println!("Migrations completed successfully.");
             return Ok(());
         }
 
+        Some(Commands::Config { action: ConfigAction::Validate }) => {
+            for issue in &issues {
+                println!("[FAIL] {}", issue);
+            }
+            if issues.is_empty() {
+                println!("Configuration is valid.");
+                return Ok(());
+            }
+            println!("\n{} configuration problem(s) found.", issues.len());
+            std::process::exit(1);
+        }
+
+        Some(Commands::Doctor) => {
+            if !run_doctor(&config).await {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+
+        Some(Commands::EncryptExisting { batch_size }) => {
+            if ContentCipher::from_config(&config.security)?.is_none() {
+                anyhow::bail!("Set security.encryption_key (or MEMCP_SECURITY__ENCRYPTION_KEY) before encrypting existing content");
+            }
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+            let store = with_configured_encryption(store, &config)?;
+            println!("Encrypting plaintext memory content...");
+            let (memories, revisions) = store.encrypt_existing(batch_size.max(1)).await?;
+            println!("Done: encrypted {} memories and {} revisions.", memories, revisions);
+            return Ok(());
+        }
+
+        Some(Commands::Consolidate { action: ConsolidateAction::Scan { limit, batch_size } }) => {
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+            let store = with_configured_encryption(store, &config)?;
+            let provider = create_synthesis_provider(&config)?;
+            let extractor = create_extraction_provider(&config).ok();
+            println!(
+                "Scanning {} for memories above similarity {}...",
+                cli.namespace.as_deref().map_or("all namespaces".to_string(), |ns| format!("namespace '{}'", ns)),
+                config.consolidation.similarity_threshold
+            );
+            let report = scan_existing(
+                &store,
+                &config.consolidation,
+                provider.as_ref(),
+                extractor.as_deref().map(|e| e as &dyn ExtractionProvider),
+                cli.namespace.as_deref(),
+                batch_size.max(1),
+                limit,
+                |progress| {
+                    println!(
+                        "  scanned {} — {} clusters merged ({} memories), {} failed",
+                        progress.scanned, progress.clusters, progress.consolidated, progress.failed
+                    );
+                },
+            )
+            .await?;
+            println!(
+                "Done: scanned {}, merged {} clusters covering {} memories.",
+                report.scanned, report.clusters, report.consolidated
+            );
+            if report.clusters > 0 {
+                println!("Consolidated memories are embedded on next startup (or run `memcp embed backfill`).");
+            }
+            return Ok(());
+        }
+
+        Some(Commands::Index { action }) => {
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+
+            // Indexes are per dimension; maintain the one current embeddings are searched with
+            let Some(dimension) = store.current_embedding_dimension().await? else {
+                println!("No current embeddings yet — the vector index is built for the model's dimension when the server starts.");
+                return Ok(());
+            };
+            match action {
+                IndexAction::Stats { sample, k, ef_search } => {
+                    let stats = store.vector_index_stats(dimension).await?;
+                    println!("{}", serde_json::to_string_pretty(&stats)?);
+                    if sample > 0 {
+                        println!("Estimating recall@{} over {} sampled embeddings...", k.max(1), sample);
+                        let recall = store.estimate_vector_recall(sample, k.max(1), ef_search, dimension).await?;
+                        println!("{}", serde_json::to_string_pretty(&recall)?);
+                    }
+                }
+                IndexAction::Rebuild => {
+                    println!("Rebuilding vector index (searches keep using the old index until done)...");
+                    let (m, ef_construction) = store.rebuild_vector_index(None, None, dimension).await?;
+                    println!("Rebuilt with m = {}, ef_construction = {}.", m, ef_construction);
+                }
+                IndexAction::Tune { m, ef_construction } => {
+                    if m.is_none() && ef_construction.is_none() {
+                        anyhow::bail!("Pass --m and/or --ef-construction (use `memcp index rebuild` to keep the current parameters)");
+                    }
+                    println!("Rebuilding vector index with new parameters...");
+                    let (m, ef_construction) = store.rebuild_vector_index(m, ef_construction, dimension).await?;
+                    println!("Rebuilt with m = {}, ef_construction = {}.", m, ef_construction);
+                    println!("Run `memcp index stats` to check the new recall estimate.");
+                }
+            }
+            return Ok(());
+        }
+
+        Some(Commands::Audit { tool, outcome, memory_id, since, until, limit }) => {
+            let parse = |s: Option<String>| -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+                s.map(|s| Ok(chrono::DateTime::parse_from_rfc3339(&s)?.with_timezone(&chrono::Utc)))
+                    .transpose()
+            };
+            let filter = memcp::audit::AuditFilter {
+                tool,
+                outcome,
+                memory_id,
+                since: parse(since)?,
+                until: parse(until)?,
+                limit: limit.max(1),
+            };
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+            let entries = store.get_audit_log(&filter).await?;
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+            if entries.is_empty() && !config.audit.enabled {
+                eprintln!("Auditing is disabled; set [audit] enabled = true (or MEMCP_AUDIT__ENABLED=true) to record tool calls.");
+            }
+            return Ok(());
+        }
+
+        Some(Commands::Admin { action }) => {
+            let mut client = connect_admin(&config).await?;
+            let result = match action {
+                AdminAction::Status => client.call("status", serde_json::json!({})).await?,
+                AdminAction::ClearCache => client.call("cache.clear", serde_json::json!({})).await?,
+                AdminAction::Consolidate { limit } => {
+                    let params = serde_json::json!({ "limit": limit, "namespace": cli.namespace });
+                    client.call("consolidation.run", params).await?
+                }
+            };
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            return Ok(());
+        }
+
+        Some(Commands::Stats) => {
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+            let stats = store.memory_stats(cli.namespace.as_deref()).await?;
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            return Ok(());
+        }
+
+        Some(Commands::SearchLog { namespace, since, until, limit }) => {
+            let parse = |s: Option<String>| -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+                s.map(|s| Ok(chrono::DateTime::parse_from_rfc3339(&s)?.with_timezone(&chrono::Utc)))
+                    .transpose()
+            };
+            let filter = memcp::search_log::SearchLogFilter {
+                namespace,
+                since: parse(since)?,
+                until: parse(until)?,
+                limit: limit.max(1),
+            };
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+            let entries = store.get_search_log(&filter).await?;
+            for entry in &entries {
+                println!("{}", serde_json::to_string(entry)?);
+            }
+            if entries.is_empty() && !config.search_log.enabled {
+                eprintln!("The search log is disabled; set [search_log] enabled = true (or MEMCP_SEARCH_LOG__ENABLED=true) to record searches.");
+            }
+            return Ok(());
+        }
+
+        Some(Commands::Embed { action: EmbedAction::Warmup }) => {
+            if config.embedding.provider == "openai" {
+                println!("Embedding provider is 'openai' — there are no local model weights to download.");
+                return Ok(());
+            }
+            println!("Downloading and loading '{}' into {}...", memcp::embedding::local::MODEL_NAME, config.embedding.cache_dir);
+            let provider = LocalEmbeddingProvider::new(&config.embedding.cache_dir).await?;
+            provider.embed("memcp warmup").await?;
+            println!("Model ready; later starts load it from the cache.");
+            return Ok(());
+        }
+
+        Some(Commands::Embed { action: EmbedAction::Stats }) => {
+            // A running server answers without a new pool, and adds its live queue depth
+            if config.admin.listen_addr.is_some() {
+                match connect_admin(&config).await {
+                    Ok(mut client) => {
+                        let stats = client.call("embed.stats", serde_json::json!({})).await?;
+                        println!("{}", serde_json::to_string_pretty(&stats)?);
+                        return Ok(());
+                    }
+                    Err(e) => eprintln!("Admin listener unavailable ({}) — reading stats from the database", e),
+                }
+            }
+            let store = PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
+                .await
+                .expect("Failed to connect to database");
+            let stats = store.embedding_stats().await?;
+            println!("{}", serde_json::to_string_pretty(&stats)?);
+            return Ok(());
+        }
+
         Some(Commands::Embed { action }) => {
-            let store = Arc::new(
-                PostgresMemoryStore::new(&config.database_url, true)
+            let store = Arc::new(with_configured_encryption(
+                PostgresMemoryStore::new_with_config(&config.database_url, true, &config.search, &config.database)
                     .await
                     .expect("Failed to connect to database"),
-            );
+                &config,
+            )?);
+            let embedding_template = EmbeddingTemplate::parse(&config.embedding.text_template)?;
 
             match action {
-                EmbedAction::Backfill => {
-                    println!("Starting embedding backfill...");
-                    let provider = create_embedding_provider(&config).await?;
-                    // No consolidation during manual backfill — consolidation is a live trigger only
-                    let pipeline = EmbeddingPipeline::new(provider, store.clone(), 1000, None);
-                    let count = backfill(&store, &pipeline.sender()).await;
-                    println!("Queued {} memories for embedding.", count);
-                    // Wait briefly for some embeddings to process
-                    tokio::time::sleep(Duration::from_secs(2)).await;
+                EmbedAction::Backfill { model: Some(model), batch_size, rate_limit } => {
+                    let options = backfill_options(&config, batch_size, rate_limit)?;
+                    let provider = create_embedding_provider_for_model(&config, &model).await?;
+                    let is_current = model == configured_embedding_model(&config);
+                    println!(
+                        "Embedding memories missing a '{}' embedding ({})...",
+                        model,
+                        if is_current { "current model" } else { "alongside the current model" }
+                    );
+                    let count = backfill_model(
+                        &store,
+                        provider.as_ref(),
+                        options,
+                        is_current,
+                        &embedding_template,
+                    )
+                    .await?;
+                    println!("Embedded {} memories with '{}'.", count, model);
                     let stats = store.embedding_stats().await?;
                     println!("Current stats: {}", serde_json::to_string_pretty(&stats)?);
                 }
-                EmbedAction::Stats => {
-                    let stats = store.embedding_stats().await?;
-                    println!("{}", serde_json::to_string_pretty(&stats)?);
+                EmbedAction::Backfill { model: None, batch_size, rate_limit } => {
+                    let options = backfill_options(&config, batch_size, rate_limit)?;
+                    // Same lock as the startup backfill, so a server queueing these memories
+                    // (or a second CLI run) doesn't embed them twice
+                    let Some(lock) = store.try_advisory_lock(EMBEDDING_BACKFILL_LOCK).await? else {
+                        anyhow::bail!("Another memcp instance is running the embedding backfill — try again once it finishes");
+                    };
+                    let provider = create_embedding_provider(&config).await?;
+                    // No consolidation during manual backfill — consolidation is a live trigger only
+                    let router = create_embedding_router(&config, provider).await?;
+                    println!(
+                        "Embedding pending and failed memories ({} per request{})...",
+                        options.batch_size,
+                        options.rate_limit.map(|r| format!(", at most {} requests/s", r)).unwrap_or_default()
+                    );
+                    let mut last_report = std::time::Instant::now();
+                    let result = run_backfill(&store, &router, &embedding_template, options, |progress| {
+                        if last_report.elapsed() >= Duration::from_secs(5) {
+                            println!("  {}", progress);
+                            last_report = std::time::Instant::now();
+                        }
+                    })
+                    .await;
+                    lock.release().await;
+                    let progress = result?;
+                    println!("Done: {}.", progress);
+                    if progress.failed > 0 {
+                        println!(
+                            "{} memories failed — see `memcp embed stats` for errors, then `memcp embed retry-failed` or run the backfill again.",
+                            progress.failed
+                        );
+                    }
+                }
+                EmbedAction::Facts => {
+                    if !config.search.fact_embeddings {
+                        println!("Note: search.fact_embeddings is disabled — fact embeddings won't be searched until it is enabled.");
+                    }
+                    let provider = create_embedding_provider(&config).await?;
+                    println!("Embedding extracted facts with '{}'...", provider.model_name());
+                    let count = backfill_facts(&store, provider.as_ref(), config.embedding.batch_size).await?;
+                    println!("Embedded the facts of {} memories.", count);
+                }
+                EmbedAction::Stats => unreachable!("embed stats is handled above"),
+                EmbedAction::Warmup => unreachable!("embed warmup runs without a database"),
+                EmbedAction::RetryFailed { limit } => {
+                    let failed = store.get_failed_embedding_memories(limit).await?;
+                    if failed.is_empty() {
+                        println!("No failed embeddings to retry.");
+                        return Ok(());
+                    }
+                    let provider = create_embedding_provider(&config).await?;
+                    let pipeline = EmbeddingPipeline::new(
+                        create_embedding_router(&config, provider).await?,
+                        store.clone(),
+                        failed.len().max(1),
+                        config.embedding.batch_size,
+                        None,
+                        RetryPolicy::from_config(&config.embedding),
+                        Shutdown::new(),
+                    );
+                    println!("Retrying {} failed embeddings...", failed.len());
+                    let ids: Vec<String> = failed.iter().map(|m| m.id.clone()).collect();
+                    for memory in failed {
+                        let text = embedding_template.render_memory(&memory);
+                        pipeline.enqueue(memcp::embedding::EmbeddingJob {
+                            memory_id: memory.id,
+                            text,
+                            attempt: 0,
+                        });
+                    }
+                    pipeline.flush().await;
+
+                    let still_failed = store.get_memories_by_ids(&ids).await?
+                        .values()
+                        .filter(|m| m.embedding_status == "failed")
+                        .count();
+                    println!("Recovered {} of {}; {} still failing.", ids.len() - still_failed, ids.len(), still_failed);
+                    if still_failed > 0 {
+                        let stats = store.embedding_stats().await?;
+                        println!("Dead-letter summary: {}", serde_json::to_string_pretty(&stats["failures"])?);
+                    }
+                }
+                EmbedAction::Verify { fix } => {
+                    let provider = create_embedding_provider(&config).await?;
+                    let model = provider.model_name().to_string();
+                    let dimension = provider.dimension() as i32;
+                    println!("Verifying embeddings against '{}' ({} dimensions)...", model, dimension);
+                    let report = store.verify_embeddings(&model, dimension).await?;
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                    if report.is_clean() {
+                        println!("Embeddings are consistent.");
+                    } else if fix {
+                        let repaired = store.repair_embeddings(&model, dimension).await?;
+                        println!(
+                            "Marked {} drifted embeddings stale, deleted {} orphaned embeddings, reset {} memories missing an embedding.",
+                            repaired.drifted, repaired.orphaned, repaired.missing
+                        );
+                        println!("Run 'memcp embed backfill' to re-embed the affected memories.");
+                    } else {
+                        println!("Run with --fix to repair.");
+                    }
                 }
                 EmbedAction::SwitchModel { model, dry_run } => {
                     let stats = store.embedding_stats().await?;
+                    let new_dimension = create_embedding_provider_for_model(&config, &model).await?.dimension() as i32;
+                    let old_dimension = store.current_embedding_dimension().await?;
+                    let dimension_changes = old_dimension.is_some_and(|old| old != new_dimension);
 
                     if dry_run {
-                        println!("DRY RUN — Switch model to '{}'", model);
+                        println!("DRY RUN — Switch model to '{}' ({} dimensions)", model, new_dimension);
                         println!("Current embedding stats:");
                         println!("{}", serde_json::to_string_pretty(&stats)?);
                         println!("\nThis would:");
                         println!("  - Mark all current embeddings as stale (is_current = false)");
                         println!("  - Set embedding_status = 'pending' for all affected memories");
+                        println!("  - Build the vector index for {}-dimensional embeddings if missing", new_dimension);
+                        if let (true, Some(old)) = (dimension_changes, old_dimension) {
+                            println!("  - Drop the vector index for the old {}-dimensional embeddings", old);
+                        }
                         println!("  - New embeddings will use model '{}' on next backfill", model);
                         println!("\nRun without --dry-run to apply.");
                     } else {
                         println!("Switching to model '{}'...", model);
                         let stale_count = store.mark_all_embeddings_stale().await?;
                         println!("Marked {} embeddings as stale.", stale_count);
+                        println!("Building the vector index for {}-dimensional embeddings...", new_dimension);
+                        store.ensure_vector_indexes(new_dimension).await?;
+                        for index in store.drop_vector_indexes_except(new_dimension).await? {
+                            println!("Dropped unused vector index {}.", index);
+                        }
                         println!("Run 'memcp embed backfill' to generate new embeddings with the new model.");
                     }
                 }
@@ -235,70 +1285,211 @@ async fn main() -> Result<()> {
 
             // 5. Initialize PostgreSQL store
             let run_migrations = !cli.skip_migrate;
-            let store = Arc::new(
-                PostgresMemoryStore::new(&config.database_url, run_migrations)
+            // With the outbox on, new memories' jobs are written in the insert transaction
+            let mut outbox_kinds = Vec::new();
+            if config.outbox.enabled {
+                outbox_kinds.push(JobKind::Embedding);
+                if config.extraction.enabled && !config.security.encrypts_content() {
+                    outbox_kinds.push(JobKind::Extraction);
+                }
+            }
+            let store = Arc::new(with_configured_encryption(
+                PostgresMemoryStore::new_with_config(&config.database_url, run_migrations, &config.search, &config.database)
                     .await
                     .expect("Failed to initialize database"),
-            );
+                &config,
+            )?.with_outbox(outbox_kinds).with_routed_models(routed_embedding_models(&config)));
+
+            // Migrations refuse a database migrated by a newer memcp; check it without them too
+            if !run_migrations {
+                store.check_schema_version().await?;
+            }
 
             tracing::info!(database_url = %config.database_url, "PostgreSQL store initialized");
 
+            // 5b. Running config shared by the service, consolidation worker, and SIGHUP handler.
+            //     Reloads keep the CLI overrides; an invalid salience decay curve is fatal here
+            //     and rejected on reload.
+            memcp::search::decay::decay_model(&config.salience)?;
+            let cli_namespace = cli.namespace.clone();
+            let cli_read_only = cli.read_only;
+            let live_config = LiveConfig::new(config.clone()).with_overrides(move |config| {
+                if let Some(ref namespace) = cli_namespace {
+                    config.default_namespace = namespace.clone();
+                }
+                if cli_read_only {
+                    config.server.read_only = true;
+                }
+            });
+
+            // 5c. Background workers register with the shutdown coordinator so exit waits for
+            //     their in-flight jobs
+            let shutdown = Shutdown::new();
+
+            // 5d. Lifecycle event notifications (webhook and/or pg_notify)
+            let events = EventNotifier::spawn(&config.events, Some(store.clone()), &shutdown);
+            if config.events.enabled {
+                tracing::info!(
+                    webhook = config.events.webhook_url.is_some(),
+                    notify_channel = ?config.events.notify_channel,
+                    events = ?config.events.events,
+                    "Memory lifecycle events enabled"
+                );
+            }
+
             // 6. Create embedding provider and pipeline
-            let provider = create_embedding_provider(&config).await
-                .expect("Failed to initialize embedding provider");
+            let embedding_template = EmbeddingTemplate::parse(&config.embedding.text_template)?;
+            let provider: Arc<dyn EmbeddingProvider + Send + Sync> =
+                if config.embedding.lazy_init && config.embedding.provider != "openai" {
+                    tracing::info!("Loading the local embedding model in the background — search is keyword-only until it is ready");
+                    Arc::new(LocalEmbeddingProvider::lazy(&config.embedding.cache_dir))
+                } else {
+                    create_embedding_provider(&config).await
+                        .expect("Failed to initialize embedding provider")
+                };
             let provider_for_search = provider.clone();  // Clone for MemoryService search
+            let router = Arc::new(create_embedding_router(&config, provider).await?);
+
+            // 6a. Make sure the vector indexes for each model's dimension exist (built concurrently
+            //     in the background; searches fall back to a sequential scan until then)
+            for provider in router.providers() {
+                let store = store.clone();
+                let dimension = provider.dimension() as i32;
+                tokio::spawn(async move {
+                    if let Err(e) = store.ensure_vector_indexes(dimension).await {
+                        tracing::warn!(error = %e, dimension = dimension, "Failed to create vector indexes");
+                    }
+                });
+            }
 
             // 6b. Create consolidation worker if enabled (must happen before embedding pipeline)
             // Consolidation is triggered indirectly via the embedding pipeline's completion callback.
             let consolidation_sender = if config.consolidation.enabled {
-                let worker = ConsolidationWorker::new(
-                    store.clone(),
-                    config.consolidation.clone(),
-                    config.extraction.ollama_base_url.clone(),
-                    config.extraction.ollama_model.clone(),
-                    500,
-                );
-                tracing::info!(
-                    threshold = config.consolidation.similarity_threshold,
-                    max_group = config.consolidation.max_consolidation_group,
-                    "Consolidation worker started"
-                );
-                Some(worker.sender())
+                match create_synthesis_provider(&config) {
+                    Ok(synthesis_provider) => {
+                        let worker = ConsolidationWorker::new(
+                            store.clone(),
+                            live_config.clone(),
+                            synthesis_provider,
+                            // Quality check fact comparison (consolidation.quality_check)
+                            create_extraction_provider(&config).ok().map(|p| p as Arc<dyn ExtractionProvider>),
+                            500,
+                            events.clone(),
+                            shutdown.clone(),
+                        );
+                        tracing::info!(
+                            provider = %config.consolidation.provider,
+                            threshold = config.consolidation.similarity_threshold,
+                            max_group = config.consolidation.max_consolidation_group,
+                            "Consolidation worker started"
+                        );
+                        Some(worker.sender())
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to init synthesis provider — consolidation disabled");
+                        None
+                    }
+                }
             } else {
                 tracing::info!("Consolidation disabled via config (consolidation.enabled=false)");
                 None
             };
 
-            let pipeline = EmbeddingPipeline::new(provider, store.clone(), 1000, consolidation_sender);
+            let pipeline = EmbeddingPipeline::new(
+                router.as_ref().clone(),
+                store.clone(),
+                config.embedding.queue_capacity,
+                config.embedding.batch_size,
+                consolidation_sender,
+                RetryPolicy::from_config(&config.embedding),
+                shutdown.clone(),
+            );
 
-            // 7. Run startup backfill — queue any un-embedded memories from previous runs
-            let queued = backfill(&store, &pipeline.sender()).await;
-            if queued > 0 {
-                tracing::info!(count = queued, "Startup backfill queued memories for embedding");
+            // 7. Run startup backfill — queue any un-embedded memories from previous runs.
+            // The lock is held until the queue drains so other instances sharing the
+            // database don't queue the same memories.
+            match store.try_advisory_lock(EMBEDDING_BACKFILL_LOCK).await {
+                Ok(Some(lock)) => {
+                    let queued = backfill(&store, &pipeline, &embedding_template).await;
+                    if queued > 0 {
+                        tracing::info!(count = queued, "Startup backfill queued memories for embedding");
+                        let pipeline = pipeline.clone();
+                        tokio::spawn(async move {
+                            pipeline.flush().await;
+                            lock.release().await;
+                        });
+                    } else {
+                        lock.release().await;
+                    }
+                }
+                Ok(None) => {
+                    tracing::info!("Another memcp instance is running the embedding backfill — skipping");
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to take the embedding backfill lock — skipping backfill");
+                }
             }
 
             // 8. Create extraction pipeline if enabled
-            let extraction_pipeline = if config.extraction.enabled {
+            let extraction_pipeline = if config.extraction.enabled && config.security.encrypts_content() {
+                tracing::warn!("Extraction disabled — its entities and facts would be stored unencrypted (security.encryption_key is set)");
+                None
+            } else if config.extraction.enabled {
                 match create_extraction_provider(&config) {
                     Ok(extraction_provider) => {
-                        let ep = ExtractionPipeline::new(extraction_provider, store.clone(), 1000);
-                        // Queue pending extractions on startup (backfill)
-                        match store.get_pending_extraction(1000).await {
-                            Ok(pending) => {
-                                let count = pending.len();
-                                for (memory_id, content) in pending {
-                                    ep.enqueue(ExtractionJob {
-                                        memory_id,
-                                        content,
-                                        attempt: 0,
-                                    });
+                        let ep = ExtractionPipeline::new(
+                            extraction_provider,
+                            store.clone(),
+                            ExtractionOptions {
+                                capacity: config.extraction.queue_capacity,
+                                concurrency: config.extraction.concurrency,
+                                fact_embedder: config.search.fact_embeddings.then(|| provider_for_search.clone()),
+                                reembed: embedding_template
+                                    .uses_extraction()
+                                    .then(|| (pipeline.sender(), embedding_template.clone())),
+                                contradiction_candidates: config
+                                    .extraction
+                                    .detect_contradictions
+                                    .then_some(config.extraction.contradiction_candidates),
+                            },
+                            shutdown.clone(),
+                        );
+                        // Queue pending extractions on startup (backfill), under the same
+                        // cross-instance lock scheme as the embedding backfill
+                        match store.try_advisory_lock(EXTRACTION_BACKFILL_LOCK).await {
+                            Ok(Some(lock)) => match store.get_pending_extraction(1000).await {
+                                Ok(pending) => {
+                                    let count = pending.len();
+                                    for (memory_id, content) in pending {
+                                        // Whether the hint was explicit is not stored, so backfill keeps it
+                                        ep.enqueue(ExtractionJob {
+                                            memory_id,
+                                            content,
+                                            attempt: 0,
+                                            classify: false,
+                                        });
+                                    }
+                                    if count > 0 {
+                                        tracing::info!(count = count, "Startup backfill queued memories for extraction");
+                                        let ep = ep.clone();
+                                        tokio::spawn(async move {
+                                            ep.flush().await;
+                                            lock.release().await;
+                                        });
+                                    } else {
+                                        lock.release().await;
+                                    }
                                 }
-                                if count > 0 {
-                                    tracing::info!(count = count, "Startup backfill queued memories for extraction");
+                                Err(e) => {
+                                    tracing::warn!(error = %e, "Failed to fetch pending extractions for backfill");
+                                    lock.release().await;
                                 }
+                            },
+                            Ok(None) => {
+                                tracing::info!("Another memcp instance is running the extraction backfill — skipping");
                             }
                             Err(e) => {
-                                tracing::warn!(error = %e, "Failed to fetch pending extractions for backfill");
+                                tracing::warn!(error = %e, "Failed to take the extraction backfill lock — skipping backfill");
                             }
                         }
                         Some(ep)
@@ -313,6 +1504,76 @@ async fn main() -> Result<()> {
                 None
             };
 
+            // 8a. Start the Prometheus /metrics listener if configured
+            if let Some(addr) = config.metrics.listen_addr.clone() {
+                tokio::spawn(async move {
+                    if let Err(e) = memcp::metrics::serve_metrics(&addr).await {
+                        tracing::warn!(error = %e, addr = %addr, "Metrics listener failed — metrics still available via get_metrics");
+                    }
+                });
+            }
+
+            // 8b. Start the expiry sweeper if enabled
+            if config.expiry.enabled {
+                spawn_expiry_sweeper(store.clone(), config.expiry.clone());
+                tracing::info!(
+                    interval_secs = config.expiry.sweep_interval_secs,
+                    action = %config.expiry.action,
+                    "Expiry sweeper started"
+                );
+            } else {
+                tracing::info!("Expiry sweeper disabled via config (expiry.enabled=false)");
+            }
+
+            // 8c. Start the decay archiver if enabled
+            if config.decay.enabled {
+                spawn_decay_archiver(store.clone(), config.decay.clone());
+                tracing::info!(
+                    interval_secs = config.decay.sweep_interval_secs,
+                    threshold = config.decay.archive_threshold,
+                    "Decay archiver started"
+                );
+            }
+
+            // 8d. Start the retention sweeper if enabled (invalid policies are fatal)
+            if config.retention.enabled {
+                let rules = memcp::retention::rules(&config.retention).map_err(|e| anyhow::anyhow!("{}", e))?;
+                tracing::info!(
+                    interval_secs = config.retention.sweep_interval_secs,
+                    policies = rules.len(),
+                    action = %config.retention.action,
+                    dry_run = config.retention.dry_run,
+                    "Retention sweeper started"
+                );
+                spawn_retention_sweeper(store.clone(), config.retention.clone(), rules);
+            }
+
+            // 8e. Relay outbox jobs (written by this or any other process) to the pipelines
+            let mut outbox_relays = Vec::new();
+            if config.outbox.enabled {
+                let embedding_pipeline = pipeline.clone();
+                let template = embedding_template.clone();
+                outbox_relays.push(OutboxRelay::spawn(store.clone(), JobKind::Embedding, &config.outbox, &shutdown, move |job| {
+                    embedding_pipeline.enqueue(memcp::embedding::EmbeddingJob {
+                        memory_id: job.memory.id.clone(),
+                        text: template.render_memory(&job.memory),
+                        attempt: 0,
+                    })
+                }));
+                if let Some(ref ep) = extraction_pipeline {
+                    let ep = ep.clone();
+                    outbox_relays.push(OutboxRelay::spawn(store.clone(), JobKind::Extraction, &config.outbox, &shutdown, move |job| {
+                        ep.enqueue(ExtractionJob {
+                            memory_id: job.memory.id.clone(),
+                            content: job.memory.content.clone(),
+                            attempt: 0,
+                            classify: job.classify,
+                        })
+                    }));
+                }
+                tracing::info!(relays = outbox_relays.len(), "Pipeline job outbox enabled");
+            }
+
             // 9. Create QI providers if enabled
             let qi_expansion_provider = if config.query_intelligence.expansion_enabled {
                 match create_qi_expansion_provider(&config) {
@@ -330,7 +1591,7 @@ async fn main() -> Result<()> {
             };
 
             let qi_reranking_provider = if config.query_intelligence.reranking_enabled {
-                match create_qi_reranking_provider(&config) {
+                match create_qi_reranking_provider(&config).await {
                     Ok(p) => {
                         tracing::info!(provider = %config.query_intelligence.reranking_provider, "Query reranking enabled");
                         Some(p)
@@ -344,8 +1605,21 @@ async fn main() -> Result<()> {
                 None
             };
 
+            // Disable QI providers that keep failing and probe them back in the background
+            let mut qi_probes = Vec::new();
+            let qi_expansion_provider =
+                qi_expansion_provider.map(|p| monitor_qi_provider(&config, p, "query_expansion", &mut qi_probes));
+            let qi_reranking_provider =
+                qi_reranking_provider.map(|p| monitor_qi_provider(&config, p, "reranking", &mut qi_probes));
+            spawn_health_probes(
+                qi_probes,
+                Duration::from_secs(config.query_intelligence.health_probe_interval_secs),
+                shutdown.clone(),
+            );
+
             // 10. Create service with store, pipeline, embedding provider, salience config, extraction pipeline, and QI providers
             let pg_store_for_search = store.clone();
+            let pg_store_for_shutdown = store.clone();
             let service = MemoryService::new(
                 store as Arc<dyn memcp::store::MemoryStore + Send + Sync>,
                 Some(pipeline),
@@ -356,17 +1630,110 @@ async fn main() -> Result<()> {
                 qi_expansion_provider,
                 qi_reranking_provider,
                 config.query_intelligence.clone(),
-            );
+            )
+            .with_default_namespace(config.default_namespace.clone())
+            .with_search_cache(
+                config.search.cache_size,
+                Duration::from_secs(config.search.cache_ttl_secs),
+            )
+            .with_dedup(config.dedup.clone())
+            .with_content_config(config.content.clone())
+            .with_embedding_template(embedding_template)
+            .with_embedding_router(router)
+            .with_digest_config(config.digest.clone())
+            .with_resources_config(config.resources.clone())
+            .with_read_only(config.server.read_only)
+            .with_tool_timeout(config.server.tool_timeout_ms)
+            .with_audit(config.audit.enabled)
+            .with_events(events)
+            .with_search_log(config.search_log.enabled)
+            .with_type_classification(
+                config.extraction.classify_type_hint,
+                config.extraction.keep_explicit_type_hint,
+            )
+            .with_live_config(live_config.clone())
+            .with_fusion(config.search.fusion.clone())
+            .with_min_relevance(config.search.default_min_relevance)
+            .with_outbox(outbox_relays)
+            .with_health_endpoints(memcp::health::llm_endpoints(&config));
+            // ingest_conversation reuses the extraction provider, even when background extraction is off
+            let service = match create_extraction_provider(&config) {
+                Ok(provider) => service.with_conversation_extractor(provider, config.extraction.conversation_chunk_chars),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Extraction provider unavailable — ingest_conversation disabled");
+                    service
+                }
+            };
+            // summarize_memories and consolidate_memories share the consolidation provider
+            // settings (with extraction fallbacks)
+            let service = match create_synthesis_provider(&config) {
+                Ok(provider) => service.with_summary_provider(provider),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Synthesis provider unavailable — summarize_memories and consolidate_memories disabled");
+                    service
+                }
+            };
+
+            // Misconfigured filters are fatal: storing unredacted content would be worse
+            let service = match create_content_scrubber(&config)? {
+                Some(scrubber) => service.with_content_scrubber(Arc::new(scrubber)),
+                None => service,
+            };
+
+            tracing::info!(namespace = %config.default_namespace, "Default namespace");
+
+            // Re-read memcp.toml on SIGHUP (same as the reload_config tool)
+            #[cfg(unix)]
+            tokio::spawn(async move {
+                use tokio::signal::unix::{signal, SignalKind};
+                let mut hangups = match signal(SignalKind::hangup()) {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to install SIGHUP handler — use reload_config instead");
+                        return;
+                    }
+                };
+                while hangups.recv().await.is_some() {
+                    match live_config.reload_from_disk() {
+                        Ok(report) => tracing::info!(
+                            applied = ?report.applied,
+                            restart_required = ?report.restart_required,
+                            "Config reloaded on SIGHUP"
+                        ),
+                        Err(e) => tracing::warn!(error = %e, "Config reload failed — keeping the running config"),
+                    }
+                }
+            });
 
-            // 11. Serve via stdio transport
+            // 11. Start the admin listener if configured (a bad token or address is fatal),
+            // then serve MCP via stdio transport
+            let service = Arc::new(service);
+            if let Some(addr) = config.admin.listen_addr.as_deref() {
+                let listener = AdminListener::bind(addr, config.admin.token.as_deref()).await?;
+                tokio::spawn(listener.serve(service.clone()));
+            }
             let (stdin, stdout) = rmcp::transport::io::stdio();
             let server = service.serve((stdin, stdout)).await?;
 
             tracing::info!("memcp server running — awaiting tool calls via stdio");
 
-            // 12. Wait for shutdown (client disconnects or signal)
+            // 12. Wait for shutdown: the client disconnects, or SIGINT/SIGTERM stops the service
+            let service_token = server.cancellation_token();
+            tokio::spawn(async move {
+                termination_signal().await;
+                tracing::info!("Termination signal received — stopping");
+                service_token.cancel();
+            });
             server.waiting().await?;
 
+            // 13. Let in-flight pipeline jobs finish writing before exit
+            let timeout = Duration::from_secs(config.server.shutdown_timeout_secs);
+            tracing::info!(timeout_secs = timeout.as_secs(), "Draining background workers");
+            if !shutdown.drain(timeout).await {
+                tracing::warn!(timeout_secs = timeout.as_secs(), "Shutdown timed out with jobs still running — they resume from backfill on next start");
+            }
+            pg_store_for_shutdown.pool().close().await;
+
             tracing::info!("memcp server stopped");
         }
     }
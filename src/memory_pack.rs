@@ -0,0 +1,194 @@
+//! Portable memory packs.
+//!
+//! export_memories serializes a topical slice of memories — selected by query and/or filters —
+//! into a self-describing JSON pack, optionally carrying each memory's current embedding.
+//! import_memories loads a pack into another memcp instance: every memory gets a fresh ID
+//! (the response maps old IDs to new ones), duplicates of memories already stored are skipped,
+//! and packed embeddings are reused when they come from the importing instance's model.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::store::{Memory, OriginRef};
+
+/// Value of `format` in every pack.
+pub const PACK_FORMAT: &str = "memcp-pack";
+/// Pack layout version written by this build; import accepts this version only.
+pub const PACK_VERSION: u32 = 1;
+/// Most memories a single pack may hold.
+pub const MAX_PACK_MEMORIES: usize = 1000;
+
+/// A portable set of memories.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryPack {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Namespace the memories were exported from
+    pub namespace: String,
+    pub memories: Vec<PackedMemory>,
+}
+
+/// One memory in a pack. Only caller-meaningful fields travel: pipeline state, access
+/// statistics, and consolidation links are rebuilt by the importing instance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackedMemory {
+    /// ID in the exporting instance (import reports it next to the new ID)
+    pub id: String,
+    pub content: String,
+    pub type_hint: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "default_importance")]
+    pub importance: i16,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub origin_refs: Vec<OriginRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<PackedEmbedding>,
+}
+
+/// A memory's embedding, tagged with the model that produced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackedEmbedding {
+    pub model: String,
+    pub vector: Vec<f32>,
+}
+
+fn default_importance() -> i16 {
+    crate::store::DEFAULT_IMPORTANCE
+}
+
+impl MemoryPack {
+    /// Start an empty pack for memories exported from `namespace`.
+    pub fn new(namespace: impl Into<String>) -> Self {
+        MemoryPack {
+            format: PACK_FORMAT.to_string(),
+            version: PACK_VERSION,
+            exported_at: Utc::now(),
+            namespace: namespace.into(),
+            memories: Vec::new(),
+        }
+    }
+
+    /// Parse and check a pack received by import_memories.
+    pub fn from_value(value: serde_json::Value) -> Result<Self, String> {
+        let pack: MemoryPack =
+            serde_json::from_value(value).map_err(|e| format!("Not a valid memory pack: {}", e))?;
+        if pack.format != PACK_FORMAT {
+            return Err(format!("Expected format \"{}\", got {:?}", PACK_FORMAT, pack.format));
+        }
+        if pack.version != PACK_VERSION {
+            return Err(format!(
+                "Unsupported pack version {} (this server reads version {})",
+                pack.version, PACK_VERSION
+            ));
+        }
+        if pack.memories.len() > MAX_PACK_MEMORIES {
+            return Err(format!(
+                "Pack holds {} memories; at most {} can be imported per call",
+                pack.memories.len(),
+                MAX_PACK_MEMORIES
+            ));
+        }
+        Ok(pack)
+    }
+}
+
+impl PackedMemory {
+    /// Pack a stored memory, with its current embedding when one was requested and exists.
+    pub fn from_memory(memory: &Memory, embedding: Option<PackedEmbedding>) -> Self {
+        let tags = memory.tags.as_ref().and_then(|t| t.as_array()).map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        });
+        PackedMemory {
+            id: memory.id.clone(),
+            content: memory.content.clone(),
+            type_hint: memory.type_hint.clone(),
+            source: memory.source.clone(),
+            tags,
+            created_at: memory.created_at,
+            importance: memory.importance,
+            pinned: memory.pinned,
+            expires_at: memory.expires_at,
+            payload: memory.payload.clone(),
+            origin_refs: OriginRef::from_stored(memory.origin_refs.as_ref()),
+            embedding,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn packed(id: &str) -> PackedMemory {
+        PackedMemory {
+            id: id.to_string(),
+            content: "Deploys go through staging first".to_string(),
+            type_hint: "instruction".to_string(),
+            source: "user".to_string(),
+            tags: Some(vec!["deploy".to_string()]),
+            created_at: "2026-01-02T03:04:05Z".parse().unwrap(),
+            importance: 4,
+            pinned: true,
+            expires_at: None,
+            payload: Some(json!({"env": "staging"})),
+            origin_refs: vec![OriginRef { conversation_id: Some("c-1".to_string()), ..Default::default() }],
+            embedding: Some(PackedEmbedding { model: "all-MiniLM-L6-v2".to_string(), vector: vec![0.5, -0.25] }),
+        }
+    }
+
+    #[test]
+    fn pack_round_trips_through_json() {
+        let mut pack = MemoryPack::new("work");
+        pack.memories.push(packed("a"));
+        let value = serde_json::to_value(&pack).unwrap();
+        assert_eq!(value["format"], PACK_FORMAT);
+        assert_eq!(value["version"], PACK_VERSION);
+        assert_eq!(MemoryPack::from_value(value).unwrap(), pack);
+    }
+
+    #[test]
+    fn optional_fields_default() {
+        let pack = MemoryPack::from_value(json!({
+            "format": PACK_FORMAT,
+            "version": PACK_VERSION,
+            "exported_at": "2026-01-02T03:04:05Z",
+            "namespace": "default",
+            "memories": [{
+                "id": "old",
+                "content": "x",
+                "type_hint": "fact",
+                "source": "user",
+                "created_at": "2026-01-02T03:04:05Z"
+            }]
+        }))
+        .unwrap();
+        let memory = &pack.memories[0];
+        assert_eq!(memory.importance, crate::store::DEFAULT_IMPORTANCE);
+        assert!(!memory.pinned);
+        assert!(memory.tags.is_none() && memory.embedding.is_none() && memory.origin_refs.is_empty());
+    }
+
+    #[test]
+    fn foreign_or_newer_packs_are_rejected() {
+        let mut value = serde_json::to_value(MemoryPack::new("default")).unwrap();
+        value["version"] = json!(PACK_VERSION + 1);
+        assert!(MemoryPack::from_value(value.clone()).unwrap_err().contains("version"));
+        value["version"] = json!(PACK_VERSION);
+        value["format"] = json!("something-else");
+        assert!(MemoryPack::from_value(value).is_err());
+        assert!(MemoryPack::from_value(json!({"memories": []})).is_err());
+    }
+}
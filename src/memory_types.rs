@@ -0,0 +1,195 @@
+//! Structured memory types.
+//!
+//! A memory type is a named schema defined per namespace, e.g. "contact" with fields
+//! name/email/company. store_structured_memory validates a memory's field values against
+//! its type before storing them in `memories.fields`; the field values are indexed so the
+//! symbolic search leg matches on them like it does on tags.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Value kinds a structured field can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    String,
+    Number,
+    Boolean,
+    /// An ISO-8601 date or timestamp, stored as a string
+    Date,
+}
+
+impl FieldKind {
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            FieldKind::String => value.is_string(),
+            FieldKind::Number => value.is_number(),
+            FieldKind::Boolean => value.is_boolean(),
+            FieldKind::Date => value.as_str().is_some_and(|s| {
+                DateTime::parse_from_rfc3339(s).is_ok() || chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+            }),
+        }
+    }
+}
+
+/// One field of a memory type.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FieldDef {
+    /// Field name, e.g. "email" (letters, digits, and underscores)
+    pub name: String,
+    /// Value kind: "string", "number", "boolean", or "date" (default: "string")
+    #[serde(rename = "type", default = "default_kind")]
+    pub kind: FieldKind,
+    /// Whether every memory of this type must set the field (default: false)
+    #[serde(default)]
+    pub required: bool,
+    /// What the field holds (optional)
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+fn default_kind() -> FieldKind {
+    FieldKind::String
+}
+
+/// A named schema for structured memories.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryType {
+    /// Type name, also used as the type_hint of its memories
+    pub name: String,
+    /// Namespace the type is defined in
+    pub namespace: String,
+    pub description: Option<String>,
+    pub fields: Vec<FieldDef>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 64 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check a type definition: a valid name and at least one uniquely named field.
+///
+/// Returns the offending parameter and a message.
+pub fn validate_definition(name: &str, fields: &[FieldDef]) -> Result<(), (&'static str, String)> {
+    if !is_identifier(name) {
+        return Err(("name", "Type name must be 1-64 letters, digits, or underscores".to_string()));
+    }
+    if fields.is_empty() {
+        return Err(("fields", "A memory type needs at least one field".to_string()));
+    }
+    for (index, field) in fields.iter().enumerate() {
+        if !is_identifier(&field.name) {
+            return Err(("fields", format!("Field name '{}' must be 1-64 letters, digits, or underscores", field.name)));
+        }
+        if fields[..index].iter().any(|f| f.name == field.name) {
+            return Err(("fields", format!("Field '{}' is defined twice", field.name)));
+        }
+    }
+    Ok(())
+}
+
+impl MemoryType {
+    /// Check field values against the schema: every required field is set, no unknown
+    /// fields, and every value has its field's kind. Null counts as unset.
+    ///
+    /// Returns one message per problem, in schema order followed by unknown fields.
+    pub fn validate(&self, values: &Map<String, Value>) -> Vec<String> {
+        let mut problems = Vec::new();
+        for field in &self.fields {
+            match values.get(&field.name).filter(|v| !v.is_null()) {
+                None if field.required => problems.push(format!("Missing required field '{}'", field.name)),
+                None => {}
+                Some(value) if !field.kind.accepts(value) => problems.push(format!(
+                    "Field '{}' must be a {}",
+                    field.name,
+                    serde_json::to_value(field.kind).ok().and_then(|k| k.as_str().map(str::to_string)).unwrap_or_default()
+                )),
+                Some(_) => {}
+            }
+        }
+        for key in values.keys() {
+            if !self.fields.iter().any(|f| &f.name == key) {
+                problems.push(format!("Unknown field '{}' for type '{}'", key, self.name));
+            }
+        }
+        problems
+    }
+
+    /// Plain-text rendering of a structured memory, used as its content when the caller
+    /// gives none so the memory is still embedded and keyword-searchable.
+    pub fn render(&self, values: &Map<String, Value>) -> String {
+        let parts: Vec<String> = self
+            .fields
+            .iter()
+            .filter_map(|field| {
+                let value = values.get(&field.name).filter(|v| !v.is_null())?;
+                let text = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                Some(format!("{}: {}", field.name, text))
+            })
+            .collect();
+        format!("{} — {}", self.name, parts.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn contact() -> MemoryType {
+        let fields: Vec<FieldDef> = serde_json::from_value(json!([
+            {"name": "name", "required": true},
+            {"name": "email"},
+            {"name": "age", "type": "number"},
+            {"name": "met_on", "type": "date"}
+        ]))
+        .unwrap();
+        MemoryType {
+            name: "contact".to_string(),
+            namespace: "default".to_string(),
+            description: None,
+            fields,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn validates_required_kinds_and_unknown_fields() {
+        let schema = contact();
+        let ok = json!({"name": "Ada", "age": 36, "met_on": "2024-05-01", "email": null});
+        assert!(schema.validate(ok.as_object().unwrap()).is_empty());
+
+        let bad = json!({"age": "old", "met_on": "last week", "phone": "555"});
+        assert_eq!(
+            schema.validate(bad.as_object().unwrap()),
+            vec![
+                "Missing required field 'name'",
+                "Field 'age' must be a number",
+                "Field 'met_on' must be a date",
+                "Unknown field 'phone' for type 'contact'",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_definitions() {
+        let fields = contact().fields;
+        assert!(validate_definition("contact", &fields).is_ok());
+        assert_eq!(validate_definition("my type", &fields).unwrap_err().0, "name");
+        assert_eq!(validate_definition("contact", &[]).unwrap_err().0, "fields");
+
+        let twice = vec![fields[0].clone(), fields[0].clone()];
+        assert_eq!(validate_definition("contact", &twice).unwrap_err().1, "Field 'name' is defined twice");
+    }
+
+    #[test]
+    fn renders_set_fields_in_schema_order() {
+        let values = json!({"email": "ada@example.com", "name": "Ada", "age": 36});
+        assert_eq!(contact().render(values.as_object().unwrap()), "contact — name: Ada; email: ada@example.com; age: 36");
+    }
+}
@@ -0,0 +1,391 @@
+//! Lightweight in-process metrics registry.
+//!
+//! Counters, gauges, and fixed-bucket latency histograms backed by atomics — no external
+//! metrics crate required. A single global registry is updated from the tool handlers and
+//! background pipelines, and exposed via the `get_metrics` MCP tool and (optionally) an
+//! HTTP `/metrics` listener in Prometheus text exposition format.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Histogram bucket upper bounds in seconds (the +Inf bucket is implicit).
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Monotonically increasing counter.
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    const fn new() -> Self {
+        Counter(AtomicU64::new(0))
+    }
+
+    /// Increment by one.
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Increment by `n`.
+    pub fn inc_by(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Current value.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Value that can go up and down (e.g. queue depth).
+pub struct Gauge(AtomicI64);
+
+impl Gauge {
+    const fn new() -> Self {
+        Gauge(AtomicI64::new(0))
+    }
+
+    /// Increment by one.
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Decrement by one.
+    pub fn dec(&self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Current value.
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Latency histogram with cumulative buckets over LATENCY_BUCKETS.
+pub struct Histogram {
+    /// Non-cumulative per-bucket counts; the last slot is the +Inf bucket.
+    buckets: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        Histogram {
+            buckets: [const { AtomicU64::new(0) }; LATENCY_BUCKETS.len() + 1],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    /// Record one observation.
+    pub fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let slot = LATENCY_BUCKETS
+            .iter()
+            .position(|&bound| secs <= bound)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[slot].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Start a timer that records its elapsed time when dropped.
+    ///
+    /// Convenient for handlers with many early returns.
+    pub fn start_timer(&self) -> HistogramTimer<'_> {
+        HistogramTimer { histogram: self, started: Instant::now() }
+    }
+
+    /// Total number of observations.
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Sum of all observations in seconds.
+    pub fn sum_secs(&self) -> f64 {
+        self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+    }
+
+    /// Cumulative bucket counts paired with their upper bounds (None = +Inf).
+    fn cumulative(&self) -> Vec<(Option<f64>, u64)> {
+        let mut running = 0;
+        self.buckets
+            .iter()
+            .enumerate()
+            .map(|(i, bucket)| {
+                running += bucket.load(Ordering::Relaxed);
+                (LATENCY_BUCKETS.get(i).copied(), running)
+            })
+            .collect()
+    }
+}
+
+/// Drop guard returned by Histogram::start_timer.
+pub struct HistogramTimer<'a> {
+    histogram: &'a Histogram,
+    started: Instant,
+}
+
+impl Drop for HistogramTimer<'_> {
+    fn drop(&mut self) {
+        self.histogram.observe(self.started.elapsed());
+    }
+}
+
+/// All memcp metrics. Access the process-wide instance via `global()`.
+pub struct Metrics {
+    /// Latency of store_memory / store_memories tool calls
+    pub store_duration: Histogram,
+    /// Latency of search_memory tool calls (including QI and salience re-ranking)
+    pub search_duration: Histogram,
+    /// Embedding jobs enqueued but not yet completed
+    pub embedding_queue_depth: Gauge,
+    /// Embeddings stored successfully
+    pub embeddings_completed: Counter,
+    /// Memories marked embedding_status = 'failed'
+    pub embeddings_failed: Counter,
+    /// Embedding jobs dropped because the queue was full (left for backfill)
+    pub embedding_jobs_dropped: Counter,
+    /// Extraction jobs dropped because the queue was full (left for backfill)
+    pub extraction_jobs_dropped: Counter,
+    /// Memories marked extraction_status = 'failed'
+    pub extraction_failures: Counter,
+    /// Consolidated memories created
+    pub consolidation_merges: Counter,
+    /// Synthesized consolidations rejected by the quality check
+    pub consolidation_rejections: Counter,
+    /// Query expansion calls that exceeded the latency budget
+    pub qi_expansion_timeouts: Counter,
+    /// LLM re-ranking calls that exceeded the latency budget
+    pub qi_reranking_timeouts: Counter,
+    /// Hybrid search legs dropped for exceeding search.leg_timeout_ms
+    pub search_leg_timeouts: Counter,
+    /// Tool calls abandoned for exceeding server.tool_timeout_ms
+    pub tool_timeouts: Counter,
+    /// Tool calls abandoned because the client cancelled them
+    pub tool_cancellations: Counter,
+    /// Sensitive spans redacted from content before storing ([privacy])
+    pub content_redactions: Counter,
+    /// Memories trashed or deleted by retention policies
+    pub retention_removals: Counter,
+    /// Memories a dry-run retention pass would have removed
+    pub retention_dry_run_matches: Counter,
+    /// Lifecycle events delivered to a webhook or notify channel ([events])
+    pub events_delivered: Counter,
+    /// Lifecycle event deliveries that failed after their retries
+    pub events_failed: Counter,
+    /// Lifecycle events dropped because the event queue was full
+    pub events_dropped: Counter,
+}
+
+static METRICS: Metrics = Metrics {
+    store_duration: Histogram::new(),
+    search_duration: Histogram::new(),
+    embedding_queue_depth: Gauge::new(),
+    embeddings_completed: Counter::new(),
+    embeddings_failed: Counter::new(),
+    embedding_jobs_dropped: Counter::new(),
+    extraction_jobs_dropped: Counter::new(),
+    extraction_failures: Counter::new(),
+    consolidation_merges: Counter::new(),
+    consolidation_rejections: Counter::new(),
+    qi_expansion_timeouts: Counter::new(),
+    qi_reranking_timeouts: Counter::new(),
+    search_leg_timeouts: Counter::new(),
+    tool_timeouts: Counter::new(),
+    tool_cancellations: Counter::new(),
+    content_redactions: Counter::new(),
+    retention_removals: Counter::new(),
+    retention_dry_run_matches: Counter::new(),
+    events_delivered: Counter::new(),
+    events_failed: Counter::new(),
+    events_dropped: Counter::new(),
+};
+
+/// The process-wide metrics registry.
+pub fn global() -> &'static Metrics {
+    &METRICS
+}
+
+impl Metrics {
+    /// Render all metrics in Prometheus text exposition format (version 0.0.4).
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        render_histogram(&mut out, "memcp_store_duration_seconds", "Latency of store tool calls", &self.store_duration);
+        render_histogram(&mut out, "memcp_search_duration_seconds", "Latency of search_memory tool calls", &self.search_duration);
+        render_single(&mut out, "memcp_embedding_queue_depth", "gauge", "Embedding jobs enqueued but not yet completed", self.embedding_queue_depth.get() as f64);
+        render_single(&mut out, "memcp_embeddings_completed_total", "counter", "Embeddings stored successfully", self.embeddings_completed.get() as f64);
+        render_single(&mut out, "memcp_embeddings_failed_total", "counter", "Memories whose embedding failed permanently", self.embeddings_failed.get() as f64);
+        render_single(&mut out, "memcp_embedding_jobs_dropped_total", "counter", "Embedding jobs dropped because the queue was full", self.embedding_jobs_dropped.get() as f64);
+        render_single(&mut out, "memcp_extraction_jobs_dropped_total", "counter", "Extraction jobs dropped because the queue was full", self.extraction_jobs_dropped.get() as f64);
+        render_single(&mut out, "memcp_extraction_failures_total", "counter", "Memories whose extraction failed permanently", self.extraction_failures.get() as f64);
+        render_single(&mut out, "memcp_consolidation_merges_total", "counter", "Consolidated memories created", self.consolidation_merges.get() as f64);
+        render_single(&mut out, "memcp_consolidation_rejections_total", "counter", "Synthesized consolidations rejected by the quality check", self.consolidation_rejections.get() as f64);
+        render_single(&mut out, "memcp_content_redactions_total", "counter", "Sensitive spans redacted from content before storing", self.content_redactions.get() as f64);
+        render_single(&mut out, "memcp_retention_removals_total", "counter", "Memories removed by retention policies", self.retention_removals.get() as f64);
+        render_single(&mut out, "memcp_retention_dry_run_matches_total", "counter", "Memories a dry-run retention pass would have removed", self.retention_dry_run_matches.get() as f64);
+        render_single(&mut out, "memcp_events_delivered_total", "counter", "Lifecycle events delivered to a webhook or notify channel", self.events_delivered.get() as f64);
+        render_single(&mut out, "memcp_events_failed_total", "counter", "Lifecycle event deliveries that failed after retries", self.events_failed.get() as f64);
+        render_single(&mut out, "memcp_events_dropped_total", "counter", "Lifecycle events dropped because the event queue was full", self.events_dropped.get() as f64);
+        render_single(&mut out, "memcp_search_leg_timeouts_total", "counter", "Hybrid search legs dropped for exceeding the leg timeout", self.search_leg_timeouts.get() as f64);
+        render_single(&mut out, "memcp_tool_timeouts_total", "counter", "Tool calls abandoned for exceeding the tool timeout", self.tool_timeouts.get() as f64);
+        render_single(&mut out, "memcp_tool_cancellations_total", "counter", "Tool calls abandoned because the client cancelled them", self.tool_cancellations.get() as f64);
+        let _ = writeln!(out, "# HELP memcp_qi_timeouts_total Query intelligence calls that exceeded the latency budget");
+        let _ = writeln!(out, "# TYPE memcp_qi_timeouts_total counter");
+        let _ = writeln!(out, "memcp_qi_timeouts_total{{stage=\"expansion\"}} {}", self.qi_expansion_timeouts.get());
+        let _ = writeln!(out, "memcp_qi_timeouts_total{{stage=\"reranking\"}} {}", self.qi_reranking_timeouts.get());
+        out
+    }
+
+    /// Summarize all metrics as JSON (for the get_metrics tool).
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "store": histogram_json(&self.store_duration),
+            "search": histogram_json(&self.search_duration),
+            "search_leg_timeouts": self.search_leg_timeouts.get(),
+            "tools": {
+                "timeouts": self.tool_timeouts.get(),
+                "cancellations": self.tool_cancellations.get(),
+            },
+            "embedding": {
+                "queue_depth": self.embedding_queue_depth.get(),
+                "completed": self.embeddings_completed.get(),
+                "failed": self.embeddings_failed.get(),
+                "dropped": self.embedding_jobs_dropped.get(),
+            },
+            "extraction": {
+                "failed": self.extraction_failures.get(),
+                "dropped": self.extraction_jobs_dropped.get(),
+            },
+            "consolidation": {
+                "merges": self.consolidation_merges.get(),
+                "rejected": self.consolidation_rejections.get(),
+            },
+            "privacy": {
+                "redactions": self.content_redactions.get(),
+            },
+            "retention": {
+                "removed": self.retention_removals.get(),
+                "dry_run_matches": self.retention_dry_run_matches.get(),
+            },
+            "events": {
+                "delivered": self.events_delivered.get(),
+                "failed": self.events_failed.get(),
+                "dropped": self.events_dropped.get(),
+            },
+            "query_intelligence": {
+                "expansion_timeouts": self.qi_expansion_timeouts.get(),
+                "reranking_timeouts": self.qi_reranking_timeouts.get(),
+            },
+        })
+    }
+}
+
+fn render_single(out: &mut String, name: &str, kind: &str, help: &str, value: f64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn render_histogram(out: &mut String, name: &str, help: &str, histogram: &Histogram) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    for (bound, count) in histogram.cumulative() {
+        match bound {
+            Some(le) => { let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, le, count); }
+            None => { let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, count); }
+        }
+    }
+    let _ = writeln!(out, "{}_sum {}", name, histogram.sum_secs());
+    let _ = writeln!(out, "{}_count {}", name, histogram.count());
+}
+
+fn histogram_json(histogram: &Histogram) -> serde_json::Value {
+    let count = histogram.count();
+    let mean_ms = if count > 0 { histogram.sum_secs() * 1000.0 / count as f64 } else { 0.0 };
+    serde_json::json!({
+        "count": count,
+        "mean_ms": (mean_ms * 100.0).round() / 100.0,
+    })
+}
+
+/// Serve `GET /metrics` over plain HTTP on the given address until the process exits.
+///
+/// Intentionally minimal (one request per connection, no keep-alive) — it exists only
+/// for Prometheus scraping. Any other path returns 404.
+pub async fn serve_metrics(listen_addr: &str) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+    tracing::info!(addr = %listen_addr, "Metrics listener started");
+
+    loop {
+        let (mut socket, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!(error = %e, "Metrics listener accept failed");
+                continue;
+            }
+        };
+
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let n = match socket.read(&mut buf).await {
+                Ok(n) => n,
+                Err(_) => return,
+            };
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let response = if request.starts_with("GET /metrics ") {
+                let body = global().render_prometheus();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let h = Histogram::new();
+        h.observe(Duration::from_millis(3));
+        h.observe(Duration::from_millis(40));
+        h.observe(Duration::from_secs(30));
+
+        let buckets = h.cumulative();
+        assert_eq!(buckets[0], (Some(0.005), 1));
+        assert_eq!(buckets[3], (Some(0.05), 2));
+        assert_eq!(buckets.last().copied(), Some((None, 3)));
+        assert_eq!(h.count(), 3);
+    }
+
+    #[test]
+    fn test_render_prometheus_includes_all_metrics() {
+        let text = global().render_prometheus();
+        for name in [
+            "memcp_store_duration_seconds_count",
+            "memcp_search_duration_seconds_bucket{le=\"+Inf\"}",
+            "memcp_embedding_queue_depth",
+            "memcp_extraction_failures_total",
+            "memcp_embedding_jobs_dropped_total",
+            "memcp_consolidation_merges_total",
+            "memcp_consolidation_rejections_total",
+            "memcp_content_redactions_total",
+            "memcp_retention_removals_total",
+            "memcp_events_delivered_total",
+            "memcp_qi_timeouts_total{stage=\"reranking\"}",
+        ] {
+            assert!(text.contains(name), "missing {}", name);
+        }
+    }
+}
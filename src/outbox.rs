@@ -0,0 +1,140 @@
+//! Transactional outbox for embedding and extraction jobs.
+//!
+//! With `[outbox] enabled`, PostgresMemoryStore writes one pipeline_jobs row per job a new
+//! memory needs, in the same transaction as the memory itself, so a crash right after the
+//! insert can no longer lose the job. One relay per pipeline claims rows with
+//! `FOR UPDATE SKIP LOCKED` (concurrent processes never claim the same job) and hands them
+//! to the pipeline's queue; the pipeline deletes the row once the job reaches a final
+//! status. Claims left behind by a process that died mid-job are claimed again by another
+//! process after `outbox.lease_secs`; a relay never re-claims its own jobs, which may just
+//! be waiting in a long queue. Re-embedding and re-extraction after updates still go straight to
+//! the in-memory queues.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::config::OutboxConfig;
+use crate::shutdown::Shutdown;
+use crate::store::Memory;
+use crate::store::postgres::PostgresMemoryStore;
+
+/// Which pipeline a job belongs to (the pipeline_jobs.kind column).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    Embedding,
+    Extraction,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::Embedding => "embedding",
+            JobKind::Extraction => "extraction",
+        }
+    }
+}
+
+/// A job claimed from the outbox, with the memory it refers to.
+#[derive(Debug, Clone)]
+pub struct ClaimedJob {
+    pub memory: Memory,
+    /// Extraction jobs: also classify the memory's type_hint
+    pub classify: bool,
+}
+
+/// A memory's job still waiting in the outbox, as reported by get_memory's pipeline status.
+#[derive(Debug, Clone)]
+pub struct PendingJob {
+    pub kind: JobKind,
+    /// Jobs of the same kind queued before this one
+    pub jobs_ahead: i64,
+    pub queued_at: DateTime<Utc>,
+    /// Set while a worker is processing the job
+    pub claimed_at: Option<DateTime<Utc>>,
+}
+
+/// Handle to a running relay. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct OutboxRelay {
+    wake: Arc<Notify>,
+}
+
+impl OutboxRelay {
+    /// Spawn a relay moving `kind` jobs from the outbox to a pipeline.
+    ///
+    /// `dispatch` enqueues one claimed job and returns false when the pipeline refused it
+    /// (queue full or shutting down); the rest of that batch is then released for a later
+    /// poll. On shutdown the relay releases every claim it still holds, so the next start
+    /// doesn't wait out the lease.
+    pub fn spawn<F>(
+        store: Arc<PostgresMemoryStore>,
+        kind: JobKind,
+        config: &OutboxConfig,
+        shutdown: &Shutdown,
+        dispatch: F,
+    ) -> Self
+    where
+        F: Fn(&ClaimedJob) -> bool + Send + Sync + 'static,
+    {
+        let wake = Arc::new(Notify::new());
+        let relay_wake = Arc::clone(&wake);
+        let worker_id = format!("{}:{}", std::process::id(), Uuid::new_v4());
+        let poll_interval = Duration::from_millis(config.poll_interval_ms.max(10));
+        let batch_size = config.batch_size.max(1);
+        let lease_secs = config.lease_secs.max(1);
+
+        let relay_shutdown = shutdown.clone();
+        shutdown.spawn(async move {
+            loop {
+                // Claim until the outbox is empty or the pipeline pushes back
+                'claim: loop {
+                    let jobs = match store.claim_pipeline_jobs(kind, &worker_id, lease_secs, batch_size).await {
+                        Ok(jobs) => jobs,
+                        Err(e) => {
+                            tracing::warn!(kind = kind.as_str(), error = %e, "Failed to claim outbox jobs");
+                            break;
+                        }
+                    };
+                    let claimed = jobs.len();
+                    for (index, job) in jobs.iter().enumerate() {
+                        if !dispatch(job) {
+                            let rest: Vec<String> = jobs[index..].iter().map(|j| j.memory.id.clone()).collect();
+                            if let Err(e) = store.release_pipeline_jobs(kind, &rest).await {
+                                tracing::warn!(kind = kind.as_str(), error = %e, "Failed to release outbox jobs");
+                            }
+                            break 'claim;
+                        }
+                    }
+                    if (claimed as i64) < batch_size {
+                        break;
+                    }
+                }
+
+                tokio::select! {
+                    biased;
+                    _ = relay_shutdown.cancelled() => break,
+                    _ = relay_wake.notified() => {}
+                    _ = tokio::time::sleep(poll_interval) => {}
+                }
+            }
+            match store.release_worker_claims(kind, &worker_id).await {
+                Ok(released) if released > 0 => {
+                    tracing::info!(kind = kind.as_str(), released, "Outbox relay stopped — unfinished jobs released");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(kind = kind.as_str(), error = %e, "Failed to release outbox claims"),
+            }
+        });
+
+        OutboxRelay { wake }
+    }
+
+    /// Check the outbox now instead of at the next poll (after this process wrote jobs).
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
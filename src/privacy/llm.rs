@@ -0,0 +1,185 @@
+//! LLM-backed content filter.
+//!
+//! Asks an Ollama or OpenAI-compatible chat model to list the personal or secret data in a
+//! memory, then redacts those spans locally. Spans the model reports that don't occur
+//! verbatim in the content are ignored, so the model can't rewrite the memory.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::patterns::PatternFilter;
+use super::{ContentFilter, Filtered, PrivacyError};
+use crate::config::LlmConfig;
+use crate::llm_client::LlmClient;
+
+/// Where the filter model is served.
+pub enum LlmEndpoint {
+    Ollama { base_url: String },
+    OpenAI { base_url: String, api_key: String },
+}
+
+#[derive(Serialize)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct OllamaChatResponse {
+    message: ResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct OpenAIChoice {
+    message: ResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct OpenAIChatResponse {
+    choices: Vec<OpenAIChoice>,
+}
+
+/// Parsed model output: the sensitive spans found.
+#[derive(Deserialize)]
+struct FilterOutput {
+    #[serde(default)]
+    items: Vec<FlaggedSpan>,
+}
+
+#[derive(Deserialize)]
+struct FlaggedSpan {
+    text: String,
+    #[serde(default)]
+    kind: String,
+}
+
+/// Content filter that flags sensitive spans with a chat model.
+pub struct LlmContentFilter {
+    client: LlmClient,
+    endpoint: LlmEndpoint,
+    model: String,
+}
+
+impl LlmContentFilter {
+    pub fn new(endpoint: LlmEndpoint, model: String) -> Result<Self, PrivacyError> {
+        if let LlmEndpoint::OpenAI { ref api_key, .. } = endpoint {
+            if api_key.trim().is_empty() {
+                return Err(PrivacyError::Config(
+                    "OpenAI API key is required when privacy.provider is 'openai'. \
+                     Set MEMCP_PRIVACY__OPENAI_API_KEY in the environment"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(LlmContentFilter { client: LlmClient::new(&LlmConfig::default()), endpoint, model })
+    }
+
+    /// Route requests through a configured `LlmClient` (retries, timeout, circuit breaker).
+    pub fn with_llm_client(mut self, client: LlmClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Send the prompt and return the raw JSON text the model produced.
+    async fn chat_json(&self, prompt: String) -> Result<String, PrivacyError> {
+        let messages = vec![ChatMessage { role: "user".to_string(), content: prompt }];
+        match self.endpoint {
+            LlmEndpoint::Ollama { ref base_url } => {
+                let body = json!({
+                    "model": self.model,
+                    "messages": messages,
+                    "stream": false,
+                    "format": "json",
+                    "options": { "temperature": 0.0 },
+                });
+                let url = format!("{}/api/chat", base_url.trim_end_matches('/'));
+                let response: OllamaChatResponse = self.client.post_json(&url, None, &body).await?;
+                Ok(response.message.content)
+            }
+            LlmEndpoint::OpenAI { ref base_url, ref api_key } => {
+                let body = json!({
+                    "model": self.model,
+                    "messages": messages,
+                    "temperature": 0.0,
+                    "response_format": { "type": "json_object" },
+                });
+                let url = format!("{}/chat/completions", base_url.trim_end_matches('/'));
+                let response: OpenAIChatResponse = self.client.post_json(&url, Some(api_key), &body).await?;
+                response
+                    .choices
+                    .into_iter()
+                    .next()
+                    .map(|choice| choice.message.content)
+                    .ok_or_else(|| PrivacyError::Filter("OpenAI returned empty choices list".to_string()))
+            }
+        }
+    }
+}
+
+fn build_filter_prompt(content: &str) -> String {
+    format!(
+        "Find personal or secret information in the text below: email addresses, phone numbers, \
+         postal addresses, full names of private individuals, government ID, account or card \
+         numbers, passwords, API keys, and access tokens. Do not flag public figures, companies, \
+         products, or placeholders such as [REDACTED:EMAIL].\n\
+         Respond with JSON: {{\"items\": [{{\"text\": \"<exact substring>\", \"kind\": \"<email|phone|address|name|id_number|account|password|api_key|other>\"}}]}}. \
+         Copy each text exactly as it appears. Return {{\"items\": []}} if there is none.\n\n\
+         Text:\n{}",
+        content
+    )
+}
+
+/// Turn model output into (kind, text) spans that occur verbatim in `content`.
+fn parse_filter_output(output: &str, content: &str) -> Result<Vec<(String, String)>, PrivacyError> {
+    let parsed: FilterOutput = serde_json::from_str(output)
+        .map_err(|e| PrivacyError::Filter(format!("Failed to parse filter JSON from model output: {}", e)))?;
+    Ok(parsed
+        .items
+        .into_iter()
+        .filter(|item| !item.text.trim().is_empty() && content.contains(&item.text))
+        .map(|item| {
+            let kind = item.kind.trim().to_lowercase();
+            (if kind.is_empty() { "other".to_string() } else { kind }, item.text)
+        })
+        .collect())
+}
+
+#[async_trait]
+impl ContentFilter for LlmContentFilter {
+    async fn filter(&self, content: &str) -> Result<Filtered, PrivacyError> {
+        let output = self.chat_json(build_filter_prompt(content)).await?;
+        let spans = parse_filter_output(&output, content)?;
+        Ok(PatternFilter::literals(&spans).apply(content))
+    }
+
+    fn name(&self) -> &str {
+        "llm"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_verbatim_spans_are_redacted() {
+        let content = "Ask Dana Whitfield at 12 Elm Street";
+        let output = r#"{"items": [
+            {"text": "Dana Whitfield", "kind": "Name"},
+            {"text": "12 Elm St.", "kind": "address"},
+            {"text": "", "kind": "other"}
+        ]}"#;
+        let spans = parse_filter_output(output, content).unwrap();
+        assert_eq!(spans, vec![("name".to_string(), "Dana Whitfield".to_string())]);
+        let filtered = PatternFilter::literals(&spans).apply(content);
+        assert_eq!(filtered.content, "Ask [REDACTED:NAME] at 12 Elm Street");
+
+        assert!(parse_filter_output("not json", content).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Write-path content filtering (PII scrubbing).
+//!
+//! With [privacy] enabled, memory content passes through a chain of `ContentFilter`s before
+//! it is persisted: the built-in pattern filter (emails, phone numbers, API keys, deny-listed
+//! terms, custom regexes) and, optionally, an LLM that flags what the patterns miss. Each
+//! match is replaced with a `[REDACTED:<KIND>]` placeholder; the server records each
+//! redaction's kind and position in memory_redactions for audit, never the original value.
+
+pub mod llm;
+pub mod patterns;
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::errors::MemcpError;
+
+/// Errors that can occur while filtering content.
+#[derive(Debug, Error)]
+pub enum PrivacyError {
+    /// Invalid [privacy] settings (unknown built-in kind, bad regex, missing API key)
+    #[error("Privacy configuration error: {0}")]
+    Config(String),
+
+    /// The LLM filter failed or returned unusable output
+    #[error("Content filter failed: {0}")]
+    Filter(String),
+}
+
+impl From<crate::llm_client::LlmError> for PrivacyError {
+    fn from(e: crate::llm_client::LlmError) -> Self {
+        PrivacyError::Filter(e.to_string())
+    }
+}
+
+impl From<PrivacyError> for MemcpError {
+    fn from(e: PrivacyError) -> Self {
+        match e {
+            PrivacyError::Config(message) => MemcpError::Config(message),
+            other => MemcpError::Internal(other.to_string()),
+        }
+    }
+}
+
+/// One replaced span: what it was, what kind of data it is, and the placeholder left behind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Redaction {
+    pub kind: String,
+    /// The redacted text. Never serialized or stored; memory_redactions keeps a keyed hash
+    #[serde(skip)]
+    pub original: String,
+    pub placeholder: String,
+    /// Byte offset of the placeholder in the filtered content
+    pub offset: usize,
+}
+
+/// Filtered content and the redactions made to it, in order of appearance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filtered {
+    pub content: String,
+    pub redactions: Vec<Redaction>,
+}
+
+/// A pre-store processing stage that removes sensitive data from memory content.
+#[async_trait]
+pub trait ContentFilter: Send + Sync {
+    /// Return `content` with sensitive spans replaced, and what was replaced.
+    async fn filter(&self, content: &str) -> Result<Filtered, PrivacyError>;
+
+    /// Short name for logs ("patterns", "llm").
+    fn name(&self) -> &str;
+}
+
+/// The placeholder a redacted span of `kind` is replaced with.
+pub fn placeholder(kind: &str) -> String {
+    format!("[REDACTED:{}]", kind.to_uppercase())
+}
+
+/// Replace the byte ranges in `matches` (tagged with their kind) by placeholders.
+///
+/// Overlapping matches keep the one starting first (the longer one on ties); the rest are
+/// dropped, so a phone number inside an API key is not redacted twice.
+pub fn apply_matches(content: &str, mut matches: Vec<(Range<usize>, String)>) -> Filtered {
+    matches.sort_by(|a, b| a.0.start.cmp(&b.0.start).then(b.0.end.cmp(&a.0.end)));
+    let mut text = String::with_capacity(content.len());
+    let mut redactions = Vec::new();
+    let mut cursor = 0;
+    for (range, kind) in matches {
+        if range.start < cursor || range.is_empty() {
+            continue;
+        }
+        let placeholder = placeholder(&kind);
+        text.push_str(&content[cursor..range.start]);
+        let offset = text.len();
+        text.push_str(&placeholder);
+        redactions.push(Redaction { kind, original: content[range.clone()].to_string(), placeholder, offset });
+        cursor = range.end;
+    }
+    text.push_str(&content[cursor..]);
+    Filtered { content: text, redactions }
+}
+
+/// The configured chain of content filters, run in order on every write.
+pub struct ContentScrubber {
+    filters: Vec<Arc<dyn ContentFilter>>,
+    /// Refuse the write when a filter fails (privacy.fail_closed); otherwise the failing
+    /// filter is skipped with a warning
+    fail_closed: bool,
+}
+
+impl ContentScrubber {
+    pub fn new(filters: Vec<Arc<dyn ContentFilter>>, fail_closed: bool) -> Self {
+        ContentScrubber { filters, fail_closed }
+    }
+
+    /// Run `content` through every filter, each seeing the previous one's output.
+    pub async fn scrub(&self, content: &str) -> Result<Filtered, PrivacyError> {
+        let mut scrubbed = Filtered { content: content.to_string(), redactions: Vec::new() };
+        for filter in &self.filters {
+            match filter.filter(&scrubbed.content).await {
+                Ok(filtered) => {
+                    shift_offsets(&mut scrubbed.redactions, &filtered.redactions);
+                    scrubbed.content = filtered.content;
+                    scrubbed.redactions.extend(filtered.redactions);
+                }
+                Err(e) if self.fail_closed => return Err(e),
+                Err(e) => {
+                    tracing::warn!(filter = filter.name(), error = %e, "Content filter failed — storing without it");
+                }
+            }
+        }
+        Ok(scrubbed)
+    }
+}
+
+/// Move `earlier` placeholders (offsets into a filter's input) to where they sit in its
+/// output, past the length changes of the `made` replacements (in order of appearance).
+fn shift_offsets(earlier: &mut [Redaction], made: &[Redaction]) {
+    for redaction in earlier {
+        let mut delta = 0isize;
+        for replaced in made {
+            let input_offset = replaced.offset as isize - delta;
+            if input_offset >= redaction.offset as isize {
+                break;
+            }
+            delta += replaced.placeholder.len() as isize - replaced.original.len() as isize;
+        }
+        redaction.offset = (redaction.offset as isize + delta) as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Failing;
+
+    #[async_trait]
+    impl ContentFilter for Failing {
+        async fn filter(&self, _content: &str) -> Result<Filtered, PrivacyError> {
+            Err(PrivacyError::Filter("unreachable".to_string()))
+        }
+
+        fn name(&self) -> &str {
+            "failing"
+        }
+    }
+
+    #[test]
+    fn overlapping_matches_keep_the_first() {
+        let content = "key sk-abc555-1234 end";
+        let matches = vec![(4..18, "api_key".to_string()), (10..18, "phone".to_string())];
+        let filtered = apply_matches(content, matches);
+        assert_eq!(filtered.content, "key [REDACTED:API_KEY] end");
+        assert_eq!(filtered.redactions.len(), 1);
+        assert_eq!(filtered.redactions[0].original, "sk-abc555-1234");
+        assert_eq!(filtered.redactions[0].offset, 4);
+    }
+
+    #[tokio::test]
+    async fn offsets_point_at_placeholders_in_the_final_content() {
+        let emails: Arc<dyn ContentFilter> = Arc::new(
+            patterns::PatternFilter::new(&["email".to_string()], &[], &Default::default()).unwrap(),
+        );
+        let names: Arc<dyn ContentFilter> =
+            Arc::new(patterns::PatternFilter::literals(&[("name".to_string(), "Dana".to_string())]));
+        let scrubber = ContentScrubber::new(vec![emails, names], true);
+        let scrubbed = scrubber.scrub("Dana wrote ann@example.com").await.unwrap();
+        assert_eq!(scrubbed.content, "[REDACTED:NAME] wrote [REDACTED:EMAIL]");
+        for redaction in &scrubbed.redactions {
+            let at = &scrubbed.content[redaction.offset..redaction.offset + redaction.placeholder.len()];
+            assert_eq!(at, redaction.placeholder);
+        }
+        let serialized = serde_json::to_value(&scrubbed.redactions[0]).unwrap();
+        assert!(serialized.get("original").is_none(), "originals are never serialized");
+    }
+
+    #[tokio::test]
+    async fn failing_filters_are_skipped_unless_fail_closed() {
+        let patterns: Arc<dyn ContentFilter> = Arc::new(
+            patterns::PatternFilter::new(&["email".to_string()], &[], &Default::default()).unwrap(),
+        );
+        let open = ContentScrubber::new(vec![patterns.clone(), Arc::new(Failing)], false);
+        let scrubbed = open.scrub("mail ann@example.com").await.unwrap();
+        assert_eq!(scrubbed.content, "mail [REDACTED:EMAIL]");
+
+        let closed = ContentScrubber::new(vec![patterns, Arc::new(Failing)], true);
+        assert!(closed.scrub("mail ann@example.com").await.is_err());
+    }
+}
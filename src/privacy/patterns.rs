@@ -0,0 +1,137 @@
+//! Built-in pattern filter: regexes for common PII and secrets, a deny-list of literal
+//! terms, and custom named regexes from [privacy.patterns].
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use regex::Regex;
+
+use super::{apply_matches, ContentFilter, Filtered, PrivacyError};
+
+/// Built-in kinds `privacy.redact` can enable, with their patterns.
+pub const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    ("email", r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b"),
+    // International numbers with a leading +, or North American 3-3-4 groups
+    ("phone", r"\+\d{1,3}(?:[ .-]?\d{2,4}){2,4}\b|(?:\(\d{3}\)|\b\d{3})[ .-]?\d{3}[ .-]?\d{4}\b"),
+    // OpenAI/Anthropic-style sk- keys, Stripe, AWS access key IDs, GitHub, Slack, Google
+    (
+        "api_key",
+        r"\b(?:sk-[A-Za-z0-9_-]{20,}|[sr]k_(?:live|test)_[A-Za-z0-9]{16,}|AKIA[0-9A-Z]{16}|gh[pousr]_[A-Za-z0-9]{36,}|xox[abpr]-[A-Za-z0-9-]{10,}|AIza[0-9A-Za-z_-]{35})",
+    ),
+];
+
+/// Kind recorded for deny-listed terms.
+pub const DENY_LIST_KIND: &str = "deny_list";
+
+/// Regex and deny-list filter. Deny-listed terms match case-insensitively on word boundaries.
+pub struct PatternFilter {
+    patterns: Vec<(Regex, String)>,
+}
+
+impl PatternFilter {
+    /// Build a filter from built-in kinds, deny-listed terms, and custom `name -> regex` patterns.
+    pub fn new(
+        redact: &[String],
+        deny_list: &[String],
+        custom: &BTreeMap<String, String>,
+    ) -> Result<Self, PrivacyError> {
+        let mut patterns = Vec::new();
+        for kind in redact {
+            let (_, pattern) = BUILTIN_PATTERNS.iter().find(|(name, _)| name == kind).ok_or_else(|| {
+                let known: Vec<&str> = BUILTIN_PATTERNS.iter().map(|(name, _)| *name).collect();
+                PrivacyError::Config(format!(
+                    "privacy.redact: unknown kind {:?} (expected one of: {})",
+                    kind,
+                    known.join(", ")
+                ))
+            })?;
+            patterns.push((Regex::new(pattern).expect("built-in pattern compiles"), kind.clone()));
+        }
+        let terms: Vec<String> = deny_list
+            .iter()
+            .map(|term| term.trim())
+            .filter(|term| !term.is_empty())
+            .map(regex::escape)
+            .collect();
+        if !terms.is_empty() {
+            let deny = Regex::new(&format!(r"(?i)\b(?:{})\b", terms.join("|")))
+                .map_err(|e| PrivacyError::Config(format!("privacy.deny_list: {}", e)))?;
+            patterns.push((deny, DENY_LIST_KIND.to_string()));
+        }
+        for (name, pattern) in custom {
+            let regex = Regex::new(pattern)
+                .map_err(|e| PrivacyError::Config(format!("privacy.patterns.{}: {}", name, e)))?;
+            patterns.push((regex, name.clone()));
+        }
+        Ok(PatternFilter { patterns })
+    }
+
+    /// A filter redacting exactly these literal spans (as flagged by the LLM filter).
+    pub fn literals(spans: &[(String, String)]) -> Self {
+        let patterns = spans
+            .iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(kind, text)| (Regex::new(&regex::escape(text)).expect("escaped literal compiles"), kind.clone()))
+            .collect();
+        PatternFilter { patterns }
+    }
+
+    /// Redact every match, synchronously.
+    pub fn apply(&self, content: &str) -> Filtered {
+        let matches = self
+            .patterns
+            .iter()
+            .flat_map(|(regex, kind)| regex.find_iter(content).map(move |m| (m.range(), kind.clone())))
+            .collect();
+        apply_matches(content, matches)
+    }
+}
+
+#[async_trait]
+impl ContentFilter for PatternFilter {
+    async fn filter(&self, content: &str) -> Result<Filtered, PrivacyError> {
+        Ok(self.apply(content))
+    }
+
+    fn name(&self) -> &str {
+        "patterns"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builtin() -> PatternFilter {
+        let kinds: Vec<String> = BUILTIN_PATTERNS.iter().map(|(name, _)| name.to_string()).collect();
+        PatternFilter::new(&kinds, &["Project Falcon".to_string()], &BTreeMap::new()).unwrap()
+    }
+
+    #[test]
+    fn redacts_builtin_kinds_and_deny_listed_terms() {
+        let filtered = builtin().apply(
+            "Mail jane.doe@example.co.uk or call +44 20 7946 0958 / (555) 123-4567 \
+             about project falcon; key sk-proj-abcdefghijklmnopqrstuvwx",
+        );
+        assert_eq!(
+            filtered.content,
+            "Mail [REDACTED:EMAIL] or call [REDACTED:PHONE] / [REDACTED:PHONE] \
+             about [REDACTED:DENY_LIST]; key [REDACTED:API_KEY]"
+        );
+        let kinds: Vec<&str> = filtered.redactions.iter().map(|r| r.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["email", "phone", "phone", "deny_list", "api_key"]);
+    }
+
+    #[test]
+    fn leaves_dates_versions_and_plain_numbers_alone() {
+        let content = "Released 2026-10-17 as v1.24.3, issue 4521, took 300 ms";
+        assert_eq!(builtin().apply(content).content, content);
+    }
+
+    #[test]
+    fn invalid_settings_are_config_errors() {
+        assert!(PatternFilter::new(&["ssn".to_string()], &[], &BTreeMap::new()).is_err());
+        let custom = BTreeMap::from([("ticket".to_string(), "(".to_string())]);
+        assert!(PatternFilter::new(&[], &[], &custom).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Health monitoring for query intelligence providers.
+//!
+//! `MonitoredProvider` wraps an expansion or reranking provider and counts consecutive
+//! failed calls — including calls abandoned when search's latency budget runs out. After
+//! `failure_threshold` of them the provider is disabled: search skips it instead of waiting
+//! for the timeout again. `spawn_health_probes` probes every monitored provider's endpoint in
+//! the background; a successful probe re-enables a disabled provider, and failed probes count
+//! as failures, so a dead endpoint is switched off before a search has to find out.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{ContextTurn, ExpandedQuery, QueryIntelligenceError, QueryIntelligenceProvider, RankedCandidate, RankedResult};
+use crate::health::{probe_endpoint, LlmEndpoint};
+use crate::shutdown::Shutdown;
+
+/// Snapshot of a monitored provider's health, reported by health_check.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProviderHealthStatus {
+    /// False while the provider is disabled after repeated failures
+    pub available: bool,
+    pub consecutive_failures: u32,
+    pub disabled_since: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_probe_at: Option<DateTime<Utc>>,
+    pub last_probe_ok: Option<bool>,
+}
+
+/// A query intelligence provider that disables itself after repeated failures.
+pub struct MonitoredProvider {
+    inner: Arc<dyn QueryIntelligenceProvider + Send + Sync>,
+    /// Subsystem name for logs: "query_expansion" or "reranking"
+    subsystem: String,
+    /// Endpoint probed by `probe` (None for the local cross-encoder)
+    endpoint: Option<LlmEndpoint>,
+    failure_threshold: u32,
+    state: Mutex<ProviderHealthStatus>,
+}
+
+impl MonitoredProvider {
+    /// `failure_threshold` consecutive failures disable the provider (minimum 1).
+    pub fn new(
+        inner: Arc<dyn QueryIntelligenceProvider + Send + Sync>,
+        subsystem: &str,
+        failure_threshold: u32,
+    ) -> Self {
+        MonitoredProvider {
+            inner,
+            subsystem: subsystem.to_string(),
+            endpoint: None,
+            failure_threshold: failure_threshold.max(1),
+            state: Mutex::new(ProviderHealthStatus { available: true, ..Default::default() }),
+        }
+    }
+
+    /// Probe this endpoint in `probe`; without one, a probe always succeeds.
+    pub fn with_probe_endpoint(mut self, endpoint: LlmEndpoint) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+
+    /// Check the endpoint once and update the provider's health with the result.
+    pub async fn probe(&self) {
+        let result = match &self.endpoint {
+            Some(endpoint) => probe_endpoint(&reqwest::Client::new(), endpoint).await.map(|_| ()),
+            None => Ok(()),
+        };
+        {
+            let mut state = self.state.lock().unwrap();
+            state.last_probe_at = Some(Utc::now());
+            state.last_probe_ok = Some(result.is_ok());
+        }
+        match result {
+            Ok(()) => self.record_success(),
+            Err(error) => self.record_failure(format!("Health probe failed: {}", error)),
+        }
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        if !state.available {
+            tracing::info!(subsystem = %self.subsystem, "Query intelligence provider re-enabled");
+        }
+        state.available = true;
+        state.consecutive_failures = 0;
+        state.disabled_since = None;
+    }
+
+    fn record_failure(&self, error: String) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        state.last_error = Some(error);
+        if state.available && state.consecutive_failures >= self.failure_threshold {
+            tracing::warn!(
+                subsystem = %self.subsystem,
+                failures = state.consecutive_failures,
+                error = ?state.last_error,
+                "Query intelligence provider disabled until a health probe succeeds"
+            );
+            state.available = false;
+            state.disabled_since = Some(Utc::now());
+        }
+    }
+
+    /// Run `call` against the inner provider, recording its outcome. A call dropped before it
+    /// finishes (search timed out waiting) counts as a failure.
+    async fn monitored<T, F>(&self, call: F) -> Result<T, QueryIntelligenceError>
+    where
+        F: std::future::Future<Output = Result<T, QueryIntelligenceError>>,
+    {
+        if !self.is_available() {
+            return Err(QueryIntelligenceError::Unavailable(self.subsystem.clone()));
+        }
+        let mut pending = PendingCall { provider: self, finished: false };
+        let result = call.await;
+        pending.finished = true;
+        match &result {
+            Ok(_) => self.record_success(),
+            Err(e) => self.record_failure(e.to_string()),
+        }
+        result
+    }
+}
+
+/// Records a failure for a monitored call that is dropped before it finishes.
+struct PendingCall<'a> {
+    provider: &'a MonitoredProvider,
+    finished: bool,
+}
+
+impl Drop for PendingCall<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.provider.record_failure("Call abandoned after exceeding the latency budget".to_string());
+        }
+    }
+}
+
+#[async_trait]
+impl QueryIntelligenceProvider for MonitoredProvider {
+    async fn expand(&self, query: &str) -> Result<ExpandedQuery, QueryIntelligenceError> {
+        self.monitored(self.inner.expand(query)).await
+    }
+
+    async fn expand_in_context(
+        &self,
+        query: &str,
+        context: &[ContextTurn],
+    ) -> Result<ExpandedQuery, QueryIntelligenceError> {
+        self.monitored(self.inner.expand_in_context(query, context)).await
+    }
+
+    async fn extract_query_entities(&self, query: &str) -> Result<Vec<String>, QueryIntelligenceError> {
+        self.monitored(self.inner.extract_query_entities(query)).await
+    }
+
+    async fn rerank(
+        &self,
+        query: &str,
+        candidates: &[RankedCandidate],
+    ) -> Result<Vec<RankedResult>, QueryIntelligenceError> {
+        self.monitored(self.inner.rerank(query, candidates)).await
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn is_available(&self) -> bool {
+        self.state.lock().unwrap().available
+    }
+
+    fn health(&self) -> Option<ProviderHealthStatus> {
+        Some(self.state.lock().unwrap().clone())
+    }
+}
+
+/// Probe `providers` every `interval` until shutdown.
+pub fn spawn_health_probes(providers: Vec<Arc<MonitoredProvider>>, interval: Duration, shutdown: Shutdown) {
+    if providers.is_empty() || interval.is_zero() {
+        return;
+    }
+    let task_shutdown = shutdown.clone();
+    shutdown.spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // The first tick fires immediately; providers start out enabled
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = task_shutdown.cancelled() => break,
+                _ = ticker.tick() => {}
+            }
+            for provider in &providers {
+                provider.probe().await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Flaky {
+        fail: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait]
+    impl QueryIntelligenceProvider for Flaky {
+        async fn expand(&self, query: &str) -> Result<ExpandedQuery, QueryIntelligenceError> {
+            if self.fail.load(std::sync::atomic::Ordering::Relaxed) {
+                return Err(QueryIntelligenceError::Timeout("down".to_string()));
+            }
+            Ok(ExpandedQuery { variants: vec![query.to_string()], time_range: None, standalone_query: None })
+        }
+
+        async fn expand_in_context(&self, query: &str, _context: &[ContextTurn]) -> Result<ExpandedQuery, QueryIntelligenceError> {
+            self.expand(query).await
+        }
+
+        async fn extract_query_entities(&self, _query: &str) -> Result<Vec<String>, QueryIntelligenceError> {
+            // Never finishes, like a hung endpoint
+            std::future::pending().await
+        }
+
+        async fn rerank(&self, _query: &str, _candidates: &[RankedCandidate]) -> Result<Vec<RankedResult>, QueryIntelligenceError> {
+            Ok(Vec::new())
+        }
+
+        fn model_name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    fn monitored(fail: bool) -> MonitoredProvider {
+        let inner = Arc::new(Flaky { fail: std::sync::atomic::AtomicBool::new(fail) });
+        MonitoredProvider::new(inner, "query_expansion", 2)
+    }
+
+    #[tokio::test]
+    async fn disables_after_consecutive_failures_and_reenables_on_probe() {
+        let provider = monitored(true);
+        assert!(provider.expand("q").await.is_err());
+        assert!(provider.is_available(), "one failure is below the threshold");
+        assert!(provider.expand("q").await.is_err());
+        assert!(!provider.is_available());
+        assert!(provider.health().unwrap().disabled_since.is_some());
+        assert!(matches!(provider.expand("q").await, Err(QueryIntelligenceError::Unavailable(_))));
+
+        // No endpoint to check: the probe succeeds and re-enables the provider
+        provider.probe().await;
+        let health = provider.health().unwrap();
+        assert!(health.available);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_probe_ok, Some(true));
+    }
+
+    #[tokio::test]
+    async fn abandoned_calls_count_as_failures() {
+        let provider = monitored(false);
+        for _ in 0..2 {
+            let timed_out = tokio::time::timeout(Duration::from_millis(5), provider.extract_query_entities("q")).await;
+            assert!(timed_out.is_err());
+        }
+        assert!(!provider.is_available());
+
+        provider.probe().await;
+        assert!(provider.expand("q").await.is_ok());
+        assert_eq!(provider.health().unwrap().consecutive_failures, 0);
+    }
+}
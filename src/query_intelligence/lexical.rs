@@ -0,0 +1,101 @@
+//! Deterministic lexical re-ranker
+//!
+//! Scores each candidate against the query with BM25 over the candidate set itself — no
+//! model, no I/O, microseconds for the re-ranking window. Used as the re-ranking fallback when
+//! the LLM re-ranker times out, fails, or the latency budget is already spent
+//! (query_intelligence.fallback_reranker = "lexical").
+
+use std::collections::{HashMap, HashSet};
+
+use super::{RankedCandidate, RankedResult};
+
+/// BM25 term-frequency saturation.
+const K1: f64 = 1.2;
+/// BM25 document-length normalization.
+const B: f64 = 0.75;
+
+/// Lowercased alphanumeric tokens, dropping single characters.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().count() > 1)
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// Rank candidates by BM25 score of the query terms, best first.
+///
+/// Document frequencies come from the candidates themselves. Ties (including candidates
+/// sharing no term with the query) keep their current rank order.
+pub fn lexical_rerank(query: &str, candidates: &[RankedCandidate]) -> Vec<RankedResult> {
+    let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+    let documents: Vec<Vec<String>> = candidates.iter().map(|c| tokenize(&c.content)).collect();
+
+    let n = documents.len() as f64;
+    let avg_len = (documents.iter().map(Vec::len).sum::<usize>() as f64 / n.max(1.0)).max(1.0);
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for doc in &documents {
+        let unique: HashSet<&str> = doc.iter().map(String::as_str).collect();
+        for term in unique.into_iter().filter(|t| query_terms.contains(*t)) {
+            *document_frequency.entry(term).or_default() += 1;
+        }
+    }
+
+    let mut scored: Vec<(f64, &RankedCandidate)> = candidates
+        .iter()
+        .zip(&documents)
+        .map(|(candidate, doc)| {
+            let len_norm = 1.0 - B + B * doc.len() as f64 / avg_len;
+            let score = document_frequency
+                .iter()
+                .map(|(term, &df)| {
+                    let tf = doc.iter().filter(|t| t == term).count() as f64;
+                    let idf = (1.0 + (n - df as f64 + 0.5) / (df as f64 + 0.5)).ln();
+                    idf * tf * (K1 + 1.0) / (tf + K1 * len_norm)
+                })
+                .sum();
+            (score, candidate)
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.1.current_rank.cmp(&b.1.current_rank))
+    });
+
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, candidate))| RankedResult { id: candidate.id.clone(), llm_rank: i + 1 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, content: &str, current_rank: usize) -> RankedCandidate {
+        RankedCandidate { id: id.to_string(), content: content.to_string(), current_rank }
+    }
+
+    #[test]
+    fn lexical_rerank_prefers_query_terms() {
+        let candidates = vec![
+            candidate("a", "The user likes hiking in the mountains", 1),
+            candidate("b", "Deploys run through the staging cluster before production", 2),
+            candidate("c", "Postgres backups run nightly on the staging cluster", 3),
+        ];
+        let ranked = lexical_rerank("how do staging deploys work", &candidates);
+        let order: Vec<&str> = ranked.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(order, vec!["b", "c", "a"]);
+        assert_eq!(ranked.iter().map(|r| r.llm_rank).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn lexical_rerank_keeps_order_without_overlap() {
+        let candidates = vec![candidate("x", "alpha beta", 1), candidate("y", "gamma delta", 2)];
+        let ranked = lexical_rerank("unrelated words", &candidates);
+        assert_eq!(ranked[0].id, "x");
+        assert_eq!(ranked[1].id, "y");
+        assert!(lexical_rerank("anything", &[]).is_empty());
+    }
+}
@@ -0,0 +1,130 @@
+//! Local cross-encoder re-ranking provider
+//!
+//! Scores (query, memory) pairs with a cross-encoder ONNX model via fastembed — no network
+//! calls and no chat model, so re-ranking costs tens of milliseconds instead of seconds.
+//! Re-ranking only: expand() and extract_query_entities() report NotConfigured, so it cannot
+//! serve as an expansion provider.
+//! All CPU-bound fastembed calls are wrapped in spawn_blocking to avoid blocking async runtime.
+
+use async_trait::async_trait;
+use fastembed::{RerankInitOptions, RerankerModel, TextRerank};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::task;
+
+use super::{
+    ContextTurn, ExpandedQuery, QueryIntelligenceError, QueryIntelligenceProvider, RankedCandidate,
+    RankedResult,
+};
+
+/// Map a configured model name to the fastembed cross-encoder it selects.
+fn reranker_model(name: &str) -> Result<RerankerModel, QueryIntelligenceError> {
+    match name {
+        "jina-reranker-v1-turbo-en" => Ok(RerankerModel::JINARerankerV1TurboEn),
+        "jina-reranker-v2-base-multilingual" => Ok(RerankerModel::JINARerankerV2BaseMultiligual),
+        "bge-reranker-base" => Ok(RerankerModel::BGERerankerBase),
+        "bge-reranker-v2-m3" => Ok(RerankerModel::BGERerankerV2M3),
+        other => Err(QueryIntelligenceError::NotConfigured(format!(
+            "Unknown local reranker model '{}' (expected jina-reranker-v1-turbo-en, \
+             jina-reranker-v2-base-multilingual, bge-reranker-base, or bge-reranker-v2-m3)",
+            other
+        ))),
+    }
+}
+
+/// Re-ranking provider backed by a local fastembed cross-encoder.
+pub struct LocalRerankingProvider {
+    model: Arc<Mutex<TextRerank>>,
+    name: String,
+}
+
+impl LocalRerankingProvider {
+    /// Create a new LocalRerankingProvider, downloading model weights if not cached.
+    ///
+    /// # Arguments
+    /// * `cache_dir` - Directory to cache model weights (shared with the local embedding model)
+    /// * `model` - Cross-encoder name, e.g. "jina-reranker-v1-turbo-en"
+    pub async fn new(cache_dir: &str, model: &str) -> Result<Self, QueryIntelligenceError> {
+        let reranker = reranker_model(model)?;
+        let cache_path = PathBuf::from(cache_dir);
+
+        let tr = task::spawn_blocking(move || {
+            TextRerank::try_new(
+                RerankInitOptions::new(reranker)
+                    .with_cache_dir(cache_path)
+                    .with_show_download_progress(true),
+            )
+        })
+        .await
+        .map_err(|e| QueryIntelligenceError::NotConfigured(e.to_string()))?
+        .map_err(|e| QueryIntelligenceError::NotConfigured(e.to_string()))?;
+
+        Ok(LocalRerankingProvider {
+            model: Arc::new(Mutex::new(tr)),
+            name: model.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl QueryIntelligenceProvider for LocalRerankingProvider {
+    async fn expand(&self, _query: &str) -> Result<ExpandedQuery, QueryIntelligenceError> {
+        Err(QueryIntelligenceError::NotConfigured(
+            "The local cross-encoder only supports re-ranking".to_string(),
+        ))
+    }
+
+    async fn expand_in_context(
+        &self,
+        _query: &str,
+        _context: &[ContextTurn],
+    ) -> Result<ExpandedQuery, QueryIntelligenceError> {
+        Err(QueryIntelligenceError::NotConfigured(
+            "The local cross-encoder only supports re-ranking".to_string(),
+        ))
+    }
+
+    async fn extract_query_entities(&self, _query: &str) -> Result<Vec<String>, QueryIntelligenceError> {
+        Err(QueryIntelligenceError::NotConfigured(
+            "The local cross-encoder only supports re-ranking".to_string(),
+        ))
+    }
+
+    async fn rerank(
+        &self,
+        query: &str,
+        candidates: &[RankedCandidate],
+    ) -> Result<Vec<RankedResult>, QueryIntelligenceError> {
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let model = Arc::clone(&self.model);
+        let query = query.to_string();
+        let documents: Vec<String> = candidates.iter().map(|c| c.content.clone()).collect();
+
+        // fastembed returns results sorted by score, most relevant first
+        let scored = task::spawn_blocking(move || {
+            let mut model = model.lock().unwrap();
+            model
+                .rerank(query, documents, false, None)
+                .map_err(|e| QueryIntelligenceError::Generation(e.to_string()))
+        })
+        .await
+        .map_err(|e| QueryIntelligenceError::Generation(format!("spawn_blocking panicked: {}", e)))??;
+
+        Ok(scored
+            .into_iter()
+            .filter_map(|r| candidates.get(r.index))
+            .enumerate()
+            .map(|(idx, c)| RankedResult {
+                id: c.id.clone(),
+                llm_rank: idx + 1,
+            })
+            .collect())
+    }
+
+    fn model_name(&self) -> &str {
+        &self.name
+    }
+}
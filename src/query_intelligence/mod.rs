@@ -1,11 +1,16 @@
 /// Query intelligence provider trait and supporting types
 ///
 /// Provides a pluggable interface for LLM-based query expansion and re-ranking.
-/// Supports Ollama (local, default, no API key) and OpenAI-compatible APIs.
+/// Supports Ollama (local, default, no API key) and OpenAI-compatible APIs, plus a local
+/// cross-encoder for re-ranking only. A deterministic lexical re-ranker can stand in when the
+/// LLM re-ranker misses its latency budget.
 ///
 /// Both features are disabled by default — set expansion_enabled or reranking_enabled
 /// in QueryIntelligenceConfig to opt in.
 
+pub mod health;
+pub mod lexical;
+pub mod local;
 pub mod ollama;
 pub mod openai;
 pub mod temporal;
@@ -34,6 +39,20 @@ pub enum QueryIntelligenceError {
     /// Operation exceeded latency budget
     #[error("Query intelligence timeout: {0}")]
     Timeout(String),
+
+    /// Provider disabled after repeated failures until a health probe succeeds
+    #[error("Query intelligence provider '{0}' is temporarily disabled after repeated failures")]
+    Unavailable(String),
+}
+
+impl From<crate::llm_client::LlmError> for QueryIntelligenceError {
+    fn from(e: crate::llm_client::LlmError) -> Self {
+        match e {
+            crate::llm_client::LlmError::Api { status, message } => QueryIntelligenceError::Api { status, message },
+            crate::llm_client::LlmError::Timeout(_) => QueryIntelligenceError::Timeout(e.to_string()),
+            other => QueryIntelligenceError::Generation(other.to_string()),
+        }
+    }
 }
 
 impl From<QueryIntelligenceError> for MemcpError {
@@ -58,8 +77,22 @@ pub struct ExpandedQuery {
     pub variants: Vec<String>,
     /// Optional time range extracted from temporal hints in the query
     pub time_range: Option<TimeRange>,
+    /// The query rewritten to stand on its own, with pronouns and ellipsis resolved from the
+    /// conversation (set by `expand_in_context` only)
+    pub standalone_query: Option<String>,
+}
+
+/// A recent conversation turn given as context for a follow-up search query.
+#[derive(Debug, Clone)]
+pub struct ContextTurn {
+    /// Speaker role, e.g. "user" or "assistant"
+    pub role: String,
+    pub content: String,
 }
 
+/// Most characters of each context turn included in the contextual expansion prompt.
+pub const MAX_CONTEXT_TURN_CHARS: usize = 1000;
+
 /// A candidate memory for re-ranking.
 #[derive(Debug, Clone)]
 pub struct RankedCandidate {
@@ -89,6 +122,19 @@ pub trait QueryIntelligenceProvider: Send + Sync {
     /// Expand a query into variants and extract any temporal hints.
     async fn expand(&self, query: &str) -> Result<ExpandedQuery, QueryIntelligenceError>;
 
+    /// Rewrite a follow-up query into a standalone one using the preceding conversation
+    /// (resolving pronouns and ellipsis like "what did he say about it?"), then expand it like
+    /// `expand`. The rewrite is returned in `standalone_query`.
+    async fn expand_in_context(
+        &self,
+        query: &str,
+        context: &[ContextTurn],
+    ) -> Result<ExpandedQuery, QueryIntelligenceError>;
+
+    /// Extract the named entities a query refers to (people, projects, tools, places), so the
+    /// symbolic search leg can match them against tags and extracted entities.
+    async fn extract_query_entities(&self, query: &str) -> Result<Vec<String>, QueryIntelligenceError>;
+
     /// Re-rank retrieved candidates, returning them in LLM-preferred order.
     async fn rerank(
         &self,
@@ -98,6 +144,17 @@ pub trait QueryIntelligenceProvider: Send + Sync {
 
     /// Return the model name identifier used by this provider.
     fn model_name(&self) -> &str;
+
+    /// Whether search should call this provider now (false while a `health::MonitoredProvider`
+    /// has it disabled).
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// Health state for health_check, when the provider is monitored.
+    fn health(&self) -> Option<health::ProviderHealthStatus> {
+        None
+    }
 }
 
 /// Build the query expansion prompt.
@@ -118,6 +175,54 @@ pub fn build_expansion_prompt(query: &str, current_date: &str) -> String {
     )
 }
 
+/// Build the contextual query expansion prompt.
+///
+/// Like `build_expansion_prompt`, but first asks for the query rewritten so it can be
+/// understood without the conversation. Each turn is cut to MAX_CONTEXT_TURN_CHARS.
+pub fn build_contextual_expansion_prompt(query: &str, context: &[ContextTurn], current_date: &str) -> String {
+    let conversation: String = context
+        .iter()
+        .map(|turn| {
+            let content: String = turn.content.trim().chars().take(MAX_CONTEXT_TURN_CHARS).collect();
+            format!("{}: {}\n", turn.role.trim(), content)
+        })
+        .collect();
+    format!(
+        "You are helping an AI assistant search its own memory bank.\n\
+         Today's date: {current_date}\n\n\
+         The search query below is the latest message of the conversation shown before it, \
+         and may only make sense in that context. Do three things:\n\
+         1. Rewrite the query as a standalone search query: replace pronouns and vague \
+            references (\"he\", \"it\", \"that project\") with what they refer to in the \
+            conversation, and fill in anything left implied. Keep it unchanged if it already \
+            stands on its own.\n\
+         2. Generate 2-3 alternative phrasings of the standalone query that would help \
+            retrieve relevant memories.\n\
+         3. If the query contains a temporal hint (e.g. 'last week', 'yesterday', \
+            'after 2024-01-01'), extract it as a time range with ISO-8601 after/before fields.\n\n\
+         Output only valid JSON matching the provided schema: \
+         {{\"standalone_query\": \"...\", \"variants\": [\"...\"], \
+         \"time_range\": {{\"after\": \"...\", \"before\": \"...\"}}}} \
+         (omit time_range when there is no temporal hint). Do not add commentary.\n\n\
+         Conversation:\n{conversation}\n\
+         Query: {query}"
+    )
+}
+
+/// Build the query entity extraction prompt.
+pub fn build_entity_prompt(query: &str) -> String {
+    format!(
+        "You are helping an AI assistant search its own memory bank.\n\
+         List the named entities the search query below refers to: people, projects, \
+         products, tools, organizations, and places. Use the names as they would appear \
+         in a note (e.g. \"PostgreSQL\", not \"the database\"). Return an empty list if \
+         there are none.\n\n\
+         Output only valid JSON matching the provided schema: \
+         {{\"entities\": [\"name1\", \"name2\", ...]}}. Do not add commentary.\n\n\
+         Query: {query}"
+    )
+}
+
 /// Build the re-ranking prompt.
 ///
 /// Instructs the LLM to re-order candidate memories by relevance to the query.
@@ -165,6 +270,57 @@ pub fn expansion_schema() -> serde_json::Value {
     })
 }
 
+/// JSON schema for contextual expansion output: the expansion schema plus a required
+/// `standalone_query`.
+pub fn contextual_expansion_schema() -> serde_json::Value {
+    let mut schema = expansion_schema();
+    schema["properties"]["standalone_query"] = serde_json::json!({
+        "type": "string",
+        "description": "The query rewritten to be understood without the conversation"
+    });
+    schema["required"] = serde_json::json!(["standalone_query", "variants"]);
+    schema
+}
+
+/// JSON schema for query entity extraction output.
+pub fn entity_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "entities": {
+                "type": "array",
+                "items": { "type": "string" },
+                "maxItems": MAX_QUERY_ENTITIES,
+                "description": "Named entities mentioned in the query"
+            }
+        },
+        "required": ["entities"]
+    })
+}
+
+/// Maximum extracted entities added to the symbolic search leg.
+pub const MAX_QUERY_ENTITIES: usize = 8;
+
+/// Clean up extracted query entities: trim, drop empties and the query itself, dedupe
+/// case-insensitively (first spelling wins), and cap at MAX_QUERY_ENTITIES.
+pub fn normalize_query_entities(entities: Vec<String>, query: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for entity in entities {
+        let entity = entity.trim();
+        if entity.is_empty()
+            || entity.eq_ignore_ascii_case(query.trim())
+            || out.iter().any(|e| e.eq_ignore_ascii_case(entity))
+        {
+            continue;
+        }
+        out.push(entity.to_string());
+        if out.len() == MAX_QUERY_ENTITIES {
+            break;
+        }
+    }
+    out
+}
+
 /// JSON schema for re-ranking output.
 ///
 /// `ranked_ids` must contain all candidate IDs, most relevant first.
@@ -181,3 +337,40 @@ pub fn reranking_schema() -> serde_json::Value {
         "required": ["ranked_ids"]
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contextual_expansion_prompt_includes_truncated_turns() {
+        let context = vec![
+            ContextTurn { role: "user".to_string(), content: "I met Alice about the billing migration".to_string() },
+            ContextTurn { role: "assistant".to_string(), content: "x".repeat(MAX_CONTEXT_TURN_CHARS + 50) },
+        ];
+        let prompt = build_contextual_expansion_prompt("what did she say about it?", &context, "2026-01-01");
+        assert!(prompt.contains("user: I met Alice about the billing migration\n"));
+        assert!(prompt.contains(&format!("assistant: {}\n", "x".repeat(MAX_CONTEXT_TURN_CHARS))));
+        assert!(!prompt.contains(&"x".repeat(MAX_CONTEXT_TURN_CHARS + 1)));
+        assert!(prompt.ends_with("Query: what did she say about it?"));
+
+        let schema = contextual_expansion_schema();
+        assert_eq!(schema["required"], serde_json::json!(["standalone_query", "variants"]));
+        assert!(schema["properties"]["time_range"].is_object());
+    }
+
+    #[test]
+    fn normalize_query_entities_dedupes_and_drops_the_query() {
+        let entities = vec![
+            " PostgreSQL ".to_string(),
+            "postgresql".to_string(),
+            String::new(),
+            "Why is postgres slow".to_string(),
+            "Alice".to_string(),
+        ];
+        assert_eq!(
+            normalize_query_entities(entities, "why is postgres slow"),
+            vec!["PostgreSQL".to_string(), "Alice".to_string()]
+        );
+    }
+}
@@ -8,10 +8,14 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::config::LlmConfig;
+use crate::llm_client::LlmClient;
+
 use super::{
-    ExpandedQuery, QueryIntelligenceError, QueryIntelligenceProvider, RankedCandidate,
-    RankedResult, TimeRange, build_expansion_prompt, build_reranking_prompt, expansion_schema,
-    reranking_schema,
+    ContextTurn, ExpandedQuery, QueryIntelligenceError, QueryIntelligenceProvider, RankedCandidate,
+    RankedResult, TimeRange, build_contextual_expansion_prompt, build_entity_prompt,
+    build_expansion_prompt, build_reranking_prompt, contextual_expansion_schema, entity_schema,
+    expansion_schema, reranking_schema,
 };
 
 // --- HTTP request/response structs (local — mirrors extraction/ollama.rs pattern) ---
@@ -56,12 +60,29 @@ struct ExpandedQueryOutput {
     time_range: Option<TimeRangeOutput>,
 }
 
+/// Parsed contextual query expansion output from LLM
+#[derive(Deserialize)]
+struct ContextualExpansionOutput {
+    #[serde(default)]
+    standalone_query: String,
+    #[serde(default)]
+    variants: Vec<String>,
+    time_range: Option<TimeRangeOutput>,
+}
+
 #[derive(Deserialize)]
 struct TimeRangeOutput {
     after: Option<String>,
     before: Option<String>,
 }
 
+/// Parsed query entity extraction output from LLM
+#[derive(Deserialize)]
+struct EntityOutput {
+    #[serde(default)]
+    entities: Vec<String>,
+}
+
 /// Parsed re-ranking output from LLM
 #[derive(Deserialize)]
 struct RerankOutput {
@@ -76,7 +97,7 @@ struct RerankOutput {
 /// Uses /api/chat with structured JSON output (format field) for both
 /// query expansion and result re-ranking.
 pub struct OllamaQueryIntelligenceProvider {
-    client: reqwest::Client,
+    client: LlmClient,
     base_url: String,
     model: String,
 }
@@ -89,12 +110,18 @@ impl OllamaQueryIntelligenceProvider {
     /// * `model` - Model name (e.g., "llama3.2:3b")
     pub fn new(base_url: String, model: String) -> Self {
         OllamaQueryIntelligenceProvider {
-            client: reqwest::Client::new(),
+            client: LlmClient::new(&LlmConfig::default()),
             base_url,
             model,
         }
     }
 
+    /// Route requests through a configured `LlmClient` (retries, timeout, circuit breaker).
+    pub fn with_llm_client(mut self, client: LlmClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// POST to Ollama /api/chat with a given prompt and schema, return content string.
     async fn chat(
         &self,
@@ -114,28 +141,7 @@ impl OllamaQueryIntelligenceProvider {
 
         let url = format!("{}/api/chat", self.base_url);
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| {
-                QueryIntelligenceError::Generation(format!("HTTP request failed: {}", e))
-            })?;
-
-        let status = response.status().as_u16();
-        if !response.status().is_success() {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "unknown error".to_string());
-            return Err(QueryIntelligenceError::Api { status, message: body });
-        }
-
-        let chat_response: OllamaChatResponse = response.json().await.map_err(|e| {
-            QueryIntelligenceError::Generation(format!("Failed to parse Ollama response: {}", e))
-        })?;
+        let chat_response: OllamaChatResponse = self.client.post_json(&url, None, &request).await?;
 
         Ok(chat_response.message.content)
     }
@@ -179,9 +185,59 @@ impl QueryIntelligenceProvider for OllamaQueryIntelligenceProvider {
         Ok(ExpandedQuery {
             variants: output.variants,
             time_range,
+            standalone_query: None,
+        })
+    }
+
+    async fn expand_in_context(
+        &self,
+        query: &str,
+        context: &[ContextTurn],
+    ) -> Result<ExpandedQuery, QueryIntelligenceError> {
+        let current_date = Utc::now().format("%Y-%m-%d").to_string();
+        let prompt = build_contextual_expansion_prompt(query, context, &current_date);
+
+        let content = self.chat(prompt, contextual_expansion_schema()).await?;
+
+        let output: ContextualExpansionOutput = serde_json::from_str(&content).map_err(|e| {
+            QueryIntelligenceError::Generation(format!(
+                "Failed to parse contextual expansion JSON from model output: {} (content: {})",
+                e, &content
+            ))
+        })?;
+
+        let standalone_query = output.standalone_query.trim();
+        if standalone_query.is_empty() {
+            return Err(QueryIntelligenceError::Generation(
+                "LLM returned no standalone query".to_string(),
+            ));
+        }
+
+        let time_range = output.time_range.map(|tr| TimeRange {
+            after: parse_datetime_opt(tr.after),
+            before: parse_datetime_opt(tr.before),
+        });
+
+        Ok(ExpandedQuery {
+            variants: output.variants,
+            time_range,
+            standalone_query: Some(standalone_query.to_string()),
         })
     }
 
+    async fn extract_query_entities(&self, query: &str) -> Result<Vec<String>, QueryIntelligenceError> {
+        let content = self.chat(build_entity_prompt(query), entity_schema()).await?;
+
+        let output: EntityOutput = serde_json::from_str(&content).map_err(|e| {
+            QueryIntelligenceError::Generation(format!(
+                "Failed to parse entity JSON from model output: {} (content: {})",
+                e, &content
+            ))
+        })?;
+
+        Ok(output.entities)
+    }
+
     async fn rerank(
         &self,
         query: &str,
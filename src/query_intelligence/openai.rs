@@ -8,9 +8,13 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::config::LlmConfig;
+use crate::llm_client::LlmClient;
+
 use super::{
-    ExpandedQuery, QueryIntelligenceError, QueryIntelligenceProvider, RankedCandidate,
-    RankedResult, TimeRange, build_expansion_prompt, build_reranking_prompt,
+    ContextTurn, ExpandedQuery, QueryIntelligenceError, QueryIntelligenceProvider, RankedCandidate,
+    RankedResult, TimeRange, build_contextual_expansion_prompt, build_entity_prompt,
+    build_expansion_prompt, build_reranking_prompt,
 };
 
 // --- HTTP request/response structs (local — mirrors extraction/openai.rs pattern) ---
@@ -59,12 +63,29 @@ struct ExpandedQueryOutput {
     time_range: Option<TimeRangeOutput>,
 }
 
+/// Parsed contextual query expansion output from LLM
+#[derive(Deserialize)]
+struct ContextualExpansionOutput {
+    #[serde(default)]
+    standalone_query: String,
+    #[serde(default)]
+    variants: Vec<String>,
+    time_range: Option<TimeRangeOutput>,
+}
+
 #[derive(Deserialize)]
 struct TimeRangeOutput {
     after: Option<String>,
     before: Option<String>,
 }
 
+/// Parsed query entity extraction output from LLM
+#[derive(Deserialize)]
+struct EntityOutput {
+    #[serde(default)]
+    entities: Vec<String>,
+}
+
 /// Parsed re-ranking output from LLM
 #[derive(Deserialize)]
 struct RerankOutput {
@@ -80,7 +101,7 @@ struct RerankOutput {
 /// base_url is configurable — not hardcoded — enabling Kimi Code API and other
 /// OpenAI-compatible endpoints in addition to api.openai.com.
 pub struct OpenAIQueryIntelligenceProvider {
-    client: reqwest::Client,
+    client: LlmClient,
     /// Configurable base URL — supports Kimi and other OpenAI-compatible APIs
     base_url: String,
     api_key: String,
@@ -111,13 +132,19 @@ impl OpenAIQueryIntelligenceProvider {
         }
 
         Ok(OpenAIQueryIntelligenceProvider {
-            client: reqwest::Client::new(),
+            client: LlmClient::new(&LlmConfig::default()),
             base_url,
             api_key,
             model,
         })
     }
 
+    /// Route requests through a configured `LlmClient` (retries, timeout, circuit breaker).
+    pub fn with_llm_client(mut self, client: LlmClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// POST to {base_url}/chat/completions with json_object response format.
     async fn chat(&self, prompt: String) -> Result<String, QueryIntelligenceError> {
         let request = ChatRequest {
@@ -133,29 +160,10 @@ impl OpenAIQueryIntelligenceProvider {
 
         let url = format!("{}/chat/completions", self.base_url);
 
-        let response = self
+        let chat_response: ChatResponse = self
             .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| {
-                QueryIntelligenceError::Generation(format!("HTTP request failed: {}", e))
-            })?;
-
-        let status = response.status().as_u16();
-        if !response.status().is_success() {
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "unknown error".to_string());
-            return Err(QueryIntelligenceError::Api { status, message: body });
-        }
-
-        let chat_response: ChatResponse = response.json().await.map_err(|e| {
-            QueryIntelligenceError::Generation(format!("Failed to parse OpenAI response: {}", e))
-        })?;
+            .post_json(&url, Some(&self.api_key), &request)
+            .await?;
 
         let content = chat_response
             .choices
@@ -210,9 +218,59 @@ impl QueryIntelligenceProvider for OpenAIQueryIntelligenceProvider {
         Ok(ExpandedQuery {
             variants: output.variants,
             time_range,
+            standalone_query: None,
         })
     }
 
+    async fn expand_in_context(
+        &self,
+        query: &str,
+        context: &[ContextTurn],
+    ) -> Result<ExpandedQuery, QueryIntelligenceError> {
+        let current_date = Utc::now().format("%Y-%m-%d").to_string();
+        let prompt = build_contextual_expansion_prompt(query, context, &current_date);
+
+        let content = self.chat(prompt).await?;
+
+        let output: ContextualExpansionOutput = serde_json::from_str(&content).map_err(|e| {
+            QueryIntelligenceError::Generation(format!(
+                "Failed to parse contextual expansion JSON from model output: {} (content: {})",
+                e, &content
+            ))
+        })?;
+
+        let standalone_query = output.standalone_query.trim();
+        if standalone_query.is_empty() {
+            return Err(QueryIntelligenceError::Generation(
+                "LLM returned no standalone query".to_string(),
+            ));
+        }
+
+        let time_range = output.time_range.map(|tr| TimeRange {
+            after: parse_datetime_opt(tr.after),
+            before: parse_datetime_opt(tr.before),
+        });
+
+        Ok(ExpandedQuery {
+            variants: output.variants,
+            time_range,
+            standalone_query: Some(standalone_query.to_string()),
+        })
+    }
+
+    async fn extract_query_entities(&self, query: &str) -> Result<Vec<String>, QueryIntelligenceError> {
+        let content = self.chat(build_entity_prompt(query)).await?;
+
+        let output: EntityOutput = serde_json::from_str(&content).map_err(|e| {
+            QueryIntelligenceError::Generation(format!(
+                "Failed to parse entity JSON from model output: {} (content: {})",
+                e, &content
+            ))
+        })?;
+
+        Ok(output.entities)
+    }
+
     async fn rerank(
         &self,
         query: &str,
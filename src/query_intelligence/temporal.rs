@@ -5,19 +5,259 @@
 /// the candidate set before vector similarity search.
 ///
 /// All patterns are matched case-insensitively against the full query string.
+/// Anchors ("since Tuesday") and explicit ranges ("between March and May", "Q3 2023")
+/// win over calendar periods ("this week", "two weeks ago"), which win over rolling
+/// windows ("last month").
+/// Calendar weeks begin on a configurable weekday (query_intelligence.week_start).
 
-use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc, Weekday};
 use regex::Regex;
 
 use super::TimeRange;
 
-/// Parse a temporal hint from the query string relative to `now`.
+/// Counts accepted before a unit: digits, number words up to twelve, or "a couple of".
+const COUNT: &str = r"(\d+|an?|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve|(?:a\s+)?couple(?:\s+of)?)";
+const UNIT: &str = r"(day|week|month|year)s?";
+const MONTHS: &str = r"(january|february|march|april|may|june|july|august|september|october|november|december|jan|feb|mar|apr|jun|jul|aug|sept|sep|oct|nov|dec)";
+const WEEKDAYS: &str = r"(monday|tuesday|wednesday|thursday|friday|saturday|sunday|mon|tues|tue|wed|thurs|thur|thu|fri|sat|sun)";
+/// Optional year after a month or quarter: "2023", "of 2023", "last year", "this year".
+const YEAR_SUFFIX: &str = r"(?:\s+(?:of\s+)?(\d{4}|last\s+year|this\s+year))?";
+
+fn compile(pattern: String) -> Regex {
+    Regex::new(&pattern).expect("temporal pattern must compile")
+}
+
+static BETWEEN_DATES_RE: LazyLock<Regex> = LazyLock::new(|| {
+    compile(r"\bbetween\s+(\d{4}-\d{2}-\d{2})\s+and\s+(\d{4}-\d{2}-\d{2})\b".to_string())
+});
+static BETWEEN_MONTHS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    compile(format!(r"\bbetween\s+{MONTHS}(?:\s+(\d{{4}}))?\s+and\s+{MONTHS}(?:\s+(\d{{4}}))?\b"))
+});
+static QUARTER_RE: LazyLock<Regex> = LazyLock::new(|| compile(format!(r"\bq([1-4]){YEAR_SUFFIX}\b")));
+static ORDINAL_QUARTER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    compile(format!(r"\b(first|second|third|fourth|1st|2nd|3rd|4th)\s+quarter{YEAR_SUFFIX}\b"))
+});
+static PREVIOUS_QUARTER_RE: LazyLock<Regex> =
+    LazyLock::new(|| compile(r"\b(?:last|previous)\s+quarter\b".to_string()));
+static MONTH_YEAR_RE: LazyLock<Regex> = LazyLock::new(|| compile(format!(r"\b{MONTHS}\s+(\d{{4}})\b")));
+static IN_MONTH_RE: LazyLock<Regex> =
+    LazyLock::new(|| compile(format!(r"\b(?:in|during)\s+{MONTHS}{YEAR_SUFFIX}\b")));
+static IN_YEAR_RE: LazyLock<Regex> =
+    LazyLock::new(|| compile(r"\b(?:in|during)\s+((?:19|20)\d{2})\b".to_string()));
+static THIS_PERIOD_RE: LazyLock<Regex> =
+    LazyLock::new(|| compile(r"\bthis\s+(week|month|quarter|year)\b".to_string()));
+static AGO_RE: LazyLock<Regex> = LazyLock::new(|| compile(format!(r"\b{COUNT}\s+{UNIT}\s+ago\b")));
+static LAST_N_RE: LazyLock<Regex> =
+    LazyLock::new(|| compile(format!(r"\b(?:last|past|previous)\s+{COUNT}\s+{UNIT}\b")));
+static SINCE_RE: LazyLock<Regex> =
+    LazyLock::new(|| compile(r"\bsince\s+(?:last\s+)?([\w-]+)(?:\s+(\d{4}))?".to_string()));
+static ON_DATE_RE: LazyLock<Regex> = LazyLock::new(|| compile(r"\bon\s+(\d{4}-\d{2}-\d{2})\b".to_string()));
+static ON_WEEKDAY_RE: LazyLock<Regex> =
+    LazyLock::new(|| compile(format!(r"\b(?:last|on|this\s+past)\s+{WEEKDAYS}\b")));
+static AFTER_RE: LazyLock<Regex> = LazyLock::new(|| compile(r"after\s+(\d{4}-\d{2}-\d{2})".to_string()));
+static BEFORE_RE: LazyLock<Regex> = LazyLock::new(|| compile(r"before\s+(\d{4}-\d{2}-\d{2})".to_string()));
+
+/// Parse a temporal hint from the query string relative to `now`, with weeks starting Monday.
 ///
 /// Returns `Some(TimeRange)` if a recognized time expression is found,
 /// or `None` if no pattern matches. Only the first matching pattern is used.
 pub fn parse_temporal_hint(query: &str, now: DateTime<Utc>) -> Option<TimeRange> {
+    parse_temporal_hint_with_week_start(query, now, Weekday::Mon)
+}
+
+/// Parse a temporal hint, treating `week_start` as the first day of a calendar week.
+///
+/// The week start only affects calendar-week expressions ("this week", "two weeks ago").
+pub fn parse_temporal_hint_with_week_start(
+    query: &str,
+    now: DateTime<Utc>,
+    week_start: Weekday,
+) -> Option<TimeRange> {
     let q = query.to_lowercase();
 
+    anchored_start(&q, now)
+        .or_else(|| explicit_range(&q, now))
+        .or_else(|| calendar_period(&q, now, week_start))
+        .or_else(|| relative_count(&q, now, week_start))
+        .or_else(|| named_period(&q, now))
+}
+
+/// Parse a configured week start ("monday", "sun", ...), defaulting to Monday.
+pub fn parse_week_start(name: &str) -> Weekday {
+    parse_weekday_name(&name.trim().to_lowercase()).unwrap_or(Weekday::Mon)
+}
+
+/// "between DATE and DATE", "between MONTH and MONTH", quarters, and named months or years.
+fn explicit_range(q: &str, now: DateTime<Utc>) -> Option<TimeRange> {
+    if let Some(cap) = BETWEEN_DATES_RE.captures(q) {
+        let start = parse_iso_date(&cap[1])?;
+        let end = parse_iso_date(&cap[2])?;
+        return span(start, end.succ_opt()?);
+    }
+
+    // e.g., "between January and March", "between november 2023 and february"
+    if let Some(cap) = BETWEEN_MONTHS_RE.captures(q) {
+        let m1 = parse_month_name(&cap[1])?;
+        let m2 = parse_month_name(&cap[3])?;
+        let y1 = cap.get(2).and_then(|y| y.as_str().parse::<i32>().ok());
+        let y2 = cap.get(4).and_then(|y| y.as_str().parse::<i32>().ok());
+        // A range that wraps the new year ("november and february") starts the year before
+        let wraps = i32::from(m1 > m2);
+        let (y1, y2) = match (y1, y2) {
+            (Some(a), Some(b)) => (a, b),
+            (Some(a), None) => (a, a + wraps),
+            (None, Some(b)) => (b - wraps, b),
+            (None, None) => (now.year() - wraps, now.year()),
+        };
+        let (end_year, end_month) = add_months(y2, m2, 1);
+        return span(month_start(y1, m1)?, month_start(end_year, end_month)?);
+    }
+
+    let quarter = QUARTER_RE
+        .captures(q)
+        .map(|cap| (cap[1].parse::<u32>().ok(), cap.get(2).map(|y| y.as_str().to_string())))
+        .or_else(|| {
+            ORDINAL_QUARTER_RE.captures(q).map(|cap| {
+                let n = match &cap[1] {
+                    "first" | "1st" => 1,
+                    "second" | "2nd" => 2,
+                    "third" | "3rd" => 3,
+                    _ => 4,
+                };
+                (Some(n), cap.get(2).map(|y| y.as_str().to_string()))
+            })
+        });
+    if let Some((Some(n), year)) = quarter {
+        let first_month = 3 * n - 2;
+        let year = resolve_year(year.as_deref(), now)
+            .unwrap_or_else(|| most_recent_year(now, first_month));
+        return quarter_range(year, n);
+    }
+
+    if PREVIOUS_QUARTER_RE.is_match(q) {
+        let current = quarter_of(now.month());
+        let (year, n) = if current == 1 { (now.year() - 1, 4) } else { (now.year(), current - 1) };
+        return quarter_range(year, n);
+    }
+
+    // "march 2023" needs no preposition; a bare month needs "in"/"during" ("may" is a verb)
+    if let Some(cap) = MONTH_YEAR_RE.captures(q) {
+        let month = parse_month_name(&cap[1])?;
+        return month_range(cap[2].parse().ok()?, month);
+    }
+    if let Some(cap) = IN_MONTH_RE.captures(q) {
+        let month = parse_month_name(&cap[1])?;
+        let year = resolve_year(cap.get(2).map(|y| y.as_str()), now)
+            .unwrap_or_else(|| most_recent_year(now, month));
+        return month_range(year, month);
+    }
+
+    if let Some(cap) = IN_YEAR_RE.captures(q) {
+        let year: i32 = cap[1].parse().ok()?;
+        return span(NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year + 1, 1, 1)?);
+    }
+
+    None
+}
+
+/// "this week/month/quarter/year": from the start of the current period, open-ended.
+fn calendar_period(q: &str, now: DateTime<Utc>, week_start: Weekday) -> Option<TimeRange> {
+    let cap = THIS_PERIOD_RE.captures(q)?;
+    let today = now.date_naive();
+    let start = match &cap[1] {
+        "week" => start_of_week(today, week_start),
+        "month" => month_start(now.year(), now.month())?,
+        "quarter" => month_start(now.year(), 3 * quarter_of(now.month()) - 2)?,
+        _ => NaiveDate::from_ymd_opt(now.year(), 1, 1)?,
+    };
+    Some(TimeRange {
+        after: Some(day_start(start)?),
+        before: None,
+    })
+}
+
+/// "two weeks ago" (the calendar period that long ago) and "last 3 days" (a rolling window).
+fn relative_count(q: &str, now: DateTime<Utc>, week_start: Weekday) -> Option<TimeRange> {
+    if let Some(cap) = AGO_RE.captures(q) {
+        let n = parse_count(&cap[1])?;
+        let today = now.date_naive();
+        return match &cap[2] {
+            "day" => day_range(today - Duration::days(n)),
+            "week" => {
+                let start = start_of_week(today, week_start) - Duration::weeks(n);
+                span(start, start + Duration::weeks(1))
+            }
+            "month" => {
+                let (year, month) = add_months(now.year(), now.month(), -(n as i32));
+                month_range(year, month)
+            }
+            _ => {
+                let year = now.year() - n as i32;
+                span(NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year + 1, 1, 1)?)
+            }
+        };
+    }
+
+    if let Some(cap) = LAST_N_RE.captures(q) {
+        let n = parse_count(&cap[1])?;
+        // Same day counts as the rolling "last week/month/year" windows below
+        let days = match &cap[2] {
+            "day" => n,
+            "week" => 7 * n,
+            "month" => 30 * n,
+            _ => 365 * n,
+        };
+        return Some(TimeRange {
+            after: Some(now - Duration::days(days)),
+            before: None,
+        });
+    }
+
+    None
+}
+
+/// "since Tuesday/March/2023/2024-01-05", "on 2024-01-05", and "last Friday".
+fn anchored_start(q: &str, now: DateTime<Utc>) -> Option<TimeRange> {
+    let today = now.date_naive();
+
+    if let Some(cap) = SINCE_RE.captures(q) {
+        let anchor = &cap[1];
+        let start = if let Some(weekday) = parse_weekday_name(anchor) {
+            Some(previous_weekday(today, weekday))
+        } else if let Some(month) = parse_month_name(anchor) {
+            let year = cap.get(2).and_then(|y| y.as_str().parse().ok())
+                .unwrap_or_else(|| most_recent_year(now, month));
+            month_start(year, month)
+        } else if anchor == "yesterday" {
+            today.pred_opt()
+        } else if anchor.len() == 4 {
+            anchor.parse().ok().and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+        } else {
+            parse_iso_date(anchor)
+        };
+        if let Some(start) = start {
+            return Some(TimeRange {
+                after: Some(day_start(start)?),
+                before: None,
+            });
+        }
+    }
+
+    if let Some(cap) = ON_DATE_RE.captures(q) {
+        return day_range(parse_iso_date(&cap[1])?);
+    }
+
+    if let Some(cap) = ON_WEEKDAY_RE.captures(q) {
+        return day_range(previous_weekday(today, parse_weekday_name(&cap[1])?));
+    }
+
+    None
+}
+
+/// Rolling named periods, "a few X ago", and open-ended after/before dates.
+fn named_period(q: &str, now: DateTime<Utc>) -> Option<TimeRange> {
     // --- relative named periods ---
 
     if q.contains("yesterday") {
@@ -89,8 +329,7 @@ pub fn parse_temporal_hint(query: &str, now: DateTime<Utc>) -> Option<TimeRange>
     // --- absolute date patterns ---
 
     // "after YYYY-MM-DD"
-    let after_re = Regex::new(r"after\s+(\d{4}-\d{2}-\d{2})").ok()?;
-    if let Some(cap) = after_re.captures(&q) {
+    if let Some(cap) = AFTER_RE.captures(q) {
         let date_str = format!("{}T00:00:00Z", &cap[1]);
         if let Ok(dt) = date_str.parse::<DateTime<Utc>>() {
             return Some(TimeRange {
@@ -101,8 +340,7 @@ pub fn parse_temporal_hint(query: &str, now: DateTime<Utc>) -> Option<TimeRange>
     }
 
     // "before YYYY-MM-DD"
-    let before_re = Regex::new(r"before\s+(\d{4}-\d{2}-\d{2})").ok()?;
-    if let Some(cap) = before_re.captures(&q) {
+    if let Some(cap) = BEFORE_RE.captures(q) {
         let date_str = format!("{}T23:59:59Z", &cap[1]);
         if let Ok(dt) = date_str.parse::<DateTime<Utc>>() {
             return Some(TimeRange {
@@ -112,34 +350,126 @@ pub fn parse_temporal_hint(query: &str, now: DateTime<Utc>) -> Option<TimeRange>
         }
     }
 
-    // --- "between MONTH and MONTH" ---
-    // e.g., "between January and March", "between march and june"
-    let between_re =
-        Regex::new(r"between\s+(\w+)\s+and\s+(\w+)").ok()?;
-    if let Some(cap) = between_re.captures(&q) {
-        let m1 = parse_month_name(&cap[1])?;
-        let m2 = parse_month_name(&cap[2])?;
-        let year = now.year();
+    None
+}
 
-        let start = Utc.with_ymd_and_hms(year, m1, 1, 0, 0, 0).single()?;
-        // End: last day of m2 — use first day of m2+1 minus 1 second
-        let (end_year, end_month) = if m2 == 12 {
-            (year + 1, 1u32)
-        } else {
-            (year, m2 + 1)
-        };
-        let end = Utc
-            .with_ymd_and_hms(end_year, end_month, 1, 0, 0, 0)
-            .single()?
-            - Duration::seconds(1);
+/// Midnight UTC at the start of `date`.
+fn day_start(date: NaiveDate) -> Option<DateTime<Utc>> {
+    Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?))
+}
 
-        return Some(TimeRange {
-            after: Some(start),
-            before: Some(end),
-        });
+/// Range from the start of `start` to one second before the start of `end` (exclusive).
+fn span(start: NaiveDate, end: NaiveDate) -> Option<TimeRange> {
+    Some(TimeRange {
+        after: Some(day_start(start)?),
+        before: Some(day_start(end)? - Duration::seconds(1)),
+    })
+}
+
+fn day_range(date: NaiveDate) -> Option<TimeRange> {
+    span(date, date.succ_opt()?)
+}
+
+fn month_start(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+fn month_range(year: i32, month: u32) -> Option<TimeRange> {
+    let (end_year, end_month) = add_months(year, month, 1);
+    span(month_start(year, month)?, month_start(end_year, end_month)?)
+}
+
+fn quarter_range(year: i32, quarter: u32) -> Option<TimeRange> {
+    let first_month = 3 * quarter - 2;
+    let (end_year, end_month) = add_months(year, first_month, 3);
+    span(month_start(year, first_month)?, month_start(end_year, end_month)?)
+}
+
+fn quarter_of(month: u32) -> u32 {
+    (month - 1) / 3 + 1
+}
+
+/// Shift (year, month) by `delta` months, carrying across year boundaries.
+fn add_months(year: i32, month: u32, delta: i32) -> (i32, u32) {
+    let total = year * 12 + month as i32 - 1 + delta;
+    (total.div_euclid(12), total.rem_euclid(12) as u32 + 1)
+}
+
+/// Year of the most recent `month` that has already started (this year, else last year).
+fn most_recent_year(now: DateTime<Utc>, month: u32) -> i32 {
+    if month <= now.month() {
+        now.year()
+    } else {
+        now.year() - 1
     }
+}
 
-    None
+/// Resolve an explicit year suffix: "2023", "last year", or "this year".
+fn resolve_year(suffix: Option<&str>, now: DateTime<Utc>) -> Option<i32> {
+    let suffix = suffix?;
+    if suffix.starts_with("last") {
+        Some(now.year() - 1)
+    } else if suffix.starts_with("this") {
+        Some(now.year())
+    } else {
+        suffix.parse().ok()
+    }
+}
+
+/// First day of the calendar week containing `date`.
+fn start_of_week(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let offset = (date.weekday().num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7;
+    date - Duration::days(offset as i64)
+}
+
+/// Most recent `weekday` strictly before `today` ("since Tuesday" on a Tuesday means a week ago).
+fn previous_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    today - Duration::days(if back == 0 { 7 } else { back as i64 })
+}
+
+fn parse_iso_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()
+}
+
+fn parse_count(word: &str) -> Option<i64> {
+    if let Ok(n) = word.parse::<i64>() {
+        // Far-past counts would overflow date arithmetic and mean nothing as a filter
+        return (n <= 10_000).then_some(n);
+    }
+    if word.contains("couple") {
+        return Some(2);
+    }
+    let n = match word {
+        "a" | "an" | "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        "eleven" => 11,
+        "twelve" => 12,
+        _ => return None,
+    };
+    Some(n)
+}
+
+/// Convert a weekday name or abbreviation (lowercase English) to a Weekday.
+fn parse_weekday_name(name: &str) -> Option<Weekday> {
+    match name {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
 }
 
 /// Convert a month name (English, case-insensitive) to its number (1–12).
@@ -284,4 +614,160 @@ mod tests {
             Utc.with_ymd_and_hms(2024, 3, 31, 23, 59, 59).unwrap()
         );
     }
+
+    /// Assert the range covers exactly [after, before] as given in UTC.
+    fn assert_range(result: TimeRange, after: (i32, u32, u32), before: Option<(i32, u32, u32)>) {
+        assert_eq!(
+            result.after.unwrap(),
+            Utc.with_ymd_and_hms(after.0, after.1, after.2, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            result.before,
+            before.map(|(y, m, d)| Utc.with_ymd_and_hms(y, m, d, 23, 59, 59).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_days_ago_is_calendar_day() {
+        let result = parse_temporal_hint("something from 3 days ago", fixed_now()).unwrap();
+        assert_range(result, (2024, 3, 12), Some((2024, 3, 12)));
+    }
+
+    #[test]
+    fn test_weeks_ago_respects_week_start() {
+        // 2024-03-15 is a Friday: Monday weeks start Mar 11, Sunday weeks start Mar 10
+        let result = parse_temporal_hint("two weeks ago", fixed_now()).unwrap();
+        assert_range(result, (2024, 2, 26), Some((2024, 3, 3)));
+
+        let result = parse_temporal_hint_with_week_start("two weeks ago", fixed_now(), Weekday::Sun).unwrap();
+        assert_range(result, (2024, 2, 25), Some((2024, 3, 2)));
+    }
+
+    #[test]
+    fn test_months_and_years_ago() {
+        let result = parse_temporal_hint("a month ago", fixed_now()).unwrap();
+        assert_range(result, (2024, 2, 1), Some((2024, 2, 29)));
+
+        let result = parse_temporal_hint("two years ago", fixed_now()).unwrap();
+        assert_range(result, (2022, 1, 1), Some((2022, 12, 31)));
+    }
+
+    #[test]
+    fn test_last_n_units_is_rolling() {
+        let now = fixed_now();
+        let result = parse_temporal_hint("in the last 3 days", now).unwrap();
+        assert_eq!(result.after.unwrap(), now - Duration::days(3));
+        assert!(result.before.is_none());
+
+        let result = parse_temporal_hint("the past two weeks", now).unwrap();
+        assert_eq!(result.after.unwrap(), now - Duration::days(14));
+    }
+
+    #[test]
+    fn test_this_period() {
+        let now = fixed_now();
+        assert_range(parse_temporal_hint("this week", now).unwrap(), (2024, 3, 11), None);
+        assert_range(
+            parse_temporal_hint_with_week_start("this week", now, Weekday::Sun).unwrap(),
+            (2024, 3, 10),
+            None,
+        );
+        assert_range(parse_temporal_hint("so far this month", now).unwrap(), (2024, 3, 1), None);
+        assert_range(parse_temporal_hint("this quarter", now).unwrap(), (2024, 1, 1), None);
+        assert_range(parse_temporal_hint("this year", now).unwrap(), (2024, 1, 1), None);
+    }
+
+    #[test]
+    fn test_since_weekday() {
+        let now = fixed_now();
+        assert_range(parse_temporal_hint("changes since Tuesday", now).unwrap(), (2024, 3, 12), None);
+        // Today is Friday, so "since Friday" means last week's Friday
+        assert_range(parse_temporal_hint("since friday", now).unwrap(), (2024, 3, 8), None);
+    }
+
+    #[test]
+    fn test_since_month_year_and_date() {
+        let now = fixed_now();
+        assert_range(parse_temporal_hint("since March", now).unwrap(), (2024, 3, 1), None);
+        assert_range(parse_temporal_hint("since November", now).unwrap(), (2023, 11, 1), None);
+        assert_range(parse_temporal_hint("since march 2022", now).unwrap(), (2022, 3, 1), None);
+        assert_range(parse_temporal_hint("since 2023", now).unwrap(), (2023, 1, 1), None);
+        assert_range(parse_temporal_hint("since 2024-02-10", now).unwrap(), (2024, 2, 10), None);
+        assert_range(parse_temporal_hint("since yesterday", now).unwrap(), (2024, 3, 14), None);
+    }
+
+    #[test]
+    fn test_specific_days() {
+        let now = fixed_now();
+        let result = parse_temporal_hint("what was decided on 2024-02-10", now).unwrap();
+        assert_range(result, (2024, 2, 10), Some((2024, 2, 10)));
+
+        let result = parse_temporal_hint("the call last Friday", now).unwrap();
+        assert_range(result, (2024, 3, 8), Some((2024, 3, 8)));
+    }
+
+    #[test]
+    fn test_quarters() {
+        let now = fixed_now();
+        // Q3 2024 has not started yet, so a bare "Q3" means Q3 2023
+        let result = parse_temporal_hint("launch plans in Q3", now).unwrap();
+        assert_range(result, (2023, 7, 1), Some((2023, 9, 30)));
+
+        let result = parse_temporal_hint("q1 2022 revenue", now).unwrap();
+        assert_range(result, (2022, 1, 1), Some((2022, 3, 31)));
+
+        let result = parse_temporal_hint("the third quarter of 2021", now).unwrap();
+        assert_range(result, (2021, 7, 1), Some((2021, 9, 30)));
+
+        let result = parse_temporal_hint("Q4 last year", now).unwrap();
+        assert_range(result, (2023, 10, 1), Some((2023, 12, 31)));
+
+        let result = parse_temporal_hint("what shipped last quarter", now).unwrap();
+        assert_range(result, (2023, 10, 1), Some((2023, 12, 31)));
+    }
+
+    #[test]
+    fn test_between_ranges() {
+        let now = fixed_now();
+        let result = parse_temporal_hint("between March and May", now).unwrap();
+        assert_range(result, (2024, 3, 1), Some((2024, 5, 31)));
+
+        // Wraps the new year: November of the previous year through February (leap year)
+        let result = parse_temporal_hint("between November and February", now).unwrap();
+        assert_range(result, (2023, 11, 1), Some((2024, 2, 29)));
+
+        let result = parse_temporal_hint("between 2024-01-10 and 2024-01-20", now).unwrap();
+        assert_range(result, (2024, 1, 10), Some((2024, 1, 20)));
+
+        // Words that are not months fall through to the other patterns
+        assert!(parse_temporal_hint("between friends and family", now).is_none());
+    }
+
+    #[test]
+    fn test_month_names_and_years() {
+        let now = fixed_now();
+        assert_range(parse_temporal_hint("notes in March", now).unwrap(), (2024, 3, 1), Some((2024, 3, 31)));
+        // June 2024 is still ahead, so the most recent June is 2023
+        assert_range(parse_temporal_hint("during june", now).unwrap(), (2023, 6, 1), Some((2023, 6, 30)));
+        assert_range(parse_temporal_hint("notes from sept 2022", now).unwrap(), (2022, 9, 1), Some((2022, 9, 30)));
+        assert_range(parse_temporal_hint("in may of 2021", now).unwrap(), (2021, 5, 1), Some((2021, 5, 31)));
+        assert_range(parse_temporal_hint("trips in 2022", now).unwrap(), (2022, 1, 1), Some((2022, 12, 31)));
+    }
+
+    #[test]
+    fn test_ambiguous_words_do_not_match() {
+        let now = fixed_now();
+        assert!(parse_temporal_hint("may I see my notes", now).is_none());
+        assert!(parse_temporal_hint("nothing since then", now).is_none());
+        assert!(parse_temporal_hint("march the tests forward", now).is_none());
+        // Absurd counts are ignored rather than overflowing
+        assert!(parse_temporal_hint("99999999999 years ago", now).is_none());
+    }
+
+    #[test]
+    fn test_parse_week_start() {
+        assert_eq!(parse_week_start("Sunday"), Weekday::Sun);
+        assert_eq!(parse_week_start("sat"), Weekday::Sat);
+        assert_eq!(parse_week_start("someday"), Weekday::Mon);
+    }
 }
@@ -0,0 +1,250 @@
+//! Per-source and per-type retention policies.
+//!
+//! `[retention.source]` and `[retention.type_hint]` map a memory's source or type_hint to a
+//! maximum age and/or a maximum number of live memories to keep per namespace. This
+//! background task periodically trashes (or deletes) memories outside their policy, so
+//! low-value memories don't accumulate forever. With `dry_run` it only logs what it would
+//! remove. Processes sharing a database take turns through the sweeper lock described on
+//! [`PostgresMemoryStore::try_advisory_lock`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::{RetentionConfig, RetentionPolicy};
+use crate::errors::MemcpError;
+use crate::store::postgres::PostgresMemoryStore;
+
+/// Advisory lock serializing retention passes across processes.
+const RETENTION_LOCK: &str = "memcp:retention-sweeper";
+
+/// Which memory column a retention rule matches on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetentionField {
+    Source,
+    TypeHint,
+}
+
+impl RetentionField {
+    pub fn column(self) -> &'static str {
+        match self {
+            RetentionField::Source => "source",
+            RetentionField::TypeHint => "type_hint",
+        }
+    }
+}
+
+/// One validated policy from the `[retention]` config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionRule {
+    pub field: RetentionField,
+    pub value: String,
+    pub max_age: Option<chrono::Duration>,
+    pub max_count: Option<u64>,
+}
+
+/// Parse a max age like "30m", "12h", "7d" or "2w".
+pub fn parse_max_age(raw: &str) -> Option<chrono::Duration> {
+    let raw = raw.trim();
+    let unit = raw.chars().last()?;
+    let amount: i64 = raw[..raw.len() - unit.len_utf8()].trim().parse().ok()?;
+    if amount <= 0 {
+        return None;
+    }
+    match unit {
+        'm' => chrono::Duration::try_minutes(amount),
+        'h' => chrono::Duration::try_hours(amount),
+        'd' => chrono::Duration::try_days(amount),
+        'w' => chrono::Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// Validate the configured policies, source rules first, each group in key order.
+pub fn rules(config: &RetentionConfig) -> Result<Vec<RetentionRule>, MemcpError> {
+    if config.action != "trash" && config.action != "delete" {
+        return Err(MemcpError::Config(format!(
+            "retention.action must be \"trash\" or \"delete\", got {:?}",
+            config.action
+        )));
+    }
+
+    let groups = [
+        (RetentionField::Source, &config.source),
+        (RetentionField::TypeHint, &config.type_hint),
+    ];
+    let mut rules = Vec::new();
+    for (field, policies) in groups {
+        for (value, policy) in policies {
+            let (max_age, max_count) = match policy {
+                RetentionPolicy::MaxAge(age) => (Some(age.as_str()), None),
+                RetentionPolicy::Limits { max_age, max_count } => (max_age.as_deref(), *max_count),
+            };
+            let max_age = match max_age {
+                Some(raw) => Some(parse_max_age(raw).ok_or_else(|| {
+                    MemcpError::Config(format!(
+                        "retention.{}.{}: invalid max age {:?} (expected e.g. \"12h\", \"7d\", \"2w\")",
+                        field.column(), value, raw
+                    ))
+                })?),
+                None => None,
+            };
+            if max_age.is_none() && max_count.is_none() {
+                return Err(MemcpError::Config(format!(
+                    "retention.{}.{}: set max_age and/or max_count",
+                    field.column(), value
+                )));
+            }
+            rules.push(RetentionRule { field, value: value.clone(), max_age, max_count });
+        }
+    }
+    Ok(rules)
+}
+
+/// Spawn the background retention task.
+///
+/// Applies every rule once per `sweep_interval_secs` (the first pass after one full interval).
+/// A tick is skipped while another process is running a pass. Failures are logged per rule
+/// and retried on the next tick — the task never exits.
+pub fn spawn_retention_sweeper(
+    store: Arc<PostgresMemoryStore>,
+    config: RetentionConfig,
+    rules: Vec<RetentionRule>,
+) -> tokio::task::JoinHandle<()> {
+    let trash = config.action == "trash";
+    let dry_run = config.dry_run;
+    let period = Duration::from_secs(config.sweep_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let lock = match store.try_advisory_lock(RETENTION_LOCK).await {
+                Ok(Some(lock)) => lock,
+                Ok(None) => {
+                    tracing::debug!("Another memcp process is applying retention policies — skipping this tick");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Retention lock failed — will retry next interval");
+                    continue;
+                }
+            };
+
+            let mut total = 0u64;
+            for rule in &rules {
+                let older_than = rule.max_age.map(|age| chrono::Utc::now() - age);
+                let keep_newest = rule.max_count.map(|count| count.min(i64::MAX as u64) as i64);
+                let candidates = match store
+                    .retention_candidates(rule.field.column(), &rule.value, older_than, keep_newest)
+                    .await
+                {
+                    Ok(ids) => ids,
+                    Err(e) => {
+                        tracing::warn!(error = %e, field = rule.field.column(), value = %rule.value, "Retention scan failed — will retry next interval");
+                        continue;
+                    }
+                };
+                if candidates.is_empty() {
+                    continue;
+                }
+                if dry_run {
+                    crate::metrics::global().retention_dry_run_matches.inc_by(candidates.len() as u64);
+                    tracing::info!(
+                        field = rule.field.column(),
+                        value = %rule.value,
+                        count = candidates.len(),
+                        "Retention dry run: memories past policy (not removed)"
+                    );
+                    continue;
+                }
+                match store.remove_for_retention(&candidates, trash).await {
+                    Ok(count) => {
+                        total += count;
+                        crate::metrics::global().retention_removals.inc_by(count);
+                        tracing::info!(
+                            field = rule.field.column(),
+                            value = %rule.value,
+                            count = count,
+                            trash = trash,
+                            "Memories removed by retention policy"
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, field = rule.field.column(), value = %rule.value, "Retention removal failed — will retry next interval");
+                    }
+                }
+            }
+            lock.release().await;
+            if total > 0 {
+                tracing::info!(count = total, "Retention pass complete");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::{providers::{Format, Toml}, Figment};
+
+    #[test]
+    fn parse_max_age_units() {
+        assert_eq!(parse_max_age("30m"), Some(chrono::Duration::minutes(30)));
+        assert_eq!(parse_max_age("12h"), Some(chrono::Duration::hours(12)));
+        assert_eq!(parse_max_age(" 7d "), Some(chrono::Duration::days(7)));
+        assert_eq!(parse_max_age("2w"), Some(chrono::Duration::weeks(2)));
+        assert_eq!(parse_max_age("7"), None);
+        assert_eq!(parse_max_age("0d"), None);
+        assert_eq!(parse_max_age("-1d"), None);
+        assert_eq!(parse_max_age("7y"), None);
+        assert_eq!(parse_max_age(""), None);
+    }
+
+    #[test]
+    fn rules_from_toml() {
+        let config: RetentionConfig = Figment::from(Toml::string(
+            r#"
+            enabled = true
+            [source]
+            scratch = "7d"
+            [type_hint]
+            event = { max_age = "90d", max_count = 5000 }
+            log = { max_count = 100 }
+            "#,
+        ))
+        .extract()
+        .unwrap();
+
+        let rules = rules(&config).unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0], RetentionRule {
+            field: RetentionField::Source,
+            value: "scratch".to_string(),
+            max_age: Some(chrono::Duration::days(7)),
+            max_count: None,
+        });
+        assert_eq!(rules[1].field, RetentionField::TypeHint);
+        assert_eq!(rules[1].value, "event");
+        assert_eq!(rules[1].max_age, Some(chrono::Duration::days(90)));
+        assert_eq!(rules[1].max_count, Some(5000));
+        assert_eq!(rules[2].value, "log");
+        assert_eq!(rules[2].max_age, None);
+        assert_eq!(rules[2].max_count, Some(100));
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        let mut config = RetentionConfig::default();
+        config.source.insert("scratch".to_string(), RetentionPolicy::MaxAge("soon".to_string()));
+        assert!(rules(&config).is_err());
+
+        config.source.insert("scratch".to_string(), RetentionPolicy::Limits { max_age: None, max_count: None });
+        assert!(rules(&config).is_err());
+
+        config.source.insert("scratch".to_string(), RetentionPolicy::MaxAge("1d".to_string()));
+        config.action = "archive".to_string();
+        assert!(rules(&config).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! In-process LRU cache for search_memory responses.
+//!
+//! Agents often re-issue the same search within a session. Caching the final response
+//! skips the embedding call, all three search legs, and salience re-ranking.
+//! Entries expire after a TTL and the whole cache is cleared on any write, so results
+//! are at most `ttl` stale with respect to background embedding/extraction progress.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct CacheEntry {
+    value: serde_json::Value,
+    inserted_at: Instant,
+    /// Monotonic access stamp — the entry with the smallest stamp is evicted first.
+    last_used: u64,
+}
+
+struct CacheInner {
+    entries: HashMap<String, CacheEntry>,
+    tick: u64,
+}
+
+/// Bounded, TTL-aware LRU cache of search responses keyed by normalized request.
+pub struct QueryCache {
+    capacity: usize,
+    ttl: Duration,
+    inner: Mutex<CacheInner>,
+}
+
+impl QueryCache {
+    /// Create a cache holding at most `capacity` entries, each valid for `ttl`.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        QueryCache {
+            capacity: capacity.max(1),
+            ttl,
+            inner: Mutex::new(CacheInner { entries: HashMap::new(), tick: 0 }),
+        }
+    }
+
+    /// Look up a cached response. Expired entries are dropped and reported as a miss.
+    pub fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.tick += 1;
+        let tick = inner.tick;
+        match inner.entries.get_mut(key) {
+            Some(entry) if entry.inserted_at.elapsed() <= self.ttl => {
+                entry.last_used = tick;
+                Some(entry.value.clone())
+            }
+            Some(_) => {
+                inner.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Insert a response, evicting the least recently used entry when full.
+    pub fn insert(&self, key: String, value: serde_json::Value) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.tick += 1;
+        let tick = inner.tick;
+        if !inner.entries.contains_key(&key) && inner.entries.len() >= self.capacity {
+            let ttl = self.ttl;
+            inner.entries.retain(|_, e| e.inserted_at.elapsed() <= ttl);
+            if inner.entries.len() >= self.capacity {
+                if let Some(oldest) = inner
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.last_used)
+                    .map(|(k, _)| k.clone())
+                {
+                    inner.entries.remove(&oldest);
+                }
+            }
+        }
+        inner.entries.insert(key, CacheEntry { value, inserted_at: Instant::now(), last_used: tick });
+    }
+
+    /// Drop every entry. Called after any write that could change search results.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        inner.entries.clear();
+    }
+
+    /// Number of live (possibly expired, not yet evicted) entries.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).entries.len()
+    }
+
+    /// Whether the cache holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Normalize a query for cache keying: lowercase and collapse whitespace.
+pub fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = QueryCache::new(2, Duration::from_secs(60));
+        cache.insert("a".into(), json!(1));
+        cache.insert("b".into(), json!(2));
+        assert_eq!(cache.get("a"), Some(json!(1))); // "b" is now least recently used
+        cache.insert("c".into(), json!(3));
+
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(json!(1)));
+        assert_eq!(cache.get("c"), Some(json!(3)));
+    }
+
+    #[test]
+    fn test_expired_entries_miss() {
+        let cache = QueryCache::new(4, Duration::ZERO);
+        cache.insert("a".into(), json!(1));
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(cache.get("a"), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_query() {
+        assert_eq!(normalize_query("  Rust   Preferences\n"), "rust preferences");
+    }
+}
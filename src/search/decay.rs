@@ -0,0 +1,161 @@
+//! Recency decay curves for salience scoring
+//!
+//! A `DecayModel` maps the days since a memory was last updated to a recency score in
+//! [0, 1]. `salience.decay_model` selects the curve:
+//!   - "exponential": e^(-λt), the default (λ = recency_lambda)
+//!   - "power_law": (1 + λt)^(-k) with k = power_law_exponent, a long tail that keeps old
+//!     memories findable
+//!   - "step": full score until the first of `step_days`, one even step down at each later
+//!     threshold, 0.0 past the last
+//!
+//! Scores are min-max normalized across the result set afterwards, so only a curve's shape
+//! matters, not its absolute level.
+
+use crate::config::SalienceConfig;
+use crate::errors::MemcpError;
+
+/// Names accepted by `salience.decay_model`.
+pub const DECAY_MODELS: &[&str] = &["exponential", "power_law", "step"];
+
+/// A recency decay curve.
+pub trait DecayModel: Send + Sync {
+    /// Recency score in [0, 1] for a memory last updated `days` ago (1.0 at 0 days,
+    /// non-increasing with age).
+    fn score(&self, days: f64) -> f64;
+
+    /// The `salience.decay_model` name of this curve.
+    fn name(&self) -> &'static str;
+}
+
+/// Exponential decay: e^(-λt). λ=0.01 gives a ~70-day half-life.
+pub struct ExponentialDecay {
+    pub lambda: f64,
+}
+
+impl DecayModel for ExponentialDecay {
+    fn score(&self, days: f64) -> f64 {
+        super::salience::recency_score(days, self.lambda)
+    }
+
+    fn name(&self) -> &'static str {
+        "exponential"
+    }
+}
+
+/// Power-law decay: (1 + λt)^(-k). With k=1 it starts as steeply as exponential decay but
+/// flattens out: λ=0.01 halves the score at 100 days and still gives 0.2 after a year.
+pub struct PowerLawDecay {
+    pub lambda: f64,
+    pub exponent: f64,
+}
+
+impl DecayModel for PowerLawDecay {
+    fn score(&self, days: f64) -> f64 {
+        (1.0 + self.lambda * days.max(0.0)).powf(-self.exponent)
+    }
+
+    fn name(&self) -> &'static str {
+        "power_law"
+    }
+}
+
+/// Step decay over ascending day thresholds: memories younger than the first threshold
+/// score 1.0, each threshold passed removes an equal share, and memories older than the
+/// last score 0.0.
+pub struct StepDecay {
+    pub thresholds: Vec<f64>,
+}
+
+impl DecayModel for StepDecay {
+    fn score(&self, days: f64) -> f64 {
+        if self.thresholds.is_empty() {
+            return 1.0;
+        }
+        let passed = self.thresholds.iter().filter(|&&threshold| days >= threshold).count();
+        1.0 - passed as f64 / self.thresholds.len() as f64
+    }
+
+    fn name(&self) -> &'static str {
+        "step"
+    }
+}
+
+/// Build the decay curve `config` selects. Errors on an unknown name or invalid parameters.
+pub fn decay_model(config: &SalienceConfig) -> Result<Box<dyn DecayModel>, MemcpError> {
+    match config.decay_model.as_str() {
+        "exponential" => Ok(Box::new(ExponentialDecay { lambda: config.recency_lambda })),
+        "power_law" => {
+            if config.power_law_exponent <= 0.0 {
+                return Err(MemcpError::Config(format!(
+                    "salience.power_law_exponent must be positive, got {}",
+                    config.power_law_exponent
+                )));
+            }
+            Ok(Box::new(PowerLawDecay { lambda: config.recency_lambda, exponent: config.power_law_exponent }))
+        }
+        "step" => {
+            let ascending = config.step_days.windows(2).all(|pair| pair[0] < pair[1]);
+            if config.step_days.is_empty() || !ascending || config.step_days[0] <= 0.0 {
+                return Err(MemcpError::Config(format!(
+                    "salience.step_days must be positive, strictly ascending day counts, got {:?}",
+                    config.step_days
+                )));
+            }
+            Ok(Box::new(StepDecay { thresholds: config.step_days.clone() }))
+        }
+        other => Err(MemcpError::Config(format!(
+            "salience.decay_model must be one of: {}, got \"{}\"",
+            DECAY_MODELS.join(", "),
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(decay_model: &str) -> SalienceConfig {
+        SalienceConfig { decay_model: decay_model.to_string(), ..SalienceConfig::default() }
+    }
+
+    #[test]
+    fn every_model_starts_at_one_and_never_rises() {
+        for name in DECAY_MODELS {
+            let model = decay_model(&config(name)).unwrap();
+            assert_eq!(model.name(), *name);
+            assert!((model.score(0.0) - 1.0).abs() < 1e-10, "{} at 0 days", name);
+            let scores: Vec<f64> = [0.5, 3.0, 20.0, 60.0, 200.0, 1000.0].iter().map(|&d| model.score(d)).collect();
+            assert!(scores.windows(2).all(|pair| pair[0] >= pair[1]), "{} rises: {:?}", name, scores);
+            assert!(scores.iter().all(|s| (0.0..=1.0).contains(s)), "{} out of range: {:?}", name, scores);
+        }
+    }
+
+    #[test]
+    fn power_law_keeps_a_longer_tail_than_exponential() {
+        let exponential = decay_model(&config("exponential")).unwrap();
+        let power_law = decay_model(&config("power_law")).unwrap();
+        assert!((power_law.score(100.0) - 0.5).abs() < 1e-10);
+        assert!(power_law.score(365.0) > exponential.score(365.0));
+    }
+
+    #[test]
+    fn step_drops_an_equal_share_per_threshold() {
+        let step = StepDecay { thresholds: vec![1.0, 7.0, 30.0, 90.0] };
+        assert_eq!(step.score(0.5), 1.0);
+        assert_eq!(step.score(1.0), 0.75);
+        assert_eq!(step.score(10.0), 0.5);
+        assert_eq!(step.score(400.0), 0.0);
+    }
+
+    #[test]
+    fn invalid_settings_are_rejected() {
+        assert!(decay_model(&config("linear")).is_err());
+        let power_law = SalienceConfig { power_law_exponent: 0.0, ..config("power_law") };
+        assert!(decay_model(&power_law).is_err());
+        let step = SalienceConfig { step_days: vec![30.0, 7.0], ..config("step") };
+        assert!(decay_model(&step).is_err());
+        let empty = SalienceConfig { step_days: Vec::new(), ..config("step") };
+        assert!(decay_model(&empty).is_err());
+    }
+}
@@ -1,8 +1,12 @@
+pub mod cache;
+pub mod decay;
 pub mod salience;
 
 // Re-export key types for convenience
 pub use salience::{SalienceScorer, ScoredHit, ScoreBreakdown};
 
+use serde::Serialize;
+
 use crate::store::Memory;
 
 /// A raw fused search hit before salience re-ranking.
@@ -12,7 +16,8 @@ use crate::store::Memory;
 #[derive(Debug, Clone)]
 pub struct HybridRawHit {
     pub memory: Memory,
-    /// Reciprocal Rank Fusion score (sum of 1/(k + rank) over all legs)
+    /// Fused score: Reciprocal Rank Fusion (sum of 1/(k + rank) over all legs) by default,
+    /// or the weighted sum of normalized leg scores under `Fusion::Weighted`
     pub rrf_score: f64,
     /// Which legs this result appeared in.
     ///
@@ -25,6 +30,64 @@ pub struct HybridRawHit {
     /// - "vector_only" (2): vector only
     /// - "bm25_only" (1): bm25 only
     pub match_source: String,
+    /// Per-leg ranks, raw scores, and fusion contributions (search_memory explain mode).
+    pub legs: LegDetails,
+    /// Chunks of this memory that matched, when it is stored as chunks (best first)
+    pub matched_chunks: Vec<ChunkMatch>,
+}
+
+/// A chunk of an oversized memory that matched the query; its hit is reported on the parent.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkMatch {
+    pub memory_id: String,
+    pub chunk_index: Option<i32>,
+    pub content: String,
+}
+
+/// A hit's position in one retrieval leg and what it added to the fused score.
+#[derive(Debug, Clone, Serialize)]
+pub struct LegHit {
+    /// 1-based rank within the leg's candidate pool
+    pub rank: i64,
+    /// The leg's raw score (ts_rank / cosine similarity / symbolic match strength)
+    pub score: f64,
+    /// This leg's share of the fused score: 1/(k + rank) under RRF, weight * normalized
+    /// score under weighted fusion
+    pub contribution: f64,
+}
+
+/// Where a fused hit appeared in each leg; None means the leg did not return it.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LegDetails {
+    pub bm25: Option<LegHit>,
+    pub vector: Option<LegHit>,
+    pub symbolic: Option<LegHit>,
+}
+
+/// How hybrid_search combines its BM25, vector, and symbolic legs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fusion {
+    /// Reciprocal Rank Fusion over leg ranks, smoothed by the per-leg k values (default).
+    Rrf,
+    /// Sum of each leg's normalized score (0.0-1.0) multiplied by the leg's weight.
+    Weighted { bm25: f64, vector: f64, symbolic: f64 },
+}
+
+/// Accepted values for `search.fusion` and the search_memory `fusion` param.
+pub const FUSION_STRATEGIES: &[&str] = &["rrf", "weighted"];
+
+/// Human-readable name for a set of leg bit flags (1=bm25, 2=vector, 4=symbolic).
+fn match_source_name(source_bits: u8) -> String {
+    match source_bits {
+        7 => "all_three".to_string(),
+        6 => "vector_symbolic".to_string(),
+        5 => "bm25_symbolic".to_string(),
+        3 => "hybrid".to_string(),       // bm25 + vector (legacy name preserved)
+        4 => "symbolic_only".to_string(),
+        2 => "vector_only".to_string(),
+        1 => "bm25_only".to_string(),
+        _ => "unknown".to_string(),
+    }
 }
 
 /// Fuse BM25, vector, and symbolic ranked lists via Reciprocal Rank Fusion (RRF).
@@ -78,17 +141,7 @@ pub fn rrf_fuse(
     let mut result: Vec<(String, f64, String)> = scores
         .into_iter()
         .map(|(id, score)| {
-            let source_bits = sources.get(&id).copied().unwrap_or(0);
-            let source = match source_bits {
-                7 => "all_three".to_string(),
-                6 => "vector_symbolic".to_string(),
-                5 => "bm25_symbolic".to_string(),
-                3 => "hybrid".to_string(),       // bm25 + vector (legacy name preserved)
-                4 => "symbolic_only".to_string(),
-                2 => "vector_only".to_string(),
-                1 => "bm25_only".to_string(),
-                _ => "unknown".to_string(),
-            };
+            let source = match_source_name(sources.get(&id).copied().unwrap_or(0));
             (id, score, source)
         })
         .collect();
@@ -97,3 +150,378 @@ pub fn rrf_fuse(
     result.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
     result
 }
+
+/// Fuse BM25, vector, and symbolic scored lists by weighted sum of normalized scores.
+///
+/// Unlike RRF, score magnitudes survive fusion: a near-exact vector match outranks a
+/// marginal one even at the same rank. Scores are normalized per leg before weighting:
+/// - BM25 and symbolic scores are unbounded, so each is divided by its leg's top score
+/// - vector scores are cosine similarities, already in 0.0-1.0, and are used as-is
+///
+/// # Arguments
+/// - `bm25_scores`, `vector_scores`, `symbolic_scores`: (id, raw score) pairs per leg
+/// - `w_bm25`, `w_vector`, `w_symbolic`: weights applied to each leg's normalized score
+///
+/// # Returns
+/// Vec of (id, fused_score, match_source) sorted by fused_score descending.
+pub fn weighted_fuse(
+    bm25_scores: &[(String, f64)],
+    vector_scores: &[(String, f64)],
+    symbolic_scores: &[(String, f64)],
+    w_bm25: f64,
+    w_vector: f64,
+    w_symbolic: f64,
+) -> Vec<(String, f64, String)> {
+    use std::collections::HashMap;
+
+    fn leg_max(scores: &[(String, f64)]) -> f64 {
+        scores.iter().map(|(_, s)| *s).fold(0.0, f64::max)
+    }
+
+    let mut scores: HashMap<String, f64> = HashMap::new();
+    let mut sources: HashMap<String, u8> = HashMap::new();
+
+    // (leg, weight, normalization scale, source bit)
+    let legs = [
+        (bm25_scores, w_bm25, leg_max(bm25_scores), 1u8),
+        (vector_scores, w_vector, 1.0, 2),
+        (symbolic_scores, w_symbolic, leg_max(symbolic_scores), 4),
+    ];
+    for (leg, weight, scale, bit) in legs {
+        for (id, raw) in leg {
+            let normalized = if scale > 0.0 { (raw / scale).clamp(0.0, 1.0) } else { 0.0 };
+            *scores.entry(id.clone()).or_default() += weight * normalized;
+            *sources.entry(id.clone()).or_default() |= bit;
+        }
+    }
+
+    let mut result: Vec<(String, f64, String)> = scores
+        .into_iter()
+        .map(|(id, score)| {
+            let source = match_source_name(sources.get(&id).copied().unwrap_or(0));
+            (id, score, source)
+        })
+        .collect();
+
+    result.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    result
+}
+
+/// Explain where `id` appeared in each leg and what each leg added to its fused score.
+///
+/// Mirrors the arithmetic of rrf_fuse and weighted_fuse: `ks` are the (bm25, vector, symbolic)
+/// RRF constants; weighted fusion normalizes BM25 and symbolic scores by the leg's top score.
+pub fn leg_details(
+    id: &str,
+    bm25: &[(String, i64, f64)],
+    vector: &[(String, i64, f64)],
+    symbolic: &[(String, i64, f64)],
+    fusion: Fusion,
+    ks: (f64, f64, f64),
+) -> LegDetails {
+    fn leg_max(leg: &[(String, i64, f64)]) -> f64 {
+        leg.iter().map(|(_, _, s)| *s).fold(0.0, f64::max)
+    }
+
+    let (w_bm25, w_vector, w_symbolic) = match fusion {
+        Fusion::Weighted { bm25, vector, symbolic } => (bm25, vector, symbolic),
+        Fusion::Rrf => (0.0, 0.0, 0.0),
+    };
+    // (leg, rrf k, weight, normalization scale)
+    let find = |leg: &[(String, i64, f64)], k: f64, weight: f64, scale: f64| {
+        leg.iter().find(|(leg_id, _, _)| leg_id == id).map(|(_, rank, score)| {
+            let contribution = match fusion {
+                Fusion::Rrf => 1.0 / (k + *rank as f64),
+                Fusion::Weighted { .. } if scale > 0.0 => weight * (score / scale).clamp(0.0, 1.0),
+                Fusion::Weighted { .. } => 0.0,
+            };
+            LegHit { rank: *rank, score: *score, contribution }
+        })
+    };
+
+    LegDetails {
+        bm25: find(bm25, ks.0, w_bm25, leg_max(bm25)),
+        vector: find(vector, ks.1, w_vector, 1.0),
+        symbolic: find(symbolic, ks.2, w_symbolic, leg_max(symbolic)),
+    }
+}
+
+/// Fuse hybrid_search results from several query variants via a second RRF pass.
+///
+/// Each variant's list is already ranked by its own three-leg RRF score; here each
+/// memory scores sum of 1/(k + rank) over the variant lists it appears in, so memories
+/// surfaced by several phrasings of the query rise to the top. A memory keeps the
+/// match_source and leg details from the highest-ranked list it appeared in.
+///
+/// A single list is returned unchanged (preserves the original RRF scores).
+pub fn fuse_variant_hits(lists: Vec<Vec<HybridRawHit>>, k: f64, limit: usize) -> Vec<HybridRawHit> {
+    use std::collections::HashMap;
+
+    if lists.len() == 1 {
+        let mut hits = lists.into_iter().next().unwrap_or_default();
+        hits.truncate(limit);
+        return hits;
+    }
+
+    let mut fused: HashMap<String, (HybridRawHit, f64, usize)> = HashMap::new();
+    for hits in lists {
+        for (i, hit) in hits.into_iter().enumerate() {
+            let contribution = 1.0 / (k + (i + 1) as f64);
+            match fused.get_mut(&hit.memory.id) {
+                Some((existing, score, best_rank)) => {
+                    *score += contribution;
+                    if i < *best_rank {
+                        existing.match_source = hit.match_source;
+                        existing.legs = hit.legs;
+                        *best_rank = i;
+                    }
+                    for chunk in hit.matched_chunks {
+                        if !existing.matched_chunks.iter().any(|c| c.memory_id == chunk.memory_id) {
+                            existing.matched_chunks.push(chunk);
+                        }
+                    }
+                }
+                None => {
+                    fused.insert(hit.memory.id.clone(), (hit, contribution, i));
+                }
+            }
+        }
+    }
+
+    let mut result: Vec<HybridRawHit> = fused
+        .into_values()
+        .map(|(mut hit, score, _)| {
+            hit.rrf_score = score;
+            hit
+        })
+        .collect();
+    result.sort_by(|a, b| b.rrf_score.partial_cmp(&a.rrf_score).unwrap_or(std::cmp::Ordering::Equal));
+    result.truncate(limit);
+    result
+}
+
+/// Merge vector-leg memory hits with per-fact hits into one ranked list.
+///
+/// Both inputs are (memory_id, similarity). A memory found by both keeps the higher
+/// similarity. Returns (memory_id, 1-based rank, similarity) sorted by similarity,
+/// truncated to `limit` — the shape hybrid_search's RRF legs expect.
+pub fn merge_fact_hits(
+    memory_hits: Vec<(String, f64)>,
+    fact_hits: Vec<(String, f64)>,
+    limit: usize,
+) -> Vec<(String, i64, f64)> {
+    use std::collections::HashMap;
+
+    let mut best: HashMap<String, f64> = HashMap::new();
+    for (id, similarity) in memory_hits.into_iter().chain(fact_hits) {
+        let entry = best.entry(id).or_insert(similarity);
+        if similarity > *entry {
+            *entry = similarity;
+        }
+    }
+
+    let mut merged: Vec<(String, f64)> = best.into_iter().collect();
+    merged.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    merged.truncate(limit);
+    merged
+        .into_iter()
+        .enumerate()
+        .map(|(i, (id, similarity))| (id, (i + 1) as i64, similarity))
+        .collect()
+}
+
+/// Marks query words in a keyword headline (markdown bold).
+pub const HIGHLIGHT_MARK: &str = "**";
+
+/// Cut `text` to at most `max_chars` characters, ending on a word boundary where one is
+/// near and marking the cut with an ellipsis.
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let keep = max_chars.saturating_sub(1);
+    let cut: String = text.chars().take(keep).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(pos) if pos >= cut.len() / 2 => cut[..pos].trim_end().to_string(),
+        _ => cut,
+    };
+    format!("{}…", cut)
+}
+
+/// Excerpt of `content` in at most `max_chars` characters, or None when it already fits.
+///
+/// Uses the keyword `headline` (from ts_headline, centered on the best match) when there is
+/// one; otherwise the start of the content. A cut that splits a highlight drops its
+/// unmatched marker.
+pub fn snippet(content: &str, headline: Option<&str>, max_chars: usize) -> Option<String> {
+    if content.chars().count() <= max_chars {
+        return None;
+    }
+    let headline = headline
+        .map(|h| h.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|h| !h.is_empty());
+    let Some(headline) = headline else {
+        return Some(truncate_chars(content, max_chars));
+    };
+
+    let plain = headline.replace(HIGHLIGHT_MARK, "");
+    let normalized: String = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    let lead = if normalized.starts_with(&plain) { "" } else { "…" };
+    let tail = if normalized.ends_with(&plain) { "" } else { "…" };
+    let mut excerpt = truncate_chars(&format!("{}{}", lead, headline), max_chars - tail.chars().count());
+    if excerpt.matches(HIGHLIGHT_MARK).count() % 2 == 1 {
+        if let Some(pos) = excerpt.rfind(HIGHLIGHT_MARK) {
+            excerpt.replace_range(pos..pos + HIGHLIGHT_MARK.len(), "");
+        }
+    }
+    if !excerpt.ends_with('…') {
+        excerpt.push_str(tail);
+    }
+    Some(excerpt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(id: &str, source: &str) -> HybridRawHit {
+        let now = chrono::Utc::now();
+        HybridRawHit {
+            memory: Memory {
+                id: id.to_string(),
+                content: id.to_string(),
+                type_hint: "fact".to_string(),
+                source: "test".to_string(),
+                tags: None,
+                created_at: now,
+                updated_at: now,
+                last_accessed_at: None,
+                access_count: 0,
+                embedding_status: "complete".to_string(),
+                extracted_entities: None,
+                extracted_facts: None,
+                extraction_status: "complete".to_string(),
+                is_consolidated_original: false,
+                consolidated_into: None,
+                namespace: "default".to_string(),
+                deleted_at: None,
+                expires_at: None,
+                archived_at: None,
+                importance: 3,
+                session_id: None,
+                parent_id: None,
+                chunk_index: None,
+                fields: None,
+                payload: None,
+                pinned: false,
+                origin_refs: None,
+            },
+            rrf_score: 0.5,
+            match_source: source.to_string(),
+            legs: LegDetails::default(),
+            matched_chunks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_fuse_variant_hits_rewards_agreement() {
+        let fused = fuse_variant_hits(
+            vec![
+                vec![hit("a", "bm25_only"), hit("b", "hybrid")],
+                vec![hit("c", "vector_only"), hit("b", "all_three")],
+                vec![hit("b", "vector_only")],
+            ],
+            60.0,
+            10,
+        );
+
+        let ids: Vec<&str> = fused.iter().map(|h| h.memory.id.as_str()).collect();
+        assert_eq!(ids[0], "b", "memory found by every variant ranks first");
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[0].match_source, "vector_only", "source from best-ranked appearance");
+    }
+
+    #[test]
+    fn test_fuse_single_variant_is_passthrough() {
+        let fused = fuse_variant_hits(vec![vec![hit("a", "hybrid"), hit("b", "hybrid")]], 60.0, 1);
+        assert_eq!(fused.len(), 1);
+        assert_eq!(fused[0].rrf_score, 0.5);
+    }
+
+    #[test]
+    fn test_weighted_fuse_keeps_score_magnitudes() {
+        let fused = weighted_fuse(
+            &[("a".to_string(), 4.0), ("b".to_string(), 2.0)],
+            &[("b".to_string(), 0.9), ("c".to_string(), 0.2)],
+            &[],
+            1.0,
+            2.0,
+            1.0,
+        );
+
+        let ids: Vec<&str> = fused.iter().map(|(id, _, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a", "c"]);
+        // b: 1.0 * (2.0 / 4.0) + 2.0 * 0.9
+        assert!((fused[0].1 - 2.3).abs() < 1e-9);
+        assert_eq!(fused[0].2, "hybrid");
+        assert_eq!(fused[2].2, "vector_only");
+    }
+
+    #[test]
+    fn test_merge_fact_hits_keeps_best_similarity_per_memory() {
+        let memory_hits = vec![("a".to_string(), 0.9), ("b".to_string(), 0.5)];
+        let fact_hits = vec![("b".to_string(), 0.95), ("c".to_string(), 0.4)];
+        let merged = merge_fact_hits(memory_hits, fact_hits, 10);
+        let ids: Vec<&str> = merged.iter().map(|(id, _, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a", "c"]);
+        assert_eq!(merged[0].1, 1);
+        assert!((merged[0].2 - 0.95).abs() < 1e-9);
+
+        let truncated = merge_fact_hits(vec![("a".to_string(), 0.9)], vec![("c".to_string(), 0.4)], 1);
+        assert_eq!(truncated.len(), 1);
+        assert_eq!(truncated[0].0, "a");
+    }
+
+    #[test]
+    fn test_leg_details_matches_fusion_arithmetic() {
+        let bm25 = vec![("a".to_string(), 1, 4.0), ("b".to_string(), 2, 2.0)];
+        let vector = vec![("b".to_string(), 1, 0.8)];
+
+        let rrf = leg_details("b", &bm25, &vector, &[], Fusion::Rrf, (60.0, 60.0, 40.0));
+        let bm25_hit = rrf.bm25.expect("b is in the bm25 leg");
+        assert_eq!(bm25_hit.rank, 2);
+        assert!((bm25_hit.contribution - 1.0 / 62.0).abs() < 1e-12);
+        assert!((rrf.vector.unwrap().contribution - 1.0 / 61.0).abs() < 1e-12);
+        assert!(rrf.symbolic.is_none());
+
+        let weighted = Fusion::Weighted { bm25: 1.0, vector: 2.0, symbolic: 1.0 };
+        let details = leg_details("b", &bm25, &vector, &[], weighted, (60.0, 60.0, 40.0));
+        // bm25 normalized by the leg's top score (4.0); vector used as-is
+        assert!((details.bm25.unwrap().contribution - 0.5).abs() < 1e-12);
+        assert!((details.vector.unwrap().contribution - 1.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_snippet_prefers_headline_and_falls_back_to_prefix() {
+        let content = "Notes from the planning meeting. The team agreed to move the billing service to Postgres next quarter.";
+        assert_eq!(snippet("short", None, 20), None);
+
+        let prefix = snippet(content, None, 30).unwrap();
+        assert_eq!(prefix, "Notes from the planning…");
+        assert!(prefix.chars().count() <= 30);
+
+        let headline = "agreed to move the **billing** service to **Postgres**";
+        let excerpt = snippet(content, Some(headline), 60).unwrap();
+        assert_eq!(excerpt, "…agreed to move the **billing** service to **Postgres**…");
+        assert!(excerpt.chars().count() <= 60);
+
+        // A cut through a highlight drops the dangling marker
+        let cut = snippet(content, Some(headline), 36).unwrap();
+        assert_eq!(cut.matches(HIGHLIGHT_MARK).count() % 2, 0);
+        assert!(cut.chars().count() <= 36);
+    }
+}
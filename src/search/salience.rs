@@ -2,7 +2,7 @@
 ///
 /// Salience is a weighted sum of four independent dimensions, each independently
 /// min-max normalized across the result set before weighting:
-///   1. Recency   — exponential decay from last_updated
+///   1. Recency   — decay from last_updated (curve chosen by salience.decay_model)
 ///   2. Access    — log-scale access frequency
 ///   3. Semantic  — cosine similarity from the query embedding (from RRF / vector search)
 ///   4. Reinforce — FSRS retrievability (standalone formula, no external crate)
@@ -10,6 +10,7 @@
 /// All scoring functions are pure — no I/O, no database writes.
 /// Decay is computed at query time only (never written back) — SRCH-05.
 
+use super::decay::{decay_model, DecayModel, ExponentialDecay};
 use crate::config::SalienceConfig;
 use crate::store::Memory;
 
@@ -17,13 +18,16 @@ use crate::store::Memory;
 // Public types
 // ---------------------------------------------------------------------------
 
-/// Debug breakdown of individual dimension scores (populated only when debug_scoring=true).
+/// Debug breakdown of individual dimension scores (populated when debug_scoring=true or
+/// search_memory runs with explain=true).
 #[derive(Debug, Clone)]
 pub struct ScoreBreakdown {
     pub recency: f64,
     pub access: f64,
     pub semantic: f64,
     pub reinforcement: f64,
+    pub links: f64,
+    pub importance: f64,
 }
 
 /// A single memory hit with RRF and salience scores.
@@ -46,6 +50,10 @@ pub struct ScoredHit {
 /// Salience scorer that re-ranks a set of hits using configurable dimension weights.
 pub struct SalienceScorer<'a> {
     config: &'a SalienceConfig,
+    /// Recency curve (salience.decay_model)
+    decay: Box<dyn DecayModel>,
+    /// Populate ScoredHit.breakdown (config.debug_scoring, or forced by explain mode)
+    breakdown: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -68,6 +76,11 @@ pub fn access_frequency_score(access_count: i64) -> f64 {
     (1.0 + access_count as f64).ln()
 }
 
+/// Map importance 1-5 onto [0.0, 1.0] (1 → 0.0, 3 → 0.5, 5 → 1.0), clamping out-of-range values.
+pub fn importance_score(importance: i16) -> f64 {
+    ((importance.clamp(1, 5) - 1) as f64) / 4.0
+}
+
 /// FSRS retrievability using the standalone power-law formula.
 ///
 /// Formula: R(t, S) = (1 + F * t / S)^C
@@ -93,6 +106,18 @@ pub fn reinforcement_score(stability: f64, days_since_reinforced: f64) -> f64 {
     fsrs_retrievability(stability, days_since_reinforced)
 }
 
+/// Stability multiplier for an explicit reinforcement rating.
+///
+/// "easy" = 2.0, "light" = 0.5 (memories the agent cited via mark_used), anything else is
+/// treated as "good" = 1.5.
+pub fn reinforcement_multiplier(rating: &str) -> f64 {
+    match rating {
+        "easy" => 2.0,
+        "light" => 0.5,
+        _ => 1.5,
+    }
+}
+
 /// Min-max normalization over a slice of values.
 ///
 /// Edge case: if max == min (including single-element slices), returns vec![1.0; n]
@@ -117,11 +142,28 @@ pub fn normalize(values: &[f64]) -> Vec<f64> {
 pub struct SalienceInput {
     pub stability: f64,
     pub days_since_reinforced: f64,
+    /// Number of memory_links touching this memory (only fetched when w_links > 0)
+    pub link_degree: i64,
 }
 
 impl<'a> SalienceScorer<'a> {
+    /// Scorer using the decay curve `config` selects. The config is validated at startup and
+    /// on reload; an invalid curve here falls back to exponential decay.
     pub fn new(config: &'a SalienceConfig) -> Self {
-        SalienceScorer { config }
+        let decay = decay_model(config).unwrap_or_else(|_| Box::new(ExponentialDecay { lambda: config.recency_lambda }));
+        SalienceScorer { config, decay, breakdown: config.debug_scoring }
+    }
+
+    /// Score recency with `decay` instead of the configured curve.
+    pub fn with_decay_model(mut self, decay: Box<dyn DecayModel>) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Also populate the dimension breakdown when `enabled`, regardless of debug_scoring.
+    pub fn with_breakdown(mut self, enabled: bool) -> Self {
+        self.breakdown |= enabled;
+        self
     }
 
     /// Re-rank hits by salience score (descending).
@@ -133,6 +175,7 @@ impl<'a> SalienceScorer<'a> {
     /// 1. Compute raw scores for each dimension
     /// 2. Normalize each dimension independently via min-max
     /// 3. Weighted sum: salience = w_r*recency + w_a*access + w_s*semantic + w_re*reinforce
+    ///    + w_l*links + w_i*importance
     /// 4. Sort hits by salience descending
     pub fn rank(&self, hits: &mut Vec<ScoredHit>, salience_inputs: &[SalienceInput]) {
         if hits.is_empty() {
@@ -146,10 +189,7 @@ impl<'a> SalienceScorer<'a> {
         // Step 1: Raw scores
         let raw_recency: Vec<f64> = hits
             .iter()
-            .map(|h| {
-                let days = days_since(h.memory.updated_at);
-                recency_score(days, cfg.recency_lambda)
-            })
+            .map(|h| self.decay.score(days_since(h.memory.updated_at)))
             .collect();
 
         let raw_access: Vec<f64> = hits
@@ -165,19 +205,32 @@ impl<'a> SalienceScorer<'a> {
             .map(|s| reinforcement_score(s.stability, s.days_since_reinforced))
             .collect();
 
+        // Link degree uses the same log dampening as access frequency
+        let raw_links: Vec<f64> = salience_inputs
+            .iter()
+            .map(|s| access_frequency_score(s.link_degree))
+            .collect();
+
+        // Importance is already on a fixed scale — not min-max normalized, so a set of
+        // equally important hits doesn't all jump to 1.0
+        let importance: Vec<f64> = hits.iter().map(|h| importance_score(h.memory.importance)).collect();
+
         // Step 2: Normalize each dimension
         let norm_recency = normalize(&raw_recency);
         let norm_access = normalize(&raw_access);
         let norm_semantic = normalize(&raw_semantic);
         let norm_reinforce = normalize(&raw_reinforce);
+        let norm_links = normalize(&raw_links);
 
         // Step 3: Weighted sum and optional breakdown
-        let debug = cfg.debug_scoring;
+        let debug = self.breakdown;
         for (i, hit) in hits.iter_mut().enumerate() {
             let salience = cfg.w_recency * norm_recency[i]
                 + cfg.w_access * norm_access[i]
                 + cfg.w_semantic * norm_semantic[i]
-                + cfg.w_reinforce * norm_reinforce[i];
+                + cfg.w_reinforce * norm_reinforce[i]
+                + cfg.w_links * norm_links[i]
+                + cfg.w_importance * importance[i];
 
             hit.salience_score = salience;
             hit.breakdown = if debug {
@@ -186,6 +239,8 @@ impl<'a> SalienceScorer<'a> {
                     access: norm_access[i],
                     semantic: norm_semantic[i],
                     reinforcement: norm_reinforce[i],
+                    links: norm_links[i],
+                    importance: importance[i],
                 })
             } else {
                 None
@@ -216,6 +271,14 @@ fn days_since(ts: chrono::DateTime<chrono::Utc>) -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_reinforcement_multiplier_orders_ratings() {
+        assert_eq!(reinforcement_multiplier("easy"), 2.0);
+        assert_eq!(reinforcement_multiplier("good"), 1.5);
+        assert_eq!(reinforcement_multiplier("unknown"), 1.5);
+        assert!(reinforcement_multiplier("light") < reinforcement_multiplier("good"));
+    }
+
     #[test]
     fn test_recency_score_at_zero() {
         // Just updated: score should be 1.0
@@ -268,6 +331,14 @@ mod tests {
         assert_eq!(fsrs_retrievability(-1.0, 5.0), 0.0);
     }
 
+    #[test]
+    fn test_importance_score_scale() {
+        assert_eq!(importance_score(1), 0.0);
+        assert_eq!(importance_score(3), 0.5);
+        assert_eq!(importance_score(5), 1.0);
+        assert_eq!(importance_score(9), 1.0);
+    }
+
     #[test]
     fn test_normalize_single_element() {
         // Single element → returns [1.0]
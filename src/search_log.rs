@@ -0,0 +1,78 @@
+//! Search quality log.
+//!
+//! With `[search_log] enabled = true`, MemoryService records one entry per hybrid search:
+//! the query and its expanded variants, how many fused hits each leg contributed, the fused
+//! and final result IDs with final scores, and — filled in later — which results the client
+//! fetched with get_memory. `memcp search-log` exports the rows as JSON lines for offline
+//! tuning of RRF k values and salience weights.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A get_memory this long after a search that returned the memory counts as a follow-up fetch.
+pub const FETCH_WINDOW_MINUTES: i64 = 30;
+
+/// Fused hits each search leg contributed to (a hit matched by several legs counts for each).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LegHitCounts {
+    pub bm25: usize,
+    pub vector: usize,
+    pub symbolic: usize,
+}
+
+impl LegHitCounts {
+    /// Count leg participation across fused hits.
+    pub fn from_hits(hits: &[crate::search::HybridRawHit]) -> Self {
+        let mut counts = LegHitCounts::default();
+        for hit in hits {
+            counts.bm25 += usize::from(hit.legs.bm25.is_some());
+            counts.vector += usize::from(hit.legs.vector.is_some());
+            counts.symbolic += usize::from(hit.legs.symbolic.is_some());
+        }
+        counts
+    }
+}
+
+/// One search to record.
+#[derive(Debug, Clone)]
+pub struct SearchLogEntry {
+    pub query: String,
+    pub namespace: String,
+    /// Expanded query variants searched besides the original query
+    pub variants: Vec<String>,
+    pub leg_hits: LegHitCounts,
+    /// Fused result IDs before salience re-ranking, best first
+    pub fused_ids: Vec<String>,
+    /// Returned result IDs, in response order
+    pub result_ids: Vec<String>,
+    /// Final relevance score of each returned result (parallel to result_ids)
+    pub scores: Vec<f64>,
+    /// Wall-clock time spent in the search
+    pub latency_ms: i32,
+}
+
+/// A recorded search log row.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchLogRecord {
+    pub id: i64,
+    pub query: String,
+    pub namespace: String,
+    pub variants: Vec<String>,
+    pub leg_hits: LegHitCounts,
+    pub fused_ids: Vec<String>,
+    pub result_ids: Vec<String>,
+    pub scores: Vec<f64>,
+    /// Results the client later fetched with get_memory, in fetch order
+    pub fetched_ids: Vec<String>,
+    pub latency_ms: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters for exporting the search log, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct SearchLogFilter {
+    pub namespace: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
@@ -4,7 +4,8 @@ use rmcp::{
     model::{
         ServerCapabilities, Implementation, ProtocolVersion, CallToolResult,
         RawResource, ListResourcesResult, ReadResourceResult, ResourceContents,
-        ReadResourceRequestParams, AnnotateAble,
+        ReadResourceRequestParams, AnnotateAble, Prompt, PromptArgument, PromptMessage,
+        PromptMessageRole, GetPromptRequestParams, GetPromptResult, ListPromptsResult,
     },
     handler::server::wrapper::Parameters,
     service::{RequestContext, RoleServer},
@@ -13,31 +14,147 @@ use rmcp::{
 use serde::{Deserialize, Serialize};
 use schemars::JsonSchema;
 use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use chrono::DateTime;
 use chrono::Utc;
-use crate::query_intelligence::{RankedCandidate, temporal::parse_temporal_hint};
+use crate::query_intelligence::{RankedCandidate, RankedResult, temporal};
+use crate::query_intelligence::lexical::lexical_rerank;
 
 use crate::config::SalienceConfig;
 use crate::embedding::{EmbeddingJob, EmbeddingProvider};
-use crate::errors::MemcpError;
+use crate::errors::{codes, MemcpError};
 use crate::extraction::ExtractionJob;
+use crate::metrics;
 use crate::search::{SalienceScorer, ScoredHit};
+use crate::search::cache::{QueryCache, normalize_query};
 use crate::search::salience::SalienceInput;
-use crate::store::{CreateMemory, ListFilter, Memory, MemoryStore, UpdateMemory};
+use crate::store::{BulkUpdate, CreateMemory, ListFilter, Memory, MemoryStore, OriginRef, Session, UpdateMemory};
+
+/// Tools that write memories, links, sessions, or tags. In read-only mode they are hidden from
+/// tools/list and calls are rejected with READ_ONLY. summarize_memories stays available but
+/// refuses `store: true`.
+const MUTATING_TOOLS: &[&str] = &[
+    "store_memory",
+    "store_memories",
+    "import_memories",
+    "store_structured_memory",
+    "define_memory_type",
+    "ingest_conversation",
+    "update_memory",
+    "pin_memory",
+    "revert_memory",
+    "delete_memory",
+    "bulk_delete_memories",
+    "bulk_update_memories",
+    "restore_memory",
+    "purge_trash",
+    "unconsolidate_memory",
+    "consolidate_memories",
+    "merge_memories",
+    "link_memories",
+    "unlink_memories",
+    "start_session",
+    "end_session",
+    "reinforce_memory",
+    "reinforce_memories",
+    "mark_used",
+    "rename_tag",
+    "merge_tags",
+    "delete_tag",
+];
+
+/// Whether a call writes: a MUTATING_TOOLS call, or summarize_memories storing its summary.
+/// Writes run to completion — the deadline and client cancellation only abandon reads.
+fn writes(tool: &str, arguments: Option<&serde_json::Map<String, serde_json::Value>>) -> bool {
+    MUTATING_TOOLS.contains(&tool)
+        || (tool == "summarize_memories"
+            && arguments.and_then(|args| args.get("store")).and_then(|v| v.as_bool()) == Some(true))
+}
+
+/// Error returned for a tool call abandoned past its deadline or cancelled by the client.
+fn timeout_error(tool: &str, cancelled: bool, timeout: Option<Duration>) -> CallToolResult {
+    let error = match (cancelled, timeout) {
+        (false, Some(timeout)) => format!("{} did not finish within {}ms and was abandoned", tool, timeout.as_millis()),
+        _ => format!("{} was cancelled by the client", tool),
+    };
+    CallToolResult::structured_error(json!({
+        "isError": true,
+        "code": codes::TIMEOUT,
+        "error": error,
+        "cancelled": cancelled,
+        "hint": "Narrow the query or filters, lower limit, or raise server.tool_timeout_ms"
+    }))
+}
+
+/// Error returned for a mutating tool call while the server is read-only.
+fn read_only_error(tool: &str) -> CallToolResult {
+    CallToolResult::structured_error(json!({
+        "isError": true,
+        "code": codes::READ_ONLY,
+        "error": format!("The server is read-only; {} is disabled", tool),
+        "hint": "Use search_memory, list_memories, or get_memory to read memories"
+    }))
+}
 
 pub struct MemoryService {
     store: Arc<dyn MemoryStore + Send + Sync>,
     pipeline: Option<crate::embedding::pipeline::EmbeddingPipeline>,
     embedding_provider: Option<Arc<dyn EmbeddingProvider>>,
     pg_store: Option<Arc<crate::store::postgres::PostgresMemoryStore>>,
-    salience_config: SalienceConfig,
     start_time: Instant,
     extraction_pipeline: Option<crate::extraction::pipeline::ExtractionPipeline>,
     qi_expansion_provider: Option<Arc<dyn crate::query_intelligence::QueryIntelligenceProvider + Send + Sync>>,
     qi_reranking_provider: Option<Arc<dyn crate::query_intelligence::QueryIntelligenceProvider + Send + Sync>>,
-    qi_config: crate::config::QueryIntelligenceConfig,
+    default_namespace: String,
+    /// LRU cache of search_memory responses (None = caching disabled)
+    search_cache: Option<QueryCache>,
+    /// Write-ahead dedup settings (None = every store inserts a new row)
+    dedup_config: Option<crate::config::DedupConfig>,
+    /// LLM used by summarize_memories and consolidate_memories (None = tools report it is unavailable)
+    summary_provider: Option<Arc<dyn crate::consolidation::SynthesisProvider>>,
+    /// Reloadable settings: salience weights, QI tuning, and the consolidation threshold and
+    /// group size. Read a snapshot per call; reload_config / SIGHUP swap it.
+    live: crate::live_config::LiveConfig,
+    /// Fusion strategy search_memory uses when the call omits `fusion` ("rrf" or "weighted")
+    default_fusion: String,
+    /// Similarity below which search_memory drops hits when the call omits `min_relevance`
+    default_min_relevance: f64,
+    /// Extraction provider used by ingest_conversation (with its per-call transcript budget) and
+    /// the consolidation quality check
+    conversation_extractor: Option<(Arc<dyn crate::extraction::ExtractionProvider>, usize)>,
+    /// LLM endpoints probed by health_check
+    health_endpoints: Vec<crate::health::LlmEndpoint>,
+    /// Size limit and chunking for oversized content
+    content_config: crate::config::ContentConfig,
+    /// Text each memory is embedded as (embedding.text_template)
+    embedding_template: crate::embedding::template::EmbeddingTemplate,
+    /// Embedding routes (embedding.routes); search_memory embeds with a routed model when
+    /// its filters pin a route (None = always embed with embedding_provider)
+    embedding_router: Option<Arc<crate::embedding::router::EmbeddingRouter>>,
+    /// Window, size, and narrative setting for memory://daily-digest
+    digest_config: crate::config::DigestConfig,
+    /// Size, type filter, and character budget for memory://session-primer
+    resources_config: crate::config::ResourcesConfig,
+    /// Reject and hide MUTATING_TOOLS (server.read_only / --read-only)
+    read_only: bool,
+    /// Deadline for calls that don't write (see `writes`; server.tool_timeout_ms; None = none)
+    tool_timeout: Option<Duration>,
+    /// Record every tool call in the audit log (audit.enabled; needs pg_store)
+    audit: bool,
+    /// Record every hybrid search in the search log (search_log.enabled; needs pg_store)
+    search_log: bool,
+    /// Let extraction classify new memories' type_hint (extraction.classify_type_hint)
+    classify_type_hint: bool,
+    /// Never reclassify a caller-supplied type_hint (extraction.keep_explicit_type_hint)
+    keep_explicit_type_hint: bool,
+    /// Relays moving outbox jobs to the pipelines (outbox.enabled; empty = enqueue directly)
+    outbox_relays: Vec<crate::outbox::OutboxRelay>,
+    /// Filters redacting sensitive data from content before it is stored (privacy.enabled)
+    content_scrubber: Option<Arc<crate::privacy::ContentScrubber>>,
+    /// Lifecycle event notifications (events.enabled; disabled = events are dropped)
+    events: crate::events::EventNotifier,
 }
 
 impl MemoryService {
@@ -57,571 +174,6296 @@ impl MemoryService {
             pipeline,
             embedding_provider,
             pg_store,
-            salience_config,
             start_time: Instant::now(),
             extraction_pipeline,
             qi_expansion_provider,
             qi_reranking_provider,
-            qi_config,
+            default_namespace: crate::store::DEFAULT_NAMESPACE.to_string(),
+            search_cache: None,
+            dedup_config: None,
+            summary_provider: None,
+            live: crate::live_config::LiveConfig::new(crate::config::Config {
+                salience: salience_config,
+                query_intelligence: qi_config,
+                ..crate::config::Config::default()
+            }),
+            default_fusion: "rrf".to_string(),
+            default_min_relevance: 0.0,
+            conversation_extractor: None,
+            health_endpoints: Vec::new(),
+            content_config: crate::config::ContentConfig::default(),
+            embedding_template: crate::embedding::template::EmbeddingTemplate::default(),
+            digest_config: crate::config::DigestConfig::default(),
+            resources_config: crate::config::ResourcesConfig::default(),
+            read_only: false,
+            tool_timeout: None,
+            audit: false,
+            search_log: false,
+            classify_type_hint: false,
+            keep_explicit_type_hint: true,
+            outbox_relays: Vec::new(),
+            embedding_router: None,
+            content_scrubber: None,
+            events: crate::events::EventNotifier::disabled(),
         }
     }
 
-    fn uptime_seconds(&self) -> u64 {
-        self.start_time.elapsed().as_secs()
+    /// Set the namespace used when a tool call omits `namespace`.
+    pub fn with_default_namespace(mut self, namespace: String) -> Self {
+        self.default_namespace = namespace;
+        self
     }
-}
 
-// Parameter structs
+    /// Enable the search response cache. A capacity of 0 leaves caching disabled.
+    pub fn with_search_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.search_cache = (capacity > 0).then(|| QueryCache::new(capacity, ttl));
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct StoreMemoryParams {
-    /// The memory content to store (required)
-    pub content: String,
-    /// Classification hint: "fact", "preference", "instruction", etc. (default: "fact")
-    pub type_hint: Option<String>,
-    /// Origin source: "user", "assistant", "system", etc. (default: "default")
-    pub source: Option<String>,
-    /// Optional tags for categorization
-    pub tags: Option<Vec<String>>,
-}
+    /// Enable write-ahead deduplication on store. Ignored unless `config.on_store` is set.
+    pub fn with_dedup(mut self, config: crate::config::DedupConfig) -> Self {
+        self.dedup_config = config.on_store.then_some(config);
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct GetMemoryParams {
-    /// Memory ID to retrieve (required)
-    pub id: String,
-}
+    /// Set the LLM provider used by summarize_memories.
+    pub fn with_summary_provider(mut self, provider: Arc<dyn crate::consolidation::SynthesisProvider>) -> Self {
+        self.summary_provider = Some(provider);
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct UpdateMemoryParams {
-    /// Memory ID to update (required)
-    pub id: String,
-    /// New content (optional)
-    pub content: Option<String>,
-    /// New classification hint (optional)
-    pub type_hint: Option<String>,
-    /// New origin source (optional)
-    pub source: Option<String>,
-    /// New tags, replaces existing (optional)
-    pub tags: Option<Vec<String>>,
-}
+    /// Set the fusion strategy search_memory uses when a call omits `fusion`.
+    pub fn with_fusion(mut self, fusion: String) -> Self {
+        self.default_fusion = fusion;
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct DeleteMemoryParams {
-    /// Memory ID to delete (required)
-    pub id: String,
-}
+    /// Set the similarity threshold search_memory applies when a call omits `min_relevance`
+    /// (clamped to 0.0-1.0).
+    pub fn with_min_relevance(mut self, min_relevance: f64) -> Self {
+        self.default_min_relevance = min_relevance.clamp(0.0, 1.0);
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct BulkDeleteMemoriesParams {
-    /// Filter by type_hint (optional)
-    pub type_hint: Option<String>,
-    /// Filter by source (optional)
-    pub source: Option<String>,
-    /// Delete memories created after this ISO-8601 timestamp (optional)
-    pub created_after: Option<String>,
-    /// Delete memories created before this ISO-8601 timestamp (optional)
-    pub created_before: Option<String>,
-    /// Delete memories updated after this ISO-8601 timestamp (optional)
-    pub updated_after: Option<String>,
-    /// Delete memories updated before this ISO-8601 timestamp (optional)
-    pub updated_before: Option<String>,
-    /// Set to true to confirm deletion (default: false — returns count only)
-    #[serde(default)]
-    pub confirm: bool,
-}
+    /// Share the running config with the SIGHUP handler and consolidation worker. Replaces the
+    /// salience and QI settings given to new(); consolidate_memories takes its threshold and
+    /// group size from here.
+    pub fn with_live_config(mut self, live: crate::live_config::LiveConfig) -> Self {
+        self.live = live;
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct ListMemoriesParams {
-    /// Filter by type_hint (optional)
-    pub type_hint: Option<String>,
-    /// Filter by source (optional)
-    pub source: Option<String>,
-    /// Filter memories created after this ISO-8601 timestamp (optional)
-    pub created_after: Option<String>,
-    /// Filter memories created before this ISO-8601 timestamp (optional)
-    pub created_before: Option<String>,
-    /// Filter memories updated after this ISO-8601 timestamp (optional)
-    pub updated_after: Option<String>,
-    /// Filter memories updated before this ISO-8601 timestamp (optional)
-    pub updated_before: Option<String>,
-    /// Maximum results to return (1-100, default: 20)
-    pub limit: Option<u32>,
-    /// Cursor from previous page for pagination (optional)
-    pub cursor: Option<String>,
-}
+    /// Set the size limit above which content is chunked or rejected.
+    pub fn with_content_config(mut self, config: crate::config::ContentConfig) -> Self {
+        self.content_config = config;
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct ReinforceMemoryParams {
-    /// Memory ID to reinforce (required)
-    pub id: String,
-    /// Reinforcement strength: "good" (default) for standard reinforcement, "easy" for stronger boost
-    #[serde(default = "default_rating")]
-    pub rating: Option<String>,
-}
+    /// Set the template new and updated memories are embedded with.
+    pub fn with_embedding_template(mut self, template: crate::embedding::template::EmbeddingTemplate) -> Self {
+        self.embedding_template = template;
+        self
+    }
 
-fn default_rating() -> Option<String> {
-    Some("good".to_string())
-}
+    /// Route search queries to the embedding providers of `[[embedding.routes]]`.
+    pub fn with_embedding_router(mut self, router: Arc<crate::embedding::router::EmbeddingRouter>) -> Self {
+        self.embedding_router = Some(router);
+        self
+    }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
-pub struct SearchMemoryParams {
-    /// Natural language query — find memories by meaning, not exact words (required)
-    pub query: String,
-    /// Maximum results to return (1-100, default: 10)
-    pub limit: Option<u32>,
-    /// Return only memories created after this ISO-8601 timestamp (optional)
-    pub created_after: Option<String>,
-    /// Return only memories created before this ISO-8601 timestamp (optional)
-    pub created_before: Option<String>,
-    /// Filter by tags — return only memories with ALL specified tags (optional)
-    pub tags: Option<Vec<String>>,
-    /// Cursor from previous page for pagination (optional)
-    pub cursor: Option<String>,
-    /// Weight for BM25 keyword search path (0.0 to disable, 1.0 = default, >1.0 = emphasize).
-    /// Controls how much exact keyword matches influence results.
-    pub bm25_weight: Option<f64>,
-    /// Weight for vector semantic search path (0.0 to disable, 1.0 = default, >1.0 = emphasize).
-    /// Controls how much meaning similarity influences results.
-    pub vector_weight: Option<f64>,
-    /// Weight for symbolic metadata search path (0.0 to disable, 1.0 = default, >1.0 = emphasize).
-    /// Controls how much tag/type/source matches influence results.
-    pub symbolic_weight: Option<f64>,
-}
+    /// Set the size, type filter, and character budget of the memory://session-primer resource.
+    pub fn with_resources_config(mut self, config: crate::config::ResourcesConfig) -> Self {
+        self.resources_config = config;
+        self
+    }
 
-// Helper: convert MemcpError to CallToolResult with isError: true
-fn store_error_to_result(err: MemcpError) -> CallToolResult {
-    match err {
-        MemcpError::NotFound { id } => {
-            CallToolResult::structured_error(json!({
-                "isError": true,
-                "error": format!("Memory not found: {}", id),
-                "hint": "Use list_memories to find available memory IDs"
-            }))
+    /// Redact sensitive data from memory content with `scrubber` before every write.
+    pub fn with_content_scrubber(mut self, scrubber: Arc<crate::privacy::ContentScrubber>) -> Self {
+        self.content_scrubber = Some(scrubber);
+        self
+    }
+
+    /// Set the window and narrative summary of the memory://daily-digest resource.
+    pub fn with_digest_config(mut self, config: crate::config::DigestConfig) -> Self {
+        self.digest_config = config;
+        self
+    }
+
+    /// Serve read tools only: MUTATING_TOOLS are hidden and rejected.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Abandon read-only tool calls after `timeout_ms` (0 = no deadline). Mutating tools
+    /// always run to completion.
+    pub fn with_tool_timeout(mut self, timeout_ms: u64) -> Self {
+        self.tool_timeout = (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms));
+        self
+    }
+
+    /// Record every tool call in the audit_log table. Ignored without the PostgreSQL backend.
+    pub fn with_audit(mut self, enabled: bool) -> Self {
+        self.audit = enabled;
+        self
+    }
+
+    /// Record every hybrid search in the search_log table. Ignored without the PostgreSQL backend.
+    pub fn with_search_log(mut self, enabled: bool) -> Self {
+        self.search_log = enabled;
+        self
+    }
+
+    /// Let the extraction pipeline assign type_hint to new memories. With `keep_explicit`,
+    /// only memories stored without a type_hint are classified.
+    pub fn with_type_classification(mut self, enabled: bool, keep_explicit: bool) -> Self {
+        self.classify_type_hint = enabled;
+        self.keep_explicit_type_hint = keep_explicit;
+        self
+    }
+
+    /// New memories' jobs go through the outbox: the store writes them with the memory and
+    /// these relays hand them to the pipelines, so the service only wakes the relays.
+    pub fn with_outbox(mut self, relays: Vec<crate::outbox::OutboxRelay>) -> Self {
+        self.outbox_relays = relays;
+        self
+    }
+
+    /// Send memory lifecycle events (created, consolidated, reinforced, deleted) through `events`.
+    pub fn with_events(mut self, events: crate::events::EventNotifier) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Set the LLM endpoints health_check probes for reachability.
+    pub fn with_health_endpoints(mut self, endpoints: Vec<crate::health::LlmEndpoint>) -> Self {
+        self.health_endpoints = endpoints;
+        self
+    }
+
+    /// Set the extraction provider used by ingest_conversation and the transcript
+    /// characters sent per LLM call.
+    pub fn with_conversation_extractor(
+        mut self,
+        provider: Arc<dyn crate::extraction::ExtractionProvider>,
+        chunk_chars: usize,
+    ) -> Self {
+        self.conversation_extractor = Some((provider, chunk_chars.max(1)));
+        self
+    }
+
+    /// Look for a live memory that duplicates `input` (dedup_on_store).
+    ///
+    /// Checks the normalized content hash first, then — when an embedding provider is
+    /// configured — probes for a near-duplicate by cosine similarity. Returns the existing
+    /// memory with the match kind ("exact" or "near") and similarity.
+    async fn find_duplicate(&self, input: &CreateMemory) -> Result<Option<(Memory, &'static str, f64)>, MemcpError> {
+        let (Some(config), Some(pg_store)) = (&self.dedup_config, &self.pg_store) else {
+            return Ok(None);
+        };
+
+        if let Some(existing) = pg_store.find_exact_duplicate(&input.content, &input.namespace).await? {
+            return Ok(Some((existing, "exact", 1.0)));
         }
-        MemcpError::Validation { message, field } => {
-            let mut obj = json!({
-                "isError": true,
-                "error": message,
-            });
-            if let Some(f) = field {
-                obj["field"] = json!(f);
-            }
-            CallToolResult::structured_error(obj)
+
+        let Some(ref provider) = self.embedding_provider else {
+            return Ok(None);
+        };
+        if config.similarity_threshold > 1.0 {
+            return Ok(None);
         }
-        MemcpError::Storage(msg) => {
-            CallToolResult::structured_error(json!({
-                "isError": true,
-                "error": format!("Storage error: {}", msg)
-            }))
+        let tags = input.tags.as_ref().map(|t| json!(t));
+        let text = self.embedding_template.render(&crate::embedding::template::TemplateValues {
+            content: &input.content,
+            type_hint: &input.type_hint,
+            source: &input.source,
+            tags: &tags,
+            entities: &None,
+            payload: &input.payload,
+        });
+        let embedding = match provider.embed(&text).await {
+            Ok(vec) => pgvector::Vector::from(vec),
+            Err(e) => {
+                // A failed probe must not block the write — fall back to exact matching only
+                tracing::warn!(error = %e, "Dedup similarity probe failed to embed content, skipping");
+                return Ok(None);
+            }
+        };
+        Ok(pg_store
+            .find_near_duplicate(embedding, &input.namespace, config.similarity_threshold)
+            .await?
+            .map(|hit| (hit.memory, "near", hit.similarity)))
+    }
+
+    /// Look for a live memory that duplicates an imported one.
+    ///
+    /// Same as find_duplicate, except exact content matches are checked even when
+    /// dedup_on_store is off, so importing the same pack twice doesn't double it.
+    async fn find_import_duplicate(&self, input: &CreateMemory) -> Result<Option<(String, &'static str)>, MemcpError> {
+        if let Some((existing, match_kind, _)) = self.find_duplicate(input).await? {
+            return Ok(Some((existing.id, match_kind)));
         }
-        other => {
-            CallToolResult::structured_error(json!({
-                "isError": true,
-                "error": other.to_string()
-            }))
+        match (&self.dedup_config, &self.pg_store) {
+            (None, Some(pg_store)) => Ok(pg_store
+                .find_exact_duplicate(&input.content, &input.namespace)
+                .await?
+                .map(|existing| (existing.id, "exact"))),
+            _ => Ok(None),
         }
     }
-}
 
-// Helper: parse optional ISO-8601 string to DateTime<Utc>
-fn parse_datetime(s: &str, field: &str) -> Result<chrono::DateTime<chrono::Utc>, CallToolResult> {
-    DateTime::parse_from_rfc3339(s)
-        .map(|dt| dt.with_timezone(&chrono::Utc))
-        .map_err(|_| {
-            CallToolResult::structured_error(json!({
-                "isError": true,
-                "error": format!("Invalid datetime format for '{}': expected ISO-8601 (e.g. 2026-02-17T00:00:00Z)", field),
-                "field": field
-            }))
-        })
-}
+    /// Drop all cached search responses after a write that may change results.
+    fn invalidate_search_cache(&self) {
+        if let Some(ref cache) = self.search_cache {
+            cache.clear();
+        }
+    }
 
-// Tool implementations
-#[rmcp::tool_router]
-impl MemoryService {
-    #[tool(description = "Store a new memory with content, type hint, source, and tags. Returns the created memory with its ID.")]
-    async fn store_memory(
-        &self,
-        Parameters(params): Parameters<StoreMemoryParams>,
-    ) -> Result<CallToolResult, McpError> {
-        tracing::info!(
-            tool = "store_memory",
-            type_hint = ?params.type_hint,
-            source = ?params.source,
-            "Tool called"
-        );
+    /// Emit a `created` event per namespace for freshly stored memories.
+    fn emit_created<'a>(&self, memories: impl IntoIterator<Item = &'a Memory>) {
+        let mut by_namespace: Vec<(String, Vec<String>)> = Vec::new();
+        for memory in memories {
+            match by_namespace.iter_mut().find(|(ns, _)| *ns == memory.namespace) {
+                Some((_, ids)) => ids.push(memory.id.clone()),
+                None => by_namespace.push((memory.namespace.clone(), vec![memory.id.clone()])),
+            }
+        }
+        for (namespace, ids) in by_namespace {
+            self.events.emit(crate::events::MemoryEvent::new(crate::events::EventKind::Created, &namespace, ids));
+        }
+    }
 
-        if params.content.trim().is_empty() {
-            return Ok(CallToolResult::structured_error(json!({
-                "isError": true,
-                "error": "Field 'content' is required and cannot be empty",
-                "field": "content"
-            })));
+    /// After a filter-based delete: drop cached searches and emit a `deleted` event with the
+    /// count (the deleted IDs aren't known).
+    fn bulk_deleted(&self, namespace: &str, count: u64, permanent: bool) {
+        self.invalidate_search_cache();
+        if count > 0 {
+            self.events.emit(
+                crate::events::MemoryEvent::new(crate::events::EventKind::Deleted, namespace, Vec::new())
+                    .with_count(count)
+                    .with_data(json!({ "permanent": permanent, "bulk": true })),
+            );
         }
+    }
 
-        let input = CreateMemory {
-            content: params.content,
-            type_hint: params.type_hint.unwrap_or_else(|| "fact".to_string()),
-            source: params.source.unwrap_or_else(|| "default".to_string()),
-            tags: params.tags,
-            created_at: None,
+    /// Write one audit log entry for a finished tool call in the background, so a slow or
+    /// failing audit insert never delays or fails the call itself.
+    fn record_audit(
+        &self,
+        tool: String,
+        params: serde_json::Value,
+        result: &Result<CallToolResult, McpError>,
+        elapsed: Duration,
+    ) {
+        let Some(pg_store) = self.pg_store.clone() else {
+            return;
         };
-
-        match self.store.store(input).await {
-            Ok(memory) => {
-                // Enqueue background embedding job (non-blocking)
-                if let Some(ref pipeline) = self.pipeline {
-                    let text = crate::embedding::build_embedding_text(&memory.content, &memory.tags);
-                    pipeline.enqueue(EmbeddingJob {
-                        memory_id: memory.id.clone(),
-                        text,
-                        attempt: 0,
-                    });
-                }
-                // Enqueue background extraction job (non-blocking)
-                if let Some(ref extraction_pipeline) = self.extraction_pipeline {
-                    extraction_pipeline.enqueue(ExtractionJob {
-                        memory_id: memory.id.clone(),
-                        content: memory.content.clone(),
-                        attempt: 0,
-                    });
-                }
-                Ok(CallToolResult::structured(json!({
-                    "id": memory.id,
-                    "content": memory.content,
-                    "type_hint": memory.type_hint,
-                    "source": memory.source,
-                    "tags": memory.tags,
-                    "created_at": memory.created_at.to_rfc3339(),
-                    "updated_at": memory.updated_at.to_rfc3339(),
-                    "access_count": memory.access_count,
-                    "embedding_status": memory.embedding_status,
-                    "hint": "Use get_memory with this ID to retrieve, or update_memory to modify"
-                })))
+        let (outcome, error_code, structured) = match result {
+            Ok(r) if r.is_error == Some(true) => {
+                let code = r
+                    .structured_content
+                    .as_ref()
+                    .and_then(|v| v.get("code"))
+                    .and_then(|c| c.as_str())
+                    .map(str::to_string);
+                ("error", code, r.structured_content.as_ref())
             }
-            Err(e) => Ok(store_error_to_result(e)),
-        }
+            Ok(r) => ("ok", None, r.structured_content.as_ref()),
+            // Protocol-level failure (unknown tool, malformed arguments): keep the JSON-RPC code
+            Err(e) => ("error", Some(e.code.0.to_string()), None),
+        };
+        let entry = crate::audit::AuditEntry {
+            memory_ids: crate::audit::affected_memory_ids(&params, structured),
+            params_json: params.to_string(),
+            tool,
+            latency_ms: elapsed.as_millis().min(i32::MAX as u128) as i32,
+            outcome: outcome.to_string(),
+            error_code,
+        };
+        tokio::spawn(async move {
+            if let Err(e) = pg_store.record_audit(&entry).await {
+                tracing::warn!(tool = %entry.tool, error = %e, "Failed to record audit log entry");
+            }
+        });
     }
 
-    #[tool(description = "Retrieve a specific memory by ID. Also updates access count and last accessed timestamp.")]
-    async fn get_memory(
+    fn uptime_seconds(&self) -> u64 {
+        self.start_time.elapsed().as_secs()
+    }
+
+    /// Queue background embedding and extraction for a freshly stored memory (non-blocking).
+    ///
+    /// `explicit_type_hint` says whether the caller chose the memory's type_hint; extraction
+    /// classifies the memory unless that hint is kept. Returns false when a saturated
+    /// pipeline dropped a job; the memory stays pending and is picked up by the next backfill.
+    fn enqueue_new_memory(&self, memory: &Memory, explicit_type_hint: bool) -> bool {
+        // The store already wrote this memory's jobs to the outbox — just skip the poll wait
+        if !self.outbox_relays.is_empty() {
+            self.outbox_relays.iter().for_each(crate::outbox::OutboxRelay::wake);
+            return true;
+        }
+        let mut accepted = true;
+        if let Some(ref pipeline) = self.pipeline {
+            let text = self.embedding_template.render_memory(memory);
+            accepted &= pipeline.enqueue(EmbeddingJob {
+                memory_id: memory.id.clone(),
+                text,
+                attempt: 0,
+            });
+        }
+        if let Some(ref extraction_pipeline) = self.extraction_pipeline {
+            accepted &= extraction_pipeline.enqueue(ExtractionJob {
+                memory_id: memory.id.clone(),
+                content: memory.content.clone(),
+                attempt: 0,
+                classify: self.classifies(explicit_type_hint),
+            });
+        }
+        accepted
+    }
+
+    /// Store an imported memory's packed embedding instead of re-embedding it.
+    ///
+    /// Only used when the embedding comes from this server's model with its dimension and no
+    /// outbox relay has already queued the memory. Returns false when the memory still needs
+    /// an embedding job.
+    async fn reuse_packed_embedding(
+        &self,
+        memory: &Memory,
+        embedding: Option<&crate::memory_pack::PackedEmbedding>,
+    ) -> bool {
+        let (Some(embedding), Some(provider), Some(pg_store)) = (embedding, &self.embedding_provider, &self.pg_store) else {
+            return false;
+        };
+        if !self.outbox_relays.is_empty()
+            || embedding.model != provider.model_name()
+            || embedding.vector.len() != provider.dimension()
+        {
+            return false;
+        }
+        let vector = pgvector::Vector::from(embedding.vector.clone());
+        let id = uuid::Uuid::new_v4().to_string();
+        let stored = pg_store
+            .insert_embedding(&id, &memory.id, &embedding.model, "v1", provider.dimension() as i32, &vector, true)
+            .await;
+        match stored {
+            Ok(()) => match pg_store.update_embedding_status(&memory.id, "complete").await {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!(memory_id = %memory.id, error = %e, "Failed to mark imported embedding complete");
+                    false
+                }
+            },
+            Err(e) => {
+                tracing::warn!(memory_id = %memory.id, error = %e, "Failed to reuse imported embedding, re-embedding");
+                false
+            }
+        }
+    }
+
+    /// Whether extraction should classify a new memory's type_hint.
+    fn classifies(&self, explicit_type_hint: bool) -> bool {
+        self.classify_type_hint && !(explicit_type_hint && self.keep_explicit_type_hint)
+    }
+
+    /// Whether `content` is longer than content.max_chars (0 = no limit).
+    fn is_oversized(&self, content: &str) -> bool {
+        self.content_config.max_chars > 0 && content.chars().count() > self.content_config.max_chars
+    }
+
+    /// Split oversized content per content.oversize: None when it fits, the chunks when
+    /// chunking applies, or a validation error when oversized content is rejected.
+    fn plan_chunks(&self, content: &str) -> Result<Option<Vec<String>>, CallToolResult> {
+        if !self.is_oversized(content) {
+            return Ok(None);
+        }
+        let config = &self.content_config;
+        if config.oversize == "reject" || self.pg_store.is_none() {
+            return Err(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Field 'content' exceeds the {} character limit", config.max_chars),
+                "field": "content",
+                "hint": "Split the content into several smaller memories"
+            })));
+        }
+        Ok(Some(crate::chunking::chunk_content(content, config.chunk_chars, config.chunk_overlap)))
+    }
+
+    /// Run content through the privacy filters (unchanged without them). Fails only when a
+    /// filter fails and privacy.fail_closed is set.
+    async fn scrub_content(
+        &self,
+        content: String,
+    ) -> Result<(String, Vec<crate::privacy::Redaction>), CallToolResult> {
+        let Some(scrubber) = &self.content_scrubber else {
+            return Ok((content, Vec::new()));
+        };
+        match scrubber.scrub(&content).await {
+            Ok(filtered) => Ok((filtered.content, filtered.redactions)),
+            Err(e) => Err(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::PROVIDER_UNAVAILABLE,
+                "error": e.to_string(),
+                "hint": "Content filtering failed and privacy.fail_closed is set — nothing was stored. Retry later."
+            }))),
+        }
+    }
+
+    /// Record the redactions made to a stored memory for audit (PostgreSQL only).
+    async fn record_redactions(&self, memory_id: &str, redactions: &[crate::privacy::Redaction]) {
+        if redactions.is_empty() {
+            return;
+        }
+        metrics::global().content_redactions.inc_by(redactions.len() as u64);
+        if let Some(pg_store) = &self.pg_store {
+            if let Err(e) = pg_store.record_redactions(memory_id, redactions).await {
+                tracing::warn!(memory_id, error = %e, "Failed to record redactions");
+            }
+        }
+    }
+
+    /// Jobs currently waiting in each background pipeline (null when the pipeline is off).
+    fn queue_depth(&self) -> serde_json::Value {
+        json!({
+            "embedding": self.pipeline.as_ref().map(|p| p.queue_depth()),
+            "extraction": self.extraction_pipeline.as_ref().map(|p| p.queue_depth()),
+        })
+    }
+
+    /// Background-processing state of a memory for get_memory's include_pipeline_status:
+    /// each pipeline's status and outbox position, consolidation, and salience.
+    async fn pipeline_status(&self, memory: &Memory) -> Result<serde_json::Value, MemcpError> {
+        let (jobs, salience) = match &self.pg_store {
+            Some(pg_store) => {
+                let jobs = pg_store.pending_pipeline_jobs(&memory.id).await?;
+                let salience = pg_store.get_salience_data(std::slice::from_ref(&memory.id)).await?.remove(&memory.id);
+                (jobs, Some(salience.unwrap_or_default()))
+            }
+            None => (Vec::new(), None),
+        };
+        let job = |kind: crate::outbox::JobKind| {
+            jobs.iter().find(|job| job.kind == kind).map(|job| {
+                json!({
+                    "jobs_ahead": job.jobs_ahead,
+                    "queued_at": job.queued_at.to_rfc3339(),
+                    "claimed_at": job.claimed_at.map(|dt| dt.to_rfc3339()),
+                })
+            })
+        };
+        let count = |value: &Option<serde_json::Value>| value.as_ref().and_then(|v| v.as_array()).map_or(0, Vec::len);
+        let embedded =
+            memory.embedding_status == "complete" || memory.embedding_status == crate::store::CHUNKED_STATUS;
+
+        Ok(json!({
+            "embedding": {
+                "status": memory.embedding_status,
+                "outbox_job": job(crate::outbox::JobKind::Embedding),
+            },
+            "extraction": {
+                "status": memory.extraction_status,
+                "entities": count(&memory.extracted_entities),
+                "facts": count(&memory.extracted_facts),
+                "outbox_job": job(crate::outbox::JobKind::Extraction),
+            },
+            "consolidation": {
+                "is_consolidated_original": memory.is_consolidated_original,
+                "consolidated_into": memory.consolidated_into,
+            },
+            "salience": salience.map(|row| json!({
+                "stability": row.stability,
+                "difficulty": row.difficulty,
+                "reinforcement_count": row.reinforcement_count,
+                "last_reinforced_at": row.last_reinforced_at.map(|dt| dt.to_rfc3339()),
+            })),
+            "queue_depth": self.queue_depth(),
+            // Consolidated originals and archived memories are left out of search entirely
+            "vector_searchable": embedded && !memory.is_consolidated_original && memory.archived_at.is_none(),
+        }))
+    }
+
+    /// Shared body of rename_tag, merge_tags, and delete_tag: rewrite `from` to `to` (None =
+    /// remove) across the namespace, then re-embed the changed memories.
+    async fn rewrite_tags(&self, namespace: Option<String>, from: Vec<String>, to: Option<String>) -> CallToolResult {
+        let namespace = match self.resolve_namespace(namespace) {
+            Ok(ns) => ns,
+            Err(result) => return result,
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Tag management requires PostgreSQL backend"
+                }));
+            }
+        };
+
+        match pg_store.rewrite_tags(&namespace, &from, to.as_deref()).await {
+            Ok(memories) => {
+                if !memories.is_empty() {
+                    self.invalidate_search_cache();
+                }
+                // Tags are part of the embedding text; trashed memories re-embed on restore
+                for memory in memories.iter().filter(|m| m.deleted_at.is_none()) {
+                    self.reprocess_updated_memory(memory, false, true);
+                }
+                let ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+                CallToolResult::structured(json!({
+                    "updated": memories.len(),
+                    "ids": ids,
+                    "namespace": namespace,
+                    "hint": if memories.is_empty() {
+                        "No memories carried these tags — use get_memory_facets to see existing tags"
+                    } else {
+                        "Tags rewritten; previous tags are kept in each memory's revision history"
+                    }
+                }))
+            }
+            Err(e) => store_error_to_result(e),
+        }
+    }
+
+    /// Queue re-embedding / re-extraction after an update changed content or tags (non-blocking).
+    ///
+    /// `metadata_changed` covers tags and any other field the embedding template includes.
+    fn reprocess_updated_memory(&self, memory: &Memory, content_changed: bool, metadata_changed: bool) {
+        // Only the chunks of a chunked memory are ever embedded or extracted
+        if memory.embedding_status == crate::store::CHUNKED_STATUS {
+            return;
+        }
+        // Re-embed when content or embedded metadata change (tags are part of the embedding text)
+        if content_changed || metadata_changed {
+            if let Some(ref pipeline) = self.pipeline {
+                let text = self.embedding_template.render_memory(memory);
+                pipeline.enqueue(EmbeddingJob {
+                    memory_id: memory.id.clone(),
+                    text,
+                    attempt: 0,
+                });
+            }
+        }
+        // Re-extract when content changes (extraction is content-only, not tags)
+        if content_changed {
+            if let Some(ref extraction_pipeline) = self.extraction_pipeline {
+                // Reset extraction status to pending, then enqueue
+                if let Some(ref pg_store) = self.pg_store {
+                    let store = pg_store.clone();
+                    let id = memory.id.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = store.update_extraction_status(&id, "pending").await {
+                            tracing::warn!("Failed to reset extraction status for {}: {}", id, e);
+                        }
+                    });
+                }
+                extraction_pipeline.enqueue(ExtractionJob {
+                    memory_id: memory.id.clone(),
+                    content: memory.content.clone(),
+                    attempt: 0,
+                    classify: false,
+                });
+            }
+        }
+    }
+
+    /// search_memory for stores without hybrid search (e.g. the in-memory store): one vector
+    /// leg through MemoryStore::search_similar, with no BM25 or symbolic legs, salience
+    /// re-ranking, or query intelligence. Supports the date, tag, type_hint, source, and cursor
+    /// parameters.
+    async fn search_vector_only(
+        &self,
+        params: &SearchMemoryParams,
+        namespace: &str,
+        limit: u32,
+        min_relevance: f64,
+    ) -> CallToolResult {
+        let Some(ref provider) = self.embedding_provider else {
+            return CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::BACKEND_UNSUPPORTED,
+                "error": "Search requires PostgreSQL backend or an embedding provider",
+                "hint": "Use list_memories to browse memories"
+            }));
+        };
+        let created_after = match params.created_after.as_deref().map(|s| parse_datetime(s, "created_after")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return result,
+        };
+        let created_before = match params.created_before.as_deref().map(|s| parse_datetime(s, "created_before")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return result,
+        };
+        let AccessFilters { min_access_count, accessed_after, never_accessed } =
+            match check_access_filters(params.min_access_count, params.accessed_after.as_deref(), params.never_accessed) {
+                Ok(filters) => filters,
+                Err(result) => return result,
+            };
+        let offset = match params.cursor.as_deref().map(crate::store::decode_search_cursor).transpose() {
+            Ok(offset) => offset.unwrap_or(0),
+            Err(e) => return store_error_to_result(e),
+        };
+        let query_embedding = match provider.embed(&params.query).await {
+            Ok(vector) => pgvector::Vector::from(vector),
+            Err(e) => {
+                return CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::PROVIDER_ERROR,
+                    "error": format!("Failed to embed query: {}", e)
+                }));
+            }
+        };
+
+        let filter = crate::store::SearchFilter {
+            query_embedding,
+            limit: limit as i64,
+            offset,
+            created_after,
+            created_before,
+            tags: params.tags.clone(),
+            namespace: Some(namespace.to_string()),
+            type_hint: params.type_hint.clone(),
+            source: params.source.clone(),
+            model: None,
+            payload_path: params.payload_path.clone(),
+            origin_ref: params.origin_ref.clone().filter(|r| !r.is_empty()),
+            min_access_count,
+            accessed_after,
+            never_accessed,
+            exclude_tags: params.exclude_tags.clone().unwrap_or_default(),
+            exclude_sources: params.exclude_sources.clone().unwrap_or_default(),
+            exclude_type_hints: params.exclude_type_hints.clone().unwrap_or_default(),
+        };
+        let mut result = match self.store.search_similar(&filter).await {
+            Ok(result) => result,
+            Err(e) => return store_error_to_result(e),
+        };
+
+        // Pinned memories matching the filters lead the first page whatever their similarity;
+        // every page leaves them out of the ranked hits
+        let pinned_filter = crate::store::SearchFilter { limit: MAX_PINNED_RESULTS, offset: 0, ..filter.clone() };
+        let pinned = match self.store.pinned_memories(&pinned_filter).await {
+            Ok(pinned) => pinned,
+            Err(e) => return store_error_to_result(e),
+        };
+        let similarities: HashMap<String, f64> =
+            result.hits.iter().map(|hit| (hit.memory.id.clone(), hit.similarity)).collect();
+        let pinned_ids: HashSet<&str> = pinned.iter().map(|m| m.id.as_str()).collect();
+        result.hits.retain(|hit| !pinned_ids.contains(hit.memory.id.as_str()));
+
+        // Hits are ordered by similarity, so once one falls below min_relevance every later
+        // page would too
+        let before = result.hits.len();
+        result.hits.retain(|hit| hit.similarity >= min_relevance);
+        let filtered_out = before - result.hits.len();
+        if filtered_out > 0 {
+            result.has_more = false;
+            result.next_cursor = None;
+        }
+
+        let to_json = |memory: &Memory, similarity: f64, match_source: &str| {
+            let mut obj = json!({
+                "id": memory.id,
+                "content": memory.content,
+                "type_hint": memory.type_hint,
+                "source": memory.source,
+                "tags": memory.tags,
+                "created_at": memory.created_at.to_rfc3339(),
+                "updated_at": memory.updated_at.to_rfc3339(),
+                "access_count": memory.access_count,
+                "importance": memory.importance,
+                "relevance_score": (similarity * 1000.0).round() / 1000.0,
+                "match_source": match_source,
+                "payload": memory.payload,
+                "origin_refs": memory.origin_refs,
+                "pinned": memory.pinned,
+            });
+            apply_snippet(&mut obj, &memory.content, None, params.snippet_chars);
+            obj
+        };
+        let leading = if offset == 0 { pinned.as_slice() } else { &[] };
+        let memories: Vec<serde_json::Value> = leading
+            .iter()
+            .map(|m| to_json(m, similarities.get(&m.id).copied().unwrap_or(0.0), "pinned"))
+            .chain(result.hits.iter().map(|hit| to_json(&hit.memory, hit.similarity, "vector")))
+            .collect();
+        let mut response = json!({
+            "memories": memories,
+            "total_results": memories.len(),
+            "query": params.query,
+            "namespace": namespace,
+            "fusion": "vector",
+            "has_more": result.has_more,
+            "next_cursor": result.next_cursor,
+        });
+        if memories.is_empty() {
+            response["hint"] = json!("No memories matched your query. Try broader search terms or use list_memories to browse all memories.");
+        }
+        report_relevance_filter(&mut response, min_relevance, filtered_out, true);
+        CallToolResult::structured(response)
+    }
+
+    /// Report a memory in another namespace as not found, so namespaces never leak.
+    ///
+    /// Returns Some(error result) when the caller must stop; None to proceed.
+    async fn reject_foreign_namespace(&self, id: &str, namespace: &str) -> Option<CallToolResult> {
+        match self.store.get_memories_by_ids(&[id.to_string()]).await {
+            Ok(found) if found.get(id).is_some_and(|m| m.namespace != namespace) => {
+                Some(store_error_to_result(MemcpError::NotFound { id: id.to_string() }))
+            }
+            Ok(_) => None,
+            Err(e) => Some(store_error_to_result(e)),
+        }
+    }
+
+    /// Validate a batch of memory IDs and reinforce them with `rating` in one transaction.
+    ///
+    /// All-or-nothing: if any ID is missing or belongs to another namespace, nothing is
+    /// reinforced and the missing IDs are reported.
+    async fn reinforce_batch(&self, ids: Vec<String>, namespace: &str, rating: &str) -> CallToolResult {
+        let Some(pg_store) = self.pg_store.as_ref() else {
+            return CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::BACKEND_UNSUPPORTED,
+                "error": "Reinforcement requires PostgreSQL backend"
+            }));
+        };
+
+        let found = match self.store.get_memories_by_ids(&ids).await {
+            Ok(found) => found,
+            Err(e) => return store_error_to_result(e),
+        };
+        let missing: Vec<&String> = ids
+            .iter()
+            .filter(|id| found.get(*id).is_none_or(|m| m.namespace != namespace))
+            .collect();
+        if !missing.is_empty() {
+            return CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::NOT_FOUND,
+                "error": format!("Memories not found: {}", missing.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(", ")),
+                "missing_ids": missing,
+                "hint": "No memories were reinforced. Remove the missing IDs and retry."
+            }));
+        }
+
+        match pg_store.reinforce_salience_batch(&ids, rating).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(rows) => {
+                self.events.emit(
+                    crate::events::MemoryEvent::new(crate::events::EventKind::Reinforced, namespace, ids.clone())
+                        .with_data(json!({ "rating": rating })),
+                );
+                let reinforced: Vec<serde_json::Value> = rows
+                    .iter()
+                    .map(|(id, row)| json!({
+                        "id": id,
+                        "stability": row.stability,
+                        "reinforcement_count": row.reinforcement_count,
+                    }))
+                    .collect();
+                CallToolResult::structured(json!({
+                    "reinforced": reinforced,
+                    "count": reinforced.len(),
+                    "rating": rating,
+                }))
+            }
+            Err(e) => store_error_to_result(e),
+        }
+    }
+
+    /// Check `session_id` (when given) names an open session in `namespace`.
+    ///
+    /// Returns the error result to send back, or None when the memory may be stored.
+    async fn check_open_session(&self, session_id: Option<&str>, namespace: &str) -> Option<CallToolResult> {
+        let session_id = session_id?;
+        let session_error = |message: &str| {
+            CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": message,
+                "field": "session_id",
+                "hint": "Call start_session for a new session ID"
+            }))
+        };
+        let Some(pg_store) = self.pg_store.as_ref() else {
+            return Some(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::BACKEND_UNSUPPORTED,
+                "error": "Sessions require PostgreSQL backend"
+            })));
+        };
+        match pg_store.get_session(session_id).await {
+            Ok(session) if session.namespace != namespace => {
+                Some(session_error("Session belongs to a different namespace"))
+            }
+            Ok(session) if session.ended_at.is_some() => Some(session_error("Session has already ended")),
+            Ok(_) => None,
+            Err(MemcpError::NotFound { .. }) => Some(session_error("Session not found")),
+            Err(e) => Some(store_error_to_result(e)),
+        }
+    }
+
+    /// Store one memory: the shared path behind store_memory and store_structured_memory.
+    ///
+    /// `fields` holds structured field values the caller has already validated.
+    async fn store_single(&self, mut params: StoreMemoryParams, fields: Option<serde_json::Value>) -> CallToolResult {
+        let _timer = metrics::global().store_duration.start_timer();
+
+        if params.content.trim().is_empty() {
+            return CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'content' is required and cannot be empty",
+                "field": "content"
+            }));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return result,
+        };
+
+        let expires_at = match parse_expires_at(params.expires_at.as_deref()) {
+            Ok(dt) => dt,
+            Err(result) => return result,
+        };
+
+        let importance = match check_importance(params.importance, "importance") {
+            Ok(value) => value.unwrap_or(crate::store::DEFAULT_IMPORTANCE),
+            Err(result) => return result,
+        };
+
+        if let Err(result) = check_payload(params.payload.as_ref()) {
+            return result;
+        }
+
+        if let Some(result) = self.check_open_session(params.session_id.as_deref(), &namespace).await {
+            return result;
+        }
+
+        let redactions = match self.scrub_content(std::mem::take(&mut params.content)).await {
+            Ok((content, redactions)) => {
+                params.content = content;
+                redactions
+            }
+            Err(result) => return result,
+        };
+
+        let chunks = match self.plan_chunks(&params.content) {
+            Ok(chunks) => chunks,
+            Err(result) => return result,
+        };
+
+        let explicit_type_hint = params.type_hint.is_some();
+        let input = CreateMemory {
+            content: params.content,
+            type_hint: params.type_hint.unwrap_or_else(|| "fact".to_string()),
+            source: params.source.unwrap_or_else(|| "default".to_string()),
+            tags: params.tags,
+            created_at: None,
+            namespace,
+            expires_at,
+            importance,
+            session_id: params.session_id,
+            fields,
+            payload: params.payload,
+            origin_refs: origin_refs(params.origin_ref),
+            classify: self.classifies(explicit_type_hint),
+        };
+
+        match self.find_duplicate(&input).await {
+            Ok(Some((existing, match_kind, similarity))) => {
+                tracing::info!(duplicate_of = %existing.id, match_kind, "Skipped storing duplicate memory");
+                return CallToolResult::structured(json!({
+                    "id": existing.id,
+                    "duplicate_of": existing.id,
+                    "stored": false,
+                    "match": match_kind,
+                    "similarity": (similarity * 1000.0).round() / 1000.0,
+                    "content": existing.content,
+                    "type_hint": existing.type_hint,
+                    "source": existing.source,
+                    "tags": existing.tags,
+                    "created_at": existing.created_at.to_rfc3339(),
+                    "namespace": existing.namespace,
+                    "hint": "An equivalent memory already exists — nothing was stored. Use update_memory to change it."
+                }));
+            }
+            Ok(None) => {}
+            Err(e) => return store_error_to_result(e),
+        }
+
+        // plan_chunks only returns chunks when the PostgreSQL store is available
+        let stored = match (chunks, &self.pg_store) {
+            (Some(chunks), Some(pg_store)) => pg_store.store_chunked(input, chunks).await,
+            _ => self.store.store(input).await.map(|memory| (memory, Vec::new())),
+        };
+
+        match stored.inspect(|_| self.invalidate_search_cache()) {
+            Ok((memory, chunks)) => {
+                self.record_redactions(&memory.id, &redactions).await;
+                self.emit_created([&memory]);
+                // Enqueue background embedding + extraction jobs (non-blocking); a chunked
+                // memory is embedded and extracted through its chunks, which keep the parent's type
+                let degraded = if chunks.is_empty() {
+                    !self.enqueue_new_memory(&memory, explicit_type_hint)
+                } else {
+                    chunks.iter().filter(|chunk| !self.enqueue_new_memory(chunk, true)).count() > 0
+                };
+                let mut response = json!({
+                    "id": memory.id,
+                    "content": memory.content,
+                    "type_hint": memory.type_hint,
+                    "source": memory.source,
+                    "tags": memory.tags,
+                    "created_at": memory.created_at.to_rfc3339(),
+                    "updated_at": memory.updated_at.to_rfc3339(),
+                    "access_count": memory.access_count,
+                    "embedding_status": memory.embedding_status,
+                    "namespace": memory.namespace,
+                    "expires_at": memory.expires_at.map(|dt| dt.to_rfc3339()),
+                    "importance": memory.importance,
+                    "session_id": memory.session_id,
+                    "queue_depth": self.queue_depth(),
+                    "degraded": degraded,
+                    "hint": if degraded {
+                        "Stored, but background pipelines are saturated — embedding and extraction are deferred to the next backfill. Slow down bulk writes."
+                    } else {
+                        "Use get_memory with this ID to retrieve, or update_memory to modify"
+                    }
+                });
+                if !chunks.is_empty() {
+                    response["chunk_count"] = json!(chunks.len());
+                    response["chunk_ids"] = json!(chunks.iter().map(|c| c.id.as_str()).collect::<Vec<_>>());
+                    if !degraded {
+                        response["hint"] = json!("Content exceeded the size limit and was stored as chunks. search_memory returns this memory with the matching chunks in matched_chunks.");
+                    }
+                }
+                if let Some(fields) = memory.fields {
+                    response["fields"] = fields;
+                }
+                if !redactions.is_empty() {
+                    response["redactions"] = json!(redactions.len());
+                }
+                CallToolResult::structured(response)
+            }
+            Err(e) => store_error_to_result(e),
+        }
+    }
+
+    /// Build the memory://session-primer text: pinned memories, condensed summaries of recent
+    /// sessions, then the most salient recent memories (those since the latest summary, when
+    /// there is one), trimmed to resources.max_chars.
+    async fn session_primer_text(&self) -> Result<String, MemcpError> {
+        let primer = &self.resources_config;
+        // Prefer condensed summaries of recent sessions over a raw list of new rows
+        let summaries = match &self.pg_store {
+            Some(pg_store) => pg_store
+                .recent_session_summaries(&self.default_namespace, 5)
+                .await?,
+            None => Vec::new(),
+        };
+
+        // Candidates are the newest memories of each configured type; with summaries, only
+        // memories newer than the latest one
+        let type_hints: Vec<Option<String>> = if primer.type_hints.is_empty() {
+            vec![None]
+        } else {
+            primer.type_hints.iter().cloned().map(Some).collect()
+        };
+        let mut candidates = Vec::new();
+        for type_hint in type_hints {
+            let filter = ListFilter {
+                namespace: Some(self.default_namespace.clone()),
+                type_hint,
+                created_after: summaries.first().map(|(_, summary)| summary.created_at),
+                limit: PRIMER_CANDIDATES,
+                ..Default::default()
+            };
+            candidates.extend(self.store.list(filter).await?.memories);
+        }
+
+        // Pinned memories always lead the primer, whatever their age or salience
+        let pinned_filter = crate::store::SearchFilter {
+            namespace: Some(self.default_namespace.clone()),
+            limit: MAX_PINNED_RESULTS,
+            ..Default::default()
+        };
+        let pinned = self.store.pinned_memories(&pinned_filter).await?;
+        candidates.retain(|m| !m.pinned);
+        let mut memories = self.rank_by_salience(candidates).await?;
+        memories.truncate(primer.count);
+
+        let entries = |memories: &[Memory]| -> Vec<String> {
+            memories.iter().map(|m| format_memories_text(std::slice::from_ref(m))).collect()
+        };
+        let mut sections = vec![(Some("Pinned:"), entries(&pinned))];
+        if !summaries.is_empty() {
+            let summary_entries = summaries
+                .iter()
+                .map(|summary| format_session_summaries(std::slice::from_ref(summary)))
+                .collect();
+            sections.push((Some("Recent sessions:"), summary_entries));
+            sections.push((Some("Since the last session:"), entries(&memories)));
+        } else {
+            sections.push((None, entries(&memories)));
+        }
+        let text = render_primer(sections, primer.max_chars);
+        if text.is_empty() {
+            return Ok("No memories stored yet. Use store_memory to add your first memory.".to_string());
+        }
+        Ok(text)
+    }
+
+    /// Order memories by salience without a query: recency, access, reinforcement, and
+    /// importance, weighted by the live [salience] settings.
+    async fn rank_by_salience(&self, memories: Vec<Memory>) -> Result<Vec<Memory>, MemcpError> {
+        let ids: Vec<String> = memories.iter().map(|m| m.id.clone()).collect();
+        let salience_data = match &self.pg_store {
+            Some(pg_store) => pg_store.get_salience_data(&ids).await?,
+            None => HashMap::new(),
+        };
+        let mut hits: Vec<ScoredHit> = memories
+            .into_iter()
+            .map(|memory| ScoredHit {
+                memory,
+                rrf_score: 0.0,
+                salience_score: 0.0,
+                match_source: "primer".to_string(),
+                breakdown: None,
+            })
+            .collect();
+        let salience_inputs: Vec<SalienceInput> = hits
+            .iter()
+            .map(|hit| {
+                let row = salience_data.get(&hit.memory.id).cloned().unwrap_or_default();
+                let days_since_reinforced = row
+                    .last_reinforced_at
+                    .map(|dt| (Utc::now().signed_duration_since(dt).num_seconds() as f64 / 86_400.0).max(0.0))
+                    .unwrap_or(365.0);
+                SalienceInput { stability: row.stability, days_since_reinforced, link_degree: 0 }
+            })
+            .collect();
+        // No query to be relevant to, and link degrees aren't fetched
+        let config = SalienceConfig { w_semantic: 0.0, w_links: 0.0, ..self.live.load().salience.clone() };
+        SalienceScorer::new(&config).rank(&mut hits, &salience_inputs);
+        Ok(hits.into_iter().map(|hit| hit.memory).collect())
+    }
+
+    /// Build the memory://daily-digest text: memories from the digest window grouped by
+    /// type_hint and source, optionally opened by a narrative from the summary provider.
+    async fn daily_digest(&self) -> Result<String, MemcpError> {
+        let since = Utc::now() - chrono::Duration::hours(self.digest_config.window_hours as i64);
+        let max = self.digest_config.max_memories.max(1);
+
+        // list() pages at most 100 rows; follow the cursor up to max_memories
+        let mut memories: Vec<Memory> = Vec::new();
+        let mut cursor = None;
+        loop {
+            let filter = ListFilter {
+                namespace: Some(self.default_namespace.clone()),
+                created_after: Some(since),
+                limit: (max - memories.len()).min(100) as i64,
+                cursor,
+                ..Default::default()
+            };
+            let page = self.store.list(filter).await?;
+            memories.extend(page.memories);
+            cursor = page.next_cursor;
+            if cursor.is_none() || memories.len() >= max {
+                break;
+            }
+        }
+
+        if memories.is_empty() {
+            return Ok(format!(
+                "No memories created in the last {}h. Use store_memory to add memories.",
+                self.digest_config.window_hours
+            ));
+        }
+
+        let mut text = String::new();
+        if let (true, Some(provider)) = (self.digest_config.summarize, &self.summary_provider) {
+            let contents: Vec<&str> = memories.iter().map(|m| m.content.as_str()).collect();
+            match provider.summarize(&contents, Some("a daily stand-up: what was learned, decided, or changed")).await {
+                Ok(summary) => text.push_str(&format!("Summary:\n{}\n\n", summary)),
+                Err(e) => tracing::warn!(error = %e, "Daily digest summary failed — listing memories only"),
+            }
+        }
+        text.push_str(&format_digest(&memories, self.digest_config.window_hours, since));
+        Ok(text)
+    }
+
+    /// Preference memories for memory://user-profile and the summarize_user_profile prompt.
+    async fn user_profile_memories(&self, namespace: String) -> Result<Vec<Memory>, MemcpError> {
+        let filter = ListFilter {
+            namespace: Some(namespace),
+            type_hint: Some("preference".to_string()),
+            limit: 50,
+            ..Default::default()
+        };
+        Ok(self.store.list(filter).await?.memories)
+    }
+
+    /// Render one of the prompts from `prompt_list` with its (string) arguments.
+    async fn prompt(
+        &self,
+        name: &str,
+        arguments: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<GetPromptResult, McpError> {
+        let argument = |key: &str| {
+            arguments
+                .get(key)
+                .and_then(|value| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let namespace = self
+            .resolve_namespace(argument("namespace"))
+            .map_err(|_| McpError::invalid_params("Argument 'namespace' cannot be empty", None))?;
+
+        match name {
+            "recall_context" => {
+                let topic = argument("topic")
+                    .ok_or_else(|| McpError::invalid_params("Prompt 'recall_context' requires the 'topic' argument", None))?;
+                let limit = match argument("limit") {
+                    Some(limit) => match limit.parse::<u32>() {
+                        Ok(limit @ 1..=50) => limit,
+                        _ => return Err(McpError::invalid_params("Argument 'limit' must be a number from 1 to 50", None)),
+                    },
+                    None => 5,
+                };
+                let params = serde_json::from_value(json!({"query": topic, "limit": limit, "namespace": namespace}))
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                let result = self.search_memory(Parameters(params)).await?;
+                let body = result.structured_content.unwrap_or_default();
+                if result.is_error == Some(true) {
+                    let error = body["error"].as_str().unwrap_or("Search failed");
+                    return Err(McpError::internal_error(error.to_string(), None));
+                }
+                let memories = body["memories"].as_array().cloned().unwrap_or_default();
+
+                let mut text = if memories.is_empty() {
+                    format!("There are no stored memories about \"{}\".", topic)
+                } else {
+                    let lines: Vec<String> = memories.iter().map(format_recalled_memory).collect();
+                    format!(
+                        "Here is what I remember about \"{}\", most relevant first:\n\n{}\n\n\
+                         Use these memories as context. Prefer newer ones when they conflict, and say so \
+                         when they don't cover something.",
+                        topic,
+                        lines.join("\n")
+                    )
+                };
+                if let Some(task) = argument("task") {
+                    text.push_str(&format!("\n\nTask: {}", task));
+                }
+                Ok(GetPromptResult {
+                    description: Some(format!("Memories about \"{}\" ({} found)", topic, memories.len())),
+                    messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+                })
+            }
+            "summarize_user_profile" => {
+                let memories = self
+                    .user_profile_memories(namespace)
+                    .await
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                let text = if memories.is_empty() {
+                    "No user preferences are stored yet, so there is no profile to summarize. Say so, and \
+                     suggest storing preferences with store_memory (type_hint: \"preference\")."
+                        .to_string()
+                } else {
+                    format!(
+                        "Summarize the user's profile from these stored preferences. Group them by theme \
+                         (e.g. tools, communication style, workflow), keep it to a short bulleted list, and \
+                         when two preferences conflict keep the newer one and mention the change.\n\n{}",
+                        format_memories_text(&memories)
+                    )
+                };
+                Ok(GetPromptResult {
+                    description: Some(format!("User profile from {} stored preferences", memories.len())),
+                    messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+                })
+            }
+            other => Err(McpError::invalid_params(format!("Prompt not found: {}", other), None)),
+        }
+    }
+
+    /// Resolve a per-call namespace, falling back to the configured default.
+    fn resolve_namespace(&self, namespace: Option<String>) -> Result<String, CallToolResult> {
+        match namespace {
+            None => Ok(self.default_namespace.clone()),
+            Some(ns) if ns.trim().is_empty() => Err(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'namespace' cannot be empty",
+                "field": "namespace"
+            }))),
+            Some(ns) => Ok(ns.trim().to_string()),
+        }
+    }
+}
+
+// Parameter structs
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct StoreMemoryParams {
+    /// The memory content to store (required)
+    pub content: String,
+    /// Classification hint: "fact", "preference", "instruction", etc. (default: "fact", replaced
+    /// by the classified type once extraction runs when auto-classification is enabled)
+    pub type_hint: Option<String>,
+    /// Origin source: "user", "assistant", "system", etc. (default: "default")
+    pub source: Option<String>,
+    /// Optional tags for categorization
+    pub tags: Option<Vec<String>>,
+    /// Namespace to store into (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// ISO-8601 timestamp after which the memory expires and is removed (optional, must be in the future)
+    pub expires_at: Option<String>,
+    /// Importance from 1 (trivia) to 5 (critical instruction) (default: 3)
+    pub importance: Option<u8>,
+    /// Open session to attach the memory to, as returned by start_session (optional)
+    pub session_id: Option<String>,
+    /// Machine-readable attachment returned with the memory — a JSON blob, URLs, a code
+    /// snippet (optional, up to 64 KiB). Not embedded; filter on it with `payload_path`.
+    pub payload: Option<serde_json::Value>,
+    /// Where the memory came from — conversation_id, message_id, file_path, and/or url
+    /// (optional). Returned with the memory and filterable in list_memories and search_memory.
+    pub origin_ref: Option<OriginRef>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct StoreStructuredMemoryParams {
+    /// Memory type defined with define_memory_type, e.g. "contact" (required). Also becomes
+    /// the memory's type_hint.
+    pub memory_type: String,
+    /// Field values keyed by field name, e.g. {"name": "Ada", "email": "ada@example.com"} (required)
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// Free-text content (default: the fields rendered as "type — field: value; ...")
+    pub content: Option<String>,
+    /// Origin source: "user", "assistant", "system", etc. (default: "default")
+    pub source: Option<String>,
+    /// Optional tags for categorization
+    pub tags: Option<Vec<String>>,
+    /// Namespace the type is defined in and the memory is stored into (default: server's
+    /// configured namespace)
+    pub namespace: Option<String>,
+    /// ISO-8601 timestamp after which the memory expires and is removed (optional, must be in the future)
+    pub expires_at: Option<String>,
+    /// Importance from 1 (trivia) to 5 (critical instruction) (default: 3)
+    pub importance: Option<u8>,
+    /// Open session to attach the memory to, as returned by start_session (optional)
+    pub session_id: Option<String>,
+    /// Machine-readable attachment returned with the memory (optional, up to 64 KiB)
+    pub payload: Option<serde_json::Value>,
+    /// Where the memory came from — conversation_id, message_id, file_path, and/or url (optional)
+    pub origin_ref: Option<OriginRef>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DefineMemoryTypeParams {
+    /// Type name, e.g. "contact" (letters, digits, and underscores; required)
+    pub name: String,
+    /// Fields of the type, e.g. [{"name": "email", "type": "string", "required": true}] (required)
+    pub fields: Vec<crate::memory_types::FieldDef>,
+    /// What memories of this type record (optional)
+    pub description: Option<String>,
+    /// Namespace to define the type in (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ListMemoryTypesParams {
+    /// Namespace to list types from (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+/// Maximum number of memories accepted by a single store_memories call.
+const MAX_BATCH_STORE: usize = 100;
+
+/// Maximum number of IDs accepted by a single get_memories call.
+const MAX_BATCH_GET: usize = 100;
+
+/// Maximum number of memories combined by a single merge_memories call.
+const MAX_MERGE: usize = 50;
+
+/// Maximum number of turns accepted by a single ingest_conversation call.
+const MAX_INGEST_TURNS: usize = 500;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ConversationTurn {
+    /// Speaker role, e.g. "user" or "assistant" (required)
+    pub role: String,
+    /// What was said (required)
+    pub content: String,
+    /// ISO-8601 time of the turn; memories extracted from it use this as created_at (optional)
+    pub timestamp: Option<String>,
+    /// ID of the message in the client's conversation; recorded in the origin_ref of memories
+    /// extracted from the turn (optional)
+    pub message_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct IngestConversationParams {
+    /// Conversation turns in order (1-500 items)
+    pub turns: Vec<ConversationTurn>,
+    /// Source recorded on every extracted memory (default: "conversation")
+    pub source: Option<String>,
+    /// Tags added to every extracted memory (optional)
+    pub tags: Option<Vec<String>>,
+    /// Namespace to store into (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// ID of the conversation; recorded in the origin_ref of every extracted memory (optional)
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct StoreMemoriesParams {
+    /// Memories to store (1-100 items). Each item accepts the same fields as store_memory.
+    pub memories: Vec<StoreMemoryParams>,
+    /// Namespace for items that don't set their own (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetMemoryParams {
+    /// Memory ID to retrieve (required)
+    pub id: String,
+    /// Also return the memory's embedding, extraction, consolidation, and salience state,
+    /// to debug why it isn't searchable yet (default: false)
+    #[serde(default)]
+    pub include_pipeline_status: bool,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetMemoriesParams {
+    /// Memory IDs to retrieve (1-100, required). Results keep this order; repeats are
+    /// returned once.
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct UpdateMemoryParams {
+    /// Memory ID to update (required)
+    pub id: String,
+    /// New content (optional)
+    pub content: Option<String>,
+    /// New classification hint (optional)
+    pub type_hint: Option<String>,
+    /// New origin source (optional)
+    pub source: Option<String>,
+    /// New tags, replaces existing (optional)
+    pub tags: Option<Vec<String>>,
+    /// New ISO-8601 expiration timestamp (optional, must be in the future); null removes
+    /// the memory's expiry
+    #[serde(default, deserialize_with = "nullable")]
+    pub expires_at: Option<Option<String>>,
+    /// New importance, 1-5 (optional)
+    pub importance: Option<u8>,
+    /// New payload, replaces the existing one (optional, up to 64 KiB)
+    pub payload: Option<serde_json::Value>,
+    /// Pin (true) or unpin (false) the memory (optional)
+    pub pinned: Option<bool>,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PinMemoryParams {
+    /// Memory ID to pin or unpin (required)
+    pub id: String,
+    /// true pins the memory, false unpins it (default: true)
+    #[serde(default = "default_pinned")]
+    pub pinned: bool,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+fn default_pinned() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DeleteMemoryParams {
+    /// Memory ID to delete (required)
+    pub id: String,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// Skip the trash and remove the memory permanently (default: false)
+    #[serde(default)]
+    pub permanent: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RestoreMemoryParams {
+    /// ID of the trashed or archived memory to restore (required)
+    pub id: String,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ConsolidateMemoriesParams {
+    /// Maximum memories to check for similar neighbours (1-5000, default: 500)
+    pub limit: Option<u32>,
+    /// Namespace to consolidate (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MergeMemoriesParams {
+    /// IDs of the memories to merge (2-50, required). All must be in the same namespace.
+    pub ids: Vec<String>,
+    /// Content for the merged memory (default: synthesized from the originals by the LLM provider)
+    pub content: Option<String>,
+    /// Namespace the memories belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PurgeTrashParams {
+    /// Namespace whose trash to empty (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// Set to true to confirm purge (default: false — returns count only)
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct BulkDeleteMemoriesParams {
+    /// Filter by type_hint (optional)
+    pub type_hint: Option<String>,
+    /// Filter by source (optional)
+    pub source: Option<String>,
+    /// Delete memories created after this ISO-8601 timestamp (optional)
+    pub created_after: Option<String>,
+    /// Delete memories created before this ISO-8601 timestamp (optional)
+    pub created_before: Option<String>,
+    /// Delete memories updated after this ISO-8601 timestamp (optional)
+    pub updated_after: Option<String>,
+    /// Delete memories updated before this ISO-8601 timestamp (optional)
+    pub updated_before: Option<String>,
+    /// Set to true to confirm deletion (default: false — returns count only)
+    #[serde(default)]
+    pub confirm: bool,
+    /// Namespace to delete from (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// Skip the trash and remove matching memories permanently (default: false)
+    #[serde(default)]
+    pub permanent: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct BulkUpdateMemoriesParams {
+    /// Filter by type_hint (optional)
+    pub type_hint: Option<String>,
+    /// Filter by source (optional)
+    pub source: Option<String>,
+    /// Update memories created after this ISO-8601 timestamp (optional)
+    pub created_after: Option<String>,
+    /// Update memories created before this ISO-8601 timestamp (optional)
+    pub created_before: Option<String>,
+    /// Update memories updated after this ISO-8601 timestamp (optional)
+    pub updated_after: Option<String>,
+    /// Update memories updated before this ISO-8601 timestamp (optional)
+    pub updated_before: Option<String>,
+    /// New type_hint for every matching memory (optional)
+    pub set_type_hint: Option<String>,
+    /// New source for every matching memory (optional)
+    pub set_source: Option<String>,
+    /// New importance for every matching memory, 1-5 (optional)
+    pub set_importance: Option<u8>,
+    /// Tags to add to every matching memory (optional)
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    /// Tags to remove from every matching memory (optional)
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+    /// Set to true to apply the update (default: false — returns count only)
+    #[serde(default)]
+    pub confirm: bool,
+    /// Namespace to update in (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ListMemoriesParams {
+    /// Filter by type_hint (optional)
+    pub type_hint: Option<String>,
+    /// Filter by source (optional)
+    pub source: Option<String>,
+    /// Filter memories created after this ISO-8601 timestamp (optional)
+    pub created_after: Option<String>,
+    /// Filter memories created before this ISO-8601 timestamp (optional)
+    pub created_before: Option<String>,
+    /// Filter memories updated after this ISO-8601 timestamp (optional)
+    pub updated_after: Option<String>,
+    /// Filter memories updated before this ISO-8601 timestamp (optional)
+    pub updated_before: Option<String>,
+    /// Maximum results to return (1-100, default: 20)
+    pub limit: Option<u32>,
+    /// Cursor from previous page for pagination (optional)
+    pub cursor: Option<String>,
+    /// Namespace to list (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// Set to true to list memories in the trash instead of live memories (default: false)
+    pub trashed: Option<bool>,
+    /// Set to true to list only memories archived by salience decay (default: false)
+    pub archived: Option<bool>,
+    /// Only list memories with at least this importance, 1-5 (optional)
+    pub min_importance: Option<u8>,
+    /// Only list memories stored in this session (optional)
+    pub session_id: Option<String>,
+    /// Only list memories whose payload satisfies this SQL/JSON path predicate, e.g.
+    /// `$.language == "rust"` or `exists($.url)` (optional, PostgreSQL backend)
+    pub payload_path: Option<String>,
+    /// Only list memories with an origin reference matching every part given here, e.g.
+    /// {"conversation_id": "c-42"} (optional)
+    pub origin_ref: Option<OriginRef>,
+    /// Only list memories fetched (get_memory/get_memories) at least this many times (optional)
+    pub min_access_count: Option<u32>,
+    /// Only list memories last fetched after this ISO-8601 timestamp (optional)
+    pub accessed_after: Option<String>,
+    /// Set to true to list only memories that were never fetched — cleanup candidates
+    /// (default: false). Can't be combined with min_access_count or accessed_after.
+    pub never_accessed: Option<bool>,
+    /// Leave out memories carrying any of these tags (optional)
+    pub exclude_tags: Option<Vec<String>>,
+    /// Leave out memories from any of these sources (optional)
+    pub exclude_sources: Option<Vec<String>>,
+    /// Leave out memories with any of these type hints (optional)
+    pub exclude_type_hints: Option<Vec<String>>,
+    /// Only list memories with this embedding status: "pending", "complete", "failed", or
+    /// "chunked" (optional; e.g. "failed" to find memories stuck without an embedding)
+    pub embedding_status: Option<String>,
+    /// Only list memories with this extraction status: "pending", "complete", "failed", or
+    /// "skipped" (optional)
+    pub extraction_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetMemoryHistoryParams {
+    /// Memory ID whose revision history to list (required)
+    pub id: String,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RevertMemoryParams {
+    /// Memory ID to roll back (required)
+    pub id: String,
+    /// Revision number to restore, as listed by get_memory_history (required)
+    pub revision: i32,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MemoryStatsParams {
+    /// Namespace to describe (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetMemoryFacetsParams {
+    /// Maximum number of tags to return, most common first (1-200, default: 50)
+    pub tag_limit: Option<u32>,
+    /// Namespace to describe (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetMemoriesByEntityParams {
+    /// Entity to look up, e.g. "PostgreSQL" or "Alice" (required)
+    pub entity: String,
+    /// Match only this exact entity; by default any entity containing it (case-insensitive)
+    /// also matches (default: false)
+    pub exact: Option<bool>,
+    /// Maximum results to return (1-100, default: 20)
+    pub limit: Option<u32>,
+    /// Cursor from previous page for pagination (optional)
+    pub cursor: Option<String>,
+    /// Namespace to search (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ListEntitiesParams {
+    /// Return only entities containing this text, case-insensitive (optional)
+    pub contains: Option<String>,
+    /// Maximum number of entities to return, most frequent first (1-500, default: 50)
+    pub limit: Option<u32>,
+    /// Namespace to describe (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RenameTagParams {
+    /// Tag to rename (required)
+    pub from: String,
+    /// New tag name (required). Memories that already carry it keep a single copy.
+    pub to: String,
+    /// Namespace to rewrite (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MergeTagsParams {
+    /// Tags to fold into `target` (required, 1-50), e.g. ["Postgres", "pg"]
+    pub sources: Vec<String>,
+    /// Tag that replaces every source tag (required), e.g. "postgres"
+    pub target: String,
+    /// Namespace to rewrite (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DeleteTagParams {
+    /// Tag to remove from every memory (required). Memories themselves are kept.
+    pub tag: String,
+    /// Namespace to rewrite (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetAuditLogParams {
+    /// Only calls to this tool, e.g. "delete_memory" (optional)
+    pub tool: Option<String>,
+    /// Only calls with this outcome: "ok" or "error" (optional)
+    pub outcome: Option<String>,
+    /// Only calls that read or changed this memory ID (optional)
+    pub memory_id: Option<String>,
+    /// Only calls at or after this ISO-8601 timestamp (optional)
+    pub since: Option<String>,
+    /// Only calls before this ISO-8601 timestamp (optional)
+    pub until: Option<String>,
+    /// Maximum entries to return, newest first (1-1000, default: 100)
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetMetricsParams {
+    /// Output format: "json" (default) or "prometheus" (text exposition format)
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct UnconsolidateMemoryParams {
+    /// ID of the consolidated memory to undo (required)
+    pub id: String,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct LinkMemoriesParams {
+    /// Memory ID the link starts from (required)
+    pub source_id: String,
+    /// Memory ID the link points to (required)
+    pub target_id: String,
+    /// Relation type, e.g. "supersedes", "works_at", "part_of" (default: "related_to")
+    pub relation: Option<String>,
+    /// Namespace both memories must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct UnlinkMemoriesParams {
+    /// Memory ID the link starts from (required)
+    pub source_id: String,
+    /// Memory ID the link points to (required)
+    pub target_id: String,
+    /// Relation to remove (default: remove every link between the pair)
+    pub relation: Option<String>,
+    /// Namespace both memories must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetMemoryGraphParams {
+    /// Memory ID to start from (required)
+    pub id: String,
+    /// Number of hops to follow in either direction (1-3, default: 1)
+    pub depth: Option<u32>,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetContradictionsParams {
+    /// Only contradictions involving this memory (optional)
+    pub id: Option<String>,
+    /// Maximum contradictions to return, newest first (1-100, default: 20)
+    pub limit: Option<u32>,
+    /// Namespace to search (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SummarizeMemoriesParams {
+    /// Natural language query selecting memories by meaning; also focuses the summary (optional)
+    pub query: Option<String>,
+    /// Only summarize memories with this type_hint (optional)
+    pub type_hint: Option<String>,
+    /// Only summarize memories from this source (optional)
+    pub source: Option<String>,
+    /// Only summarize memories with ALL of these tags (optional)
+    pub tags: Option<Vec<String>>,
+    /// Only summarize memories created after this ISO-8601 timestamp (optional)
+    pub created_after: Option<String>,
+    /// Only summarize memories created before this ISO-8601 timestamp (optional)
+    pub created_before: Option<String>,
+    /// Maximum memories to include (1-100, default: 50)
+    pub limit: Option<u32>,
+    /// Store the summary as a new memory with type_hint "summary" (default: false)
+    #[serde(default)]
+    pub store: bool,
+    /// Namespace to summarize (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ExportMemoriesParams {
+    /// Natural language query selecting memories by meaning (optional; requires PostgreSQL)
+    pub query: Option<String>,
+    /// Only export memories with this type_hint (optional)
+    pub type_hint: Option<String>,
+    /// Only export memories from this source (optional)
+    pub source: Option<String>,
+    /// Only export memories with ALL of these tags (optional)
+    pub tags: Option<Vec<String>>,
+    /// Only export memories created after this ISO-8601 timestamp (optional)
+    pub created_after: Option<String>,
+    /// Only export memories created before this ISO-8601 timestamp (optional)
+    pub created_before: Option<String>,
+    /// Maximum memories to export (1-1000, default: 100)
+    pub limit: Option<u32>,
+    /// Include each memory's current embedding and its model (default: false)
+    #[serde(default)]
+    pub include_embeddings: bool,
+    /// Namespace to export from (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ImportMemoriesParams {
+    /// Pack returned by export_memories (required)
+    pub pack: serde_json::Value,
+    /// Namespace to import into (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// Skip memories whose content is already stored in the namespace (default: true)
+    pub skip_duplicates: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct StartSessionParams {
+    /// Short label for the session, e.g. "refactor auth module" (optional)
+    pub title: Option<String>,
+    /// Namespace the session's memories are stored in (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct EndSessionParams {
+    /// Session ID returned by start_session (required)
+    pub session_id: String,
+}
+
+/// Maximum session memories fed to the summary provider by end_session.
+const MAX_SESSION_SUMMARY_MEMORIES: i64 = 100;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetRelatedMemoriesParams {
+    /// Memory ID whose neighbours to find (required)
+    pub id: String,
+    /// Maximum related memories to return (1-50, default: 5)
+    pub limit: Option<u32>,
+    /// Namespace the memory must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ReinforceMemoryParams {
+    /// Memory ID to reinforce (required)
+    pub id: String,
+    /// Reinforcement strength: "good" (default) for standard reinforcement, "easy" for stronger boost
+    #[serde(default = "default_rating")]
+    pub rating: Option<String>,
+}
+
+fn default_rating() -> Option<String> {
+    Some("good".to_string())
+}
+
+/// Maximum number of memories accepted by a single reinforce_memories or mark_used call.
+const MAX_BATCH_REINFORCE: usize = 100;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ReinforceMemoriesParams {
+    /// Memory IDs to reinforce (1-100 items)
+    pub ids: Vec<String>,
+    /// Reinforcement strength applied to every memory: "good" (default) or "easy"
+    #[serde(default = "default_rating")]
+    pub rating: Option<String>,
+    /// Namespace the memories must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MarkUsedParams {
+    /// IDs of search results your answer actually relied on (1-100 items)
+    pub ids: Vec<String>,
+    /// Namespace the memories must belong to (default: server's configured namespace)
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SearchMemoryParams {
+    /// Natural language query — find memories by meaning, not exact words (required)
+    pub query: String,
+    /// Maximum results to return (1-100, default: 10)
+    pub limit: Option<u32>,
+    /// Return only memories created after this ISO-8601 timestamp (optional)
+    pub created_after: Option<String>,
+    /// Return only memories created before this ISO-8601 timestamp (optional)
+    pub created_before: Option<String>,
+    /// Filter by tags — return only memories with ALL specified tags (optional)
+    pub tags: Option<Vec<String>>,
+    /// Filter by exact type_hint (optional)
+    pub type_hint: Option<String>,
+    /// Filter by exact source (optional)
+    pub source: Option<String>,
+    /// Leave out memories carrying any of these tags, e.g. ["scratch"] (optional)
+    pub exclude_tags: Option<Vec<String>>,
+    /// Leave out memories from any of these sources (optional)
+    pub exclude_sources: Option<Vec<String>>,
+    /// Leave out memories with any of these type hints (optional)
+    pub exclude_type_hints: Option<Vec<String>>,
+    /// Cursor from previous page for pagination (optional)
+    pub cursor: Option<String>,
+    /// Weight for BM25 keyword search path (0.0 to disable, 1.0 = default, >1.0 = emphasize).
+    /// Controls how much exact keyword matches influence results.
+    pub bm25_weight: Option<f64>,
+    /// Weight for vector semantic search path (0.0 to disable, 1.0 = default, >1.0 = emphasize).
+    /// Controls how much meaning similarity influences results.
+    pub vector_weight: Option<f64>,
+    /// Weight for symbolic metadata search path (0.0 to disable, 1.0 = default, >1.0 = emphasize).
+    /// Controls how much tag/type/source matches influence results.
+    pub symbolic_weight: Option<f64>,
+    /// Namespace to search (default: server's configured namespace)
+    pub namespace: Option<String>,
+    /// Candidates fetched per search path before fusion (1-1000, default: server's
+    /// search.candidate_pool_per_leg). Raise to improve recall on large memory stores.
+    pub candidate_pool: Option<u32>,
+    /// How the search paths are combined: "rrf" (rank-based) or "weighted" (normalized
+    /// scores multiplied by the path weights). Default: server's search.fusion.
+    pub fusion: Option<String>,
+    /// Return an `explain` object per result: its rank, raw score, and fusion contribution in
+    /// each search path, the temporal boost applied, and the re-ranking change. Also adds
+    /// `score_breakdown`. Use this to tune weights (default: false).
+    pub explain: Option<bool>,
+    /// Filter by payload — return only memories whose payload satisfies this SQL/JSON path
+    /// predicate, e.g. `$.language == "rust"` or `exists($.url)` (optional)
+    pub payload_path: Option<String>,
+    /// Filter by provenance — return only memories with an origin reference matching every
+    /// part given here, e.g. {"conversation_id": "c-42"} (optional)
+    pub origin_ref: Option<OriginRef>,
+    /// Filter by usage — return only memories fetched (get_memory/get_memories) at least this
+    /// many times (optional)
+    pub min_access_count: Option<u32>,
+    /// Filter by usage — return only memories last fetched after this ISO-8601 timestamp (optional)
+    pub accessed_after: Option<String>,
+    /// Set to true to return only memories that were never fetched (default: false). Can't be
+    /// combined with min_access_count or accessed_after.
+    pub never_accessed: Option<bool>,
+    /// Drop results whose semantic similarity to the query (0.0-1.0) is below this instead of
+    /// padding to `limit`; the response reports how many were filtered out. Default: server's
+    /// search.default_min_relevance (0.0 = keep every result).
+    pub min_relevance: Option<f64>,
+    /// Return at most this many characters of each memory's content (20-10000): an excerpt
+    /// centered on the best keyword match, or the start of the content. Truncated results carry
+    /// `content_truncated` and `content_length`; use get_memory for the full text (optional)
+    pub snippet_chars: Option<u32>,
+    /// Salience profile to rank with, e.g. "recent" (what changed lately) or "instructional"
+    /// (close, frequently reinforced matches regardless of age), or any profile configured under
+    /// [salience.profiles]. Default: the base [salience] weights.
+    pub profile: Option<String>,
+    /// Recent conversation turns, oldest first (at most 20). With query expansion enabled,
+    /// a follow-up query like "what did he say about it?" is first rewritten to stand alone
+    /// using them; the rewrite is returned as `resolved_query`. Ignored without expansion.
+    pub conversation_context: Option<Vec<SearchContextTurn>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SearchContextTurn {
+    /// Speaker role, e.g. "user" or "assistant" (required)
+    pub role: String,
+    /// What was said (required)
+    pub content: String,
+}
+
+/// Maximum conversation turns accepted as search_memory `conversation_context`.
+const MAX_CONTEXT_TURNS: usize = 20;
+
+/// A copy of an order-insensitive list filter in canonical (sorted) order, for cache keys.
+fn sorted_list(list: Option<&Vec<String>>) -> Option<Vec<String>> {
+    list.map(|items| {
+        let mut sorted = items.clone();
+        sorted.sort();
+        sorted
+    })
+}
+
+/// Accepted range for search_memory `snippet_chars`.
+const SNIPPET_CHARS_RANGE: std::ops::RangeInclusive<u32> = 20..=10_000;
+
+/// Most pinned memories a search injects ahead of its ranked results.
+const MAX_PINNED_RESULTS: i64 = 20;
+
+/// Newest memories (per configured type) the session primer ranks by salience.
+const PRIMER_CANDIDATES: i64 = 100;
+
+/// Replace a search result's `content` with a snippet of at most `snippet_chars` characters
+/// (see `crate::search::snippet`). Every result reports `content_truncated` and
+/// `content_length` (in characters) once snippets are requested.
+fn apply_snippet(obj: &mut serde_json::Value, content: &str, headline: Option<&str>, snippet_chars: Option<u32>) {
+    let Some(max_chars) = snippet_chars else { return };
+    let excerpt = crate::search::snippet(content, headline, max_chars as usize);
+    obj["content_truncated"] = json!(excerpt.is_some());
+    obj["content_length"] = json!(content.chars().count());
+    if let Some(excerpt) = excerpt {
+        obj["content"] = json!(excerpt);
+    }
+}
+
+/// Put pinned memories ahead of the ranked hits. A pinned memory the search also retrieved
+/// keeps its scores; the others get match_source "pinned" and zero scores.
+fn pin_hits(hits: Vec<ScoredHit>, pinned: Vec<Memory>) -> Vec<ScoredHit> {
+    if pinned.is_empty() {
+        return hits;
+    }
+    let pinned_ids: HashSet<String> = pinned.iter().map(|m| m.id.clone()).collect();
+    let (mut retrieved, ranked): (Vec<ScoredHit>, Vec<ScoredHit>) =
+        hits.into_iter().partition(|hit| pinned_ids.contains(&hit.memory.id));
+
+    let mut result = Vec::with_capacity(pinned.len() + ranked.len());
+    for memory in pinned {
+        match retrieved.iter().position(|hit| hit.memory.id == memory.id) {
+            Some(index) => result.push(retrieved.swap_remove(index)),
+            None => result.push(ScoredHit {
+                memory,
+                rrf_score: 0.0,
+                salience_score: 0.0,
+                match_source: "pinned".to_string(),
+                breakdown: None,
+            }),
+        }
+    }
+    result.extend(ranked);
+    result
+}
+
+/// Blend a re-ranker's order into the scores of the first `top_n` hits and re-sort that
+/// window; hits past it keep their salience order. A re-ranked hit scores
+/// `blend * 1 / (1 + rank) + (1 - blend) * salience`, with salience min-max normalized over
+/// all hits. Returns the window's (id, score) order from before re-ranking.
+fn blend_rerank(scored_hits: &mut [ScoredHit], top_n: usize, ranked: &[RankedResult], blend: f64) -> Vec<(String, f64)> {
+    let max_salience = scored_hits.iter().map(|h| h.salience_score).fold(f64::MIN, f64::max);
+    let min_salience = scored_hits.iter().map(|h| h.salience_score).fold(f64::MAX, f64::min);
+    let salience_range = (max_salience - min_salience).max(1e-6);
+    let before: Vec<(String, f64)> = scored_hits[..top_n]
+        .iter()
+        .map(|h| (h.memory.id.clone(), h.salience_score))
+        .collect();
+
+    for hit in scored_hits[..top_n].iter_mut() {
+        if let Some(r) = ranked.iter().find(|r| r.id == hit.memory.id) {
+            let llm_score = 1.0 / (1.0 + r.llm_rank as f64);
+            let norm_salience = (hit.salience_score - min_salience) / salience_range;
+            hit.salience_score = blend * llm_score + (1.0 - blend) * norm_salience;
+        }
+    }
+    scored_hits[..top_n].sort_by(|a, b| b.salience_score.partial_cmp(&a.salience_score).unwrap_or(std::cmp::Ordering::Equal));
+    before
+}
+
+/// Positions of the re-ranked window that now hold a different memory (the calibration
+/// log's `moved`).
+fn rerank_moves(before: &[&str], after: &[&str]) -> usize {
+    before.iter().zip(after).filter(|(b, a)| b != a).count()
+}
+
+/// Report a search_memory `min_relevance` threshold in the response: how many hits it
+/// dropped, or that it could not apply. An empty result says its hits were filtered out.
+fn report_relevance_filter(response: &mut serde_json::Value, min_relevance: f64, filtered_out: usize, applied: bool) {
+    if min_relevance <= 0.0 {
+        return;
+    }
+    response["min_relevance"] = json!(min_relevance);
+    response["filtered_out"] = json!(filtered_out);
+    if !applied {
+        response["min_relevance_skipped"] = json!("No similarity scores — vector search is disabled or the query could not be embedded");
+    } else if filtered_out > 0 && response["total_results"] == 0 {
+        response["hint"] = json!(format!(
+            "{} matches scored below min_relevance {} and were filtered out. Nothing stored is closely related; lower min_relevance to see weaker matches.",
+            filtered_out, min_relevance
+        ));
+    }
+}
+
+// Helper: convert MemcpError to CallToolResult with isError: true
+/// Validate an `ids` list for the batch reinforcement tools (1-MAX_BATCH_REINFORCE, no blanks).
+fn validate_id_batch(ids: &[String]) -> Option<CallToolResult> {
+    let error = |message: String| {
+        Some(CallToolResult::structured_error(json!({
+            "isError": true,
+            "code": codes::VALIDATION,
+            "error": message,
+            "field": "ids"
+        })))
+    };
+    if ids.is_empty() {
+        return error("Field 'ids' must contain at least one memory ID".to_string());
+    }
+    if ids.len() > MAX_BATCH_REINFORCE {
+        return error(format!("Field 'ids' accepts at most {} items per call (got {})", MAX_BATCH_REINFORCE, ids.len()));
+    }
+    if ids.iter().any(|id| id.trim().is_empty()) {
+        return error("Field 'ids' cannot contain empty IDs".to_string());
+    }
+    None
+}
+
+fn store_error_to_result(err: MemcpError) -> CallToolResult {
+    let code = err.code();
+    match err {
+        MemcpError::NotFound { id } => {
+            CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": code,
+                "error": format!("Memory not found: {}", id),
+                "hint": "Use list_memories to find available memory IDs"
+            }))
+        }
+        MemcpError::Validation { message, field } => {
+            let mut obj = json!({
+                "isError": true,
+                "code": code,
+                "error": message,
+            });
+            if let Some(f) = field {
+                obj["field"] = json!(f);
+            }
+            CallToolResult::structured_error(obj)
+        }
+        MemcpError::Storage(msg) => {
+            CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": code,
+                "error": format!("Storage error: {}", msg)
+            }))
+        }
+        other => {
+            CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": code,
+                "error": other.to_string()
+            }))
+        }
+    }
+}
+
+/// Deserialize a param that can be left out (None) or set to null (Some(None)).
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+// Helper: parse optional ISO-8601 string to DateTime<Utc>
+/// Parse an optional `expires_at` param, rejecting timestamps that are not in the future.
+fn parse_expires_at(s: Option<&str>) -> Result<Option<chrono::DateTime<chrono::Utc>>, CallToolResult> {
+    let Some(s) = s else { return Ok(None) };
+    let dt = parse_datetime(s, "expires_at")?;
+    if dt <= chrono::Utc::now() {
+        return Err(CallToolResult::structured_error(json!({
+            "isError": true,
+            "code": codes::VALIDATION,
+            "error": "Field 'expires_at' must be in the future",
+            "field": "expires_at"
+        })));
+    }
+    Ok(Some(dt))
+}
+
+/// Largest payload accepted on a memory, in bytes of serialized JSON.
+const MAX_PAYLOAD_BYTES: usize = 64 * 1024;
+
+/// Check a payload fits within MAX_PAYLOAD_BYTES, returning a field error otherwise.
+fn check_payload(payload: Option<&serde_json::Value>) -> Result<(), CallToolResult> {
+    match payload {
+        Some(value) if value.to_string().len() > MAX_PAYLOAD_BYTES => Err(CallToolResult::structured_error(json!({
+            "isError": true,
+            "code": codes::VALIDATION,
+            "error": format!("Field 'payload' exceeds {} bytes of JSON", MAX_PAYLOAD_BYTES),
+            "field": "payload"
+        }))),
+        _ => Ok(()),
+    }
+}
+
+/// The origin references a memory is stored with: `origin_ref` unless none of its parts is set.
+fn origin_refs(origin_ref: Option<OriginRef>) -> Vec<OriginRef> {
+    origin_ref.into_iter().filter(|r| !r.is_empty()).collect()
+}
+
+/// Every distinct origin reference of `memories`, for a memory summarizing them.
+fn merged_origin_refs(memories: &[Memory]) -> Vec<OriginRef> {
+    let mut merged = Vec::new();
+    for origin_ref in memories.iter().flat_map(|m| OriginRef::from_stored(m.origin_refs.as_ref())) {
+        if !merged.contains(&origin_ref) {
+            merged.push(origin_ref);
+        }
+    }
+    merged
+}
+
+/// Check an importance value is within 1-5, returning a field error otherwise.
+fn check_importance(importance: Option<u8>, field: &str) -> Result<Option<i16>, CallToolResult> {
+    match importance {
+        Some(value) if !(1..=5).contains(&value) => Err(CallToolResult::structured_error(json!({
+            "isError": true,
+            "code": codes::VALIDATION,
+            "error": format!("Field '{}' must be between 1 and 5", field),
+            "field": field
+        }))),
+        other => Ok(other.map(i16::from)),
+    }
+}
+
+fn check_status(status: Option<String>, allowed: &[&str], field: &str) -> Result<Option<String>, CallToolResult> {
+    match status {
+        Some(value) if !allowed.contains(&value.as_str()) => Err(CallToolResult::structured_error(json!({
+            "isError": true,
+            "code": codes::VALIDATION,
+            "error": format!("Field '{}' must be one of: {}", field, allowed.join(", ")),
+            "field": field
+        }))),
+        other => Ok(other),
+    }
+}
+
+/// Access-statistics filters of list_memories and search_memory, validated.
+struct AccessFilters {
+    min_access_count: Option<i64>,
+    accessed_after: Option<DateTime<Utc>>,
+    never_accessed: bool,
+}
+
+/// Validate the access-statistics filters, returning a field error when they don't parse or
+/// never_accessed contradicts the other two.
+fn check_access_filters(
+    min_access_count: Option<u32>,
+    accessed_after: Option<&str>,
+    never_accessed: Option<bool>,
+) -> Result<AccessFilters, CallToolResult> {
+    let accessed_after = accessed_after.map(|s| parse_datetime(s, "accessed_after")).transpose()?;
+    let never_accessed = never_accessed.unwrap_or(false);
+    if never_accessed && (min_access_count.is_some_and(|n| n > 0) || accessed_after.is_some()) {
+        return Err(CallToolResult::structured_error(json!({
+            "isError": true,
+            "code": codes::VALIDATION,
+            "error": "Field 'never_accessed' can't be combined with min_access_count or accessed_after",
+            "field": "never_accessed"
+        })));
+    }
+    Ok(AccessFilters { min_access_count: min_access_count.map(i64::from), accessed_after, never_accessed })
+}
+
+fn parse_datetime(s: &str, field: &str) -> Result<chrono::DateTime<chrono::Utc>, CallToolResult> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .map_err(|_| {
+            CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Invalid datetime format for '{}': expected ISO-8601 (e.g. 2026-02-17T00:00:00Z)", field),
+                "field": field
+            }))
+        })
+}
+
+// Tool implementations
+#[rmcp::tool_router]
+impl MemoryService {
+    #[tool(description = "Store a new memory with content, type hint, source, tags, optional importance (1-5, default 3), an optional JSON payload for machine-readable data, and an optional origin_ref pointing back to the conversation, message, file, or URL it came from. Returns the created memory with its ID. Content over the configured size limit is stored as linked chunks (or rejected, per config). When deduplication is enabled and an equivalent memory exists, returns that memory with duplicate_of instead of storing.")]
+    async fn store_memory(
+        &self,
+        Parameters(params): Parameters<StoreMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "store_memory",
+            type_hint = ?params.type_hint,
+            source = ?params.source,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+        Ok(self.store_single(params, None).await)
+    }
+
+    #[tool(description = "Store a structured memory against a type defined with define_memory_type, e.g. a contact with name/email/company. Field values are validated against the type; the type name becomes the memory's type_hint and search matches on the field values. Content defaults to a rendering of the fields.")]
+    async fn store_structured_memory(
+        &self,
+        Parameters(params): Parameters<StoreStructuredMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "store_structured_memory",
+            memory_type = %params.memory_type,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Structured memories require PostgreSQL backend"
+                })));
+            }
+        };
+
+        let memory_type = match pg_store.get_memory_type(&namespace, params.memory_type.trim()).await {
+            Ok(memory_type) => memory_type,
+            Err(MemcpError::NotFound { .. }) => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": format!("Unknown memory type '{}' in namespace '{}'", params.memory_type, namespace),
+                    "field": "memory_type",
+                    "hint": "Use list_memory_types to see defined types, or define_memory_type to add one"
+                })));
+            }
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+
+        let problems = memory_type.validate(&params.fields);
+        if !problems.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": problems.join("; "),
+                "field": "fields",
+                "problems": problems,
+                "schema": memory_type.fields
+            })));
+        }
+
+        // Nulls mean "unset" — keep only the values that are stored and matched on
+        let fields: serde_json::Map<String, serde_json::Value> =
+            params.fields.into_iter().filter(|(_, v)| !v.is_null()).collect();
+        let content = params
+            .content
+            .filter(|c| !c.trim().is_empty())
+            .unwrap_or_else(|| memory_type.render(&fields));
+        let input = StoreMemoryParams {
+            content,
+            type_hint: Some(memory_type.name),
+            source: params.source,
+            tags: params.tags,
+            namespace: Some(namespace),
+            expires_at: params.expires_at,
+            importance: params.importance,
+            session_id: params.session_id,
+            payload: params.payload,
+            origin_ref: params.origin_ref,
+        };
+        Ok(self.store_single(input, Some(serde_json::Value::Object(fields))).await)
+    }
+
+    #[tool(description = "Store multiple memories in one call (up to 100). Valid items are inserted in a single transaction; invalid items are reported individually. Returns per-item IDs and statuses in input order.")]
+    async fn store_memories(
+        &self,
+        Parameters(params): Parameters<StoreMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "store_memories",
+            count = params.memories.len(),
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+        let _timer = metrics::global().store_duration.start_timer();
+
+        if params.memories.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'memories' must contain at least one memory",
+                "field": "memories"
+            })));
+        }
+        if params.memories.len() > MAX_BATCH_STORE {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Field 'memories' accepts at most {} items per call (got {})", MAX_BATCH_STORE, params.memories.len()),
+                "field": "memories"
+            })));
+        }
+
+        let batch_namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        // Validate each item up front. Invalid items are reported, valid ones are stored together.
+        let total = params.memories.len();
+        let mut results: Vec<serde_json::Value> = vec![serde_json::Value::Null; total];
+        // (item index, whether the item set type_hint, redactions made) for each input
+        let mut valid_indices: Vec<(usize, bool, Vec<crate::privacy::Redaction>)> = Vec::with_capacity(total);
+        let mut inputs: Vec<CreateMemory> = Vec::with_capacity(total);
+        let mut duplicate_count = 0;
+
+        for (index, mut item) in params.memories.into_iter().enumerate() {
+            if item.content.trim().is_empty() {
+                results[index] = json!({
+                    "index": index,
+                    "status": "error",
+                    "error": "Field 'content' is required and cannot be empty",
+                    "field": "content"
+                });
+                continue;
+            }
+            let redactions = match self.scrub_content(std::mem::take(&mut item.content)).await {
+                Ok((content, redactions)) => {
+                    item.content = content;
+                    redactions
+                }
+                Err(_) => {
+                    results[index] = json!({
+                        "index": index,
+                        "status": "error",
+                        "error": "Content filtering failed and privacy.fail_closed is set — not stored",
+                        "field": "content"
+                    });
+                    continue;
+                }
+            };
+            // Batches insert one row per item — oversized content goes through store_memory
+            if self.is_oversized(&item.content) {
+                results[index] = json!({
+                    "index": index,
+                    "status": "error",
+                    "error": format!(
+                        "Field 'content' exceeds the {} character limit; store it with store_memory instead",
+                        self.content_config.max_chars
+                    ),
+                    "field": "content"
+                });
+                continue;
+            }
+            let namespace = match item.namespace {
+                Some(ns) if ns.trim().is_empty() => {
+                    results[index] = json!({
+                        "index": index,
+                        "status": "error",
+                        "error": "Field 'namespace' cannot be empty",
+                        "field": "namespace"
+                    });
+                    continue;
+                }
+                Some(ns) => ns.trim().to_string(),
+                None => batch_namespace.clone(),
+            };
+            let expires_at = match parse_expires_at(item.expires_at.as_deref()) {
+                Ok(dt) => dt,
+                Err(_) => {
+                    results[index] = json!({
+                        "index": index,
+                        "status": "error",
+                        "error": "Field 'expires_at' must be a future ISO-8601 timestamp",
+                        "field": "expires_at"
+                    });
+                    continue;
+                }
+            };
+            let importance = match check_importance(item.importance, "importance") {
+                Ok(value) => value.unwrap_or(crate::store::DEFAULT_IMPORTANCE),
+                Err(_) => {
+                    results[index] = json!({
+                        "index": index,
+                        "status": "error",
+                        "error": "Field 'importance' must be between 1 and 5",
+                        "field": "importance"
+                    });
+                    continue;
+                }
+            };
+            if check_payload(item.payload.as_ref()).is_err() {
+                results[index] = json!({
+                    "index": index,
+                    "status": "error",
+                    "error": format!("Field 'payload' exceeds {} bytes of JSON", MAX_PAYLOAD_BYTES),
+                    "field": "payload"
+                });
+                continue;
+            }
+            if self.check_open_session(item.session_id.as_deref(), &namespace).await.is_some() {
+                results[index] = json!({
+                    "index": index,
+                    "status": "error",
+                    "error": "Field 'session_id' must be an open session in the same namespace",
+                    "field": "session_id"
+                });
+                continue;
+            }
+            let explicit_type_hint = item.type_hint.is_some();
+            let input = CreateMemory {
+                content: item.content,
+                type_hint: item.type_hint.unwrap_or_else(|| "fact".to_string()),
+                source: item.source.unwrap_or_else(|| "default".to_string()),
+                tags: item.tags,
+                created_at: None,
+                namespace,
+                expires_at,
+                importance,
+                session_id: item.session_id,
+                fields: None,
+                payload: item.payload,
+                origin_refs: origin_refs(item.origin_ref),
+                classify: self.classifies(explicit_type_hint),
+            };
+            match self.find_duplicate(&input).await {
+                Ok(Some((existing, match_kind, _))) => {
+                    duplicate_count += 1;
+                    results[index] = json!({
+                        "index": index,
+                        "status": "duplicate",
+                        "id": existing.id,
+                        "duplicate_of": existing.id,
+                        "match": match_kind,
+                        "namespace": existing.namespace,
+                    });
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+            valid_indices.push((index, explicit_type_hint, redactions));
+            inputs.push(input);
+        }
+
+        let stored_count = inputs.len();
+        let mut degraded = false;
+        if !inputs.is_empty() {
+            match self.store.store_batch(inputs).await.inspect(|_| self.invalidate_search_cache()) {
+                Ok(memories) => {
+                    self.emit_created(&memories);
+                    for ((index, explicit_type_hint, redactions), memory) in
+                        valid_indices.into_iter().zip(memories.iter())
+                    {
+                        self.record_redactions(&memory.id, &redactions).await;
+                        degraded |= !self.enqueue_new_memory(memory, explicit_type_hint);
+                        results[index] = json!({
+                            "index": index,
+                            "status": "stored",
+                            "id": memory.id,
+                            "namespace": memory.namespace,
+                            "embedding_status": memory.embedding_status,
+                        });
+                        if !redactions.is_empty() {
+                            results[index]["redactions"] = json!(redactions.len());
+                        }
+                    }
+                }
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        }
+
+        let failed_count = total - stored_count - duplicate_count;
+        Ok(CallToolResult::structured(json!({
+            "results": results,
+            "stored": stored_count,
+            "duplicates": duplicate_count,
+            "failed": failed_count,
+            "queue_depth": self.queue_depth(),
+            "degraded": degraded,
+            "hint": if failed_count > 0 {
+                "Some items failed validation — fix them and resend only those items"
+            } else if degraded {
+                "Stored, but background pipelines are saturated — embedding and extraction are deferred to the next backfill. Slow down bulk writes."
+            } else {
+                "All memories stored. Embedding and extraction run in the background."
+            }
+        })))
+    }
+
+    #[tool(description = "Export memories selected by a query and/or filters (type_hint, source, tags, time range) as a portable JSON memory pack, optionally with their embeddings. Pass the pack to import_memories on another memcp instance.")]
+    async fn export_memories(
+        &self,
+        Parameters(params): Parameters<ExportMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "export_memories",
+            query = ?params.query,
+            type_hint = ?params.type_hint,
+            source = ?params.source,
+            include_embeddings = params.include_embeddings,
+            "Tool called"
+        );
+
+        let limit = params.limit.unwrap_or(100);
+        if !(1..=crate::memory_pack::MAX_PACK_MEMORIES as u32).contains(&limit) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Field 'limit' must be between 1 and {}", crate::memory_pack::MAX_PACK_MEMORIES),
+                "field": "limit"
+            })));
+        }
+        let limit = limit as usize;
+        let query = params.query.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+        let created_after = match params.created_after.as_deref().map(|s| parse_datetime(s, "created_after")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return Ok(result),
+        };
+        let created_before = match params.created_before.as_deref().map(|s| parse_datetime(s, "created_before")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return Ok(result),
+        };
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let has_tags = |m: &Memory| {
+            params.tags.as_ref().is_none_or(|wanted| {
+                let have: Vec<&str> = m.tags.as_ref()
+                    .and_then(|t| t.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+                    .unwrap_or_default();
+                wanted.iter().all(|w| have.contains(&w.as_str()))
+            })
+        };
+
+        // Select: hybrid search when a query is given, otherwise newest-first listing
+        let mut memories: Vec<Memory> = if let Some(query) = query {
+            let pg_store = match &self.pg_store {
+                Some(s) => s,
+                None => {
+                    return Ok(CallToolResult::structured_error(json!({
+                        "isError": true,
+                        "code": codes::BACKEND_UNSUPPORTED,
+                        "error": "Query-based exports require PostgreSQL backend",
+                        "hint": "Omit 'query' to export by filters only"
+                    })));
+                }
+            };
+            let query_embedding = match self.embedding_provider {
+                Some(ref provider) if provider.is_ready() => provider.embed(query).await.ok().map(pgvector::Vector::from),
+                _ => None,
+            };
+            let filter = crate::store::SearchFilter {
+                limit: limit as i64,
+                created_after,
+                created_before,
+                tags: params.tags.clone(),
+                namespace: Some(namespace.clone()),
+                type_hint: params.type_hint.clone(),
+                source: params.source.clone(),
+                ..Default::default()
+            };
+            match pg_store.hybrid_search(
+                query,
+                query_embedding.as_ref(),
+                &[],
+                &filter,
+                Some(60.0),
+                Some(60.0),
+                Some(40.0),
+                None,
+                crate::search::Fusion::Rrf,
+            ).await {
+                Ok(hits) => hits.into_iter().map(|h| h.memory).filter(|m| has_tags(m)).collect(),
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        } else {
+            let mut memories = Vec::new();
+            let mut cursor = None;
+            loop {
+                let filter = ListFilter {
+                    namespace: Some(namespace.clone()),
+                    type_hint: params.type_hint.clone(),
+                    source: params.source.clone(),
+                    created_after,
+                    created_before,
+                    limit: 100,
+                    cursor,
+                    ..ListFilter::default()
+                };
+                let page = match self.store.list(filter).await {
+                    Ok(page) => page,
+                    Err(e) => return Ok(store_error_to_result(e)),
+                };
+                memories.extend(page.memories.into_iter().filter(|m| has_tags(m)));
+                cursor = page.next_cursor;
+                if memories.len() >= limit || cursor.is_none() {
+                    break;
+                }
+            }
+            memories
+        };
+        // Chunks travel inside their parent's content
+        memories.retain(|m| m.parent_id.is_none());
+        memories.truncate(limit);
+
+        let mut embeddings = HashMap::new();
+        if params.include_embeddings {
+            if let Some(ref pg_store) = self.pg_store {
+                let ids: Vec<String> = memories.iter().map(|m| m.id.clone()).collect();
+                embeddings = match pg_store.get_current_embeddings(&ids).await {
+                    Ok(found) => found,
+                    Err(e) => return Ok(store_error_to_result(e)),
+                };
+            }
+        }
+
+        let mut pack = crate::memory_pack::MemoryPack::new(namespace);
+        pack.memories = memories
+            .iter()
+            .map(|memory| {
+                let embedding = embeddings.remove(&memory.id).map(|(model, vector): (String, pgvector::Vector)| {
+                    crate::memory_pack::PackedEmbedding { model, vector: vector.to_vec() }
+                });
+                crate::memory_pack::PackedMemory::from_memory(memory, embedding)
+            })
+            .collect();
+        let embedded = pack.memories.iter().filter(|m| m.embedding.is_some()).count();
+
+        Ok(CallToolResult::structured(json!({
+            "count": pack.memories.len(),
+            "embeddings": embedded,
+            "pack": pack,
+            "hint": if pack.memories.is_empty() {
+                "No memories matched. Loosen the query or filters."
+            } else {
+                "Pass 'pack' unchanged to import_memories on the target memcp instance."
+            }
+        })))
+    }
+
+    #[tool(description = "Import a memory pack produced by export_memories. Every memory gets a new ID (id_map maps packed IDs to stored ones); memories already stored are reported as duplicates and skipped. Packed embeddings are reused when they come from this server's embedding model.")]
+    async fn import_memories(
+        &self,
+        Parameters(params): Parameters<ImportMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "import_memories",
+            namespace = ?params.namespace,
+            skip_duplicates = ?params.skip_duplicates,
+            "Tool called"
+        );
+        let _timer = metrics::global().store_duration.start_timer();
+
+        let pack = match crate::memory_pack::MemoryPack::from_value(params.pack) {
+            Ok(pack) => pack,
+            Err(e) => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": e,
+                    "field": "pack",
+                    "hint": "Pass the 'pack' object returned by export_memories unchanged"
+                })));
+            }
+        };
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+        let skip_duplicates = params.skip_duplicates.unwrap_or(true);
+
+        let total = pack.memories.len();
+        let now = Utc::now();
+        let mut results: Vec<serde_json::Value> = vec![serde_json::Value::Null; total];
+        let mut id_map = serde_json::Map::new();
+        // Content already taken from this pack, mapped to the packed ID of its first occurrence
+        let mut seen: HashMap<String, String> = HashMap::new();
+        // (item index, packed ID of the earlier copy) for repeats within the pack
+        let mut repeats: Vec<(usize, String)> = Vec::new();
+        let mut valid: Vec<(usize, crate::memory_pack::PackedMemory, Vec<crate::privacy::Redaction>)> =
+            Vec::with_capacity(total);
+        let mut inputs: Vec<CreateMemory> = Vec::with_capacity(total);
+        let mut duplicate_count = 0;
+        let mut skipped_count = 0;
+
+        for (index, mut item) in pack.memories.into_iter().enumerate() {
+            let invalid = if item.content.trim().is_empty() {
+                Some(("content", "Field 'content' is required and cannot be empty".to_string()))
+            } else if self.is_oversized(&item.content) {
+                Some(("content", format!("Field 'content' exceeds the {} character limit", self.content_config.max_chars)))
+            } else if !(1..=5).contains(&item.importance) {
+                Some(("importance", "Field 'importance' must be between 1 and 5".to_string()))
+            } else if check_payload(item.payload.as_ref()).is_err() {
+                Some(("payload", format!("Field 'payload' exceeds {} bytes of JSON", MAX_PAYLOAD_BYTES)))
+            } else {
+                None
+            };
+            if let Some((field, error)) = invalid {
+                results[index] = json!({
+                    "index": index,
+                    "id": item.id,
+                    "status": "error",
+                    "error": error,
+                    "field": field
+                });
+                continue;
+            }
+            if item.expires_at.is_some_and(|at| at <= now) {
+                skipped_count += 1;
+                results[index] = json!({ "index": index, "id": item.id, "status": "expired" });
+                continue;
+            }
+            let redactions = match self.scrub_content(std::mem::take(&mut item.content)).await {
+                Ok((content, redactions)) => {
+                    item.content = content;
+                    redactions
+                }
+                Err(_) => {
+                    results[index] = json!({
+                        "index": index,
+                        "id": item.id,
+                        "status": "error",
+                        "error": "Content filtering failed and privacy.fail_closed is set — not imported",
+                        "field": "content"
+                    });
+                    continue;
+                }
+            };
+
+            let input = CreateMemory {
+                content: item.content.clone(),
+                type_hint: item.type_hint.clone(),
+                source: item.source.clone(),
+                tags: item.tags.clone(),
+                created_at: Some(item.created_at),
+                namespace: namespace.clone(),
+                expires_at: item.expires_at,
+                importance: item.importance,
+                session_id: None,
+                fields: None,
+                payload: item.payload.clone(),
+                origin_refs: item.origin_refs.clone(),
+                classify: self.classifies(true),
+            };
+            if skip_duplicates {
+                if let Some(first) = seen.get(&item.content) {
+                    duplicate_count += 1;
+                    repeats.push((index, first.clone()));
+                    results[index] = json!({ "index": index, "id": item.id, "status": "duplicate" });
+                    continue;
+                }
+                match self.find_import_duplicate(&input).await {
+                    Ok(Some((existing_id, match_kind))) => {
+                        duplicate_count += 1;
+                        id_map.insert(item.id.clone(), json!(existing_id));
+                        results[index] = json!({
+                            "index": index,
+                            "id": item.id,
+                            "status": "duplicate",
+                            "new_id": existing_id,
+                            "duplicate_of": existing_id,
+                            "match": match_kind,
+                        });
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(e) => return Ok(store_error_to_result(e)),
+                }
+                seen.insert(item.content.clone(), item.id.clone());
+            }
+            valid.push((index, item, redactions));
+            inputs.push(input);
+        }
+
+        let stored_count = inputs.len();
+        let mut reused_embeddings = 0;
+        let mut degraded = false;
+        if !inputs.is_empty() {
+            let memories = match self.store.store_batch(inputs).await.inspect(|_| self.invalidate_search_cache()) {
+                Ok(memories) => memories,
+                Err(e) => return Ok(store_error_to_result(e)),
+            };
+            self.emit_created(&memories);
+            for ((index, item, redactions), mut memory) in valid.into_iter().zip(memories) {
+                self.record_redactions(&memory.id, &redactions).await;
+                if item.pinned {
+                    let pin = UpdateMemory { pinned: Some(true), ..Default::default() };
+                    match self.store.update(&memory.id, pin).await {
+                        Ok(pinned) => memory = pinned,
+                        Err(e) => tracing::warn!(memory_id = %memory.id, error = %e, "Failed to pin imported memory"),
+                    }
+                }
+                // A packed embedding of redacted content was computed from the originals
+                if redactions.is_empty() && self.reuse_packed_embedding(&memory, item.embedding.as_ref()).await {
+                    reused_embeddings += 1;
+                    memory.embedding_status = "complete".to_string();
+                    if let Some(ref extraction_pipeline) = self.extraction_pipeline {
+                        degraded |= !extraction_pipeline.enqueue(ExtractionJob {
+                            memory_id: memory.id.clone(),
+                            content: memory.content.clone(),
+                            attempt: 0,
+                            classify: self.classifies(true),
+                        });
+                    }
+                } else {
+                    degraded |= !self.enqueue_new_memory(&memory, true);
+                }
+                id_map.insert(item.id.clone(), json!(memory.id));
+                results[index] = json!({
+                    "index": index,
+                    "id": item.id,
+                    "status": "stored",
+                    "new_id": memory.id,
+                    "embedding_status": memory.embedding_status,
+                });
+            }
+        }
+
+        // Repeats within the pack map to wherever their first copy ended up
+        for (index, first) in repeats {
+            if let Some(new_id) = id_map.get(&first).cloned() {
+                results[index]["new_id"] = new_id.clone();
+                results[index]["duplicate_of"] = new_id.clone();
+                if let Some(old_id) = results[index]["id"].as_str() {
+                    id_map.insert(old_id.to_string(), new_id);
+                }
+            }
+        }
+
+        let failed_count = total - stored_count - duplicate_count - skipped_count;
+        Ok(CallToolResult::structured(json!({
+            "namespace": namespace,
+            "results": results,
+            "id_map": id_map,
+            "stored": stored_count,
+            "duplicates": duplicate_count,
+            "expired": skipped_count,
+            "failed": failed_count,
+            "reused_embeddings": reused_embeddings,
+            "queue_depth": self.queue_depth(),
+            "degraded": degraded,
+            "hint": if failed_count > 0 {
+                "Some memories failed validation and were not imported — see 'results'"
+            } else {
+                "Pack imported. Use id_map to translate IDs referenced by the source instance."
+            }
+        })))
+    }
+
+    #[tool(description = "Ingest a conversation transcript: an LLM splits the turns into discrete memories (facts, preferences, decisions, instructions, events) and stores each with its type_hint and the timestamp of the turn it came from.")]
+    async fn ingest_conversation(
+        &self,
+        Parameters(params): Parameters<IngestConversationParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "ingest_conversation",
+            turns = params.turns.len(),
+            source = ?params.source,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+        let _timer = metrics::global().store_duration.start_timer();
+
+        if params.turns.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'turns' must contain at least one turn",
+                "field": "turns"
+            })));
+        }
+        if params.turns.len() > MAX_INGEST_TURNS {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Field 'turns' accepts at most {} turns per call (got {})", MAX_INGEST_TURNS, params.turns.len()),
+                "field": "turns"
+            })));
+        }
+
+        let mut timestamps: Vec<Option<DateTime<Utc>>> = Vec::with_capacity(params.turns.len());
+        for (index, turn) in params.turns.iter().enumerate() {
+            if turn.content.trim().is_empty() {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": format!("Turn {} has empty content", index),
+                    "field": format!("turns[{}].content", index)
+                })));
+            }
+            match turn.timestamp.as_deref().map(|s| parse_datetime(s, &format!("turns[{}].timestamp", index))).transpose() {
+                Ok(ts) => timestamps.push(ts),
+                Err(result) => return Ok(result),
+            }
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let (provider, chunk_chars) = match &self.conversation_extractor {
+            Some((p, chars)) => (p, *chars),
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::PROVIDER_UNAVAILABLE,
+                    "error": "Conversation ingestion requires an extraction provider",
+                    "hint": "Configure the [extraction] provider in memcp.toml"
+                })));
+            }
+        };
+
+        // Number turns globally and split on turn boundaries so each LLM call stays within budget
+        let mut chunks: Vec<String> = Vec::new();
+        let mut current = String::new();
+        for (index, turn) in params.turns.iter().enumerate() {
+            let line = format!("[{}] {}: {}\n", index, turn.role.trim(), turn.content.trim());
+            if !current.is_empty() && current.len() + line.len() > chunk_chars {
+                chunks.push(std::mem::take(&mut current));
+            }
+            current.push_str(&line);
+        }
+        if !current.is_empty() {
+            chunks.push(current);
+        }
+
+        let source = params.source.unwrap_or_else(|| "conversation".to_string());
+        let mut extracted = Vec::new();
+        let mut failed_chunks = 0;
+        for chunk in &chunks {
+            match provider.extract_conversation(chunk).await {
+                Ok(memories) => extracted.extend(memories),
+                Err(e) => {
+                    failed_chunks += 1;
+                    tracing::warn!(error = %e, "Conversation chunk extraction failed");
+                }
+            }
+        }
+        if failed_chunks == chunks.len() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::PROVIDER_ERROR,
+                "error": "Conversation extraction failed for every chunk",
+                "hint": "Check that the extraction provider is reachable, then retry"
+            })));
+        }
+
+        let mut inputs: Vec<CreateMemory> = Vec::with_capacity(extracted.len());
+        let mut input_redactions: Vec<Vec<crate::privacy::Redaction>> = Vec::with_capacity(extracted.len());
+        let mut duplicates: Vec<serde_json::Value> = Vec::new();
+        for memory in extracted {
+            // Nothing is stored yet, so a fail-closed filter error rejects the whole call
+            let (content, redactions) = match self.scrub_content(memory.content).await {
+                Ok(scrubbed) => scrubbed,
+                Err(result) => return Ok(result),
+            };
+            let input = CreateMemory {
+                content,
+                type_hint: memory.type_hint,
+                source: source.clone(),
+                tags: params.tags.clone(),
+                // Turn numbers come from the model — ignore any that don't exist
+                created_at: memory.turn.and_then(|t| timestamps.get(t).copied().flatten()),
+                namespace: namespace.clone(),
+                expires_at: None,
+                importance: crate::store::DEFAULT_IMPORTANCE,
+                session_id: None,
+                fields: None,
+                payload: None,
+                origin_refs: origin_refs(Some(OriginRef {
+                    conversation_id: params.conversation_id.clone(),
+                    message_id: memory.turn.and_then(|t| params.turns.get(t)).and_then(|turn| turn.message_id.clone()),
+                    ..Default::default()
+                })),
+                classify: self.classifies(true),
+            };
+            match self.find_duplicate(&input).await {
+                Ok(Some((existing, match_kind, _))) => {
+                    duplicates.push(json!({
+                        "content": input.content,
+                        "duplicate_of": existing.id,
+                        "match": match_kind,
+                    }));
+                }
+                Ok(None) => {
+                    inputs.push(input);
+                    input_redactions.push(redactions);
+                }
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        }
+
+        let stored = if inputs.is_empty() {
+            Vec::new()
+        } else {
+            match self.store.store_batch(inputs).await.inspect(|_| self.invalidate_search_cache()) {
+                Ok(memories) => memories,
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        };
+        self.emit_created(&stored);
+        let mut degraded = false;
+        // The extractor already assigned each memory's type_hint
+        for (memory, redactions) in stored.iter().zip(&input_redactions) {
+            self.record_redactions(&memory.id, redactions).await;
+            degraded |= !self.enqueue_new_memory(memory, true);
+        }
+
+        let memories: Vec<serde_json::Value> = stored
+            .iter()
+            .map(|m| {
+                json!({
+                    "id": m.id,
+                    "content": m.content,
+                    "type_hint": m.type_hint,
+                    "created_at": m.created_at.to_rfc3339(),
+                })
+            })
+            .collect();
+        Ok(CallToolResult::structured(json!({
+            "stored": memories.len(),
+            "memories": memories,
+            "duplicates": duplicates,
+            "chunks": chunks.len(),
+            "failed_chunks": failed_chunks,
+            "queue_depth": self.queue_depth(),
+            "degraded": degraded,
+            "hint": if failed_chunks > 0 {
+                "Some transcript chunks failed to extract — resend those turns to retry"
+            } else if memories.is_empty() && duplicates.is_empty() {
+                "Nothing worth remembering was found in this conversation"
+            } else if degraded {
+                "Stored, but background pipelines are saturated — embedding and extraction are deferred to the next backfill"
+            } else {
+                "Memories stored. Embedding and extraction run in the background."
+            }
+        })))
+    }
+
+    #[tool(description = "Retrieve a specific memory by ID. Also updates access count and last accessed timestamp. Pass include_pipeline_status: true to see its embedding, extraction, consolidation, and salience state when it isn't showing up in search.")]
+    async fn get_memory(
+        &self,
+        Parameters(params): Parameters<GetMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "get_memory",
+            id = %params.id,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        match self.store.get(&params.id).await {
+            Ok(memory) => {
+                // Implicit salience bump on direct retrieval (fire-and-forget, not on search results)
+                if let Some(ref pg_store) = self.pg_store {
+                    let store = pg_store.clone();
+                    let id = params.id.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = store.touch_salience(&id).await {
+                            tracing::warn!("Failed to touch salience for {}: {}", id, e);
+                        }
+                    });
+                    if self.search_log {
+                        let store = pg_store.clone();
+                        let (id, namespace) = (memory.id.clone(), memory.namespace.clone());
+                        tokio::spawn(async move {
+                            if let Err(e) = store.record_search_fetch(&id, &namespace).await {
+                                tracing::warn!(error = %e, "Failed to record search fetch");
+                            }
+                        });
+                    }
+                }
+                let pipeline_status = if params.include_pipeline_status {
+                    match self.pipeline_status(&memory).await {
+                        Ok(status) => Some(status),
+                        Err(e) => return Ok(store_error_to_result(e)),
+                    }
+                } else {
+                    None
+                };
+                let mut response = memory_detail_json(&memory);
+                response["hint"] = json!("Use update_memory to modify or delete_memory to remove");
+                if let Some(status) = pipeline_status {
+                    response["pipeline_status"] = status;
+                }
+                Ok(CallToolResult::structured(response))
+            }
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Retrieve several memories by ID in one call (up to 100), e.g. IDs kept from an earlier search. Returns the found memories in request order and lists IDs that don't exist in not_found. Updates access counts like get_memory.")]
+    async fn get_memories(
+        &self,
+        Parameters(params): Parameters<GetMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(tool = "get_memories", count = params.ids.len(), "Tool called");
+
+        let mut ids: Vec<String> = Vec::with_capacity(params.ids.len());
+        for id in params.ids.iter().map(|id| id.trim()) {
+            if !id.is_empty() && !ids.iter().any(|seen| seen == id) {
+                ids.push(id.to_string());
+            }
+        }
+        if ids.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'ids' must contain at least one memory ID",
+                "field": "ids"
+            })));
+        }
+        if ids.len() > MAX_BATCH_GET {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Field 'ids' accepts at most {} IDs per call (got {})", MAX_BATCH_GET, ids.len()),
+                "field": "ids"
+            })));
+        }
+
+        let mut found = match self.store.get_memories_by_ids(&ids).await {
+            Ok(found) => found,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+        // Like get_memory, trashed memories are not found
+        found.retain(|_, memory| memory.deleted_at.is_none());
+        let (memories, not_found): (Vec<&String>, Vec<&String>) = ids.iter().partition(|id| found.contains_key(*id));
+        let found_ids: Vec<String> = memories.iter().map(|id| id.to_string()).collect();
+
+        // One access-stats update and one salience bump for the whole batch
+        if let Err(e) = self.store.touch_many(&found_ids).await {
+            tracing::warn!(error = %e, "Failed to update access stats for get_memories");
+        }
+        if let Some(ref pg_store) = self.pg_store {
+            let store = pg_store.clone();
+            let touched = found_ids.clone();
+            tokio::spawn(async move {
+                if let Err(e) = store.touch_salience_batch(&touched).await {
+                    tracing::warn!(error = %e, "Failed to touch salience for get_memories");
+                }
+            });
+            if self.search_log {
+                let store = pg_store.clone();
+                let fetched: Vec<(String, String)> =
+                    found.values().map(|m| (m.id.clone(), m.namespace.clone())).collect();
+                tokio::spawn(async move {
+                    for (id, namespace) in fetched {
+                        if let Err(e) = store.record_search_fetch(&id, &namespace).await {
+                            tracing::warn!(error = %e, "Failed to record search fetch");
+                        }
+                    }
+                });
+            }
+        }
+
+        let memories: Vec<serde_json::Value> = memories.iter().map(|id| memory_detail_json(&found[*id])).collect();
+        Ok(CallToolResult::structured(json!({
+            "memories": memories,
+            "not_found": not_found,
+            "requested": ids.len(),
+            "found": found_ids.len(),
+            "hint": if not_found.is_empty() {
+                "Use update_memory to modify or delete_memory to remove"
+            } else {
+                "IDs in not_found don't exist or are in the trash (restore_memory brings trashed memories back)"
+            }
+        })))
+    }
+
+    #[tool(description = "Update an existing memory's content, type hint, source, tags, expiry (expires_at: null removes it), importance, payload, or pinned flag. At least one field must be provided.")]
+    async fn update_memory(
+        &self,
+        Parameters(mut params): Parameters<UpdateMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "update_memory",
+            id = %params.id,
+            has_content = params.content.is_some(),
+            has_type_hint = params.type_hint.is_some(),
+            has_source = params.source.is_some(),
+            has_tags = params.tags.is_some(),
+            has_expires_at = params.expires_at.is_some(),
+            importance = ?params.importance,
+            pinned = ?params.pinned,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        if params.content.is_none()
+            && params.type_hint.is_none()
+            && params.source.is_none()
+            && params.tags.is_none()
+            && params.expires_at.is_none()
+            && params.importance.is_none()
+            && params.payload.is_none()
+            && params.pinned.is_none()
+        {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "At least one of 'content', 'type_hint', 'source', 'tags', 'expires_at', 'importance', 'payload', or 'pinned' must be provided"
+            })));
+        }
+
+        if let Err(result) = check_payload(params.payload.as_ref()) {
+            return Ok(result);
+        }
+
+        // null (Some(None)) clears the expiry
+        let expires_at = match params.expires_at.as_ref().map(|at| parse_expires_at(at.as_deref())).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return Ok(result),
+        };
+
+        let importance = match check_importance(params.importance, "importance") {
+            Ok(value) => value,
+            Err(result) => return Ok(result),
+        };
+
+        let namespace = match self.resolve_namespace(params.namespace.take()) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        let mut redactions = Vec::new();
+        if let Some(content) = params.content.take() {
+            match self.scrub_content(content).await {
+                Ok((content, made)) => {
+                    params.content = Some(content);
+                    redactions = made;
+                }
+                Err(result) => return Ok(result),
+            }
+        }
+
+        if let Some(ref content) = params.content {
+            if self.is_oversized(content) {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": format!("Field 'content' exceeds the {} character limit", self.content_config.max_chars),
+                    "field": "content"
+                })));
+            }
+            // Chunks mirror their parent's content — replace chunked memories instead of editing
+            match self.store.get(&params.id).await {
+                Ok(existing) if existing.parent_id.is_some() || existing.embedding_status == crate::store::CHUNKED_STATUS => {
+                    return Ok(CallToolResult::structured_error(json!({
+                        "isError": true,
+                        "code": codes::VALIDATION,
+                        "error": "The content of a chunked memory or one of its chunks cannot be edited",
+                        "field": "content",
+                        "hint": "Delete the memory and store the new content with store_memory"
+                    })));
+                }
+                Ok(_) => {}
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        }
+
+        // Track if content or embedded metadata changed — determines if re-embedding is needed
+        let content_changed = params.content.is_some();
+        let metadata_changed = params.tags.is_some()
+            || (params.type_hint.is_some() && self.embedding_template.uses_field("type_hint"))
+            || (params.source.is_some() && self.embedding_template.uses_field("source"))
+            || (params.payload.is_some() && self.embedding_template.uses_field("payload"));
+
+        let input = UpdateMemory {
+            content: params.content,
+            type_hint: params.type_hint,
+            source: params.source,
+            tags: params.tags,
+            expires_at,
+            importance,
+            payload: params.payload,
+            pinned: params.pinned,
+        };
+
+        match self.store.update(&params.id, input).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(memory) => {
+                self.record_redactions(&memory.id, &redactions).await;
+                self.reprocess_updated_memory(&memory, content_changed, metadata_changed);
+                let mut response = json!({
+                    "id": memory.id,
+                    "content": memory.content,
+                    "type_hint": memory.type_hint,
+                    "source": memory.source,
+                    "tags": memory.tags,
+                    "created_at": memory.created_at.to_rfc3339(),
+                    "updated_at": memory.updated_at.to_rfc3339(),
+                    "access_count": memory.access_count,
+                    "embedding_status": memory.embedding_status,
+                    "expires_at": memory.expires_at.map(|dt| dt.to_rfc3339()),
+                    "importance": memory.importance,
+                    "pinned": memory.pinned,
+                    "hint": "Use get_memory to re-read or delete_memory to remove"
+                });
+                if !redactions.is_empty() {
+                    response["redactions"] = json!(redactions.len());
+                }
+                Ok(CallToolResult::structured(response))
+            }
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Delete a single memory by ID. By default the memory moves to the trash and can be brought back with restore_memory. Set permanent: true to remove it immediately (cannot be undone).")]
+    async fn delete_memory(
+        &self,
+        Parameters(params): Parameters<DeleteMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "delete_memory",
+            id = %params.id,
+            permanent = params.permanent,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        let deleted = |permanent: bool| {
+            self.invalidate_search_cache();
+            self.events.emit(
+                crate::events::MemoryEvent::new(crate::events::EventKind::Deleted, &namespace, vec![params.id.clone()])
+                    .with_data(json!({ "permanent": permanent })),
+            );
+        };
+        if params.permanent {
+            match self.store.delete(&params.id).await.inspect(|_| deleted(true)) {
+                Ok(()) => Ok(CallToolResult::structured(json!({
+                    "deleted": true,
+                    "trashed": false,
+                    "id": params.id,
+                    "hint": "Memory permanently removed. Use store_memory to create new memories."
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            }
+        } else {
+            match self.store.trash(&params.id).await.inspect(|_| deleted(false)) {
+                Ok(()) => Ok(CallToolResult::structured(json!({
+                    "deleted": true,
+                    "trashed": true,
+                    "id": params.id,
+                    "hint": "Memory moved to trash. Use restore_memory to undo, or purge_trash to remove it permanently."
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            }
+        }
+    }
+
+    #[tool(description = "Restore a memory from the trash, or un-archive a memory archived by salience decay, so it appears in search and list results again.")]
+    async fn restore_memory(
+        &self,
+        Parameters(params): Parameters<RestoreMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "restore_memory",
+            id = %params.id,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        match self.store.restore(&params.id).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(memory) => Ok(CallToolResult::structured(json!({
+                "restored": true,
+                "id": memory.id,
+                "content": memory.content,
+                "type_hint": memory.type_hint,
+                "namespace": memory.namespace,
+                "hint": "Memory restored. Use get_memory to read it."
+            }))),
+            Err(MemcpError::NotFound { id }) => Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::NOT_FOUND,
+                "error": format!("Memory not found in trash or archive: {}", id),
+                "hint": "Use list_memories with trashed: true or archived: true to see restorable memories"
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Pin a memory so it is always injected at the top of search results whose filters it matches and always listed in the session primer, regardless of salience. Pass pinned: false to unpin.")]
+    async fn pin_memory(
+        &self,
+        Parameters(params): Parameters<PinMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "pin_memory",
+            id = %params.id,
+            pinned = params.pinned,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        let input = UpdateMemory { pinned: Some(params.pinned), ..Default::default() };
+        match self.store.update(&params.id, input).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(memory) => Ok(CallToolResult::structured(json!({
+                "id": memory.id,
+                "pinned": memory.pinned,
+                "namespace": memory.namespace,
+                "hint": if memory.pinned {
+                    "Memory pinned. It now tops matching search results and the session primer."
+                } else {
+                    "Memory unpinned. It is ranked by salience again."
+                }
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "List the previous versions of a memory, newest first. Every update_memory call saves the version it replaces. Use revert_memory to roll back to one.")]
+    async fn get_memory_history(
+        &self,
+        Parameters(params): Parameters<GetMemoryHistoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "get_memory_history",
+            id = %params.id,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory history requires PostgreSQL backend"
+                })));
+            }
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        // Load the current version too, so callers see the full timeline
+        let current = match pg_store.get_memories_by_ids(std::slice::from_ref(&params.id)).await {
+            Ok(mut found) => match found.remove(&params.id) {
+                Some(memory) => memory,
+                None => return Ok(store_error_to_result(MemcpError::NotFound { id: params.id })),
+            },
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+
+        match pg_store.list_revisions(&params.id).await {
+            Ok(revisions) => {
+                let items: Vec<serde_json::Value> = revisions
+                    .iter()
+                    .map(|r| {
+                        json!({
+                            "revision": r.revision,
+                            "content": r.content,
+                            "type_hint": r.type_hint,
+                            "source": r.source,
+                            "tags": r.tags,
+                            "valid_from": r.valid_from.to_rfc3339(),
+                            "replaced_at": r.replaced_at.to_rfc3339(),
+                        })
+                    })
+                    .collect();
+                let count = items.len();
+                Ok(CallToolResult::structured(json!({
+                    "id": current.id,
+                    "current": {
+                        "content": current.content,
+                        "type_hint": current.type_hint,
+                        "source": current.source,
+                        "tags": current.tags,
+                        "updated_at": current.updated_at.to_rfc3339(),
+                    },
+                    "revisions": items,
+                    "count": count,
+                    "hint": if count == 0 {
+                        "This memory has never been updated"
+                    } else {
+                        "Use revert_memory with a revision number to roll back"
+                    }
+                })))
+            }
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Roll a memory back to an earlier revision from get_memory_history. The version being replaced is saved as a new revision, so a revert can itself be undone.")]
+    async fn revert_memory(
+        &self,
+        Parameters(params): Parameters<RevertMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "revert_memory",
+            id = %params.id,
+            revision = params.revision,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+        if params.revision < 1 {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'revision' must be a positive revision number",
+                "field": "revision"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory history requires PostgreSQL backend"
+                })));
+            }
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        match pg_store.revert_to_revision(&params.id, params.revision).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(memory) => {
+                self.reprocess_updated_memory(&memory, true, true);
+                Ok(CallToolResult::structured(json!({
+                    "reverted": true,
+                    "id": memory.id,
+                    "revision": params.revision,
+                    "content": memory.content,
+                    "type_hint": memory.type_hint,
+                    "source": memory.source,
+                    "tags": memory.tags,
+                    "updated_at": memory.updated_at.to_rfc3339(),
+                    "hint": "Memory reverted. The replaced version was saved — see get_memory_history."
+                })))
+            }
+            Err(MemcpError::NotFound { id }) => Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::NOT_FOUND,
+                "error": format!("Memory or revision not found: {}", id),
+                "field": "revision",
+                "hint": "Use get_memory_history to list available revisions"
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Undo a consolidation. Deletes the consolidated memory and restores the original memories it was merged from, so they appear in search again.")]
+    async fn unconsolidate_memory(
+        &self,
+        Parameters(params): Parameters<UnconsolidateMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "unconsolidate_memory",
+            id = %params.id,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Unconsolidation requires PostgreSQL backend"
+                })));
+            }
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        match pg_store.unconsolidate(&params.id).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(restored_ids) => Ok(CallToolResult::structured(json!({
+                "unconsolidated": true,
+                "id": params.id,
+                "restored_ids": restored_ids,
+                "restored": restored_ids.len(),
+                "hint": "Original memories are searchable again. Use get_memory to review them."
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Create a typed, directed link between two memories (e.g. 'supersedes', 'works_at', 'part_of'). Linking the same pair with the same relation again is a no-op. Explore links with get_memory_graph.")]
+    async fn link_memories(
+        &self,
+        Parameters(params): Parameters<LinkMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "link_memories",
+            source_id = %params.source_id,
+            target_id = %params.target_id,
+            relation = ?params.relation,
+            "Tool called"
+        );
+
+        for (field, value) in [("source_id", &params.source_id), ("target_id", &params.target_id)] {
+            if value.trim().is_empty() {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": format!("Field '{}' is required and cannot be empty", field),
+                    "field": field
+                })));
+            }
+        }
+
+        let relation = params.relation.as_deref().map(str::trim).unwrap_or("related_to");
+        if relation.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'relation' cannot be empty",
+                "field": "relation"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory links require PostgreSQL backend"
+                })));
+            }
+        };
+
+        for id in [&params.source_id, &params.target_id] {
+            if let Some(result) = self.reject_foreign_namespace(id, &namespace).await {
+                return Ok(result);
+            }
+        }
+
+        match pg_store.create_link(&params.source_id, &params.target_id, relation).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(link) => Ok(CallToolResult::structured(json!({
+                "linked": true,
+                "id": link.id,
+                "source_id": link.source_id,
+                "target_id": link.target_id,
+                "relation": link.relation,
+                "created_at": link.created_at.to_rfc3339(),
+                "hint": "Link created. Use get_memory_graph to explore connected memories."
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Remove links between two memories. Removes only the given relation, or every link from source to target when relation is omitted.")]
+    async fn unlink_memories(
+        &self,
+        Parameters(params): Parameters<UnlinkMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "unlink_memories",
+            source_id = %params.source_id,
+            target_id = %params.target_id,
+            relation = ?params.relation,
+            "Tool called"
+        );
+
+        for (field, value) in [("source_id", &params.source_id), ("target_id", &params.target_id)] {
+            if value.trim().is_empty() {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": format!("Field '{}' is required and cannot be empty", field),
+                    "field": field
+                })));
+            }
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory links require PostgreSQL backend"
+                })));
+            }
+        };
+
+        for id in [&params.source_id, &params.target_id] {
+            if let Some(result) = self.reject_foreign_namespace(id, &namespace).await {
+                return Ok(result);
+            }
+        }
+
+        let relation = params.relation.as_deref().map(str::trim).filter(|r| !r.is_empty());
+        match pg_store.delete_link(&params.source_id, &params.target_id, relation).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(removed) => Ok(CallToolResult::structured(json!({
+                "removed": removed,
+                "source_id": params.source_id,
+                "target_id": params.target_id,
+                "hint": if removed == 0 {
+                    "No matching link found. Links are directed — check source_id and target_id order."
+                } else {
+                    "Link removed."
+                }
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Get the neighbourhood of a memory: every memory reachable by following links (in either direction) up to 'depth' hops, plus the links between them.")]
+    async fn get_memory_graph(
+        &self,
+        Parameters(params): Parameters<GetMemoryGraphParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "get_memory_graph",
+            id = %params.id,
+            depth = ?params.depth,
+            "Tool called"
+        );
+
+        if params.id.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'id' is required and cannot be empty",
+                "field": "id"
+            })));
+        }
+
+        let depth = params.depth.unwrap_or(1);
+        if !(1..=3).contains(&depth) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'depth' must be between 1 and 3",
+                "field": "depth"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory links require PostgreSQL backend"
+                })));
+            }
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        match pg_store.get_memory_graph(&params.id, depth).await {
+            Ok((memories, _)) if memories.is_empty() => Ok(store_error_to_result(MemcpError::NotFound { id: params.id })),
+            Ok((memories, links)) => {
+                let nodes: Vec<serde_json::Value> = memories
+                    .iter()
+                    .map(|m| {
+                        json!({
+                            "id": m.id,
+                            "content": m.content,
+                            "type_hint": m.type_hint,
+                            "source": m.source,
+                            "tags": m.tags,
+                            "created_at": m.created_at.to_rfc3339(),
+                        })
+                    })
+                    .collect();
+                let edges: Vec<serde_json::Value> = links
+                    .iter()
+                    .map(|l| {
+                        json!({
+                            "id": l.id,
+                            "source_id": l.source_id,
+                            "target_id": l.target_id,
+                            "relation": l.relation,
+                        })
+                    })
+                    .collect();
+                Ok(CallToolResult::structured(json!({
+                    "id": params.id,
+                    "depth": depth,
+                    "memories": nodes,
+                    "links": edges,
+                    "hint": if edges.is_empty() {
+                        "This memory has no links yet. Use link_memories to connect it."
+                    } else {
+                        "Call get_memory_graph on any memory to keep exploring, or increase depth"
+                    }
+                })))
+            }
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "List memories that contradict each other, e.g. \"prefers tabs\" vs a later \"prefers spaces\". Contradictions are detected after extraction when extraction.detect_contradictions is on, and recorded as 'contradicts' links from the newer memory to the older one. Pass id to check one memory. Resolve them with update_memory, delete_memory, or unlink_memories.")]
+    async fn get_contradictions(
+        &self,
+        Parameters(params): Parameters<GetContradictionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "get_contradictions",
+            id = ?params.id,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let limit = params.limit.unwrap_or(20);
+        if !(1..=100).contains(&limit) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'limit' must be between 1 and 100",
+                "field": "limit"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory links require PostgreSQL backend"
+                })));
+            }
+        };
+
+        let id = params.id.as_deref().map(str::trim).filter(|id| !id.is_empty());
+        if let Some(id) = id {
+            if let Some(result) = self.reject_foreign_namespace(id, &namespace).await {
+                return Ok(result);
+            }
+        }
+
+        let links = match pg_store.get_contradictions(&namespace, id, limit as i64).await {
+            Ok(links) => links,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+        let ids: Vec<String> = links
+            .iter()
+            .flat_map(|l| [l.source_id.clone(), l.target_id.clone()])
+            .collect();
+        let memories = match pg_store.get_memories_by_ids(&ids).await {
+            Ok(memories) => memories,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+        let summary = |id: &str| {
+            memories.get(id).map(|m| {
+                json!({
+                    "id": m.id,
+                    "content": m.content,
+                    "type_hint": m.type_hint,
+                    "created_at": m.created_at.to_rfc3339(),
+                })
+            })
+        };
+        let contradictions: Vec<serde_json::Value> = links
+            .iter()
+            .map(|l| {
+                json!({
+                    "link_id": l.id,
+                    "memory": summary(&l.source_id),
+                    "contradicts": summary(&l.target_id),
+                    "detected_at": l.created_at.to_rfc3339(),
+                })
+            })
+            .collect();
+
+        Ok(CallToolResult::structured(json!({
+            "namespace": namespace,
+            "count": contradictions.len(),
+            "contradictions": contradictions,
+            "hint": if contradictions.is_empty() {
+                "No contradictions recorded. Detection runs after extraction when extraction.detect_contradictions is enabled."
+            } else {
+                "Each memory is newer than the one it contradicts. Update or delete the stale one, or unlink_memories if both hold."
+            }
+        })))
+    }
+
+    #[tool(description = "Generate an on-demand digest of memories selected by a query and/or filters (type_hint, source, tags, time range) using the configured LLM. Set store: true to save the digest as a new memory with type_hint 'summary'.")]
+    async fn summarize_memories(
+        &self,
+        Parameters(params): Parameters<SummarizeMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "summarize_memories",
+            query = ?params.query,
+            type_hint = ?params.type_hint,
+            source = ?params.source,
+            store = params.store,
+            "Tool called"
+        );
+
+        if params.store && self.read_only {
+            return Ok(read_only_error("summarize_memories with store: true"));
+        }
+
+        let limit = params.limit.unwrap_or(50);
+        if !(1..=100).contains(&limit) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'limit' must be between 1 and 100",
+                "field": "limit"
+            })));
+        }
+        let query = params.query.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+        let created_after = match params.created_after.as_deref().map(|s| parse_datetime(s, "created_after")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return Ok(result),
+        };
+        let created_before = match params.created_before.as_deref().map(|s| parse_datetime(s, "created_before")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return Ok(result),
+        };
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let provider = match &self.summary_provider {
+            Some(p) => p,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::PROVIDER_UNAVAILABLE,
+                    "error": "Summaries require an LLM provider",
+                    "hint": "Configure [consolidation] or [extraction] provider settings in memcp.toml"
+                })));
+            }
+        };
+
+        // Retrieve: hybrid search when a query is given, otherwise newest-first listing. Both
+        // apply every filter before the limit, so filtered memories don't crowd out matches.
+        let memories: Vec<Memory> = if let Some(query) = query {
+            let pg_store = match &self.pg_store {
+                Some(s) => s,
+                None => {
+                    return Ok(CallToolResult::structured_error(json!({
+                        "isError": true,
+                        "code": codes::BACKEND_UNSUPPORTED,
+                        "error": "Query-based summaries require PostgreSQL backend",
+                        "hint": "Omit 'query' to summarize by filters only"
+                    })));
+                }
+            };
+            let query_embedding = match self.embedding_provider {
+                Some(ref provider) if provider.is_ready() => provider.embed(query).await.ok().map(pgvector::Vector::from),
+                _ => None,
+            };
+            let filter = crate::store::SearchFilter {
+                limit: limit as i64,
+                created_after,
+                created_before,
+                tags: params.tags.clone(),
+                namespace: Some(namespace.clone()),
+                type_hint: params.type_hint.clone(),
+                source: params.source.clone(),
+                ..Default::default()
+            };
+            match pg_store.hybrid_search(
+                query,
+                query_embedding.as_ref(),
+                &[],
+                &filter,
+                Some(60.0),
+                Some(60.0),
+                Some(40.0),
+                None,
+                crate::search::Fusion::Rrf,
+            ).await {
+                Ok(hits) => hits.into_iter().map(|h| h.memory).collect(),
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        } else {
+            let filter = ListFilter {
+                namespace: Some(namespace.clone()),
+                type_hint: params.type_hint.clone(),
+                source: params.source.clone(),
+                tags: params.tags.clone(),
+                created_after,
+                created_before,
+                limit: limit as i64,
+                ..ListFilter::default()
+            };
+            match self.store.list(filter).await {
+                Ok(result) => result.memories,
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        };
+
+        if memories.is_empty() {
+            return Ok(CallToolResult::structured(json!({
+                "summary": null,
+                "memory_count": 0,
+                "hint": "No memories matched — broaden the query or filters"
+            })));
+        }
+
+        let contents: Vec<&str> = memories.iter().map(|m| m.content.as_str()).collect();
+        let summary = match provider.summarize(&contents, query).await {
+            Ok(text) => text,
+            Err(e) => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::PROVIDER_ERROR,
+                    "error": format!("Summary generation failed: {}", e),
+                    "hint": "Check that the LLM provider is reachable, then retry"
+                })));
+            }
+        };
+        let source_ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+
+        let stored_id = if params.store {
+            let input = CreateMemory {
+                content: summary.clone(),
+                type_hint: "summary".to_string(),
+                source: "summarize_memories".to_string(),
+                tags: params.tags.clone(),
+                created_at: None,
+                namespace,
+                expires_at: None,
+                importance: crate::store::DEFAULT_IMPORTANCE,
+                session_id: None,
+                fields: None,
+                payload: None,
+                origin_refs: merged_origin_refs(&memories),
+                classify: self.classifies(true),
+            };
+            match self.store.store(input).await.inspect(|_| self.invalidate_search_cache()) {
+                Ok(memory) => {
+                    self.enqueue_new_memory(&memory, true);
+                    Some(memory.id)
+                }
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        } else {
+            None
+        };
+
+        Ok(CallToolResult::structured(json!({
+            "summary": summary,
+            "memory_count": memories.len(),
+            "source_ids": source_ids,
+            "stored_id": stored_id,
+            "model": provider.model_name(),
+            "hint": if stored_id.is_some() {
+                "Summary stored as a new memory with type_hint 'summary'"
+            } else {
+                "Pass store: true to save this summary as a memory"
+            }
+        })))
+    }
+
+    #[tool(description = "Start a working session. Pass the returned session_id to store_memory so memories are grouped by session; call end_session when done to condense them into a session summary that the memory://session-primer resource shows at the start of later sessions.")]
+    async fn start_session(
+        &self,
+        Parameters(params): Parameters<StartSessionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "start_session",
+            title = ?params.title,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+        let title = params.title.as_deref().map(str::trim).filter(|t| !t.is_empty());
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Sessions require PostgreSQL backend"
+                })));
+            }
+        };
+
+        match pg_store.start_session(&namespace, title).await {
+            Ok(session) => Ok(CallToolResult::structured(json!({
+                "session_id": session.id,
+                "title": session.title,
+                "namespace": session.namespace,
+                "started_at": session.started_at.to_rfc3339(),
+                "hint": "Pass session_id to store_memory, then call end_session to summarize the session"
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "End a session started with start_session. The session's memories are condensed by the LLM provider into one memory with type_hint 'session_summary', which the memory://session-primer resource prefers over raw recent memories. A session can only be ended once.")]
+    async fn end_session(
+        &self,
+        Parameters(params): Parameters<EndSessionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(tool = "end_session", session_id = %params.session_id, "Tool called");
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Sessions require PostgreSQL backend"
+                })));
+            }
+        };
+
+        let session = match pg_store.get_session(&params.session_id).await {
+            Ok(session) if session.ended_at.is_some() => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": "Session has already ended",
+                    "field": "session_id",
+                    "summary_memory_id": session.summary_memory_id
+                })));
+            }
+            Ok(session) => session,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+
+        let memories = match pg_store.get_session_memories(&session.id, MAX_SESSION_SUMMARY_MEMORIES).await {
+            Ok(memories) => memories,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+
+        // Summarize before closing so a provider failure leaves the session open for a retry
+        let mut summary_memory = None;
+        if !memories.is_empty() {
+            if let Some(provider) = &self.summary_provider {
+                let contents: Vec<&str> = memories.iter().map(|m| m.content.as_str()).collect();
+                let focus = "what was worked on, decided, and left open in this session";
+                let summary = match provider.summarize(&contents, Some(focus)).await {
+                    Ok(text) => text,
+                    Err(e) => {
+                        return Ok(CallToolResult::structured_error(json!({
+                            "isError": true,
+                            "code": codes::PROVIDER_ERROR,
+                            "error": format!("Session summary generation failed: {}", e),
+                            "hint": "Check that the LLM provider is reachable, then retry end_session"
+                        })));
+                    }
+                };
+                let input = CreateMemory {
+                    content: summary,
+                    type_hint: "session_summary".to_string(),
+                    source: "end_session".to_string(),
+                    tags: None,
+                    created_at: None,
+                    namespace: session.namespace.clone(),
+                    expires_at: None,
+                    importance: crate::store::DEFAULT_IMPORTANCE,
+                    session_id: Some(session.id.clone()),
+                    fields: None,
+                    payload: None,
+                    origin_refs: merged_origin_refs(&memories),
+                    classify: self.classifies(true),
+                };
+                match self.store.store(input).await.inspect(|_| self.invalidate_search_cache()) {
+                    Ok(memory) => {
+                        self.enqueue_new_memory(&memory, true);
+                        summary_memory = Some(memory);
+                    }
+                    Err(e) => return Ok(store_error_to_result(e)),
+                }
+            }
+        }
+
+        let summary_id = summary_memory.as_ref().map(|m| m.id.as_str());
+        match pg_store.end_session(&session.id, summary_id).await {
+            Ok(ended) => Ok(CallToolResult::structured(json!({
+                "session_id": ended.id,
+                "title": ended.title,
+                "namespace": ended.namespace,
+                "started_at": ended.started_at.to_rfc3339(),
+                "ended_at": ended.ended_at.map(|dt| dt.to_rfc3339()),
+                "memory_count": memories.len(),
+                "summary_memory_id": ended.summary_memory_id,
+                "summary": summary_memory.map(|m| m.content),
+                "hint": if ended.summary_memory_id.is_some() {
+                    "Session summary stored — memory://session-primer will show it at the start of the next session"
+                } else if memories.is_empty() {
+                    "Session ended with no memories, so no summary was written"
+                } else {
+                    "Session ended without a summary — configure an LLM provider in [consolidation] or [extraction] to enable session summaries"
+                }
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Deduplicate existing memories: walk embedded memories in a namespace, find clusters above the consolidation similarity threshold, and merge each cluster into one synthesized memory (originals are kept and hidden from search). Consolidation otherwise only runs when new memories are embedded.")]
+    async fn consolidate_memories(
+        &self,
+        Parameters(params): Parameters<ConsolidateMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "consolidate_memories",
+            limit = ?params.limit,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let limit = params.limit.unwrap_or(500);
+        if !(1..=5000).contains(&limit) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'limit' must be between 1 and 5000",
+                "field": "limit"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Consolidation requires PostgreSQL backend"
+                })));
+            }
+        };
+        let provider = match &self.summary_provider {
+            Some(p) => p,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::PROVIDER_UNAVAILABLE,
+                    "error": "Consolidation requires an LLM provider",
+                    "hint": "Configure [consolidation] or [extraction] provider settings in memcp.toml"
+                })));
+            }
+        };
+
+        const SCAN_BATCH_SIZE: i64 = 100;
+        let config = self.live.load();
+        let mut batches: Vec<serde_json::Value> = Vec::new();
+        let report = match crate::consolidation::scan_existing(
+            pg_store,
+            &config.consolidation,
+            provider.as_ref(),
+            self.conversation_extractor.as_ref().map(|(extractor, _)| extractor.as_ref()),
+            Some(&namespace),
+            SCAN_BATCH_SIZE,
+            Some(limit as u64),
+            |progress| {
+                tracing::info!(
+                    scanned = progress.scanned,
+                    clusters = progress.clusters,
+                    "Consolidation scan progress"
+                );
+                batches.push(json!({
+                    "scanned": progress.scanned,
+                    "clusters": progress.clusters,
+                    "consolidated": progress.consolidated,
+                }));
+            },
+        )
+        .await
+        {
+            Ok(report) => report,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+
+        if report.clusters > 0 {
+            self.invalidate_search_cache();
+            // Consolidated memories are created un-embedded — queue them like any new memory
+            if let Ok(created) = pg_store.get_memories_by_ids(&report.consolidated_ids).await {
+                for memory in created.values() {
+                    self.enqueue_new_memory(memory, true);
+                    self.events.emit(crate::events::MemoryEvent::new(
+                        crate::events::EventKind::Consolidated,
+                        &memory.namespace,
+                        vec![memory.id.clone()],
+                    ));
+                }
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "scanned": report.scanned,
+            "clusters": report.clusters,
+            "consolidated": report.consolidated,
+            "failed": report.failed,
+            "consolidated_ids": report.consolidated_ids,
+            "similarity_threshold": config.consolidation.similarity_threshold,
+            "batches": batches,
+            "hint": if report.clusters > 0 {
+                "Originals are hidden from search; use unconsolidate_memory to undo a merge"
+            } else if report.scanned as u32 >= limit {
+                "No clusters found in this pass — raise 'limit' to scan more memories"
+            } else {
+                "No clusters above the similarity threshold"
+            }
+        })))
+    }
+
+    #[tool(description = "Manually merge 2 or more memories into one consolidated memory, using the given content or content synthesized by the LLM provider. Originals are kept and hidden from search, like automatic consolidation; unconsolidate_memory undoes the merge.")]
+    async fn merge_memories(
+        &self,
+        Parameters(params): Parameters<MergeMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "merge_memories",
+            count = params.ids.len(),
+            has_content = params.content.is_some(),
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let mut ids: Vec<String> = Vec::with_capacity(params.ids.len());
+        for id in params.ids.iter().map(|id| id.trim()) {
+            if !id.is_empty() && !ids.iter().any(|seen| seen == id) {
+                ids.push(id.to_string());
+            }
+        }
+        if !(2..=MAX_MERGE).contains(&ids.len()) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Field 'ids' must contain between 2 and {} distinct memory IDs (got {})", MAX_MERGE, ids.len()),
+                "field": "ids"
+            })));
+        }
+        if params.content.as_ref().is_some_and(|c| c.trim().is_empty()) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'content' cannot be empty — omit it to synthesize the merged content",
+                "field": "content"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Merging memories requires PostgreSQL backend"
+                })));
+            }
+        };
+
+        let mut found = match pg_store.get_memories_by_ids(&ids).await {
+            Ok(found) => found,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+        found.retain(|_, memory| memory.deleted_at.is_none() && memory.namespace == namespace);
+        let not_found: Vec<&String> = ids.iter().filter(|id| !found.contains_key(*id)).collect();
+        if !not_found.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::NOT_FOUND,
+                "error": format!("Memories not found in namespace '{}': {}", namespace, not_found.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(", ")),
+                "field": "ids",
+                "not_found": not_found,
+                "hint": "Memories must exist, be out of the trash, and share one namespace"
+            })));
+        }
+        let already_merged: Vec<serde_json::Value> = ids
+            .iter()
+            .filter_map(|id| found[id].consolidated_into.as_ref().map(|into| json!({"id": id, "consolidated_into": into})))
+            .collect();
+        if !already_merged.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Some memories are already merged into a consolidated memory",
+                "field": "ids",
+                "already_merged": already_merged,
+                "hint": "Merge the consolidated memory instead, or unconsolidate_memory it first"
+            })));
+        }
+
+        let originals: Vec<&Memory> = ids.iter().map(|id| &found[id]).collect();
+        let synthesized = params.content.is_none();
+        let (content, redactions) = match params.content {
+            Some(content) => match self.scrub_content(content).await {
+                Ok(scrubbed) => scrubbed,
+                Err(result) => return Ok(result),
+            },
+            None => {
+                let Some(provider) = &self.summary_provider else {
+                    return Ok(CallToolResult::structured_error(json!({
+                        "isError": true,
+                        "code": codes::PROVIDER_UNAVAILABLE,
+                        "error": "Synthesizing merged content requires an LLM provider",
+                        "hint": "Pass 'content', or configure [consolidation] or [extraction] provider settings in memcp.toml"
+                    })));
+                };
+                let contents: Vec<&str> = originals.iter().map(|m| m.content.as_str()).collect();
+                match provider.synthesize(&contents).await {
+                    Ok(text) => (text, Vec::new()),
+                    Err(e) => {
+                        return Ok(CallToolResult::structured_error(json!({
+                            "isError": true,
+                            "code": codes::PROVIDER_ERROR,
+                            "error": format!("Merged content synthesis failed: {}", e),
+                            "hint": "Check that the LLM provider is reachable and retry, or pass 'content'"
+                        })));
+                    }
+                }
+            }
+        };
+
+        // A manual merge is the user's call, not a similarity match — record full similarity
+        let similarities = vec![1.0; ids.len()];
+        let merged_id = match pg_store.create_consolidated_memory(&content, &ids, &similarities, None).await {
+            Ok(id) => id,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+        metrics::global().consolidation_merges.inc();
+        self.invalidate_search_cache();
+        self.record_redactions(&merged_id, &redactions).await;
+        self.events.emit(
+            crate::events::MemoryEvent::new(crate::events::EventKind::Consolidated, &namespace, vec![merged_id.clone()])
+                .with_data(json!({ "source_ids": ids, "manual": true })),
+        );
+        // Like automatic consolidation, the merged memory is created un-embedded
+        if let Ok(created) = pg_store.get_memories_by_ids(std::slice::from_ref(&merged_id)).await {
+            for memory in created.values() {
+                self.enqueue_new_memory(memory, true);
+            }
+        }
+
+        Ok(CallToolResult::structured(json!({
+            "id": merged_id,
+            "content": content,
+            "merged_ids": ids,
+            "merged": ids.len(),
+            "synthesized": synthesized,
+            "namespace": namespace,
+            "hint": "Originals are hidden from search; use unconsolidate_memory to undo the merge"
+        })))
+    }
+
+    #[tool(description = "Permanently remove all trashed memories in a namespace. First call (confirm: false) returns the count. Second call (confirm: true) purges.")]
+    async fn purge_trash(
+        &self,
+        Parameters(params): Parameters<PurgeTrashParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "purge_trash",
+            confirm = params.confirm,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let filter = ListFilter {
+            namespace: Some(namespace.clone()),
+            trashed: true,
+            ..ListFilter::default()
+        };
+
+        if !params.confirm {
+            match self.store.count_matching(&filter).await {
+                Ok(count) => Ok(CallToolResult::structured(json!({
+                    "matched": count,
+                    "purged": false,
+                    "hint": format!("Call purge_trash again with confirm: true to permanently remove these {} memories", count)
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            }
+        } else {
+            match self.store.delete_matching(&filter).await.inspect(|count| self.bulk_deleted(&namespace, *count, true)) {
+                Ok(count) => Ok(CallToolResult::structured(json!({
+                    "purged": count,
+                    "confirmed": true,
+                    "hint": "Trash emptied. Purged memories cannot be restored."
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            }
+        }
+    }
+
+    #[tool(description = "Bulk delete memories by filter. First call (confirm: false) returns the count. Second call (confirm: true) moves them to the trash, or removes them permanently with permanent: true.")]
+    async fn bulk_delete_memories(
+        &self,
+        Parameters(params): Parameters<BulkDeleteMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "bulk_delete_memories",
+            confirm = params.confirm,
+            type_hint = ?params.type_hint,
+            source = ?params.source,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        // Parse optional datetime strings
+        let created_after = if let Some(ref s) = params.created_after {
+            match parse_datetime(s, "created_after") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let created_before = if let Some(ref s) = params.created_before {
+            match parse_datetime(s, "created_before") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let updated_after = if let Some(ref s) = params.updated_after {
+            match parse_datetime(s, "updated_after") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let updated_before = if let Some(ref s) = params.updated_before {
+            match parse_datetime(s, "updated_before") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let filter = ListFilter {
+            namespace: Some(namespace.clone()),
+            type_hint: params.type_hint,
+            source: params.source,
+            created_after,
+            created_before,
+            updated_after,
+            updated_before,
+            ..ListFilter::default()
+        };
+
+        if !params.confirm {
+            match self.store.count_matching(&filter).await {
+                Ok(count) => Ok(CallToolResult::structured(json!({
+                    "matched": count,
+                    "deleted": false,
+                    "hint": format!("Call bulk_delete_memories again with confirm: true to delete these {} memories", count)
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            }
+        } else if params.permanent {
+            match self.store.delete_matching(&filter).await.inspect(|count| self.bulk_deleted(&namespace, *count, true)) {
+                Ok(count) => Ok(CallToolResult::structured(json!({
+                    "deleted": count,
+                    "trashed": false,
+                    "confirmed": true,
+                    "hint": "Bulk deletion complete. Use list_memories to verify."
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            }
+        } else {
+            match self.store.trash_matching(&filter).await.inspect(|count| self.bulk_deleted(&namespace, *count, false)) {
+                Ok(count) => Ok(CallToolResult::structured(json!({
+                    "deleted": count,
+                    "trashed": true,
+                    "confirmed": true,
+                    "hint": "Memories moved to trash. Use restore_memory to undo, or purge_trash to remove them permanently."
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            }
+        }
+    }
+
+    #[tool(description = "Bulk update memories by filter: set type_hint, source, or importance, and add or remove tags. First call (confirm: false) returns the count. Second call (confirm: true) applies the update to every matching memory in one transaction; previous values are kept in revision history.")]
+    async fn bulk_update_memories(
+        &self,
+        Parameters(params): Parameters<BulkUpdateMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "bulk_update_memories",
+            confirm = params.confirm,
+            type_hint = ?params.type_hint,
+            source = ?params.source,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let importance = match check_importance(params.set_importance, "set_importance") {
+            Ok(value) => value,
+            Err(result) => return Ok(result),
+        };
+
+        let update = BulkUpdate {
+            type_hint: params.set_type_hint,
+            source: params.set_source,
+            importance,
+            add_tags: params.add_tags,
+            remove_tags: params.remove_tags,
+        };
+        if update.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "At least one of 'set_type_hint', 'set_source', 'set_importance', 'add_tags', or 'remove_tags' must be provided"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let created_after = if let Some(ref s) = params.created_after {
+            match parse_datetime(s, "created_after") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let created_before = if let Some(ref s) = params.created_before {
+            match parse_datetime(s, "created_before") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let updated_after = if let Some(ref s) = params.updated_after {
+            match parse_datetime(s, "updated_after") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let updated_before = if let Some(ref s) = params.updated_before {
+            match parse_datetime(s, "updated_before") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let filter = ListFilter {
+            namespace: Some(namespace),
+            type_hint: params.type_hint,
+            source: params.source,
+            created_after,
+            created_before,
+            updated_after,
+            updated_before,
+            ..ListFilter::default()
+        };
+
+        if !params.confirm {
+            return match self.store.count_matching(&filter).await {
+                Ok(count) => Ok(CallToolResult::structured(json!({
+                    "matched": count,
+                    "updated": false,
+                    "hint": format!("Call bulk_update_memories again with confirm: true to update these {} memories", count)
+                }))),
+                Err(e) => Ok(store_error_to_result(e)),
+            };
+        }
+
+        match self.store.update_matching(&filter, &update).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(memories) => {
+                // Tags (and whatever else the template includes) are part of the embedding text
+                let metadata_changed = update.changes_tags()
+                    || (update.type_hint.is_some() && self.embedding_template.uses_field("type_hint"))
+                    || (update.source.is_some() && self.embedding_template.uses_field("source"));
+                for memory in &memories {
+                    self.reprocess_updated_memory(memory, false, metadata_changed);
+                }
+                let ids: Vec<&str> = memories.iter().map(|m| m.id.as_str()).collect();
+                Ok(CallToolResult::structured(json!({
+                    "updated": memories.len(),
+                    "ids": ids,
+                    "confirmed": true,
+                    "hint": "Bulk update complete. Previous values are kept in each memory's revision history."
+                })))
+            }
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "List memories with optional filters and cursor-based pagination. Filter by embedding_status or extraction_status (e.g. \"failed\") to find memories stuck in the pipelines.")]
+    async fn list_memories(
+        &self,
+        Parameters(params): Parameters<ListMemoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "list_memories",
+            type_hint = ?params.type_hint,
+            source = ?params.source,
+            limit = ?params.limit,
+            has_cursor = params.cursor.is_some(),
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        // Parse optional datetime strings
+        let created_after = if let Some(ref s) = params.created_after {
+            match parse_datetime(s, "created_after") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let created_before = if let Some(ref s) = params.created_before {
+            match parse_datetime(s, "created_before") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let updated_after = if let Some(ref s) = params.updated_after {
+            match parse_datetime(s, "updated_after") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let updated_before = if let Some(ref s) = params.updated_before {
+            match parse_datetime(s, "updated_before") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let min_importance = match check_importance(params.min_importance, "min_importance") {
+            Ok(value) => value,
+            Err(result) => return Ok(result),
+        };
+        let embedding_status = match check_status(params.embedding_status, crate::store::EMBEDDING_STATUSES, "embedding_status") {
+            Ok(value) => value,
+            Err(result) => return Ok(result),
+        };
+        let extraction_status = match check_status(params.extraction_status, crate::store::EXTRACTION_STATUSES, "extraction_status") {
+            Ok(value) => value,
+            Err(result) => return Ok(result),
+        };
+        let AccessFilters { min_access_count, accessed_after, never_accessed } =
+            match check_access_filters(params.min_access_count, params.accessed_after.as_deref(), params.never_accessed) {
+                Ok(filters) => filters,
+                Err(result) => return Ok(result),
+            };
+
+        let filter = ListFilter {
+            namespace: Some(namespace),
+            type_hint: params.type_hint,
+            source: params.source,
+            tags: None,
+            created_after,
+            created_before,
+            updated_after,
+            updated_before,
+            limit: limit as i64,
+            cursor: params.cursor,
+            trashed: params.trashed.unwrap_or(false),
+            archived: params.archived.unwrap_or(false),
+            min_importance,
+            session_id: params.session_id,
+            payload_path: params.payload_path,
+            origin_ref: params.origin_ref.filter(|r| !r.is_empty()),
+            min_access_count,
+            accessed_after,
+            never_accessed,
+            exclude_tags: params.exclude_tags.unwrap_or_default(),
+            exclude_sources: params.exclude_sources.unwrap_or_default(),
+            exclude_type_hints: params.exclude_type_hints.unwrap_or_default(),
+            embedding_status,
+            extraction_status,
+        };
+
+        match self.store.list(filter).await {
+            Ok(result) => {
+                let memories: Vec<serde_json::Value> = result
+                    .memories
+                    .iter()
+                    .map(|m| {
+                        json!({
+                            "id": m.id,
+                            "content": m.content,
+                            "type_hint": m.type_hint,
+                            "source": m.source,
+                            "tags": m.tags,
+                            "created_at": m.created_at.to_rfc3339(),
+                            "updated_at": m.updated_at.to_rfc3339(),
+                            "access_count": m.access_count,
+                            "embedding_status": m.embedding_status,
+                            "extraction_status": m.extraction_status,
+                            "namespace": m.namespace,
+                            "deleted_at": m.deleted_at.map(|dt| dt.to_rfc3339()),
+                            "expires_at": m.expires_at.map(|dt| dt.to_rfc3339()),
+                            "archived_at": m.archived_at.map(|dt| dt.to_rfc3339()),
+                            "importance": m.importance,
+                            "session_id": m.session_id,
+                            "payload": m.payload,
+                            "origin_refs": m.origin_refs,
+                            "pinned": m.pinned,
+                        })
+                    })
+                    .collect();
+
+                let count = memories.len();
+                let has_more = result.next_cursor.is_some();
+
+                Ok(CallToolResult::structured(json!({
+                    "memories": memories,
+                    "count": count,
+                    "next_cursor": result.next_cursor,
+                    "has_more": has_more,
+                    "hint": "Use next_cursor value in cursor parameter to get next page"
+                })))
+            }
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+
+    #[tool(description = "Search memories using both keyword matching and semantic similarity for best results. Use this when you want to find memories related to a concept, topic, or question. Results are ranked by salience score combining recency, access frequency, semantic relevance, and reinforcement. Pass explain=true to see how each result was ranked, min_relevance to drop weak matches instead of padding to limit, snippet_chars to get excerpts of long memories instead of their full content, profile (e.g. \"recent\" or \"instructional\") to rank for the task at hand, and exclude_tags/exclude_sources/exclude_type_hints to leave out noise such as scratch notes. Pinned memories (see pin_memory) matching the filters always come first, on top of the ranked results. For browsing all memories or filtering by type/source, use list_memories instead.")]
+    async fn search_memory(
+        &self,
+        Parameters(params): Parameters<SearchMemoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "search_memory",
+            query = %params.query,
+            limit = ?params.limit,
+            has_cursor = params.cursor.is_some(),
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+        let _timer = metrics::global().search_duration.start_timer();
+        let started = Instant::now();
+
+        // 1. Validate query
+        if params.query.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'query' is required and cannot be empty",
+                "field": "query"
+            })));
+        }
+
+        // 2. Validate limit and fusion, and resolve namespace
+        let limit = params.limit.unwrap_or(10).clamp(1, 100);
+        let fusion_name = params.fusion.clone().unwrap_or_else(|| self.default_fusion.clone());
+        if !crate::search::FUSION_STRATEGIES.contains(&fusion_name.as_str()) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!(
+                    "Field 'fusion' must be one of: {}",
+                    crate::search::FUSION_STRATEGIES.join(", ")
+                ),
+                "field": "fusion"
+            })));
+        }
+        let min_relevance = params.min_relevance.unwrap_or(self.default_min_relevance);
+        if !(0.0..=1.0).contains(&min_relevance) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'min_relevance' must be between 0.0 and 1.0",
+                "field": "min_relevance"
+            })));
+        }
+        if params.snippet_chars.is_some_and(|n| !SNIPPET_CHARS_RANGE.contains(&n)) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!(
+                    "Field 'snippet_chars' must be between {} and {}",
+                    SNIPPET_CHARS_RANGE.start(),
+                    SNIPPET_CHARS_RANGE.end()
+                ),
+                "field": "snippet_chars"
+            })));
+        }
+        let context_turns = params.conversation_context.as_deref().unwrap_or_default();
+        if context_turns.len() > MAX_CONTEXT_TURNS {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Field 'conversation_context' accepts at most {} turns (got {})", MAX_CONTEXT_TURNS, context_turns.len()),
+                "field": "conversation_context"
+            })));
+        }
+        if let Some(index) = context_turns.iter().position(|turn| turn.content.trim().is_empty()) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Turn {} has empty content", index),
+                "field": format!("conversation_context[{}].content", index)
+            })));
+        }
+        let profile_salience = match params.profile.as_deref() {
+            Some(name) => {
+                let salience = &self.live.load().salience;
+                match salience.with_profile(name) {
+                    Some(profile) => Some(profile),
+                    None => {
+                        return Ok(CallToolResult::structured_error(json!({
+                            "isError": true,
+                            "code": codes::VALIDATION,
+                            "error": format!(
+                                "Unknown salience profile '{}'. Available: {}",
+                                name,
+                                salience.profile_names().join(", ")
+                            ),
+                            "field": "profile"
+                        })));
+                    }
+                }
+            }
+            None => None,
+        };
+        let namespace = match self.resolve_namespace(params.namespace.clone()) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        // 2b. Serve repeated searches from the cache (key covers every result-affecting param,
+        //     including the config generation so a reload never serves stale rankings)
+        let cache_key = self.search_cache.as_ref().map(|_| {
+            json!({
+                "query": normalize_query(&params.query),
+                "limit": limit,
+                "namespace": namespace,
+                "created_after": params.created_after,
+                "created_before": params.created_before,
+                "tags": sorted_list(params.tags.as_ref()),
+                "type_hint": params.type_hint,
+                "source": params.source,
+                "exclude_tags": sorted_list(params.exclude_tags.as_ref()),
+                "exclude_sources": sorted_list(params.exclude_sources.as_ref()),
+                "exclude_type_hints": sorted_list(params.exclude_type_hints.as_ref()),
+                "payload_path": params.payload_path,
+                "origin_ref": params.origin_ref,
+                "min_access_count": params.min_access_count,
+                "accessed_after": params.accessed_after,
+                "never_accessed": params.never_accessed,
+                "cursor": params.cursor,
+                "bm25_weight": params.bm25_weight,
+                "vector_weight": params.vector_weight,
+                "symbolic_weight": params.symbolic_weight,
+                "candidate_pool": params.candidate_pool,
+                "fusion": fusion_name,
+                "explain": params.explain.unwrap_or(false),
+                "min_relevance": min_relevance,
+                "snippet_chars": params.snippet_chars,
+                "profile": params.profile,
+                "conversation_context": params.conversation_context,
+                "config_generation": self.live.generation(),
+            })
+            .to_string()
+        });
+        if let (Some(cache), Some(key)) = (&self.search_cache, &cache_key) {
+            if let Some(mut cached) = cache.get(key) {
+                tracing::debug!("Search served from cache");
+                cached["cached"] = json!(true);
+                return Ok(CallToolResult::structured(cached));
+            }
+        }
+
+        // 3. Get concrete PostgresMemoryStore reference (required for hybrid search); other
+        //    backends get plain vector search
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => return Ok(self.search_vector_only(&params, &namespace, limit, min_relevance).await),
+        };
+
+        // 4. Query Intelligence: expansion (if enabled)
+        let config = self.live.load();
+        let qi_start = Instant::now();
+        let qi_budget = Duration::from_millis(config.query_intelligence.latency_budget_ms);
+
+        // A provider disabled after repeated failures is skipped like an unconfigured one
+        let expansion_provider = self.qi_expansion_provider.as_ref().filter(|p| p.is_available());
+        let (search_queries, qi_time_range, query_entities, resolved_query) = if let Some(provider) = expansion_provider {
+            let expansion_budget = qi_budget * 6 / 10; // 60% for expansion
+            let entities_enabled = config.query_intelligence.entity_extraction_enabled;
+            let extract_entities = |query: String, budget: Duration| async move {
+                if !entities_enabled {
+                    return Vec::new();
+                }
+                match tokio::time::timeout(budget, provider.extract_query_entities(&query)).await {
+                    Ok(Ok(entities)) => crate::query_intelligence::normalize_query_entities(entities, &query),
+                    Ok(Err(e)) => {
+                        tracing::warn!(error = %e, "Query entity extraction failed, searching without entities");
+                        Vec::new()
+                    }
+                    Err(_) => {
+                        tracing::warn!("Query entity extraction timed out, searching without entities");
+                        Vec::new()
+                    }
+                }
+            };
+            let (expansion, entities) = if context_turns.is_empty() {
+                // Entity extraction runs alongside expansion and shares its budget
+                tokio::join!(
+                    tokio::time::timeout(expansion_budget, provider.expand(&params.query)),
+                    extract_entities(params.query.clone(), expansion_budget),
+                )
+            } else {
+                // A follow-up query is rewritten first; its entities come from the rewrite
+                let context: Vec<crate::query_intelligence::ContextTurn> = context_turns
+                    .iter()
+                    .map(|turn| crate::query_intelligence::ContextTurn {
+                        role: turn.role.clone(),
+                        content: turn.content.clone(),
+                    })
+                    .collect();
+                let expansion =
+                    tokio::time::timeout(expansion_budget, provider.expand_in_context(&params.query, &context)).await;
+                let standalone = match &expansion {
+                    Ok(Ok(expanded)) => expanded.standalone_query.clone(),
+                    _ => None,
+                };
+                let remaining = expansion_budget.saturating_sub(qi_start.elapsed());
+                let entities = extract_entities(standalone.unwrap_or_else(|| params.query.clone()), remaining).await;
+                (expansion, entities)
+            };
+            if !entities.is_empty() {
+                tracing::info!(entities = entities.len(), "Extracted query entities");
+            }
+            let (queries, time_range, resolved) = match expansion {
+                Ok(Ok(expanded)) => {
+                    tracing::info!(
+                        variants = expanded.variants.len(),
+                        has_time_range = expanded.time_range.is_some(),
+                        resolved = expanded.standalone_query.is_some(),
+                        "Query expanded"
+                    );
+                    // Search the original (or resolved) query plus every distinct variant
+                    // (variants may omit it)
+                    let resolved = expanded.standalone_query.filter(|q| q != &params.query);
+                    let mut queries = vec![resolved.clone().unwrap_or_else(|| params.query.clone())];
+                    for variant in expanded.variants {
+                        let variant = variant.trim();
+                        if !variant.is_empty() && !queries.iter().any(|q| q.eq_ignore_ascii_case(variant)) {
+                            queries.push(variant.to_string());
+                        }
+                    }
+                    (queries, expanded.time_range, resolved)
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!(error = %e, "Query expansion failed, using original query");
+                    (vec![params.query.clone()], None, None)
+                }
+                Err(_) => {
+                    metrics::global().qi_expansion_timeouts.inc();
+                    tracing::warn!(elapsed_ms = ?qi_start.elapsed().as_millis(), "Query expansion timed out, using original query");
+                    (vec![params.query.clone()], None, None)
+                }
+            };
+            (queries, time_range, entities, resolved)
+        } else {
+            // No LLM expansion — try deterministic temporal fallback
+            let week_start = temporal::parse_week_start(&config.query_intelligence.week_start);
+            let time_range = temporal::parse_temporal_hint_with_week_start(&params.query, Utc::now(), week_start);
+            (vec![params.query.clone()], time_range, Vec::new(), None)
+        };
+        // Re-ranking and snippets use the query as resolved from the conversation
+        let retrieval_query = resolved_query.as_deref().unwrap_or(&params.query);
+
+        // 5. Query embeddings are computed per variant in step 8 (graceful degradation
+        //    to BM25-only when no provider is configured or embedding fails)
+
+        // 6. Parse optional datetime params
+        let created_after = if let Some(ref s) = params.created_after {
+            match parse_datetime(s, "created_after") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let created_before = if let Some(ref s) = params.created_before {
+            match parse_datetime(s, "created_before") {
+                Ok(dt) => Some(dt),
+                Err(result) => return Ok(result),
+            }
+        } else {
+            None
+        };
+
+        let AccessFilters { min_access_count, accessed_after, never_accessed } =
+            match check_access_filters(params.min_access_count, params.accessed_after.as_deref(), params.never_accessed) {
+                Ok(filters) => filters,
+                Err(result) => return Ok(result),
+            };
+
+        // 7. Convert weight params to per-leg k values for RRF fusion.
+        //    Formula: k = base_k / weight (lower k = more top-result influence).
+        //    weight=0.0 → None (skip leg entirely).
+        //    weight=None → default k (1.0 = no change to base_k).
+        const BM25_BASE_K: f64 = 60.0;
+        const VECTOR_BASE_K: f64 = 60.0;
+        const SYMBOLIC_BASE_K: f64 = 40.0;
+
+        let bm25_k = match params.bm25_weight {
+            Some(w) if w == 0.0 => None,          // disabled
+            Some(w) => Some(BM25_BASE_K / w),     // weight=2.0 → k=30.0 (stronger influence)
+            None => Some(BM25_BASE_K),             // default
+        };
+        let vector_k = match params.vector_weight {
+            Some(w) if w == 0.0 => None,
+            Some(w) => Some(VECTOR_BASE_K / w),
+            None => Some(VECTOR_BASE_K),
+        };
+        let symbolic_k = match params.symbolic_weight {
+            Some(w) if w == 0.0 => None,
+            Some(w) => Some(SYMBOLIC_BASE_K / w),
+            None => Some(SYMBOLIC_BASE_K),
+        };
+
+        // Validate that at least one search path is enabled
+        if bm25_k.is_none() && vector_k.is_none() && symbolic_k.is_none() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "At least one search path must be enabled (bm25_weight, vector_weight, or symbolic_weight must be non-zero)",
+            })));
+        }
+
+        // Weighted fusion uses the weights directly instead of folding them into k
+        let fusion = if fusion_name == "weighted" {
+            crate::search::Fusion::Weighted {
+                bm25: params.bm25_weight.unwrap_or(1.0),
+                vector: params.vector_weight.unwrap_or(1.0),
+                symbolic: params.symbolic_weight.unwrap_or(1.0),
+            }
+        } else {
+            crate::search::Fusion::Rrf
+        };
+
+        // 8. Call hybrid_search — BM25 + vector + symbolic with three-way fusion —
+        // once per query variant, concurrently (bounded by max_parallel_variants), then
+        // fuse the per-variant lists with a second RRF pass. Extracted entities join the
+        // original query's symbolic leg only, so they count once in the variant fusion.
+        // Note: cursor-based pagination not applied at this level; salience re-ranking
+        // must happen on the full result set before we can paginate meaningfully.
+        let variant_count = search_queries.len();
+        let logged_variants: Vec<String> = if self.search_log {
+            search_queries.iter().filter(|q| *q != &params.query).cloned().collect()
+        } else {
+            Vec::new()
+        };
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(config.query_intelligence.max_parallel_variants.max(1)));
+        let candidate_pool = params.candidate_pool.map(|n| n.clamp(1, 1000) as i64);
+        // A search whose filters pin an embedding route embeds with that route's model and
+        // only matches vectors of it
+        let (embedding_provider, model) = match self.embedding_router {
+            Some(ref router) if router.is_routed() => {
+                let provider = router.for_search(&namespace, params.type_hint.as_deref(), params.source.as_deref());
+                if provider.model_name() == router.default_provider().model_name() {
+                    (self.embedding_provider.clone(), None)
+                } else {
+                    (Some(provider.clone()), Some(provider.model_name().to_string()))
+                }
+            }
+            _ => (self.embedding_provider.clone(), None),
+        };
+        // Every filter narrows all three legs before fusion
+        let filter = crate::store::SearchFilter {
+            limit: limit as i64,
+            created_after,
+            created_before,
+            tags: params.tags.clone(),
+            namespace: Some(namespace.clone()),
+            type_hint: params.type_hint.clone(),
+            source: params.source.clone(),
+            payload_path: params.payload_path.clone(),
+            origin_ref: params.origin_ref.clone().filter(|r| !r.is_empty()),
+            min_access_count,
+            accessed_after,
+            never_accessed,
+            exclude_tags: params.exclude_tags.clone().unwrap_or_default(),
+            exclude_sources: params.exclude_sources.clone().unwrap_or_default(),
+            exclude_type_hints: params.exclude_type_hints.clone().unwrap_or_default(),
+            model,
+            ..Default::default()
+        };
+        let mut variant_tasks = tokio::task::JoinSet::new();
+        for (index, query) in search_queries.into_iter().enumerate() {
+            let semaphore = semaphore.clone();
+            let pg_store = pg_store.clone();
+            let embedding_provider = embedding_provider.clone();
+            let filter = filter.clone();
+            let symbolic_terms = if index == 0 { query_entities.clone() } else { Vec::new() };
+            variant_tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                let query_embedding: Option<pgvector::Vector> = match embedding_provider {
+                    // A model still loading in the background would stall the search
+                    Some(provider) if !provider.is_ready() => {
+                        tracing::info!("Embedding model still loading, searching BM25-only");
+                        None
+                    }
+                    Some(provider) => match provider.embed(&query).await {
+                        Ok(vec) => Some(pgvector::Vector::from(vec)),
+                        Err(e) => {
+                            tracing::warn!("Failed to embed search query, falling back to BM25-only: {}", e);
+                            None
+                        }
+                    },
+                    None => None,
+                };
+                let hits = pg_store.hybrid_search(
+                    &query,
+                    query_embedding.as_ref(),
+                    &symbolic_terms,
+                    &filter,
+                    bm25_k,
+                    vector_k,
+                    symbolic_k,
+                    candidate_pool,
+                    fusion,
+                ).await;
+                (index, hits)
+            });
+        }
+
+        // Keep variant order stable (original query first) regardless of completion order
+        let mut variant_results: Vec<Option<Vec<crate::search::HybridRawHit>>> = vec![None; variant_count];
+        while let Some(joined) = variant_tasks.join_next().await {
+            match joined {
+                Ok((index, Ok(hits))) => variant_results[index] = Some(hits),
+                Ok((_, Err(e))) => return Ok(store_error_to_result(e)),
+                Err(e) => return Ok(store_error_to_result(MemcpError::Internal(format!("Search task failed: {}", e)))),
+            }
+        }
+        if variant_count > 1 {
+            tracing::info!(variants = variant_count, "Fusing results across query variants");
+        }
+        const VARIANT_RRF_K: f64 = 60.0;
+        let raw_hits = crate::search::fuse_variant_hits(
+            variant_results.into_iter().flatten().collect(),
+            VARIANT_RRF_K,
+            limit as usize,
+        );
+
+        // 9. Fetch salience data for all result IDs
+        let ids: Vec<String> = raw_hits.iter().map(|h| h.memory.id.clone()).collect();
+        let leg_hits = crate::search_log::LegHitCounts::from_hits(&raw_hits);
+        let salience_data = match pg_store.get_salience_data(&ids).await {
+            Ok(data) => data,
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+
+        // Link degrees only matter when the link weight is enabled — skip the query otherwise
+        let link_degrees = if config.salience.w_links > 0.0 {
+            match pg_store.get_link_degrees(&ids).await {
+                Ok(degrees) => degrees,
+                Err(e) => return Ok(store_error_to_result(e)),
+            }
+        } else {
+            HashMap::new()
+        };
+
+        // 9b. Explain mode: record each hit's leg details before salience re-ranking
+        let explain = params.explain.unwrap_or(false);
+        let mut explanations: HashMap<String, serde_json::Value> = HashMap::new();
+        if explain {
+            for (i, hit) in raw_hits.iter().enumerate() {
+                explanations.insert(hit.memory.id.clone(), json!({
+                    "legs": hit.legs,
+                    "fused_rank": i + 1,
+                    "fused_score": hit.rrf_score,
+                    "temporal_boost": 1.0,
+                    "rerank": null,
+                }));
+            }
+        }
+
+        // 9c. Chunked memories report the chunks that matched
+        let matched_chunks: HashMap<String, Vec<crate::search::ChunkMatch>> = raw_hits
+            .iter()
+            .filter(|hit| !hit.matched_chunks.is_empty())
+            .map(|hit| (hit.memory.id.clone(), hit.matched_chunks.clone()))
+            .collect();
+
+        // 9d. Each hit's cosine similarity to the query, for min_relevance (vector leg only)
+        let similarities: HashMap<String, f64> = raw_hits
+            .iter()
+            .filter_map(|hit| hit.legs.vector.as_ref().map(|leg| (hit.memory.id.clone(), leg.score)))
+            .collect();
+
+        // 9e. Hits the keyword leg matched get snippets centered on the match
+        let keyword_matched: HashSet<String> = raw_hits
+            .iter()
+            .filter(|hit| hit.legs.bm25.is_some())
+            .map(|hit| hit.memory.id.clone())
+            .collect();
+
+        // 10. Build ScoredHit vec for salience re-ranking
+        let mut scored_hits: Vec<ScoredHit> = raw_hits
+            .into_iter()
+            .map(|hit| ScoredHit {
+                memory: hit.memory,
+                rrf_score: hit.rrf_score,
+                salience_score: 0.0, // populated by rank()
+                match_source: hit.match_source,
+                breakdown: None,     // populated by rank() when debug_scoring=true
+            })
+            .collect();
+
+        // 11. Build SalienceInput for each hit (parallel order to scored_hits)
+        let salience_inputs: Vec<SalienceInput> = scored_hits
+            .iter()
+            .map(|hit| {
+                let row = salience_data
+                    .get(&hit.memory.id)
+                    .cloned()
+                    .unwrap_or_default();
+                let days_since_reinforced = row.last_reinforced_at
+                    .map(|dt| {
+                        let duration = Utc::now().signed_duration_since(dt);
+                        (duration.num_seconds() as f64 / 86_400.0).max(0.0)
+                    })
+                    .unwrap_or(365.0); // 1 year default for never-reinforced memories
+                SalienceInput {
+                    stability: row.stability,
+                    days_since_reinforced,
+                    link_degree: link_degrees.get(&hit.memory.id).copied().unwrap_or(0),
+                }
+            })
+            .collect();
+
+        // 12. Apply salience re-ranking
+        let scorer = SalienceScorer::new(profile_salience.as_ref().unwrap_or(&config.salience)).with_breakdown(explain);
+        scorer.rank(&mut scored_hits, &salience_inputs);
+
+        // 12.5 Apply temporal soft boost if time range extracted
+        if let Some(ref time_range) = qi_time_range {
+            for hit in &mut scored_hits {
+                let created = hit.memory.created_at;
+                let in_range = match (time_range.after, time_range.before) {
+                    (Some(after), Some(before)) => created >= after && created <= before,
+                    (Some(after), None) => created >= after,
+                    (None, Some(before)) => created <= before,
+                    (None, None) => false,
+                };
+                if in_range {
+                    hit.salience_score *= 2.0; // 2x boost for in-range memories (soft boost, not filter)
+                    if let Some(explanation) = explanations.get_mut(&hit.memory.id) {
+                        explanation["temporal_boost"] = json!(2.0);
+                    }
+                }
+            }
+            // Re-sort by boosted salience score
+            scored_hits.sort_by(|a, b| b.salience_score.partial_cmp(&a.salience_score).unwrap_or(std::cmp::Ordering::Equal));
+        }
+
+        // 12.75 LLM re-ranking (if enabled and budget remaining), with an optional lexical
+        //       fallback when the LLM misses its budget or fails
+        if let Some(ref provider) = self.qi_reranking_provider {
+            // Take the top rerank_top_n hits for re-ranking; the rest keep salience order
+            let top_n = scored_hits.len().min(config.query_intelligence.rerank_top_n);
+            let candidates: Vec<RankedCandidate> = scored_hits[..top_n]
+                .iter()
+                .enumerate()
+                .map(|(i, hit)| {
+                    let content = if hit.memory.content.len() > config.query_intelligence.rerank_content_chars {
+                        hit.memory.content[..config.query_intelligence.rerank_content_chars].to_string()
+                    } else {
+                        hit.memory.content.clone()
+                    };
+                    RankedCandidate {
+                        id: hit.memory.id.clone(),
+                        content,
+                        current_rank: i + 1,
+                    }
+                })
+                .collect();
+
+            let mut reranked: Option<(Vec<RankedResult>, &str)> = None;
+            let remaining = qi_budget.saturating_sub(qi_start.elapsed());
+            if !provider.is_available() {
+                tracing::debug!("Skipping LLM re-ranking — provider disabled after repeated failures");
+            } else if remaining > Duration::from_millis(100) { // Only attempt if >100ms remains
+                match tokio::time::timeout(remaining, provider.rerank(retrieval_query, &candidates)).await {
+                    Ok(Ok(ranked)) => {
+                        tracing::info!(ranked_count = ranked.len(), "LLM re-ranking applied");
+                        reranked = Some((ranked, "llm"));
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!(error = %e, "LLM re-ranking failed");
+                    }
+                    Err(_) => {
+                        metrics::global().qi_reranking_timeouts.inc();
+                        tracing::warn!(elapsed_ms = ?qi_start.elapsed().as_millis(), "LLM re-ranking timed out");
+                    }
+                }
+            } else {
+                tracing::debug!(remaining_ms = ?remaining.as_millis(), "Skipping LLM re-ranking — insufficient budget remaining");
+            }
+            if reranked.is_none() && config.query_intelligence.fallback_reranker == "lexical" {
+                tracing::debug!("Applying lexical fallback re-ranking");
+                reranked = Some((lexical_rerank(retrieval_query, &candidates), "lexical"));
+            }
+
+            if let Some((ranked, reranker)) = reranked {
+                let blend = config.query_intelligence.rerank_blend;
+                let before = blend_rerank(&mut scored_hits, top_n, &ranked, blend);
+                if config.query_intelligence.rerank_calibration {
+                    let before_ids: Vec<&str> = before.iter().map(|(id, _)| id.as_str()).collect();
+                    let after_ids: Vec<&str> = scored_hits[..top_n].iter().map(|h| h.memory.id.as_str()).collect();
+                    let moved = rerank_moves(&before_ids, &after_ids);
+                    tracing::info!(
+                        query = %retrieval_query,
+                        reranker,
+                        blend,
+                        moved,
+                        before = ?before_ids,
+                        after = ?after_ids,
+                        "Re-ranking calibration"
+                    );
+                }
+                if explain {
+                    for (i, hit) in scored_hits[..top_n].iter().enumerate() {
+                        let Some(old) = before.iter().position(|(id, _)| *id == hit.memory.id) else { continue };
+                        if let Some(explanation) = explanations.get_mut(&hit.memory.id) {
+                            explanation["rerank"] = json!({
+                                "reranker": reranker,
+                                "rank_before": old + 1,
+                                "rank_after": i + 1,
+                                "score_delta": hit.salience_score - before[old].1,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // 12.9 Drop hits below min_relevance rather than padding to `limit`. Relevance is the
+        //      vector leg's similarity (a hit it did not return counts as 0); without any
+        //      similarity — vector leg disabled or the query not embedded — nothing is dropped.
+        let relevance_applied = min_relevance > 0.0 && !similarities.is_empty();
+        let before_filter = scored_hits.len();
+        if relevance_applied {
+            scored_hits.retain(|hit| similarities.get(&hit.memory.id).copied().unwrap_or(0.0) >= min_relevance);
+        }
+        let filtered_out = before_filter - scored_hits.len();
+
+        // 12.92 Pinned memories matching the hard filters lead the results regardless of
+        //       salience and min_relevance, on top of the `limit` ranked hits
+        let pinned_filter = crate::store::SearchFilter { limit: MAX_PINNED_RESULTS, ..filter.clone() };
+        let scored_hits = match self.store.pinned_memories(&pinned_filter).await {
+            Ok(pinned) => pin_hits(scored_hits, pinned),
+            Err(e) => return Ok(store_error_to_result(e)),
+        };
+
+        // 12.95 Keyword headlines for long hits the BM25 leg matched; the rest (and any
+        //       failure here) fall back to prefix snippets
+        let headlines = match params.snippet_chars {
+            Some(max_chars) => {
+                let long_hits: Vec<(String, String)> = scored_hits
+                    .iter()
+                    .filter(|hit| keyword_matched.contains(&hit.memory.id))
+                    .filter(|hit| hit.memory.content.chars().count() > max_chars as usize)
+                    .map(|hit| (hit.memory.id.clone(), hit.memory.content.clone()))
+                    .collect();
+                // ~6 characters per word, so the headline rarely needs cutting
+                match pg_store.keyword_headlines(retrieval_query, &long_hits, max_chars as usize / 6).await {
+                    Ok(headlines) => headlines,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Keyword headlines failed, using prefix snippets");
+                        HashMap::new()
+                    }
+                }
+            }
+            None => HashMap::new(),
+        };
+
+        // 13. Format results
+        let count = scored_hits.len();
+        let results: Vec<serde_json::Value> = scored_hits.iter().map(|hit| {
+            let mut obj = json!({
+                "id": hit.memory.id,
+                "content": hit.memory.content,
+                "type_hint": hit.memory.type_hint,
+                "source": hit.memory.source,
+                "tags": hit.memory.tags,
+                "created_at": hit.memory.created_at.to_rfc3339(),
+                "updated_at": hit.memory.updated_at.to_rfc3339(),
+                "access_count": hit.memory.access_count,
+                "importance": hit.memory.importance,
+                "relevance_score": (hit.salience_score * 1000.0).round() / 1000.0,
+                "match_source": hit.match_source,
+                "rrf_score": (hit.rrf_score * 10000.0).round() / 10000.0,
+                "payload": hit.memory.payload,
+                "origin_refs": hit.memory.origin_refs,
+                "pinned": hit.memory.pinned,
+            });
+            // Add score breakdown when debug_scoring is enabled
+            if let Some(ref bd) = hit.breakdown {
+                obj["score_breakdown"] = json!({
+                    "recency": (bd.recency * 1000.0).round() / 1000.0,
+                    "access": (bd.access * 1000.0).round() / 1000.0,
+                    "semantic": (bd.semantic * 1000.0).round() / 1000.0,
+                    "reinforcement": (bd.reinforcement * 1000.0).round() / 1000.0,
+                    "links": (bd.links * 1000.0).round() / 1000.0,
+                    "importance": (bd.importance * 1000.0).round() / 1000.0,
+                });
+            }
+            if let Some(chunks) = matched_chunks.get(&hit.memory.id) {
+                obj["matched_chunks"] = json!(chunks);
+            }
+            if let Some(explanation) = explanations.get(&hit.memory.id) {
+                obj["explain"] = explanation.clone();
+            }
+            apply_snippet(&mut obj, &hit.memory.content, headlines.get(&hit.memory.id).map(String::as_str), params.snippet_chars);
+            obj
+        }).collect();
+
+        // 14. Build final response JSON
+        let mut response = json!({
+            "memories": results,
+            "total_results": count,
+            "query": params.query,
+            "namespace": namespace,
+            "fusion": fusion_name,
+            "has_more": false,
+        });
+        if let Some(ref profile) = params.profile {
+            response["profile"] = json!(profile);
+        }
+        if let Some(ref resolved) = resolved_query {
+            response["resolved_query"] = json!(resolved);
+        }
+        if explain {
+            response["explain"] = json!({
+                "rrf_k": { "bm25": bm25_k, "vector": vector_k, "symbolic": symbolic_k },
+                "query_variants": variant_count,
+                "query_entities": query_entities,
+                "time_range": qi_time_range.as_ref().map(|tr| json!({
+                    "after": tr.after.map(|dt| dt.to_rfc3339()),
+                    "before": tr.before.map(|dt| dt.to_rfc3339()),
+                })),
+            });
+        }
+
+        if count == 0 {
+            response["hint"] = json!("No memories matched your query. Try broader search terms or use list_memories to browse all memories.");
+        }
+        report_relevance_filter(&mut response, min_relevance, filtered_out, relevance_applied);
+
+        // 15. Record the search for offline tuning (in the background, never failing the call)
+        if self.search_log {
+            let entry = crate::search_log::SearchLogEntry {
+                query: params.query.clone(),
+                namespace: namespace.clone(),
+                variants: logged_variants,
+                leg_hits,
+                fused_ids: ids,
+                result_ids: scored_hits.iter().map(|hit| hit.memory.id.clone()).collect(),
+                scores: scored_hits.iter().map(|hit| hit.salience_score).collect(),
+                latency_ms: started.elapsed().as_millis().min(i32::MAX as u128) as i32,
+            };
+            let pg_store = pg_store.clone();
+            tokio::spawn(async move {
+                if let Err(e) = pg_store.record_search(&entry).await {
+                    tracing::warn!(error = %e, "Failed to record search log entry");
+                }
+            });
+        }
+
+        if let (Some(cache), Some(key)) = (&self.search_cache, cache_key) {
+            cache.insert(key, response.clone());
+        }
+
+        Ok(CallToolResult::structured(response))
+    }
+
+    #[tool(description = "Find memories most similar to a known memory, using its stored embedding. Use this to explore context around a memory you already have. Returns up to `limit` neighbours with similarity scores; consolidated originals are excluded.")]
+    async fn get_related_memories(
         &self,
-        Parameters(params): Parameters<GetMemoryParams>,
+        Parameters(params): Parameters<GetRelatedMemoriesParams>,
     ) -> Result<CallToolResult, McpError> {
         tracing::info!(
-            tool = "get_memory",
+            tool = "get_related_memories",
             id = %params.id,
+            limit = ?params.limit,
             "Tool called"
         );
 
         if params.id.trim().is_empty() {
             return Ok(CallToolResult::structured_error(json!({
                 "isError": true,
+                "code": codes::VALIDATION,
                 "error": "Field 'id' is required and cannot be empty",
                 "field": "id"
             })));
         }
 
-        match self.store.get(&params.id).await {
-            Ok(memory) => {
-                // Implicit salience bump on direct retrieval (fire-and-forget, not on search results)
-                if let Some(ref pg_store) = self.pg_store {
-                    let store = pg_store.clone();
-                    let id = params.id.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = store.touch_salience(&id).await {
-                            tracing::warn!("Failed to touch salience for {}: {}", id, e);
-                        }
-                    });
-                }
+        let limit = params.limit.unwrap_or(5).clamp(1, 50);
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Related memory lookup requires PostgreSQL backend"
+                })));
+            }
+        };
+
+        if let Some(result) = self.reject_foreign_namespace(&params.id, &namespace).await {
+            return Ok(result);
+        }
+
+        match pg_store.get_related_memories(&params.id, limit as i64).await {
+            Ok(hits) => {
+                let related: Vec<serde_json::Value> = hits
+                    .iter()
+                    .map(|hit| {
+                        json!({
+                            "id": hit.memory.id,
+                            "content": hit.memory.content,
+                            "type_hint": hit.memory.type_hint,
+                            "source": hit.memory.source,
+                            "tags": hit.memory.tags,
+                            "created_at": hit.memory.created_at.to_rfc3339(),
+                            "similarity": (hit.similarity * 1000.0).round() / 1000.0,
+                        })
+                    })
+                    .collect();
+                let count = related.len();
                 Ok(CallToolResult::structured(json!({
-                    "id": memory.id,
-                    "content": memory.content,
-                    "type_hint": memory.type_hint,
-                    "source": memory.source,
-                    "tags": memory.tags,
-                    "created_at": memory.created_at.to_rfc3339(),
-                    "updated_at": memory.updated_at.to_rfc3339(),
-                    "last_accessed_at": memory.last_accessed_at.map(|dt| dt.to_rfc3339()),
-                    "access_count": memory.access_count,
-                    "embedding_status": memory.embedding_status,
-                    "hint": "Use update_memory to modify or delete_memory to remove"
+                    "id": params.id,
+                    "related": related,
+                    "count": count,
+                    "hint": "Call get_related_memories on any result to keep exploring, or get_memory to read one in full"
                 })))
             }
+            Err(MemcpError::Validation { message, field }) => Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": message,
+                "field": field,
+                "hint": "Embeddings are generated in the background — retry shortly"
+            }))),
             Err(e) => Ok(store_error_to_result(e)),
         }
     }
 
-    #[tool(description = "Update an existing memory's content, type hint, source, or tags. At least one field must be provided.")]
-    async fn update_memory(
+    #[tool(description = "Reinforce a memory to boost its salience in future searches. Use when a memory is particularly relevant or important. Reinforcing a faded memory produces a stronger boost than reinforcing a recently accessed one (spaced repetition). Rating: 'good' (default) for standard reinforcement, 'easy' for extra-strong boost.")]
+    async fn reinforce_memory(
         &self,
-        Parameters(params): Parameters<UpdateMemoryParams>,
+        Parameters(params): Parameters<ReinforceMemoryParams>,
     ) -> Result<CallToolResult, McpError> {
         tracing::info!(
-            tool = "update_memory",
+            tool = "reinforce_memory",
             id = %params.id,
-            has_content = params.content.is_some(),
-            has_type_hint = params.type_hint.is_some(),
-            has_source = params.source.is_some(),
-            has_tags = params.tags.is_some(),
+            rating = ?params.rating,
             "Tool called"
         );
 
         if params.id.trim().is_empty() {
             return Ok(CallToolResult::structured_error(json!({
                 "isError": true,
+                "code": codes::VALIDATION,
                 "error": "Field 'id' is required and cannot be empty",
                 "field": "id"
             })));
         }
 
-        if params.content.is_none()
-            && params.type_hint.is_none()
-            && params.source.is_none()
-            && params.tags.is_none()
-        {
-            return Ok(CallToolResult::structured_error(json!({
-                "isError": true,
-                "error": "At least one of 'content', 'type_hint', 'source', or 'tags' must be provided"
-            })));
-        }
+        // Verify memory exists
+        let namespace = match self.store.get(&params.id).await {
+            Err(MemcpError::NotFound { .. }) => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::NOT_FOUND,
+                    "error": format!("Memory not found: {}", params.id),
+                    "hint": "Use list_memories to find available memory IDs"
+                })));
+            }
+            Err(e) => return Ok(store_error_to_result(e)),
+            Ok(memory) => memory.namespace,
+        };
 
-        // Track if content or tags changed — determines if re-embedding is needed
-        let content_changed = params.content.is_some();
-        let tags_changed = params.tags.is_some();
+        // Validate and normalize rating
+        let rating = params.rating.as_deref().unwrap_or("good");
+        let rating = if rating == "easy" { "easy" } else { "good" };
 
-        let input = UpdateMemory {
-            content: params.content,
-            type_hint: params.type_hint,
-            source: params.source,
-            tags: params.tags,
+        // Get concrete pg_store reference
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Reinforcement requires PostgreSQL backend"
+                })));
+            }
         };
 
-        match self.store.update(&params.id, input).await {
-            Ok(memory) => {
-                // Re-embed when content or tags change (tags are part of the embedding text)
-                if content_changed || tags_changed {
-                    if let Some(ref pipeline) = self.pipeline {
-                        let text = crate::embedding::build_embedding_text(&memory.content, &memory.tags);
-                        pipeline.enqueue(EmbeddingJob {
-                            memory_id: memory.id.clone(),
-                            text,
-                            attempt: 0,
-                        });
-                    }
-                }
-                // Re-extract when content changes (extraction is content-only, not tags)
-                if content_changed {
-                    if let Some(ref extraction_pipeline) = self.extraction_pipeline {
-                        // Reset extraction status to pending, then enqueue
-                        if let Some(ref pg_store) = self.pg_store {
-                            let store = pg_store.clone();
-                            let id = memory.id.clone();
-                            tokio::spawn(async move {
-                                if let Err(e) = store.update_extraction_status(&id, "pending").await {
-                                    tracing::warn!("Failed to reset extraction status for {}: {}", id, e);
-                                }
-                            });
-                        }
-                        extraction_pipeline.enqueue(ExtractionJob {
-                            memory_id: memory.id.clone(),
-                            content: memory.content.clone(),
-                            attempt: 0,
-                        });
-                    }
-                }
+        match pg_store.reinforce_salience(&params.id, rating).await.inspect(|_| self.invalidate_search_cache()) {
+            Ok(row) => {
+                self.events.emit(
+                    crate::events::MemoryEvent::new(crate::events::EventKind::Reinforced, &namespace, vec![params.id.clone()])
+                        .with_data(json!({ "rating": rating })),
+                );
                 Ok(CallToolResult::structured(json!({
-                    "id": memory.id,
-                    "content": memory.content,
-                    "type_hint": memory.type_hint,
-                    "source": memory.source,
-                    "tags": memory.tags,
-                    "created_at": memory.created_at.to_rfc3339(),
-                    "updated_at": memory.updated_at.to_rfc3339(),
-                    "access_count": memory.access_count,
-                    "embedding_status": memory.embedding_status,
-                    "hint": "Use get_memory to re-read or delete_memory to remove"
+                    "id": params.id,
+                    "stability": row.stability,
+                    "reinforcement_count": row.reinforcement_count,
+                    "message": format!(
+                        "Memory reinforced. Stability: {:.1} days, reinforcements: {}",
+                        row.stability, row.reinforcement_count
+                    )
                 })))
             }
             Err(e) => Ok(store_error_to_result(e)),
         }
     }
 
-    #[tool(description = "Delete a single memory by ID. This is permanent and cannot be undone.")]
-    async fn delete_memory(
+    #[tool(description = "Reinforce several memories at once (1-100 IDs, one transaction) with the same rating: 'good' (default) or 'easy'. Either every memory is reinforced or none is — missing IDs are reported.")]
+    async fn reinforce_memories(
         &self,
-        Parameters(params): Parameters<DeleteMemoryParams>,
+        Parameters(params): Parameters<ReinforceMemoriesParams>,
     ) -> Result<CallToolResult, McpError> {
         tracing::info!(
-            tool = "delete_memory",
-            id = %params.id,
+            tool = "reinforce_memories",
+            count = params.ids.len(),
+            rating = ?params.rating,
+            namespace = ?params.namespace,
             "Tool called"
         );
 
-        if params.id.trim().is_empty() {
-            return Ok(CallToolResult::structured_error(json!({
-                "isError": true,
-                "error": "Field 'id' is required and cannot be empty",
-                "field": "id"
-            })));
+        if let Some(result) = validate_id_batch(&params.ids) {
+            return Ok(result);
         }
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
 
-        match self.store.delete(&params.id).await {
-            Ok(()) => Ok(CallToolResult::structured(json!({
-                "deleted": true,
-                "id": params.id,
-                "hint": "Memory permanently removed. Use store_memory to create new memories."
-            }))),
-            Err(e) => Ok(store_error_to_result(e)),
-        }
+        let rating = if params.rating.as_deref() == Some("easy") { "easy" } else { "good" };
+        Ok(self.reinforce_batch(params.ids, &namespace, rating).await)
     }
 
-    #[tool(description = "Bulk delete memories by filter. First call (confirm: false) returns the count. Second call (confirm: true) performs deletion.")]
-    async fn bulk_delete_memories(
+    #[tool(description = "Report which search results your answer actually used. When the server has salience.auto_reinforce_on_search enabled, those memories get a light reinforcement so they rank higher next time; otherwise the call is acknowledged without changes. Call it after answering from search_memory results.")]
+    async fn mark_used(
         &self,
-        Parameters(params): Parameters<BulkDeleteMemoriesParams>,
+        Parameters(params): Parameters<MarkUsedParams>,
     ) -> Result<CallToolResult, McpError> {
         tracing::info!(
-            tool = "bulk_delete_memories",
-            confirm = params.confirm,
-            type_hint = ?params.type_hint,
-            source = ?params.source,
+            tool = "mark_used",
+            count = params.ids.len(),
+            namespace = ?params.namespace,
             "Tool called"
         );
 
-        // Parse optional datetime strings
-        let created_after = if let Some(ref s) = params.created_after {
-            match parse_datetime(s, "created_after") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
+        if let Some(result) = validate_id_batch(&params.ids) {
+            return Ok(result);
+        }
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        if !self.live.load().salience.auto_reinforce_on_search {
+            return Ok(CallToolResult::structured(json!({
+                "reinforced": [],
+                "count": 0,
+                "hint": "Auto-reinforcement is disabled on this server (salience.auto_reinforce_on_search = false); nothing was changed"
+            })));
+        }
+        Ok(self.reinforce_batch(params.ids, &namespace, "light").await)
+    }
+
+    #[tool(description = "Check server health: database connectivity, embedding and extraction pipelines (queue depth, pending and failed counts), and reachability of configured LLM endpoints. Each subsystem reports ok, degraded, down, or disabled.")]
+    async fn health_check(
+        &self,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(tool = "health_check", "Tool called");
+
+        // Probe LLM endpoints concurrently while the database checks run
+        let mut probes = tokio::task::JoinSet::new();
+        let client = reqwest::Client::new();
+        for endpoint in self.health_endpoints.iter().cloned() {
+            let client = client.clone();
+            probes.spawn(async move {
+                let result = crate::health::probe_endpoint(&client, &endpoint).await;
+                (endpoint, result)
+            });
+        }
+
+        let (database, counts) = match &self.pg_store {
+            Some(pg) => {
+                let start = Instant::now();
+                match tokio::time::timeout(crate::health::PROBE_TIMEOUT, pg.ping()).await {
+                    Ok(Ok(())) => {
+                        let latency_ms = start.elapsed().as_millis() as u64;
+                        let counts = pg.pipeline_counts().await.ok();
+                        let bm25_backend = if pg.uses_paradedb() { "paradedb" } else { "native" };
+                        (json!({"status": "ok", "latency_ms": latency_ms, "bm25_backend": bm25_backend}), counts)
+                    }
+                    Ok(Err(e)) => (json!({"status": "down", "error": e.to_string()}), None),
+                    Err(_) => (json!({"status": "down", "error": "Database ping timed out"}), None),
+                }
             }
-        } else {
-            None
+            None => (json!({"status": "disabled"}), None),
         };
 
-        let created_before = if let Some(ref s) = params.created_before {
-            match parse_datetime(s, "created_before") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
+        let embedding = match (&self.embedding_provider, &self.pipeline) {
+            (Some(provider), Some(pipeline)) => {
+                let failed = counts.as_ref().map(|c| c.embedding_failed).unwrap_or(0);
+                json!({
+                    "status": if !provider.is_ready() { "loading" } else if failed > 0 { "degraded" } else { "ok" },
+                    "model": provider.model_name(),
+                    "queue_depth": pipeline.queue_depth(),
+                    "pending": counts.as_ref().map(|c| c.embedding_pending),
+                    "failed": counts.as_ref().map(|c| c.embedding_failed),
+                })
             }
-        } else {
-            None
+            _ => json!({"status": "down", "error": "No embedding provider — search is limited to keyword matching"}),
         };
 
-        let updated_after = if let Some(ref s) = params.updated_after {
-            match parse_datetime(s, "updated_after") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
+        let extraction = match &self.extraction_pipeline {
+            Some(pipeline) => {
+                let failed = counts.as_ref().map(|c| c.extraction_failed).unwrap_or(0);
+                json!({
+                    "status": if failed > 0 { "degraded" } else { "ok" },
+                    "queue_depth": pipeline.queue_depth(),
+                    "pending": counts.as_ref().map(|c| c.extraction_pending),
+                    "failed": counts.as_ref().map(|c| c.extraction_failed),
+                })
             }
-        } else {
-            None
+            None => json!({"status": "disabled"}),
         };
 
-        let updated_before = if let Some(ref s) = params.updated_before {
-            match parse_datetime(s, "updated_before") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
+        let mut llm = serde_json::Map::new();
+        while let Some(joined) = probes.join_next().await {
+            let Ok((endpoint, result)) = joined else { continue };
+            let entry = match result {
+                Ok(latency_ms) => json!({
+                    "status": "ok",
+                    "provider": endpoint.provider,
+                    "base_url": endpoint.base_url,
+                    "latency_ms": latency_ms,
+                }),
+                Err(error) => json!({
+                    "status": "down",
+                    "provider": endpoint.provider,
+                    "base_url": endpoint.base_url,
+                    "error": error,
+                }),
+            };
+            llm.insert(endpoint.subsystem, entry);
+        }
+
+        // Providers disabled after repeated failures (see query_intelligence::health)
+        let mut query_intelligence = serde_json::Map::new();
+        for (name, provider) in [("expansion", &self.qi_expansion_provider), ("reranking", &self.qi_reranking_provider)] {
+            let Some(provider) = provider else { continue };
+            let mut entry = json!({
+                "status": if provider.is_available() { "ok" } else { "degraded" },
+                "model": provider.model_name(),
+            });
+            if let Some(health) = provider.health() {
+                entry["health"] = json!(health);
             }
+            query_intelligence.insert(name.to_string(), entry);
+        }
+
+        // Overall: down if the database is down, degraded if anything else is not ok
+        let statuses: Vec<&str> = [&database, &embedding, &extraction]
+            .into_iter()
+            .chain(llm.values())
+            .chain(query_intelligence.values())
+            .filter_map(|s| s["status"].as_str())
+            .collect();
+        let status = if database["status"] == "down" {
+            "down"
+        } else if statuses.iter().any(|s| matches!(*s, "down" | "degraded" | "loading")) {
+            "degraded"
         } else {
-            None
+            "ok"
         };
 
-        let filter = ListFilter {
-            type_hint: params.type_hint,
-            source: params.source,
-            created_after,
-            created_before,
-            updated_after,
-            updated_before,
-            ..ListFilter::default()
+        let response = json!({
+            "status": status,
+            "version": env!("CARGO_PKG_VERSION"),
+            "uptime_seconds": self.uptime_seconds(),
+            "subsystems": {
+                "database": database,
+                "embedding": embedding,
+                "extraction": extraction,
+                "llm": llm,
+                "query_intelligence": query_intelligence,
+            },
+        });
+
+        Ok(CallToolResult::structured(response))
+    }
+
+    #[tool(description = "Discover what kinds of memories exist: distinct type_hints and sources, and the most common tags, each with counts. Use the values as filters for list_memories, search_memory, or bulk_delete_memories.")]
+    async fn get_memory_facets(
+        &self,
+        Parameters(params): Parameters<GetMemoryFacetsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "get_memory_facets",
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let tag_limit = params.tag_limit.unwrap_or(50);
+        if !(1..=200).contains(&tag_limit) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'tag_limit' must be between 1 and 200",
+                "field": "tag_limit"
+            })));
+        }
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
         };
 
-        if !params.confirm {
-            match self.store.count_matching(&filter).await {
-                Ok(count) => Ok(CallToolResult::structured(json!({
-                    "matched": count,
-                    "deleted": false,
-                    "hint": format!("Call bulk_delete_memories again with confirm: true to delete these {} memories", count)
-                }))),
-                Err(e) => Ok(store_error_to_result(e)),
-            }
-        } else {
-            match self.store.delete_matching(&filter).await {
-                Ok(count) => Ok(CallToolResult::structured(json!({
-                    "deleted": count,
-                    "confirmed": true,
-                    "hint": "Bulk deletion complete. Use list_memories to verify."
-                }))),
-                Err(e) => Ok(store_error_to_result(e)),
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory facets require PostgreSQL backend"
+                })));
             }
+        };
+
+        match pg_store.get_memory_facets(Some(&namespace), tag_limit as i64).await {
+            Ok(facets) => Ok(CallToolResult::structured(json!({
+                "namespace": namespace,
+                "total": facets.total,
+                "type_hints": facets.type_hints,
+                "sources": facets.sources,
+                "tags": facets.tags,
+                "hint": if facets.total == 0 {
+                    "No memories in this namespace yet"
+                } else {
+                    "Filter list_memories by type_hint/source, or search_memory by tags"
+                }
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
         }
     }
 
-    #[tool(description = "List memories with optional filters and cursor-based pagination.")]
-    async fn list_memories(
+
+    #[tool(description = "Storage statistics for capacity planning: memory counts (live, archived, trashed, expired, chunks, consolidated) and counts by type_hint and source, average content length, oldest and newest memory, percentiles of FSRS retrievability and stability, and table sizes in bytes.")]
+    async fn memory_stats(
         &self,
-        Parameters(params): Parameters<ListMemoriesParams>,
+        Parameters(params): Parameters<MemoryStatsParams>,
     ) -> Result<CallToolResult, McpError> {
         tracing::info!(
-            tool = "list_memories",
-            type_hint = ?params.type_hint,
-            source = ?params.source,
-            limit = ?params.limit,
-            has_cursor = params.cursor.is_some(),
+            tool = "memory_stats",
+            namespace = ?params.namespace,
             "Tool called"
         );
 
-        let limit = params.limit.unwrap_or(20).clamp(1, 100);
-
-        // Parse optional datetime strings
-        let created_after = if let Some(ref s) = params.created_after {
-            match parse_datetime(s, "created_after") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
-            }
-        } else {
-            None
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
         };
 
-        let created_before = if let Some(ref s) = params.created_before {
-            match parse_datetime(s, "created_before") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory statistics require PostgreSQL backend"
+                })));
             }
-        } else {
-            None
         };
 
-        let updated_after = if let Some(ref s) = params.updated_after {
-            match parse_datetime(s, "updated_after") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
+        match pg_store.memory_stats(Some(&namespace)).await {
+            Ok(stats) => {
+                let mut response = json!(stats);
+                response["namespace"] = json!(namespace);
+                response["hint"] = json!("Counts cover this namespace; storage sizes cover the whole database. `memcp stats` reports all namespaces.");
+                Ok(CallToolResult::structured(response))
             }
-        } else {
-            None
-        };
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
 
-        let updated_before = if let Some(ref s) = params.updated_before {
-            match parse_datetime(s, "updated_before") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
-            }
-        } else {
-            None
+    #[tool(description = "Find every memory mentioning an entity (a person, project, technology, ...) picked out by extraction. Matches the exact entity and, unless exact=true, any entity containing it, newest first with cursor pagination. Use list_entities to see which entities exist.")]
+    async fn get_memories_by_entity(
+        &self,
+        Parameters(params): Parameters<GetMemoriesByEntityParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "get_memories_by_entity",
+            entity = %params.entity,
+            exact = ?params.exact,
+            has_cursor = params.cursor.is_some(),
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        let entity = params.entity.trim();
+        if entity.is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'entity' is required and cannot be empty",
+                "field": "entity"
+            })));
+        }
+        let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
         };
 
-        let filter = ListFilter {
-            type_hint: params.type_hint,
-            source: params.source,
-            created_after,
-            created_before,
-            updated_after,
-            updated_before,
-            limit: limit as i64,
-            cursor: params.cursor,
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Entity lookup requires PostgreSQL backend"
+                })));
+            }
         };
 
-        match self.store.list(filter).await {
+        let exact = params.exact.unwrap_or(false);
+        match pg_store
+            .get_memories_by_entity(&namespace, entity, exact, limit as i64, params.cursor.as_deref())
+            .await
+        {
             Ok(result) => {
                 let memories: Vec<serde_json::Value> = result
                     .memories
@@ -633,448 +6475,738 @@ impl MemoryService {
                             "type_hint": m.type_hint,
                             "source": m.source,
                             "tags": m.tags,
+                            "extracted_entities": m.extracted_entities,
                             "created_at": m.created_at.to_rfc3339(),
                             "updated_at": m.updated_at.to_rfc3339(),
-                            "access_count": m.access_count,
-                            "embedding_status": m.embedding_status,
+                            "importance": m.importance,
+                            "payload": m.payload,
+                            "origin_refs": m.origin_refs,
+                            "pinned": m.pinned,
                         })
                     })
                     .collect();
 
                 let count = memories.len();
-                let has_more = result.next_cursor.is_some();
-
-                Ok(CallToolResult::structured(json!({
+                let mut response = json!({
+                    "entity": entity,
+                    "exact": exact,
+                    "namespace": namespace,
                     "memories": memories,
                     "count": count,
                     "next_cursor": result.next_cursor,
-                    "has_more": has_more,
-                    "hint": "Use next_cursor value in cursor parameter to get next page"
-                })))
+                    "has_more": result.next_cursor.is_some(),
+                });
+                if count == 0 && params.cursor.is_none() {
+                    response["hint"] = json!("No memory mentions this entity. Use list_entities to see extracted entities, or search_memory for a broader match.");
+                }
+                Ok(CallToolResult::structured(response))
             }
             Err(e) => Ok(store_error_to_result(e)),
         }
     }
 
-    #[tool(description = "Search memories using both keyword matching and semantic similarity for best results. Use this when you want to find memories related to a concept, topic, or question. Results are ranked by salience score combining recency, access frequency, semantic relevance, and reinforcement. For browsing all memories or filtering by type/source, use list_memories instead.")]
-    async fn search_memory(
+    #[tool(description = "List the entities (people, projects, technologies, ...) extraction has found in memories, most frequent first, with the number of memories mentioning each. Pass an entity to get_memories_by_entity to read those memories.")]
+    async fn list_entities(
         &self,
-        Parameters(params): Parameters<SearchMemoryParams>,
+        Parameters(params): Parameters<ListEntitiesParams>,
     ) -> Result<CallToolResult, McpError> {
         tracing::info!(
-            tool = "search_memory",
-            query = %params.query,
-            limit = ?params.limit,
-            has_cursor = params.cursor.is_some(),
+            tool = "list_entities",
+            contains = ?params.contains,
+            namespace = ?params.namespace,
             "Tool called"
         );
 
-        // 1. Validate query
-        if params.query.trim().is_empty() {
+        let limit = params.limit.unwrap_or(50);
+        if !(1..=500).contains(&limit) {
             return Ok(CallToolResult::structured_error(json!({
                 "isError": true,
-                "error": "Field 'query' is required and cannot be empty",
-                "field": "query"
+                "code": codes::VALIDATION,
+                "error": "Field 'limit' must be between 1 and 500",
+                "field": "limit"
             })));
         }
 
-        // 2. Validate limit
-        let limit = params.limit.unwrap_or(10).clamp(1, 100);
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
 
-        // 3. Get concrete PostgresMemoryStore reference (required for hybrid search)
         let pg_store = match &self.pg_store {
             Some(s) => s,
             None => {
                 return Ok(CallToolResult::structured_error(json!({
                     "isError": true,
-                    "error": "Search requires PostgreSQL backend",
-                    "hint": "Use list_memories to browse memories"
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Entity listing requires PostgreSQL backend"
                 })));
             }
         };
 
-        // 4. Query Intelligence: expansion (if enabled)
-        let qi_start = Instant::now();
-        let qi_budget = Duration::from_millis(self.qi_config.latency_budget_ms);
-
-        let (search_query, qi_time_range) = if let Some(ref provider) = self.qi_expansion_provider {
-            let expansion_budget = qi_budget * 6 / 10; // 60% for expansion
-            match tokio::time::timeout(expansion_budget, provider.expand(&params.query)).await {
-                Ok(Ok(expanded)) => {
-                    tracing::info!(
-                        variants = expanded.variants.len(),
-                        has_time_range = expanded.time_range.is_some(),
-                        "Query expanded"
-                    );
-                    // Use first variant as the search query (best formulation)
-                    let best_query = expanded.variants.into_iter().next().unwrap_or_else(|| params.query.clone());
-                    (best_query, expanded.time_range)
-                }
-                Ok(Err(e)) => {
-                    tracing::warn!(error = %e, "Query expansion failed, using original query");
-                    (params.query.clone(), None)
-                }
-                Err(_) => {
-                    tracing::warn!(elapsed_ms = ?qi_start.elapsed().as_millis(), "Query expansion timed out, using original query");
-                    (params.query.clone(), None)
-                }
-            }
-        } else {
-            // No LLM expansion — try deterministic temporal fallback
-            let time_range = parse_temporal_hint(&params.query, Utc::now());
-            (params.query.clone(), time_range)
-        };
-
-        // 5. Optionally embed the search_query (graceful degradation to BM25-only if no provider)
-        let query_embedding: Option<pgvector::Vector> = if let Some(ref provider) = self.embedding_provider {
-            match provider.embed(&search_query).await {
-                Ok(vec) => Some(pgvector::Vector::from(vec)),
-                Err(e) => {
-                    tracing::warn!("Failed to embed search query, falling back to BM25-only: {}", e);
-                    None
-                }
-            }
-        } else {
-            None
-        };
-
-        // 6. Parse optional datetime params
-        let created_after = if let Some(ref s) = params.created_after {
-            match parse_datetime(s, "created_after") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
-            }
-        } else {
-            None
-        };
-
-        let created_before = if let Some(ref s) = params.created_before {
-            match parse_datetime(s, "created_before") {
-                Ok(dt) => Some(dt),
-                Err(result) => return Ok(result),
-            }
-        } else {
-            None
-        };
-
-        // 7. Convert weight params to per-leg k values for RRF fusion.
-        //    Formula: k = base_k / weight (lower k = more top-result influence).
-        //    weight=0.0 → None (skip leg entirely).
-        //    weight=None → default k (1.0 = no change to base_k).
-        const BM25_BASE_K: f64 = 60.0;
-        const VECTOR_BASE_K: f64 = 60.0;
-        const SYMBOLIC_BASE_K: f64 = 40.0;
+        let contains = params.contains.as_deref().map(str::trim).filter(|c| !c.is_empty());
+        match pg_store.list_entities(&namespace, contains, limit as i64).await {
+            Ok(entities) => Ok(CallToolResult::structured(json!({
+                "namespace": namespace,
+                "count": entities.len(),
+                "hint": if entities.is_empty() {
+                    "No extracted entities yet — extraction may be disabled or still pending"
+                } else {
+                    "Pass an entity to get_memories_by_entity to read the memories mentioning it"
+                },
+                "entities": entities,
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
 
-        let bm25_k = match params.bm25_weight {
-            Some(w) if w == 0.0 => None,          // disabled
-            Some(w) => Some(BM25_BASE_K / w),     // weight=2.0 → k=30.0 (stronger influence)
-            None => Some(BM25_BASE_K),             // default
-        };
-        let vector_k = match params.vector_weight {
-            Some(w) if w == 0.0 => None,
-            Some(w) => Some(VECTOR_BASE_K / w),
-            None => Some(VECTOR_BASE_K),
-        };
-        let symbolic_k = match params.symbolic_weight {
-            Some(w) if w == 0.0 => None,
-            Some(w) => Some(SYMBOLIC_BASE_K / w),
-            None => Some(SYMBOLIC_BASE_K),
-        };
+    #[tool(description = "Define (or redefine) a memory type: a named schema such as \"contact\" with fields name/email/company. Each field has a type (string, number, boolean, date) and may be required. Store memories against it with store_structured_memory.")]
+    async fn define_memory_type(
+        &self,
+        Parameters(params): Parameters<DefineMemoryTypeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "define_memory_type",
+            name = %params.name,
+            field_count = params.fields.len(),
+            namespace = ?params.namespace,
+            "Tool called"
+        );
 
-        // Validate that at least one search path is enabled
-        if bm25_k.is_none() && vector_k.is_none() && symbolic_k.is_none() {
+        let name = params.name.trim();
+        if let Err((field, message)) = crate::memory_types::validate_definition(name, &params.fields) {
             return Ok(CallToolResult::structured_error(json!({
                 "isError": true,
-                "error": "At least one search path must be enabled (bm25_weight, vector_weight, or symbolic_weight must be non-zero)",
+                "code": codes::VALIDATION,
+                "error": message,
+                "field": field
             })));
         }
 
-        // 8. Call hybrid_search — BM25 + vector + symbolic with three-way RRF fusion.
-        // Note: cursor-based pagination not applied at this level; salience re-ranking
-        // must happen on the full result set before we can paginate meaningfully.
-        let tags_slice: Option<Vec<String>> = params.tags.clone();
-        let raw_hits = match pg_store.hybrid_search(
-            &search_query,
-            query_embedding.as_ref(),
-            limit as i64,
-            created_after,
-            created_before,
-            tags_slice.as_deref(),
-            bm25_k,
-            vector_k,
-            symbolic_k,
-        ).await {
-            Ok(hits) => hits,
-            Err(e) => return Ok(store_error_to_result(e)),
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
         };
 
-        // 9. Fetch salience data for all result IDs
-        let ids: Vec<String> = raw_hits.iter().map(|h| h.memory.id.clone()).collect();
-        let salience_data = match pg_store.get_salience_data(&ids).await {
-            Ok(data) => data,
-            Err(e) => return Ok(store_error_to_result(e)),
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory types require PostgreSQL backend"
+                })));
+            }
         };
 
-        // 10. Build ScoredHit vec for salience re-ranking
-        let mut scored_hits: Vec<ScoredHit> = raw_hits
-            .into_iter()
-            .map(|hit| ScoredHit {
-                memory: hit.memory,
-                rrf_score: hit.rrf_score,
-                salience_score: 0.0, // populated by rank()
-                match_source: hit.match_source,
-                breakdown: None,     // populated by rank() when debug_scoring=true
-            })
-            .collect();
-
-        // 11. Build SalienceInput for each hit (parallel order to scored_hits)
-        let salience_inputs: Vec<SalienceInput> = scored_hits
-            .iter()
-            .map(|hit| {
-                let row = salience_data
-                    .get(&hit.memory.id)
-                    .cloned()
-                    .unwrap_or_default();
-                let days_since_reinforced = row.last_reinforced_at
-                    .map(|dt| {
-                        let duration = Utc::now().signed_duration_since(dt);
-                        (duration.num_seconds() as f64 / 86_400.0).max(0.0)
-                    })
-                    .unwrap_or(365.0); // 1 year default for never-reinforced memories
-                SalienceInput {
-                    stability: row.stability,
-                    days_since_reinforced,
-                }
-            })
-            .collect();
-
-        // 12. Apply salience re-ranking
-        let scorer = SalienceScorer::new(&self.salience_config);
-        scorer.rank(&mut scored_hits, &salience_inputs);
-
-        // 12.5 Apply temporal soft boost if time range extracted
-        if let Some(ref time_range) = qi_time_range {
-            for hit in &mut scored_hits {
-                let created = hit.memory.created_at;
-                let in_range = match (time_range.after, time_range.before) {
-                    (Some(after), Some(before)) => created >= after && created <= before,
-                    (Some(after), None) => created >= after,
-                    (None, Some(before)) => created <= before,
-                    (None, None) => false,
-                };
-                if in_range {
-                    hit.salience_score *= 2.0; // 2x boost for in-range memories (soft boost, not filter)
-                }
-            }
-            // Re-sort by boosted salience score
-            scored_hits.sort_by(|a, b| b.salience_score.partial_cmp(&a.salience_score).unwrap_or(std::cmp::Ordering::Equal));
+        let description = params.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+        match pg_store.define_memory_type(&namespace, name, description, &params.fields).await {
+            Ok(memory_type) => Ok(CallToolResult::structured(json!({
+                "name": memory_type.name,
+                "namespace": memory_type.namespace,
+                "description": memory_type.description,
+                "fields": memory_type.fields,
+                "created_at": memory_type.created_at.to_rfc3339(),
+                "updated_at": memory_type.updated_at.to_rfc3339(),
+                "hint": "Use store_structured_memory with memory_type to store memories of this type"
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
         }
+    }
 
-        // 12.75 LLM re-ranking (if enabled and budget remaining)
-        if let Some(ref provider) = self.qi_reranking_provider {
-            let remaining = qi_budget.saturating_sub(qi_start.elapsed());
-            if remaining > Duration::from_millis(100) { // Only attempt if >100ms remains
-                // Take top 10 for re-ranking (locked decision)
-                let top_n = scored_hits.len().min(10);
-                let candidates: Vec<RankedCandidate> = scored_hits[..top_n]
-                    .iter()
-                    .enumerate()
-                    .map(|(i, hit)| {
-                        let content = if hit.memory.content.len() > self.qi_config.rerank_content_chars {
-                            hit.memory.content[..self.qi_config.rerank_content_chars].to_string()
-                        } else {
-                            hit.memory.content.clone()
-                        };
-                        RankedCandidate {
-                            id: hit.memory.id.clone(),
-                            content,
-                            current_rank: i + 1,
-                        }
-                    })
-                    .collect();
+    #[tool(description = "List the memory types defined in a namespace, with their fields.")]
+    async fn list_memory_types(
+        &self,
+        Parameters(params): Parameters<ListMemoryTypesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(tool = "list_memory_types", namespace = ?params.namespace, "Tool called");
 
-                match tokio::time::timeout(remaining, provider.rerank(&params.query, &candidates)).await {
-                    Ok(Ok(ranked)) => {
-                        tracing::info!(ranked_count = ranked.len(), "LLM re-ranking applied");
-                        // Blend: 0.7 * llm_rank_score + 0.3 * salience_score (normalized)
-                        // llm_rank_score = 1.0 / (1.0 + llm_rank as f64)
-                        let max_salience = scored_hits.iter().map(|h| h.salience_score).fold(f64::MIN, f64::max);
-                        let min_salience = scored_hits.iter().map(|h| h.salience_score).fold(f64::MAX, f64::min);
-                        let salience_range = (max_salience - min_salience).max(1e-6);
-
-                        for hit in scored_hits[..top_n].iter_mut() {
-                            if let Some(r) = ranked.iter().find(|r| r.id == hit.memory.id) {
-                                let llm_score = 1.0 / (1.0 + r.llm_rank as f64);
-                                let norm_salience = (hit.salience_score - min_salience) / salience_range;
-                                hit.salience_score = 0.7 * llm_score + 0.3 * norm_salience;
-                            }
-                        }
-                        // Re-sort top_n portion only
-                        scored_hits[..top_n].sort_by(|a, b| b.salience_score.partial_cmp(&a.salience_score).unwrap_or(std::cmp::Ordering::Equal));
-                    }
-                    Ok(Err(e)) => {
-                        tracing::warn!(error = %e, "LLM re-ranking failed, keeping salience order");
-                    }
-                    Err(_) => {
-                        tracing::warn!(elapsed_ms = ?qi_start.elapsed().as_millis(), "LLM re-ranking timed out, keeping salience order");
-                    }
-                }
-            } else {
-                tracing::debug!(remaining_ms = ?remaining.as_millis(), "Skipping re-ranking — insufficient budget remaining");
+        let namespace = match self.resolve_namespace(params.namespace) {
+            Ok(ns) => ns,
+            Err(result) => return Ok(result),
+        };
+
+        let pg_store = match &self.pg_store {
+            Some(s) => s,
+            None => {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "Memory types require PostgreSQL backend"
+                })));
             }
+        };
+
+        match pg_store.list_memory_types(&namespace).await {
+            Ok(types) => Ok(CallToolResult::structured(json!({
+                "namespace": namespace,
+                "count": types.len(),
+                "types": types.iter().map(|t| json!({
+                    "name": t.name,
+                    "description": t.description,
+                    "fields": t.fields,
+                    "updated_at": t.updated_at.to_rfc3339(),
+                })).collect::<Vec<_>>(),
+            }))),
+            Err(e) => Ok(store_error_to_result(e)),
         }
+    }
+    #[tool(description = "Rename a tag on every memory in a namespace (e.g. 'Postgres' -> 'postgres'). Changed memories are re-embedded.")]
+    async fn rename_tag(
+        &self,
+        Parameters(params): Parameters<RenameTagParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "rename_tag",
+            from = %params.from,
+            to = %params.to,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
 
-        // 13. Format results
-        let count = scored_hits.len();
-        let results: Vec<serde_json::Value> = scored_hits.iter().map(|hit| {
-            let mut obj = json!({
-                "id": hit.memory.id,
-                "content": hit.memory.content,
-                "type_hint": hit.memory.type_hint,
-                "source": hit.memory.source,
-                "tags": hit.memory.tags,
-                "created_at": hit.memory.created_at.to_rfc3339(),
-                "updated_at": hit.memory.updated_at.to_rfc3339(),
-                "access_count": hit.memory.access_count,
-                "relevance_score": (hit.salience_score * 1000.0).round() / 1000.0,
-                "match_source": hit.match_source,
-                "rrf_score": (hit.rrf_score * 10000.0).round() / 10000.0,
-            });
-            // Add score breakdown when debug_scoring is enabled
-            if let Some(ref bd) = hit.breakdown {
-                obj["score_breakdown"] = json!({
-                    "recency": (bd.recency * 1000.0).round() / 1000.0,
-                    "access": (bd.access * 1000.0).round() / 1000.0,
-                    "semantic": (bd.semantic * 1000.0).round() / 1000.0,
-                    "reinforcement": (bd.reinforcement * 1000.0).round() / 1000.0,
-                });
+        for (field, value) in [("from", &params.from), ("to", &params.to)] {
+            if value.trim().is_empty() {
+                return Ok(CallToolResult::structured_error(json!({
+                    "isError": true,
+                    "code": codes::VALIDATION,
+                    "error": format!("Field '{}' is required and cannot be empty", field),
+                    "field": field
+                })));
             }
-            obj
-        }).collect();
+        }
+        if params.from == params.to {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Fields 'from' and 'to' must differ",
+                "field": "to"
+            })));
+        }
 
-        // 14. Build final response JSON
-        let mut response = json!({
-            "memories": results,
-            "total_results": count,
-            "query": params.query,
-            "has_more": false,
-        });
+        Ok(self.rewrite_tags(params.namespace, vec![params.from], Some(params.to)).await)
+    }
 
-        if count == 0 {
-            response["hint"] = json!("No memories matched your query. Try broader search terms or use list_memories to browse all memories.");
+    #[tool(description = "Merge several tags into one across every memory in a namespace (e.g. ['Postgres', 'pg'] -> 'postgres'). Changed memories are re-embedded.")]
+    async fn merge_tags(
+        &self,
+        Parameters(params): Parameters<MergeTagsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(
+            tool = "merge_tags",
+            sources = ?params.sources,
+            target = %params.target,
+            namespace = ?params.namespace,
+            "Tool called"
+        );
+
+        if params.sources.is_empty() || params.sources.len() > 50 {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'sources' must contain between 1 and 50 tags",
+                "field": "sources"
+            })));
+        }
+        if let Some(i) = params.sources.iter().position(|t| t.trim().is_empty()) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Source tags cannot be empty",
+                "field": format!("sources[{}]", i)
+            })));
+        }
+        if params.target.trim().is_empty() {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "Field 'target' is required and cannot be empty",
+                "field": "target"
+            })));
         }
 
-        Ok(CallToolResult::structured(response))
+        Ok(self.rewrite_tags(params.namespace, params.sources, Some(params.target)).await)
     }
 
-    #[tool(description = "Reinforce a memory to boost its salience in future searches. Use when a memory is particularly relevant or important. Reinforcing a faded memory produces a stronger boost than reinforcing a recently accessed one (spaced repetition). Rating: 'good' (default) for standard reinforcement, 'easy' for extra-strong boost.")]
-    async fn reinforce_memory(
+    #[tool(description = "Remove a tag from every memory in a namespace. The memories themselves are kept and re-embedded.")]
+    async fn delete_tag(
         &self,
-        Parameters(params): Parameters<ReinforceMemoryParams>,
+        Parameters(params): Parameters<DeleteTagParams>,
     ) -> Result<CallToolResult, McpError> {
         tracing::info!(
-            tool = "reinforce_memory",
-            id = %params.id,
-            rating = ?params.rating,
+            tool = "delete_tag",
+            tag = %params.tag,
+            namespace = ?params.namespace,
             "Tool called"
         );
 
-        if params.id.trim().is_empty() {
+        if params.tag.trim().is_empty() {
             return Ok(CallToolResult::structured_error(json!({
                 "isError": true,
-                "error": "Field 'id' is required and cannot be empty",
-                "field": "id"
+                "code": codes::VALIDATION,
+                "error": "Field 'tag' is required and cannot be empty",
+                "field": "tag"
             })));
         }
 
-        // Verify memory exists
-        match self.store.get(&params.id).await {
-            Err(MemcpError::NotFound { .. }) => {
+        Ok(self.rewrite_tags(params.namespace, vec![params.tag], None).await)
+    }
+
+    #[tool(description = "Read the audit log of tool calls, newest first: tool, parameter hash, affected memory IDs, latency, and outcome. Filter by tool, outcome, memory_id, or time range. Entries are only recorded when [audit] enabled = true.")]
+    async fn get_audit_log(
+        &self,
+        Parameters(params): Parameters<GetAuditLogParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(tool = "get_audit_log", filter_tool = ?params.tool, memory_id = ?params.memory_id, "Tool called");
+
+        let limit = params.limit.unwrap_or(100);
+        if !(1..=1000).contains(&limit) {
+            return Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": "limit must be between 1 and 1000",
+                "field": "limit"
+            })));
+        }
+        if let Some(ref outcome) = params.outcome {
+            if outcome != "ok" && outcome != "error" {
                 return Ok(CallToolResult::structured_error(json!({
                     "isError": true,
-                    "error": format!("Memory not found: {}", params.id),
-                    "hint": "Use list_memories to find available memory IDs"
+                    "code": codes::VALIDATION,
+                    "error": format!("Invalid outcome '{}': expected \"ok\" or \"error\"", outcome),
+                    "field": "outcome"
                 })));
             }
-            Err(e) => return Ok(store_error_to_result(e)),
-            Ok(_) => {}
         }
+        let since = match params.since.as_deref().map(|s| parse_datetime(s, "since")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return Ok(result),
+        };
+        let until = match params.until.as_deref().map(|s| parse_datetime(s, "until")).transpose() {
+            Ok(dt) => dt,
+            Err(result) => return Ok(result),
+        };
 
-        // Validate and normalize rating
-        let rating = params.rating.as_deref().unwrap_or("good");
-        let rating = if rating == "easy" { "easy" } else { "good" };
-
-        // Get concrete pg_store reference
         let pg_store = match &self.pg_store {
             Some(s) => s,
             None => {
                 return Ok(CallToolResult::structured_error(json!({
                     "isError": true,
-                    "error": "Reinforcement requires PostgreSQL backend"
+                    "code": codes::BACKEND_UNSUPPORTED,
+                    "error": "The audit log requires PostgreSQL backend"
                 })));
             }
         };
 
-        match pg_store.reinforce_salience(&params.id, rating).await {
-            Ok(row) => Ok(CallToolResult::structured(json!({
-                "id": params.id,
-                "stability": row.stability,
-                "reinforcement_count": row.reinforcement_count,
-                "message": format!(
-                    "Memory reinforced. Stability: {:.1} days, reinforcements: {}",
-                    row.stability, row.reinforcement_count
-                )
-            }))),
+        let filter = crate::audit::AuditFilter {
+            tool: params.tool,
+            outcome: params.outcome,
+            memory_id: params.memory_id,
+            since,
+            until,
+            limit: limit as i64,
+        };
+        match pg_store.get_audit_log(&filter).await {
+            Ok(entries) => {
+                let items: Vec<serde_json::Value> = entries
+                    .iter()
+                    .map(|e| {
+                        json!({
+                            "id": e.id,
+                            "tool": e.tool,
+                            "params_hash": e.params_hash,
+                            "memory_ids": e.memory_ids,
+                            "latency_ms": e.latency_ms,
+                            "outcome": e.outcome,
+                            "error_code": e.error_code,
+                            "created_at": e.created_at.to_rfc3339(),
+                        })
+                    })
+                    .collect();
+                let hint = if self.audit {
+                    "Pass memory_id to see every call that touched a memory"
+                } else {
+                    "Auditing is disabled; set [audit] enabled = true to record tool calls"
+                };
+                Ok(CallToolResult::structured(json!({
+                    "entries": items,
+                    "count": items.len(),
+                    "audit_enabled": self.audit,
+                    "hint": hint
+                })))
+            }
             Err(e) => Ok(store_error_to_result(e)),
         }
     }
 
-    #[tool(description = "Check server health and status")]
-    async fn health_check(
+    #[tool(description = "Operational metrics: store/search latency, embedding queue depth, extraction failures, consolidation merges, and query intelligence timeouts. Set format: \"prometheus\" for Prometheus text output.")]
+    async fn get_metrics(
         &self,
+        Parameters(params): Parameters<GetMetricsParams>,
     ) -> Result<CallToolResult, McpError> {
-        tracing::info!(tool = "health_check", "Tool called");
+        tracing::info!(tool = "get_metrics", format = ?params.format, "Tool called");
 
-        let response = json!({
-            "status": "ok",
-            "version": env!("CARGO_PKG_VERSION"),
-            "uptime_seconds": self.uptime_seconds(),
+        let registry = metrics::global();
+        match params.format.as_deref().unwrap_or("json") {
+            "json" => {
+                let mut response = registry.to_json();
+                response["uptime_seconds"] = json!(self.uptime_seconds());
+                Ok(CallToolResult::structured(response))
+            }
+            "prometheus" => Ok(CallToolResult::structured(json!({
+                "format": "prometheus",
+                "text": registry.render_prometheus(),
+            }))),
+            other => Ok(CallToolResult::structured_error(json!({
+                "isError": true,
+                "code": codes::VALIDATION,
+                "error": format!("Invalid format '{}': expected \"json\" or \"prometheus\"", other),
+                "field": "format"
+            }))),
+        }
+    }
+
+    #[tool(description = "Re-read memcp.toml and MEMCP_ environment variables and apply the salience weights, query intelligence tuning, consolidation thresholds, and log level without restarting. Other changed sections are listed under restart_required and keep their running values. Sending SIGHUP to the server does the same.")]
+    async fn reload_config(
+        &self,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::info!(tool = "reload_config", "Tool called");
+
+        match self.live.reload_from_disk() {
+            Ok(report) => {
+                tracing::info!(applied = ?report.applied, restart_required = ?report.restart_required, "Config reloaded");
+                Ok(CallToolResult::structured(json!({
+                    "applied": report.applied,
+                    "restart_required": report.restart_required,
+                    "generation": self.live.generation(),
+                    "hint": if report.restart_required.is_empty() {
+                        "Changes are live for the next tool call"
+                    } else {
+                        "Sections under restart_required only take effect after the server restarts"
+                    }
+                })))
+            }
+            Err(e) => Ok(store_error_to_result(e)),
+        }
+    }
+}
+
+// Helper: format a slice of memories into human-readable text for resource consumption
+fn format_memories_text(memories: &[Memory]) -> String {
+    if memories.is_empty() {
+        return String::new();
+    }
+    memories
+        .iter()
+        .map(|m| {
+            format!(
+                "---\n[{}] {}\nCreated: {} | Source: {} | Accessed: {} times\n---",
+                m.type_hint,
+                m.content,
+                m.created_at.to_rfc3339(),
+                m.source,
+                m.access_count
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The prompts served over prompts/list (rendered by `MemoryService::prompt`).
+fn prompt_list() -> Vec<Prompt> {
+    let argument = |name: &str, description: &str, required: bool| PromptArgument {
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        required: Some(required),
+    };
+    let namespace = || argument("namespace", "Namespace to read from (default: server's configured namespace)", false);
+    vec![
+        Prompt::new(
+            "recall_context",
+            Some("Ground the conversation in what memcp remembers about a topic: runs a search and lists the matching memories"),
+            Some(vec![
+                argument("topic", "What to recall, e.g. \"deployment setup\"", true),
+                argument("task", "Question or task to answer with the recalled context", false),
+                argument("limit", "Maximum memories to include (1-50, default: 5)", false),
+                namespace(),
+            ]),
+        ),
+        Prompt::new(
+            "summarize_user_profile",
+            Some("Ask for a short summary of the user's stored preferences"),
+            Some(vec![namespace()]),
+        ),
+    ]
+}
+
+/// One search result as a prompt line: "- [type] content (source, created YYYY-MM-DD)".
+fn format_recalled_memory(memory: &serde_json::Value) -> String {
+    let created = memory["created_at"].as_str().unwrap_or_default();
+    format!(
+        "- [{}] {} ({}, created {})",
+        memory["type_hint"].as_str().unwrap_or("fact"),
+        memory["content"].as_str().unwrap_or_default(),
+        memory["source"].as_str().unwrap_or("default"),
+        created.get(..10).unwrap_or(created)
+    )
+}
+
+/// Join session-primer sections — an optional header and its entries, in rank order — with
+/// blank lines between sections, skipping empty ones. Once the next entry would push the text
+/// past `max_chars` (0 = unlimited), it and everything after it are left out; an entry too
+/// long to fit on its own is cut to the budget instead.
+fn render_primer(sections: Vec<(Option<&str>, Vec<String>)>, max_chars: usize) -> String {
+    let mut text = String::new();
+    let mut length = 0;
+    for (header, entries) in sections {
+        let mut first = true;
+        for entry in entries {
+            let mut addition = String::new();
+            if first {
+                if !text.is_empty() {
+                    addition.push_str("\n\n");
+                }
+                if let Some(header) = header {
+                    addition.push_str(header);
+                    addition.push('\n');
+                }
+            } else {
+                addition.push('\n');
+            }
+            addition.push_str(&entry);
+            let added = addition.chars().count();
+            if max_chars > 0 && length + added > max_chars {
+                if text.is_empty() {
+                    text = addition.chars().take(max_chars).collect();
+                }
+                return text;
+            }
+            text.push_str(&addition);
+            length += added;
+            first = false;
+        }
+    }
+    text
+}
+
+/// Format session summaries (newest first) for the session-primer resource.
+fn format_session_summaries(summaries: &[(Session, Memory)]) -> String {
+    summaries
+        .iter()
+        .map(|(session, summary)| {
+            format!(
+                "---\n[session] {}\nStarted: {} | Ended: {}\n{}\n---",
+                session.title.as_deref().unwrap_or("untitled"),
+                session.started_at.to_rfc3339(),
+                session.ended_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+                summary.content
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The full JSON view of one memory returned by get_memory and get_memories.
+fn memory_detail_json(memory: &Memory) -> serde_json::Value {
+    json!({
+        "id": memory.id,
+        "content": memory.content,
+        "type_hint": memory.type_hint,
+        "source": memory.source,
+        "tags": memory.tags,
+        "created_at": memory.created_at.to_rfc3339(),
+        "updated_at": memory.updated_at.to_rfc3339(),
+        "last_accessed_at": memory.last_accessed_at.map(|dt| dt.to_rfc3339()),
+        "access_count": memory.access_count,
+        "embedding_status": memory.embedding_status,
+        "namespace": memory.namespace,
+        "expires_at": memory.expires_at.map(|dt| dt.to_rfc3339()),
+        "archived_at": memory.archived_at.map(|dt| dt.to_rfc3339()),
+        "importance": memory.importance,
+        "parent_id": memory.parent_id,
+        "chunk_index": memory.chunk_index,
+        "fields": memory.fields,
+        "payload": memory.payload,
+        "origin_refs": memory.origin_refs,
+        "pinned": memory.pinned,
+    })
+}
+
+/// Format the memory://daily-digest listing: a header, then memories grouped by type_hint
+/// and source (both alphabetical), each group newest first.
+fn format_digest(memories: &[Memory], window_hours: u32, since: DateTime<Utc>) -> String {
+    let mut groups: std::collections::BTreeMap<(&str, &str), Vec<&Memory>> = std::collections::BTreeMap::new();
+    for memory in memories {
+        groups.entry((memory.type_hint.as_str(), memory.source.as_str())).or_default().push(memory);
+    }
+
+    let mut text = format!(
+        "Daily digest: {} memories from the last {}h (since {})",
+        memories.len(),
+        window_hours,
+        since.format("%Y-%m-%d %H:%M UTC")
+    );
+    let mut current_type = "";
+    for ((type_hint, source), group) in groups {
+        if type_hint != current_type {
+            text.push_str(&format!("\n\n[{}]", type_hint));
+            current_type = type_hint;
+        }
+        text.push_str(&format!("\nSource: {} ({})", source, group.len()));
+        for memory in group {
+            text.push_str(&format!("\n- {} {}", memory.created_at.format("%m-%d %H:%M"), memory.content));
+        }
+    }
+    text
+}
+
+// Admin listener calls (see crate::admin) — operational commands kept off the tool surface
+#[async_trait::async_trait]
+impl crate::admin::AdminHandler for MemoryService {
+    async fn handle(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, crate::admin::RpcError> {
+        use crate::admin::RpcError;
+        match method {
+            "status" => Ok(json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "uptime_secs": self.start_time.elapsed().as_secs(),
+                "backend": if self.pg_store.is_some() { "postgres" } else { "memory" },
+                "default_namespace": self.default_namespace,
+                "read_only": self.read_only,
+                "queue_depth": self.queue_depth(),
+            })),
+            "embed.stats" => {
+                let pg_store = self.pg_store.as_ref().ok_or_else(|| RpcError::server("Embedding stats require the PostgreSQL backend"))?;
+                let mut stats = pg_store.embedding_stats().await.map_err(|e| RpcError::server(e.to_string()))?;
+                stats["queue_depth"] = self.queue_depth()["embedding"].clone();
+                Ok(stats)
+            }
+            "cache.clear" => {
+                let entries = self.search_cache.as_ref().map(|cache| cache.len());
+                self.invalidate_search_cache();
+                Ok(json!({ "enabled": entries.is_some(), "cleared": entries.unwrap_or(0) }))
+            }
+            "consolidation.run" => {
+                if self.read_only {
+                    return Err(RpcError::server("The server is read-only; consolidation is disabled"));
+                }
+                let params: ConsolidateMemoriesParams =
+                    serde_json::from_value(params).map_err(|e| RpcError::invalid_params(e.to_string()))?;
+                let result = self.consolidate_memories(Parameters(params)).await.map_err(|e| RpcError::server(e.message))?;
+                let body = result.structured_content.unwrap_or_default();
+                if result.is_error == Some(true) {
+                    return Err(RpcError::server(body["error"].as_str().unwrap_or("Consolidation failed")));
+                }
+                Ok(body)
+            }
+            other => Err(RpcError::new(crate::admin::rpc_codes::METHOD_NOT_FOUND, format!("Unknown method '{}'", other))),
+        }
+    }
+}
+
+impl MemoryService {
+    /// Run a read-only tool call until it finishes, server.tool_timeout_ms passes, or
+    /// `cancelled` (the client's notifications/cancelled) resolves. Abandoning the call drops
+    /// everything it awaits: the query embedding, query expansion and re-ranking requests,
+    /// and the search legs — variant searches run in a JoinSet, which aborts them on drop.
+    async fn bounded<F, C>(&self, tool: &str, call: F, cancelled: C) -> Result<CallToolResult, McpError>
+    where
+        F: std::future::Future<Output = Result<CallToolResult, McpError>>,
+        C: std::future::Future<Output = ()>,
+    {
+        let deadline = async {
+            match self.tool_timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            result = call => result,
+            _ = deadline => {
+                metrics::global().tool_timeouts.inc();
+                tracing::warn!(tool, timeout_ms = ?self.tool_timeout.map(|t| t.as_millis()), "Tool call exceeded its deadline — abandoned");
+                Ok(timeout_error(tool, false, self.tool_timeout))
+            }
+            _ = cancelled => {
+                metrics::global().tool_cancellations.inc();
+                tracing::info!(tool, "Tool call cancelled by the client — abandoned");
+                Ok(timeout_error(tool, true, self.tool_timeout))
+            }
+        }
+    }
+}
+
+// ServerHandler implementation
+impl ServerHandler for MemoryService {
+    // Hand-written rather than #[tool_handler] so read-only mode can hide mutating tools from
+    // tools/list while still answering calls to them with a clear error.
+    async fn call_tool(
+        &self,
+        request: rmcp::model::CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let started = Instant::now();
+        let audited = self.audit.then(|| {
+            let params = serde_json::Value::Object(request.arguments.clone().unwrap_or_default());
+            (request.name.to_string(), params)
         });
 
-        Ok(CallToolResult::structured(response))
+        let result = if self.read_only && MUTATING_TOOLS.contains(&request.name.as_ref()) {
+            tracing::info!(tool = %request.name, "Rejected mutating tool call in read-only mode");
+            Ok(read_only_error(&request.name))
+        } else {
+            let tool = request.name.clone();
+            let write = writes(&tool, request.arguments.as_ref());
+            let cancel = context.ct.clone();
+            let tcc = rmcp::handler::server::tool::ToolCallContext::new(self, request, context);
+            let router = Self::tool_router();
+            let call = router.call(tcc);
+            if write {
+                call.await
+            } else {
+                self.bounded(&tool, call, async move { cancel.cancelled().await }).await
+            }
+        };
+
+        if let Some((tool, params)) = audited {
+            self.record_audit(tool, params, &result, started.elapsed());
+        }
+        result
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListToolsResult, McpError> {
+        let tools = Self::tool_router()
+            .list_all()
+            .into_iter()
+            .filter(|tool| !(self.read_only && MUTATING_TOOLS.contains(&tool.name.as_ref())))
+            .collect();
+        Ok(rmcp::model::ListToolsResult {
+            tools,
+            meta: None,
+            next_cursor: None,
+        })
     }
-}
 
-// Helper: format a slice of memories into human-readable text for resource consumption
-fn format_memories_text(memories: &[Memory]) -> String {
-    if memories.is_empty() {
-        return String::new();
+    fn get_tool(&self, name: &str) -> Option<rmcp::model::Tool> {
+        if self.read_only && MUTATING_TOOLS.contains(&name) {
+            return None;
+        }
+        Self::tool_router().get(name).cloned()
     }
-    memories
-        .iter()
-        .map(|m| {
-            format!(
-                "---\n[{}] {}\nCreated: {} | Source: {} | Accessed: {} times\n---",
-                m.type_hint,
-                m.content,
-                m.created_at.to_rfc3339(),
-                m.source,
-                m.access_count
-            )
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-}
 
-// ServerHandler implementation
-#[rmcp::tool_handler(router = Self::tool_router())]
-impl ServerHandler for MemoryService {
     fn get_info(&self) -> rmcp::model::InitializeResult {
+        let mut instructions = "Memory server for AI agents. Tools: store_memory, store_memories, store_structured_memory, define_memory_type, list_memory_types, ingest_conversation, get_memory, get_memories, search_memory, update_memory, pin_memory, get_memory_history, revert_memory, delete_memory, bulk_delete_memories, bulk_update_memories, list_memories, get_memory_facets, memory_stats, get_memories_by_entity, list_entities, rename_tag, merge_tags, delete_tag, get_related_memories, link_memories, unlink_memories, get_memory_graph, get_contradictions, summarize_memories, export_memories, import_memories, start_session, end_session, consolidate_memories, merge_memories, restore_memory, unconsolidate_memory, purge_trash, health_check, get_metrics, reload_config, reinforce_memory, reinforce_memories, mark_used, get_audit_log. delete_memory moves memories to the trash by default. Pass `namespace` to store/search/list/delete tools to isolate memories per agent (omitted = server default). Resources: memory://session-primer (pinned memories, recent session summaries and memories), memory://daily-digest (the last day's memories by type and source), memory://user-profile (preferences). Prompts: recall_context (memories about a topic), summarize_user_profile.".to_string();
+        if self.read_only {
+            instructions.push_str(" This server is read-only: tools that modify memories are disabled.");
+        }
         rmcp::model::InitializeResult {
             protocol_version: ProtocolVersion::V_2024_11_05,
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
                 .enable_resources()
+                .enable_prompts()
                 .build(),
             server_info: Implementation {
                 name: "memcp".to_string(),
@@ -1084,9 +7216,7 @@ impl ServerHandler for MemoryService {
                 icons: None,
                 website_url: None,
             },
-            instructions: Some(
-                "Memory server for AI agents. Tools: store_memory, get_memory, search_memory, update_memory, delete_memory, bulk_delete_memories, list_memories, health_check, reinforce_memory. Resources: memory://session-primer (recent memories), memory://user-profile (preferences).".to_string()
-            ),
+            instructions: Some(instructions),
         }
     }
 
@@ -1102,7 +7232,18 @@ impl ServerHandler for MemoryService {
                     uri: "memory://session-primer".to_string(),
                     name: "session-primer".to_string(),
                     title: Some("Session Memory Primer".to_string()),
-                    description: Some("Recent memories for session context".to_string()),
+                    description: Some("Pinned memories, summaries of recent sessions and newer memories, for session context".to_string()),
+                    mime_type: Some("text/plain".to_string()),
+                    size: None,
+                    icons: None,
+                    meta: None,
+                }
+                .no_annotation(),
+                RawResource {
+                    uri: "memory://daily-digest".to_string(),
+                    name: "daily-digest".to_string(),
+                    title: Some("Daily Digest".to_string()),
+                    description: Some("Memories from the last day grouped by type and source, optionally with a narrative summary".to_string()),
                     mime_type: Some("text/plain".to_string()),
                     size: None,
                     icons: None,
@@ -1132,42 +7273,35 @@ impl ServerHandler for MemoryService {
     ) -> Result<ReadResourceResult, McpError> {
         match request.uri.as_str() {
             "memory://session-primer" => {
-                let filter = ListFilter {
-                    limit: 20,
-                    ..Default::default()
-                };
-                let result = self
-                    .store
-                    .list(filter)
+                let text = self
+                    .session_primer_text()
                     .await
                     .map_err(|e| McpError::resource_not_found(e.to_string(), None))?;
 
-                let text = if result.memories.is_empty() {
-                    "No memories stored yet. Use store_memory to add your first memory.".to_string()
-                } else {
-                    format_memories_text(&result.memories)
-                };
+                Ok(ReadResourceResult {
+                    contents: vec![ResourceContents::text(text, request.uri)],
+                })
+            }
+            "memory://daily-digest" => {
+                let text = self
+                    .daily_digest()
+                    .await
+                    .map_err(|e| McpError::resource_not_found(e.to_string(), None))?;
 
                 Ok(ReadResourceResult {
                     contents: vec![ResourceContents::text(text, request.uri)],
                 })
             }
             "memory://user-profile" => {
-                let filter = ListFilter {
-                    type_hint: Some("preference".to_string()),
-                    limit: 50,
-                    ..Default::default()
-                };
-                let result = self
-                    .store
-                    .list(filter)
+                let memories = self
+                    .user_profile_memories(self.default_namespace.clone())
                     .await
                     .map_err(|e| McpError::resource_not_found(e.to_string(), None))?;
 
-                let text = if result.memories.is_empty() {
+                let text = if memories.is_empty() {
                     "No user preferences stored yet. Use store_memory with type_hint: 'preference' to add preferences.".to_string()
                 } else {
-                    format_memories_text(&result.memories)
+                    format_memories_text(&memories)
                 };
 
                 Ok(ReadResourceResult {
@@ -1180,4 +7314,806 @@ impl ServerHandler for MemoryService {
             )),
         }
     }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult::with_all_items(prompt_list()))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        self.prompt(&request.name, &request.arguments.unwrap_or_default()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::memory::{InMemoryStore, KeywordEmbedder};
+
+    /// A service over a fresh in-memory store that embeds with KeywordEmbedder.
+    fn service() -> MemoryService {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(KeywordEmbedder);
+        let store = InMemoryStore::new().with_embedding_provider(provider.clone());
+        MemoryService::new(
+            Arc::new(store),
+            None,
+            Some(provider),
+            None,
+            SalienceConfig::default(),
+            None,
+            None,
+            None,
+            crate::config::QueryIntelligenceConfig::default(),
+        )
+    }
+
+    fn params<T: serde::de::DeserializeOwned>(value: serde_json::Value) -> Parameters<T> {
+        Parameters(serde_json::from_value(value).expect("valid tool params"))
+    }
+
+    fn body(result: Result<CallToolResult, McpError>) -> serde_json::Value {
+        result.expect("tool call").structured_content.expect("structured content")
+    }
+
+    #[tokio::test]
+    async fn store_get_update_roundtrip() {
+        let service = service();
+        let stored = body(service.store_memory(params(json!({"content": "Uses Rust", "tags": ["lang"]}))).await);
+        let id = stored["id"].as_str().unwrap().to_string();
+
+        let updated = body(service.update_memory(params(json!({"id": id, "content": "Uses Rust and Postgres"}))).await);
+        assert_eq!(updated["content"], "Uses Rust and Postgres");
+
+        let fetched = body(service.get_memory(params(json!({"id": id}))).await);
+        assert_eq!(fetched["content"], "Uses Rust and Postgres");
+        assert_eq!(fetched["tags"], json!(["lang"]));
+        assert_eq!(fetched["embedding_status"], "complete");
+        assert!(fetched.get("pipeline_status").is_none());
+
+        let fetched = body(service.get_memory(params(json!({"id": id, "include_pipeline_status": true}))).await);
+        let status = &fetched["pipeline_status"];
+        assert_eq!(status["embedding"]["status"], "complete");
+        assert_eq!(status["extraction"]["status"], "pending");
+        assert_eq!(status["consolidation"]["is_consolidated_original"], false);
+        assert_eq!(status["salience"], serde_json::Value::Null, "salience lives in PostgreSQL");
+        assert_eq!(status["vector_searchable"], true);
+    }
+
+    #[tokio::test]
+    async fn get_memories_returns_found_in_order_and_reports_missing() {
+        let service = service();
+        let first = body(service.store_memory(params(json!({"content": "Uses Rust"}))).await);
+        let second = body(service.store_memory(params(json!({"content": "Deploys with Nix"}))).await);
+        let (first, second) = (first["id"].as_str().unwrap(), second["id"].as_str().unwrap());
+
+        let fetched = body(service.get_memories(params(json!({"ids": [second, "missing", first, second]}))).await);
+        assert_eq!(fetched["requested"], 3);
+        assert_eq!(fetched["found"], 2);
+        assert_eq!(fetched["memories"][0]["content"], "Deploys with Nix");
+        assert_eq!(fetched["memories"][1]["id"], first);
+        assert_eq!(fetched["not_found"], json!(["missing"]));
+
+        let single = body(service.get_memory(params(json!({"id": first}))).await);
+        assert_eq!(single["access_count"], 1, "get_memories bumps access stats");
+
+        let empty = body(service.get_memories(params(json!({"ids": []}))).await);
+        assert_eq!(empty["field"], "ids");
+        let ids: Vec<String> = (0..=MAX_BATCH_GET).map(|i| i.to_string()).collect();
+        let too_many = body(service.get_memories(params(json!({"ids": ids}))).await);
+        assert_eq!(too_many["field"], "ids");
+    }
+
+    #[tokio::test]
+    async fn prompts_render_search_results_and_preferences() {
+        let service = service();
+        service.store_memory(params(json!({"content": "Deploys run on Postgres 16", "source": "user"}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Prefers short answers", "type_hint": "preference"}))).await.unwrap();
+
+        let text = |result: GetPromptResult| match &result.messages[0].content {
+            rmcp::model::PromptMessageContent::Text { text } => text.clone(),
+            other => panic!("unexpected content {:?}", other),
+        };
+        let arguments = |value: serde_json::Value| value.as_object().cloned().unwrap();
+
+        let recalled = service
+            .prompt("recall_context", &arguments(json!({"topic": "postgres", "task": "Write the release steps"})))
+            .await
+            .unwrap();
+        let recalled = text(recalled);
+        assert!(recalled.contains("- [fact] Deploys run on Postgres 16 (user, created "), "{}", recalled);
+        assert!(recalled.ends_with("Task: Write the release steps"));
+
+        let profile = text(service.prompt("summarize_user_profile", &arguments(json!({}))).await.unwrap());
+        assert!(profile.contains("Prefers short answers"));
+        assert!(!profile.contains("Postgres"));
+
+        assert!(service.prompt("recall_context", &arguments(json!({}))).await.is_err());
+        assert!(service.prompt("recall_context", &arguments(json!({"topic": "x", "limit": "0"}))).await.is_err());
+        assert!(service.prompt("unknown", &arguments(json!({}))).await.is_err());
+        let names: Vec<String> = prompt_list().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["recall_context", "summarize_user_profile"]);
+    }
+
+    #[tokio::test]
+    async fn payload_round_trips_and_is_replaced_on_update() {
+        let service = service();
+        let payload = json!({"language": "rust", "url": "https://docs.rs/sqlx"});
+        let stored = body(service.store_memory(params(json!({"content": "sqlx docs", "payload": payload}))).await);
+        let id = stored["id"].as_str().unwrap().to_string();
+
+        let fetched = body(service.get_memory(params(json!({"id": id}))).await);
+        assert_eq!(fetched["payload"], payload);
+
+        body(service.update_memory(params(json!({"id": id, "payload": {"language": "go"}}))).await);
+        let listed = body(service.list_memories(params(json!({}))).await);
+        assert_eq!(listed["memories"][0]["payload"], json!({"language": "go"}));
+
+        let filtered = body(service.list_memories(params(json!({"payload_path": "$.language == \"go\""}))).await);
+        assert_eq!(filtered["field"], "payload_path", "path filters need PostgreSQL");
+
+        let oversized = "x".repeat(MAX_PAYLOAD_BYTES);
+        let rejected = body(service.store_memory(params(json!({"content": "big", "payload": oversized}))).await);
+        assert_eq!(rejected["field"], "payload");
+    }
+
+    #[tokio::test]
+    async fn origin_refs_are_returned_and_filterable() {
+        let service = service();
+        let origin = json!({"conversation_id": "c-42", "message_id": "m-7"});
+        let stored = body(service.store_memory(params(json!({"content": "prefers tabs", "origin_ref": origin}))).await);
+        body(service.store_memory(params(json!({"content": "uses vim", "origin_ref": {"file_path": "notes.md"}}))).await);
+        body(service.store_memory(params(json!({"content": "no provenance", "origin_ref": {}}))).await);
+
+        let fetched = body(service.get_memory(params(json!({"id": stored["id"]}))).await);
+        assert_eq!(fetched["origin_refs"], json!([origin]));
+
+        let listed = body(service.list_memories(params(json!({"origin_ref": {"conversation_id": "c-42"}}))).await);
+        assert_eq!(listed["memories"].as_array().unwrap().len(), 1);
+        assert_eq!(listed["memories"][0]["content"], "prefers tabs");
+
+        let none = body(service.list_memories(params(json!({"origin_ref": {"conversation_id": "c-42", "message_id": "m-8"}}))).await);
+        assert!(none["memories"].as_array().unwrap().is_empty());
+
+        let all = body(service.list_memories(params(json!({}))).await);
+        let unreferenced = all["memories"].as_array().unwrap().iter().find(|m| m["content"] == "no provenance").unwrap();
+        assert!(unreferenced["origin_refs"].is_null(), "an empty origin_ref stores nothing");
+    }
+
+    #[tokio::test]
+    async fn access_filters_split_used_and_unused_memories() {
+        let service = service();
+        let used = body(service.store_memory(params(json!({"content": "fetched often"}))).await);
+        body(service.store_memory(params(json!({"content": "never fetched"}))).await);
+        let before_fetch = Utc::now().to_rfc3339();
+        for _ in 0..2 {
+            body(service.get_memory(params(json!({"id": used["id"]}))).await);
+        }
+
+        let unused = body(service.list_memories(params(json!({"never_accessed": true}))).await);
+        assert_eq!(unused["memories"].as_array().unwrap().len(), 1);
+        assert_eq!(unused["memories"][0]["content"], "never fetched");
+
+        let well_worn = body(service.list_memories(params(json!({"min_access_count": 2}))).await);
+        assert_eq!(well_worn["memories"].as_array().unwrap().len(), 1);
+        assert_eq!(well_worn["memories"][0]["id"], used["id"]);
+
+        let recent = body(service.list_memories(params(json!({"accessed_after": before_fetch}))).await);
+        assert_eq!(recent["memories"].as_array().unwrap().len(), 1);
+
+        let conflicting = body(service.list_memories(params(json!({"never_accessed": true, "min_access_count": 1}))).await);
+        assert_eq!(conflicting["field"], "never_accessed");
+    }
+
+    #[tokio::test]
+    async fn bounded_calls_are_abandoned_on_deadline_or_cancellation() {
+        let service = service().with_tool_timeout(20);
+        let slow = std::future::pending::<Result<CallToolResult, McpError>>();
+        let timed_out = body(service.bounded("search_memory", slow, std::future::pending()).await);
+        assert_eq!(timed_out["code"], codes::TIMEOUT);
+        assert_eq!(timed_out["cancelled"], false);
+
+        let slow = std::future::pending::<Result<CallToolResult, McpError>>();
+        let cancelled = body(service.bounded("search_memory", slow, std::future::ready(())).await);
+        assert_eq!(cancelled["cancelled"], true);
+
+        let fast = service.list_memories(params(json!({})));
+        let listed = body(service.bounded("list_memories", fast, std::future::pending()).await);
+        assert!(listed["memories"].is_array(), "calls that finish in time pass through");
+    }
+
+    #[test]
+    fn only_reads_are_bounded() {
+        let args = |value: serde_json::Value| value.as_object().cloned();
+        assert!(writes("store_memory", None));
+        assert!(!writes("search_memory", args(json!({"query": "x"})).as_ref()));
+        assert!(!writes("summarize_memories", args(json!({"query": "x"})).as_ref()));
+        assert!(!writes("summarize_memories", args(json!({"store": false})).as_ref()));
+        assert!(writes("summarize_memories", args(json!({"store": true})).as_ref()), "storing a summary writes");
+    }
+
+    #[tokio::test]
+    async fn restore_clears_a_passed_expiry_and_update_can_remove_one() {
+        let service = service();
+        let expired = service.store.store(CreateMemory {
+            content: "Expired note".to_string(),
+            expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
+            ..Default::default()
+        }).await.unwrap();
+        service.store.trash(&expired.id).await.unwrap();
+        let restored = body(service.restore_memory(params(json!({"id": expired.id}))).await);
+        assert_eq!(restored["restored"], true);
+        assert_eq!(service.store.get(&expired.id).await.unwrap().expires_at, None, "a passed expiry is cleared");
+        let listed = body(service.list_memories(params(json!({}))).await);
+        assert_eq!(listed["memories"][0]["id"], expired.id.as_str(), "the restored memory is visible again");
+
+        let later = (Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+        let stored = body(service.store_memory(params(json!({"content": "Temporary note", "expires_at": later}))).await);
+        let id = stored["id"].as_str().unwrap();
+        let updated = body(service.update_memory(params(json!({"id": id, "expires_at": null}))).await);
+        assert!(updated["expires_at"].is_null(), "null removes the expiry");
+        assert_eq!(service.store.get(id).await.unwrap().expires_at, None);
+
+        let nothing = body(service.update_memory(params(json!({"id": id}))).await);
+        assert_eq!(nothing["code"], codes::VALIDATION, "leaving expires_at out changes nothing");
+    }
+
+    /// Summarizes by joining the contents, so tests can see which memories were picked.
+    struct JoiningSummarizer;
+
+    #[async_trait::async_trait]
+    impl crate::consolidation::SynthesisProvider for JoiningSummarizer {
+        async fn complete(&self, prompt: &str) -> Result<String, crate::consolidation::SynthesisError> {
+            Ok(prompt.to_string())
+        }
+
+        async fn summarize(&self, contents: &[&str], _focus: Option<&str>) -> Result<String, crate::consolidation::SynthesisError> {
+            Ok(contents.join(" | "))
+        }
+
+        fn model_name(&self) -> &str {
+            "joining"
+        }
+    }
+
+    #[tokio::test]
+    async fn summarize_filters_before_the_limit() {
+        let service = service().with_summary_provider(Arc::new(JoiningSummarizer));
+        let create = |content: &str, tags: &[&str], type_hint: &str, days_ago: i64| CreateMemory {
+            content: content.to_string(),
+            tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+            type_hint: type_hint.to_string(),
+            created_at: Some(Utc::now() - chrono::Duration::days(days_ago)),
+            ..Default::default()
+        };
+        service.store.store(create("Tagged decision", &["keep"], "decision", 30)).await.unwrap();
+        for i in 0..5 {
+            service.store.store(create(&format!("Recent note {}", i), &[], "fact", 1)).await.unwrap();
+        }
+
+        let tagged = body(service.summarize_memories(params(json!({"tags": ["keep"], "limit": 2}))).await);
+        assert_eq!(tagged["memory_count"], 1, "{}", tagged);
+        assert_eq!(tagged["summary"], "Tagged decision");
+
+        let typed = body(service.summarize_memories(params(json!({"type_hint": "decision", "limit": 2}))).await);
+        assert_eq!(typed["memory_count"], 1, "newer memories of other types don't fill the limit");
+        assert_eq!(typed["summary"], "Tagged decision");
+    }
+
+    /// Fails every call, like an unreachable LLM.
+    struct FailingSummarizer;
+
+    #[async_trait::async_trait]
+    impl crate::consolidation::SynthesisProvider for FailingSummarizer {
+        async fn complete(&self, _prompt: &str) -> Result<String, crate::consolidation::SynthesisError> {
+            Err(crate::consolidation::SynthesisError::Http("connection refused".to_string()))
+        }
+
+        async fn summarize(&self, _contents: &[&str], _focus: Option<&str>) -> Result<String, crate::consolidation::SynthesisError> {
+            Err(crate::consolidation::SynthesisError::Http("connection refused".to_string()))
+        }
+
+        fn model_name(&self) -> &str {
+            "failing"
+        }
+    }
+
+    #[tokio::test]
+    async fn summarize_returns_and_stores_the_provider_output() {
+        let service = service().with_summary_provider(Arc::new(JoiningSummarizer));
+        let first = body(service.store_memory(params(json!({"content": "Deploys on Tuesdays"}))).await);
+        let second = body(service.store_memory(params(json!({"content": "No deploys on Fridays"}))).await);
+
+        let summary = body(service.summarize_memories(params(json!({}))).await);
+        assert_eq!(summary["memory_count"], 2, "{}", summary);
+        assert_eq!(summary["model"], "joining");
+        assert!(summary["stored_id"].is_null());
+        let text = summary["summary"].as_str().unwrap();
+        assert!(text.contains("Deploys on Tuesdays") && text.contains("No deploys on Fridays"), "{}", text);
+        let mut sources: Vec<&str> = summary["source_ids"].as_array().unwrap().iter().map(|id| id.as_str().unwrap()).collect();
+        sources.sort_unstable();
+        let mut expected = [first["id"].as_str().unwrap(), second["id"].as_str().unwrap()];
+        expected.sort_unstable();
+        assert_eq!(sources, expected);
+
+        let stored = body(service.summarize_memories(params(json!({"store": true}))).await);
+        let fetched = body(service.get_memory(params(json!({"id": stored["stored_id"]}))).await);
+        assert_eq!(fetched["content"], stored["summary"]);
+        assert_eq!(fetched["type_hint"], "summary");
+        assert_eq!(fetched["source"], "summarize_memories");
+    }
+
+    #[tokio::test]
+    async fn summarize_reports_provider_failures() {
+        let unconfigured = service();
+        unconfigured.store_memory(params(json!({"content": "Deploys on Tuesdays"}))).await.unwrap();
+        let missing = body(unconfigured.summarize_memories(params(json!({}))).await);
+        assert_eq!(missing["code"], codes::PROVIDER_UNAVAILABLE);
+
+        let service = service().with_summary_provider(Arc::new(FailingSummarizer));
+        let empty = body(service.summarize_memories(params(json!({}))).await);
+        assert!(empty["summary"].is_null(), "nothing to summarize never reaches the provider");
+        assert_eq!(empty["memory_count"], 0);
+
+        service.store_memory(params(json!({"content": "Deploys on Tuesdays"}))).await.unwrap();
+        let failed = body(service.summarize_memories(params(json!({"store": true}))).await);
+        assert_eq!(failed["code"], codes::PROVIDER_ERROR);
+        assert!(failed["error"].as_str().unwrap().contains("connection refused"), "{}", failed);
+        let listed = body(service.list_memories(params(json!({"type_hint": "summary"}))).await);
+        assert_eq!(listed["memories"], json!([]), "a failed summary stores nothing");
+    }
+
+    /// Turns each user line of a transcript chunk into a memory, and records every chunk.
+    #[derive(Default)]
+    struct TranscriptExtractor {
+        chunks: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::extraction::ExtractionProvider for TranscriptExtractor {
+        async fn extract(&self, _content: &str) -> Result<crate::extraction::ExtractionResult, crate::extraction::ExtractionError> {
+            Ok(crate::extraction::ExtractionResult { entities: Vec::new(), facts: Vec::new(), type_hint: None })
+        }
+
+        async fn extract_conversation(&self, transcript: &str) -> Result<Vec<crate::extraction::ExtractedMemory>, crate::extraction::ExtractionError> {
+            self.chunks.lock().unwrap().push(transcript.to_string());
+            Ok(transcript
+                .lines()
+                .filter_map(|line| {
+                    let (turn, rest) = line.strip_prefix('[')?.split_once("] ")?;
+                    let content = rest.strip_prefix("user: ")?;
+                    Some(crate::extraction::ExtractedMemory {
+                        content: content.to_string(),
+                        type_hint: "preference".to_string(),
+                        turn: turn.parse().ok(),
+                    })
+                })
+                .collect())
+        }
+
+        async fn detect_contradictions(
+            &self,
+            _facts: &[String],
+            _candidates: &[(String, String)],
+        ) -> Result<Vec<crate::extraction::Contradiction>, crate::extraction::ExtractionError> {
+            Ok(Vec::new())
+        }
+
+        fn model_name(&self) -> &str {
+            "transcript"
+        }
+    }
+
+    #[tokio::test]
+    async fn ingest_conversation_stores_one_memory_per_extracted_turn() {
+        let first_two = "[0] user: Prefers tea over coffee\n[1] assistant: Noted, tea it is\n";
+        let extractor = Arc::new(TranscriptExtractor::default());
+        let service = service().with_conversation_extractor(extractor.clone(), first_two.len());
+
+        let result = body(service.ingest_conversation(params(json!({
+            "turns": [
+                {"role": "user", "content": "Prefers tea over coffee", "timestamp": "2026-01-05T10:00:00Z", "message_id": "m-0"},
+                {"role": "assistant", "content": "  Noted, tea it is  ", "message_id": "m-1"},
+                {"role": "user", "content": "Works from Berlin", "message_id": "m-2"}
+            ],
+            "source": "chat-import",
+            "tags": ["chat"],
+            "namespace": "ingest",
+            "conversation_id": "c-1"
+        }))).await);
+
+        // Turns are numbered across the whole conversation and split only between turns
+        assert_eq!(
+            *extractor.chunks.lock().unwrap(),
+            [first_two.to_string(), "[2] user: Works from Berlin\n".to_string()]
+        );
+        assert_eq!(result["chunks"], 2);
+        assert_eq!(result["failed_chunks"], 0);
+        assert_eq!(result["stored"], 2, "{}", result);
+
+        let stored: Vec<&str> = result["memories"].as_array().unwrap().iter().map(|m| m["content"].as_str().unwrap()).collect();
+        assert_eq!(stored, ["Prefers tea over coffee", "Works from Berlin"]);
+        let listed = body(service.list_memories(params(json!({"namespace": "ingest"}))).await);
+        assert_eq!(listed["memories"].as_array().unwrap().len(), 2, "the assistant turn yields nothing");
+
+        for (memory, message_id) in result["memories"].as_array().unwrap().iter().zip(["m-0", "m-2"]) {
+            let fetched = body(service.get_memory(params(json!({"id": memory["id"], "namespace": "ingest"}))).await);
+            assert_eq!(fetched["source"], "chat-import");
+            assert_eq!(fetched["tags"], json!(["chat"]));
+            assert_eq!(fetched["type_hint"], "preference");
+            assert_eq!(fetched["origin_refs"], json!([{"conversation_id": "c-1", "message_id": message_id}]));
+        }
+        let first = body(service.get_memory(params(json!({"id": result["memories"][0]["id"], "namespace": "ingest"}))).await);
+        let created_at = DateTime::parse_from_rfc3339(first["created_at"].as_str().unwrap()).unwrap();
+        assert_eq!(created_at, DateTime::parse_from_rfc3339("2026-01-05T10:00:00Z").unwrap(), "the turn's timestamp becomes created_at");
+    }
+
+    #[tokio::test]
+    async fn namespaces_isolate_delete_and_list() {
+        let service = service();
+        let stored = body(service.store_memory(params(json!({"content": "team a note", "namespace": "a"}))).await);
+        let id = stored["id"].as_str().unwrap();
+
+        let foreign = body(service.delete_memory(params(json!({"id": id, "namespace": "b"}))).await);
+        assert_eq!(foreign["code"], codes::NOT_FOUND);
+
+        let listed = body(service.list_memories(params(json!({"namespace": "b"}))).await);
+        assert_eq!(listed["memories"], json!([]));
+
+        let deleted = body(service.delete_memory(params(json!({"id": id, "namespace": "a"}))).await);
+        assert_eq!(deleted["trashed"], true);
+        let listed = body(service.list_memories(params(json!({"namespace": "a"}))).await);
+        assert_eq!(listed["memories"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn namespaces_isolate_get() {
+        let service = service();
+        let stored = body(service.store_memory(params(json!({"content": "team a note", "namespace": "a"}))).await);
+        let id = stored["id"].as_str().unwrap();
+
+        let foreign = body(service.get_memory(params(json!({"id": id, "namespace": "b"}))).await);
+        assert_eq!(foreign["code"], codes::NOT_FOUND);
+        let default = body(service.get_memory(params(json!({"id": id}))).await);
+        assert_eq!(default["code"], codes::NOT_FOUND, "no namespace means the server's default one");
+
+        let fetched = body(service.get_memory(params(json!({"id": id, "namespace": "a"}))).await);
+        assert_eq!(fetched["content"], "team a note");
+    }
+
+    #[tokio::test]
+    async fn namespaces_isolate_update() {
+        let service = service();
+        let stored = body(service.store_memory(params(json!({"content": "team a note", "namespace": "a"}))).await);
+        let id = stored["id"].as_str().unwrap();
+
+        let foreign = body(service.update_memory(params(json!({"id": id, "content": "rewritten by b", "namespace": "b"}))).await);
+        assert_eq!(foreign["code"], codes::NOT_FOUND);
+        let fetched = body(service.get_memory(params(json!({"id": id, "namespace": "a"}))).await);
+        assert_eq!(fetched["content"], "team a note", "a foreign update leaves the memory untouched");
+
+        let updated = body(service.update_memory(params(json!({"id": id, "content": "rewritten by a", "namespace": "a"}))).await);
+        assert_eq!(updated["content"], "rewritten by a");
+    }
+
+    #[tokio::test]
+    async fn search_falls_back_to_vector_search() {
+        let service = service();
+        service.store_memory(params(json!({"content": "Prefers coffee in the morning"}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Deploys Rust services"}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Rust in another namespace", "namespace": "other"}))).await.unwrap();
+
+        let result = body(service.search_memory(params(json!({"query": "rust", "limit": 1}))).await);
+        assert_eq!(result["fusion"], "vector");
+        assert_eq!(result["memories"][0]["content"], "Deploys Rust services");
+        assert_eq!(result["has_more"], true);
+
+        let next = body(service.search_memory(params(json!({"query": "rust", "cursor": result["next_cursor"]}))).await);
+        assert_eq!(next["memories"][0]["content"], "Prefers coffee in the morning");
+        assert_eq!(next["has_more"], false);
+    }
+
+    #[tokio::test]
+    async fn search_validates_conversation_context() {
+        let service = service();
+        let turns: Vec<serde_json::Value> =
+            (0..=MAX_CONTEXT_TURNS).map(|i| json!({"role": "user", "content": format!("turn {}", i)})).collect();
+        let too_many = body(service.search_memory(params(json!({"query": "rust", "conversation_context": turns}))).await);
+        assert_eq!(too_many["field"], "conversation_context");
+
+        let context = json!([{"role": "user", "content": "We use Rust"}, {"role": "assistant", "content": "  "}]);
+        let empty = body(service.search_memory(params(json!({"query": "why it?", "conversation_context": context}))).await);
+        assert_eq!(empty["field"], "conversation_context[1].content");
+    }
+
+    #[tokio::test]
+    async fn rerank_blends_within_the_window_and_counts_moves() {
+        let service = service();
+        let mut hits = Vec::new();
+        for (content, salience) in [("a", 0.9), ("b", 0.6), ("c", 0.3), ("d", 0.0)] {
+            let memory = service.store.store(CreateMemory { content: content.to_string(), ..Default::default() }).await.unwrap();
+            hits.push(ScoredHit { memory, rrf_score: 0.0, salience_score: salience, match_source: "hybrid".to_string(), breakdown: None });
+        }
+        let id = |hits: &[ScoredHit], content: &str| hits.iter().find(|h| h.memory.content == content).unwrap().memory.id.clone();
+        let ranked: Vec<RankedResult> = ["c", "b", "a"]
+            .iter()
+            .enumerate()
+            .map(|(i, content)| RankedResult { id: id(&hits, content), llm_rank: i + 1 })
+            .collect();
+        let order = |hits: &[ScoredHit]| hits.iter().map(|h| h.memory.content.clone()).collect::<Vec<_>>();
+
+        let mut salience_only = hits.clone();
+        blend_rerank(&mut salience_only, 3, &ranked, 0.0);
+        assert_eq!(order(&salience_only), ["a", "b", "c", "d"], "blend 0 keeps salience order");
+
+        let before = blend_rerank(&mut hits, 3, &ranked, 1.0);
+        assert_eq!(order(&hits), ["c", "b", "a", "d"], "only the top_n window is re-ranked");
+        assert!((hits[0].salience_score - 0.5).abs() < 1e-9, "rank 1 scores 1 / (1 + 1)");
+        assert_eq!(hits[3].salience_score, 0.0, "hits past the window keep their score");
+
+        let before_ids: Vec<&str> = before.iter().map(|(id, _)| id.as_str()).collect();
+        let after_ids: Vec<&str> = hits[..3].iter().map(|h| h.memory.id.as_str()).collect();
+        assert_eq!(rerank_moves(&before_ids, &after_ids), 2, "a and c swapped, b stayed");
+        assert_eq!(rerank_moves(&before_ids, &before_ids), 0);
+    }
+
+    #[tokio::test]
+    async fn min_relevance_drops_weak_matches_instead_of_padding() {
+        let service = service();
+        service.store_memory(params(json!({"content": "Prefers coffee in the morning"}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Deploys Rust services"}))).await.unwrap();
+
+        let result = body(service.search_memory(params(json!({"query": "rust", "min_relevance": 0.5}))).await);
+        assert_eq!(result["total_results"], 1);
+        assert_eq!(result["memories"][0]["content"], "Deploys Rust services");
+        assert_eq!(result["filtered_out"], 1);
+        assert_eq!(result["has_more"], false);
+
+        let none = body(service.search_memory(params(json!({"query": "tea", "min_relevance": 0.5}))).await);
+        assert_eq!(none["total_results"], 0);
+        assert!(none["hint"].as_str().unwrap().contains("filtered out"));
+
+        let invalid = body(service.search_memory(params(json!({"query": "rust", "min_relevance": 1.5}))).await);
+        assert_eq!(invalid["field"], "min_relevance");
+    }
+
+    #[tokio::test]
+    async fn exclude_filters_leave_out_matching_memories() {
+        let service = service();
+        service.store_memory(params(json!({"content": "Rust scratch idea", "tags": ["scratch", "rust"]}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Rust from the cli", "source": "cli"}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Deploys Rust services", "type_hint": "decision"}))).await.unwrap();
+
+        let result = body(service.search_memory(params(json!({"query": "rust", "exclude_tags": ["scratch"]}))).await);
+        assert_eq!(result["total_results"], 2);
+
+        let result = body(service.search_memory(params(json!({
+            "query": "rust",
+            "exclude_tags": ["scratch"],
+            "exclude_sources": ["cli"]
+        }))).await);
+        assert_eq!(result["memories"][0]["content"], "Deploys Rust services");
+        assert_eq!(result["total_results"], 1);
+
+        let listed = body(service.list_memories(params(json!({"exclude_type_hints": ["decision"], "exclude_sources": ["cli"]}))).await);
+        assert_eq!(listed["memories"].as_array().unwrap().len(), 1);
+        assert_eq!(listed["memories"][0]["content"], "Rust scratch idea");
+    }
+
+    #[tokio::test]
+    async fn pinned_memories_lead_search_results_and_the_primer() {
+        let service = service();
+        service.store_memory(params(json!({"content": "Deploys Rust services"}))).await.unwrap();
+        let stored = body(service.store_memory(params(json!({"content": "Always answer in British English"}))).await);
+        let id = stored["id"].as_str().unwrap().to_string();
+        service.store_memory(params(json!({"content": "Pinned elsewhere", "namespace": "other"}))).await.unwrap();
+
+        let pinned = body(service.pin_memory(params(json!({"id": id}))).await);
+        assert_eq!(pinned["pinned"], true);
+
+        let result = body(service.search_memory(params(json!({"query": "rust", "limit": 1, "min_relevance": 0.5}))).await);
+        assert_eq!(result["total_results"], 2, "pinned memories come on top of the limit");
+        assert_eq!(result["memories"][0]["id"], id.as_str());
+        assert_eq!(result["memories"][0]["match_source"], "pinned");
+        assert_eq!(result["memories"][1]["content"], "Deploys Rust services");
+
+        let filtered = body(service.search_memory(params(json!({"query": "rust", "type_hint": "preference"}))).await);
+        assert_eq!(filtered["total_results"], 0, "pinned memories still honor hard filters");
+
+        let primer = service.session_primer_text().await.unwrap();
+        assert!(primer.starts_with("Pinned:\n"));
+        assert_eq!(primer.matches("British English").count(), 1);
+
+        let unpinned = body(service.update_memory(params(json!({"id": id, "pinned": false}))).await);
+        assert_eq!(unpinned["pinned"], false);
+        let result = body(service.search_memory(params(json!({"query": "rust", "limit": 1}))).await);
+        assert_eq!(result["memories"][0]["content"], "Deploys Rust services");
+    }
+
+    #[tokio::test]
+    async fn session_primer_applies_resources_config() {
+        let service = service().with_resources_config(crate::config::ResourcesConfig {
+            count: 1,
+            type_hints: vec!["fact".to_string()],
+            max_chars: 0,
+        });
+        let create = |content: &str, type_hint: &str, days_ago: i64| CreateMemory {
+            content: content.to_string(),
+            type_hint: type_hint.to_string(),
+            created_at: Some(Utc::now() - chrono::Duration::days(days_ago)),
+            ..Default::default()
+        };
+        service.store.store(create("Older fact", "fact", 30)).await.unwrap();
+        service.store.store(create("Newer fact", "fact", 1)).await.unwrap();
+        service.store.store(create("A preference", "preference", 0)).await.unwrap();
+
+        let primer = service.session_primer_text().await.unwrap();
+        assert!(primer.contains("Newer fact"), "{}", primer);
+        assert!(!primer.contains("Older fact") && !primer.contains("A preference"), "{}", primer);
+    }
+
+    #[tokio::test]
+    async fn content_scrubber_redacts_stored_and_updated_content() {
+        let patterns = crate::privacy::patterns::PatternFilter::new(
+            &["email".to_string()],
+            &["Project Falcon".to_string()],
+            &Default::default(),
+        )
+        .unwrap();
+        let scrubber = crate::privacy::ContentScrubber::new(vec![Arc::new(patterns)], false);
+        let service = service().with_content_scrubber(Arc::new(scrubber));
+
+        let stored = body(service.store_memory(params(json!({"content": "Ann (ann@example.com) leads Project Falcon"}))).await);
+        assert_eq!(stored["content"], "Ann ([REDACTED:EMAIL]) leads [REDACTED:DENY_LIST]");
+        assert_eq!(stored["redactions"], 2);
+
+        let id = stored["id"].as_str().unwrap();
+        let updated = body(service.update_memory(params(json!({"id": id, "content": "Ask bob@example.com"}))).await);
+        assert_eq!(updated["content"], "Ask [REDACTED:EMAIL]");
+
+        let clean = body(service.store_memory(params(json!({"content": "Nothing sensitive"}))).await);
+        assert!(clean.get("redactions").is_none());
+    }
+
+    #[test]
+    fn render_primer_drops_entries_past_the_budget() {
+        let sections = || vec![
+            (Some("Pinned:"), vec!["a".repeat(10)]),
+            (None, vec!["b".repeat(10), "c".repeat(10)]),
+        ];
+        let full = render_primer(sections(), 0);
+        assert_eq!(full, format!("Pinned:\n{}\n\n{}\n{}", "a".repeat(10), "b".repeat(10), "c".repeat(10)));
+        assert_eq!(render_primer(sections(), 32), format!("Pinned:\n{}\n\n{}", "a".repeat(10), "b".repeat(10)));
+        assert_eq!(render_primer(sections(), 5), "Pinne");
+        assert_eq!(render_primer(vec![(Some("Pinned:"), Vec::new())], 0), "");
+    }
+
+    #[tokio::test]
+    async fn vector_search_honors_type_hint_and_source() {
+        let service = service();
+        service
+            .store_memory(params(json!({"content": "Rust for services", "type_hint": "fact", "source": "cli"})))
+            .await
+            .unwrap();
+        service
+            .store_memory(params(json!({"content": "Enjoys Rust", "type_hint": "preference", "source": "chat"})))
+            .await
+            .unwrap();
+
+        let by_type = body(service.search_memory(params(json!({"query": "rust", "type_hint": "preference"}))).await);
+        assert_eq!(by_type["memories"].as_array().unwrap().len(), 1);
+        assert_eq!(by_type["memories"][0]["content"], "Enjoys Rust");
+
+        let by_source = body(service.search_memory(params(json!({"query": "rust", "source": "cli"}))).await);
+        assert_eq!(by_source["memories"].as_array().unwrap().len(), 1);
+        assert_eq!(by_source["memories"][0]["content"], "Rust for services");
+    }
+
+    #[tokio::test]
+    async fn reloaded_salience_config_applies_to_the_next_call() {
+        let live = crate::live_config::LiveConfig::new(crate::config::Config::default());
+        let service = service().with_live_config(live.clone());
+        let before = body(service.mark_used(params(json!({"ids": ["a"]}))).await);
+        assert_eq!(before["count"], 0, "auto-reinforcement starts disabled");
+
+        let mut next = crate::config::Config::default();
+        next.salience.auto_reinforce_on_search = true;
+        assert_eq!(live.reload(&next).unwrap().applied, vec!["salience"]);
+
+        let after = body(service.mark_used(params(json!({"ids": ["a"]}))).await);
+        assert_eq!(after["code"], codes::BACKEND_UNSUPPORTED, "now reinforces, which needs PostgreSQL");
+    }
+
+    #[tokio::test]
+    async fn daily_digest_groups_recent_memories_by_type_and_source() {
+        let service = service();
+        let create = |content: &str, type_hint: &str, hours_ago: i64| CreateMemory {
+            content: content.to_string(),
+            type_hint: type_hint.to_string(),
+            source: "cli".to_string(),
+            created_at: Some(Utc::now() - chrono::Duration::hours(hours_ago)),
+            ..Default::default()
+        };
+        service.store.store(create("Chose Postgres for storage", "decision", 2)).await.unwrap();
+        service.store.store(create("Deploys run on Fridays", "fact", 3)).await.unwrap();
+        service.store.store(create("Old news from last week", "fact", 24 * 7)).await.unwrap();
+
+        let digest = service.daily_digest().await.unwrap();
+        assert!(digest.starts_with("Daily digest: 2 memories from the last 24h"), "{}", digest);
+        let decision = digest.find("[decision]").expect("decision group");
+        let fact = digest.find("[fact]").expect("fact group");
+        assert!(decision < fact, "groups are alphabetical");
+        assert!(digest.contains("Source: cli (1)"));
+        assert!(!digest.contains("Old news"), "memories outside the window are left out");
+
+        let empty = service.with_digest_config(crate::config::DigestConfig { window_hours: 1, ..Default::default() });
+        assert!(empty.daily_digest().await.unwrap().starts_with("No memories created in the last 1h"));
+    }
+
+    #[tokio::test]
+    async fn memory_type_tools_validate_before_needing_postgres() {
+        let service = service();
+        let bad_name = body(service.define_memory_type(params(json!({"name": "a type", "fields": [{"name": "x"}]}))).await);
+        assert_eq!(bad_name["field"], "name");
+
+        let no_fields = body(service.define_memory_type(params(json!({"name": "contact", "fields": []}))).await);
+        assert_eq!(no_fields["field"], "fields");
+
+        let unsupported = body(service.store_structured_memory(params(json!({"memory_type": "contact", "fields": {}}))).await);
+        assert_eq!(unsupported["code"], codes::BACKEND_UNSUPPORTED);
+    }
+
+    #[tokio::test]
+    async fn batch_reinforcement_validates_ids_and_needs_postgres() {
+        let service = service();
+        let empty = body(service.reinforce_memories(params(json!({"ids": []}))).await);
+        assert_eq!(empty["code"], codes::VALIDATION);
+        assert_eq!(empty["field"], "ids");
+
+        let blank = body(service.mark_used(params(json!({"ids": ["a", " "]}))).await);
+        assert_eq!(blank["field"], "ids");
+
+        let unsupported = body(service.reinforce_memories(params(json!({"ids": ["a"]}))).await);
+        assert_eq!(unsupported["code"], codes::BACKEND_UNSUPPORTED);
+
+        // Auto-reinforcement is off by default: mark_used acknowledges without touching storage
+        let acknowledged = body(service.mark_used(params(json!({"ids": ["a"]}))).await);
+        assert_eq!(acknowledged["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn bulk_update_previews_then_retags_matching_memories() {
+        let service = service();
+        service.store_memory(params(json!({"content": "Rust note", "type_hint": "note", "tags": ["lang", "old"]}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Coffee note", "type_hint": "note"}))).await.unwrap();
+        service.store_memory(params(json!({"content": "Tea fact", "type_hint": "fact", "tags": ["old"]}))).await.unwrap();
+
+        let nothing = body(service.bulk_update_memories(params(json!({"type_hint": "note"}))).await);
+        assert_eq!(nothing["code"], codes::VALIDATION);
+
+        let preview = body(service.bulk_update_memories(params(json!({"type_hint": "note", "set_source": "import"}))).await);
+        assert_eq!(preview["matched"], 2);
+        assert_eq!(preview["updated"], false);
+
+        let update = json!({
+            "type_hint": "note",
+            "set_type_hint": "fact",
+            "add_tags": ["rust", "lang"],
+            "remove_tags": ["old"],
+            "confirm": true
+        });
+        let applied = body(service.bulk_update_memories(params(update)).await);
+        assert_eq!(applied["updated"], 2);
+
+        let facts = body(service.list_memories(params(json!({"type_hint": "fact"}))).await);
+        let mut tags: Vec<_> = facts["memories"].as_array().unwrap().iter().map(|m| m["tags"].clone()).collect();
+        tags.sort_by_key(|t| t.to_string());
+        assert_eq!(tags, vec![json!(["lang", "rust"]), json!(["old"]), json!(["rust", "lang"])]);
+    }
 }
@@ -0,0 +1,87 @@
+//! Graceful shutdown of the background pipelines.
+//!
+//! When the client disconnects (or SIGINT/SIGTERM arrives), the embedding, extraction, and
+//! consolidation workers stop accepting and starting jobs, finish the ones already in
+//! flight, and write their statuses before the process exits. Queued jobs are not run:
+//! their memories are still 'pending' in the database, so the startup backfill picks them
+//! up on the next run. Queued consolidation checks are dropped (run `memcp consolidate scan` to
+//! catch up). Draining is bounded by `server.shutdown_timeout_secs`.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+/// Shared shutdown signal plus the worker tasks to wait for. Cheap to clone; clones
+/// observe the same signal.
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    token: CancellationToken,
+    tasks: TaskTracker,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether shutdown has begun — new jobs must be refused.
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown begins.
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Spawn a worker task that `drain` waits for.
+    pub fn spawn<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.tasks.spawn(task);
+    }
+
+    /// Signal shutdown and wait up to `timeout` for every spawned task to finish.
+    /// Returns false when the timeout elapsed with tasks still running.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.token.cancel();
+        self.tasks.close();
+        tokio::time::timeout(timeout, self.tasks.wait()).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn drain_waits_for_in_flight_work_to_finish() {
+        let shutdown = Shutdown::new();
+        let finished = Arc::new(AtomicBool::new(false));
+        let (worker, done) = (shutdown.clone(), finished.clone());
+        shutdown.spawn(async move {
+            worker.cancelled().await;
+            // Work started before shutdown still completes
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            done.store(true, Ordering::SeqCst);
+        });
+
+        assert!(shutdown.drain(Duration::from_secs(5)).await);
+        assert!(shutdown.is_shutting_down());
+        assert!(finished.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn drain_gives_up_after_the_timeout() {
+        let shutdown = Shutdown::new();
+        shutdown.spawn(async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+        assert!(!shutdown.drain(Duration::from_millis(20)).await);
+    }
+}
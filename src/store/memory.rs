@@ -0,0 +1,698 @@
+//! In-memory MemoryStore for tests and embedded use.
+//!
+//! Keeps every memory in a HashMap behind a mutex and follows the PostgreSQL store's
+//! semantics for trash, restore, expiry, chunk cascades, list ordering, and cursors.
+//! With an embedding provider attached, memories are embedded inline on store and on
+//! content/tag updates, and search_similar is a brute-force cosine scan. Nothing is
+//! persisted, and revisions, links, sessions, and salience are not kept.
+
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::embedding::template::EmbeddingTemplate;
+use crate::embedding::EmbeddingProvider;
+use crate::errors::MemcpError;
+use crate::store::{
+    decode_cursor, encode_cursor, encode_search_cursor, BulkUpdate, CreateMemory, ListFilter, ListResult, Memory, MemoryStore,
+    SearchFilter, SearchHit, SearchResult, UpdateMemory,
+};
+
+/// A memory with its current embedding and the model that produced it.
+struct Entry {
+    memory: Memory,
+    embedding: Option<(String, Vec<f32>)>,
+}
+
+/// Process-local MemoryStore. Cheap to create; each instance is an independent store.
+#[derive(Default)]
+pub struct InMemoryStore {
+    entries: Mutex<HashMap<String, Entry>>,
+    embedder: Option<Arc<dyn EmbeddingProvider>>,
+    template: EmbeddingTemplate,
+}
+
+impl InMemoryStore {
+    /// Create an empty store. Memories stay `pending` and are not searchable.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Embed memories inline with `provider`, making them available to search_similar.
+    pub fn with_embedding_provider(mut self, provider: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedder = Some(provider);
+        self
+    }
+
+    /// Render embedding text with `template` instead of content followed by tags.
+    pub fn with_embedding_template(mut self, template: EmbeddingTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Embed a memory's content and tags. Returns the new embedding_status and vector.
+    async fn embed(&self, memory: &Memory) -> (String, Option<(String, Vec<f32>)>) {
+        let Some(ref provider) = self.embedder else {
+            return ("pending".to_string(), None);
+        };
+        match provider.embed(&self.template.render_memory(memory)).await {
+            Ok(vector) => ("complete".to_string(), Some((provider.model_name().to_string(), vector))),
+            Err(e) => {
+                tracing::warn!(memory_id = %memory.id, error = %e, "In-memory store failed to embed memory");
+                ("failed".to_string(), None)
+            }
+        }
+    }
+}
+
+/// Whether a memory satisfies the ListFilter predicates (cursor and limit excluded).
+///
+/// Mirrors the PostgreSQL list conditions: chunks are never matched on their own, and live
+/// matches exclude expired memories.
+fn matches_list_filter(memory: &Memory, filter: &ListFilter) -> bool {
+    let now = Utc::now();
+    let scoped = if filter.trashed {
+        memory.deleted_at.is_some()
+    } else {
+        memory.deleted_at.is_none() && memory.expires_at.is_none_or(|at| at > now)
+    };
+    memory.parent_id.is_none()
+        && scoped
+        && (!filter.archived || memory.archived_at.is_some())
+        && filter.namespace.as_ref().is_none_or(|ns| &memory.namespace == ns)
+        && filter.type_hint.as_ref().is_none_or(|th| &memory.type_hint == th)
+        && filter.source.as_ref().is_none_or(|src| &memory.source == src)
+        && filter.tags.as_ref().is_none_or(|tags| has_all_tags(memory, tags))
+        && filter.created_after.is_none_or(|at| memory.created_at > at)
+        && filter.created_before.is_none_or(|at| memory.created_at < at)
+        && filter.updated_after.is_none_or(|at| memory.updated_at > at)
+        && filter.updated_before.is_none_or(|at| memory.updated_at < at)
+        && filter.min_importance.is_none_or(|min| memory.importance >= min)
+        && filter.session_id.as_ref().is_none_or(|sid| memory.session_id.as_ref() == Some(sid))
+        && filter.origin_ref.as_ref().is_none_or(|r| r.matches_any(memory.origin_refs.as_ref()))
+        && filter.min_access_count.is_none_or(|min| memory.access_count >= min)
+        && filter.accessed_after.is_none_or(|at| memory.last_accessed_at.is_some_and(|last| last > at))
+        && (!filter.never_accessed || memory.access_count == 0)
+        && filter.embedding_status.as_ref().is_none_or(|status| &memory.embedding_status == status)
+        && filter.extraction_status.as_ref().is_none_or(|status| &memory.extraction_status == status)
+        && !is_excluded(memory, &filter.exclude_tags, &filter.exclude_sources, &filter.exclude_type_hints)
+}
+
+/// True when the memory carries any excluded tag or has an excluded source or type hint.
+fn is_excluded(memory: &Memory, tags: &[String], sources: &[String], type_hints: &[String]) -> bool {
+    sources.contains(&memory.source)
+        || type_hints.contains(&memory.type_hint)
+        || memory
+            .tags
+            .as_ref()
+            .and_then(|t| t.as_array())
+            .is_some_and(|arr| arr.iter().filter_map(|v| v.as_str()).any(|tag| tags.iter().any(|t| t == tag)))
+}
+
+/// Payload path predicates are evaluated by PostgreSQL's jsonpath engine, which this store
+/// does not have.
+fn reject_payload_path(payload_path: &Option<String>) -> Result<(), MemcpError> {
+    match payload_path {
+        Some(_) => Err(MemcpError::Validation {
+            message: "Payload path filters require the PostgreSQL backend".to_string(),
+            field: Some("payload_path".to_string()),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Whether a memory carries every tag in `tags` (JSONB containment in PostgreSQL).
+fn has_all_tags(memory: &Memory, tags: &[String]) -> bool {
+    let stored: Vec<&str> = memory
+        .tags
+        .as_ref()
+        .and_then(|t| t.as_array())
+        .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    tags.iter().all(|tag| stored.contains(&tag.as_str()))
+}
+
+/// Cosine similarity of two vectors (0.0 when either is all zeros or dimensions differ).
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        dot += *x as f64 * *y as f64;
+        norm_a += *x as f64 * *x as f64;
+        norm_b += *y as f64 * *y as f64;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+#[async_trait]
+impl MemoryStore for InMemoryStore {
+    async fn store(&self, input: CreateMemory) -> Result<Memory, MemcpError> {
+        let now = input.created_at.unwrap_or_else(Utc::now);
+        let mut memory = Memory {
+            id: Uuid::new_v4().to_string(),
+            content: input.content,
+            type_hint: input.type_hint,
+            source: input.source,
+            tags: input.tags.as_ref().map(|t| serde_json::json!(t)),
+            created_at: now,
+            updated_at: now,
+            last_accessed_at: None,
+            access_count: 0,
+            embedding_status: "pending".to_string(),
+            extracted_entities: None,
+            extracted_facts: None,
+            extraction_status: "pending".to_string(),
+            is_consolidated_original: false,
+            consolidated_into: None,
+            namespace: input.namespace,
+            deleted_at: None,
+            expires_at: input.expires_at,
+            archived_at: None,
+            importance: input.importance,
+            session_id: input.session_id,
+            parent_id: None,
+            chunk_index: None,
+            fields: input.fields,
+            payload: input.payload,
+            pinned: false,
+            origin_refs: (!input.origin_refs.is_empty()).then(|| serde_json::json!(input.origin_refs)),
+        };
+        let (status, embedding) = self.embed(&memory).await;
+        memory.embedding_status = status;
+
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(memory.id.clone(), Entry { memory: memory.clone(), embedding });
+        Ok(memory)
+    }
+
+    async fn get(&self, id: &str) -> Result<Memory, MemcpError> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries
+            .get_mut(id)
+            .filter(|e| e.memory.deleted_at.is_none())
+            .ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?;
+
+        // Return the record as read, then bump access stats (same as PostgreSQL's get + touch)
+        let memory = entry.memory.clone();
+        entry.memory.access_count += 1;
+        entry.memory.last_accessed_at = Some(Utc::now());
+        Ok(memory)
+    }
+
+    async fn update(&self, id: &str, input: UpdateMemory) -> Result<Memory, MemcpError> {
+        let mut memory = {
+            let entries = self.entries.lock().unwrap();
+            entries
+                .get(id)
+                .filter(|e| e.memory.deleted_at.is_none())
+                .map(|e| e.memory.clone())
+                .ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?
+        };
+
+        let reembed = input.content.is_some()
+            || input.tags.is_some()
+            || (input.type_hint.is_some() && self.template.uses_field("type_hint"))
+            || (input.source.is_some() && self.template.uses_field("source"))
+            || (input.payload.is_some() && self.template.uses_field("payload"));
+        memory.updated_at = Utc::now();
+        if let Some(content) = input.content {
+            memory.content = content;
+        }
+        if let Some(type_hint) = input.type_hint {
+            memory.type_hint = type_hint;
+        }
+        if let Some(source) = input.source {
+            memory.source = source;
+        }
+        if let Some(tags) = input.tags {
+            memory.tags = Some(serde_json::json!(tags));
+        }
+        if let Some(expires_at) = input.expires_at {
+            memory.expires_at = expires_at;
+        }
+        if let Some(importance) = input.importance {
+            memory.importance = importance;
+        }
+        if let Some(payload) = input.payload {
+            memory.payload = Some(payload);
+        }
+        if let Some(pinned) = input.pinned {
+            memory.pinned = pinned;
+        }
+
+        // Embed outside the lock; a concurrent delete wins over this update
+        let embedding = if reembed {
+            let (status, embedding) = self.embed(&memory).await;
+            memory.embedding_status = status;
+            Some(embedding)
+        } else {
+            None
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(id).ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?;
+        entry.memory = memory.clone();
+        if let Some(embedding) = embedding {
+            entry.embedding = embedding;
+        }
+        Ok(memory)
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), MemcpError> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.remove(id).is_none() {
+            return Err(MemcpError::NotFound { id: id.to_string() });
+        }
+        // Chunks go with their parent (ON DELETE CASCADE in PostgreSQL)
+        entries.retain(|_, e| e.memory.parent_id.as_deref() != Some(id));
+        Ok(())
+    }
+
+    async fn trash(&self, id: &str) -> Result<(), MemcpError> {
+        let now = Utc::now();
+        let mut trashed = 0;
+        for entry in self.entries.lock().unwrap().values_mut() {
+            let memory = &mut entry.memory;
+            if (memory.id == id || memory.parent_id.as_deref() == Some(id)) && memory.deleted_at.is_none() {
+                memory.deleted_at = Some(now);
+                trashed += 1;
+            }
+        }
+        if trashed == 0 {
+            return Err(MemcpError::NotFound { id: id.to_string() });
+        }
+        Ok(())
+    }
+
+    async fn restore(&self, id: &str) -> Result<Memory, MemcpError> {
+        let mut entries = self.entries.lock().unwrap();
+        let memory = entries
+            .get(id)
+            .map(|e| &e.memory)
+            .filter(|m| m.deleted_at.is_some() || m.archived_at.is_some())
+            .ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?;
+        let id = memory.id.clone();
+
+        let now = Utc::now();
+        for entry in entries.values_mut() {
+            if entry.memory.id == id || entry.memory.parent_id.as_deref() == Some(&id) {
+                entry.memory.deleted_at = None;
+                entry.memory.archived_at = None;
+                entry.memory.expires_at = entry.memory.expires_at.filter(|at| *at > now);
+            }
+        }
+        Ok(entries[&id].memory.clone())
+    }
+
+    async fn list(&self, filter: ListFilter) -> Result<ListResult, MemcpError> {
+        reject_payload_path(&filter.payload_path)?;
+        let limit = filter.limit.clamp(1, 100) as usize;
+        let cursor = filter.cursor.as_deref().map(decode_cursor).transpose()?;
+
+        let mut memories: Vec<Memory> = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .map(|e| &e.memory)
+            .filter(|m| matches_list_filter(m, &filter))
+            .filter(|m| {
+                cursor.as_ref().is_none_or(|(created_at, id)| {
+                    m.created_at < *created_at || (m.created_at == *created_at && m.id > *id)
+                })
+            })
+            .cloned()
+            .collect();
+        // Newest first, ties broken by id — the order the cursor encodes
+        memories.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| a.id.cmp(&b.id)));
+
+        let has_more = memories.len() > limit;
+        memories.truncate(limit);
+        let next_cursor = if has_more {
+            memories.last().map(|m| encode_cursor(&m.created_at, &m.id))
+        } else {
+            None
+        };
+
+        Ok(ListResult { memories, next_cursor })
+    }
+
+    async fn count_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError> {
+        reject_payload_path(&filter.payload_path)?;
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|e| matches_list_filter(&e.memory, filter))
+            .count() as u64)
+    }
+
+    async fn delete_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError> {
+        reject_payload_path(&filter.payload_path)?;
+        let mut entries = self.entries.lock().unwrap();
+        let doomed: Vec<String> = entries
+            .values()
+            .filter(|e| matches_list_filter(&e.memory, filter))
+            .map(|e| e.memory.id.clone())
+            .collect();
+        for id in &doomed {
+            entries.remove(id);
+        }
+        entries.retain(|_, e| e.memory.parent_id.as_ref().is_none_or(|parent| !doomed.contains(parent)));
+        Ok(doomed.len() as u64)
+    }
+
+    async fn trash_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError> {
+        reject_payload_path(&filter.payload_path)?;
+        // Only live memories can be trashed, regardless of filter.trashed
+        let live = ListFilter { trashed: false, ..filter.clone() };
+        let now = Utc::now();
+        let mut trashed = 0;
+        for entry in self.entries.lock().unwrap().values_mut() {
+            if matches_list_filter(&entry.memory, &live) {
+                entry.memory.deleted_at = Some(now);
+                trashed += 1;
+            }
+        }
+        Ok(trashed)
+    }
+
+    async fn update_matching(&self, filter: &ListFilter, update: &BulkUpdate) -> Result<Vec<Memory>, MemcpError> {
+        reject_payload_path(&filter.payload_path)?;
+        // Only live memories are updated, regardless of filter.trashed
+        let live = ListFilter { trashed: false, ..filter.clone() };
+        let now = Utc::now();
+        let mut updated: Vec<Memory> = Vec::new();
+        for entry in self.entries.lock().unwrap().values_mut() {
+            if !matches_list_filter(&entry.memory, &live) {
+                continue;
+            }
+            let memory = &mut entry.memory;
+            memory.updated_at = now;
+            if let Some(ref type_hint) = update.type_hint {
+                memory.type_hint = type_hint.clone();
+            }
+            if let Some(ref source) = update.source {
+                memory.source = source.clone();
+            }
+            if let Some(importance) = update.importance {
+                memory.importance = importance;
+            }
+            if update.changes_tags() {
+                memory.tags = Some(serde_json::json!(update.apply_tags(memory.tags.as_ref())));
+            }
+            updated.push(memory.clone());
+        }
+
+        // Re-embed outside the lock, as update() does for changes to the embedding text
+        let reembed = update.changes_tags()
+            || (update.type_hint.is_some() && self.template.uses_field("type_hint"))
+            || (update.source.is_some() && self.template.uses_field("source"));
+        if reembed {
+            for memory in &mut updated {
+                let (status, embedding) = self.embed(memory).await;
+                memory.embedding_status = status;
+                if let Some(entry) = self.entries.lock().unwrap().get_mut(&memory.id) {
+                    entry.memory.embedding_status = memory.embedding_status.clone();
+                    entry.embedding = embedding;
+                }
+            }
+        }
+        Ok(updated)
+    }
+
+    async fn touch(&self, id: &str) -> Result<(), MemcpError> {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(id) {
+            entry.memory.access_count += 1;
+            entry.memory.last_accessed_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn get_memories_by_ids(&self, ids: &[String]) -> Result<HashMap<String, Memory>, MemcpError> {
+        let entries = self.entries.lock().unwrap();
+        Ok(ids
+            .iter()
+            .filter_map(|id| entries.get(id).map(|e| (id.clone(), e.memory.clone())))
+            .collect())
+    }
+
+    async fn search_similar(&self, filter: &SearchFilter) -> Result<SearchResult, MemcpError> {
+        reject_payload_path(&filter.payload_path)?;
+        let now = Utc::now();
+        let query = filter.query_embedding.as_slice();
+
+        let mut hits: Vec<SearchHit> = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|e| {
+                let (model, vector) = e.embedding.as_ref()?;
+                let m = &e.memory;
+                let embedded = match filter.model {
+                    Some(ref wanted) => model == wanted,
+                    None => m.embedding_status == "complete",
+                };
+                let visible = embedded
+                    && m.deleted_at.is_none()
+                    && m.archived_at.is_none()
+                    && m.expires_at.is_none_or(|at| at > now)
+                    && !m.is_consolidated_original
+                    && filter.created_after.is_none_or(|at| m.created_at > at)
+                    && filter.created_before.is_none_or(|at| m.created_at < at)
+                    && filter.tags.as_ref().is_none_or(|tags| has_all_tags(m, tags))
+                    && filter.namespace.as_ref().is_none_or(|ns| &m.namespace == ns)
+                    && filter.type_hint.as_ref().is_none_or(|th| &m.type_hint == th)
+                    && filter.source.as_ref().is_none_or(|src| &m.source == src)
+                    && filter.origin_ref.as_ref().is_none_or(|r| r.matches_any(m.origin_refs.as_ref()))
+                    && filter.min_access_count.is_none_or(|min| m.access_count >= min)
+                    && filter.accessed_after.is_none_or(|at| m.last_accessed_at.is_some_and(|last| last > at))
+                    && (!filter.never_accessed || m.access_count == 0)
+                    && !is_excluded(m, &filter.exclude_tags, &filter.exclude_sources, &filter.exclude_type_hints);
+                visible.then(|| SearchHit {
+                    memory: m.clone(),
+                    similarity: cosine_similarity(query, vector).clamp(0.0, 1.0),
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.similarity
+                .partial_cmp(&a.similarity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.memory.id.cmp(&b.memory.id))
+        });
+
+        let total_matches = hits.len() as u64;
+        let hits: Vec<SearchHit> = hits
+            .into_iter()
+            .skip(filter.offset.max(0) as usize)
+            .take(filter.limit.max(0) as usize)
+            .collect();
+
+        let next_offset = filter.offset + filter.limit;
+        let has_more = next_offset < total_matches as i64;
+        Ok(SearchResult {
+            hits,
+            total_matches,
+            total_matches_exact: true,
+            next_cursor: has_more.then(|| encode_search_cursor(next_offset)),
+            has_more,
+        })
+    }
+
+    async fn pinned_memories(&self, filter: &SearchFilter) -> Result<Vec<Memory>, MemcpError> {
+        reject_payload_path(&filter.payload_path)?;
+        let now = Utc::now();
+
+        let mut memories: Vec<Memory> = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .map(|e| &e.memory)
+            .filter(|m| {
+                m.pinned
+                    && m.deleted_at.is_none()
+                    && m.archived_at.is_none()
+                    && m.expires_at.is_none_or(|at| at > now)
+                    && !m.is_consolidated_original
+                    && filter.created_after.is_none_or(|at| m.created_at > at)
+                    && filter.created_before.is_none_or(|at| m.created_at < at)
+                    && filter.tags.as_ref().is_none_or(|tags| has_all_tags(m, tags))
+                    && filter.namespace.as_ref().is_none_or(|ns| &m.namespace == ns)
+                    && filter.type_hint.as_ref().is_none_or(|th| &m.type_hint == th)
+                    && filter.source.as_ref().is_none_or(|src| &m.source == src)
+                    && filter.origin_ref.as_ref().is_none_or(|r| r.matches_any(m.origin_refs.as_ref()))
+                    && filter.min_access_count.is_none_or(|min| m.access_count >= min)
+                    && filter.accessed_after.is_none_or(|at| m.last_accessed_at.is_some_and(|last| last > at))
+                    && (!filter.never_accessed || m.access_count == 0)
+                    && !is_excluded(m, &filter.exclude_tags, &filter.exclude_sources, &filter.exclude_type_hints)
+            })
+            .cloned()
+            .collect();
+        memories.sort_by(|a, b| b.updated_at.cmp(&a.updated_at).then_with(|| a.id.cmp(&b.id)));
+        memories.truncate(filter.limit.max(0) as usize);
+        Ok(memories)
+    }
+}
+
+/// Deterministic embedder for unit tests: one dimension per keyword in KEYWORDS.
+#[cfg(test)]
+pub(crate) struct KeywordEmbedder;
+
+#[cfg(test)]
+impl KeywordEmbedder {
+    pub(crate) const KEYWORDS: [&'static str; 4] = ["rust", "postgres", "coffee", "tea"];
+}
+
+#[cfg(test)]
+#[async_trait]
+impl EmbeddingProvider for KeywordEmbedder {
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, crate::embedding::EmbeddingError> {
+        let text = text.to_lowercase();
+        Ok(Self::KEYWORDS.iter().map(|k| if text.contains(k) { 1.0 } else { 0.0 }).collect())
+    }
+
+    fn model_name(&self) -> &str {
+        "keywords"
+    }
+
+    fn dimension(&self) -> usize {
+        Self::KEYWORDS.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(content: &str, namespace: &str) -> CreateMemory {
+        CreateMemory { content: content.to_string(), namespace: namespace.to_string(), ..Default::default() }
+    }
+
+    #[tokio::test]
+    async fn list_paginates_newest_first_and_skips_trash() {
+        let store = InMemoryStore::new();
+        let mut ids = Vec::new();
+        for i in 0..5 {
+            let input = CreateMemory {
+                created_at: Some(Utc::now() - chrono::Duration::minutes(10 - i)),
+                ..create(&format!("memory {}", i), "default")
+            };
+            ids.push(store.store(input).await.unwrap().id);
+        }
+        store.trash(&ids[4]).await.unwrap();
+
+        let first = store.list(ListFilter { limit: 2, ..Default::default() }).await.unwrap();
+        assert_eq!(first.memories.iter().map(|m| &m.id).collect::<Vec<_>>(), vec![&ids[3], &ids[2]]);
+        let second = store
+            .list(ListFilter { limit: 2, cursor: first.next_cursor, ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(second.memories.iter().map(|m| &m.id).collect::<Vec<_>>(), vec![&ids[1], &ids[0]]);
+        assert!(second.next_cursor.is_none());
+
+        let trashed = ListFilter { trashed: true, ..Default::default() };
+        assert_eq!(store.count_matching(&trashed).await.unwrap(), 1);
+        assert!(matches!(store.get(&ids[4]).await, Err(MemcpError::NotFound { .. })));
+        store.restore(&ids[4]).await.unwrap();
+        assert_eq!(store.count_matching(&ListFilter::default()).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn get_bumps_access_count_and_update_applies_fields() {
+        let store = InMemoryStore::new();
+        let memory = store.store(create("original", "default")).await.unwrap();
+        assert_eq!(memory.embedding_status, "pending", "no embedder attached");
+
+        store.get(&memory.id).await.unwrap();
+        let update = UpdateMemory { content: Some("edited".to_string()), importance: Some(5), ..Default::default() };
+        let updated = store.update(&memory.id, update).await.unwrap();
+        assert_eq!(updated.content, "edited");
+        assert_eq!(updated.importance, 5);
+        assert_eq!(updated.access_count, 1);
+    }
+
+    #[tokio::test]
+    async fn list_filters_by_pipeline_status() {
+        let store = InMemoryStore::new().with_embedding_provider(Arc::new(KeywordEmbedder));
+        let earlier = Utc::now() - chrono::Duration::minutes(1);
+        let first = store
+            .store(CreateMemory { created_at: Some(earlier), ..create("Rust backend", "default") })
+            .await
+            .unwrap();
+        let second = store.store(create("Coffee", "default")).await.unwrap();
+
+        let complete = store
+            .list(ListFilter { embedding_status: Some("complete".to_string()), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(complete.memories.iter().map(|m| &m.id).collect::<Vec<_>>(), vec![&second.id, &first.id]);
+        let failed = ListFilter { embedding_status: Some("failed".to_string()), ..Default::default() };
+        assert_eq!(store.count_matching(&failed).await.unwrap(), 0);
+
+        let pending = ListFilter { extraction_status: Some("pending".to_string()), ..Default::default() };
+        assert_eq!(store.count_matching(&pending).await.unwrap(), 2);
+        let extracted = ListFilter { extraction_status: Some("complete".to_string()), ..pending };
+        assert_eq!(store.count_matching(&extracted).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn search_similar_ranks_by_cosine_within_namespace() {
+        let store = InMemoryStore::new().with_embedding_provider(Arc::new(KeywordEmbedder));
+        let rust = store.store(create("Rust and Postgres backend", "a")).await.unwrap();
+        let coffee = store.store(create("Prefers coffee over tea", "a")).await.unwrap();
+        store.store(create("Rust everywhere", "b")).await.unwrap();
+        assert_eq!(rust.embedding_status, "complete");
+
+        let query = KeywordEmbedder.embed("rust").await.unwrap();
+        let filter = SearchFilter {
+            query_embedding: pgvector::Vector::from(query),
+            limit: 1,
+            namespace: Some("a".to_string()),
+            ..Default::default()
+        };
+        let result = store.search_similar(&filter).await.unwrap();
+        assert_eq!(result.total_matches, 2);
+        assert_eq!(result.hits[0].memory.id, rust.id);
+        assert!(result.has_more);
+
+        let next = SearchFilter { offset: 1, ..filter };
+        let result = store.search_similar(&next).await.unwrap();
+        assert_eq!(result.hits[0].memory.id, coffee.id);
+        assert_eq!(result.hits[0].similarity, 0.0);
+    }
+
+    #[tokio::test]
+    async fn embedding_template_puts_metadata_in_the_embedded_text() {
+        let template = EmbeddingTemplate::parse("{type_hint}: {content}").unwrap();
+        let store = InMemoryStore::new()
+            .with_embedding_provider(Arc::new(KeywordEmbedder))
+            .with_embedding_template(template);
+        let drink = CreateMemory { type_hint: "tea".to_string(), ..create("Morning drink", "default") };
+        let drink = store.store(drink).await.unwrap();
+
+        let query = KeywordEmbedder.embed("tea").await.unwrap();
+        let filter = SearchFilter { query_embedding: pgvector::Vector::from(query), limit: 1, ..Default::default() };
+        let result = store.search_similar(&filter).await.unwrap();
+        assert_eq!(result.hits[0].memory.id, drink.id);
+        assert!(result.hits[0].similarity > 0.9, "type_hint was embedded with the content");
+
+        // Changing an embedded field re-embeds the memory
+        let update = UpdateMemory { type_hint: Some("coffee".to_string()), ..Default::default() };
+        store.update(&drink.id, update).await.unwrap();
+        let result = store.search_similar(&filter).await.unwrap();
+        assert_eq!(result.hits[0].similarity, 0.0);
+    }
+}
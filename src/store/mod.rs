@@ -1,17 +1,27 @@
 /// Memory store abstraction layer
 ///
 /// Provides the MemoryStore trait and associated types for CRUD operations on memories.
-/// The trait abstraction enables multiple database backends — currently PostgreSQL.
+/// The trait abstraction enables multiple database backends — PostgreSQL, plus an in-memory
+/// store for tests and embedded use (`memory-store` feature).
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use pgvector::Vector;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::errors::MemcpError;
 
+#[cfg(any(test, feature = "memory-store"))]
+pub mod memory;
 pub mod postgres;
 
+/// Namespace used when neither the caller nor the config names one.
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// Importance assigned to memories stored without one (scale 1-5).
+pub const DEFAULT_IMPORTANCE: i16 = 3;
+
 /// Represents a stored memory with all rich metadata fields.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Memory {
@@ -46,6 +56,88 @@ pub struct Memory {
     pub is_consolidated_original: bool,
     /// ID of the consolidated memory this was merged into (None if not consolidated)
     pub consolidated_into: Option<String>,
+    /// Isolation namespace — memories in different namespaces never see each other
+    pub namespace: String,
+    /// When the memory was moved to the trash (None = live memory)
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// When the memory expires (None = never). Expired memories are hidden from search/list.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When the decay archiver demoted the memory (None = active). Archived memories are
+    /// excluded from search until restored.
+    pub archived_at: Option<DateTime<Utc>>,
+    /// Caller-assigned importance, 1 (trivia) to 5 (critical); default 3
+    pub importance: i16,
+    /// Session the memory was stored in (None = not session-scoped)
+    pub session_id: Option<String>,
+    /// For a chunk of an oversized memory: the memory holding the full content
+    pub parent_id: Option<String>,
+    /// Position of this chunk within its parent (None for ordinary memories)
+    pub chunk_index: Option<i32>,
+    /// Field values of a structured memory, keyed by field name (None = unstructured)
+    pub fields: Option<serde_json::Value>,
+    /// Machine-readable attachment (JSON blob, URLs, code) carried alongside the content.
+    /// Not embedded unless embedding.text_template uses {payload}.
+    pub payload: Option<serde_json::Value>,
+    /// Pinned memories top every search whose hard filters they match and always appear in
+    /// the session primer, regardless of salience.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Where the memory came from, as a JSON array of origin references (see `OriginRef`).
+    /// A consolidated memory carries the references of every original it merged.
+    pub origin_refs: Option<serde_json::Value>,
+}
+
+/// embedding_status of a memory stored as chunks: the full content is never embedded,
+/// its child chunks are.
+pub const CHUNKED_STATUS: &str = "chunked";
+
+/// Pointer back to where a memory came from: the conversation and message it was said in,
+/// a file, or a URL. Every part is optional; at least one must be set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct OriginRef {
+    /// Conversation or chat thread ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+    /// Message ID within the conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    /// Source file path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// Source URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl OriginRef {
+    /// Whether no part is set.
+    pub fn is_empty(&self) -> bool {
+        self.conversation_id.is_none() && self.message_id.is_none() && self.file_path.is_none() && self.url.is_none()
+    }
+
+    /// Parse a memory's stored `origin_refs` array (entries that don't parse are skipped).
+    pub fn from_stored(origin_refs: Option<&serde_json::Value>) -> Vec<OriginRef> {
+        origin_refs
+            .and_then(|refs| refs.as_array())
+            .map(|refs| refs.iter().filter_map(|r| serde_json::from_value(r.clone()).ok()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether a stored `origin_refs` array holds a reference agreeing with every part set
+    /// here (JSONB containment in PostgreSQL).
+    pub fn matches_any(&self, origin_refs: Option<&serde_json::Value>) -> bool {
+        let parts = [
+            ("conversation_id", &self.conversation_id),
+            ("message_id", &self.message_id),
+            ("file_path", &self.file_path),
+            ("url", &self.url),
+        ];
+        origin_refs.and_then(|refs| refs.as_array()).is_some_and(|refs| {
+            refs.iter().any(|stored| {
+                parts.iter().all(|(key, want)| want.as_ref().is_none_or(|want| stored.get(key).and_then(|v| v.as_str()) == Some(want.as_str())))
+            })
+        })
+    }
 }
 
 /// Input type for creating a new memory.
@@ -67,6 +159,55 @@ pub struct CreateMemory {
     /// Used by benchmark harness for ingesting historical sessions.
     #[serde(default)]
     pub created_at: Option<DateTime<Utc>>,
+    /// Namespace the memory belongs to (default: "default")
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    /// Optional expiration timestamp. After this time the memory is hidden and later swept.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Importance from 1 (trivia) to 5 (critical) (default: 3)
+    #[serde(default = "default_importance")]
+    pub importance: i16,
+    /// Session to attach the memory to, as returned by start_session (optional)
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// Field values validated against a memory type (set by store_structured_memory)
+    #[serde(default)]
+    pub fields: Option<serde_json::Value>,
+    /// Structured payload stored with the memory (optional)
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+    /// Where the memory came from (optional; more than one for merged or imported memories)
+    #[serde(default)]
+    pub origin_refs: Vec<OriginRef>,
+    /// Whether extraction should also classify the memory's type_hint. Recorded on the
+    /// memory's outbox job (see `crate::outbox`); ignored when the outbox is off.
+    #[serde(default)]
+    pub classify: bool,
+}
+
+impl Default for CreateMemory {
+    fn default() -> Self {
+        CreateMemory {
+            content: String::new(),
+            type_hint: default_type_hint(),
+            source: default_source(),
+            tags: None,
+            created_at: None,
+            namespace: default_namespace(),
+            expires_at: None,
+            importance: DEFAULT_IMPORTANCE,
+            session_id: None,
+            fields: None,
+            payload: None,
+            origin_refs: Vec::new(),
+            classify: false,
+        }
+    }
+}
+
+fn default_importance() -> i16 {
+    DEFAULT_IMPORTANCE
 }
 
 fn default_type_hint() -> String {
@@ -77,6 +218,10 @@ fn default_source() -> String {
     "default".to_string()
 }
 
+fn default_namespace() -> String {
+    DEFAULT_NAMESPACE.to_string()
+}
+
 /// Input type for partially updating an existing memory.
 ///
 /// All fields are optional — only non-None fields are updated.
@@ -90,15 +235,225 @@ pub struct UpdateMemory {
     pub source: Option<String>,
     /// New tags (optional, replaces existing tags)
     pub tags: Option<Vec<String>>,
+    /// New expiration timestamp (optional; Some(None) removes the expiry)
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+    /// New importance, 1-5 (optional)
+    pub importance: Option<i16>,
+    /// New payload (optional, replaces the existing payload)
+    pub payload: Option<serde_json::Value>,
+    /// Pin or unpin the memory (optional)
+    pub pinned: Option<bool>,
+}
+
+/// Changes applied to every memory matched by `update_matching`.
+///
+/// None / empty fields are left unchanged. Tags are edited in place rather than replaced:
+/// `remove_tags` are dropped and `add_tags` appended, keeping the existing order.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct BulkUpdate {
+    /// New type hint (optional)
+    pub type_hint: Option<String>,
+    /// New source (optional)
+    pub source: Option<String>,
+    /// New importance, 1-5 (optional)
+    pub importance: Option<i16>,
+    /// Tags to add where missing
+    pub add_tags: Vec<String>,
+    /// Tags to remove where present
+    pub remove_tags: Vec<String>,
+}
+
+impl BulkUpdate {
+    /// Whether the update changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.type_hint.is_none()
+            && self.source.is_none()
+            && self.importance.is_none()
+            && !self.changes_tags()
+    }
+
+    /// Whether the update edits tags (which are part of the embedding text).
+    pub fn changes_tags(&self) -> bool {
+        !self.add_tags.is_empty() || !self.remove_tags.is_empty()
+    }
+
+    /// Apply the tag edits to a memory's tags: existing order kept, duplicates removed.
+    pub fn apply_tags(&self, tags: Option<&serde_json::Value>) -> Vec<String> {
+        let existing = tags.and_then(|t| t.as_array()).into_iter().flatten().filter_map(|t| t.as_str());
+        let mut result: Vec<String> = Vec::new();
+        for tag in existing.chain(self.add_tags.iter().map(String::as_str)) {
+            if !self.remove_tags.iter().any(|r| r == tag) && !result.iter().any(|t| t == tag) {
+                result.push(tag.to_string());
+            }
+        }
+        result
+    }
+}
+
+/// A snapshot of a memory taken just before an update replaced it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryRevision {
+    /// The memory this revision belongs to
+    pub memory_id: String,
+    /// 1-based revision number, increasing with each update
+    pub revision: i32,
+    /// Content at the time of the snapshot
+    pub content: String,
+    /// Type hint at the time of the snapshot
+    pub type_hint: String,
+    /// Source at the time of the snapshot
+    pub source: String,
+    /// Tags at the time of the snapshot
+    pub tags: Option<serde_json::Value>,
+    /// When this version became current
+    pub valid_from: DateTime<Utc>,
+    /// When this version was replaced
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// A distinct value with the number of live memories carrying it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// Distinct type_hints, sources, and tags across live memories, with counts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryFacets {
+    /// Total live memories considered
+    pub total: i64,
+    /// Every distinct type_hint, most common first
+    pub type_hints: Vec<FacetCount>,
+    /// Every distinct source, most common first
+    pub sources: Vec<FacetCount>,
+    /// The most common tags, truncated to the requested limit
+    pub tags: Vec<FacetCount>,
+}
+
+/// Counts, sizes, and distributions for capacity planning, from `memory_stats`.
+///
+/// Counts cover whole memories; the chunk rows of oversized memories are counted separately.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// Live memories (not trashed or expired), archived ones included
+    pub total: i64,
+    pub archived: i64,
+    pub trashed: i64,
+    /// Expired memories the sweeper hasn't removed yet
+    pub expired: i64,
+    /// Chunk rows stored for oversized memories
+    pub chunks: i64,
+    /// Live memories per type_hint and per source, most common first
+    pub by_type_hint: Vec<FacetCount>,
+    pub by_source: Vec<FacetCount>,
+    /// Live memories created by consolidation or merge_memories
+    pub consolidated: i64,
+    /// Live memories merged into a consolidated memory (hidden from search)
+    pub consolidated_originals: i64,
+    /// Average stored content length in characters (ciphertext length under encryption at rest)
+    pub avg_content_chars: Option<f64>,
+    pub oldest_created_at: Option<DateTime<Utc>>,
+    pub newest_created_at: Option<DateTime<Utc>>,
+    /// Current FSRS retrievability (0-1) of live memories
+    pub retrievability: Option<Percentiles>,
+    /// FSRS stability of live memories, in days
+    pub stability_days: Option<Percentiles>,
+    /// On-disk sizes; these cover every namespace
+    pub storage: StorageSizes,
+}
+
+/// Selected percentiles of a distribution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub p10: f64,
+    pub p25: f64,
+    pub p50: f64,
+    pub p75: f64,
+    pub p90: f64,
+}
+
+impl Percentiles {
+    /// Percentiles at 0.1, 0.25, 0.5, 0.75, 0.9, in that order (None unless exactly five).
+    pub fn from_slice(values: &[f64]) -> Option<Percentiles> {
+        match *values {
+            [p10, p25, p50, p75, p90] => Some(Percentiles { p10, p25, p50, p75, p90 }),
+            _ => None,
+        }
+    }
+}
+
+/// Table sizes in bytes, indexes and TOAST included.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageSizes {
+    pub memories_bytes: i64,
+    pub embeddings_bytes: i64,
+    pub fact_embeddings_bytes: i64,
+    pub database_bytes: i64,
+}
+
+/// Dead-letter record for a memory whose embedding has failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingFailure {
+    /// Memory whose embedding failed
+    pub memory_id: String,
+    /// Error message from the most recent failed attempt
+    pub last_error: String,
+    /// Total failed attempts recorded (across retries and retry-failed runs)
+    pub attempts: i32,
+    /// When the first failure was recorded
+    pub first_failed_at: DateTime<Utc>,
+    /// When the most recent failure was recorded
+    pub last_failed_at: DateTime<Utc>,
+}
+
+/// A typed, directed edge between two memories.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryLink {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+    /// Memory the edge starts from
+    pub source_id: String,
+    /// Memory the edge points to
+    pub target_id: String,
+    /// Relation type, e.g. "related_to", "supersedes", "works_at"
+    pub relation: String,
+    /// When the link was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// Link relation recorded by contradiction detection, from the newer memory to the older one
+/// it contradicts.
+pub const CONTRADICTS_RELATION: &str = "contradicts";
+
+/// A working session opened by start_session and closed by end_session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Unique identifier (UUID v4)
+    pub id: String,
+    /// Namespace the session's memories are stored in
+    pub namespace: String,
+    /// Optional caller-supplied label, e.g. "refactor auth module"
+    pub title: Option<String>,
+    /// When the session was opened
+    pub started_at: DateTime<Utc>,
+    /// When the session was closed (None = still open)
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Summary memory written by end_session (None = open, or nothing to summarize)
+    pub summary_memory_id: Option<String>,
 }
 
 /// Filter criteria for listing memories with cursor-based pagination.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListFilter {
+    /// Restrict to a single namespace (None = all namespaces)
+    pub namespace: Option<String>,
     /// Filter by type_hint (exact match)
     pub type_hint: Option<String>,
     /// Filter by source (exact match)
     pub source: Option<String>,
+    /// Match only memories carrying ALL of these tags
+    pub tags: Option<Vec<String>>,
     /// Filter memories created after this timestamp
     pub created_after: Option<DateTime<Utc>>,
     /// Filter memories created before this timestamp
@@ -111,19 +466,71 @@ pub struct ListFilter {
     pub limit: i64,
     /// Cursor from previous page for pagination
     pub cursor: Option<String>,
+    /// When true, match only trashed memories; when false (default), only live ones.
+    /// Live matches also exclude memories past their expires_at.
+    pub trashed: bool,
+    /// When true, match only archived memories; when false (default), archived and active alike.
+    pub archived: bool,
+    /// Match only memories with at least this importance
+    pub min_importance: Option<i16>,
+    /// Match only memories stored in this session
+    pub session_id: Option<String>,
+    /// Match only memories whose payload satisfies this SQL/JSON path predicate,
+    /// e.g. `$.language == "rust"` or `exists($.url)`
+    pub payload_path: Option<String>,
+    /// Match only memories with an origin reference agreeing with every part set here
+    pub origin_ref: Option<OriginRef>,
+    /// Match only memories retrieved at least this many times
+    pub min_access_count: Option<i64>,
+    /// Match only memories last retrieved after this timestamp
+    pub accessed_after: Option<DateTime<Utc>>,
+    /// When true, match only memories that were never retrieved
+    pub never_accessed: bool,
+    /// Skip memories carrying any of these tags (empty = exclude nothing)
+    pub exclude_tags: Vec<String>,
+    /// Skip memories from any of these sources
+    pub exclude_sources: Vec<String>,
+    /// Skip memories with any of these type hints
+    pub exclude_type_hints: Vec<String>,
+    /// Match only memories with this embedding_status ("pending", "complete", "failed", "chunked")
+    pub embedding_status: Option<String>,
+    /// Match only memories with this extraction_status ("pending", "complete", "failed", "skipped")
+    pub extraction_status: Option<String>,
 }
 
+/// Values `ListFilter::embedding_status` can match.
+pub const EMBEDDING_STATUSES: &[&str] = &["pending", "complete", "failed", CHUNKED_STATUS];
+
+/// Values `ListFilter::extraction_status` can match.
+pub const EXTRACTION_STATUSES: &[&str] = &["pending", "complete", "failed", "skipped"];
+
 impl Default for ListFilter {
     fn default() -> Self {
         ListFilter {
+            namespace: None,
             type_hint: None,
             source: None,
+            tags: None,
             created_after: None,
             created_before: None,
             updated_after: None,
             updated_before: None,
             limit: 20,
             cursor: None,
+            trashed: false,
+            archived: false,
+            min_importance: None,
+            session_id: None,
+            payload_path: None,
+            origin_ref: None,
+            min_access_count: None,
+            accessed_after: None,
+            never_accessed: false,
+            exclude_tags: Vec::new(),
+            exclude_sources: Vec::new(),
+            exclude_type_hints: Vec::new(),
+            embedding_status: None,
+            extraction_status: None,
         }
     }
 }
@@ -155,6 +562,31 @@ pub struct SearchFilter {
     pub created_before: Option<DateTime<Utc>>,
     /// Filter memories that have ALL specified tags (containment match)
     pub tags: Option<Vec<String>>,
+    /// Restrict to a single namespace (None = all namespaces)
+    pub namespace: Option<String>,
+    /// Filter by exact type_hint
+    pub type_hint: Option<String>,
+    /// Filter by exact source
+    pub source: Option<String>,
+    /// Search embeddings from this model instead of the current ones (None = current model).
+    /// The query embedding must come from the same model.
+    pub model: Option<String>,
+    /// Match only memories whose payload satisfies this SQL/JSON path predicate
+    pub payload_path: Option<String>,
+    /// Match only memories with an origin reference agreeing with every part set here
+    pub origin_ref: Option<OriginRef>,
+    /// Match only memories retrieved at least this many times
+    pub min_access_count: Option<i64>,
+    /// Match only memories last retrieved after this timestamp
+    pub accessed_after: Option<DateTime<Utc>>,
+    /// When true, match only memories that were never retrieved
+    pub never_accessed: bool,
+    /// Skip memories carrying any of these tags (empty = exclude nothing)
+    pub exclude_tags: Vec<String>,
+    /// Skip memories from any of these sources
+    pub exclude_sources: Vec<String>,
+    /// Skip memories with any of these type hints
+    pub exclude_type_hints: Vec<String>,
 }
 
 impl Default for SearchFilter {
@@ -167,6 +599,18 @@ impl Default for SearchFilter {
             created_after: None,
             created_before: None,
             tags: None,
+            namespace: None,
+            type_hint: None,
+            source: None,
+            model: None,
+            payload_path: None,
+            origin_ref: None,
+            min_access_count: None,
+            accessed_after: None,
+            never_accessed: false,
+            exclude_tags: Vec::new(),
+            exclude_sources: Vec::new(),
+            exclude_type_hints: Vec::new(),
         }
     }
 }
@@ -185,14 +629,56 @@ pub struct SearchHit {
 pub struct SearchResult {
     /// The matched memories with similarity scores, ordered by similarity descending
     pub hits: Vec<SearchHit>,
-    /// Total number of embedded memories matching the filters (ignoring limit/offset)
+    /// Number of embedded memories matching the filters (ignoring limit/offset). A lower
+    /// bound unless `total_matches_exact`: with search.count_matches = "none" it counts the
+    /// matches up to the end of this page, plus one when more follow; "capped" stops at the cap.
     pub total_matches: u64,
+    /// Whether `total_matches` is the exact count
+    pub total_matches_exact: bool,
     /// Base64-encoded offset for fetching the next page (None if no more results)
     pub next_cursor: Option<String>,
     /// Whether there are more results beyond the current page
     pub has_more: bool,
 }
 
+/// Encode a list_memories pagination cursor from created_at and id.
+pub(crate) fn encode_cursor(created_at: &DateTime<Utc>, id: &str) -> String {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    let raw = format!("{}|{}", created_at.to_rfc3339(), id);
+    URL_SAFE_NO_PAD.encode(raw.as_bytes())
+}
+
+/// Decode a pagination cursor back into (created_at, id).
+pub(crate) fn decode_cursor(cursor: &str) -> Result<(DateTime<Utc>, String), MemcpError> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    let bytes = URL_SAFE_NO_PAD
+        .decode(cursor)
+        .map_err(|e| MemcpError::Validation {
+            message: format!("Invalid cursor encoding: {}", e),
+            field: Some("cursor".to_string()),
+        })?;
+    let raw = String::from_utf8(bytes).map_err(|e| MemcpError::Validation {
+        message: format!("Invalid cursor content: {}", e),
+        field: Some("cursor".to_string()),
+    })?;
+    let mut parts = raw.splitn(2, '|');
+    let ts_str = parts.next().ok_or_else(|| MemcpError::Validation {
+        message: "Cursor missing timestamp".to_string(),
+        field: Some("cursor".to_string()),
+    })?;
+    let id_str = parts.next().ok_or_else(|| MemcpError::Validation {
+        message: "Cursor missing id".to_string(),
+        field: Some("cursor".to_string()),
+    })?;
+    let created_at = ts_str
+        .parse::<DateTime<Utc>>()
+        .map_err(|e| MemcpError::Validation {
+            message: format!("Cursor timestamp parse error: {}", e),
+            field: Some("cursor".to_string()),
+        })?;
+    Ok((created_at, id_str.to_string()))
+}
+
 /// Encode a search pagination cursor from an offset value.
 ///
 /// Search cursors are OFFSET-based (not keyset-based like list_memories cursors)
@@ -228,34 +714,90 @@ pub trait MemoryStore: Send + Sync {
     /// Store a new memory and return the created record.
     async fn store(&self, input: CreateMemory) -> Result<Memory, MemcpError>;
 
+    /// Store several memories at once, returning the created records in input order.
+    ///
+    /// Backends that support transactions should override this so the batch is atomic.
+    /// The default implementation stores each memory sequentially.
+    async fn store_batch(&self, inputs: Vec<CreateMemory>) -> Result<Vec<Memory>, MemcpError> {
+        let mut memories = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            memories.push(self.store(input).await?);
+        }
+        Ok(memories)
+    }
+
     /// Retrieve a memory by ID.
     ///
+    /// Trashed memories are reported as NotFound.
     /// Also increments access_count and updates last_accessed_at via touch().
     async fn get(&self, id: &str) -> Result<Memory, MemcpError>;
 
     /// Update an existing memory (partial update).
     ///
     /// Only non-None fields in UpdateMemory are applied.
+    /// Backends that keep history snapshot the previous content/metadata first.
     async fn update(&self, id: &str, input: UpdateMemory) -> Result<Memory, MemcpError>;
 
-    /// Delete a memory by ID.
+    /// Permanently delete a memory by ID (live or trashed).
     ///
     /// Returns NotFound error if the memory doesn't exist.
     async fn delete(&self, id: &str) -> Result<(), MemcpError>;
 
+    /// Move a live memory to the trash (soft delete).
+    ///
+    /// Returns NotFound if the memory doesn't exist or is already trashed.
+    async fn trash(&self, id: &str) -> Result<(), MemcpError>;
+
+    /// Restore a trashed or archived memory and return it. An expiry that has already passed
+    /// is cleared, or the memory would stay hidden and be swept again.
+    ///
+    /// Returns NotFound if the memory doesn't exist or is neither trashed nor archived.
+    async fn restore(&self, id: &str) -> Result<Memory, MemcpError>;
+
     /// List memories with optional filtering and cursor-based pagination.
     async fn list(&self, filter: ListFilter) -> Result<ListResult, MemcpError>;
 
     /// Count memories matching the given filter (for two-step bulk delete confirmation).
     async fn count_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError>;
 
-    /// Delete all memories matching the given filter.
+    /// Permanently delete all memories matching the given filter.
     ///
     /// Returns the number of deleted memories.
     async fn delete_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError>;
 
+    /// Move all live memories matching the given filter to the trash.
+    ///
+    /// Returns the number of trashed memories.
+    async fn trash_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError>;
+
+    /// Apply `update` to all live memories matching the given filter in one transaction,
+    /// snapshotting a revision of each first like update().
+    ///
+    /// Returns the updated memories.
+    async fn update_matching(&self, filter: &ListFilter, update: &BulkUpdate) -> Result<Vec<Memory>, MemcpError>;
+
     /// Update last_accessed_at and increment access_count for a memory.
     ///
     /// Silently ignores if the ID doesn't exist (fire-and-forget semantics).
     async fn touch(&self, id: &str) -> Result<(), MemcpError>;
+
+    /// touch() every memory in `ids`. Unknown IDs are ignored.
+    async fn touch_many(&self, ids: &[String]) -> Result<(), MemcpError> {
+        for id in ids {
+            self.touch(id).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetch memories by ID, live or trashed, without touching access stats.
+    ///
+    /// IDs that don't exist are simply absent from the result.
+    async fn get_memories_by_ids(&self, ids: &[String]) -> Result<HashMap<String, Memory>, MemcpError>;
+
+    /// Vector similarity search over live, embedded memories with OFFSET-based pagination.
+    async fn search_similar(&self, filter: &SearchFilter) -> Result<SearchResult, MemcpError>;
+
+    /// Live pinned memories matching the filter's hard filters (the query embedding, offset
+    /// and model are ignored), most recently updated first, at most `filter.limit`.
+    async fn pinned_memories(&self, filter: &SearchFilter) -> Result<Vec<Memory>, MemcpError>;
 }
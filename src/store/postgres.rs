@@ -4,23 +4,49 @@
 /// Supports optional migration execution on startup.
 
 use async_trait::async_trait;
-use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sqlx::{
-    postgres::{PgPool, PgPoolOptions, PgRow},
-    Row,
+    migrate::Migrator,
+    pool::PoolConnection,
+    postgres::{PgArguments, PgConnectOptions, PgConnection, PgPool, PgPoolOptions, PgRow},
+    query::Query,
+    Connection, Postgres, Row,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
 
-use crate::config::SearchConfig;
+use crate::audit::{AuditEntry, AuditFilter, AuditRecord};
+use crate::search_log::{SearchLogEntry, SearchLogFilter, SearchLogRecord};
+use crate::config::{DatabaseConfig, MatchCount, SearchConfig};
+use crate::encryption::ContentCipher;
 use crate::errors::MemcpError;
+use crate::memory_types::{FieldDef, MemoryType};
+use crate::outbox::{ClaimedJob, JobKind, PendingJob};
 use crate::store::{
-    encode_search_cursor, CreateMemory, ListFilter, ListResult, Memory, MemoryStore,
-    SearchFilter, SearchHit, SearchResult, UpdateMemory,
+    decode_cursor, encode_cursor, encode_search_cursor, CreateMemory, EmbeddingFailure, FacetCount, ListFilter, ListResult, Memory, MemoryFacets, MemoryLink, MemoryStats, Percentiles, StorageSizes,
+    BulkUpdate, MemoryRevision, MemoryStore, OriginRef, SearchFilter, SearchHit, SearchResult, Session, UpdateMemory, CONTRADICTS_RELATION,
 };
 
+/// A cross-process lock, held until `release` or drop.
+///
+/// Backed by a PostgreSQL transaction-scoped advisory lock, so it is released when the
+/// transaction ends — including when the process holding it dies and its connection closes.
+/// Lets several memcp processes share one database without repeating the same work.
+pub struct AdvisoryLock {
+    tx: sqlx::Transaction<'static, Postgres>,
+}
+
+impl AdvisoryLock {
+    /// Release the lock now instead of when the guard is dropped.
+    pub async fn release(self) {
+        let _ = self.tx.commit().await;
+    }
+}
+
 /// FSRS state row fetched from memory_salience table.
 ///
 /// Missing rows are represented as defaults (stability=1.0, difficulty=5.0, count=0).
@@ -43,6 +69,93 @@ impl Default for SalienceRow {
     }
 }
 
+/// Counts of live memories still waiting on, or failed by, the background pipelines.
+#[derive(Debug, Clone, Default)]
+pub struct PipelineCounts {
+    pub embedding_pending: i64,
+    pub embedding_failed: i64,
+    pub extraction_pending: i64,
+    pub extraction_failed: i64,
+}
+
+/// Embedding rows and memories that disagree with the configured embedding model,
+/// as found (or repaired) by `memcp embed verify`.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct EmbeddingConsistency {
+    /// Current embeddings from another model or with another dimension
+    pub drifted: i64,
+    /// Embeddings whose memory row no longer exists
+    pub orphaned: i64,
+    /// Live memories marked complete without a current embedding
+    pub missing: i64,
+}
+
+impl EmbeddingConsistency {
+    pub fn is_clean(&self) -> bool {
+        self.drifted == 0 && self.orphaned == 0 && self.missing == 0
+    }
+}
+
+/// Name of the GIN expression index serving native BM25 for a text search configuration.
+fn fts_index_name(config: &str) -> String {
+    format!("idx_memories_fts_{}", config.replace('.', "_"))
+}
+
+/// Name of the ParadeDB BM25 index prepare_paradedb_index creates.
+const PARADEDB_INDEX_NAME: &str = "memories_bm25_idx";
+
+/// Tags, extracted entities and extracted facts flattened to one string for fuzzy symbolic
+/// matching. Must match the expression indexed by migration 029.
+const SYMBOLIC_TEXT_SQL: &str = "(COALESCE(tags::text, '') || ' ' || COALESCE(extracted_entities::text, '') || ' ' || COALESCE(extracted_facts::text, ''))";
+
+/// Migrations bundled with this binary (migrations/).
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Where one migration stands in a database, reported by `memcp migrate --status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the recorded checksum differs from this binary's migration file
+    Modified,
+    /// Applied by a newer binary; this one doesn't have the migration
+    Unknown,
+}
+
+impl MigrationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Unknown => "unknown",
+        }
+    }
+}
+
+/// One migration's state in the database.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+    /// SHA-384 of the migration SQL (hex); the recorded one for unknown migrations
+    pub checksum: String,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+/// Map a migration failure, explaining a database migrated by a newer memcp.
+fn migrate_error(e: sqlx::migrate::MigrateError) -> MemcpError {
+    match e {
+        sqlx::migrate::MigrateError::VersionMissing(version) => MemcpError::Config(format!(
+            "Database schema has migration {:03}, which this memcp binary doesn't know — upgrade memcp before using this database",
+            version
+        )),
+        e => MemcpError::Storage(format!("Migration failed: {}", e)),
+    }
+}
+
 /// PostgreSQL-backed memory store using sqlx connection pool.
 pub struct PostgresMemoryStore {
     pool: PgPool,
@@ -51,6 +164,34 @@ pub struct PostgresMemoryStore {
     paradedb_available: bool,
     /// Whether to use ParadeDB for BM25 search (paradedb_available AND config says "paradedb").
     use_paradedb: bool,
+    /// Default per-leg candidate pool for hybrid_search (from SearchConfig).
+    candidate_pool_per_leg: i64,
+    /// Whether hybrid_search's vector leg also matches per-fact embeddings (from SearchConfig).
+    fact_embeddings: bool,
+    /// Time each hybrid_search leg may take before it is dropped (None = no limit).
+    leg_timeout: Option<Duration>,
+    /// Pool connections hybrid_search legs may hold at once (`leg_connection_budget` permits).
+    /// Concurrent searches queue here rather than drain the pool that writes also need.
+    leg_connections: Arc<tokio::sync::Semaphore>,
+    /// Half the pool, at least one connection.
+    leg_connection_budget: u32,
+    /// Text search configuration used by native BM25 (validated identifier, safe to inline).
+    text_search_config: String,
+    /// pg_trgm word similarity cutoff for fuzzy symbolic matches (None = exact matches only).
+    symbolic_fuzzy_threshold: Option<f64>,
+    /// How search_similar counts matches beyond the page (search.count_matches).
+    match_count: MatchCount,
+    /// Content encryption at rest (None = content stored as plaintext).
+    cipher: Option<Arc<ContentCipher>>,
+    /// Pipeline jobs written to the outbox with each new memory (empty = outbox off).
+    outbox: Vec<JobKind>,
+    /// Models only used by embedding routes; their current embeddings are left out of
+    /// searches that don't ask for a model (see `crate::embedding::router`).
+    routed_models: Vec<String>,
+    /// Query dimension found to match the current embeddings (0 = not checked yet). The
+    /// embedding model is fixed per process, so searches run the catalog check only until
+    /// it first passes.
+    checked_dimension: AtomicI32,
 }
 
 impl PostgresMemoryStore {
@@ -65,39 +206,78 @@ impl PostgresMemoryStore {
 
     /// Create a new PostgresMemoryStore with an explicit SearchConfig.
     ///
-    /// Allows operators to set bm25_backend via config or env var.
+    /// Allows operators to set bm25_backend and candidate_pool_per_leg via config or env var.
     pub async fn new_with_search_config(
         database_url: &str,
         run_migrations: bool,
         search_config: &SearchConfig,
     ) -> Result<Self, MemcpError> {
+        Self::new_with_config(database_url, run_migrations, search_config, &DatabaseConfig::default()).await
+    }
+
+    /// Create a new PostgresMemoryStore with explicit search and connection pool settings.
+    ///
+    /// `database_config` sizes the pool and sets connection/statement timeouts — stdio
+    /// deployments want a small pool, long-running HTTP deployments a larger one.
+    pub async fn new_with_config(
+        database_url: &str,
+        run_migrations: bool,
+        search_config: &SearchConfig,
+        database_config: &DatabaseConfig,
+    ) -> Result<Self, MemcpError> {
+        let text_search_config = search_config.effective_text_search_config()?;
+        let symbolic_fuzzy_threshold = search_config.validated_symbolic_fuzzy_threshold()?;
+        let match_count = search_config.validated_match_count()?;
+
+        let mut connect_options: PgConnectOptions = database_url
+            .parse()
+            .map_err(|e| MemcpError::Config(format!("Invalid database_url: {}", e)))?;
+        if database_config.statement_timeout_ms > 0 {
+            // Applied per session, so it covers every pooled connection
+            connect_options = connect_options
+                .options([("statement_timeout", format!("{}ms", database_config.statement_timeout_ms))]);
+        }
+
+        let max_connections = database_config.max_connections.max(1);
+        let leg_connection_budget = (max_connections / 2).max(1);
         let pool = PgPoolOptions::new()
-            .max_connections(10)         // good default for single-server MCP stdio
-            .min_connections(1)          // keep at least one warm connection
-            .idle_timeout(Duration::from_secs(300))    // 5 min idle cleanup
-            .max_lifetime(Duration::from_secs(1800))   // 30 min max connection age
-            .connect(database_url)
+            .max_connections(max_connections)
+            .min_connections(database_config.min_connections.min(max_connections))
+            .acquire_timeout(Duration::from_secs(database_config.connect_timeout_secs))
+            .idle_timeout(Duration::from_secs(database_config.idle_timeout_secs))
+            .max_lifetime(Duration::from_secs(database_config.max_lifetime_secs))
+            .connect_with(connect_options)
             .await
             .map_err(|e| MemcpError::Storage(format!("Failed to connect to database: {}", e)))?;
 
         if run_migrations {
-            sqlx::migrate!("./migrations")
-                .run(&pool)
-                .await
-                .map_err(|e| MemcpError::Storage(format!("Migration failed: {}", e)))?;
+            MIGRATOR.run(&pool).await.map_err(migrate_error)?;
+            Self::prepare_text_search(&pool, search_config, &text_search_config).await?;
         }
 
         // Detect ParadeDB at startup — cached as bool for the lifetime of the store
         let paradedb_available = Self::detect_paradedb(&pool).await;
 
         // Determine effective BM25 backend:
-        // - "paradedb" config + available → use ParadeDB
-        // - "paradedb" config + NOT available → warn, fall back to native
+        // - "paradedb" config + available + valid BM25 index → use ParadeDB
+        // - "paradedb" config + NOT available, or no usable index → warn, fall back to native
         // - "native" config (default) → always use native
         let use_paradedb = if search_config.bm25_backend == "paradedb" {
             if paradedb_available {
-                tracing::info!("ParadeDB pg_search extension detected — using ParadeDB for BM25");
-                true
+                if run_migrations {
+                    if let Err(e) = Self::prepare_paradedb_index(&pool, search_config).await {
+                        tracing::warn!(error = %e, "Failed to create ParadeDB BM25 index");
+                    }
+                }
+                if Self::paradedb_index_valid(&pool).await {
+                    tracing::info!("ParadeDB pg_search extension detected — using ParadeDB for BM25");
+                    true
+                } else {
+                    tracing::warn!(
+                        "bm25_backend=paradedb configured but memories has no valid BM25 index — falling back to native PostgreSQL tsvector (run `memcp migrate` to create it)"
+                    );
+                    false
+                }
             } else {
                 tracing::warn!(
                     "bm25_backend=paradedb configured but pg_search extension not found — falling back to native PostgreSQL tsvector"
@@ -113,7 +293,152 @@ impl PostgresMemoryStore {
             false
         };
 
-        Ok(PostgresMemoryStore { pool, paradedb_available, use_paradedb })
+        Ok(PostgresMemoryStore {
+            pool,
+            paradedb_available,
+            use_paradedb,
+            candidate_pool_per_leg: search_config.candidate_pool_per_leg.max(1),
+            fact_embeddings: search_config.fact_embeddings,
+            leg_timeout: (search_config.leg_timeout_ms > 0).then(|| Duration::from_millis(search_config.leg_timeout_ms)),
+            leg_connections: Arc::new(tokio::sync::Semaphore::new(leg_connection_budget as usize)),
+            leg_connection_budget,
+            text_search_config,
+            symbolic_fuzzy_threshold,
+            match_count,
+            cipher: None,
+            outbox: Vec::new(),
+            routed_models: Vec::new(),
+            checked_dimension: AtomicI32::new(0),
+        })
+    }
+
+    /// Create the derived stop-list configuration (if configured) and the GIN index for
+    /// the effective text search configuration. Migration 004 already indexes "english".
+    async fn prepare_text_search(pool: &PgPool, search_config: &SearchConfig, name: &str) -> Result<(), MemcpError> {
+        if let Some(stopwords) = &search_config.text_search_stopwords {
+            let exists: bool = sqlx::query_scalar("SELECT to_regconfig($1) IS NOT NULL")
+                .bind(name)
+                .fetch_one(pool)
+                .await
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            if !exists {
+                Self::create_stopword_config(pool, &search_config.text_search_config, stopwords, name).await?;
+            }
+        }
+
+        if name != "english" {
+            let sql = format!(
+                "CREATE INDEX IF NOT EXISTS {} ON memories USING GIN (to_tsvector('{}', content)) WITH (fastupdate=off)",
+                fts_index_name(name),
+                name
+            );
+            sqlx::query(&sql)
+                .execute(pool)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to create full-text index for '{}': {}", name, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Copy `base` into configuration `name`, putting a simple dictionary with the stop-list
+    /// in front of the word dictionaries. Non-stop words pass through (ACCEPT = false) to
+    /// the base configuration's own dictionaries, so stemming is unchanged.
+    async fn create_stopword_config(pool: &PgPool, base: &str, stopwords: &str, name: &str) -> Result<(), MemcpError> {
+        let dictionary = format!("{}_stop", name);
+        let mut tx = pool.begin().await.map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        let mappings: Vec<(String, String)> = sqlx::query_as(
+            "SELECT t.alias, string_agg(m.mapdict::regdictionary::text, ', ' ORDER BY m.mapseqno)
+             FROM pg_ts_config_map m
+             JOIN ts_token_type('default') t ON t.tokid = m.maptokentype
+             WHERE m.mapcfg = $1::regconfig
+               AND t.alias IN ('asciiword', 'word', 'asciihword', 'hword', 'hword_asciipart', 'hword_part')
+             GROUP BY t.alias",
+        )
+        .bind(base)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Unknown text search configuration '{}': {}", base, e)))?;
+
+        let mut statements = vec![
+            format!(
+                "CREATE TEXT SEARCH DICTIONARY {} (TEMPLATE = pg_catalog.simple, STOPWORDS = {}, ACCEPT = false)",
+                dictionary, stopwords
+            ),
+            format!("CREATE TEXT SEARCH CONFIGURATION {} (COPY = {})", name, base),
+        ];
+        statements.extend(mappings.iter().map(|(alias, dictionaries)| {
+            format!(
+                "ALTER TEXT SEARCH CONFIGURATION {} ALTER MAPPING FOR {} WITH {}, {}",
+                name, alias, dictionary, dictionaries
+            )
+        }));
+        for sql in &statements {
+            sqlx::query(sql).execute(&mut *tx).await.map_err(|e| {
+                MemcpError::Storage(format!("Failed to create text search configuration '{}': {}", name, e))
+            })?;
+        }
+
+        tx.commit().await.map_err(|e| MemcpError::Storage(e.to_string()))?;
+        tracing::info!(config = name, base = base, stopwords = stopwords, "Created text search configuration");
+        Ok(())
+    }
+
+    /// Create the ParadeDB BM25 index over content with the configured tokenizer unless it
+    /// exists. An existing index keeps its tokenizer.
+    async fn prepare_paradedb_index(pool: &PgPool, search_config: &SearchConfig) -> Result<(), MemcpError> {
+        // Validated against PARADEDB_TOKENIZERS, so it is safe to inline
+        let tokenizer = search_config.validated_paradedb_tokenizer()?;
+        let sql = format!(
+            "CREATE INDEX IF NOT EXISTS {} ON memories USING bm25 (id, content) \
+             WITH (key_field = 'id', text_fields = '{{\"content\": {{\"tokenizer\": {{\"type\": \"{}\"}}}}}}')",
+            PARADEDB_INDEX_NAME, tokenizer
+        );
+        sqlx::query(&sql)
+            .execute(pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to create ParadeDB BM25 index: {}", e)))?;
+        tracing::info!(index = PARADEDB_INDEX_NAME, tokenizer = tokenizer, "ParadeDB BM25 index ready");
+        Ok(())
+    }
+
+    /// Whether memories has a valid (fully built) BM25 index, by any name.
+    async fn paradedb_index_valid(pool: &PgPool) -> bool {
+        sqlx::query_scalar::<_, bool>(
+            "SELECT COALESCE(bool_or(i.indisvalid AND i.indisready), FALSE) \
+             FROM pg_index i \
+             JOIN pg_class c ON c.oid = i.indexrelid \
+             JOIN pg_am a ON a.oid = c.relam \
+             WHERE i.indrelid = 'memories'::regclass AND a.amname = 'bm25'",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap_or(false)
+    }
+
+    /// Whether keyword search uses ParadeDB (configured, installed, and indexed) rather than
+    /// native tsvector.
+    pub fn uses_paradedb(&self) -> bool {
+        self.use_paradedb
+    }
+
+    /// Re-check the ParadeDB BM25 index (for `memcp doctor`); false when pg_search is absent.
+    pub async fn paradedb_index_ready(&self) -> bool {
+        self.paradedb_available && Self::paradedb_index_valid(&self.pool).await
+    }
+
+    /// The text search configuration native BM25 uses.
+    pub fn text_search_config(&self) -> &str {
+        &self.text_search_config
+    }
+
+    /// Whether the configured text search configuration exists in the database.
+    pub async fn text_search_config_exists(&self) -> Result<bool, MemcpError> {
+        sqlx::query_scalar("SELECT to_regconfig($1) IS NOT NULL")
+            .bind(&self.text_search_config)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))
     }
 
     /// Truncate all benchmark-relevant tables: memories, memory_embeddings, memory_salience, memory_consolidations.
@@ -126,6 +451,129 @@ impl PostgresMemoryStore {
         Ok(())
     }
 
+    /// List migrations bundled with this binary that have not been applied, as "NNN description".
+    ///
+    /// A database that has never been migrated (no _sqlx_migrations table) reports all of them.
+    pub async fn pending_migrations(&self) -> Result<Vec<String>, MemcpError> {
+        Ok(self
+            .migration_status()
+            .await?
+            .into_iter()
+            .filter(|m| m.state == MigrationState::Pending)
+            .map(|m| format!("{:03} {}", m.version, m.description))
+            .collect())
+    }
+
+    /// Every migration bundled with this binary or recorded as applied, by version.
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>, MemcpError> {
+        let has_table: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let mut applied: HashMap<i64, (String, Vec<u8>, DateTime<Utc>)> = HashMap::new();
+        if has_table {
+            let rows = sqlx::query("SELECT version, description, checksum, installed_on FROM _sqlx_migrations WHERE success")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            for row in rows {
+                let get_err = |e: sqlx::Error| MemcpError::Storage(e.to_string());
+                applied.insert(
+                    row.try_get("version").map_err(get_err)?,
+                    (
+                        row.try_get("description").map_err(get_err)?,
+                        row.try_get("checksum").map_err(get_err)?,
+                        row.try_get("installed_on").map_err(get_err)?,
+                    ),
+                );
+            }
+        }
+
+        let mut status: Vec<MigrationStatus> = MIGRATOR
+            .iter()
+            .map(|m| {
+                let recorded = applied.remove(&m.version);
+                let state = match &recorded {
+                    None => MigrationState::Pending,
+                    Some((_, checksum, _)) if checksum.as_slice() != &*m.checksum => MigrationState::Modified,
+                    Some(_) => MigrationState::Applied,
+                };
+                MigrationStatus {
+                    version: m.version,
+                    description: m.description.to_string(),
+                    state,
+                    checksum: hex::encode(&*m.checksum),
+                    applied_at: recorded.map(|(_, _, at)| at),
+                }
+            })
+            .collect();
+        // What's left was applied by a binary with migrations this one doesn't have
+        status.extend(applied.into_iter().map(|(version, (description, checksum, at))| MigrationStatus {
+            version,
+            description,
+            state: MigrationState::Unknown,
+            checksum: hex::encode(checksum),
+            applied_at: Some(at),
+        }));
+        status.sort_by_key(|m| m.version);
+        Ok(status)
+    }
+
+    /// Refuse a database migrated by a newer memcp: its schema may not match what this binary
+    /// reads and writes.
+    pub async fn check_schema_version(&self) -> Result<(), MemcpError> {
+        let unknown: Vec<i64> = self
+            .migration_status()
+            .await?
+            .into_iter()
+            .filter(|m| m.state == MigrationState::Unknown)
+            .map(|m| m.version)
+            .collect();
+        match unknown.last() {
+            None => Ok(()),
+            Some(latest) => Err(MemcpError::Config(format!(
+                "Database schema is at migration {:03}, but this memcp binary only knows migrations up to {:03} — upgrade memcp before using this database",
+                latest,
+                MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+            ))),
+        }
+    }
+
+    /// Apply pending migrations up to and including `target` (None = all), returning the
+    /// versions applied. Migrations past `target` stay pending.
+    pub async fn migrate_to(&self, target: Option<i64>) -> Result<Vec<i64>, MemcpError> {
+        self.check_schema_version().await?;
+        let pending: Vec<i64> = self
+            .migration_status()
+            .await?
+            .into_iter()
+            .filter(|m| m.state == MigrationState::Pending && target.is_none_or(|t| m.version <= t))
+            .map(|m| m.version)
+            .collect();
+
+        let result = match target {
+            None => MIGRATOR.run(&self.pool).await,
+            Some(target) => {
+                let mut migrator = sqlx::migrate!("./migrations");
+                migrator.migrations = MIGRATOR.iter().filter(|m| m.version <= target).cloned().collect();
+                // Versions past the target applied by an earlier full run are known to this binary
+                migrator.set_ignore_missing(true);
+                migrator.run(&self.pool).await
+            }
+        };
+        result.map_err(migrate_error)?;
+        Ok(pending)
+    }
+
+    /// Return the installed version of a PostgreSQL extension, or None if it is not installed.
+    pub async fn extension_version(&self, name: &str) -> Result<Option<String>, MemcpError> {
+        sqlx::query_scalar("SELECT extversion FROM pg_extension WHERE extname = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))
+    }
+
     /// Detect whether the ParadeDB pg_search extension is installed on this PostgreSQL instance.
     ///
     /// Queries the pg_extension catalog once at startup. Returns true if pg_search is present.
@@ -137,40 +585,307 @@ impl PostgresMemoryStore {
     }
 }
 
-/// Encode a pagination cursor from created_at and id.
-fn encode_cursor(created_at: &DateTime<Utc>, id: &str) -> String {
-    let raw = format!("{}|{}", created_at.to_rfc3339(), id);
-    URL_SAFE_NO_PAD.encode(raw.as_bytes())
+/// Prefix of the per-dimension pgvector HNSW indexes on memory_embeddings (migration 026).
+const HNSW_INDEX_PREFIX: &str = "idx_memory_embeddings_hnsw";
+
+/// Prefix of the per-dimension pgvector HNSW indexes on fact_embeddings (migration 026).
+const FACT_HNSW_INDEX_PREFIX: &str = "idx_fact_embeddings_hnsw";
+
+/// Name of the index over `dimension`-dimensional vectors, e.g. "idx_memory_embeddings_hnsw_384".
+fn hnsw_index_name(prefix: &str, dimension: i32) -> String {
+    format!("{}_{}", prefix, dimension)
 }
 
-/// Decode a pagination cursor back into (created_at, id).
-fn decode_cursor(cursor: &str) -> Result<(DateTime<Utc>, String), MemcpError> {
-    let bytes = URL_SAFE_NO_PAD
-        .decode(cursor)
-        .map_err(|e| MemcpError::Validation {
-            message: format!("Invalid cursor encoding: {}", e),
-            field: Some("cursor".to_string()),
-        })?;
-    let raw = String::from_utf8(bytes).map_err(|e| MemcpError::Validation {
-        message: format!("Invalid cursor content: {}", e),
-        field: Some("cursor".to_string()),
-    })?;
-    let mut parts = raw.splitn(2, '|');
-    let ts_str = parts.next().ok_or_else(|| MemcpError::Validation {
-        message: "Cursor missing timestamp".to_string(),
-        field: Some("cursor".to_string()),
-    })?;
-    let id_str = parts.next().ok_or_else(|| MemcpError::Validation {
-        message: "Cursor missing id".to_string(),
-        field: Some("cursor".to_string()),
-    })?;
-    let created_at = ts_str
-        .parse::<DateTime<Utc>>()
-        .map_err(|e| MemcpError::Validation {
-            message: format!("Cursor timestamp parse error: {}", e),
-            field: Some("cursor".to_string()),
-        })?;
-    Ok((created_at, id_str.to_string()))
+/// DDL for a partial HNSW index over the rows of `table` with `dimension`-dimensional vectors.
+///
+/// The column itself is dimensionless so models can coexist; the index casts to a fixed
+/// dimension, and queries must use the same cast and `dimension = N` filter to hit it.
+fn hnsw_index_sql(name: &str, table: &str, dimension: i32, m: u32, ef_construction: u32) -> String {
+    format!(
+        "CREATE INDEX CONCURRENTLY IF NOT EXISTS {} ON {} \
+         USING hnsw ((embedding::vector({})) vector_cosine_ops) WITH (m = {}, ef_construction = {}) \
+         WHERE dimension = {}",
+        name, table, dimension, m, ef_construction, dimension
+    )
+}
+
+/// Extract (m, ef_construction) from an index's reloptions, e.g. ["m=16", "ef_construction=64"].
+fn parse_hnsw_options(options: &[String]) -> (Option<u32>, Option<u32>) {
+    let value = |key: &str| {
+        options.iter().find_map(|opt| {
+            let (k, v) = opt.split_once('=')?;
+            (k == key).then(|| v.parse().ok()).flatten()
+        })
+    };
+    (value("m"), value("ef_construction"))
+}
+
+/// Columns selected for a full Memory read, in the order row_to_memory() expects.
+const MEMORY_COLUMNS: &str = "id, content, type_hint, source, tags, created_at, updated_at, \
+    last_accessed_at, access_count, embedding_status, extracted_entities, extracted_facts, \
+    extraction_status, is_consolidated_original, consolidated_into, namespace, deleted_at, expires_at, archived_at, importance, session_id, parent_id, chunk_index, fields, payload, pinned, origin_refs";
+
+/// MEMORY_COLUMNS qualified with a table alias, for JOIN queries where names collide.
+fn memory_columns_with_alias(alias: &str) -> String {
+    MEMORY_COLUMNS
+        .split(',')
+        .map(|c| format!("{}.{}", alias, c.trim()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+type PgQuery<'q> = Query<'q, Postgres, PgArguments>;
+
+/// Append WHERE conditions for the ListFilter predicates (cursor and limit excluded).
+///
+/// Always scopes to either live or trashed memories, so the result is never empty.
+/// Parameters are numbered from `param_idx`, which is advanced past the last one used.
+/// Binding order must match bind_list_filter().
+fn push_list_conditions(filter: &ListFilter, conditions: &mut Vec<String>, param_idx: &mut u32) {
+    // Chunks are reached through their parent, never listed on their own
+    conditions.push("parent_id IS NULL".to_string());
+    if filter.trashed {
+        conditions.push("deleted_at IS NOT NULL".to_string());
+    } else {
+        conditions.push("deleted_at IS NULL".to_string());
+        conditions.push("(expires_at IS NULL OR expires_at > NOW())".to_string());
+    }
+    if filter.archived {
+        conditions.push("archived_at IS NOT NULL".to_string());
+    }
+    if filter.namespace.is_some() {
+        conditions.push(format!("namespace = ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.type_hint.is_some() {
+        conditions.push(format!("type_hint = ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.source.is_some() {
+        conditions.push(format!("source = ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.tags.is_some() {
+        // JSONB containment: matches memories that have ALL specified tags
+        conditions.push(format!("tags @> ${}::jsonb", param_idx));
+        *param_idx += 1;
+    }
+    if filter.created_after.is_some() {
+        conditions.push(format!("created_at > ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.created_before.is_some() {
+        conditions.push(format!("created_at < ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.updated_after.is_some() {
+        conditions.push(format!("updated_at > ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.updated_before.is_some() {
+        conditions.push(format!("updated_at < ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.min_importance.is_some() {
+        conditions.push(format!("importance >= ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.session_id.is_some() {
+        conditions.push(format!("session_id = ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.payload_path.is_some() {
+        conditions.push(format!("payload @@ ${}::text::jsonpath", param_idx));
+        *param_idx += 1;
+    }
+    if filter.origin_ref.is_some() {
+        conditions.push(format!("origin_refs @> ${}::jsonb", param_idx));
+        *param_idx += 1;
+    }
+    if filter.min_access_count.is_some() {
+        conditions.push(format!("access_count >= ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.accessed_after.is_some() {
+        conditions.push(format!("last_accessed_at > ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.never_accessed {
+        conditions.push("access_count = 0".to_string());
+    }
+    if filter.embedding_status.is_some() {
+        conditions.push(format!("embedding_status = ${}", param_idx));
+        *param_idx += 1;
+    }
+    if filter.extraction_status.is_some() {
+        conditions.push(format!("extraction_status = ${}", param_idx));
+        *param_idx += 1;
+    }
+    push_exclusion_conditions(
+        "",
+        [&filter.exclude_tags, &filter.exclude_sources, &filter.exclude_type_hints],
+        conditions,
+        param_idx,
+    );
+}
+
+/// Bind ListFilter values in the same order push_list_conditions() numbered them.
+fn bind_list_filter<'q>(mut q: PgQuery<'q>, filter: &'q ListFilter) -> PgQuery<'q> {
+    if let Some(ref ns) = filter.namespace {
+        q = q.bind(ns);
+    }
+    if let Some(ref th) = filter.type_hint {
+        q = q.bind(th);
+    }
+    if let Some(ref src) = filter.source {
+        q = q.bind(src);
+    }
+    if let Some(ref tags) = filter.tags {
+        q = q.bind(serde_json::json!(tags));
+    }
+    if let Some(ref ca) = filter.created_after {
+        q = q.bind(ca);
+    }
+    if let Some(ref cb) = filter.created_before {
+        q = q.bind(cb);
+    }
+    if let Some(ref ua) = filter.updated_after {
+        q = q.bind(ua);
+    }
+    if let Some(ref ub) = filter.updated_before {
+        q = q.bind(ub);
+    }
+    if let Some(mi) = filter.min_importance {
+        q = q.bind(mi);
+    }
+    if let Some(ref sid) = filter.session_id {
+        q = q.bind(sid);
+    }
+    if let Some(ref path) = filter.payload_path {
+        q = q.bind(path);
+    }
+    if let Some(ref origin_ref) = filter.origin_ref {
+        q = q.bind(origin_ref_array(origin_ref));
+    }
+    if let Some(count) = filter.min_access_count {
+        q = q.bind(count);
+    }
+    if let Some(ref aa) = filter.accessed_after {
+        q = q.bind(aa);
+    }
+    if let Some(ref status) = filter.embedding_status {
+        q = q.bind(status);
+    }
+    if let Some(ref status) = filter.extraction_status {
+        q = q.bind(status);
+    }
+    bind_exclusions(q, [&filter.exclude_tags, &filter.exclude_sources, &filter.exclude_type_hints])
+}
+
+/// Await one hybrid search leg's queries for at most `limit` (None = no limit). A leg that
+/// runs out of time contributes no candidates instead of failing the search.
+async fn time_leg(
+    leg: &'static str,
+    limit: Option<Duration>,
+    search: impl std::future::Future<Output = Result<Vec<(String, i64, f64)>, MemcpError>>,
+) -> Result<Vec<(String, i64, f64)>, MemcpError> {
+    let Some(limit) = limit else {
+        return search.await;
+    };
+    match tokio::time::timeout(limit, search).await {
+        Ok(result) => result,
+        Err(_) => {
+            crate::metrics::global().search_leg_timeouts.inc();
+            tracing::warn!(leg = leg, timeout_ms = limit.as_millis() as u64, "Search leg timed out — fusing the other legs without it");
+            Ok(vec![])
+        }
+    }
+}
+
+/// Metadata filters shared by the BM25 and symbolic search legs, as SQL over `memories`
+/// with fourteen parameters starting at `$first`: namespace, created_after, created_before,
+/// tags (JSONB containment), type_hint, source, payload path predicate, origin reference
+/// (JSONB containment), min_access_count, accessed_after (all nullable), never_accessed,
+/// then the excluded tags, sources, and type hints (possibly empty arrays).
+/// Bound by `bind_leg_filters`.
+fn leg_filter_sql(first: u32) -> String {
+    format!(
+        "(${0}::text IS NULL OR namespace = ${0}) \
+         AND (${1}::timestamptz IS NULL OR created_at > ${1}) \
+         AND (${2}::timestamptz IS NULL OR created_at < ${2}) \
+         AND (${3}::jsonb IS NULL OR tags @> ${3}) \
+         AND (${4}::text IS NULL OR type_hint = ${4}) \
+         AND (${5}::text IS NULL OR source = ${5}) \
+         AND (${6}::text IS NULL OR payload @@ ${6}::text::jsonpath) \
+         AND (${7}::jsonb IS NULL OR origin_refs @> ${7}) \
+         AND (${8}::bigint IS NULL OR access_count >= ${8}) \
+         AND (${9}::timestamptz IS NULL OR last_accessed_at > ${9}) \
+         AND (NOT ${10}::boolean OR access_count = 0) \
+         AND NOT COALESCE(tags ?| ${11}::text[], FALSE) \
+         AND source <> ALL(${12}::text[]) \
+         AND type_hint <> ALL(${13}::text[])",
+        first,
+        first + 1,
+        first + 2,
+        first + 3,
+        first + 4,
+        first + 5,
+        first + 6,
+        first + 7,
+        first + 8,
+        first + 9,
+        first + 10,
+        first + 11,
+        first + 12,
+        first + 13
+    )
+}
+
+/// Bind the parameters of `leg_filter_sql` from a SearchFilter, in order.
+fn bind_leg_filters<'q>(query: PgQuery<'q>, filter: &'q SearchFilter) -> PgQuery<'q> {
+    query
+        .bind(filter.namespace.as_deref())
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.tags.as_ref().map(|tags| serde_json::json!(tags)))
+        .bind(filter.type_hint.as_deref())
+        .bind(filter.source.as_deref())
+        .bind(filter.payload_path.as_deref())
+        .bind(filter.origin_ref.as_ref().map(origin_ref_array))
+        .bind(filter.min_access_count)
+        .bind(filter.accessed_after)
+        .bind(filter.never_accessed)
+        .bind(&filter.exclude_tags)
+        .bind(&filter.exclude_sources)
+        .bind(&filter.exclude_type_hints)
+}
+
+/// Append NOT-conditions for the exclude_tags/exclude_sources/exclude_type_hints filters
+/// (in that order) on columns qualified by `prefix` ("" or "m."), skipping empty lists.
+/// Binding order must match bind_exclusions().
+fn push_exclusion_conditions(prefix: &str, exclusions: [&[String]; 3], conditions: &mut Vec<String>, param_idx: &mut u32) {
+    let [tags, sources, type_hints] = exclusions;
+    if !tags.is_empty() {
+        // ?| matches when any excluded tag is in the memory's tag array
+        conditions.push(format!("NOT COALESCE({}tags ?| ${}::text[], FALSE)", prefix, param_idx));
+        *param_idx += 1;
+    }
+    if !sources.is_empty() {
+        conditions.push(format!("{}source <> ALL(${}::text[])", prefix, param_idx));
+        *param_idx += 1;
+    }
+    if !type_hints.is_empty() {
+        conditions.push(format!("{}type_hint <> ALL(${}::text[])", prefix, param_idx));
+        *param_idx += 1;
+    }
+}
+
+/// Bind the non-empty exclusion lists in the order push_exclusion_conditions() numbered them.
+fn bind_exclusions<'q>(mut q: PgQuery<'q>, exclusions: [&'q [String]; 3]) -> PgQuery<'q> {
+    for list in exclusions.into_iter().filter(|list| !list.is_empty()) {
+        q = q.bind(list);
+    }
+    q
 }
 
 /// Map a sqlx PgRow to a Memory struct.
@@ -198,68 +913,228 @@ fn row_to_memory(row: &PgRow) -> Result<Memory, MemcpError> {
         extraction_status: row.try_get("extraction_status").unwrap_or_else(|_| "pending".to_string()),
         is_consolidated_original: row.try_get("is_consolidated_original").unwrap_or(false),
         consolidated_into: row.try_get("consolidated_into").unwrap_or(None),
+        namespace: row.try_get("namespace").unwrap_or_else(|_| crate::store::DEFAULT_NAMESPACE.to_string()),
+        deleted_at: row.try_get("deleted_at").unwrap_or(None),
+        expires_at: row.try_get("expires_at").unwrap_or(None),
+        archived_at: row.try_get("archived_at").unwrap_or(None),
+        importance: row.try_get("importance").unwrap_or(crate::store::DEFAULT_IMPORTANCE),
+        session_id: row.try_get("session_id").unwrap_or(None),
+        parent_id: row.try_get("parent_id").unwrap_or(None),
+        chunk_index: row.try_get("chunk_index").unwrap_or(None),
+        fields: row.try_get("fields").unwrap_or(None),
+        payload: row.try_get("payload").unwrap_or(None),
+        pinned: row.try_get("pinned").unwrap_or(false),
+        origin_refs: row.try_get("origin_refs").unwrap_or(None),
+    })
+}
+
+fn row_to_link(row: &PgRow) -> Result<MemoryLink, MemcpError> {
+    Ok(MemoryLink {
+        id: row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        source_id: row.try_get("source_id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        target_id: row.try_get("target_id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        relation: row.try_get("relation").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        created_at: row.try_get("created_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+    })
+}
+
+fn row_to_session(row: &PgRow) -> Result<Session, MemcpError> {
+    Ok(Session {
+        id: row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        namespace: row.try_get("namespace").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        title: row.try_get("title").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        started_at: row.try_get("started_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        ended_at: row.try_get("ended_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        summary_memory_id: row.try_get("summary_memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+    })
+}
+
+fn row_to_memory_type(row: &PgRow) -> Result<MemoryType, MemcpError> {
+    let fields: serde_json::Value = row.try_get("fields").map_err(|e| MemcpError::Storage(e.to_string()))?;
+    Ok(MemoryType {
+        name: row.try_get("name").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        namespace: row.try_get("namespace").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        description: row.try_get("description").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        fields: serde_json::from_value(fields).map_err(|e| MemcpError::Storage(format!("Invalid memory type fields: {}", e)))?,
+        created_at: row.try_get("created_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        updated_at: row.try_get("updated_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+    })
+}
+
+fn row_to_revision(row: &PgRow) -> Result<MemoryRevision, MemcpError> {
+    Ok(MemoryRevision {
+        memory_id: row.try_get("memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        revision: row.try_get("revision").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        content: row.try_get("content").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        type_hint: row.try_get("type_hint").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        source: row.try_get("source").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        tags: row.try_get("tags").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        valid_from: row.try_get("valid_from").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        replaced_at: row.try_get("replaced_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+    })
+}
+
+/// Keyed digest of `content` for exact-duplicate lookups with encryption on (None without a
+/// cipher). Normalized like the content_hash column: lowercased, whitespace collapsed.
+fn content_digest(cipher: Option<&ContentCipher>, content: &str) -> Option<String> {
+    cipher.map(|cipher| cipher.keyed_hash(&content.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()))
+}
+
+/// Insert a single memory row using any executor (pool or open transaction).
+///
+/// Shared by store() and store_batch() so both paths write identical rows. With a cipher
+/// the content is stored encrypted; the returned Memory always holds the plaintext.
+async fn insert_memory<'c, E>(executor: E, input: CreateMemory, cipher: Option<&ContentCipher>) -> Result<Memory, MemcpError>
+where
+    E: sqlx::Executor<'c, Database = Postgres>,
+{
+    let id = Uuid::new_v4().to_string();
+    let now = input.created_at.unwrap_or_else(Utc::now);
+    let stored_content = match cipher {
+        Some(cipher) => cipher.encrypt(&input.content)?,
+        None => input.content.clone(),
+    };
+
+    // Convert tags Vec<String> to serde_json::Value for JSONB binding
+    let tags_json: Option<serde_json::Value> = input
+        .tags
+        .as_ref()
+        .map(|t| serde_json::json!(t));
+    let origin_refs = (!input.origin_refs.is_empty()).then(|| serde_json::json!(input.origin_refs));
+
+    sqlx::query(
+        "INSERT INTO memories (id, content, type_hint, source, tags, created_at, updated_at, access_count, embedding_status, namespace, expires_at, importance, session_id, fields, payload, origin_refs, content_digest) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, 0, 'pending', $8, $9, $10, $11, $12, $13, $14, $15)",
+    )
+    .bind(&id)
+    .bind(&stored_content)
+    .bind(&input.type_hint)
+    .bind(&input.source)
+    .bind(&tags_json)     // JSONB — bind serde_json::Value directly
+    .bind(&now)           // TIMESTAMPTZ — bind DateTime<Utc> directly
+    .bind(&now)
+    .bind(&input.namespace)
+    .bind(input.expires_at)
+    .bind(input.importance)
+    .bind(&input.session_id)
+    .bind(&input.fields)
+    .bind(&input.payload)
+    .bind(&origin_refs)
+    .bind(content_digest(cipher, &input.content))
+    .execute(executor)
+    .await
+    .map_err(|e| MemcpError::Storage(format!("Failed to insert memory: {}", e)))?;
+
+    Ok(Memory {
+        id,
+        content: input.content,
+        type_hint: input.type_hint,
+        source: input.source,
+        tags: tags_json,
+        created_at: now,
+        updated_at: now,
+        last_accessed_at: None,
+        access_count: 0,
+        embedding_status: "pending".to_string(),
+        extracted_entities: None,
+        extracted_facts: None,
+        extraction_status: "pending".to_string(),
+        is_consolidated_original: false,
+        consolidated_into: None,
+        namespace: input.namespace,
+        deleted_at: None,
+        expires_at: input.expires_at,
+        archived_at: None,
+        importance: input.importance,
+        session_id: input.session_id,
+        parent_id: None,
+        chunk_index: None,
+        fields: input.fields,
+        payload: input.payload,
+        pinned: false,
+        origin_refs,
     })
 }
 
+/// An origin reference as a one-element JSONB array, the containment operand of an
+/// origin_ref filter.
+fn origin_ref_array(origin_ref: &OriginRef) -> serde_json::Value {
+    serde_json::json!([origin_ref])
+}
+
+/// Queue outbox jobs of each kind for a memory inside the transaction that inserted it.
+async fn insert_pipeline_jobs<'c, E>(executor: E, memory_id: &str, kinds: &[JobKind], classify: bool) -> Result<(), MemcpError>
+where
+    E: sqlx::Executor<'c, Database = Postgres>,
+{
+    if kinds.is_empty() {
+        return Ok(());
+    }
+    let kinds: Vec<&str> = kinds.iter().map(JobKind::as_str).collect();
+    sqlx::query(
+        "INSERT INTO pipeline_jobs (memory_id, kind, classify) \
+         SELECT $1, kind, $3 FROM unnest($2::text[]) AS kind \
+         ON CONFLICT (memory_id, kind) DO NOTHING",
+    )
+    .bind(memory_id)
+    .bind(&kinds)
+    .bind(classify)
+    .execute(executor)
+    .await
+    .map_err(|e| MemcpError::Storage(format!("Failed to queue pipeline jobs: {}", e)))?;
+    Ok(())
+}
+
 #[async_trait]
 impl MemoryStore for PostgresMemoryStore {
     async fn store(&self, input: CreateMemory) -> Result<Memory, MemcpError> {
-        let id = Uuid::new_v4().to_string();
-        let now = input.created_at.unwrap_or_else(Utc::now);
+        if self.outbox.is_empty() {
+            return insert_memory(&self.pool, input, self.cipher.as_deref()).await;
+        }
+        // The memory and its outbox jobs commit together
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin insert transaction: {}", e))
+        })?;
+        let classify = input.classify;
+        let memory = insert_memory(&mut *tx, input, self.cipher.as_deref()).await?;
+        insert_pipeline_jobs(&mut *tx, &memory.id, &self.outbox, classify).await?;
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit insert transaction: {}", e))
+        })?;
+        Ok(memory)
+    }
 
-        // Convert tags Vec<String> to serde_json::Value for JSONB binding
-        let tags_json: Option<serde_json::Value> = input
-            .tags
-            .as_ref()
-            .map(|t| serde_json::json!(t));
+    async fn store_batch(&self, inputs: Vec<CreateMemory>) -> Result<Vec<Memory>, MemcpError> {
+        // Single transaction: either every memory in the batch is stored or none are
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin batch insert transaction: {}", e))
+        })?;
 
-        sqlx::query(
-            "INSERT INTO memories (id, content, type_hint, source, tags, created_at, updated_at, access_count, embedding_status) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7, 0, 'pending')",
-        )
-        .bind(&id)
-        .bind(&input.content)
-        .bind(&input.type_hint)
-        .bind(&input.source)
-        .bind(&tags_json)     // JSONB — bind serde_json::Value directly
-        .bind(&now)           // TIMESTAMPTZ — bind DateTime<Utc> directly
-        .bind(&now)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| MemcpError::Storage(format!("Failed to insert memory: {}", e)))?;
-
-        Ok(Memory {
-            id,
-            content: input.content,
-            type_hint: input.type_hint,
-            source: input.source,
-            tags: tags_json,
-            created_at: now,
-            updated_at: now,
-            last_accessed_at: None,
-            access_count: 0,
-            embedding_status: "pending".to_string(),
-            extracted_entities: None,
-            extracted_facts: None,
-            extraction_status: "pending".to_string(),
-            is_consolidated_original: false,
-            consolidated_into: None,
-        })
+        let mut memories = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let classify = input.classify;
+            let memory = insert_memory(&mut *tx, input, self.cipher.as_deref()).await?;
+            insert_pipeline_jobs(&mut *tx, &memory.id, &self.outbox, classify).await?;
+            memories.push(memory);
+        }
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit batch insert transaction: {}", e))
+        })?;
+
+        Ok(memories)
     }
 
     async fn get(&self, id: &str) -> Result<Memory, MemcpError> {
-        let row = sqlx::query(
-            "SELECT id, content, type_hint, source, tags, created_at, updated_at, last_accessed_at, access_count, embedding_status, \
-             extracted_entities, extracted_facts, extraction_status, is_consolidated_original, consolidated_into \
-             FROM memories WHERE id = $1",
-        )
-        .bind(id)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| MemcpError::Storage(e.to_string()))?
-        .ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?;
+        let sql = format!("SELECT {} FROM memories WHERE id = $1 AND deleted_at IS NULL", MEMORY_COLUMNS);
+        let row = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?
+            .ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?;
 
-        let memory = row_to_memory(&row)?;
+        let memory = self.memory_from_row(&row)?;
 
         // Fire-and-forget touch to update access stats
         let _ = self.touch(id).await;
@@ -268,10 +1143,15 @@ impl MemoryStore for PostgresMemoryStore {
     }
 
     async fn update(&self, id: &str, input: UpdateMemory) -> Result<Memory, MemcpError> {
-        // Verify the memory exists first
-        let row = sqlx::query("SELECT id FROM memories WHERE id = $1")
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin update transaction: {}", e))
+        })?;
+
+        // Verify the memory exists first (trashed memories must be restored before editing).
+        // FOR UPDATE serializes concurrent updates so revision numbers never collide.
+        let row = sqlx::query("SELECT id FROM memories WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
             .bind(id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *tx)
             .await
             .map_err(|e| MemcpError::Storage(e.to_string()))?;
 
@@ -279,6 +1159,23 @@ impl MemoryStore for PostgresMemoryStore {
             return Err(MemcpError::NotFound { id: id.to_string() });
         }
 
+        // Snapshot the current version when any versioned field changes
+        if input.content.is_some() || input.type_hint.is_some() || input.source.is_some() || input.tags.is_some() {
+            sqlx::query(
+                "INSERT INTO memory_revisions \
+                 (id, memory_id, revision, content, type_hint, source, tags, valid_from, replaced_at) \
+                 SELECT $1, m.id, \
+                        COALESCE((SELECT MAX(revision) FROM memory_revisions WHERE memory_id = m.id), 0) + 1, \
+                        m.content, m.type_hint, m.source, m.tags, m.updated_at, NOW() \
+                 FROM memories m WHERE m.id = $2",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to snapshot memory revision: {}", e)))?;
+        }
+
         let now = Utc::now();
 
         // Build dynamic SET clause with numbered PostgreSQL parameters
@@ -290,8 +1187,8 @@ impl MemoryStore for PostgresMemoryStore {
         param_idx += 1;
 
         if input.content.is_some() {
-            sets.push(format!("content = ${}", param_idx));
-            param_idx += 1;
+            sets.push(format!("content = ${}, content_digest = ${}", param_idx, param_idx + 1));
+            param_idx += 2;
         }
         if input.type_hint.is_some() {
             sets.push(format!("type_hint = ${}", param_idx));
@@ -305,16 +1202,33 @@ impl MemoryStore for PostgresMemoryStore {
             sets.push(format!("tags = ${}", param_idx));
             param_idx += 1;
         }
-
-        let sql = format!(
-            "UPDATE memories SET {} WHERE id = ${}",
-            sets.join(", "),
+        if input.expires_at.is_some() {
+            sets.push(format!("expires_at = ${}", param_idx));
+            param_idx += 1;
+        }
+        if input.importance.is_some() {
+            sets.push(format!("importance = ${}", param_idx));
+            param_idx += 1;
+        }
+        if input.payload.is_some() {
+            sets.push(format!("payload = ${}", param_idx));
+            param_idx += 1;
+        }
+        if input.pinned.is_some() {
+            sets.push(format!("pinned = ${}", param_idx));
+            param_idx += 1;
+        }
+
+        let sql = format!(
+            "UPDATE memories SET {} WHERE id = ${}",
+            sets.join(", "),
             param_idx
         );
 
+        let stored_content = input.content.as_deref().map(|c| self.encrypt_content(c)).transpose()?;
         let mut q = sqlx::query(&sql).bind(&now); // $1 = updated_at
-        if let Some(ref content) = input.content {
-            q = q.bind(content);
+        if let Some(ref content) = stored_content {
+            q = q.bind(content).bind(input.content.as_deref().and_then(|c| content_digest(self.cipher.as_deref(), c)));
         }
         if let Some(ref type_hint) = input.type_hint {
             q = q.bind(type_hint);
@@ -327,24 +1241,37 @@ impl MemoryStore for PostgresMemoryStore {
             let tags_json = serde_json::json!(tags);
             q = q.bind(tags_json);
         }
+        if let Some(expires_at) = input.expires_at {
+            q = q.bind(expires_at);
+        }
+        if let Some(importance) = input.importance {
+            q = q.bind(importance);
+        }
+        if let Some(ref payload) = input.payload {
+            q = q.bind(payload);
+        }
+        if let Some(pinned) = input.pinned {
+            q = q.bind(pinned);
+        }
         q = q.bind(id); // final $N = id
 
-        q.execute(&self.pool)
+        q.execute(&mut *tx)
             .await
             .map_err(|e| MemcpError::Storage(format!("Failed to update memory: {}", e)))?;
 
         // Re-fetch and return the updated record
-        let updated_row = sqlx::query(
-            "SELECT id, content, type_hint, source, tags, created_at, updated_at, last_accessed_at, access_count, embedding_status, \
-             extracted_entities, extracted_facts, extraction_status, is_consolidated_original, consolidated_into \
-             FROM memories WHERE id = $1",
-        )
-        .bind(id)
-        .fetch_one(&self.pool)
-        .await
-        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let select_sql = format!("SELECT {} FROM memories WHERE id = $1", MEMORY_COLUMNS);
+        let updated_row = sqlx::query(&select_sql)
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
 
-        row_to_memory(&updated_row)
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit update transaction: {}", e))
+        })?;
+
+        self.memory_from_row(&updated_row)
     }
 
     async fn delete(&self, id: &str) -> Result<(), MemcpError> {
@@ -361,6 +1288,55 @@ impl MemoryStore for PostgresMemoryStore {
         Ok(())
     }
 
+    async fn trash(&self, id: &str) -> Result<(), MemcpError> {
+        // A chunked memory's chunks go to the trash with it
+        let result = sqlx::query(
+            "UPDATE memories SET deleted_at = NOW() WHERE (id = $1 OR parent_id = $1) AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to trash memory: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(MemcpError::NotFound { id: id.to_string() });
+        }
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: &str) -> Result<Memory, MemcpError> {
+        // A passed expiry is cleared — otherwise the memory stays hidden and the next
+        // expiry sweep trashes it again
+        let restored = "deleted_at = NULL, archived_at = NULL, \
+                        expires_at = CASE WHEN expires_at <= NOW() THEN NULL ELSE expires_at END";
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin restore transaction: {}", e))
+        })?;
+        let sql = format!(
+            "UPDATE memories SET {} \
+             WHERE id = $1 AND (deleted_at IS NOT NULL OR archived_at IS NOT NULL) RETURNING {}",
+            restored, MEMORY_COLUMNS
+        );
+        let row = sqlx::query(&sql)
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to restore memory: {}", e)))?
+            .ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?;
+
+        sqlx::query(&format!("UPDATE memories SET {} WHERE parent_id = $1", restored))
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to restore memory chunks: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit restore transaction: {}", e))
+        })?;
+        self.memory_from_row(&row)
+    }
+
     async fn list(&self, filter: ListFilter) -> Result<ListResult, MemcpError> {
         let limit = filter.limit.min(100).max(1);
 
@@ -370,30 +1346,7 @@ impl MemoryStore for PostgresMemoryStore {
         let mut cursor_created_at: Option<DateTime<Utc>> = None;
         let mut cursor_id: Option<String> = None;
 
-        if filter.type_hint.is_some() {
-            conditions.push(format!("type_hint = ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.source.is_some() {
-            conditions.push(format!("source = ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.created_after.is_some() {
-            conditions.push(format!("created_at > ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.created_before.is_some() {
-            conditions.push(format!("created_at < ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.updated_after.is_some() {
-            conditions.push(format!("updated_at > ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.updated_before.is_some() {
-            conditions.push(format!("updated_at < ${}", param_idx));
-            param_idx += 1;
-        }
+        push_list_conditions(&filter, &mut conditions, &mut param_idx);
         if let Some(ref cursor) = filter.cursor {
             let (ca, cid) = decode_cursor(cursor)?;
             cursor_created_at = Some(ca);
@@ -413,31 +1366,11 @@ impl MemoryStore for PostgresMemoryStore {
         };
 
         let sql = format!(
-            "SELECT id, content, type_hint, source, tags, created_at, updated_at, last_accessed_at, access_count, embedding_status, \
-             extracted_entities, extracted_facts, extraction_status, is_consolidated_original, consolidated_into \
-             FROM memories {} ORDER BY created_at DESC, id ASC LIMIT ${}",
-            where_clause, param_idx
+            "SELECT {} FROM memories {} ORDER BY created_at DESC, id ASC LIMIT ${}",
+            MEMORY_COLUMNS, where_clause, param_idx
         );
 
-        let mut q = sqlx::query(&sql);
-        if let Some(ref th) = filter.type_hint {
-            q = q.bind(th);
-        }
-        if let Some(ref src) = filter.source {
-            q = q.bind(src);
-        }
-        if let Some(ref ca) = filter.created_after {
-            q = q.bind(ca);
-        }
-        if let Some(ref cb) = filter.created_before {
-            q = q.bind(cb);
-        }
-        if let Some(ref ua) = filter.updated_after {
-            q = q.bind(ua);
-        }
-        if let Some(ref ub) = filter.updated_before {
-            q = q.bind(ub);
-        }
+        let mut q = bind_list_filter(sqlx::query(&sql), &filter);
         if let Some(ref ca) = cursor_created_at {
             let cid = cursor_id.as_deref().unwrap_or("");
             // Bind 3 times for the cursor comparison: $N, $N+1 (same value), $N+2
@@ -456,7 +1389,7 @@ impl MemoryStore for PostgresMemoryStore {
         let mut memories = Vec::with_capacity(take);
 
         for row in rows.iter().take(take) {
-            memories.push(row_to_memory(row)?);
+            memories.push(self.memory_from_row(row)?);
         }
 
         let next_cursor = if has_more {
@@ -474,31 +1407,7 @@ impl MemoryStore for PostgresMemoryStore {
     async fn count_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError> {
         let mut conditions: Vec<String> = Vec::new();
         let mut param_idx: u32 = 1;
-
-        if filter.type_hint.is_some() {
-            conditions.push(format!("type_hint = ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.source.is_some() {
-            conditions.push(format!("source = ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.created_after.is_some() {
-            conditions.push(format!("created_at > ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.created_before.is_some() {
-            conditions.push(format!("created_at < ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.updated_after.is_some() {
-            conditions.push(format!("updated_at > ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.updated_before.is_some() {
-            conditions.push(format!("updated_at < ${}", param_idx));
-            let _ = param_idx + 1; // suppress unused increment warning
-        }
+        push_list_conditions(filter, &mut conditions, &mut param_idx);
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -508,27 +1417,7 @@ impl MemoryStore for PostgresMemoryStore {
 
         let sql = format!("SELECT COUNT(*) as count FROM memories {}", where_clause);
 
-        let mut q = sqlx::query(&sql);
-        if let Some(ref th) = filter.type_hint {
-            q = q.bind(th);
-        }
-        if let Some(ref src) = filter.source {
-            q = q.bind(src);
-        }
-        if let Some(ref ca) = filter.created_after {
-            q = q.bind(ca);
-        }
-        if let Some(ref cb) = filter.created_before {
-            q = q.bind(cb);
-        }
-        if let Some(ref ua) = filter.updated_after {
-            q = q.bind(ua);
-        }
-        if let Some(ref ub) = filter.updated_before {
-            q = q.bind(ub);
-        }
-
-        let row = q
+        let row = bind_list_filter(sqlx::query(&sql), filter)
             .fetch_one(&self.pool)
             .await
             .map_err(|e| MemcpError::Storage(e.to_string()))?;
@@ -540,31 +1429,7 @@ impl MemoryStore for PostgresMemoryStore {
     async fn delete_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError> {
         let mut conditions: Vec<String> = Vec::new();
         let mut param_idx: u32 = 1;
-
-        if filter.type_hint.is_some() {
-            conditions.push(format!("type_hint = ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.source.is_some() {
-            conditions.push(format!("source = ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.created_after.is_some() {
-            conditions.push(format!("created_at > ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.created_before.is_some() {
-            conditions.push(format!("created_at < ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.updated_after.is_some() {
-            conditions.push(format!("updated_at > ${}", param_idx));
-            param_idx += 1;
-        }
-        if filter.updated_before.is_some() {
-            conditions.push(format!("updated_at < ${}", param_idx));
-            let _ = param_idx + 1; // suppress unused increment warning
-        }
+        push_list_conditions(filter, &mut conditions, &mut param_idx);
 
         let where_clause = if conditions.is_empty() {
             String::new()
@@ -574,27 +1439,7 @@ impl MemoryStore for PostgresMemoryStore {
 
         let sql = format!("DELETE FROM memories {}", where_clause);
 
-        let mut q = sqlx::query(&sql);
-        if let Some(ref th) = filter.type_hint {
-            q = q.bind(th);
-        }
-        if let Some(ref src) = filter.source {
-            q = q.bind(src);
-        }
-        if let Some(ref ca) = filter.created_after {
-            q = q.bind(ca);
-        }
-        if let Some(ref cb) = filter.created_before {
-            q = q.bind(cb);
-        }
-        if let Some(ref ua) = filter.updated_after {
-            q = q.bind(ua);
-        }
-        if let Some(ref ub) = filter.updated_before {
-            q = q.bind(ub);
-        }
-
-        let result = q
+        let result = bind_list_filter(sqlx::query(&sql), filter)
             .execute(&self.pool)
             .await
             .map_err(|e| MemcpError::Storage(e.to_string()))?;
@@ -602,6 +1447,115 @@ impl MemoryStore for PostgresMemoryStore {
         Ok(result.rows_affected())
     }
 
+    async fn trash_matching(&self, filter: &ListFilter) -> Result<u64, MemcpError> {
+        // Only live memories can be trashed, regardless of filter.trashed
+        let live = ListFilter { trashed: false, ..filter.clone() };
+
+        let mut conditions: Vec<String> = Vec::new();
+        let mut param_idx: u32 = 1;
+        push_list_conditions(&live, &mut conditions, &mut param_idx);
+
+        let sql = format!(
+            "UPDATE memories SET deleted_at = NOW() WHERE {}",
+            conditions.join(" AND ")
+        );
+
+        let result = bind_list_filter(sqlx::query(&sql), &live)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to trash memories: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn update_matching(&self, filter: &ListFilter, update: &BulkUpdate) -> Result<Vec<Memory>, MemcpError> {
+        // Only live memories are updated, regardless of filter.trashed
+        let live = ListFilter { trashed: false, ..filter.clone() };
+
+        let mut conditions: Vec<String> = Vec::new();
+        let mut param_idx: u32 = 1;
+        push_list_conditions(&live, &mut conditions, &mut param_idx);
+
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin bulk update transaction: {}", e))
+        })?;
+
+        // Lock matching rows so concurrent updates can't interleave revision numbers
+        let sql = format!("SELECT id FROM memories WHERE {} FOR UPDATE", conditions.join(" AND "));
+        let ids: Vec<String> = bind_list_filter(sqlx::query(&sql), &live)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to find matching memories: {}", e)))?
+            .iter()
+            .map(|row| row.try_get("id"))
+            .collect::<Result<_, _>>()
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let revision_ids: Vec<String> = ids.iter().map(|_| Uuid::new_v4().to_string()).collect();
+        sqlx::query(
+            "INSERT INTO memory_revisions \
+             (id, memory_id, revision, content, type_hint, source, tags, valid_from, replaced_at) \
+             SELECT r.revision_id, m.id, \
+                    COALESCE((SELECT MAX(revision) FROM memory_revisions WHERE memory_id = m.id), 0) + 1, \
+                    m.content, m.type_hint, m.source, m.tags, m.updated_at, NOW() \
+             FROM memories m \
+             JOIN UNNEST($1::text[], $2::text[]) AS r(memory_id, revision_id) ON r.memory_id = m.id",
+        )
+        .bind(&ids)
+        .bind(&revision_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to snapshot memory revisions: {}", e)))?;
+
+        // Tag edits: existing tags then added ones, minus removed ones, first occurrence kept
+        let sql = format!(
+            "UPDATE memories m SET updated_at = NOW(), \
+                 type_hint = COALESCE($2, m.type_hint), \
+                 source = COALESCE($3, m.source), \
+                 importance = COALESCE($4, m.importance), \
+                 tags = CASE WHEN cardinality($5::text[]) + cardinality($6::text[]) = 0 THEN m.tags ELSE ( \
+                     SELECT COALESCE(jsonb_agg(t.tag ORDER BY t.pos, t.ord), '[]'::jsonb) \
+                     FROM ( \
+                         SELECT DISTINCT ON (tag) tag, pos, ord \
+                         FROM ( \
+                             SELECT e.tag, 0 AS pos, e.ord \
+                             FROM jsonb_array_elements_text( \
+                                 CASE WHEN jsonb_typeof(m.tags) = 'array' THEN m.tags ELSE '[]'::jsonb END \
+                             ) WITH ORDINALITY AS e(tag, ord) \
+                             UNION ALL \
+                             SELECT a.tag, 1 AS pos, a.ord \
+                             FROM UNNEST($5::text[]) WITH ORDINALITY AS a(tag, ord) \
+                         ) merged \
+                         WHERE tag <> ALL($6::text[]) \
+                         ORDER BY tag, pos, ord \
+                     ) t \
+                 ) END \
+             WHERE m.id = ANY($1) \
+             RETURNING {}",
+            memory_columns_with_alias("m")
+        );
+        let rows = sqlx::query(&sql)
+            .bind(&ids)
+            .bind(&update.type_hint)
+            .bind(&update.source)
+            .bind(update.importance)
+            .bind(&update.add_tags)
+            .bind(&update.remove_tags)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to update memories: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit bulk update transaction: {}", e))
+        })?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
     async fn touch(&self, id: &str) -> Result<(), MemcpError> {
         let now = Utc::now();
         // Silently ignore if id doesn't exist (fire-and-forget)
@@ -615,10 +1569,57 @@ impl MemoryStore for PostgresMemoryStore {
 
         Ok(())
     }
+
+    async fn touch_many(&self, ids: &[String]) -> Result<(), MemcpError> {
+        // Same fire-and-forget semantics as touch(), in one statement
+        let _ = sqlx::query(
+            "UPDATE memories SET last_accessed_at = NOW(), access_count = access_count + 1 WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .execute(&self.pool)
+        .await;
+
+        Ok(())
+    }
+
+    async fn get_memories_by_ids(&self, ids: &[String]) -> Result<HashMap<String, Memory>, MemcpError> {
+        PostgresMemoryStore::get_memories_by_ids(self, ids).await
+    }
+
+    async fn search_similar(&self, filter: &SearchFilter) -> Result<SearchResult, MemcpError> {
+        PostgresMemoryStore::search_similar(self, filter).await
+    }
+
+    async fn pinned_memories(&self, filter: &SearchFilter) -> Result<Vec<Memory>, MemcpError> {
+        let sql = format!(
+            "SELECT {} FROM memories \
+             WHERE pinned \
+               AND is_consolidated_original = FALSE \
+               AND deleted_at IS NULL \
+               AND archived_at IS NULL \
+               AND (expires_at IS NULL OR expires_at > NOW()) \
+               AND {} \
+             ORDER BY updated_at DESC, id \
+             LIMIT $15",
+            MEMORY_COLUMNS,
+            leg_filter_sql(1)
+        );
+        let rows = bind_leg_filters(sqlx::query(&sql), filter)
+            .bind(filter.limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to fetch pinned memories: {}", e)))?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
 }
 
 impl PostgresMemoryStore {
     /// Insert a new embedding record for a memory.
+    ///
+    /// Replaces any earlier embedding of the same memory under the same model, so each
+    /// memory holds at most one vector per model. Embeddings under other models are kept,
+    /// which lets two models coexist while migrating between them.
     pub async fn insert_embedding(
         &self,
         id: &str,
@@ -630,6 +1631,17 @@ impl PostgresMemoryStore {
         is_current: bool,
     ) -> Result<(), MemcpError> {
         let now = Utc::now();
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin embedding transaction: {}", e))
+        })?;
+
+        sqlx::query("DELETE FROM memory_embeddings WHERE memory_id = $1 AND model_name = $2")
+            .bind(memory_id)
+            .bind(model_name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to replace embedding: {}", e)))?;
+
         sqlx::query(
             "INSERT INTO memory_embeddings \
              (id, memory_id, model_name, model_version, dimension, embedding, is_current, created_at, updated_at) \
@@ -644,10 +1656,14 @@ impl PostgresMemoryStore {
         .bind(is_current)
         .bind(&now)
         .bind(&now)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await
         .map_err(|e| MemcpError::Storage(format!("Failed to insert embedding: {}", e)))?;
 
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit embedding transaction: {}", e))
+        })?;
+
         Ok(())
     }
 
@@ -667,50 +1683,195 @@ impl PostgresMemoryStore {
         Ok(())
     }
 
-    /// Retrieve memories that need embedding (status 'pending' or 'failed'), ordered oldest first.
-    pub async fn get_pending_memories(&self, limit: i64) -> Result<Vec<crate::store::Memory>, MemcpError> {
-        let rows = sqlx::query(
-            "SELECT id, content, type_hint, source, tags, created_at, updated_at, last_accessed_at, access_count, embedding_status, \
-             extracted_entities, extracted_facts, extraction_status, is_consolidated_original, consolidated_into \
-             FROM memories WHERE embedding_status IN ('pending', 'failed') \
-             ORDER BY created_at ASC LIMIT $1",
+    /// Record a failed embedding attempt in the dead-letter table.
+    ///
+    /// Upserts per memory: increments attempts and keeps the latest error message.
+    pub async fn record_embedding_failure(
+        &self,
+        memory_id: &str,
+        error: &str,
+    ) -> Result<(), MemcpError> {
+        sqlx::query(
+            "INSERT INTO memory_embedding_failures (memory_id, last_error, attempts, first_failed_at, last_failed_at) \
+             VALUES ($1, $2, 1, NOW(), NOW()) \
+             ON CONFLICT (memory_id) DO UPDATE SET \
+                 last_error = EXCLUDED.last_error, \
+                 attempts = memory_embedding_failures.attempts + 1, \
+                 last_failed_at = NOW()",
         )
-        .bind(limit)
-        .fetch_all(&self.pool)
+        .bind(memory_id)
+        .bind(error)
+        .execute(&self.pool)
         .await
-        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+        .map_err(|e| MemcpError::Storage(format!("Failed to record embedding failure: {}", e)))?;
 
-        rows.iter().map(row_to_memory).collect()
+        Ok(())
     }
 
-    /// Return embedding statistics grouped by status and by model.
-    ///
-    /// Returns:
-    /// ```json
-    /// { "by_status": { "pending": N, "complete": N, "failed": N },
-    ///   "by_model": [ { "model_name": ..., "model_version": ..., "is_current": true, "count": N } ] }
-    /// ```
-    pub async fn embedding_stats(&self) -> Result<serde_json::Value, MemcpError> {
-        // Query 1: counts by embedding_status
-        let status_rows = sqlx::query(
-            "SELECT embedding_status, COUNT(*) as count FROM memories GROUP BY embedding_status",
+    /// Remove a memory from the dead-letter table after a successful embedding.
+    pub async fn clear_embedding_failure(&self, memory_id: &str) -> Result<(), MemcpError> {
+        sqlx::query("DELETE FROM memory_embedding_failures WHERE memory_id = $1")
+            .bind(memory_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to clear embedding failure: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// List dead-letter entries, most recent failure first.
+    pub async fn list_embedding_failures(&self, limit: i64) -> Result<Vec<EmbeddingFailure>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT memory_id, last_error, attempts, first_failed_at, last_failed_at \
+             FROM memory_embedding_failures ORDER BY last_failed_at DESC LIMIT $1",
         )
+        .bind(limit)
         .fetch_all(&self.pool)
         .await
         .map_err(|e| MemcpError::Storage(e.to_string()))?;
 
-        let mut by_status = serde_json::Map::new();
-        for row in &status_rows {
-            let status: String = row
-                .try_get("embedding_status")
-                .map_err(|e| MemcpError::Storage(e.to_string()))?;
-            let count: i64 = row
-                .try_get("count")
-                .map_err(|e| MemcpError::Storage(e.to_string()))?;
-            by_status.insert(status, serde_json::json!(count));
-        }
-
-        // Query 2: counts by model
+        rows.iter()
+            .map(|row| {
+                Ok(EmbeddingFailure {
+                    memory_id: row.try_get("memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    last_error: row.try_get("last_error").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    attempts: row.try_get("attempts").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    first_failed_at: row.try_get("first_failed_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    last_failed_at: row.try_get("last_failed_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Retrieve live memories whose embedding is marked 'failed', oldest first.
+    pub async fn get_failed_embedding_memories(&self, limit: i64) -> Result<Vec<crate::store::Memory>, MemcpError> {
+        let sql = format!(
+            "SELECT {} FROM memories WHERE embedding_status = 'failed' AND deleted_at IS NULL \
+             ORDER BY created_at ASC LIMIT $1",
+            MEMORY_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
+    /// Retrieve memories that need embedding (status 'pending' or 'failed'), ordered oldest first.
+    /// Memories with an embedding job in the outbox are left to the outbox relay.
+    pub async fn get_pending_memories(&self, limit: i64) -> Result<Vec<crate::store::Memory>, MemcpError> {
+        let sql = format!(
+            "SELECT {} FROM memories WHERE embedding_status IN ('pending', 'failed') \
+             AND NOT EXISTS (SELECT 1 FROM pipeline_jobs j WHERE j.memory_id = memories.id AND j.kind = 'embedding') \
+             ORDER BY created_at ASC LIMIT $1",
+            MEMORY_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
+    /// Count the memories `get_pending_memories` would return (pending or failed, not queued
+    /// in the outbox). Used as the total for backfill progress.
+    pub async fn count_pending_memories(&self) -> Result<i64, MemcpError> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM memories WHERE embedding_status IN ('pending', 'failed') \
+             AND NOT EXISTS (SELECT 1 FROM pipeline_jobs j WHERE j.memory_id = memories.id AND j.kind = 'embedding')",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))
+    }
+
+    /// Page through pending or failed memories oldest first, starting after the
+    /// `(created_at, id)` of the last memory of the previous page.
+    ///
+    /// Keyset paging lets a backfill run move past memories that fail again instead of
+    /// fetching them forever.
+    pub async fn get_pending_memories_after(
+        &self,
+        after: Option<&(DateTime<Utc>, String)>,
+        limit: i64,
+    ) -> Result<Vec<crate::store::Memory>, MemcpError> {
+        let sql = format!(
+            "SELECT {} FROM memories WHERE embedding_status IN ('pending', 'failed') \
+             AND NOT EXISTS (SELECT 1 FROM pipeline_jobs j WHERE j.memory_id = memories.id AND j.kind = 'embedding') \
+             AND ($1::timestamptz IS NULL OR (created_at, id) > ($1, $2)) \
+             ORDER BY created_at ASC, id ASC LIMIT $3",
+            MEMORY_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(after.map(|(created_at, _)| *created_at))
+            .bind(after.map(|(_, id)| id.as_str()).unwrap_or(""))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
+    /// Retrieve live memories that have no embedding under `model_name`, ordered oldest first.
+    ///
+    /// Used by `embed backfill --model` to fill the gap for one model without touching
+    /// memories that already carry a vector from it.
+    pub async fn get_memories_missing_embedding(
+        &self,
+        model_name: &str,
+        limit: i64,
+    ) -> Result<Vec<crate::store::Memory>, MemcpError> {
+        let sql = format!(
+            "SELECT {} FROM memories m WHERE m.deleted_at IS NULL AND m.embedding_status <> 'chunked' \
+             AND NOT EXISTS (SELECT 1 FROM memory_embeddings me \
+                             WHERE me.memory_id = m.id AND me.model_name = $1) \
+             ORDER BY m.created_at ASC LIMIT $2",
+            memory_columns_with_alias("m")
+        );
+        let rows = sqlx::query(&sql)
+            .bind(model_name)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
+    /// Return embedding statistics grouped by status and by model.
+    ///
+    /// Returns:
+    /// ```json
+    /// { "by_status": { "pending": N, "complete": N, "failed": N },
+    ///   "by_model": [ { "model_name": ..., "model_version": ..., "is_current": true, "count": N } ],
+    ///   "failures": { "count": N, "max_attempts": N, "oldest": ..., "recent": [ ... ] } }
+    /// ```
+    pub async fn embedding_stats(&self) -> Result<serde_json::Value, MemcpError> {
+        // Query 1: counts by embedding_status
+        let status_rows = sqlx::query(
+            "SELECT embedding_status, COUNT(*) as count FROM memories GROUP BY embedding_status",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        let mut by_status = serde_json::Map::new();
+        for row in &status_rows {
+            let status: String = row
+                .try_get("embedding_status")
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let count: i64 = row
+                .try_get("count")
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            by_status.insert(status, serde_json::json!(count));
+        }
+
+        // Query 2: counts by model
         let model_rows = sqlx::query(
             "SELECT model_name, model_version, is_current, COUNT(*) as count \
              FROM memory_embeddings GROUP BY model_name, model_version, is_current",
@@ -741,12 +1902,73 @@ impl PostgresMemoryStore {
             }));
         }
 
+        // Query 3: dead-letter summary
+        let failure_row = sqlx::query(
+            "SELECT COUNT(*) AS count, COALESCE(MAX(attempts), 0) AS max_attempts, \
+                    MIN(first_failed_at) AS oldest \
+             FROM memory_embedding_failures",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let failure_count: i64 = failure_row.try_get("count").map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let max_attempts: i32 = failure_row.try_get("max_attempts").map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let oldest: Option<chrono::DateTime<Utc>> = failure_row.try_get("oldest").map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let recent: Vec<serde_json::Value> = self
+            .list_embedding_failures(5)
+            .await?
+            .into_iter()
+            .map(|f| serde_json::json!({
+                "memory_id": f.memory_id,
+                "error": f.last_error,
+                "attempts": f.attempts,
+                "last_failed_at": f.last_failed_at.to_rfc3339(),
+            }))
+            .collect();
+
         Ok(serde_json::json!({
             "by_status": by_status,
             "by_model": by_model,
+            "failures": {
+                "count": failure_count,
+                "max_attempts": max_attempts,
+                "oldest": oldest.map(|dt| dt.to_rfc3339()),
+                "recent": recent,
+            },
         }))
     }
 
+    /// Round-trip a trivial query to confirm the database is reachable.
+    pub async fn ping(&self) -> Result<(), MemcpError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Count live memories pending or failed in the embedding and extraction pipelines.
+    pub async fn pipeline_counts(&self) -> Result<PipelineCounts, MemcpError> {
+        let row = sqlx::query(
+            "SELECT \
+                COUNT(*) FILTER (WHERE embedding_status = 'pending') AS embedding_pending, \
+                COUNT(*) FILTER (WHERE embedding_status = 'failed') AS embedding_failed, \
+                COUNT(*) FILTER (WHERE extraction_status = 'pending') AS extraction_pending, \
+                COUNT(*) FILTER (WHERE extraction_status = 'failed') AS extraction_failed \
+             FROM memories WHERE deleted_at IS NULL",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        Ok(PipelineCounts {
+            embedding_pending: row.try_get("embedding_pending").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            embedding_failed: row.try_get("embedding_failed").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            extraction_pending: row.try_get("extraction_pending").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            extraction_failed: row.try_get("extraction_failed").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        })
+    }
+
     /// Mark ALL current embeddings as stale (used when switching to a new embedding model).
     ///
     /// Sets is_current = false on all memory_embeddings, and resets embedding_status = 'pending'
@@ -785,11 +2007,228 @@ impl PostgresMemoryStore {
         Ok(count)
     }
 
+    /// Check memory_embeddings against the configured model and its dimension.
+    ///
+    /// Counts current embeddings that belong to another model or have another dimension
+    /// (e.g. after a model switch that crashed midway), embeddings left without a memory
+    /// row, and live memories marked 'complete' that have no current embedding.
+    pub async fn verify_embeddings(
+        &self,
+        model_name: &str,
+        dimension: i32,
+    ) -> Result<EmbeddingConsistency, MemcpError> {
+        let row = sqlx::query(
+            "SELECT                 (SELECT COUNT(*) FROM memory_embeddings                  WHERE is_current = TRUE AND model_name <> ALL($3)                    AND (model_name <> $1 OR dimension <> $2                        OR vector_dims(embedding) <> $2)) AS drifted,                 (SELECT COUNT(*) FROM memory_embeddings me                  WHERE NOT EXISTS (SELECT 1 FROM memories m WHERE m.id = me.memory_id)) AS orphaned,                 (SELECT COUNT(*) FROM memories m                  WHERE m.deleted_at IS NULL AND m.embedding_status = 'complete'                    AND NOT EXISTS (SELECT 1 FROM memory_embeddings me                                    WHERE me.memory_id = m.id AND me.is_current = TRUE)) AS missing",
+        )
+        .bind(model_name)
+        .bind(dimension)
+        .bind(&self.routed_models)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to verify embeddings: {}", e)))?;
+
+        Ok(EmbeddingConsistency {
+            drifted: row.try_get("drifted").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            orphaned: row.try_get("orphaned").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            missing: row.try_get("missing").map_err(|e| MemcpError::Storage(e.to_string()))?,
+        })
+    }
+
+    /// Repair what `verify_embeddings` finds, in one transaction: drifted embeddings are
+    /// marked stale, orphaned embeddings are deleted, and the affected memories (plus those
+    /// missing an embedding) are reset to 'pending' for the next backfill. Returns the
+    /// counts repaired.
+    pub async fn repair_embeddings(
+        &self,
+        model_name: &str,
+        dimension: i32,
+    ) -> Result<EmbeddingConsistency, MemcpError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin embedding repair transaction: {}", e))
+        })?;
+
+        let drifted_ids: Vec<String> = sqlx::query_scalar(
+            "UPDATE memory_embeddings SET is_current = false, updated_at = NOW()              WHERE is_current = TRUE AND model_name <> ALL($3)                AND (model_name <> $1 OR dimension <> $2                    OR vector_dims(embedding) <> $2)              RETURNING memory_id",
+        )
+        .bind(model_name)
+        .bind(dimension)
+        .bind(&self.routed_models)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to mark drifted embeddings stale: {}", e)))?;
+
+        let orphaned = sqlx::query(
+            "DELETE FROM memory_embeddings me              WHERE NOT EXISTS (SELECT 1 FROM memories m WHERE m.id = me.memory_id)",
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to delete orphaned embeddings: {}", e)))?
+        .rows_affected();
+
+        // Runs after the drift update, so drifted memories left without a current
+        // embedding are reset here too; only those are excluded from the missing count
+        let reset_ids: Vec<String> = sqlx::query_scalar(
+            "UPDATE memories m SET embedding_status = 'pending'              WHERE m.deleted_at IS NULL AND m.embedding_status = 'complete'                AND NOT EXISTS (SELECT 1 FROM memory_embeddings me                                WHERE me.memory_id = m.id AND me.is_current = TRUE)              RETURNING m.id",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to reset memory embedding_status: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit embedding repair transaction: {}", e))
+        })?;
+
+        let drifted: std::collections::HashSet<&String> = drifted_ids.iter().collect();
+        Ok(EmbeddingConsistency {
+            drifted: drifted_ids.len() as i64,
+            orphaned: orphaned as i64,
+            missing: reset_ids.iter().filter(|id| !drifted.contains(id)).count() as i64,
+        })
+    }
+
     /// Return the underlying PgPool so embedding pipeline can share the connection pool.
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
 
+    /// Encrypt memory and revision content at rest with `cipher` (see `crate::encryption`).
+    pub fn with_encryption(mut self, cipher: ContentCipher) -> Self {
+        self.cipher = Some(Arc::new(cipher));
+        self
+    }
+
+    /// Refuse to write extraction output, which would sit in plaintext next to encrypted content.
+    fn check_extraction_allowed(&self) -> Result<(), MemcpError> {
+        match self.cipher {
+            Some(_) => Err(MemcpError::Config(
+                "Extracted entities and facts can't be stored with content encryption on — extraction is disabled".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Write `kinds` of pipeline jobs to the outbox with every new memory (see `crate::outbox`).
+    pub fn with_outbox(mut self, kinds: Vec<JobKind>) -> Self {
+        self.outbox = kinds;
+        self
+    }
+
+    /// Treat `models` as routed-only models (see `crate::embedding::router`): searches
+    /// without a model skip their embeddings, and dimension and drift checks ignore them.
+    pub fn with_routed_models(mut self, models: Vec<String>) -> Self {
+        self.routed_models = models;
+        self
+    }
+
+    /// Encrypt content for writing to the memories table (no-op without a key).
+    fn encrypt_content(&self, plaintext: &str) -> Result<String, MemcpError> {
+        match self.cipher {
+            Some(ref cipher) => cipher.encrypt(plaintext),
+            None => Ok(plaintext.to_string()),
+        }
+    }
+
+    /// Decrypt a content value read straight from the memories table (no-op without a key).
+    pub fn decrypt_content(&self, stored: String) -> Result<String, MemcpError> {
+        match self.cipher {
+            Some(ref cipher) => cipher.decrypt(stored),
+            None => Ok(stored),
+        }
+    }
+
+    /// Encrypt content stored before encryption was enabled, `batch_size` rows at a time.
+    ///
+    /// Covers memories and their revisions. Rows that are already encrypted are skipped, so
+    /// it is safe to re-run and to run alongside a server using the same key. Extracted
+    /// entities, facts and fact embeddings are dropped: they quote the content in plaintext.
+    /// Returns the number of (memories, revisions) encrypted.
+    pub async fn encrypt_existing(&self, batch_size: i64) -> Result<(u64, u64), MemcpError> {
+        let cipher = self.cipher.as_ref().ok_or_else(|| {
+            MemcpError::Config("No encryption key configured — set security.encryption_key first".to_string())
+        })?;
+        let encrypted_pattern = format!("{}%", crate::encryption::ENCRYPTED_PREFIX);
+
+        let mut counts = [0u64; 2];
+        for (table, count) in ["memories", "memory_revisions"].into_iter().zip(counts.iter_mut()) {
+            loop {
+                let rows = sqlx::query(&format!(
+                    "SELECT id, content FROM {} WHERE content NOT LIKE $1 LIMIT $2",
+                    table
+                ))
+                .bind(&encrypted_pattern)
+                .bind(batch_size)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to fetch plaintext {}: {}", table, e)))?;
+
+                if rows.is_empty() {
+                    break;
+                }
+
+                let mut ids: Vec<String> = Vec::with_capacity(rows.len());
+                let mut contents: Vec<String> = Vec::with_capacity(rows.len());
+                let mut digests: Vec<Option<String>> = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    ids.push(row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?);
+                    let content: String = row.try_get("content").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                    contents.push(cipher.encrypt(&content)?);
+                    digests.push(content_digest(Some(cipher), &content));
+                }
+
+                // Revisions are never probed for duplicates, so only memories carry a digest
+                let digest_set = if table == "memories" { ", content_digest = u.digest" } else { "" };
+                // The NOT LIKE guard keeps a row rewritten concurrently from being encrypted twice
+                let result = sqlx::query(&format!(
+                    "UPDATE {} t SET content = u.content{} \
+                     FROM UNNEST($1::text[], $2::text[], $4::text[]) AS u(id, content, digest) \
+                     WHERE t.id = u.id AND t.content NOT LIKE $3",
+                    table, digest_set
+                ))
+                .bind(&ids)
+                .bind(&contents)
+                .bind(&encrypted_pattern)
+                .bind(&digests)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to encrypt {}: {}", table, e)))?;
+
+                *count += result.rows_affected();
+                tracing::info!(table, encrypted = *count, "Encrypted existing content");
+            }
+        }
+
+        let cleared = sqlx::query(
+            "UPDATE memories SET extracted_entities = NULL, extracted_facts = NULL \
+             WHERE extracted_entities IS NOT NULL OR extracted_facts IS NOT NULL",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to clear extraction results: {}", e)))?;
+        sqlx::query("DELETE FROM fact_embeddings")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to clear fact embeddings: {}", e)))?;
+        if cleared.rows_affected() > 0 {
+            tracing::info!(memories = cleared.rows_affected(), "Dropped plaintext extraction results");
+        }
+
+        Ok((counts[0], counts[1]))
+    }
+
+    /// row_to_memory() plus content decryption.
+    fn memory_from_row(&self, row: &PgRow) -> Result<Memory, MemcpError> {
+        let mut memory = row_to_memory(row)?;
+        memory.content = self.decrypt_content(memory.content)?;
+        Ok(memory)
+    }
+
+    /// row_to_revision() plus content decryption.
+    fn revision_from_row(&self, row: &PgRow) -> Result<MemoryRevision, MemcpError> {
+        let mut revision = row_to_revision(row)?;
+        revision.content = self.decrypt_content(revision.content)?;
+        Ok(revision)
+    }
+
     /// Returns whether the ParadeDB pg_search extension is available on this PostgreSQL instance.
     /// Detected once at construction time — cached for the lifetime of the store.
     pub fn paradedb_available(&self) -> bool {
@@ -895,7 +2334,7 @@ impl PostgresMemoryStore {
     /// The key spaced repetition property (SRCH-04): faded memories (low retrievability)
     /// receive a larger stability boost than fresh memories (high retrievability).
     /// Formula: new_stability = stability * (1.0 + (1.0 - retrievability) * multiplier)
-    /// where multiplier=1.5 for "good", 2.0 for "easy".
+    /// where multiplier=1.5 for "good", 2.0 for "easy", 0.5 for "light" (mark_used).
     ///
     /// Clamps resulting stability to [0.1, 36500.0] (0.1 days to ~100 years).
     /// Increments reinforcement_count and sets last_reinforced_at = now.
@@ -904,63 +2343,114 @@ impl PostgresMemoryStore {
         memory_id: &str,
         rating: &str,
     ) -> Result<SalienceRow, MemcpError> {
-        // 1. Fetch current salience row (defaults if no row exists)
-        let row_map = self.get_salience_data(&[memory_id.to_string()]).await?;
-        let current = row_map.get(memory_id).cloned().unwrap_or_default();
-
-        // 2. Compute days elapsed since last reinforcement (or 365 if never reinforced)
-        let days_elapsed = current.last_reinforced_at
-            .map(|dt| {
-                let duration = Utc::now().signed_duration_since(dt);
-                (duration.num_seconds() as f64 / 86_400.0).max(0.0)
-            })
-            .unwrap_or(365.0);
+        self.reinforce_salience_batch(&[memory_id.to_string()], rating)
+            .await?
+            .pop()
+            .map(|(_, row)| row)
+            .ok_or_else(|| MemcpError::NotFound { id: memory_id.to_string() })
+    }
 
-        // 3. Compute current retrievability (how fresh is the memory right now?)
-        let retrievability = crate::search::salience::fsrs_retrievability(
-            current.stability,
-            days_elapsed,
-        );
+    /// Reinforce several memories with the same rating in a single transaction.
+    ///
+    /// Existing salience rows are locked while the new stabilities are computed, so
+    /// concurrent reinforcements of the same memory apply one after the other. Duplicate
+    /// IDs are reinforced once. Returns (memory_id, updated row) in first-seen order.
+    pub async fn reinforce_salience_batch(
+        &self,
+        memory_ids: &[String],
+        rating: &str,
+    ) -> Result<Vec<(String, SalienceRow)>, MemcpError> {
+        let mut ids: Vec<String> = Vec::with_capacity(memory_ids.len());
+        for id in memory_ids {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
 
-        // 4. Update stability — faded memories (low retrievability) get bigger boosts
-        //    multiplier: 1.5 for "good", 2.0 for "easy"
-        let multiplier = if rating == "easy" { 2.0_f64 } else { 1.5_f64 };
-        let new_stability = current.stability * (1.0 + (1.0 - retrievability) * multiplier);
+        let mut tx = self.pool.begin().await
+            .map_err(|e| MemcpError::Storage(format!("Failed to begin transaction: {}", e)))?;
+
+        // 1. Fetch and lock current salience rows (defaults if no row exists)
+        let rows = sqlx::query(
+            "SELECT memory_id, stability, difficulty, reinforcement_count, last_reinforced_at \
+             FROM memory_salience \
+             WHERE memory_id = ANY($1) \
+             FOR UPDATE",
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to fetch salience data: {}", e)))?;
 
-        // 5. Clamp to [0.1, 36500.0]
-        let new_stability = new_stability.clamp(0.1, 36_500.0);
+        let mut current: HashMap<String, SalienceRow> = HashMap::with_capacity(rows.len());
+        for row in &rows {
+            let memory_id: String = row.try_get("memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            current.insert(memory_id, SalienceRow {
+                stability: row.try_get("stability").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                difficulty: row.try_get("difficulty").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                reinforcement_count: row.try_get("reinforcement_count").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                last_reinforced_at: row.try_get("last_reinforced_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            });
+        }
 
-        let new_count = current.reinforcement_count + 1;
         let now = Utc::now();
+        let multiplier = crate::search::salience::reinforcement_multiplier(rating);
+        let mut updated = Vec::with_capacity(ids.len());
+        for id in ids {
+            let current = current.remove(&id).unwrap_or_default();
+
+            // 2. Compute days elapsed since last reinforcement (or 365 if never reinforced)
+            let days_elapsed = current.last_reinforced_at
+                .map(|dt| {
+                    let duration = now.signed_duration_since(dt);
+                    (duration.num_seconds() as f64 / 86_400.0).max(0.0)
+                })
+                .unwrap_or(365.0);
+
+            // 3. Compute current retrievability (how fresh is the memory right now?)
+            let retrievability = crate::search::salience::fsrs_retrievability(
+                current.stability,
+                days_elapsed,
+            );
 
-        // 6. Upsert to memory_salience
-        sqlx::query(
-            "INSERT INTO memory_salience \
-             (memory_id, stability, difficulty, reinforcement_count, last_reinforced_at, created_at, updated_at) \
-             VALUES ($1, $2, $3, $4, $5, $6, $6) \
-             ON CONFLICT (memory_id) DO UPDATE SET \
-               stability = EXCLUDED.stability, \
-               reinforcement_count = EXCLUDED.reinforcement_count, \
-               last_reinforced_at = EXCLUDED.last_reinforced_at, \
-               updated_at = EXCLUDED.updated_at",
-        )
-        .bind(memory_id)
-        .bind(new_stability)
-        .bind(current.difficulty)
-        .bind(new_count)
-        .bind(&now)
-        .bind(&now)
-        .execute(&self.pool)
-        .await
-        .map_err(|e| MemcpError::Storage(format!("Failed to reinforce salience: {}", e)))?;
+            // 4. Update stability — faded memories (low retrievability) get bigger boosts,
+            //    then clamp to [0.1, 36500.0]
+            let new_stability = current.stability * (1.0 + (1.0 - retrievability) * multiplier);
+            let new_stability = new_stability.clamp(0.1, 36_500.0);
+            let new_count = current.reinforcement_count + 1;
 
-        // 7. Return updated SalienceRow
-        Ok(SalienceRow {
-            stability: new_stability,
-            difficulty: current.difficulty,
-            reinforcement_count: new_count,
-            last_reinforced_at: Some(now),
-        })
+            // 5. Upsert to memory_salience
+            sqlx::query(
+                "INSERT INTO memory_salience \
+                 (memory_id, stability, difficulty, reinforcement_count, last_reinforced_at, created_at, updated_at) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $6) \
+                 ON CONFLICT (memory_id) DO UPDATE SET \
+                   stability = EXCLUDED.stability, \
+                   reinforcement_count = EXCLUDED.reinforcement_count, \
+                   last_reinforced_at = EXCLUDED.last_reinforced_at, \
+                   updated_at = EXCLUDED.updated_at",
+            )
+            .bind(&id)
+            .bind(new_stability)
+            .bind(current.difficulty)
+            .bind(new_count)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to reinforce salience: {}", e)))?;
+
+            updated.push((id, SalienceRow {
+                stability: new_stability,
+                difficulty: current.difficulty,
+                reinforcement_count: new_count,
+                last_reinforced_at: Some(now),
+            }));
+        }
+
+        tx.commit().await
+            .map_err(|e| MemcpError::Storage(format!("Failed to commit reinforcement: {}", e)))?;
+        Ok(updated)
     }
 
     /// Apply a small implicit salience bump from direct memory retrieval.
@@ -985,31 +2475,123 @@ impl PostgresMemoryStore {
         Ok(())
     }
 
-    /// Search for memories semantically similar to the query embedding.
-    ///
-    /// Uses HNSW approximate nearest neighbor search ordered by cosine distance ascending.
-    /// When filters are present, enables hnsw.iterative_scan to prevent over-filtering.
-    /// Returns results with similarity scores, total match count, and OFFSET-based pagination.
-    pub async fn search_similar(
-        &self,
-        filter: &SearchFilter,
-    ) -> Result<SearchResult, MemcpError> {
-        // Acquire an explicit connection — SET hnsw.iterative_scan is session-scoped
-        // and must run on the same connection as the search query.
-        let mut conn = self.pool.acquire().await.map_err(|e| {
-            MemcpError::Storage(format!("Failed to acquire connection: {}", e))
-        })?;
+    /// touch_salience() for several memories in one statement.
+    pub async fn touch_salience_batch(&self, memory_ids: &[String]) -> Result<(), MemcpError> {
+        if memory_ids.is_empty() {
+            return Ok(());
+        }
+        let sql = "INSERT INTO memory_salience (memory_id, stability, updated_at) \
+            SELECT id, 1.1, NOW() FROM UNNEST($1::text[]) AS t(id) \
+            ON CONFLICT (memory_id) \
+            DO UPDATE SET \
+                stability = memory_salience.stability * 1.1, \
+                updated_at = NOW()";
 
-        // Determine if any optional filters are present
-        let has_filters = filter.created_after.is_some()
-            || filter.created_before.is_some()
-            || filter.tags.is_some();
+        sqlx::query(sql)
+            .bind(memory_ids)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
 
-        // Enable iterative scan when filters are present to prevent over-filtering.
-        // Iterative scan requires pgvector 0.8.0+ — gracefully skip if SET fails.
-        if has_filters {
-            if let Err(e) = sqlx::query("SET hnsw.iterative_scan = 'relaxed_order'")
-                .execute(&mut *conn)
+        Ok(())
+    }
+
+    /// Bind search_similar's parameters in placeholder order: $1=query_embedding, model?,
+    /// routed models?, created_after?, created_before?, tags?, namespace?, type_hint?,
+    /// source?, payload_path?, origin_ref?, min_access_count?, accessed_after?, exclusions.
+    fn bind_search_filter<'q>(&'q self, mut q: PgQuery<'q>, filter: &'q SearchFilter, exclude_routed: bool) -> PgQuery<'q> {
+        q = q.bind(&filter.query_embedding);
+        if let Some(ref model) = filter.model {
+            q = q.bind(model);
+        }
+        if exclude_routed {
+            q = q.bind(&self.routed_models);
+        }
+        if let Some(ref ca) = filter.created_after {
+            q = q.bind(ca);
+        }
+        if let Some(ref cb) = filter.created_before {
+            q = q.bind(cb);
+        }
+        if let Some(ref tags) = filter.tags {
+            q = q.bind(serde_json::json!(tags));
+        }
+        if let Some(ref ns) = filter.namespace {
+            q = q.bind(ns);
+        }
+        if let Some(ref type_hint) = filter.type_hint {
+            q = q.bind(type_hint);
+        }
+        if let Some(ref source) = filter.source {
+            q = q.bind(source);
+        }
+        if let Some(ref path) = filter.payload_path {
+            q = q.bind(path);
+        }
+        if let Some(ref origin_ref) = filter.origin_ref {
+            q = q.bind(origin_ref_array(origin_ref));
+        }
+        if let Some(count) = filter.min_access_count {
+            q = q.bind(count);
+        }
+        if let Some(ref aa) = filter.accessed_after {
+            q = q.bind(aa);
+        }
+        bind_exclusions(q, [filter.exclude_tags.as_slice(), &filter.exclude_sources, &filter.exclude_type_hints])
+    }
+
+    /// Search for memories semantically similar to the query embedding.
+    ///
+    /// Uses HNSW approximate nearest neighbor search ordered by cosine distance ascending.
+    /// When filters are present, enables hnsw.iterative_scan to prevent over-filtering.
+    /// Returns results with similarity scores, OFFSET-based pagination, and a match count that
+    /// is only exact past the last page when search.count_matches asks for counting.
+    pub async fn search_similar(
+        &self,
+        filter: &SearchFilter,
+    ) -> Result<SearchResult, MemcpError> {
+        // Acquire an explicit connection — SET hnsw.iterative_scan is session-scoped
+        // and must run on the same connection as the search query.
+        let mut conn = self.pool.acquire().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to acquire connection: {}", e))
+        })?;
+        self.search_similar_on(&mut conn, filter).await
+    }
+
+    /// search_similar on a connection the caller already holds (a hybrid_search leg's).
+    async fn search_similar_on(
+        &self,
+        conn: &mut PgConnection,
+        filter: &SearchFilter,
+    ) -> Result<SearchResult, MemcpError> {
+        // Only vectors of the query's dimension are comparable (and covered by its index)
+        let dimension = filter.query_embedding.as_slice().len() as i32;
+        if filter.model.is_none() {
+            self.check_dimension_with(&mut *conn, dimension).await?;
+        }
+
+        // Determine if any optional filters are present
+        let has_filters = filter.created_after.is_some()
+            || filter.created_before.is_some()
+            || filter.tags.is_some()
+            || filter.namespace.is_some()
+            || filter.type_hint.is_some()
+            || filter.source.is_some()
+            || filter.model.is_some()
+            || filter.payload_path.is_some()
+            || filter.origin_ref.is_some()
+            || filter.min_access_count.is_some()
+            || filter.accessed_after.is_some()
+            || filter.never_accessed
+            || !filter.exclude_tags.is_empty()
+            || !filter.exclude_sources.is_empty()
+            || !filter.exclude_type_hints.is_empty();
+
+        // Enable iterative scan when filters are present to prevent over-filtering.
+        // Iterative scan requires pgvector 0.8.0+ — gracefully skip if SET fails.
+        if has_filters {
+            if let Err(e) = sqlx::query("SET hnsw.iterative_scan = 'relaxed_order'")
+                .execute(&mut *conn)
                 .await
             {
                 tracing::warn!(
@@ -1021,13 +2603,33 @@ impl PostgresMemoryStore {
 
         // Build WHERE conditions with numbered PostgreSQL parameters.
         // $1 is always the query embedding — build filter params starting at $2.
-        let mut conditions: Vec<String> = Vec::new();
-        // Always filter for current embeddings on complete memories
-        conditions.push("me.is_current = true".to_string());
-        conditions.push("m.embedding_status = 'complete'".to_string());
-
+        // Always filter for live, unexpired, unarchived memories. Without a model, search the
+        // current embeddings of completely embedded memories; with one, embedding_status
+        // (which tracks the current model) doesn't apply — any stored vector of that model counts.
         let mut param_idx: u32 = 2; // $1 is reserved for query_embedding
 
+        let mut conditions: Vec<String> = if filter.model.is_some() {
+            param_idx += 1;
+            vec!["me.model_name = $2".to_string()]
+        } else {
+            vec![
+                "me.is_current = true".to_string(),
+                "m.embedding_status = 'complete'".to_string(),
+            ]
+        };
+        // Routed models' vectors aren't comparable with the default model's query vector
+        let exclude_routed = filter.model.is_none() && !self.routed_models.is_empty();
+        if exclude_routed {
+            conditions.push(format!("me.model_name <> ALL(${})", param_idx));
+            param_idx += 1;
+        }
+        conditions.extend([
+            format!("me.dimension = {}", dimension),
+            "m.deleted_at IS NULL".to_string(),
+            "m.archived_at IS NULL".to_string(),
+            "(m.expires_at IS NULL OR m.expires_at > NOW())".to_string(),
+        ]);
+
         if filter.created_after.is_some() {
             conditions.push(format!("m.created_at > ${}", param_idx));
             param_idx += 1;
@@ -1041,84 +2643,108 @@ impl PostgresMemoryStore {
             conditions.push(format!("m.tags @> ${}::jsonb", param_idx));
             param_idx += 1;
         }
+        if filter.namespace.is_some() {
+            conditions.push(format!("m.namespace = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.type_hint.is_some() {
+            conditions.push(format!("m.type_hint = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.source.is_some() {
+            conditions.push(format!("m.source = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.payload_path.is_some() {
+            conditions.push(format!("m.payload @@ ${}::text::jsonpath", param_idx));
+            param_idx += 1;
+        }
+        if filter.origin_ref.is_some() {
+            conditions.push(format!("m.origin_refs @> ${}::jsonb", param_idx));
+            param_idx += 1;
+        }
+        if filter.min_access_count.is_some() {
+            conditions.push(format!("m.access_count >= ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.accessed_after.is_some() {
+            conditions.push(format!("m.last_accessed_at > ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.never_accessed {
+            conditions.push("m.access_count = 0".to_string());
+        }
+        let exclusions = [filter.exclude_tags.as_slice(), &filter.exclude_sources, &filter.exclude_type_hints];
+        push_exclusion_conditions("m.", exclusions, &mut conditions, &mut param_idx);
 
         let where_clause = format!("WHERE {}", conditions.join(" AND "));
 
         // Main search query: JOIN memories with embeddings, compute cosine similarity,
         // ORDER BY distance ASC (NOT alias) so HNSW index is used.
-        // Suppress consolidated originals from search results.
+        // Suppress consolidated originals from search results. One row past the page is
+        // fetched to learn has_more without counting.
         let sql = format!(
-            "SELECT m.id, m.content, m.type_hint, m.source, m.tags, \
-                    m.created_at, m.updated_at, m.last_accessed_at, \
-                    m.access_count, m.embedding_status, \
-                    m.extracted_entities, m.extracted_facts, m.extraction_status, \
-                    m.is_consolidated_original, m.consolidated_into, \
-                    (1 - (me.embedding <=> $1)) AS similarity \
-             FROM memories m \
-             JOIN memory_embeddings me ON me.memory_id = m.id \
-             {} AND m.is_consolidated_original = FALSE \
-             ORDER BY me.embedding <=> $1 ASC \
-             LIMIT ${} OFFSET ${}",
-            where_clause, param_idx, param_idx + 1
-        );
-
-        // Count query: same JOIN and WHERE but no ORDER BY / LIMIT / OFFSET
-        let count_sql = format!(
-            "SELECT COUNT(*) as total \
+            "SELECT {columns}, \
+                    (1 - (me.embedding::vector({dim}) <=> $1)) AS similarity \
              FROM memories m \
              JOIN memory_embeddings me ON me.memory_id = m.id \
-             {} AND m.is_consolidated_original = FALSE",
-            where_clause
+             {where_clause} AND m.is_consolidated_original = FALSE \
+             ORDER BY me.embedding::vector({dim}) <=> $1 ASC \
+             LIMIT ${limit} OFFSET ${offset}",
+            columns = memory_columns_with_alias("m"),
+            dim = dimension,
+            where_clause = where_clause,
+            limit = param_idx,
+            offset = param_idx + 1
         );
 
-        // Helper: bind all optional filter params (same order for both queries)
-        // We build the binding in a macro-like closure to avoid code duplication.
-        // Binding order: $1=query_embedding, $2=created_after?, $3=created_before?, $4=tags?
-
-        // Execute main search query
-        let mut q = sqlx::query(&sql).bind(&filter.query_embedding);
-        if let Some(ref ca) = filter.created_after {
-            q = q.bind(ca);
-        }
-        if let Some(ref cb) = filter.created_before {
-            q = q.bind(cb);
-        }
-        if let Some(ref tags) = filter.tags {
-            q = q.bind(serde_json::json!(tags));
-        }
-        q = q.bind(filter.limit).bind(filter.offset);
-
-        let rows = q
+        let mut rows = self
+            .bind_search_filter(sqlx::query(&sql), filter, exclude_routed)
+            .bind(filter.limit + 1)
+            .bind(filter.offset)
             .fetch_all(&mut *conn)
             .await
             .map_err(|e| MemcpError::Storage(format!("Search query failed: {}", e)))?;
-
-        // Execute count query on same connection
-        let mut count_q = sqlx::query(&count_sql).bind(&filter.query_embedding);
-        if let Some(ref ca) = filter.created_after {
-            count_q = count_q.bind(ca);
-        }
-        if let Some(ref cb) = filter.created_before {
-            count_q = count_q.bind(cb);
-        }
-        if let Some(ref tags) = filter.tags {
-            count_q = count_q.bind(serde_json::json!(tags));
-        }
-
-        let count_row = count_q
-            .fetch_one(&mut *conn)
-            .await
-            .map_err(|e| MemcpError::Storage(format!("Search count query failed: {}", e)))?;
-
-        let total_matches: i64 = count_row
-            .try_get("total")
-            .map_err(|e| MemcpError::Storage(e.to_string()))?;
-        let total_matches = total_matches as u64;
+        let has_more = rows.len() as i64 > filter.limit;
+        rows.truncate(filter.limit.max(0) as usize);
+
+        // Without a next page the matches end on this one, so there is nothing to count
+        // (unless the offset ran past them)
+        let ends_here = !has_more && (filter.offset == 0 || !rows.is_empty());
+        let seen = filter.offset.max(0) as u64 + rows.len() as u64;
+        let (total_matches, total_matches_exact) = match self.match_count {
+            _ if ends_here => (seen, true),
+            MatchCount::None if rows.is_empty() => (0, false),
+            MatchCount::None => (seen + has_more as u64, false),
+            count => {
+                let cap = match count {
+                    MatchCount::Capped(cap) => Some(cap),
+                    _ => None,
+                };
+                let count_sql = format!(
+                    "SELECT COUNT(*) AS total FROM ( \
+                         SELECT 1 FROM memories m \
+                         JOIN memory_embeddings me ON me.memory_id = m.id \
+                         {} AND m.is_consolidated_original = FALSE {} \
+                     ) matches",
+                    where_clause,
+                    cap.map(|cap| format!("LIMIT {}", cap)).unwrap_or_default()
+                );
+                let total: i64 = self
+                    .bind_search_filter(sqlx::query(&count_sql), filter, exclude_routed)
+                    .fetch_one(&mut *conn)
+                    .await
+                    .and_then(|row| row.try_get("total"))
+                    .map_err(|e| MemcpError::Storage(format!("Search count query failed: {}", e)))?;
+                let total = total as u64;
+                (total, cap.is_none_or(|cap| total < cap))
+            }
+        };
 
         // Parse result rows into SearchHit records
         let mut hits = Vec::with_capacity(rows.len());
         for row in &rows {
-            let memory = row_to_memory(row)?;
+            let memory = self.memory_from_row(row)?;
             let raw_similarity: f64 = row
                 .try_get("similarity")
                 .map_err(|e| MemcpError::Storage(e.to_string()))?;
@@ -1129,7 +2755,6 @@ impl PostgresMemoryStore {
 
         // Compute OFFSET-based pagination
         let next_offset = filter.offset + filter.limit;
-        let has_more = next_offset < total_matches as i64;
         let next_cursor = if has_more {
             Some(encode_search_cursor(next_offset))
         } else {
@@ -1139,11 +2764,107 @@ impl PostgresMemoryStore {
         Ok(SearchResult {
             hits,
             total_matches,
+            total_matches_exact,
             next_cursor,
             has_more,
         })
     }
 
+    /// Find the memories nearest to an existing memory's stored embedding.
+    ///
+    /// Searches within the source memory's namespace and excludes the source itself.
+    /// Consolidated originals and trashed memories are excluded (same rules as search_similar).
+    /// Returns NotFound if the memory doesn't exist (or is trashed), and a Validation error
+    /// if it has no current embedding yet.
+    pub async fn get_related_memories(
+        &self,
+        memory_id: &str,
+        limit: i64,
+    ) -> Result<Vec<SearchHit>, MemcpError> {
+        let source = self
+            .get_memories_by_ids(&[memory_id.to_string()])
+            .await?
+            .remove(memory_id)
+            .filter(|m| m.deleted_at.is_none())
+            .ok_or_else(|| MemcpError::NotFound { id: memory_id.to_string() })?;
+
+        let embedding = self.get_memory_embedding(memory_id).await?.ok_or_else(|| {
+            MemcpError::Validation {
+                message: format!(
+                    "Memory {} has no embedding yet (embedding_status: {})",
+                    memory_id, source.embedding_status
+                ),
+                field: Some("id".to_string()),
+            }
+        })?;
+
+        // Fetch one extra so the source memory can be dropped without shrinking the result
+        let filter = SearchFilter {
+            query_embedding: embedding,
+            limit: limit + 1,
+            namespace: Some(source.namespace),
+            ..SearchFilter::default()
+        };
+        let result = self.search_similar(&filter).await?;
+
+        Ok(result
+            .hits
+            .into_iter()
+            .filter(|hit| hit.memory.id != memory_id)
+            .take(limit as usize)
+            .collect())
+    }
+
+    /// Find a live memory in `namespace` whose normalized content matches `content` exactly.
+    ///
+    /// Normalization (lowercase, collapsed whitespace) happens in SQL so it matches the
+    /// generated content_hash column byte for byte. With encryption on, content_hash covers
+    /// ciphertext, so the keyed content_digest of the plaintext is compared instead.
+    pub async fn find_exact_duplicate(
+        &self,
+        content: &str,
+        namespace: &str,
+    ) -> Result<Option<Memory>, MemcpError> {
+        let (predicate, key) = match content_digest(self.cipher.as_deref(), content) {
+            Some(digest) => ("content_digest = $1", digest),
+            None => ("content_hash = md5(lower(btrim(regexp_replace($1, '\\s+', ' ', 'g'))))", content.to_string()),
+        };
+        let sql = format!(
+            "SELECT {} FROM memories \
+             WHERE namespace = $2 AND deleted_at IS NULL \
+               AND (expires_at IS NULL OR expires_at > NOW()) \
+               AND {} \
+             ORDER BY created_at ASC LIMIT 1",
+            MEMORY_COLUMNS, predicate
+        );
+        let row = sqlx::query(&sql)
+            .bind(key)
+            .bind(namespace)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to check for duplicate content: {}", e)))?;
+
+        row.as_ref().map(|row| self.memory_from_row(row)).transpose()
+    }
+
+    /// Find the most similar live memory in `namespace` if its cosine similarity is at
+    /// least `threshold`. Only memories with a stored embedding can match.
+    pub async fn find_near_duplicate(
+        &self,
+        embedding: pgvector::Vector,
+        namespace: &str,
+        threshold: f64,
+    ) -> Result<Option<SearchHit>, MemcpError> {
+        let filter = SearchFilter {
+            query_embedding: embedding,
+            limit: 1,
+            namespace: Some(namespace.to_string()),
+            ..SearchFilter::default()
+        };
+        let result = self.search_similar(&filter).await?;
+        Ok(result.hits.into_iter().next().filter(|hit| hit.similarity >= threshold))
+    }
+
     /// Fetch full Memory objects for a list of IDs.
     ///
     /// Returns a HashMap<id, Memory> for efficient lookup by ID.
@@ -1156,260 +2877,539 @@ impl PostgresMemoryStore {
             return Ok(HashMap::new());
         }
 
-        let rows = sqlx::query(
-            "SELECT id, content, type_hint, source, tags, created_at, updated_at, \
-             last_accessed_at, access_count, embedding_status, \
-             extracted_entities, extracted_facts, extraction_status, is_consolidated_original, consolidated_into \
-             FROM memories WHERE id = ANY($1)",
-        )
-        .bind(ids)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| MemcpError::Storage(format!("Failed to fetch memories by ids: {}", e)))?;
+        let sql = format!("SELECT {} FROM memories WHERE id = ANY($1)", MEMORY_COLUMNS);
+        let rows = sqlx::query(&sql)
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to fetch memories by ids: {}", e)))?;
 
         let mut map = HashMap::with_capacity(rows.len());
         for row in &rows {
-            let memory = row_to_memory(row)?;
+            let memory = self.memory_from_row(row)?;
             map.insert(memory.id.clone(), memory);
         }
         Ok(map)
     }
 
-    /// Orchestrate hybrid BM25 + vector + symbolic search with three-way RRF fusion.
+    /// Orchestrate hybrid BM25 + vector + symbolic search with three-way fusion.
+    ///
+    /// `fusion` selects how the legs combine: `Fusion::Rrf` (rank-based, the default) or
+    /// `Fusion::Weighted` (normalized leg scores times the given weights).
     ///
-    /// All three legs run independently with a candidate pool of 40 results each.
+    /// All three legs run concurrently, each on its own pool connection, with a candidate pool
+    /// of `candidate_pool` results each (None = the store's configured `candidate_pool_per_leg`,
+    /// default 40). Legs across all concurrent searches hold at most half the pool; a leg
+    /// whose queries take longer than search.leg_timeout_ms is dropped from fusion.
     /// When query_embedding is None (embedding provider unavailable), gracefully
     /// falls back to BM25 + symbolic search only.
     ///
     /// Per-leg k overrides control RRF smoothing (lower k = more top-result influence):
-    /// - None means "skip this leg entirely"
+    /// - None means "skip this leg entirely" (under either fusion strategy)
     /// - Some(k) means "run with this k value" (default: bm25=60.0, vector=60.0, symbolic=40.0)
     ///
     /// Salience re-ranking is NOT performed here — the server layer applies it
     /// after fetching salience data from the database.
+    ///
+    /// `symbolic_terms` are extra terms (e.g. entities extracted from the query) the symbolic
+    /// leg matches alongside `query_text`; BM25 and vector legs only see `query_text`.
+    ///
+    /// `filter` narrows every leg before fusion: namespace, created dates, tags, type_hint and
+    /// source. `filter.limit` is the number of fused hits to return; its query_embedding and
+    /// offset are ignored (the vector leg embeds with `query_embedding`), and `filter.model`
+    /// restricts the vector leg to that model's embeddings (for routed queries).
+    /// Hits on chunks of an oversized memory are reported on the parent, with the matching
+    /// chunks in `matched_chunks`.
     pub async fn hybrid_search(
         &self,
         query_text: &str,
         query_embedding: Option<&pgvector::Vector>,
-        limit: i64,
-        created_after: Option<chrono::DateTime<Utc>>,
-        created_before: Option<chrono::DateTime<Utc>>,
-        tags: Option<&[String]>,
+        symbolic_terms: &[String],
+        filter: &SearchFilter,
         bm25_k: Option<f64>,
         vector_k: Option<f64>,
         symbolic_k: Option<f64>,
+        candidate_pool: Option<i64>,
+        fusion: crate::search::Fusion,
     ) -> Result<Vec<crate::search::HybridRawHit>, MemcpError> {
-        // 40 candidates per leg — research recommendation balancing recall vs cost
-        let candidate_limit = 40i64;
+        let candidate_limit = candidate_pool.unwrap_or(self.candidate_pool_per_leg).max(1);
 
+        // Each leg gets the connections it needs (none when it is skipped) from bounded_leg
         // BM25 leg — skip when bm25_k is None (weight=0.0 = disabled)
-        let bm25_results: Vec<(String, i64)> = if bm25_k.is_some() {
-            self.search_bm25(query_text, candidate_limit).await?
-        } else {
-            tracing::info!("BM25 search leg disabled (bm25_weight=0.0)");
-            vec![]
+        let bm25_leg = |mut conns: Vec<PoolConnection<Postgres>>| async move {
+            let Some(conn) = conns.first_mut() else {
+                tracing::info!("BM25 search leg disabled (bm25_weight=0.0)");
+                return Ok(vec![]);
+            };
+            self.search_bm25(conn, query_text, candidate_limit, filter).await
         };
 
-        // Vector leg — only runs when query embedding is available AND vector_k is Some
-        let vector_results: Vec<(String, i64)> = if vector_k.is_some() {
-            if let Some(embedding) = query_embedding {
-                let filter = SearchFilter {
-                    query_embedding: embedding.clone(),
-                    limit: candidate_limit,
-                    offset: 0,
-                    created_after,
-                    created_before,
-                    tags: tags.map(|t| t.to_vec()),
-                };
-                let result = self.search_similar(&filter).await?;
-                result
-                    .hits
-                    .iter()
-                    .enumerate()
-                    .map(|(i, hit)| (hit.memory.id.clone(), (i + 1) as i64))
-                    .collect()
-            } else {
-                tracing::info!("No query embedding available — skipping vector search leg");
-                vec![]
+        // Vector leg — only runs when query embedding is available AND vector_k is Some.
+        // Fact embeddings are searched on a second connection when the budget allows one.
+        let vector_connections = match (vector_k, query_embedding) {
+            (Some(_), Some(_)) if self.fact_embeddings && self.leg_connection_budget >= 2 => 2,
+            (Some(_), Some(_)) => 1,
+            _ => 0,
+        };
+        let vector_leg = |conns: Vec<PoolConnection<Postgres>>| async move {
+            if vector_k.is_none() {
+                tracing::info!("Vector search leg disabled (vector_weight=0.0)");
+                return Ok(vec![]);
             }
-        } else {
-            tracing::info!("Vector search leg disabled (vector_weight=0.0)");
-            vec![]
+            let Some(embedding) = query_embedding else {
+                tracing::info!("No query embedding available — skipping vector search leg");
+                return Ok(vec![]);
+            };
+            let mut conns = conns.into_iter();
+            let Some(mut conn) = conns.next() else { return Ok(vec![]) };
+            let filter = SearchFilter {
+                query_embedding: embedding.clone(),
+                limit: candidate_limit,
+                offset: 0,
+                ..filter.clone()
+            };
+            // Fact rows map back to their parent memory; a memory ranks by its best match
+            let (result, fact_hits) = match conns.next() {
+                Some(mut fact_conn) => tokio::try_join!(
+                    self.search_similar_on(&mut conn, &filter),
+                    self.search_fact_embeddings(&mut fact_conn, &filter),
+                )?,
+                None => {
+                    let result = self.search_similar_on(&mut conn, &filter).await?;
+                    let fact_hits = if self.fact_embeddings {
+                        self.search_fact_embeddings(&mut conn, &filter).await?
+                    } else {
+                        Vec::new()
+                    };
+                    (result, fact_hits)
+                }
+            };
+            let memory_hits: Vec<(String, f64)> = result
+                .hits
+                .iter()
+                .map(|hit| (hit.memory.id.clone(), hit.similarity))
+                .collect();
+            Ok(crate::search::merge_fact_hits(memory_hits, fact_hits, candidate_limit as usize))
         };
 
         // Symbolic leg — skip when symbolic_k is None (weight=0.0 = disabled)
-        let symbolic_results: Vec<(String, i64)> = if symbolic_k.is_some() {
-            self.search_symbolic(query_text, candidate_limit).await?
-        } else {
-            tracing::info!("Symbolic search leg disabled (symbolic_weight=0.0)");
-            vec![]
+        let symbolic_leg = |mut conns: Vec<PoolConnection<Postgres>>| async move {
+            let Some(conn) = conns.first_mut() else {
+                tracing::info!("Symbolic search leg disabled (symbolic_weight=0.0)");
+                return Ok(vec![]);
+            };
+            let mut terms = vec![query_text.to_string()];
+            terms.extend(symbolic_terms.iter().filter(|t| !t.eq_ignore_ascii_case(query_text)).cloned());
+            self.search_symbolic(conn, &terms, candidate_limit, filter).await
         };
 
-        // Three-way RRF fusion with per-leg k parameters
-        let fused = crate::search::rrf_fuse(
-            &bm25_results,
-            &vector_results,
-            &symbolic_results,
-            bm25_k.unwrap_or(60.0),
-            vector_k.unwrap_or(60.0),
-            symbolic_k.unwrap_or(40.0),
+        let (bm25_results, vector_results, symbolic_results) = tokio::join!(
+            self.bounded_leg("bm25", bm25_k.is_some() as u32, bm25_leg),
+            self.bounded_leg("vector", vector_connections, vector_leg),
+            self.bounded_leg("symbolic", symbolic_k.is_some() as u32, symbolic_leg),
         );
+        let (bm25_results, vector_results, symbolic_results) = (bm25_results?, vector_results?, symbolic_results?);
 
-        // Fetch full Memory objects for the top fused IDs
-        let top_ids: Vec<String> = fused
-            .iter()
-            .take(limit as usize)
-            .map(|(id, _, _)| id.clone())
-            .collect();
-        let memories = self.get_memories_by_ids(&top_ids).await?;
+        let fused = match fusion {
+            // Three-way RRF fusion with per-leg k parameters
+            crate::search::Fusion::Rrf => {
+                let ranks = |leg: &[(String, i64, f64)]| -> Vec<(String, i64)> {
+                    leg.iter().map(|(id, rank, _)| (id.clone(), *rank)).collect()
+                };
+                crate::search::rrf_fuse(
+                    &ranks(&bm25_results),
+                    &ranks(&vector_results),
+                    &ranks(&symbolic_results),
+                    bm25_k.unwrap_or(60.0),
+                    vector_k.unwrap_or(60.0),
+                    symbolic_k.unwrap_or(40.0),
+                )
+            }
+            // Weighted sum of normalized leg scores
+            crate::search::Fusion::Weighted { bm25, vector, symbolic } => {
+                let scores = |leg: &[(String, i64, f64)]| -> Vec<(String, f64)> {
+                    leg.iter().map(|(id, _, score)| (id.clone(), *score)).collect()
+                };
+                crate::search::weighted_fuse(
+                    &scores(&bm25_results),
+                    &scores(&vector_results),
+                    &scores(&symbolic_results),
+                    bm25,
+                    vector,
+                    symbolic,
+                )
+            }
+        };
 
-        // Build HybridRawHit results, preserving RRF rank order
-        let mut hits = Vec::new();
-        for (id, rrf_score, match_source) in fused.iter().take(limit as usize) {
-            if let Some(memory) = memories.get(id) {
+        // Build HybridRawHit results, preserving fused rank order. Chunk hits are reported on
+        // their parent, so a window of fused IDs can yield fewer hits — keep reading windows
+        // until `limit` distinct memories are found.
+        let ks = (bm25_k.unwrap_or(60.0), vector_k.unwrap_or(60.0), symbolic_k.unwrap_or(40.0));
+        let limit = filter.limit.max(1) as usize;
+        let mut hits: Vec<crate::search::HybridRawHit> = Vec::new();
+        let mut hit_index: HashMap<String, usize> = HashMap::new();
+        for window in fused.chunks(limit) {
+            let ids: Vec<String> = window.iter().map(|(id, _, _)| id.clone()).collect();
+            let memories = self.get_memories_by_ids(&ids).await?;
+            let parent_ids: Vec<String> = memories
+                .values()
+                .filter_map(|m| m.parent_id.clone())
+                .filter(|pid| !memories.contains_key(pid) && !hit_index.contains_key(pid))
+                .collect();
+            let parents = self.get_memories_by_ids(&parent_ids).await?;
+
+            for (id, rrf_score, match_source) in window {
+                let Some(memory) = memories.get(id) else { continue };
+                let (target, chunk) = match memory.parent_id {
+                    Some(ref pid) => {
+                        let chunk = crate::search::ChunkMatch {
+                            memory_id: memory.id.clone(),
+                            chunk_index: memory.chunk_index,
+                            content: memory.content.clone(),
+                        };
+                        (pid.clone(), Some(chunk))
+                    }
+                    None => (id.clone(), None),
+                };
+                if let Some(&index) = hit_index.get(&target) {
+                    hits[index].matched_chunks.extend(chunk);
+                    continue;
+                }
+                let Some(target_memory) = memories.get(&target).or_else(|| parents.get(&target)) else { continue };
+                // A chunk can outlive a trashed or archived parent — drop it with the parent
+                if target_memory.deleted_at.is_some() || target_memory.archived_at.is_some() {
+                    continue;
+                }
+                hit_index.insert(target.clone(), hits.len());
                 hits.push(crate::search::HybridRawHit {
-                    memory: memory.clone(),
+                    memory: target_memory.clone(),
                     rrf_score: *rrf_score,
                     match_source: match_source.clone(),
+                    legs: crate::search::leg_details(
+                        id,
+                        &bm25_results,
+                        &vector_results,
+                        &symbolic_results,
+                        fusion,
+                        ks,
+                    ),
+                    matched_chunks: chunk.into_iter().collect(),
                 });
+                if hits.len() >= limit {
+                    break;
+                }
+            }
+            if hits.len() >= limit {
+                break;
             }
         }
 
         Ok(hits)
     }
 
+    /// Run one hybrid search leg on `connections` pool connections of its own, under the
+    /// leg timeout. The connections are taken (waiting for leg_connections permits first)
+    /// before the clock starts, so only the leg's queries are timed; a busy pool delays a
+    /// leg but never drops it.
+    async fn bounded_leg<F, Fut>(
+        &self,
+        leg: &'static str,
+        connections: u32,
+        search: F,
+    ) -> Result<Vec<(String, i64, f64)>, MemcpError>
+    where
+        F: FnOnce(Vec<PoolConnection<Postgres>>) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<(String, i64, f64)>, MemcpError>>,
+    {
+        if connections == 0 {
+            return search(Vec::new()).await;
+        }
+        let _permits = self
+            .leg_connections
+            .acquire_many(connections.min(self.leg_connection_budget))
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Search leg connections unavailable: {}", e)))?;
+        let mut conns = Vec::with_capacity(connections as usize);
+        for _ in 0..connections {
+            conns.push(self.pool.acquire().await.map_err(|e| {
+                MemcpError::Storage(format!("Failed to acquire connection: {}", e))
+            })?);
+        }
+        time_leg(leg, self.leg_timeout, search(conns)).await
+    }
+
     /// Search for memories matching query terms against symbolic metadata fields.
     ///
-    /// Matches against: tags, extracted_entities, extracted_facts (JSONB containment),
-    /// type_hint and source (ILIKE). Results scored by match strength, returned as
-    /// (memory_id, symbolic_rank) pairs ordered by rank ascending (1 = best match).
+    /// Each term is matched against: tags, extracted_entities, extracted_facts, structured
+    /// field values (JSONB containment), type_hint and source (ILIKE). With
+    /// `search.symbolic_fuzzy_threshold` set, a term of 3+ characters that matches none of
+    /// tags/entities/facts exactly can still match them by pg_trgm word similarity ("Postgres"
+    /// finds "PostgreSQL"), scoring 2 × similarity. A memory's score is the sum of its per-term
+    /// match strengths, so memories matching several terms of a query bundle rank first.
+    /// Returned as (memory_id, symbolic_rank, score) triples ordered by rank ascending (1 =
+    /// best match). The raw score feeds weighted fusion; RRF only uses the rank.
     ///
     /// Suppresses consolidated originals from results (is_consolidated_original = FALSE).
+    /// Only the metadata filters of `filter` apply (namespace, dates, tags, type_hint, source);
+    /// a None namespace searches across all namespaces.
     pub async fn search_symbolic(
         &self,
-        query: &str,
+        conn: &mut PgConnection,
+        terms: &[String],
         limit: i64,
-    ) -> Result<Vec<(String, i64)>, MemcpError> {
-        // Build JSONB array for containment matching: ["query term"]
-        // This matches tags/entities/facts that contain the query string as an element.
-        let query_jsonb = serde_json::json!([query]);
-        // ILIKE pattern for type_hint and source matching
-        let ilike_pattern = format!("%{}%", query);
-
-        let sql = "SELECT id, ROW_NUMBER() OVER (ORDER BY score DESC) AS symbolic_rank
+        filter: &SearchFilter,
+    ) -> Result<Vec<(String, i64, f64)>, MemcpError> {
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Fuzzy matching goes through the trigram index from migration 029 (`%>` uses
+        // pg_trgm.word_similarity_threshold, set per transaction below)
+        let (fuzzy_score, fuzzy_match) = if self.symbolic_fuzzy_threshold.is_some() {
+            (
+                format!(
+                    "+ CASE WHEN length(t.term) >= 3
+                          AND NOT (tags @> jsonb_build_array(t.term)
+                                   OR extracted_entities @> jsonb_build_array(t.term)
+                                   OR extracted_facts @> jsonb_build_array(t.term))
+                          AND {text} %> t.term
+                        THEN 2 * word_similarity(t.term, {text}) ELSE 0 END",
+                    text = SYMBOLIC_TEXT_SQL
+                ),
+                format!("OR (length(t.term) >= 3 AND {} %> t.term)", SYMBOLIC_TEXT_SQL),
+            )
+        } else {
+            (String::new(), String::new())
+        };
+
+        // Each term becomes a one-element JSONB array (["term"]) for containment matching
+        // against tags/entities/facts/structured field values, and an ILIKE pattern for
+        // type_hint and source.
+        let sql = format!("SELECT id, ROW_NUMBER() OVER (ORDER BY score DESC) AS symbolic_rank,
+                score::FLOAT8 AS symbolic_score
             FROM (
                 SELECT id,
-                    (CASE WHEN tags @> $1::jsonb THEN 3 ELSE 0 END
-                     + CASE WHEN extracted_entities @> $1::jsonb THEN 2 ELSE 0 END
-                     + CASE WHEN extracted_facts @> $1::jsonb THEN 2 ELSE 0 END
-                     + CASE WHEN type_hint ILIKE $2 THEN 1 ELSE 0 END
-                     + CASE WHEN source ILIKE $2 THEN 1 ELSE 0 END) AS score
+                    SUM(CASE WHEN tags @> jsonb_build_array(t.term) THEN 3 ELSE 0 END
+                     + CASE WHEN extracted_entities @> jsonb_build_array(t.term) THEN 2 ELSE 0 END
+                     + CASE WHEN extracted_facts @> jsonb_build_array(t.term) THEN 2 ELSE 0 END
+                     + CASE WHEN field_values @> jsonb_build_array(t.term) THEN 2 ELSE 0 END
+                     + CASE WHEN type_hint ILIKE '%' || t.term || '%' THEN 1 ELSE 0 END
+                     + CASE WHEN source ILIKE '%' || t.term || '%' THEN 1 ELSE 0 END
+                     {fuzzy_score}) AS score
                 FROM memories
+                CROSS JOIN unnest($1::text[]) AS t(term)
                 WHERE is_consolidated_original = FALSE
+                  AND deleted_at IS NULL
+                  AND archived_at IS NULL
+                  AND (expires_at IS NULL OR expires_at > NOW())
+                  AND {filters}
                   AND (
-                    tags @> $1::jsonb
-                    OR extracted_entities @> $1::jsonb
-                    OR extracted_facts @> $1::jsonb
-                    OR type_hint ILIKE $2
-                    OR source ILIKE $2
+                    tags @> jsonb_build_array(t.term)
+                    OR extracted_entities @> jsonb_build_array(t.term)
+                    OR extracted_facts @> jsonb_build_array(t.term)
+                    OR field_values @> jsonb_build_array(t.term)
+                    OR type_hint ILIKE '%' || t.term || '%'
+                    OR source ILIKE '%' || t.term || '%'
+                    {fuzzy_match}
                   )
+                GROUP BY id
             ) ranked
             WHERE score > 0
             ORDER BY symbolic_rank
-            LIMIT $3";
-
-        let rows = sqlx::query(sql)
-            .bind(&query_jsonb)
-            .bind(&ilike_pattern)
-            .bind(limit)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| MemcpError::Storage(format!("Symbolic search failed: {}", e)))?;
+            LIMIT $2", filters = leg_filter_sql(3));
+
+        let query = bind_leg_filters(sqlx::query(&sql).bind(terms).bind(limit), filter);
+        let rows = match self.symbolic_fuzzy_threshold {
+            Some(threshold) => {
+                let mut tx = conn.begin().await.map_err(|e| MemcpError::Storage(e.to_string()))?;
+                sqlx::query("SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)")
+                    .bind(threshold.to_string())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| MemcpError::Storage(format!("Symbolic search failed: {}", e)))?;
+                let rows = query.fetch_all(&mut *tx).await;
+                tx.commit().await.map_err(|e| MemcpError::Storage(e.to_string()))?;
+                rows
+            }
+            None => query.fetch_all(&mut *conn).await,
+        }
+        .map_err(|e| MemcpError::Storage(format!("Symbolic search failed: {}", e)))?;
 
         rows.iter().map(|row| {
             let id: String = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
             let rank: i64 = row.try_get("symbolic_rank").map_err(|e| MemcpError::Storage(e.to_string()))?;
-            Ok((id, rank))
+            let score: f64 = row.try_get("symbolic_score").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            Ok((id, rank, score))
         }).collect::<Result<Vec<_>, MemcpError>>()
     }
 
     /// Search for memories matching the query using BM25 full-text ranking.
     ///
     /// Uses native PostgreSQL tsvector/ts_rank_cd by default. When use_paradedb is true
-    /// (ParadeDB available AND bm25_backend=paradedb configured), uses pg_search extension
-    /// for true BM25 scoring.
+    /// (ParadeDB available and indexed AND bm25_backend=paradedb configured), uses pg_search
+    /// extension for true BM25 scoring, falling back to native if the ParadeDB query fails.
     ///
-    /// Returns (memory_id, bm25_rank) pairs ordered by relevance. Rank is a 1-based position
-    /// (lower = more relevant) for the native path; same semantics for ParadeDB path.
+    /// Returns (memory_id, bm25_rank, score) triples ordered by relevance. Rank is a 1-based
+    /// position (lower = more relevant) for the native path; same semantics for ParadeDB path.
+    /// The raw score (ts_rank_cd or paradedb.score) feeds weighted fusion.
+    /// Only the metadata filters of `filter` apply (namespace, dates, tags, type_hint, source);
+    /// a None namespace searches across all namespaces.
     pub async fn search_bm25(
         &self,
+        conn: &mut PgConnection,
         query: &str,
         limit: i64,
-    ) -> Result<Vec<(String, i64)>, MemcpError> {
-        let sql = if self.use_paradedb {
+        filter: &SearchFilter,
+    ) -> Result<Vec<(String, i64, f64)>, MemcpError> {
+        if self.use_paradedb {
             // ParadeDB path: true BM25 scoring via pg_search extension
             // Uses ParadeDB's @@@ operator and paradedb.score() function for BM25 ranking
-            "SELECT id, ROW_NUMBER() OVER (
+            let sql = format!(
+                "SELECT id, ROW_NUMBER() OVER (
                 ORDER BY paradedb.score(id) DESC
-            ) AS bm25_rank
+            ) AS bm25_rank,
+            paradedb.score(id)::FLOAT8 AS bm25_score
             FROM memories
             WHERE content @@@ $1
               AND is_consolidated_original = FALSE
+              AND deleted_at IS NULL
+              AND archived_at IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+              AND {filters}
             ORDER BY bm25_rank
-            LIMIT $2"
-        } else {
-            // Native PostgreSQL tsvector path — uses GIN index from migration 004
-            // ts_rank_cd uses cover density ranking; ORDER BY bm25_rank for result order
+            LIMIT $2",
+                filters = leg_filter_sql(3)
+            );
+            // A BM25 index dropped after startup fails the query — answer from native search
+            match self.run_bm25_query(&mut *conn, &sql, query, limit, filter).await {
+                Ok(hits) => return Ok(hits),
+                Err(e) => tracing::warn!(error = %e, "ParadeDB BM25 search failed — falling back to native tsvector"),
+            }
+        }
+
+        // Native PostgreSQL tsvector path — uses the GIN index from migration 004 (english)
+        // or the one prepare_text_search created. The configuration is inlined as a literal
+        // so the planner can match the index expression.
+        // ts_rank_cd uses cover density ranking; ORDER BY bm25_rank for result order
+        let sql = format!(
             "SELECT id, ROW_NUMBER() OVER (
                 ORDER BY ts_rank_cd(
-                    to_tsvector('english', content),
-                    plainto_tsquery('english', $1)
+                    to_tsvector('{cfg}', content),
+                    plainto_tsquery('{cfg}', $1)
                 ) DESC
-            ) AS bm25_rank
+            ) AS bm25_rank,
+            ts_rank_cd(
+                to_tsvector('{cfg}', content),
+                plainto_tsquery('{cfg}', $1)
+            )::FLOAT8 AS bm25_score
             FROM memories
-            WHERE to_tsvector('english', content) @@ plainto_tsquery('english', $1)
+            WHERE to_tsvector('{cfg}', content) @@ plainto_tsquery('{cfg}', $1)
               AND is_consolidated_original = FALSE
+              AND deleted_at IS NULL
+              AND archived_at IS NULL
+              AND (expires_at IS NULL OR expires_at > NOW())
+              AND {filters}
             ORDER BY bm25_rank
-            LIMIT $2"
-        };
+            LIMIT $2",
+            cfg = self.text_search_config,
+            filters = leg_filter_sql(3)
+        );
+        self.run_bm25_query(conn, &sql, query, limit, filter).await
+    }
 
-        let rows = sqlx::query(sql)
-            .bind(query)
-            .bind(limit)
-            .fetch_all(&self.pool)
+    /// Run a search_bm25 query bound to ($1 query, $2 limit, leg filters from $3).
+    async fn run_bm25_query(
+        &self,
+        conn: &mut PgConnection,
+        sql: &str,
+        query: &str,
+        limit: i64,
+        filter: &SearchFilter,
+    ) -> Result<Vec<(String, i64, f64)>, MemcpError> {
+        let rows = bind_leg_filters(sqlx::query(sql).bind(query).bind(limit), filter)
+            .fetch_all(conn)
             .await
             .map_err(|e| MemcpError::Storage(format!("BM25 search failed: {}", e)))?;
 
         rows.iter().map(|row| {
             let id: String = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
             let rank: i64 = row.try_get("bm25_rank").map_err(|e| MemcpError::Storage(e.to_string()))?;
-            Ok((id, rank))
+            let score: f64 = row.try_get("bm25_score").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            Ok((id, rank, score))
         }).collect::<Result<Vec<_>, MemcpError>>()
     }
 
+    /// Keyword headlines for search_memory snippets: ts_headline over each (id, content) pair,
+    /// centered on the best match of `query` and marked with `crate::search::HIGHLIGHT_MARK`.
+    ///
+    /// Content is passed in rather than read from the table so it works when content is
+    /// encrypted at rest. Uses the native text search configuration on both BM25 backends.
+    pub async fn keyword_headlines(
+        &self,
+        query: &str,
+        contents: &[(String, String)],
+        max_words: usize,
+    ) -> Result<HashMap<String, String>, MemcpError> {
+        if contents.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let max_words = max_words.max(2);
+        let options = format!(
+            "StartSel={mark}, StopSel={mark}, MaxWords={max}, MinWords={min}, ShortWord=2, MaxFragments=1",
+            mark = crate::search::HIGHLIGHT_MARK,
+            max = max_words,
+            min = (max_words / 2).max(1),
+        );
+        let sql = format!(
+            "SELECT t.id, ts_headline('{cfg}', t.content, plainto_tsquery('{cfg}', $3), $4) AS headline
+             FROM UNNEST($1::text[], $2::text[]) AS t(id, content)",
+            cfg = self.text_search_config
+        );
+        let (ids, texts): (Vec<String>, Vec<String>) = contents.iter().cloned().unzip();
+        let rows = sqlx::query(&sql)
+            .bind(&ids)
+            .bind(&texts)
+            .bind(query)
+            .bind(&options)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to build keyword headlines: {}", e)))?;
+
+        rows.iter().map(|row| {
+            let id: String = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let headline: String = row.try_get("headline").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            Ok((id, headline))
+        }).collect()
+    }
+
     // -------------------------------------------------------------------------
     // Extraction pipeline support methods
     // -------------------------------------------------------------------------
 
     /// Store extraction results (entities and facts) for a memory.
     ///
-    /// Updates the extracted_entities and extracted_facts JSONB columns.
+    /// Updates the extracted_entities and extracted_facts JSONB columns, and replaces
+    /// type_hint when a classified type is given (None keeps the stored hint).
     /// Called by the extraction pipeline after successful entity/fact extraction.
     pub async fn update_extraction_results(
         &self,
         memory_id: &str,
         entities: &[String],
         facts: &[String],
+        type_hint: Option<&str>,
     ) -> Result<(), MemcpError> {
+        self.check_extraction_allowed()?;
         let entities_json = serde_json::json!(entities);
         let facts_json = serde_json::json!(facts);
 
         sqlx::query(
-            "UPDATE memories SET extracted_entities = $2, extracted_facts = $3 WHERE id = $1",
+            "UPDATE memories SET extracted_entities = $2, extracted_facts = $3, \
+             type_hint = COALESCE($4, type_hint) WHERE id = $1",
         )
         .bind(memory_id)
         .bind(&entities_json)
         .bind(&facts_json)
+        .bind(type_hint)
         .execute(&self.pool)
         .await
         .map_err(|e| MemcpError::Storage(format!("Failed to update extraction results: {}", e)))?;
@@ -1437,13 +3437,16 @@ impl PostgresMemoryStore {
 
     /// Fetch memories with pending extraction status for backfill.
     ///
-    /// Returns (id, content) pairs for queuing into the extraction pipeline.
+    /// Returns (id, content) pairs for queuing into the extraction pipeline. Memories with an
+    /// extraction job in the outbox are left to the outbox relay.
     pub async fn get_pending_extraction(
         &self,
         limit: i64,
     ) -> Result<Vec<(String, String)>, MemcpError> {
         let rows = sqlx::query(
-            "SELECT id, content FROM memories WHERE extraction_status = 'pending' LIMIT $1",
+            "SELECT id, content FROM memories WHERE extraction_status = 'pending' \
+             AND NOT EXISTS (SELECT 1 FROM pipeline_jobs j WHERE j.memory_id = memories.id AND j.kind = 'extraction') \
+             LIMIT $1",
         )
         .bind(limit)
         .fetch_all(&self.pool)
@@ -1454,54 +3457,271 @@ impl PostgresMemoryStore {
             .map(|row| {
                 let id: String = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
                 let content: String = row.try_get("content").map_err(|e| MemcpError::Storage(e.to_string()))?;
-                Ok((id, content))
+                Ok((id, self.decrypt_content(content)?))
             })
             .collect::<Result<Vec<_>, MemcpError>>()
     }
 
     // -------------------------------------------------------------------------
-    // Consolidation pipeline support methods
+    // Cross-process coordination
     // -------------------------------------------------------------------------
 
-    /// Atomically create a consolidated memory and link its originals.
-    ///
-    /// Runs in a single database transaction:
-    /// 1. INSERT a new memory row with `type_hint='consolidated'`, `source='consolidation'`.
-    /// 2. For each source_id: INSERT into `memory_consolidations` with similarity score.
-    /// 3. For each source_id: UPDATE memories SET `is_consolidated_original=TRUE`, `consolidated_into=id`.
-    ///
-    /// The UNIQUE constraint on (consolidated_id, original_id) prevents race conditions —
-    /// concurrent workers attempting the same consolidation will get a duplicate key error,
-    /// which the caller should handle gracefully by ignoring the violation.
+    /// Take the advisory lock `name` if no other session holds it (None when one does).
     ///
-    /// Returns the new consolidated memory's ID.
-    pub async fn create_consolidated_memory(
-        &self,
-        content: &str,
-        source_ids: &[String],
-        similarities: &[f64],
-    ) -> Result<String, MemcpError> {
-        let consolidated_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
+    /// The periodic background tasks (decay archiving, expiry and retention sweeps) take
+    /// their lock at the start of each tick and skip the tick on None, so when several memcp
+    /// processes share a database only one of them runs a given pass at a time. A process
+    /// that dies mid-pass frees the lock with its connection, and the next tick proceeds.
+    pub async fn try_advisory_lock(&self, name: &str) -> Result<Option<AdvisoryLock>, MemcpError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin advisory lock transaction: {}", e))
+        })?;
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_xact_lock(hashtext($1))")
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to take advisory lock {}: {}", name, e)))?;
+        Ok(acquired.then_some(AdvisoryLock { tx }))
+    }
 
-        // Start a database transaction for atomic create + link + mark
+    /// Take the advisory lock `name`, waiting while another session holds it (bounded by
+    /// database.statement_timeout_ms).
+    pub async fn advisory_lock(&self, name: &str) -> Result<AdvisoryLock, MemcpError> {
         let mut tx = self.pool.begin().await.map_err(|e| {
-            MemcpError::Storage(format!("Failed to begin consolidation transaction: {}", e))
+            MemcpError::Storage(format!("Failed to begin advisory lock transaction: {}", e))
         })?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to take advisory lock {}: {}", name, e)))?;
+        Ok(AdvisoryLock { tx })
+    }
 
-        // 1. Insert the consolidated memory row
-        sqlx::query(
-            "INSERT INTO memories \
+    // -------------------------------------------------------------------------
+    // Pipeline job outbox
+    // -------------------------------------------------------------------------
+
+    /// Claim up to `limit` unclaimed `kind` jobs (or ones another worker claimed more than
+    /// `lease_secs` ago) for `worker`, oldest first, with the memories they refer to.
+    ///
+    /// SKIP LOCKED lets concurrent workers claim disjoint batches without blocking. A worker
+    /// never re-claims its own jobs: those may still be waiting in its pipeline's queue
+    /// after the lease ran out, and claiming them again would run them twice.
+    pub async fn claim_pipeline_jobs(
+        &self,
+        kind: JobKind,
+        worker: &str,
+        lease_secs: u64,
+        limit: i64,
+    ) -> Result<Vec<ClaimedJob>, MemcpError> {
+        let rows = sqlx::query(
+            "UPDATE pipeline_jobs SET claimed_at = NOW(), claimed_by = $2 \
+             WHERE id IN ( \
+                 SELECT id FROM pipeline_jobs \
+                 WHERE kind = $1 \
+                   AND (claimed_at IS NULL \
+                        OR (claimed_at < NOW() - make_interval(secs => $3) \
+                            AND claimed_by IS DISTINCT FROM $2)) \
+                 ORDER BY id \
+                 LIMIT $4 \
+                 FOR UPDATE SKIP LOCKED) \
+             RETURNING id, memory_id, classify",
+        )
+        .bind(kind.as_str())
+        .bind(worker)
+        .bind(lease_secs as f64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to claim pipeline jobs: {}", e)))?;
+
+        let mut claimed: Vec<(i64, String, bool)> = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let id: i64 = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let memory_id: String = row.try_get("memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let classify: bool = row.try_get("classify").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            claimed.push((id, memory_id, classify));
+        }
+        // RETURNING order is unspecified — hand jobs out oldest first
+        claimed.sort_by_key(|(id, _, _)| *id);
+
+        let ids: Vec<String> = claimed.iter().map(|(_, memory_id, _)| memory_id.clone()).collect();
+        let mut memories = self.get_memories_by_ids(&ids).await?;
+        Ok(claimed
+            .into_iter()
+            .filter_map(|(_, memory_id, classify)| {
+                memories.remove(&memory_id).map(|memory| ClaimedJob { memory, classify })
+            })
+            .collect())
+    }
+
+    /// Hand claimed `kind` jobs for `memory_ids` back to the outbox (e.g. the queue was full).
+    pub async fn release_pipeline_jobs(&self, kind: JobKind, memory_ids: &[String]) -> Result<u64, MemcpError> {
+        let result = sqlx::query(
+            "UPDATE pipeline_jobs SET claimed_at = NULL, claimed_by = NULL \
+             WHERE kind = $1 AND memory_id = ANY($2)",
+        )
+        .bind(kind.as_str())
+        .bind(memory_ids)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to release pipeline jobs: {}", e)))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Hand back every `kind` job still claimed by `worker` (on shutdown).
+    pub async fn release_worker_claims(&self, kind: JobKind, worker: &str) -> Result<u64, MemcpError> {
+        let result = sqlx::query(
+            "UPDATE pipeline_jobs SET claimed_at = NULL, claimed_by = NULL \
+             WHERE kind = $1 AND claimed_by = $2",
+        )
+        .bind(kind.as_str())
+        .bind(worker)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to release pipeline jobs: {}", e)))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Remove a memory's `kind` job from the outbox once it reached a final status
+    /// (complete or failed). A no-op when the outbox doesn't carry `kind`.
+    pub async fn complete_pipeline_job(&self, memory_id: &str, kind: JobKind) -> Result<(), MemcpError> {
+        if !self.outbox.contains(&kind) {
+            return Ok(());
+        }
+        sqlx::query("DELETE FROM pipeline_jobs WHERE memory_id = $1 AND kind = $2")
+            .bind(memory_id)
+            .bind(kind.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to complete pipeline job: {}", e)))?;
+        Ok(())
+    }
+
+    /// A memory's jobs still in the outbox, each with the number of same-kind jobs queued
+    /// ahead of it. Empty once every job finished (or when the outbox is off).
+    pub async fn pending_pipeline_jobs(&self, memory_id: &str) -> Result<Vec<PendingJob>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT j.kind, j.created_at, j.claimed_at, \
+                    (SELECT COUNT(*) FROM pipeline_jobs a WHERE a.kind = j.kind AND a.id < j.id) AS jobs_ahead \
+             FROM pipeline_jobs j \
+             WHERE j.memory_id = $1 \
+             ORDER BY j.kind",
+        )
+        .bind(memory_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to fetch pipeline jobs: {}", e)))?;
+
+        let mut jobs = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let kind: String = row.try_get("kind").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            jobs.push(PendingJob {
+                kind: if kind == JobKind::Embedding.as_str() { JobKind::Embedding } else { JobKind::Extraction },
+                jobs_ahead: row.try_get("jobs_ahead").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                queued_at: row.try_get("created_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                claimed_at: row.try_get("claimed_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            });
+        }
+        Ok(jobs)
+    }
+
+    // -------------------------------------------------------------------------
+    // Consolidation pipeline support methods
+    // -------------------------------------------------------------------------
+
+    /// Fetch the next batch of memories an on-demand consolidation scan should check.
+    ///
+    /// Returns live, embedded memories that aren't consolidated originals, each with its
+    /// current embedding, ordered by (created_at, id). Pass the last row's (created_at, id)
+    /// as `after` to continue from where the previous batch ended.
+    pub async fn get_consolidation_candidates(
+        &self,
+        namespace: Option<&str>,
+        after: Option<&(chrono::DateTime<Utc>, String)>,
+        limit: i64,
+    ) -> Result<Vec<(Memory, pgvector::Vector)>, MemcpError> {
+        let sql = format!(
+            "SELECT {}, me.embedding \
+             FROM memories m \
+             JOIN memory_embeddings me ON me.memory_id = m.id AND me.is_current = TRUE \
+             WHERE m.embedding_status = 'complete' \
+               AND m.is_consolidated_original = FALSE \
+               AND m.deleted_at IS NULL \
+               AND ($1::TEXT IS NULL OR m.namespace = $1) \
+               AND ($2::TIMESTAMPTZ IS NULL OR (m.created_at, m.id) > ($2, $3)) \
+             ORDER BY m.created_at ASC, m.id ASC \
+             LIMIT $4",
+            memory_columns_with_alias("m")
+        );
+        let rows = sqlx::query(&sql)
+            .bind(namespace)
+            .bind(after.map(|(created_at, _)| *created_at))
+            .bind(after.map(|(_, id)| id.as_str()))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to fetch consolidation candidates: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let embedding: pgvector::Vector = row
+                    .try_get("embedding")
+                    .map_err(|e| MemcpError::Storage(e.to_string()))?;
+                Ok((self.memory_from_row(row)?, embedding))
+            })
+            .collect()
+    }
+
+    /// Atomically create a consolidated memory and link its originals.
+    ///
+    /// Runs in a single database transaction:
+    /// 1. INSERT a new memory row with `type_hint='consolidated'`, `source='consolidation'`,
+    ///    in the same namespace as the first original, carrying every original's origin_refs.
+    /// 2. For each source_id: INSERT into `memory_consolidations` with similarity score.
+    /// 3. For each source_id: UPDATE memories SET `is_consolidated_original=TRUE`, `consolidated_into=id`.
+    ///
+    /// The UNIQUE constraint on (consolidated_id, original_id) prevents race conditions —
+    /// concurrent workers attempting the same consolidation will get a duplicate key error,
+    /// which the caller should handle gracefully by ignoring the violation.
+    ///
+    /// Returns the new consolidated memory's ID.
+    pub async fn create_consolidated_memory(
+        &self,
+        content: &str,
+        source_ids: &[String],
+        similarities: &[f64],
+        fact_coverage: Option<f64>,
+    ) -> Result<String, MemcpError> {
+        let consolidated_id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        // Start a database transaction for atomic create + link + mark
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin consolidation transaction: {}", e))
+        })?;
+
+        // 1. Insert the consolidated memory row
+        sqlx::query(
+            "INSERT INTO memories \
              (id, content, type_hint, source, created_at, updated_at, access_count, \
-              embedding_status, extraction_status) \
-             VALUES ($1, $2, 'consolidated', 'consolidation', $3, $3, 0, 'pending', 'pending')",
+              embedding_status, extraction_status, namespace, origin_refs, content_digest) \
+             VALUES ($1, $2, 'consolidated', 'consolidation', $3, $3, 0, 'pending', 'pending', \
+                     COALESCE((SELECT namespace FROM memories WHERE id = $4), 'default'), \
+                     (SELECT jsonb_agg(DISTINCT r) FROM memories m, jsonb_array_elements(m.origin_refs) r \
+                      WHERE m.id = ANY($5)), $6)",
         )
         .bind(&consolidated_id)
-        .bind(content)
+        .bind(self.encrypt_content(content)?)
         .bind(&now)
+        .bind(source_ids.first())
+        .bind(source_ids)
+        .bind(content_digest(self.cipher.as_deref(), content))
         .execute(&mut *tx)
         .await
         .map_err(|e| MemcpError::Storage(format!("Failed to insert consolidated memory: {}", e)))?;
+        insert_pipeline_jobs(&mut *tx, &consolidated_id, &self.outbox, false).await?;
 
         // 2. Insert consolidation provenance records + mark originals
         for (source_id, &similarity) in source_ids.iter().zip(similarities.iter()) {
@@ -1510,13 +3730,14 @@ impl PostgresMemoryStore {
             // Insert memory_consolidations record
             sqlx::query(
                 "INSERT INTO memory_consolidations \
-                 (id, consolidated_id, original_id, similarity_score, created_at) \
-                 VALUES ($1, $2, $3, $4, $5)",
+                 (id, consolidated_id, original_id, similarity_score, fact_coverage, created_at) \
+                 VALUES ($1, $2, $3, $4, $5, $6)",
             )
             .bind(&link_id)
             .bind(&consolidated_id)
             .bind(source_id)
             .bind(similarity as f32)  // REAL column — use f32
+            .bind(fact_coverage.map(|c| c as f32))
             .bind(&now)
             .execute(&mut *tx)
             .await
@@ -1542,29 +3763,1940 @@ impl PostgresMemoryStore {
         Ok(consolidated_id)
     }
 
-    /// Fetch the current embedding vector for a memory.
-    ///
-    /// Returns None if no current embedding exists (not yet embedded, or embedding was staled).
-    pub async fn get_memory_embedding(
+    /// Record a synthesized consolidation the quality check rejected.
+    pub async fn record_consolidation_rejection(
         &self,
-        memory_id: &str,
-    ) -> Result<Option<pgvector::Vector>, MemcpError> {
+        memory_ids: &[String],
+        reason: &str,
+        fact_coverage: Option<f64>,
+    ) -> Result<(), MemcpError> {
+        let mut memory_ids = memory_ids.to_vec();
+        memory_ids.sort();
+        sqlx::query(
+            "INSERT INTO consolidation_rejections (id, memory_ids, reason, fact_coverage) \
+             VALUES ($1, $2, $3, $4)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&memory_ids)
+        .bind(reason)
+        .bind(fact_coverage.map(|c| c as f32))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to record consolidation rejection: {}", e)))?;
+        Ok(())
+    }
+
+    /// Whether a synthesis of exactly this group of memories was rejected before.
+    pub async fn consolidation_rejected(&self, memory_ids: &[String]) -> Result<bool, MemcpError> {
+        sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM consolidation_rejections \
+             WHERE memory_ids @> $1 AND memory_ids <@ $1)",
+        )
+        .bind(memory_ids)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to check consolidation rejections: {}", e)))
+    }
+
+    /// List the stored revisions of a memory, newest first.
+    ///
+    /// The current version is not included — it lives in the memories row itself.
+    pub async fn list_revisions(&self, memory_id: &str) -> Result<Vec<MemoryRevision>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT memory_id, revision, content, type_hint, source, tags, valid_from, replaced_at \
+             FROM memory_revisions WHERE memory_id = $1 ORDER BY revision DESC",
+        )
+        .bind(memory_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to list memory revisions: {}", e)))?;
+
+        rows.iter().map(|row| self.revision_from_row(row)).collect()
+    }
+
+    /// Roll a memory back to an earlier revision.
+    ///
+    /// Applied as a normal update, so the version being replaced is itself snapshotted
+    /// and the revert can be undone. Returns NotFound if the memory or revision doesn't exist.
+    pub async fn revert_to_revision(&self, memory_id: &str, revision: i32) -> Result<Memory, MemcpError> {
         let row = sqlx::query(
-            "SELECT embedding FROM memory_embeddings WHERE memory_id = $1 AND is_current = TRUE",
+            "SELECT memory_id, revision, content, type_hint, source, tags, valid_from, replaced_at \
+             FROM memory_revisions WHERE memory_id = $1 AND revision = $2",
         )
         .bind(memory_id)
+        .bind(revision)
         .fetch_optional(&self.pool)
         .await
-        .map_err(|e| MemcpError::Storage(format!("Failed to fetch memory embedding: {}", e)))?;
+        .map_err(|e| MemcpError::Storage(e.to_string()))?
+        .ok_or_else(|| MemcpError::NotFound { id: format!("{} (revision {})", memory_id, revision) })?;
 
-        match row {
-            None => Ok(None),
-            Some(r) => {
-                let embedding: pgvector::Vector = r
-                    .try_get("embedding")
-                    .map_err(|e| MemcpError::Storage(e.to_string()))?;
-                Ok(Some(embedding))
+        let target = self.revision_from_row(&row)?;
+        let tags: Vec<String> = target
+            .tags
+            .as_ref()
+            .and_then(|t| serde_json::from_value(t.clone()).ok())
+            .unwrap_or_default();
+
+        self.update(
+            memory_id,
+            UpdateMemory {
+                content: Some(target.content),
+                type_hint: Some(target.type_hint),
+                source: Some(target.source),
+                tags: Some(tags),
+                expires_at: None,
+                importance: None,
+                payload: None,
+                pinned: None,
+            },
+        )
+        .await
+    }
+
+    /// Create a typed link from `source_id` to `target_id`.
+    ///
+    /// Both memories must exist, be live, and share a namespace. Linking the same pair with
+    /// the same relation twice is idempotent — the existing link is returned.
+    pub async fn create_link(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation: &str,
+    ) -> Result<MemoryLink, MemcpError> {
+        if source_id == target_id {
+            return Err(MemcpError::Validation {
+                message: "A memory cannot be linked to itself".to_string(),
+                field: Some("target_id".to_string()),
+            });
+        }
+
+        let rows = sqlx::query(
+            "SELECT id, namespace FROM memories WHERE id = ANY($1) AND deleted_at IS NULL",
+        )
+        .bind(&[source_id.to_string(), target_id.to_string()][..])
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        let mut namespaces: HashMap<String, String> = HashMap::with_capacity(2);
+        for row in &rows {
+            let id: String = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let namespace: String = row.try_get("namespace").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            namespaces.insert(id, namespace);
+        }
+        for id in [source_id, target_id] {
+            if !namespaces.contains_key(id) {
+                return Err(MemcpError::NotFound { id: id.to_string() });
+            }
+        }
+        if namespaces[source_id] != namespaces[target_id] {
+            return Err(MemcpError::Validation {
+                message: "Linked memories must belong to the same namespace".to_string(),
+                field: Some("target_id".to_string()),
+            });
+        }
+
+        // ON CONFLICT DO UPDATE (no-op) so RETURNING yields the existing row on duplicates
+        let row = sqlx::query(
+            "INSERT INTO memory_links (id, source_id, target_id, relation, created_at) \
+             VALUES ($1, $2, $3, $4, NOW()) \
+             ON CONFLICT (source_id, target_id, relation) DO UPDATE SET relation = EXCLUDED.relation \
+             RETURNING id, source_id, target_id, relation, created_at",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(source_id)
+        .bind(target_id)
+        .bind(relation)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to create memory link: {}", e)))?;
+
+        row_to_link(&row)
+    }
+
+    /// Delete links from `source_id` to `target_id` (all relations when `relation` is None).
+    ///
+    /// Returns the number of links removed.
+    pub async fn delete_link(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation: Option<&str>,
+    ) -> Result<u64, MemcpError> {
+        let result = sqlx::query(
+            "DELETE FROM memory_links \
+             WHERE source_id = $1 AND target_id = $2 AND ($3::text IS NULL OR relation = $3)",
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .bind(relation)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to delete memory link: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Collect the neighbourhood of a memory by following links in both directions.
+    ///
+    /// Breadth-first up to `depth` hops; trashed memories are skipped and not traversed.
+    /// Returns the reachable memories (including the start) and every link between them.
+    pub async fn get_memory_graph(
+        &self,
+        memory_id: &str,
+        depth: u32,
+    ) -> Result<(Vec<Memory>, Vec<MemoryLink>), MemcpError> {
+        let mut visited: HashSet<String> = HashSet::from([memory_id.to_string()]);
+        let mut frontier: Vec<String> = vec![memory_id.to_string()];
+        let mut links: HashMap<String, MemoryLink> = HashMap::new();
+
+        for _ in 0..depth {
+            if frontier.is_empty() {
+                break;
+            }
+            let rows = sqlx::query(
+                "SELECT l.id, l.source_id, l.target_id, l.relation, l.created_at \
+                 FROM memory_links l \
+                 JOIN memories s ON s.id = l.source_id AND s.deleted_at IS NULL \
+                 JOIN memories t ON t.id = l.target_id AND t.deleted_at IS NULL \
+                 WHERE l.source_id = ANY($1) OR l.target_id = ANY($1)",
+            )
+            .bind(&frontier)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to traverse memory links: {}", e)))?;
+
+            let mut next: Vec<String> = Vec::new();
+            for row in &rows {
+                let link = row_to_link(row)?;
+                for id in [&link.source_id, &link.target_id] {
+                    if visited.insert(id.clone()) {
+                        next.push(id.clone());
+                    }
+                }
+                links.insert(link.id.clone(), link);
             }
+            frontier = next;
+        }
+
+        let ids: Vec<String> = visited.into_iter().collect();
+        let found = self.get_memories_by_ids(&ids).await?;
+        let mut memories: Vec<Memory> = found.into_values().filter(|m| m.deleted_at.is_none()).collect();
+        memories.sort_by_key(|m| std::cmp::Reverse(m.created_at));
+
+        let mut links: Vec<MemoryLink> = links.into_values().collect();
+        links.sort_by_key(|l| l.created_at);
+
+        Ok((memories, links))
+    }
+
+    /// Live memories in the namespace of `memory_id` sharing at least one of `entities`
+    /// (its extracted entities), most shared entities first, then newest. Consolidated
+    /// originals and archived memories are skipped. Used as contradiction detection candidates.
+    pub async fn get_entity_neighbours(
+        &self,
+        memory_id: &str,
+        entities: &[String],
+        limit: i64,
+    ) -> Result<Vec<Memory>, MemcpError> {
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT {} FROM memories
+             WHERE namespace = (SELECT namespace FROM memories WHERE id = $1)
+               AND id <> $1
+               AND deleted_at IS NULL
+               AND archived_at IS NULL
+               AND is_consolidated_original = FALSE
+               AND (expires_at IS NULL OR expires_at > NOW())
+               AND jsonb_typeof(extracted_entities) = 'array'
+               AND extracted_entities ?| $2
+             ORDER BY (
+                 SELECT COUNT(*) FROM jsonb_array_elements_text(extracted_entities) AS e(entity)
+                 WHERE e.entity = ANY($2)
+               ) DESC, created_at DESC
+             LIMIT $3",
+            MEMORY_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(memory_id)
+            .bind(entities)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to fetch entity neighbours: {}", e)))?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
+    /// `contradicts` links between live memories in `namespace`, newest first.
+    ///
+    /// With `memory_id`, only links starting or ending at that memory.
+    pub async fn get_contradictions(
+        &self,
+        namespace: &str,
+        memory_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<MemoryLink>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT l.id, l.source_id, l.target_id, l.relation, l.created_at \
+             FROM memory_links l \
+             JOIN memories s ON s.id = l.source_id \
+             JOIN memories t ON t.id = l.target_id \
+             WHERE l.relation = $1 \
+               AND s.namespace = $2 \
+               AND s.deleted_at IS NULL AND t.deleted_at IS NULL \
+               AND ($3::text IS NULL OR l.source_id = $3 OR l.target_id = $3) \
+             ORDER BY l.created_at DESC, l.id ASC \
+             LIMIT $4",
+        )
+        .bind(CONTRADICTS_RELATION)
+        .bind(namespace)
+        .bind(memory_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to fetch contradictions: {}", e)))?;
+
+        rows.iter().map(row_to_link).collect()
+    }
+
+    /// Aggregate distinct type_hints, sources, and top tags over live memories.
+    ///
+    /// `namespace` = None aggregates across all namespaces. Tags are capped at `tag_limit`.
+    pub async fn get_memory_facets(
+        &self,
+        namespace: Option<&str>,
+        tag_limit: i64,
+    ) -> Result<MemoryFacets, MemcpError> {
+        const LIVE: &str = "deleted_at IS NULL \
+             AND (expires_at IS NULL OR expires_at > NOW()) \
+             AND ($1::text IS NULL OR namespace = $1)";
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM memories WHERE {}", LIVE))
+            .bind(namespace)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        let type_hints = self
+            .facet_counts(
+                &format!(
+                    "SELECT type_hint AS value, COUNT(*) AS count FROM memories WHERE {} \
+                     GROUP BY type_hint ORDER BY count DESC, value ASC",
+                    LIVE
+                ),
+                namespace,
+                None,
+            )
+            .await?;
+        let sources = self
+            .facet_counts(
+                &format!(
+                    "SELECT source AS value, COUNT(*) AS count FROM memories WHERE {} \
+                     GROUP BY source ORDER BY count DESC, value ASC",
+                    LIVE
+                ),
+                namespace,
+                None,
+            )
+            .await?;
+        let tags = self
+            .facet_counts(
+                &format!(
+                    "SELECT tag AS value, COUNT(*) AS count \
+                     FROM memories, jsonb_array_elements_text(tags) AS tag \
+                     WHERE {} AND jsonb_typeof(tags) = 'array' \
+                     GROUP BY tag ORDER BY count DESC, value ASC LIMIT $2",
+                    LIVE
+                ),
+                namespace,
+                Some(tag_limit),
+            )
+            .await?;
+
+        Ok(MemoryFacets { total, type_hints, sources, tags })
+    }
+
+    /// Run a (value, count) aggregate bound to ($1 namespace[, $2 limit]).
+    async fn facet_counts(
+        &self,
+        sql: &str,
+        namespace: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<Vec<FacetCount>, MemcpError> {
+        let mut query = sqlx::query(sql).bind(namespace);
+        if let Some(limit) = limit {
+            query = query.bind(limit);
         }
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to aggregate facets: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(FacetCount {
+                    value: row.try_get("value").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    count: row.try_get("count").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Aggregate counts, content length, age range, FSRS distributions, and table sizes.
+    ///
+    /// `namespace` = None aggregates across all namespaces; table sizes always cover all.
+    pub async fn memory_stats(&self, namespace: Option<&str>) -> Result<MemoryStats, MemcpError> {
+        const LIVE: &str = "deleted_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())";
+        let storage_err = |e: sqlx::Error| MemcpError::Storage(format!("Failed to aggregate memory stats: {}", e));
+
+        let row = sqlx::query(&format!(
+            "SELECT COUNT(*) FILTER (WHERE parent_id IS NULL AND {live}) AS total, \
+                    COUNT(*) FILTER (WHERE parent_id IS NULL AND {live} AND archived_at IS NOT NULL) AS archived, \
+                    COUNT(*) FILTER (WHERE parent_id IS NULL AND deleted_at IS NOT NULL) AS trashed, \
+                    COUNT(*) FILTER (WHERE parent_id IS NULL AND deleted_at IS NULL AND expires_at <= NOW()) AS expired, \
+                    COUNT(*) FILTER (WHERE parent_id IS NOT NULL) AS chunks, \
+                    COUNT(*) FILTER (WHERE parent_id IS NULL AND {live} AND is_consolidated_original) AS consolidated_originals, \
+                    AVG(char_length(content)) FILTER (WHERE parent_id IS NULL AND {live})::float8 AS avg_content_chars, \
+                    MIN(created_at) FILTER (WHERE parent_id IS NULL AND {live}) AS oldest, \
+                    MAX(created_at) FILTER (WHERE parent_id IS NULL AND {live}) AS newest \
+             FROM memories WHERE ($1::text IS NULL OR namespace = $1)",
+            live = LIVE
+        ))
+        .bind(namespace)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(storage_err)?;
+
+        let consolidated: i64 = sqlx::query_scalar(
+            "SELECT COUNT(DISTINCT c.consolidated_id) FROM memory_consolidations c \
+             JOIN memories m ON m.id = c.consolidated_id \
+             WHERE m.deleted_at IS NULL AND (m.expires_at IS NULL OR m.expires_at > NOW()) \
+               AND ($1::text IS NULL OR m.namespace = $1)",
+        )
+        .bind(namespace)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(storage_err)?;
+
+        // Memories without a salience row have the default stability of 1 day, as in scoring
+        let distribution_row = sqlx::query(
+            "WITH fsrs AS ( \
+                 SELECT COALESCE(s.stability, 1.0)::float8 AS stability, \
+                        EXTRACT(EPOCH FROM NOW() - COALESCE(s.last_reinforced_at, m.created_at))::float8 / 86400.0 AS days \
+                 FROM memories m LEFT JOIN memory_salience s ON s.memory_id = m.id \
+                 WHERE m.parent_id IS NULL AND m.deleted_at IS NULL \
+                   AND (m.expires_at IS NULL OR m.expires_at > NOW()) \
+                   AND ($1::text IS NULL OR m.namespace = $1) \
+             ) \
+             SELECT percentile_cont(ARRAY[0.1, 0.25, 0.5, 0.75, 0.9]) WITHIN GROUP (ORDER BY stability) AS stability, \
+                    percentile_cont(ARRAY[0.1, 0.25, 0.5, 0.75, 0.9]) WITHIN GROUP ( \
+                        ORDER BY CASE WHEN stability > 0 \
+                                      THEN power(1.0 + (19.0 / 81.0) * GREATEST(days, 0.0) / stability, -0.5) \
+                                      ELSE 0.0 END \
+                    ) AS retrievability \
+             FROM fsrs",
+        )
+        .bind(namespace)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(storage_err)?;
+        let percentiles = |column: &str| -> Result<Option<Percentiles>, MemcpError> {
+            let values: Option<Vec<f64>> = distribution_row.try_get(column).map_err(|e| MemcpError::Storage(e.to_string()))?;
+            Ok(values.as_deref().and_then(Percentiles::from_slice))
+        };
+
+        let sizes = sqlx::query(
+            "SELECT pg_total_relation_size('memories') AS memories_bytes, \
+                    pg_total_relation_size('memory_embeddings') AS embeddings_bytes, \
+                    pg_total_relation_size('fact_embeddings') AS fact_embeddings_bytes, \
+                    pg_database_size(current_database()) AS database_bytes",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(storage_err)?;
+
+        let counts_sql = |column: &str| {
+            format!(
+                "SELECT {column} AS value, COUNT(*) AS count FROM memories \
+                 WHERE parent_id IS NULL AND {LIVE} AND ($1::text IS NULL OR namespace = $1) \
+                 GROUP BY {column} ORDER BY count DESC, value ASC"
+            )
+        };
+        let get = |name: &str| row.try_get::<i64, _>(name).map_err(|e| MemcpError::Storage(e.to_string()));
+        let size = |name: &str| sizes.try_get::<i64, _>(name).map_err(|e| MemcpError::Storage(e.to_string()));
+        Ok(MemoryStats {
+            total: get("total")?,
+            archived: get("archived")?,
+            trashed: get("trashed")?,
+            expired: get("expired")?,
+            chunks: get("chunks")?,
+            by_type_hint: self.facet_counts(&counts_sql("type_hint"), namespace, None).await?,
+            by_source: self.facet_counts(&counts_sql("source"), namespace, None).await?,
+            consolidated,
+            consolidated_originals: get("consolidated_originals")?,
+            avg_content_chars: row.try_get("avg_content_chars").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            oldest_created_at: row.try_get("oldest").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            newest_created_at: row.try_get("newest").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            retrievability: percentiles("retrievability")?,
+            stability_days: percentiles("stability")?,
+            storage: StorageSizes {
+                memories_bytes: size("memories_bytes")?,
+                embeddings_bytes: size("embeddings_bytes")?,
+                fact_embeddings_bytes: size("fact_embeddings_bytes")?,
+                database_bytes: size("database_bytes")?,
+            },
+        })
+    }
+
+    /// Live memories in `namespace` whose extracted entities mention `entity`, newest first.
+    ///
+    /// Matches the exact entity by JSONB containment; unless `exact`, also any entity
+    /// containing it case-insensitively (ILIKE), so "postgres" finds "PostgreSQL".
+    /// Paginated with the same (created_at, id) keyset cursor as list().
+    pub async fn get_memories_by_entity(
+        &self,
+        namespace: &str,
+        entity: &str,
+        exact: bool,
+        limit: i64,
+        cursor: Option<&str>,
+    ) -> Result<ListResult, MemcpError> {
+        let limit = limit.clamp(1, 100);
+        let (cursor_created_at, cursor_id) = match cursor {
+            Some(cursor) => {
+                let (created_at, id) = decode_cursor(cursor)?;
+                (Some(created_at), Some(id))
+            }
+            None => (None, None),
+        };
+
+        let sql = format!(
+            "SELECT {} FROM memories
+             WHERE namespace = $1
+               AND deleted_at IS NULL
+               AND (expires_at IS NULL OR expires_at > NOW())
+               AND jsonb_typeof(extracted_entities) = 'array'
+               AND (
+                 extracted_entities @> jsonb_build_array($2::text)
+                 OR (NOT $3 AND EXISTS (
+                   SELECT 1 FROM jsonb_array_elements_text(extracted_entities) AS e(entity)
+                   WHERE e.entity ILIKE '%' || $2 || '%'
+                 ))
+               )
+               AND ($4::timestamptz IS NULL OR created_at < $4 OR (created_at = $4 AND id > $5))
+             ORDER BY created_at DESC, id ASC
+             LIMIT $6",
+            MEMORY_COLUMNS
+        );
+        // Fetch one extra to determine if there are more pages
+        let rows = sqlx::query(&sql)
+            .bind(namespace)
+            .bind(entity)
+            .bind(exact)
+            .bind(cursor_created_at)
+            .bind(cursor_id)
+            .bind(limit + 1)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to fetch memories by entity: {}", e)))?;
+
+        let has_more = rows.len() as i64 > limit;
+        let memories = rows
+            .iter()
+            .take(limit as usize)
+            .map(|row| self.memory_from_row(row))
+            .collect::<Result<Vec<_>, MemcpError>>()?;
+        let next_cursor = if has_more {
+            memories.last().map(|m| encode_cursor(&m.created_at, &m.id))
+        } else {
+            None
+        };
+
+        Ok(ListResult { memories, next_cursor })
+    }
+
+    /// Extracted entities across live memories in `namespace`, most frequent first.
+    ///
+    /// Each count is the number of memories mentioning the entity. `contains` keeps only
+    /// entities containing it case-insensitively; at most `limit` are returned.
+    pub async fn list_entities(
+        &self,
+        namespace: &str,
+        contains: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<FacetCount>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT entity AS value, COUNT(DISTINCT id) AS count \
+             FROM memories, jsonb_array_elements_text(extracted_entities) AS entity \
+             WHERE namespace = $1 \
+               AND deleted_at IS NULL \
+               AND (expires_at IS NULL OR expires_at > NOW()) \
+               AND jsonb_typeof(extracted_entities) = 'array' \
+               AND ($2::text IS NULL OR entity ILIKE '%' || $2 || '%') \
+             GROUP BY entity ORDER BY count DESC, value ASC LIMIT $3",
+        )
+        .bind(namespace)
+        .bind(contains)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to aggregate entities: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(FacetCount {
+                    value: row.try_get("value").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    count: row.try_get("count").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Rewrite tags across every memory in `namespace` carrying any tag in `from`.
+    ///
+    /// Each matching tag is replaced by `to` (rename / merge) or dropped when `to` is None
+    /// (delete); the result keeps the original tag order with duplicates removed. Trashed
+    /// memories are rewritten too so a later restore doesn't bring the old tag back.
+    /// Runs in one transaction and snapshots a revision of every changed memory first,
+    /// like update(). Returns the rewritten memories.
+    pub async fn rewrite_tags(
+        &self,
+        namespace: &str,
+        from: &[String],
+        to: Option<&str>,
+    ) -> Result<Vec<Memory>, MemcpError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin tag rewrite transaction: {}", e))
+        })?;
+
+        // Lock affected rows so concurrent updates can't interleave revision numbers
+        let ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM memories \
+             WHERE namespace = $1 AND jsonb_typeof(tags) = 'array' AND tags ?| $2 \
+             FOR UPDATE",
+        )
+        .bind(namespace)
+        .bind(from)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to find tagged memories: {}", e)))?;
+
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let revision_ids: Vec<String> = ids.iter().map(|_| Uuid::new_v4().to_string()).collect();
+        sqlx::query(
+            "INSERT INTO memory_revisions \
+             (id, memory_id, revision, content, type_hint, source, tags, valid_from, replaced_at) \
+             SELECT r.revision_id, m.id, \
+                    COALESCE((SELECT MAX(revision) FROM memory_revisions WHERE memory_id = m.id), 0) + 1, \
+                    m.content, m.type_hint, m.source, m.tags, m.updated_at, NOW() \
+             FROM memories m \
+             JOIN UNNEST($1::text[], $2::text[]) AS r(memory_id, revision_id) ON r.memory_id = m.id",
+        )
+        .bind(&ids)
+        .bind(&revision_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to snapshot memory revisions: {}", e)))?;
+
+        let sql = format!(
+            "UPDATE memories m SET updated_at = NOW(), tags = ( \
+                 SELECT COALESCE(jsonb_agg(t.tag ORDER BY t.ord), '[]'::jsonb) \
+                 FROM ( \
+                     SELECT DISTINCT ON (tag) tag, ord \
+                     FROM ( \
+                         SELECT CASE WHEN e.tag = ANY($2) THEN $3 ELSE e.tag END AS tag, e.ord \
+                         FROM jsonb_array_elements_text(m.tags) WITH ORDINALITY AS e(tag, ord) \
+                     ) mapped \
+                     WHERE tag IS NOT NULL \
+                     ORDER BY tag, ord \
+                 ) t \
+             ) \
+             WHERE m.id = ANY($1) \
+             RETURNING {}",
+            memory_columns_with_alias("m")
+        );
+        let rows = sqlx::query(&sql)
+            .bind(&ids)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to rewrite tags: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit tag rewrite transaction: {}", e))
+        })?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
+    /// Count links touching each memory (in + out), for the salience link boost.
+    ///
+    /// IDs without links are absent from the map (treat as degree 0).
+    pub async fn get_link_degrees(
+        &self,
+        memory_ids: &[String],
+    ) -> Result<HashMap<String, i64>, MemcpError> {
+        if memory_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = sqlx::query(
+            "SELECT id, COUNT(*) AS degree FROM ( \
+                 SELECT source_id AS id FROM memory_links WHERE source_id = ANY($1) \
+                 UNION ALL \
+                 SELECT target_id AS id FROM memory_links WHERE target_id = ANY($1) \
+             ) edges GROUP BY id",
+        )
+        .bind(memory_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to fetch link degrees: {}", e)))?;
+
+        let mut degrees = HashMap::with_capacity(rows.len());
+        for row in &rows {
+            let id: String = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let degree: i64 = row.try_get("degree").map_err(|e| MemcpError::Storage(e.to_string()))?;
+            degrees.insert(id, degree);
+        }
+        Ok(degrees)
+    }
+
+    /// Remove memories whose expires_at has passed.
+    ///
+    /// With `archive = true` expired live memories are moved to the trash (restorable);
+    /// otherwise they are permanently deleted, whether live or already trashed.
+    /// Returns the number of memories affected.
+    pub async fn sweep_expired(&self, archive: bool) -> Result<u64, MemcpError> {
+        let sql = if archive {
+            "UPDATE memories SET deleted_at = NOW() \
+             WHERE expires_at <= NOW() AND deleted_at IS NULL"
+        } else {
+            "DELETE FROM memories WHERE expires_at <= NOW()"
+        };
+
+        let result = sqlx::query(sql)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to sweep expired memories: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Archive live memories whose FSRS retrievability has decayed below `threshold`.
+    ///
+    /// Scans memory_salience, measuring elapsed time from the last reinforcement (or from
+    /// creation for memories never reinforced). Archived memories drop out of search but
+    /// remain readable by ID; restore() clears the flag. Returns the number archived.
+    pub async fn archive_stale_memories(&self, threshold: f64) -> Result<u64, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT s.memory_id, s.stability::float8 AS stability, COALESCE(s.last_reinforced_at, m.created_at) AS reinforced_at \
+             FROM memory_salience s \
+             JOIN memories m ON m.id = s.memory_id \
+             WHERE m.deleted_at IS NULL AND m.archived_at IS NULL \
+               AND m.is_consolidated_original = FALSE",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to scan salience for archival: {}", e)))?;
+
+        let now = Utc::now();
+        let mut stale: Vec<String> = Vec::new();
+        for row in &rows {
+            let stability: f64 = row
+                .try_get("stability")
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let reinforced_at: DateTime<Utc> = row
+                .try_get("reinforced_at")
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let days_elapsed = (now.signed_duration_since(reinforced_at).num_seconds() as f64 / 86_400.0).max(0.0);
+            if crate::search::salience::fsrs_retrievability(stability, days_elapsed) < threshold {
+                stale.push(row.try_get("memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?);
+            }
+        }
+
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        let result = sqlx::query(
+            "UPDATE memories SET archived_at = NOW() \
+             WHERE id = ANY($1) AND deleted_at IS NULL AND archived_at IS NULL",
+        )
+        .bind(&stale)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to archive stale memories: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// IDs of live memories whose `column` equals `value` and that fall outside a retention policy.
+    ///
+    /// A memory matches when it was created before `older_than`, or when it is not among the
+    /// `keep_newest` most recent matching memories of its namespace. Pinned memories and chunk
+    /// rows never match — chunks follow their parent. `column` must be "source" or "type_hint".
+    pub async fn retention_candidates(
+        &self,
+        column: &str,
+        value: &str,
+        older_than: Option<DateTime<Utc>>,
+        keep_newest: Option<i64>,
+    ) -> Result<Vec<String>, MemcpError> {
+        if older_than.is_none() && keep_newest.is_none() {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "SELECT id FROM ( \
+               SELECT id, created_at, \
+                      ROW_NUMBER() OVER (PARTITION BY namespace ORDER BY created_at DESC, id DESC) AS rank \
+               FROM memories \
+               WHERE {} = $1 AND deleted_at IS NULL AND pinned = FALSE AND parent_id IS NULL \
+             ) ranked \
+             WHERE ($2::timestamptz IS NOT NULL AND created_at < $2) \
+                OR ($3::bigint IS NOT NULL AND rank > $3)",
+            column
+        );
+        sqlx::query_scalar(&sql)
+            .bind(value)
+            .bind(older_than)
+            .bind(keep_newest)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to find retention candidates: {}", e)))
+    }
+
+    /// Remove memories selected by a retention policy, along with their chunks.
+    ///
+    /// With `trash = true` they are moved to the trash (restorable); otherwise they are
+    /// permanently deleted. Returns the number of memories affected, chunks excluded.
+    pub async fn remove_for_retention(&self, ids: &[String], trash: bool) -> Result<u64, MemcpError> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let result = if trash {
+            let affected: Vec<bool> = sqlx::query_scalar(
+                "UPDATE memories SET deleted_at = NOW() \
+                 WHERE (id = ANY($1) OR parent_id = ANY($1)) AND deleted_at IS NULL \
+                 RETURNING parent_id IS NULL",
+            )
+            .bind(ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to trash memories for retention: {}", e)))?;
+            affected.into_iter().filter(|top_level| *top_level).count() as u64
+        } else {
+            // Chunks cascade with their parent and are not counted
+            sqlx::query("DELETE FROM memories WHERE id = ANY($1)")
+                .bind(ids)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to delete memories for retention: {}", e)))?
+                .rows_affected()
+        };
+        Ok(result)
+    }
+
+    /// Undo a consolidation: delete the consolidated memory and re-enable its originals.
+    ///
+    /// Runs in a single transaction:
+    /// 1. Collects the originals linked via memory_consolidations.
+    /// 2. Clears `is_consolidated_original` / `consolidated_into` so search sees them again.
+    /// 3. Deletes the consolidated memory (links and embeddings cascade).
+    ///
+    /// Returns the restored original IDs. NotFound if the memory doesn't exist;
+    /// Validation error if it exists but is not a consolidated memory.
+    pub async fn unconsolidate(&self, consolidated_id: &str) -> Result<Vec<String>, MemcpError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin unconsolidate transaction: {}", e))
+        })?;
+
+        let exists = sqlx::query("SELECT 1 FROM memories WHERE id = $1 FOR UPDATE")
+            .bind(consolidated_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+        if exists.is_none() {
+            return Err(MemcpError::NotFound { id: consolidated_id.to_string() });
+        }
+
+        let original_ids: Vec<String> = sqlx::query_scalar(
+            "SELECT original_id FROM memory_consolidations \
+             WHERE consolidated_id = $1 ORDER BY created_at, original_id",
+        )
+        .bind(consolidated_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to load consolidation links: {}", e)))?;
+
+        if original_ids.is_empty() {
+            return Err(MemcpError::Validation {
+                message: format!("Memory {} is not a consolidated memory", consolidated_id),
+                field: Some("id".to_string()),
+            });
+        }
+
+        sqlx::query(
+            "UPDATE memories SET is_consolidated_original = FALSE, consolidated_into = NULL \
+             WHERE consolidated_into = $1 OR id = ANY($2)",
+        )
+        .bind(consolidated_id)
+        .bind(&original_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to re-enable originals: {}", e)))?;
+
+        sqlx::query("DELETE FROM memories WHERE id = $1")
+            .bind(consolidated_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to delete consolidated memory: {}", e)))?;
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit unconsolidate transaction: {}", e))
+        })?;
+
+        Ok(original_ids)
+    }
+
+    /// Fetch the current embedding vector for a memory.
+    ///
+    /// Returns None if no current embedding exists (not yet embedded, or embedding was staled).
+    pub async fn get_memory_embedding(
+        &self,
+        memory_id: &str,
+    ) -> Result<Option<pgvector::Vector>, MemcpError> {
+        let row = sqlx::query(
+            "SELECT embedding FROM memory_embeddings WHERE memory_id = $1 AND is_current = TRUE",
+        )
+        .bind(memory_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to fetch memory embedding: {}", e)))?;
+
+        match row {
+            None => Ok(None),
+            Some(r) => {
+                let embedding: pgvector::Vector = r
+                    .try_get("embedding")
+                    .map_err(|e| MemcpError::Storage(e.to_string()))?;
+                Ok(Some(embedding))
+            }
+        }
+    }
+
+    /// Fetch the current embeddings of several memories with the model that produced each.
+    ///
+    /// Memories without a current embedding are absent from the map.
+    pub async fn get_current_embeddings(
+        &self,
+        memory_ids: &[String],
+    ) -> Result<HashMap<String, (String, pgvector::Vector)>, MemcpError> {
+        if memory_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query(
+            "SELECT memory_id, model_name, embedding FROM memory_embeddings \
+             WHERE memory_id = ANY($1) AND is_current = TRUE",
+        )
+        .bind(memory_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to fetch memory embeddings: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let memory_id: String = row.try_get("memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                let model: String = row.try_get("model_name").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                let embedding: pgvector::Vector = row.try_get("embedding").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                Ok((memory_id, (model, embedding)))
+            })
+            .collect()
+    }
+
+    // -------------------------------------------------------------------------
+    // Chunked memories
+    // -------------------------------------------------------------------------
+
+    /// Store an oversized memory as a parent holding the full content plus one child per chunk.
+    ///
+    /// All rows are written in one transaction. The parent is marked CHUNKED_STATUS and its
+    /// extraction skipped — callers queue embedding and extraction for the returned chunks only
+    /// (with the outbox on, only the chunks get jobs).
+    /// Chunks inherit the parent's type, source, tags, namespace, expiry, importance, and session;
+    /// structured fields and the payload stay on the parent.
+    pub async fn store_chunked(
+        &self,
+        input: CreateMemory,
+        chunks: Vec<String>,
+    ) -> Result<(Memory, Vec<Memory>), MemcpError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin chunked insert transaction: {}", e))
+        })?;
+
+        let template = input.clone();
+        let mut parent = insert_memory(&mut *tx, input, self.cipher.as_deref()).await?;
+        sqlx::query("UPDATE memories SET embedding_status = $1, extraction_status = 'skipped' WHERE id = $2")
+            .bind(crate::store::CHUNKED_STATUS)
+            .bind(&parent.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to mark memory as chunked: {}", e)))?;
+        parent.embedding_status = crate::store::CHUNKED_STATUS.to_string();
+        parent.extraction_status = "skipped".to_string();
+
+        let mut children = Vec::with_capacity(chunks.len());
+        for (index, content) in chunks.into_iter().enumerate() {
+            let mut child = insert_memory(&mut *tx, CreateMemory { content, fields: None, payload: None, ..template.clone() }, self.cipher.as_deref()).await?;
+            sqlx::query("UPDATE memories SET parent_id = $1, chunk_index = $2 WHERE id = $3")
+                .bind(&parent.id)
+                .bind(index as i32)
+                .bind(&child.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to link memory chunk: {}", e)))?;
+            insert_pipeline_jobs(&mut *tx, &child.id, &self.outbox, template.classify).await?;
+            child.parent_id = Some(parent.id.clone());
+            child.chunk_index = Some(index as i32);
+            children.push(child);
+        }
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit chunked insert transaction: {}", e))
+        })?;
+
+        Ok((parent, children))
+    }
+
+    // -------------------------------------------------------------------------
+    // Fact embeddings
+    // -------------------------------------------------------------------------
+
+    /// Replace a memory's fact embeddings with one row per (fact, vector) pair.
+    ///
+    /// Rows from every model are removed first — they describe the previous facts.
+    pub async fn replace_fact_embeddings(
+        &self,
+        memory_id: &str,
+        model_name: &str,
+        facts: &[String],
+        vectors: Vec<Vec<f32>>,
+    ) -> Result<(), MemcpError> {
+        self.check_extraction_allowed()?;
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to begin fact embedding transaction: {}", e))
+        })?;
+
+        sqlx::query("DELETE FROM fact_embeddings WHERE memory_id = $1")
+            .bind(memory_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to replace fact embeddings: {}", e)))?;
+
+        for (index, (fact, vector)) in facts.iter().zip(vectors).enumerate() {
+            let dimension = vector.len() as i32;
+            sqlx::query(
+                "INSERT INTO fact_embeddings (id, memory_id, fact_index, fact, model_name, dimension, embedding) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(memory_id)
+            .bind(index as i32)
+            .bind(fact)
+            .bind(model_name)
+            .bind(dimension)
+            .bind(pgvector::Vector::from(vector))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Failed to insert fact embedding: {}", e)))?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            MemcpError::Storage(format!("Failed to commit fact embedding transaction: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Find memories whose individual facts are nearest to the query embedding.
+    ///
+    /// Applies the same live/namespace/date/tag filters as search_similar and only uses fact
+    /// rows embedded with the memory's current model. Returns (memory_id, similarity) with
+    /// each memory's best-matching fact, most similar first.
+    pub async fn search_fact_embeddings(
+        &self,
+        conn: &mut PgConnection,
+        filter: &SearchFilter,
+    ) -> Result<Vec<(String, f64)>, MemcpError> {
+        let dimension = filter.query_embedding.as_slice().len() as i32;
+        // The live-memory filters always apply, so let the HNSW scan keep going past them
+        if let Err(e) = sqlx::query("SET hnsw.iterative_scan = 'relaxed_order'")
+            .execute(&mut *conn)
+            .await
+        {
+            tracing::warn!("Failed to set hnsw.iterative_scan (pgvector < 0.8.0?): {}", e);
+        }
+
+        let mut conditions = vec![
+            "m.deleted_at IS NULL".to_string(),
+            "m.archived_at IS NULL".to_string(),
+            "(m.expires_at IS NULL OR m.expires_at > NOW())".to_string(),
+            "m.is_consolidated_original = FALSE".to_string(),
+            format!("fe.dimension = {}", dimension),
+            "EXISTS (SELECT 1 FROM memory_embeddings me WHERE me.memory_id = fe.memory_id \
+             AND me.is_current = TRUE AND me.model_name = fe.model_name)".to_string(),
+        ];
+        let mut param_idx: u32 = 2; // $1 is the query embedding
+        // Same model selection as search_similar: the requested model, else no routed models
+        let exclude_routed = filter.model.is_none() && !self.routed_models.is_empty();
+        if filter.model.is_some() {
+            conditions.push(format!("fe.model_name = ${}", param_idx));
+            param_idx += 1;
+        } else if exclude_routed {
+            conditions.push(format!("fe.model_name <> ALL(${})", param_idx));
+            param_idx += 1;
+        }
+        if filter.created_after.is_some() {
+            conditions.push(format!("m.created_at > ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.created_before.is_some() {
+            conditions.push(format!("m.created_at < ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.tags.is_some() {
+            conditions.push(format!("m.tags @> ${}::jsonb", param_idx));
+            param_idx += 1;
+        }
+        if filter.namespace.is_some() {
+            conditions.push(format!("m.namespace = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.type_hint.is_some() {
+            conditions.push(format!("m.type_hint = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.source.is_some() {
+            conditions.push(format!("m.source = ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.payload_path.is_some() {
+            conditions.push(format!("m.payload @@ ${}::text::jsonpath", param_idx));
+            param_idx += 1;
+        }
+        if filter.origin_ref.is_some() {
+            conditions.push(format!("m.origin_refs @> ${}::jsonb", param_idx));
+            param_idx += 1;
+        }
+        if filter.min_access_count.is_some() {
+            conditions.push(format!("m.access_count >= ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.accessed_after.is_some() {
+            conditions.push(format!("m.last_accessed_at > ${}", param_idx));
+            param_idx += 1;
+        }
+        if filter.never_accessed {
+            conditions.push("m.access_count = 0".to_string());
+        }
+        let exclusions = [filter.exclude_tags.as_slice(), &filter.exclude_sources, &filter.exclude_type_hints];
+        push_exclusion_conditions("m.", exclusions, &mut conditions, &mut param_idx);
+
+        // Nearest facts first (HNSW), then keep each memory's best fact
+        let sql = format!(
+            "WITH nearest AS ( \
+                SELECT fe.memory_id, 1 - (fe.embedding::vector({dim}) <=> $1) AS similarity \
+                FROM fact_embeddings fe JOIN memories m ON m.id = fe.memory_id \
+                WHERE {conditions} \
+                ORDER BY fe.embedding::vector({dim}) <=> $1 ASC \
+                LIMIT ${limit} \
+             ) \
+             SELECT DISTINCT ON (memory_id) memory_id, similarity FROM nearest \
+             ORDER BY memory_id, similarity DESC",
+            dim = dimension,
+            conditions = conditions.join(" AND "),
+            limit = param_idx
+        );
+
+        let mut q = sqlx::query(&sql).bind(&filter.query_embedding);
+        if let Some(ref model) = filter.model {
+            q = q.bind(model);
+        } else if exclude_routed {
+            q = q.bind(&self.routed_models);
+        }
+        if let Some(ref ca) = filter.created_after {
+            q = q.bind(ca);
+        }
+        if let Some(ref cb) = filter.created_before {
+            q = q.bind(cb);
+        }
+        if let Some(ref tags) = filter.tags {
+            q = q.bind(serde_json::json!(tags));
+        }
+        if let Some(ref ns) = filter.namespace {
+            q = q.bind(ns);
+        }
+        if let Some(ref type_hint) = filter.type_hint {
+            q = q.bind(type_hint);
+        }
+        if let Some(ref source) = filter.source {
+            q = q.bind(source);
+        }
+        if let Some(ref path) = filter.payload_path {
+            q = q.bind(path);
+        }
+        if let Some(ref origin_ref) = filter.origin_ref {
+            q = q.bind(origin_ref_array(origin_ref));
+        }
+        if let Some(count) = filter.min_access_count {
+            q = q.bind(count);
+        }
+        if let Some(ref aa) = filter.accessed_after {
+            q = q.bind(aa);
+        }
+        q = bind_exclusions(q, exclusions);
+        // Several facts can share a memory — over-fetch so `limit` memories survive dedup
+        q = q.bind(filter.limit * 3);
+
+        let rows = q
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| MemcpError::Storage(format!("Fact search query failed: {}", e)))?;
+
+        let mut hits = rows
+            .iter()
+            .map(|row| {
+                let id: String = row.try_get("memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                let similarity: f64 = row.try_get("similarity").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                Ok((id, similarity.clamp(0.0, 1.0)))
+            })
+            .collect::<Result<Vec<_>, MemcpError>>()?;
+        hits.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(filter.limit.max(0) as usize);
+        Ok(hits)
+    }
+
+    /// Live memories with extracted facts but no fact embeddings under `model`, oldest first.
+    ///
+    /// Returns (memory_id, facts) pairs for `memcp embed facts`.
+    pub async fn get_memories_missing_fact_embeddings(
+        &self,
+        model: &str,
+        limit: i64,
+    ) -> Result<Vec<(String, Vec<String>)>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT m.id, m.extracted_facts FROM memories m \
+             WHERE m.deleted_at IS NULL \
+               AND jsonb_typeof(m.extracted_facts) = 'array' AND jsonb_array_length(m.extracted_facts) > 0 \
+               AND NOT EXISTS (SELECT 1 FROM fact_embeddings fe WHERE fe.memory_id = m.id AND fe.model_name = $1) \
+             ORDER BY m.created_at ASC, m.id ASC LIMIT $2",
+        )
+        .bind(model)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                let id: String = row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                let facts: serde_json::Value = row.try_get("extracted_facts").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                let facts = serde_json::from_value(facts).unwrap_or_default();
+                Ok((id, facts))
+            })
+            .collect()
+    }
+
+    // -------------------------------------------------------------------------
+    // Vector index maintenance
+    // -------------------------------------------------------------------------
+
+    /// Dimension shared by most current embeddings, or None when nothing is embedded yet.
+    pub async fn current_embedding_dimension(&self) -> Result<Option<i32>, MemcpError> {
+        sqlx::query_scalar(
+            "SELECT dimension FROM memory_embeddings WHERE is_current \
+             GROUP BY dimension ORDER BY COUNT(*) DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))
+    }
+
+    /// Refuse to search with `dimension`-dimensional query vectors while current embeddings
+    /// of another dimension exist — the embedding model changed without `embed switch-model`,
+    /// and those memories would silently drop out of vector search.
+    ///
+    /// A passing dimension is remembered, so later checks for it skip the query; a failing
+    /// one is checked again each time, so searches recover once the backfill finishes.
+    pub async fn check_embedding_dimension(&self, dimension: i32) -> Result<(), MemcpError> {
+        self.check_dimension_with(&self.pool, dimension).await
+    }
+
+    async fn check_dimension_with<'e>(
+        &self,
+        executor: impl sqlx::PgExecutor<'e>,
+        dimension: i32,
+    ) -> Result<(), MemcpError> {
+        if self.checked_dimension.load(Ordering::Relaxed) == dimension {
+            return Ok(());
+        }
+        // Two range scans on the partial dimension index; <> cannot use it
+        let other: Option<i32> = sqlx::query_scalar(
+            "SELECT dimension FROM memory_embeddings \
+             WHERE is_current AND (dimension < $1 OR dimension > $1) \
+               AND model_name <> ALL($2) LIMIT 1",
+        )
+        .bind(dimension)
+        .bind(&self.routed_models)
+        .fetch_optional(executor)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        match other {
+            Some(stored) => Err(MemcpError::Config(format!(
+                "the embedding model produces {}-dimensional vectors but current embeddings have {} dimensions. \
+                 Run `memcp embed switch-model --model <model>` and then `memcp embed backfill`",
+                dimension, stored
+            ))),
+            None => {
+                self.checked_dimension.store(dimension, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+    }
+
+    /// Create the memory and fact HNSW indexes for `dimension`-dimensional vectors if missing,
+    /// with the default build parameters. Built concurrently, so searches and writes continue.
+    pub async fn ensure_vector_indexes(&self, dimension: i32) -> Result<(), MemcpError> {
+        if dimension <= 0 {
+            return Err(MemcpError::Validation {
+                message: "dimension must be positive".to_string(),
+                field: Some("dimension".to_string()),
+            });
+        }
+        for (prefix, table) in [(HNSW_INDEX_PREFIX, "memory_embeddings"), (FACT_HNSW_INDEX_PREFIX, "fact_embeddings")] {
+            let sql = hnsw_index_sql(&hnsw_index_name(prefix, dimension), table, dimension, 16, 64);
+            sqlx::query(&sql)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to create vector index: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Drop the HNSW indexes of every dimension other than `keep`. Returns the dropped index names.
+    pub async fn drop_vector_indexes_except(&self, keep: i32) -> Result<Vec<String>, MemcpError> {
+        let keep_names = [hnsw_index_name(HNSW_INDEX_PREFIX, keep), hnsw_index_name(FACT_HNSW_INDEX_PREFIX, keep)];
+        let names: Vec<String> = sqlx::query_scalar(
+            "SELECT indexname FROM pg_indexes \
+             WHERE tablename IN ('memory_embeddings', 'fact_embeddings') \
+               AND (indexname ~ ('^' || $1 || '_[0-9]+$') OR indexname ~ ('^' || $2 || '_[0-9]+$'))",
+        )
+        .bind(HNSW_INDEX_PREFIX)
+        .bind(FACT_HNSW_INDEX_PREFIX)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        let mut dropped = Vec::new();
+        for name in names.into_iter().filter(|name| !keep_names.contains(name)) {
+            sqlx::query(&format!("DROP INDEX CONCURRENTLY IF EXISTS {}", name))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to drop vector index: {}", e)))?;
+            dropped.push(name);
+        }
+        Ok(dropped)
+    }
+
+    /// Read the HNSW index's build parameters, size, and validity plus embedding row counts.
+    ///
+    /// Covers the index over `dimension`-dimensional vectors; returns `"exists": false` when it
+    /// has not been built (or was dropped).
+    pub async fn vector_index_stats(&self, dimension: i32) -> Result<serde_json::Value, MemcpError> {
+        let index_name = hnsw_index_name(HNSW_INDEX_PREFIX, dimension);
+        let index = sqlx::query(
+            "SELECT c.reloptions, i.indisvalid, pg_relation_size(c.oid) AS size_bytes, \
+                    pg_size_pretty(pg_relation_size(c.oid)) AS size \
+             FROM pg_class c JOIN pg_index i ON i.indexrelid = c.oid \
+             WHERE c.relname = $1",
+        )
+        .bind(&index_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        let counts = sqlx::query(
+            "SELECT COUNT(*) AS total, COUNT(*) FILTER (WHERE is_current) AS current FROM memory_embeddings",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let total: i64 = counts.try_get("total").map_err(|e| MemcpError::Storage(e.to_string()))?;
+        let current: i64 = counts.try_get("current").map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        // hnsw.ef_search is a pgvector GUC; unavailable only if the extension isn't loaded
+        let ef_search: Option<String> = sqlx::query_scalar("SELECT current_setting('hnsw.ef_search', true)")
+            .fetch_one(&self.pool)
+            .await
+            .unwrap_or(None);
+
+        let Some(index) = index else {
+            return Ok(serde_json::json!({
+                "index": index_name,
+                "dimension": dimension,
+                "exists": false,
+                "embeddings": total,
+                "current_embeddings": current,
+            }));
+        };
+
+        let options: Vec<String> = index.try_get::<Option<Vec<String>>, _>("reloptions")
+            .map_err(|e| MemcpError::Storage(e.to_string()))?
+            .unwrap_or_default();
+        let (m, ef_construction) = parse_hnsw_options(&options);
+        Ok(serde_json::json!({
+            "index": index_name,
+            "dimension": dimension,
+            "exists": true,
+            "valid": index.try_get::<bool, _>("indisvalid").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            "m": m,
+            "ef_construction": ef_construction,
+            "ef_search": ef_search.and_then(|v| v.parse::<u32>().ok()),
+            "size_bytes": index.try_get::<i64, _>("size_bytes").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            "size": index.try_get::<String, _>("size").map_err(|e| MemcpError::Storage(e.to_string()))?,
+            "embeddings": total,
+            "current_embeddings": current,
+        }))
+    }
+
+    /// Estimate HNSW recall by comparing approximate and exact top-`k` neighbours.
+    ///
+    /// Samples up to `sample` current `dimension`-dimensional embeddings as queries; each is
+    /// searched once through the index and once by sequential scan. `ef_search` overrides
+    /// hnsw.ef_search for the approximate pass. Reports mean recall@k and the mean latency
+    /// of each pass.
+    pub async fn estimate_vector_recall(
+        &self,
+        sample: i64,
+        k: i64,
+        ef_search: Option<u32>,
+        dimension: i32,
+    ) -> Result<serde_json::Value, MemcpError> {
+        let queries: Vec<pgvector::Vector> = sqlx::query_scalar(
+            "SELECT embedding FROM memory_embeddings WHERE is_current = TRUE AND dimension = $2 \
+             ORDER BY random() LIMIT $1",
+        )
+        .bind(sample)
+        .bind(dimension)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        let neighbours_sql = format!(
+            "SELECT memory_id FROM memory_embeddings WHERE is_current = TRUE AND dimension = {dim} \
+             ORDER BY embedding::vector({dim}) <=> $1 LIMIT $2",
+            dim = dimension
+        );
+        let mut recall_sum = 0.0;
+        let mut ann_time = Duration::ZERO;
+        let mut exact_time = Duration::ZERO;
+        for query in &queries {
+            // SET LOCAL keeps the planner overrides inside this transaction
+            let mut tx = self.pool.begin().await.map_err(|e| MemcpError::Storage(e.to_string()))?;
+            if let Some(ef) = ef_search {
+                sqlx::query(&format!("SET LOCAL hnsw.ef_search = {}", ef))
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| MemcpError::Storage(format!("Failed to set hnsw.ef_search: {}", e)))?;
+            }
+
+            let started = std::time::Instant::now();
+            let approximate: Vec<String> = sqlx::query_scalar(&neighbours_sql)
+                .bind(query)
+                .bind(k)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            ann_time += started.elapsed();
+
+            sqlx::query("SET LOCAL enable_indexscan = off")
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            let started = std::time::Instant::now();
+            let exact: Vec<String> = sqlx::query_scalar(&neighbours_sql)
+                .bind(query)
+                .bind(k)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| MemcpError::Storage(e.to_string()))?;
+            exact_time += started.elapsed();
+            tx.rollback().await.map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+            if !exact.is_empty() {
+                let exact: HashSet<&String> = exact.iter().collect();
+                let hits = approximate.iter().filter(|id| exact.contains(id)).count();
+                recall_sum += hits as f64 / exact.len() as f64;
+            }
+        }
+
+        let n = queries.len().max(1) as f64;
+        Ok(serde_json::json!({
+            "sample": queries.len(),
+            "k": k,
+            "ef_search": ef_search,
+            "recall": (!queries.is_empty()).then(|| (recall_sum / n * 1000.0).round() / 1000.0),
+            "mean_index_ms": (ann_time.as_secs_f64() * 1000.0 / n * 100.0).round() / 100.0,
+            "mean_exact_ms": (exact_time.as_secs_f64() * 1000.0 / n * 100.0).round() / 100.0,
+        }))
+    }
+
+    /// Rebuild the HNSW index over `dimension`-dimensional vectors, optionally with new `m` /
+    /// `ef_construction` parameters.
+    ///
+    /// Parameters left as None keep the index's current values (migration defaults 16/64 if
+    /// the index is missing). The replacement is built concurrently under a temporary name and
+    /// swapped in, so searches keep using the old index until the new one is ready.
+    /// Returns the (m, ef_construction) the index was built with.
+    pub async fn rebuild_vector_index(
+        &self,
+        m: Option<u32>,
+        ef_construction: Option<u32>,
+        dimension: i32,
+    ) -> Result<(u32, u32), MemcpError> {
+        let index_name = hnsw_index_name(HNSW_INDEX_PREFIX, dimension);
+        let options: Vec<String> = sqlx::query_scalar::<_, Option<Vec<String>>>(
+            "SELECT reloptions FROM pg_class WHERE relname = $1",
+        )
+        .bind(&index_name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?
+        .flatten()
+        .unwrap_or_default();
+        let (current_m, current_ef) = parse_hnsw_options(&options);
+        let m = m.or(current_m).unwrap_or(16);
+        let ef_construction = ef_construction.or(current_ef).unwrap_or(64);
+
+        // pgvector's accepted ranges; ef_construction must also cover both directions of m links
+        if !(2..=100).contains(&m) {
+            return Err(MemcpError::Validation {
+                message: "m must be between 2 and 100".to_string(),
+                field: Some("m".to_string()),
+            });
+        }
+        if !(4..=1000).contains(&ef_construction) || ef_construction < 2 * m {
+            return Err(MemcpError::Validation {
+                message: "ef_construction must be between 4 and 1000 and at least 2 * m".to_string(),
+                field: Some("ef_construction".to_string()),
+            });
+        }
+
+        let staging = format!("{}_new", index_name);
+        // CONCURRENTLY cannot run inside a transaction — each statement runs on the pool.
+        // Drop any invalid leftover from an interrupted rebuild first.
+        for sql in [
+            format!("DROP INDEX CONCURRENTLY IF EXISTS {}", staging),
+            hnsw_index_sql(&staging, "memory_embeddings", dimension, m, ef_construction),
+            format!("DROP INDEX CONCURRENTLY IF EXISTS {}", index_name),
+            format!("ALTER INDEX {} RENAME TO {}", staging, index_name),
+        ] {
+            sqlx::query(&sql)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| MemcpError::Storage(format!("Failed to rebuild vector index: {}", e)))?;
+        }
+
+        Ok((m, ef_construction))
+    }
+
+    // -------------------------------------------------------------------------
+    // Sessions
+    // -------------------------------------------------------------------------
+
+    /// Open a new session in `namespace`.
+    pub async fn start_session(&self, namespace: &str, title: Option<&str>) -> Result<Session, MemcpError> {
+        let row = sqlx::query(
+            "INSERT INTO sessions (id, namespace, title) VALUES ($1, $2, $3) RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(namespace)
+        .bind(title)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to start session: {}", e)))?;
+
+        row_to_session(&row)
+    }
+
+    /// Fetch a session by ID, open or closed.
+    pub async fn get_session(&self, id: &str) -> Result<Session, MemcpError> {
+        let row = sqlx::query("SELECT * FROM sessions WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?
+            .ok_or_else(|| MemcpError::NotFound { id: id.to_string() })?;
+
+        row_to_session(&row)
+    }
+
+    /// Live memories stored in a session, oldest first, up to `limit`.
+    pub async fn get_session_memories(&self, session_id: &str, limit: i64) -> Result<Vec<Memory>, MemcpError> {
+        let sql = format!(
+            "SELECT {} FROM memories WHERE session_id = $1 AND deleted_at IS NULL \
+             ORDER BY created_at ASC, id ASC LIMIT $2",
+            MEMORY_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(session_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter().map(|row| self.memory_from_row(row)).collect()
+    }
+
+    /// Close an open session, recording its summary memory.
+    ///
+    /// Returns NotFound when the session does not exist and a validation error when it has
+    /// already ended, so a session is only ever summarized once.
+    pub async fn end_session(&self, id: &str, summary_memory_id: Option<&str>) -> Result<Session, MemcpError> {
+        let row = sqlx::query(
+            "UPDATE sessions SET ended_at = NOW(), summary_memory_id = $2 \
+             WHERE id = $1 AND ended_at IS NULL RETURNING *",
+        )
+        .bind(id)
+        .bind(summary_memory_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to end session: {}", e)))?;
+
+        match row {
+            Some(row) => row_to_session(&row),
+            None => {
+                // Distinguish a missing session from one that was already closed
+                self.get_session(id).await?;
+                Err(MemcpError::Validation {
+                    message: "Session has already ended".to_string(),
+                    field: Some("session_id".to_string()),
+                })
+            }
+        }
+    }
+
+    /// Summaries of the most recently ended sessions in `namespace`, newest first.
+    ///
+    /// Sessions whose summary memory was trashed are skipped.
+    pub async fn recent_session_summaries(
+        &self,
+        namespace: &str,
+        limit: i64,
+    ) -> Result<Vec<(Session, Memory)>, MemcpError> {
+        let sql = format!(
+            "SELECT s.id AS s_id, s.namespace AS s_namespace, s.title, s.started_at, s.ended_at, \
+             s.summary_memory_id, {} \
+             FROM sessions s JOIN memories m ON m.id = s.summary_memory_id \
+             WHERE s.namespace = $1 AND m.deleted_at IS NULL \
+             ORDER BY s.ended_at DESC LIMIT $2",
+            memory_columns_with_alias("m")
+        );
+        let rows = sqlx::query(&sql)
+            .bind(namespace)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                let session = Session {
+                    id: row.try_get("s_id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    namespace: row.try_get("s_namespace").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    title: row.try_get("title").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    started_at: row.try_get("started_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    ended_at: row.try_get("ended_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    summary_memory_id: row.try_get("summary_memory_id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                };
+                Ok((session, self.memory_from_row(row)?))
+            })
+            .collect()
+    }
+
+    // -------------------------------------------------------------------------
+    // Memory types
+    // -------------------------------------------------------------------------
+
+    /// Create or replace the memory type `name` in `namespace`.
+    ///
+    /// Redefining a type does not revalidate memories already stored against it.
+    pub async fn define_memory_type(
+        &self,
+        namespace: &str,
+        name: &str,
+        description: Option<&str>,
+        fields: &[FieldDef],
+    ) -> Result<MemoryType, MemcpError> {
+        let fields_json = serde_json::to_value(fields).map_err(|e| MemcpError::Internal(e.to_string()))?;
+        let row = sqlx::query(
+            "INSERT INTO memory_types (namespace, name, description, fields) VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (namespace, name) DO UPDATE \
+             SET description = EXCLUDED.description, fields = EXCLUDED.fields, updated_at = NOW() \
+             RETURNING *",
+        )
+        .bind(namespace)
+        .bind(name)
+        .bind(description)
+        .bind(&fields_json)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to define memory type: {}", e)))?;
+
+        row_to_memory_type(&row)
+    }
+
+    /// Fetch the memory type `name` in `namespace`.
+    pub async fn get_memory_type(&self, namespace: &str, name: &str) -> Result<MemoryType, MemcpError> {
+        let row = sqlx::query("SELECT * FROM memory_types WHERE namespace = $1 AND name = $2")
+            .bind(namespace)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?
+            .ok_or_else(|| MemcpError::NotFound { id: name.to_string() })?;
+
+        row_to_memory_type(&row)
+    }
+
+    /// All memory types defined in `namespace`, by name.
+    pub async fn list_memory_types(&self, namespace: &str) -> Result<Vec<MemoryType>, MemcpError> {
+        let rows = sqlx::query("SELECT * FROM memory_types WHERE namespace = $1 ORDER BY name")
+            .bind(namespace)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter().map(row_to_memory_type).collect()
+    }
+
+    // -------------------------------------------------------------------------
+    // Audit log
+    // -------------------------------------------------------------------------
+
+    /// Append one tool call to the audit log. Parameters are stored as their MD5 hash only.
+    pub async fn record_audit(&self, entry: &AuditEntry) -> Result<(), MemcpError> {
+        sqlx::query(
+            "INSERT INTO audit_log (tool, params_hash, memory_ids, latency_ms, outcome, error_code) \
+             VALUES ($1, md5($2), $3, $4, $5, $6)",
+        )
+        .bind(&entry.tool)
+        .bind(&entry.params_json)
+        .bind(&entry.memory_ids)
+        .bind(entry.latency_ms)
+        .bind(&entry.outcome)
+        .bind(&entry.error_code)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to record audit entry: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Read audit log entries matching `filter`, newest first.
+    pub async fn get_audit_log(&self, filter: &AuditFilter) -> Result<Vec<AuditRecord>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT id, tool, params_hash, memory_ids, latency_ms, outcome, error_code, created_at \
+             FROM audit_log \
+             WHERE ($1::text IS NULL OR tool = $1) \
+               AND ($2::text IS NULL OR outcome = $2) \
+               AND ($3::text IS NULL OR memory_ids @> ARRAY[$3::text]) \
+               AND ($4::timestamptz IS NULL OR created_at >= $4) \
+               AND ($5::timestamptz IS NULL OR created_at < $5) \
+             ORDER BY created_at DESC, id DESC LIMIT $6",
+        )
+        .bind(&filter.tool)
+        .bind(&filter.outcome)
+        .bind(&filter.memory_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(AuditRecord {
+                    id: row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    tool: row.try_get("tool").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    params_hash: row.try_get("params_hash").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    memory_ids: row.try_get("memory_ids").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    latency_ms: row.try_get("latency_ms").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    outcome: row.try_get("outcome").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    error_code: row.try_get("error_code").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    created_at: row.try_get("created_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                })
+            })
+            .collect()
+    }
+
+    // -------------------------------------------------------------------------
+    // Redactions
+    // -------------------------------------------------------------------------
+
+    /// Record the spans the content filters redacted from `memory_id`: kind, placeholder, and
+    /// its byte offset in the stored content. The original itself is never stored — only its
+    /// keyed hash, and only with content encryption on (there is no key otherwise).
+    pub async fn record_redactions(
+        &self,
+        memory_id: &str,
+        redactions: &[crate::privacy::Redaction],
+    ) -> Result<(), MemcpError> {
+        if redactions.is_empty() {
+            return Ok(());
+        }
+        let mut kinds = Vec::with_capacity(redactions.len());
+        let mut placeholders = Vec::with_capacity(redactions.len());
+        let mut offsets = Vec::with_capacity(redactions.len());
+        let mut hashes = Vec::with_capacity(redactions.len());
+        for redaction in redactions {
+            kinds.push(redaction.kind.clone());
+            placeholders.push(redaction.placeholder.clone());
+            offsets.push(redaction.offset as i32);
+            hashes.push(self.cipher.as_ref().map(|cipher| cipher.keyed_hash(&redaction.original)));
+        }
+        sqlx::query(
+            "INSERT INTO memory_redactions (memory_id, kind, placeholder, byte_offset, original_hash) \
+             SELECT $1, r.kind, r.placeholder, r.byte_offset, r.original_hash \
+             FROM UNNEST($2::text[], $3::text[], $4::int4[], $5::text[]) \
+                 AS r(kind, placeholder, byte_offset, original_hash)",
+        )
+        .bind(memory_id)
+        .bind(&kinds)
+        .bind(&placeholders)
+        .bind(&offsets)
+        .bind(&hashes)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to record redactions: {}", e)))?;
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    // Search log
+    // -------------------------------------------------------------------------
+
+    /// Append one search to the search log.
+    pub async fn record_search(&self, entry: &SearchLogEntry) -> Result<(), MemcpError> {
+        let leg_hits = serde_json::to_value(entry.leg_hits)
+            .map_err(|e| MemcpError::Internal(format!("Failed to encode leg hit counts: {}", e)))?;
+        sqlx::query(
+            "INSERT INTO search_log (query, namespace, variants, leg_hits, fused_ids, result_ids, scores, latency_ms) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&entry.query)
+        .bind(&entry.namespace)
+        .bind(&entry.variants)
+        .bind(leg_hits)
+        .bind(&entry.fused_ids)
+        .bind(&entry.result_ids)
+        .bind(&entry.scores)
+        .bind(entry.latency_ms)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to record search log entry: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Note that the client fetched `memory_id`, on the most recent search in `namespace` that
+    /// returned it within the last FETCH_WINDOW_MINUTES. Repeat fetches are recorded once.
+    pub async fn record_search_fetch(&self, memory_id: &str, namespace: &str) -> Result<(), MemcpError> {
+        sqlx::query(
+            "UPDATE search_log SET fetched_ids = array_append(fetched_ids, $1) \
+             WHERE id = ( \
+                 SELECT id FROM search_log \
+                 WHERE namespace = $2 AND result_ids @> ARRAY[$1::text] \
+                   AND created_at > NOW() - make_interval(mins => $3) \
+                 ORDER BY created_at DESC, id DESC LIMIT 1 \
+             ) AND NOT (fetched_ids @> ARRAY[$1::text])",
+        )
+        .bind(memory_id)
+        .bind(namespace)
+        .bind(crate::search_log::FETCH_WINDOW_MINUTES as i32)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(format!("Failed to record search fetch: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Read search log entries matching `filter`, oldest first.
+    pub async fn get_search_log(&self, filter: &SearchLogFilter) -> Result<Vec<SearchLogRecord>, MemcpError> {
+        let rows = sqlx::query(
+            "SELECT id, query, namespace, variants, leg_hits, fused_ids, result_ids, scores, fetched_ids, latency_ms, created_at \
+             FROM search_log \
+             WHERE ($1::text IS NULL OR namespace = $1) \
+               AND ($2::timestamptz IS NULL OR created_at >= $2) \
+               AND ($3::timestamptz IS NULL OR created_at < $3) \
+             ORDER BY created_at, id LIMIT $4",
+        )
+        .bind(&filter.namespace)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| MemcpError::Storage(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                let leg_hits: serde_json::Value = row.try_get("leg_hits").map_err(|e| MemcpError::Storage(e.to_string()))?;
+                Ok(SearchLogRecord {
+                    id: row.try_get("id").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    query: row.try_get("query").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    namespace: row.try_get("namespace").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    variants: row.try_get("variants").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    leg_hits: serde_json::from_value(leg_hits).unwrap_or_default(),
+                    fused_ids: row.try_get("fused_ids").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    result_ids: row.try_get("result_ids").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    scores: row.try_get("scores").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    fetched_ids: row.try_get("fetched_ids").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    latency_ms: row.try_get("latency_ms").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                    created_at: row.try_get("created_at").map_err(|e| MemcpError::Storage(e.to_string()))?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn slow_legs_are_dropped_and_errors_pass_through() {
+        let limit = Some(Duration::from_millis(20));
+        let timeouts = crate::metrics::global().search_leg_timeouts.get();
+        let slow = std::future::pending::<Result<Vec<(String, i64, f64)>, MemcpError>>();
+        assert!(time_leg("bm25", limit, slow).await.unwrap().is_empty(), "a slow leg degrades to no candidates");
+        assert!(crate::metrics::global().search_leg_timeouts.get() > timeouts);
+
+        let hits = vec![("m1".to_string(), 1, 0.5)];
+        let fast = std::future::ready(Ok(hits.clone()));
+        assert_eq!(time_leg("vector", limit, fast).await.unwrap(), hits);
+
+        let failed = std::future::ready(Err(MemcpError::Storage("boom".to_string())));
+        assert!(time_leg("symbolic", limit, failed).await.is_err(), "query errors still fail the search");
+        let unbounded = std::future::ready(Ok(hits.clone()));
+        assert_eq!(time_leg("bm25", None, unbounded).await.unwrap(), hits);
     }
 }